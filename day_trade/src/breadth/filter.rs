@@ -0,0 +1,206 @@
+//! Breadth conditions and a strategy wrapper gated by them
+
+use super::MarketBreadth;
+use crate::{DailyOhlcv, Signal, TradeError, TradingStrategy};
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+/// Condition under which breadth counts as positive on a date
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BreadthRule {
+    /// The advance/decline line closes above its simple moving average of
+    /// the given number of days
+    AdvanceDeclineAboveAverage(usize),
+    /// The McClellan oscillator is above the level
+    McClellanAbove(f64),
+    /// The ratio-adjusted McClellan oscillator is above the level
+    RatioAdjustedMcClellanAbove(f64),
+    /// The Arms index is below the level; 1.0 is neutral
+    TrinBelow(f64),
+    /// New highs minus new lows exceed the count
+    NetNewHighsAbove(i64),
+    /// Up volume per unit of down volume exceeds the ratio
+    UpDownVolumeAbove(f64),
+}
+
+impl Default for BreadthRule {
+    /// A positive McClellan oscillator
+    fn default() -> Self {
+        BreadthRule::McClellanAbove(0.0)
+    }
+}
+
+impl BreadthRule {
+    /// Whether breadth is positive on each date of `breadth`
+    ///
+    /// Dates where the indicator is undefined, such as the first days of a
+    /// moving average or a TRIN without decliners, are not positive.
+    pub fn evaluate(&self, breadth: &MarketBreadth) -> Vec<(NaiveDate, bool)> {
+        let days = breadth.days();
+        match *self {
+            BreadthRule::AdvanceDeclineAboveAverage(period) => {
+                let line = breadth.advance_decline_line();
+                line.iter()
+                    .enumerate()
+                    .map(|(i, &(date, value))| {
+                        let positive = period > 0
+                            && i + 1 >= period
+                            && value
+                                > line[i + 1 - period..=i].iter().map(|p| p.1).sum::<f64>()
+                                    / period as f64;
+                        (date, positive)
+                    })
+                    .collect()
+            }
+            BreadthRule::McClellanAbove(level) => breadth
+                .mcclellan_oscillator(false)
+                .into_iter()
+                .map(|(date, value)| (date, value > level))
+                .collect(),
+            BreadthRule::RatioAdjustedMcClellanAbove(level) => breadth
+                .mcclellan_oscillator(true)
+                .into_iter()
+                .map(|(date, value)| (date, value > level))
+                .collect(),
+            BreadthRule::TrinBelow(level) => days
+                .iter()
+                .map(|d| (d.date, d.trin().is_some_and(|t| t < level)))
+                .collect(),
+            BreadthRule::NetNewHighsAbove(count) => days
+                .iter()
+                .map(|d| (d.date, d.net_new_highs() > count))
+                .collect(),
+            BreadthRule::UpDownVolumeAbove(ratio) => days
+                .iter()
+                .map(|d| (d.date, d.up_down_volume_ratio().is_some_and(|r| r > ratio)))
+                .collect(),
+        }
+    }
+}
+
+/// Only buy while market breadth is positive
+///
+/// A Buy on a bar is replaced with Hold unless the [`BreadthRule`] holds on
+/// that bar's date, including dates the breadth does not cover. Breadth on
+/// a date is known at its close and signals execute at the next bar's open,
+/// so the filter does not look ahead. Sells and Holds pass through, so open
+/// positions are managed by the inner strategy as usual.
+#[derive(Debug, Clone)]
+pub struct BreadthFilter<S> {
+    strategy: S,
+    breadth: MarketBreadth,
+    rule: BreadthRule,
+}
+
+impl<S> BreadthFilter<S> {
+    /// Wrap `strategy`, buying only while the McClellan oscillator is
+    /// positive
+    pub fn new(strategy: S, breadth: MarketBreadth) -> Self {
+        Self {
+            strategy,
+            breadth,
+            rule: BreadthRule::default(),
+        }
+    }
+
+    /// Buy only while `rule` holds
+    pub fn with_rule(mut self, rule: BreadthRule) -> Self {
+        self.rule = rule;
+        self
+    }
+
+    /// The wrapped strategy
+    pub fn inner(&self) -> &S {
+        &self.strategy
+    }
+
+    /// The breadth the filter reads
+    pub fn breadth(&self) -> &MarketBreadth {
+        &self.breadth
+    }
+}
+
+impl<S: TradingStrategy> TradingStrategy for BreadthFilter<S> {
+    fn generate_signals(&self, data: &[DailyOhlcv]) -> Result<Vec<Signal>, TradeError> {
+        let positive: BTreeMap<NaiveDate, bool> =
+            self.rule.evaluate(&self.breadth).into_iter().collect();
+        let mut signals = self.strategy.generate_signals(data)?;
+        for (signal, bar) in signals.iter_mut().zip(data) {
+            if *signal == Signal::Buy && !positive.get(&bar.date).copied().unwrap_or(false) {
+                *signal = Signal::Hold;
+            }
+        }
+        Ok(signals)
+    }
+
+    fn calculate_performance(
+        &self,
+        data: &[DailyOhlcv],
+        signals: &[Signal],
+    ) -> Result<f64, TradeError> {
+        self.strategy.calculate_performance(data, signals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::issue;
+    use super::*;
+
+    /// Buys on every bar
+    struct AlwaysBuy;
+
+    impl TradingStrategy for AlwaysBuy {
+        fn generate_signals(&self, data: &[DailyOhlcv]) -> Result<Vec<Signal>, TradeError> {
+            Ok(vec![Signal::Buy; data.len()])
+        }
+
+        fn calculate_performance(&self, _: &[DailyOhlcv], _: &[Signal]) -> Result<f64, TradeError> {
+            Ok(0.0)
+        }
+    }
+
+    #[test]
+    fn test_filter_blocks_buys_on_weak_breadth() {
+        // Two issues rise for five days, then both fall for five days
+        let closes = [
+            10.0, 11.0, 12.0, 13.0, 14.0, 15.0, 14.0, 13.0, 12.0, 11.0, 10.0,
+        ];
+        let a = issue(&closes, 100);
+        let b = issue(&closes, 200);
+        let breadth = MarketBreadth::from_universe([&a[..], &b[..]], 3).unwrap();
+
+        let filter = BreadthFilter::new(AlwaysBuy, breadth.clone())
+            .with_rule(BreadthRule::NetNewHighsAbove(0));
+        let signals = filter.generate_signals(&a).unwrap();
+        let buys: Vec<bool> = signals.iter().map(|s| *s == Signal::Buy).collect();
+        // No breadth on the first bar and no new highs before the fourth
+        assert_eq!(
+            buys,
+            [false, false, false, true, true, true, false, false, false, false, false]
+        );
+
+        // The oscillator stays at zero while net advances are constant and
+        // turns positive once the decline reverses
+        let mut v = closes;
+        v[..6].reverse();
+        v[6..].reverse();
+        let rebound = [issue(&v, 100), issue(&v, 100)];
+        let universe = rebound.iter().map(Vec::as_slice);
+        let mcclellan = BreadthFilter::new(
+            AlwaysBuy,
+            MarketBreadth::from_universe(universe, 3).unwrap(),
+        );
+        let signals = mcclellan.generate_signals(&rebound[0]).unwrap();
+        assert!(signals[..6].iter().all(|s| *s == Signal::Hold));
+        assert!(signals[6..].iter().all(|s| *s == Signal::Buy));
+
+        let trin = BreadthRule::TrinBelow(1.0).evaluate(&breadth);
+        assert!(trin.iter().all(|p| !p.1));
+        let volume = BreadthRule::UpDownVolumeAbove(1.0).evaluate(&breadth);
+        assert!(volume.iter().all(|p| !p.1));
+
+        let line = BreadthRule::AdvanceDeclineAboveAverage(3).evaluate(&breadth);
+        assert!(!line[1].1 && line[2].1 && !line[7].1);
+    }
+}
@@ -0,0 +1,398 @@
+//! Market breadth and index-level internals
+//!
+//! An index can rise on a handful of large stocks while most of the market
+//! falls. Breadth measures how many stocks take part in a move. This module
+//! tallies a universe of daily bars into one [`BreadthDay`] per date, and
+//! [`MarketBreadth`] derives the usual internals from them:
+//!
+//! - the advance/decline line, the running total of advancers minus
+//!   decliners
+//! - the McClellan oscillator and summation index, exponential averages of
+//!   net advances
+//! - new highs and new lows over a lookback of bars
+//! - the TRIN or Arms index, the advance/decline ratio over the up/down
+//!   volume ratio
+//! - up and down volume
+//!
+//! A stock advances on a date when it closes above its own previous close,
+//! so every series only depends on closes up to that date. [`BreadthFilter`]
+//! uses a [`BreadthRule`] to let a strategy buy only while breadth is
+//! positive.
+//!
+//! # Example
+//!
+//! ```
+//! use day_trade::breadth::{BreadthFilter, BreadthRule, MarketBreadth};
+//! use day_trade::utils::{generate_test_data_with_rng, seeded_rng};
+//! use day_trade::{BreakoutStrategy, TradingStrategy};
+//!
+//! let mut rng = seeded_rng(7);
+//! let universe: Vec<_> = (0..20)
+//!     .map(|_| generate_test_data_with_rng(120, 100.0, 0.02, &mut rng))
+//!     .collect();
+//! let breadth = MarketBreadth::from_universe(universe.iter().map(Vec::as_slice), 52).unwrap();
+//! assert_eq!(breadth.len(), 119);
+//!
+//! let strategy = BreakoutStrategy::default();
+//! let filtered = BreadthFilter::new(strategy, breadth).with_rule(BreadthRule::McClellanAbove(0.0));
+//! let signals = filtered.generate_signals(&universe[0]).unwrap();
+//! assert_eq!(signals.len(), 120);
+//! ```
+
+mod filter;
+
+pub use filter::{BreadthFilter, BreadthRule};
+
+use crate::{DailyOhlcv, TradeError};
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Smoothing periods of the McClellan oscillator
+const MCCLELLAN_FAST: usize = 19;
+const MCCLELLAN_SLOW: usize = 39;
+
+/// Advancing and declining issues and their volume on one date
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct BreadthDay {
+    /// Trading date
+    pub date: NaiveDate,
+    /// Issues that closed above their previous close
+    pub advancers: usize,
+    /// Issues that closed below their previous close
+    pub decliners: usize,
+    /// Issues that closed unchanged
+    pub unchanged: usize,
+    /// Volume of the advancing issues
+    pub up_volume: f64,
+    /// Volume of the declining issues
+    pub down_volume: f64,
+    /// Issues whose high exceeded every high of the lookback before it
+    pub new_highs: usize,
+    /// Issues whose low undercut every low of the lookback before it
+    pub new_lows: usize,
+}
+
+impl BreadthDay {
+    fn new(date: NaiveDate) -> Self {
+        Self {
+            date,
+            advancers: 0,
+            decliners: 0,
+            unchanged: 0,
+            up_volume: 0.0,
+            down_volume: 0.0,
+            new_highs: 0,
+            new_lows: 0,
+        }
+    }
+
+    /// Issues that traded on this date and the one before
+    pub fn issues(&self) -> usize {
+        self.advancers + self.decliners + self.unchanged
+    }
+
+    /// Advancers minus decliners
+    pub fn net_advances(&self) -> i64 {
+        self.advancers as i64 - self.decliners as i64
+    }
+
+    /// Net advances over the issues that moved, (A - D) / (A + D) x 1000
+    ///
+    /// Unchanged issues are left out of the denominator, as in the standard
+    /// ratio-adjusted McClellan oscillator. Zero when nothing moved.
+    pub fn ratio_adjusted_net_advances(&self) -> f64 {
+        match self.advancers + self.decliners {
+            0 => 0.0,
+            moved => self.net_advances() as f64 / moved as f64 * 1000.0,
+        }
+    }
+
+    /// New highs minus new lows
+    pub fn net_new_highs(&self) -> i64 {
+        self.new_highs as i64 - self.new_lows as i64
+    }
+
+    /// Up volume per unit of down volume; `None` without down volume
+    pub fn up_down_volume_ratio(&self) -> Option<f64> {
+        (self.down_volume > 0.0).then(|| self.up_volume / self.down_volume)
+    }
+
+    /// Arms index: the advance/decline ratio divided by the up/down volume
+    /// ratio
+    ///
+    /// Below 1 volume flows into advancing issues, a bullish reading; above
+    /// 1 it flows into decliners. `None` when either ratio is undefined.
+    pub fn trin(&self) -> Option<f64> {
+        if self.decliners == 0 || self.up_volume <= 0.0 {
+            return None;
+        }
+        let volume_ratio = self.up_down_volume_ratio()?;
+        Some(self.advancers as f64 / self.decliners as f64 / volume_ratio)
+    }
+}
+
+/// Breadth of a universe, one [`BreadthDay`] per date
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MarketBreadth {
+    days: Vec<BreadthDay>,
+    high_low_period: usize,
+}
+
+impl MarketBreadth {
+    /// Tally the daily bars of every issue in `universe`
+    ///
+    /// A new high or low needs `high_low_period` earlier bars of the same
+    /// issue, 252 for the classic 52-week highs and lows. Dates on which no
+    /// issue has a previous bar are left out.
+    pub fn from_universe<'a, I>(universe: I, high_low_period: usize) -> Result<Self, TradeError>
+    where
+        I: IntoIterator<Item = &'a [DailyOhlcv]>,
+    {
+        if high_low_period == 0 {
+            return Err(TradeError::InvalidData(
+                "New high/low lookback must be at least one bar".to_string(),
+            ));
+        }
+        let mut days: BTreeMap<NaiveDate, BreadthDay> = BTreeMap::new();
+        for bars in universe {
+            for (i, pair) in bars.windows(2).enumerate() {
+                let (previous, bar) = (&pair[0].data, &pair[1]);
+                let day = days
+                    .entry(bar.date)
+                    .or_insert_with(|| BreadthDay::new(bar.date));
+                let volume = bar.data.volume as f64;
+                match bar.data.close.partial_cmp(&previous.close) {
+                    Some(std::cmp::Ordering::Greater) => {
+                        day.advancers += 1;
+                        day.up_volume += volume;
+                    }
+                    Some(std::cmp::Ordering::Less) => {
+                        day.decliners += 1;
+                        day.down_volume += volume;
+                    }
+                    _ => day.unchanged += 1,
+                }
+
+                // Bar `i + 1` against the `high_low_period` bars before it
+                if let Some(start) = (i + 1).checked_sub(high_low_period) {
+                    let window = &bars[start..=i];
+                    if window.iter().all(|b| bar.data.high > b.data.high) {
+                        day.new_highs += 1;
+                    }
+                    if window.iter().all(|b| bar.data.low < b.data.low) {
+                        day.new_lows += 1;
+                    }
+                }
+            }
+        }
+        if days.is_empty() {
+            return Err(TradeError::InsufficientData(
+                "Breadth needs at least one issue with two bars".to_string(),
+            ));
+        }
+        Ok(Self {
+            days: days.into_values().collect(),
+            high_low_period,
+        })
+    }
+
+    /// Tallies in date order
+    pub fn days(&self) -> &[BreadthDay] {
+        &self.days
+    }
+
+    /// Number of dates
+    pub fn len(&self) -> usize {
+        self.days.len()
+    }
+
+    /// Whether no date was tallied
+    pub fn is_empty(&self) -> bool {
+        self.days.is_empty()
+    }
+
+    /// Bars a new high or low is measured against
+    pub fn high_low_period(&self) -> usize {
+        self.high_low_period
+    }
+
+    /// Tally of `date`
+    pub fn get(&self, date: NaiveDate) -> Option<&BreadthDay> {
+        self.days
+            .binary_search_by_key(&date, |d| d.date)
+            .ok()
+            .map(|i| &self.days[i])
+    }
+
+    fn series<T>(&self, mut f: impl FnMut(&BreadthDay) -> T) -> Vec<(NaiveDate, T)> {
+        self.days.iter().map(|d| (d.date, f(d))).collect()
+    }
+
+    /// Running total of net advances
+    pub fn advance_decline_line(&self) -> Vec<(NaiveDate, f64)> {
+        let mut total = 0.0;
+        self.series(|d| {
+            total += d.net_advances() as f64;
+            total
+        })
+    }
+
+    /// 19-day minus 39-day exponential average of net advances
+    ///
+    /// Both averages start at the first day's net advances, so the first
+    /// few weeks are a warm-up. With `ratio_adjusted`, the averages use
+    /// [`BreadthDay::ratio_adjusted_net_advances`] instead, which keeps the
+    /// scale comparable as the universe grows.
+    pub fn mcclellan_oscillator(&self, ratio_adjusted: bool) -> Vec<(NaiveDate, f64)> {
+        let net: Vec<f64> = self
+            .days
+            .iter()
+            .map(|d| {
+                if ratio_adjusted {
+                    d.ratio_adjusted_net_advances()
+                } else {
+                    d.net_advances() as f64
+                }
+            })
+            .collect();
+        let fast = ema(&net, MCCLELLAN_FAST);
+        let slow = ema(&net, MCCLELLAN_SLOW);
+        self.days
+            .iter()
+            .zip(fast.iter().zip(&slow))
+            .map(|(d, (f, s))| (d.date, f - s))
+            .collect()
+    }
+
+    /// Running total of the McClellan oscillator
+    pub fn mcclellan_summation(&self, ratio_adjusted: bool) -> Vec<(NaiveDate, f64)> {
+        let mut total = 0.0;
+        self.mcclellan_oscillator(ratio_adjusted)
+            .into_iter()
+            .map(|(date, value)| {
+                total += value;
+                (date, total)
+            })
+            .collect()
+    }
+
+    /// New highs and new lows of each date
+    pub fn new_highs_lows(&self) -> Vec<(NaiveDate, usize, usize)> {
+        self.days
+            .iter()
+            .map(|d| (d.date, d.new_highs, d.new_lows))
+            .collect()
+    }
+
+    /// Arms index of each date; see [`BreadthDay::trin`]
+    pub fn trin(&self) -> Vec<(NaiveDate, Option<f64>)> {
+        self.series(BreadthDay::trin)
+    }
+
+    /// Volume of advancing and of declining issues on each date
+    pub fn up_down_volume(&self) -> Vec<(NaiveDate, f64, f64)> {
+        self.days
+            .iter()
+            .map(|d| (d.date, d.up_volume, d.down_volume))
+            .collect()
+    }
+}
+
+/// Exponential moving average seeded with the first value
+fn ema(values: &[f64], period: usize) -> Vec<f64> {
+    let alpha = 2.0 / (period as f64 + 1.0);
+    let mut average = None;
+    values
+        .iter()
+        .map(|&v| {
+            let next = average.map_or(v, |a: f64| a + alpha * (v - a));
+            average = Some(next);
+            next
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OhlcvData;
+
+    pub(super) fn issue(closes: &[f64], volume: u64) -> Vec<DailyOhlcv> {
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, &close)| DailyOhlcv {
+                date: start + chrono::Duration::days(i as i64),
+                data: OhlcvData {
+                    open: close,
+                    high: close,
+                    low: close,
+                    close,
+                    volume,
+                },
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_tally_and_trin() {
+        let up = issue(&[10.0, 11.0, 12.0, 13.0], 300);
+        let down = issue(&[10.0, 9.0, 8.0, 7.0], 100);
+        let flat = issue(&[10.0, 10.0, 10.0, 11.0], 100);
+        let late = issue(&[5.0, 4.0], 100);
+        let universe = [&up[..], &down[..], &flat[..], &late[..1], &late[..]];
+        let breadth = MarketBreadth::from_universe(universe, 2).unwrap();
+
+        assert_eq!(breadth.len(), 3);
+        let first = &breadth.days()[0];
+        assert_eq!(
+            (first.advancers, first.decliners, first.unchanged),
+            (1, 2, 1)
+        );
+        assert_eq!(first.net_advances(), -1);
+        // The unchanged issue stays out of (1 - 2) / (1 + 2)
+        assert!((first.ratio_adjusted_net_advances() + 1000.0 / 3.0).abs() < 1e-9);
+        // (1 / 2) / (300 / 200)
+        assert!((first.trin().unwrap() - 1.0 / 3.0).abs() < 1e-12);
+        assert_eq!(first.up_down_volume_ratio(), Some(1.5));
+
+        let last = breadth.get(up[3].date).unwrap();
+        assert_eq!((last.advancers, last.decliners), (2, 1));
+        assert_eq!((last.new_highs, last.new_lows), (2, 1));
+        assert_eq!(breadth.days()[1].new_highs, 1);
+        assert!(breadth.get(up[0].date).is_none());
+
+        let line: Vec<f64> = breadth.advance_decline_line().iter().map(|p| p.1).collect();
+        assert_eq!(line, [-1.0, -1.0, 0.0]);
+        assert!(MarketBreadth::from_universe([up.as_slice()], 0).is_err());
+        assert!(MarketBreadth::from_universe([&up[..1]], 5).is_err());
+    }
+
+    #[test]
+    fn test_mcclellan_oscillator() {
+        let rising: Vec<f64> = (0..60).map(|i| 100.0 + i as f64).collect();
+        let mut falling = rising.clone();
+        falling.reverse();
+        let universe = [
+            issue(&rising, 100),
+            issue(&rising, 100),
+            issue(&falling, 100),
+        ];
+        let breadth = MarketBreadth::from_universe(universe.iter().map(Vec::as_slice), 10).unwrap();
+
+        // Constant net advances leave both averages equal
+        let oscillator = breadth.mcclellan_oscillator(false);
+        assert_eq!(oscillator.len(), 59);
+        assert!(oscillator.iter().all(|p| p.1.abs() < 1e-9));
+
+        // The fast average turns first when breadth deteriorates
+        let net = [5.0, 5.0, -5.0, -5.0];
+        let fast = ema(&net, MCCLELLAN_FAST);
+        let slow = ema(&net, MCCLELLAN_SLOW);
+        assert!(fast[2] < slow[2] && fast[3] < slow[3]);
+        let adjusted = breadth.mcclellan_summation(true);
+        assert!(adjusted.iter().all(|p| p.1.abs() < 1e-6));
+    }
+}
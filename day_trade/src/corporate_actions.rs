@@ -0,0 +1,453 @@
+//! Corporate actions and split/dividend-adjusted price history
+//!
+//! `DailyOhlcv` data holds the prices that actually traded, so a 4-for-1 split
+//! looks like a 75% crash to any strategy reading raw closes. This module models
+//! splits, cash dividends and symbol changes, loads them from CSV files, and
+//! produces adjusted views of a price history:
+//!
+//! - **Back-adjusted**: the most recent bar keeps its raw price and older bars are
+//!   scaled down, the convention used by most charting and data vendors
+//! - **Forward-adjusted**: the first bar keeps its raw price and later bars are
+//!   scaled up, which keeps historical entry prices intact
+//!
+//! Split adjustments also scale volume so share counts stay comparable across
+//! the split. Dividends only adjust prices.
+//!
+//! # File format
+//!
+//! ```text
+//! symbol,ex_date,action,value
+//! AAPL,2020-08-31,split,4
+//! AAPL,2023-08-11,dividend,0.24
+//! FB,2022-06-09,symbol_change,META
+//! ```
+//!
+//! # Example
+//!
+//! ```
+//! use day_trade::corporate_actions::CorporateActions;
+//! use day_trade::utils::generate_test_data;
+//!
+//! let actions = CorporateActions::parse_csv(
+//!     "symbol,ex_date,action,value\nTEST,2023-01-20,split,2\n",
+//! )
+//! .unwrap();
+//!
+//! let raw = generate_test_data(40, 100.0, 0.02);
+//! let adjusted = actions.back_adjust("TEST", &raw);
+//! assert_eq!(adjusted.len(), raw.len());
+//! ```
+
+use crate::{DailyOhlcv, OhlcvData, TradeError};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// Type of corporate action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CorporateActionKind {
+    /// Stock split expressed as new shares per old share
+    /// (2.0 for a 2-for-1 split, 0.1 for a 1-for-10 reverse split)
+    Split { ratio: f64 },
+    /// Cash dividend paid per share to holders as of the ex-date
+    CashDividend { amount: f64 },
+    /// Ticker change, effective from the ex-date
+    SymbolChange { new_symbol: String },
+}
+
+/// A single corporate action for one security
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CorporateAction {
+    /// Ticker the action was announced under
+    pub symbol: String,
+    /// First trading date on which the action is reflected in prices
+    pub ex_date: NaiveDate,
+    /// Action details
+    pub kind: CorporateActionKind,
+}
+
+impl CorporateAction {
+    /// Create a stock split action
+    pub fn split(symbol: &str, ex_date: NaiveDate, ratio: f64) -> Result<Self, String> {
+        if ratio <= 0.0 || !ratio.is_finite() {
+            return Err("Split ratio must be positive".to_string());
+        }
+
+        Ok(Self {
+            symbol: symbol.to_string(),
+            ex_date,
+            kind: CorporateActionKind::Split { ratio },
+        })
+    }
+
+    /// Create a cash dividend action
+    pub fn cash_dividend(symbol: &str, ex_date: NaiveDate, amount: f64) -> Result<Self, String> {
+        if amount < 0.0 || !amount.is_finite() {
+            return Err("Dividend amount must be non-negative".to_string());
+        }
+
+        Ok(Self {
+            symbol: symbol.to_string(),
+            ex_date,
+            kind: CorporateActionKind::CashDividend { amount },
+        })
+    }
+
+    /// Create a symbol change action
+    pub fn symbol_change(symbol: &str, ex_date: NaiveDate, new_symbol: &str) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            ex_date,
+            kind: CorporateActionKind::SymbolChange {
+                new_symbol: new_symbol.to_string(),
+            },
+        }
+    }
+}
+
+/// Price and volume multipliers applied to a single bar
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdjustmentFactor {
+    /// Multiplier for open, high, low and close
+    pub price: f64,
+    /// Multiplier for volume
+    pub volume: f64,
+}
+
+/// Collection of corporate actions, kept sorted by ex-date
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CorporateActions {
+    actions: Vec<CorporateAction>,
+}
+
+impl CorporateActions {
+    /// Create a collection from a list of actions
+    pub fn new(mut actions: Vec<CorporateAction>) -> Self {
+        actions.sort_by_key(|a| a.ex_date);
+        Self { actions }
+    }
+
+    /// Load corporate actions from a CSV file
+    ///
+    /// See the module documentation for the expected format.
+    pub fn load_csv<P: AsRef<Path>>(path: P) -> Result<Self, TradeError> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| TradeError::DataLoadError(format!("Failed to open file: {}", e)))?;
+
+        Self::parse_csv(&contents)
+    }
+
+    /// Parse corporate actions from CSV text with a header row
+    pub fn parse_csv(contents: &str) -> Result<Self, TradeError> {
+        let mut actions = Vec::new();
+
+        for (i, line) in contents.lines().enumerate().skip(1) {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split(',').map(|f| f.trim()).collect();
+            if fields.len() != 4 {
+                return Err(TradeError::DataLoadError(format!(
+                    "Invalid CSV format at line {}, expected 4 fields",
+                    i + 1
+                )));
+            }
+
+            let ex_date = NaiveDate::parse_from_str(fields[1], "%Y-%m-%d").map_err(|e| {
+                TradeError::DataLoadError(format!("Invalid ex_date at line {}: {}", i + 1, e))
+            })?;
+
+            let parse_value = |name: &str| {
+                fields[3].parse::<f64>().map_err(|e| {
+                    TradeError::DataLoadError(format!("Invalid {} at line {}: {}", name, i + 1, e))
+                })
+            };
+
+            let action = match fields[2].to_lowercase().as_str() {
+                "split" => CorporateAction::split(fields[0], ex_date, parse_value("split ratio")?),
+                "dividend" | "cash_dividend" => CorporateAction::cash_dividend(
+                    fields[0],
+                    ex_date,
+                    parse_value("dividend amount")?,
+                ),
+                "symbol_change" => Ok(CorporateAction::symbol_change(
+                    fields[0], ex_date, fields[3],
+                )),
+                other => {
+                    return Err(TradeError::DataLoadError(format!(
+                        "Unknown action '{}' at line {}",
+                        other,
+                        i + 1
+                    )))
+                }
+            }
+            .map_err(|e| TradeError::DataLoadError(format!("{} at line {}", e, i + 1)))?;
+
+            actions.push(action);
+        }
+
+        Ok(Self::new(actions))
+    }
+
+    /// Get all actions sorted by ex-date
+    pub fn actions(&self) -> &[CorporateAction] {
+        &self.actions
+    }
+
+    /// Number of actions in the collection
+    pub fn len(&self) -> usize {
+        self.actions.len()
+    }
+
+    /// Check if the collection is empty
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// Get every ticker the security currently trading as `symbol` has used
+    fn aliases(&self, symbol: &str) -> HashSet<String> {
+        let mut aliases = HashSet::new();
+        aliases.insert(symbol.to_string());
+
+        // Walk symbol changes newest first so chains like A -> B -> C resolve
+        for action in self.actions.iter().rev() {
+            if let CorporateActionKind::SymbolChange { new_symbol } = &action.kind {
+                if aliases.contains(new_symbol) {
+                    aliases.insert(action.symbol.clone());
+                }
+            }
+        }
+
+        aliases
+    }
+
+    /// Get the actions affecting the security currently trading as `symbol`,
+    /// including actions announced under its former tickers
+    pub fn for_symbol(&self, symbol: &str) -> CorporateActions {
+        let aliases = self.aliases(symbol);
+
+        CorporateActions {
+            actions: self
+                .actions
+                .iter()
+                .filter(|a| aliases.contains(&a.symbol))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Get the ticker that the security currently trading as `symbol` used on `date`
+    pub fn symbol_on(&self, symbol: &str, date: NaiveDate) -> String {
+        let mut current = symbol.to_string();
+
+        for action in self.actions.iter().rev() {
+            if action.ex_date <= date {
+                break;
+            }
+            if let CorporateActionKind::SymbolChange { new_symbol } = &action.kind {
+                if *new_symbol == current {
+                    current = action.symbol.clone();
+                }
+            }
+        }
+
+        current
+    }
+
+    /// Calculate back-adjustment factors for each bar of `data`
+    ///
+    /// A bar is adjusted by every split and dividend whose ex-date falls after it.
+    /// Dividends use the multiplicative `1 - dividend / previous close` convention,
+    /// where the previous close is the last raw close before the ex-date.
+    pub fn back_adjustment_factors(
+        &self,
+        symbol: &str,
+        data: &[DailyOhlcv],
+    ) -> Vec<AdjustmentFactor> {
+        let relevant = self.for_symbol(symbol);
+        let mut factors = vec![
+            AdjustmentFactor {
+                price: 1.0,
+                volume: 1.0,
+            };
+            data.len()
+        ];
+
+        for action in &relevant.actions {
+            // Bars strictly before the ex-date receive the adjustment
+            let cutoff = data.partition_point(|bar| bar.date < action.ex_date);
+            if cutoff == 0 {
+                continue;
+            }
+
+            let (price, volume) = match action.kind {
+                CorporateActionKind::Split { ratio } => (1.0 / ratio, ratio),
+                CorporateActionKind::CashDividend { amount } => {
+                    let prev_close = data[cutoff - 1].data.close;
+                    if prev_close <= amount || prev_close <= 0.0 {
+                        continue;
+                    }
+                    (1.0 - amount / prev_close, 1.0)
+                }
+                CorporateActionKind::SymbolChange { .. } => continue,
+            };
+
+            for factor in &mut factors[..cutoff] {
+                factor.price *= price;
+                factor.volume *= volume;
+            }
+        }
+
+        factors
+    }
+
+    /// Produce a back-adjusted copy of `data`
+    ///
+    /// The last bar keeps its raw prices; earlier bars are scaled so that splits
+    /// and dividends no longer appear as price gaps.
+    pub fn back_adjust(&self, symbol: &str, data: &[DailyOhlcv]) -> Vec<DailyOhlcv> {
+        let factors = self.back_adjustment_factors(symbol, data);
+        apply_factors(data, &factors)
+    }
+
+    /// Produce a forward-adjusted copy of `data`
+    ///
+    /// The first bar keeps its raw prices; later bars are scaled into the same
+    /// terms, so historical prices match what was actually paid at the time.
+    pub fn forward_adjust(&self, symbol: &str, data: &[DailyOhlcv]) -> Vec<DailyOhlcv> {
+        let back = self.back_adjustment_factors(symbol, data);
+        let base = match back.first() {
+            Some(factor) => *factor,
+            None => return Vec::new(),
+        };
+
+        let factors: Vec<AdjustmentFactor> = back
+            .iter()
+            .map(|f| AdjustmentFactor {
+                price: f.price / base.price,
+                volume: f.volume / base.volume,
+            })
+            .collect();
+
+        apply_factors(data, &factors)
+    }
+}
+
+/// Apply per-bar adjustment factors to a price history
+fn apply_factors(data: &[DailyOhlcv], factors: &[AdjustmentFactor]) -> Vec<DailyOhlcv> {
+    data.iter()
+        .zip(factors.iter())
+        .map(|(bar, factor)| DailyOhlcv {
+            date: bar.date,
+            data: OhlcvData {
+                open: bar.data.open * factor.price,
+                high: bar.data.high * factor.price,
+                low: bar.data.low * factor.price,
+                close: bar.data.close * factor.price,
+                volume: (bar.data.volume as f64 * factor.volume).round() as u64,
+            },
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(day: u32, price: f64, volume: u64) -> DailyOhlcv {
+        DailyOhlcv {
+            date: NaiveDate::from_ymd_opt(2023, 3, day).unwrap(),
+            data: OhlcvData {
+                open: price,
+                high: price + 1.0,
+                low: price - 1.0,
+                close: price,
+                volume,
+            },
+        }
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2023, 3, day).unwrap()
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let csv = "symbol,ex_date,action,value\n\
+                   ABC,2023-03-03,split,2\n\
+                   ABC,2023-03-02,dividend,0.5\n\
+                   ABC,2023-03-04,symbol_change,XYZ\n";
+        let actions = CorporateActions::parse_csv(csv).unwrap();
+
+        assert_eq!(actions.len(), 3);
+        // Sorted by ex-date
+        assert_eq!(actions.actions()[0].ex_date, date(2));
+        assert_eq!(
+            actions.actions()[1].kind,
+            CorporateActionKind::Split { ratio: 2.0 }
+        );
+
+        assert!(CorporateActions::parse_csv("h\nABC,2023-03-03,merger,1\n").is_err());
+        assert!(CorporateActions::parse_csv("h\nABC,2023-03-03,split,-2\n").is_err());
+        assert!(CorporateActions::parse_csv("h\nABC,03/03/2023,split,2\n").is_err());
+    }
+
+    #[test]
+    fn test_split_back_and_forward_adjust() {
+        let data = vec![bar(1, 100.0, 1000), bar(2, 102.0, 1000), bar(3, 51.0, 2000)];
+        let actions =
+            CorporateActions::new(vec![CorporateAction::split("ABC", date(3), 2.0).unwrap()]);
+
+        let back = actions.back_adjust("ABC", &data);
+        assert!((back[0].data.close - 50.0).abs() < 1e-9);
+        assert!((back[1].data.close - 51.0).abs() < 1e-9);
+        assert!((back[2].data.close - 51.0).abs() < 1e-9);
+        assert_eq!(back[0].data.volume, 2000);
+        assert_eq!(back[2].data.volume, 2000);
+
+        let forward = actions.forward_adjust("ABC", &data);
+        assert!((forward[0].data.close - 100.0).abs() < 1e-9);
+        assert!((forward[2].data.close - 102.0).abs() < 1e-9);
+        assert_eq!(forward[2].data.volume, 1000);
+    }
+
+    #[test]
+    fn test_dividend_adjustment() {
+        let data = vec![bar(1, 100.0, 1000), bar(2, 99.0, 1000)];
+        let actions =
+            CorporateActions::new(vec![
+                CorporateAction::cash_dividend("ABC", date(2), 1.0).unwrap()
+            ]);
+
+        let back = actions.back_adjust("ABC", &data);
+        assert!((back[0].data.close - 99.0).abs() < 1e-9);
+        assert!((back[1].data.close - 99.0).abs() < 1e-9);
+        // Dividends don't touch volume
+        assert_eq!(back[0].data.volume, 1000);
+    }
+
+    #[test]
+    fn test_symbol_change_chain() {
+        let actions = CorporateActions::new(vec![
+            CorporateAction::split("OLD", date(2), 2.0).unwrap(),
+            CorporateAction::symbol_change("OLD", date(3), "MID"),
+            CorporateAction::symbol_change("MID", date(5), "NEW"),
+            CorporateAction::split("OTHER", date(2), 3.0).unwrap(),
+        ]);
+
+        let relevant = actions.for_symbol("NEW");
+        assert_eq!(relevant.len(), 3);
+
+        assert_eq!(actions.symbol_on("NEW", date(1)), "OLD");
+        assert_eq!(actions.symbol_on("NEW", date(4)), "MID");
+        assert_eq!(actions.symbol_on("NEW", date(6)), "NEW");
+
+        let data = vec![bar(1, 100.0, 1000), bar(2, 50.0, 2000)];
+        let back = actions.back_adjust("NEW", &data);
+        assert!((back[0].data.close - 50.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,1249 @@
+//! Data quality checks and cleaning pipeline
+//!
+//! Raw vendor data regularly contains bad ticks: highs below lows, one-bar price
+//! spikes, frozen feeds that repeat the same price, missing bars and prints
+//! outside the trading session. This module provides a pipeline of pluggable
+//! cleaning stages that runs over `DailyOhlcv` or `MinuteOhlcv` bars and returns
+//! the cleaned series together with an audit log of every change it made.
+//!
+//! Available stages:
+//!
+//! - [`OhlcConsistency`]: repairs or drops bars whose high/low don't bracket open/close
+//! - [`SpikeFilter`]: removes one-bar spikes using MAD on returns or a Hampel filter
+//! - [`StalePriceDetector`]: flags or drops runs of repeated, zero-range prices
+//! - [`GapFill`]: fills missing values and missing bars (forward-fill, drop, interpolate)
+//! - [`SessionFilter`]: keeps only bars inside a trading session
+//!
+//! # Example
+//!
+//! ```
+//! use day_trade::data_quality::{CleaningPipeline, GapFill, GapPolicy, OhlcConsistency, SpikeFilter};
+//! use day_trade::utils::generate_test_data;
+//!
+//! let data = generate_test_data(100, 100.0, 0.02);
+//!
+//! let pipeline = CleaningPipeline::new()
+//!     .with_stage(OhlcConsistency::repair())
+//!     .with_stage(SpikeFilter::hampel(5, 3.0).unwrap())
+//!     .with_stage(GapFill::daily(GapPolicy::ForwardFill));
+//!
+//! let result = pipeline.run(data).unwrap();
+//! for entry in result.audit() {
+//!     println!("{}", entry);
+//! }
+//! ```
+
+use crate::{DailyOhlcv, MinuteOhlcv, OhlcvData, TradeError};
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveTime, Timelike, Utc, Weekday,
+};
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// A price bar that can be cleaned by the pipeline
+pub trait Bar: Clone {
+    /// Timestamp of the bar (daily bars use midnight UTC)
+    fn timestamp(&self) -> DateTime<Utc>;
+
+    /// OHLCV values of the bar
+    fn ohlcv(&self) -> &OhlcvData;
+
+    /// Mutable OHLCV values of the bar
+    fn ohlcv_mut(&mut self) -> &mut OhlcvData;
+
+    /// Create a new bar at the given timestamp
+    fn from_parts(timestamp: DateTime<Utc>, data: OhlcvData) -> Self;
+}
+
+impl Bar for DailyOhlcv {
+    fn timestamp(&self) -> DateTime<Utc> {
+        self.date.and_time(NaiveTime::MIN).and_utc()
+    }
+
+    fn ohlcv(&self) -> &OhlcvData {
+        &self.data
+    }
+
+    fn ohlcv_mut(&mut self) -> &mut OhlcvData {
+        &mut self.data
+    }
+
+    fn from_parts(timestamp: DateTime<Utc>, data: OhlcvData) -> Self {
+        Self {
+            date: timestamp.date_naive(),
+            data,
+        }
+    }
+}
+
+impl Bar for MinuteOhlcv {
+    fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    fn ohlcv(&self) -> &OhlcvData {
+        &self.data
+    }
+
+    fn ohlcv_mut(&mut self) -> &mut OhlcvData {
+        &mut self.data
+    }
+
+    fn from_parts(timestamp: DateTime<Utc>, data: OhlcvData) -> Self {
+        Self { timestamp, data }
+    }
+}
+
+/// Kind of change recorded in the audit log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    /// Bar values were changed
+    Modified,
+    /// Bar was removed from the series
+    Removed,
+    /// Bar was inserted into the series
+    Inserted,
+    /// Bar was left unchanged but looks suspicious
+    Flagged,
+}
+
+/// A single change made by a cleaning stage
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditEntry {
+    /// Name of the stage that made the change
+    pub stage: String,
+    /// Timestamp of the affected bar
+    pub timestamp: DateTime<Utc>,
+    /// What happened to the bar
+    pub action: AuditAction,
+    /// Human-readable details
+    pub detail: String,
+}
+
+impl fmt::Display for AuditEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}] {} {:?}: {}",
+            self.stage,
+            self.timestamp.format("%Y-%m-%d %H:%M:%S"),
+            self.action,
+            self.detail
+        )
+    }
+}
+
+/// Audit log collected while running a pipeline
+#[derive(Debug, Clone, Default)]
+pub struct AuditLog {
+    entries: Vec<AuditEntry>,
+}
+
+impl AuditLog {
+    /// Record a change
+    pub fn record(
+        &mut self,
+        stage: &str,
+        timestamp: DateTime<Utc>,
+        action: AuditAction,
+        detail: impl Into<String>,
+    ) {
+        self.entries.push(AuditEntry {
+            stage: stage.to_string(),
+            timestamp,
+            action,
+            detail: detail.into(),
+        });
+    }
+
+    /// All recorded entries in the order they were made
+    pub fn entries(&self) -> &[AuditEntry] {
+        &self.entries
+    }
+
+    /// Number of entries with the given action
+    pub fn count(&self, action: AuditAction) -> usize {
+        self.entries.iter().filter(|e| e.action == action).count()
+    }
+}
+
+/// A single step of a cleaning pipeline
+pub trait CleaningStage<B: Bar> {
+    /// Name used in the audit log
+    fn name(&self) -> &str;
+
+    /// Clean the bars, recording every change in `log`
+    fn apply(&self, bars: Vec<B>, log: &mut AuditLog) -> Result<Vec<B>, TradeError>;
+}
+
+/// Cleaned series plus the audit log describing how it was produced
+#[derive(Debug, Clone)]
+pub struct CleaningResult<B> {
+    data: Vec<B>,
+    log: AuditLog,
+}
+
+impl<B> CleaningResult<B> {
+    /// The cleaned bars
+    pub fn data(&self) -> &[B] {
+        &self.data
+    }
+
+    /// Consume the result, returning the cleaned bars
+    pub fn into_data(self) -> Vec<B> {
+        self.data
+    }
+
+    /// Every change made by the pipeline
+    pub fn audit(&self) -> &[AuditEntry] {
+        self.log.entries()
+    }
+
+    /// The full audit log
+    pub fn log(&self) -> &AuditLog {
+        &self.log
+    }
+}
+
+/// Ordered sequence of cleaning stages
+pub struct CleaningPipeline<B: Bar> {
+    stages: Vec<Box<dyn CleaningStage<B>>>,
+}
+
+impl<B: Bar> Default for CleaningPipeline<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<B: Bar> CleaningPipeline<B> {
+    /// Create an empty pipeline
+    pub fn new() -> Self {
+        Self { stages: Vec::new() }
+    }
+
+    /// Append a stage to the pipeline
+    pub fn with_stage<S: CleaningStage<B> + 'static>(mut self, stage: S) -> Self {
+        self.stages.push(Box::new(stage));
+        self
+    }
+
+    /// Names of the configured stages, in execution order
+    pub fn stage_names(&self) -> Vec<&str> {
+        self.stages.iter().map(|s| s.name()).collect()
+    }
+
+    /// Run every stage in order
+    ///
+    /// Bars are sorted by timestamp before the first stage runs.
+    pub fn run(&self, mut bars: Vec<B>) -> Result<CleaningResult<B>, TradeError> {
+        let mut log = AuditLog::default();
+        bars.sort_by_key(|b| b.timestamp());
+
+        for stage in &self.stages {
+            bars = stage.apply(bars, &mut log)?;
+        }
+
+        Ok(CleaningResult { data: bars, log })
+    }
+}
+
+/// What to do with a bar that fails a check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultAction {
+    /// Fix the bar in place
+    Repair,
+    /// Remove the bar
+    Drop,
+    /// Keep the bar unchanged and only record it
+    Flag,
+}
+
+/// Checks that high and low bracket open and close and that prices are positive
+#[derive(Debug, Clone)]
+pub struct OhlcConsistency {
+    action: FaultAction,
+}
+
+impl OhlcConsistency {
+    /// Create a consistency check with the given fault action
+    pub fn new(action: FaultAction) -> Self {
+        Self { action }
+    }
+
+    /// Widen high/low to cover open and close
+    pub fn repair() -> Self {
+        Self::new(FaultAction::Repair)
+    }
+
+    /// Remove inconsistent bars
+    pub fn drop() -> Self {
+        Self::new(FaultAction::Drop)
+    }
+}
+
+impl<B: Bar> CleaningStage<B> for OhlcConsistency {
+    fn name(&self) -> &str {
+        "ohlc_consistency"
+    }
+
+    fn apply(&self, bars: Vec<B>, log: &mut AuditLog) -> Result<Vec<B>, TradeError> {
+        let stage = CleaningStage::<B>::name(self);
+        let mut cleaned = Vec::with_capacity(bars.len());
+
+        for mut bar in bars {
+            let d = bar.ohlcv();
+            let prices = [d.open, d.high, d.low, d.close];
+
+            // NaN prices are left to GapFill; only impossible values are handled here
+            if prices.iter().any(|p| p.is_infinite() || *p <= 0.0) {
+                if self.action == FaultAction::Flag {
+                    log.record(
+                        stage,
+                        bar.timestamp(),
+                        AuditAction::Flagged,
+                        "non-positive price",
+                    );
+                    cleaned.push(bar);
+                } else {
+                    log.record(
+                        stage,
+                        bar.timestamp(),
+                        AuditAction::Removed,
+                        "non-positive price",
+                    );
+                }
+                continue;
+            }
+
+            if prices.iter().any(|p| p.is_nan()) {
+                cleaned.push(bar);
+                continue;
+            }
+
+            let max_body = d.open.max(d.close);
+            let min_body = d.open.min(d.close);
+            if d.high >= max_body && d.low <= min_body {
+                cleaned.push(bar);
+                continue;
+            }
+
+            let detail = format!(
+                "inconsistent OHLC (o={:.4}, h={:.4}, l={:.4}, c={:.4})",
+                d.open, d.high, d.low, d.close
+            );
+            match self.action {
+                FaultAction::Repair => {
+                    let data = bar.ohlcv_mut();
+                    let high = data.high.max(data.low).max(max_body);
+                    let low = data.low.min(data.high).min(min_body);
+                    data.high = high;
+                    data.low = low;
+                    log.record(stage, bar.timestamp(), AuditAction::Modified, detail);
+                    cleaned.push(bar);
+                }
+                FaultAction::Drop => {
+                    log.record(stage, bar.timestamp(), AuditAction::Removed, detail);
+                }
+                FaultAction::Flag => {
+                    log.record(stage, bar.timestamp(), AuditAction::Flagged, detail);
+                    cleaned.push(bar);
+                }
+            }
+        }
+
+        Ok(cleaned)
+    }
+}
+
+/// Outlier detection method used by [`SpikeFilter`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpikeMethod {
+    /// Median absolute deviation of log returns over the whole series
+    ///
+    /// A bar is a spike when both the return into it and the return out of it
+    /// are outliers with opposite signs, so genuine level shifts are kept.
+    Mad {
+        /// Outlier threshold in scaled MADs
+        threshold: f64,
+    },
+    /// Hampel filter on closes over a centered rolling window
+    Hampel {
+        /// Number of bars on each side of the center
+        half_window: usize,
+        /// Outlier threshold in scaled MADs
+        threshold: f64,
+    },
+}
+
+/// Scale factor that makes the MAD a consistent estimator of the standard deviation
+const MAD_SCALE: f64 = 1.4826;
+
+/// Removes one-bar price spikes
+#[derive(Debug, Clone)]
+pub struct SpikeFilter {
+    method: SpikeMethod,
+    action: FaultAction,
+}
+
+impl SpikeFilter {
+    /// Create a spike filter with the given method and fault action
+    pub fn new(method: SpikeMethod, action: FaultAction) -> Result<Self, String> {
+        match method {
+            SpikeMethod::Mad { threshold } => {
+                crate::utils::validate_positive(threshold, "MAD threshold")?;
+            }
+            SpikeMethod::Hampel {
+                half_window,
+                threshold,
+            } => {
+                if half_window == 0 {
+                    return Err("Hampel half window must be at least 1".to_string());
+                }
+                crate::utils::validate_positive(threshold, "Hampel threshold")?;
+            }
+        }
+
+        Ok(Self { method, action })
+    }
+
+    /// MAD filter on log returns that repairs spikes
+    pub fn mad(threshold: f64) -> Result<Self, String> {
+        Self::new(SpikeMethod::Mad { threshold }, FaultAction::Repair)
+    }
+
+    /// Hampel filter on closes that repairs spikes
+    pub fn hampel(half_window: usize, threshold: f64) -> Result<Self, String> {
+        Self::new(
+            SpikeMethod::Hampel {
+                half_window,
+                threshold,
+            },
+            FaultAction::Repair,
+        )
+    }
+
+    /// Find spikes, returning (index, replacement close)
+    fn detect(&self, closes: &[f64]) -> Vec<(usize, f64)> {
+        match self.method {
+            SpikeMethod::Mad { threshold } => detect_mad_spikes(closes, threshold),
+            SpikeMethod::Hampel {
+                half_window,
+                threshold,
+            } => detect_hampel_spikes(closes, half_window, threshold),
+        }
+    }
+}
+
+impl<B: Bar> CleaningStage<B> for SpikeFilter {
+    fn name(&self) -> &str {
+        match self.method {
+            SpikeMethod::Mad { .. } => "spike_filter_mad",
+            SpikeMethod::Hampel { .. } => "spike_filter_hampel",
+        }
+    }
+
+    fn apply(&self, mut bars: Vec<B>, log: &mut AuditLog) -> Result<Vec<B>, TradeError> {
+        let stage = CleaningStage::<B>::name(self);
+        let closes: Vec<f64> = bars.iter().map(|b| b.ohlcv().close).collect();
+        let spikes = self.detect(&closes);
+
+        let mut remove = vec![false; bars.len()];
+        for (i, replacement) in spikes {
+            let detail = format!("close {:.4} vs expected {:.4}", closes[i], replacement);
+            match self.action {
+                FaultAction::Repair => {
+                    // Rescale the whole bar so its intrabar shape is kept
+                    let scale = replacement / closes[i];
+                    let data = bars[i].ohlcv_mut();
+                    data.open *= scale;
+                    data.high *= scale;
+                    data.low *= scale;
+                    data.close = replacement;
+                    log.record(stage, bars[i].timestamp(), AuditAction::Modified, detail);
+                }
+                FaultAction::Drop => {
+                    remove[i] = true;
+                    log.record(stage, bars[i].timestamp(), AuditAction::Removed, detail);
+                }
+                FaultAction::Flag => {
+                    log.record(stage, bars[i].timestamp(), AuditAction::Flagged, detail);
+                }
+            }
+        }
+
+        let mut index = 0;
+        bars.retain(|_| {
+            let keep = !remove[index];
+            index += 1;
+            keep
+        });
+
+        Ok(bars)
+    }
+}
+
+/// Median of a slice of finite values
+fn median(values: &mut [f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+
+    values.sort_by(|a, b| a.total_cmp(b));
+    let mid = values.len() / 2;
+    if values.len() % 2 == 1 {
+        Some(values[mid])
+    } else {
+        Some((values[mid - 1] + values[mid]) / 2.0)
+    }
+}
+
+/// Median and scaled median absolute deviation of a set of values
+fn median_and_mad(values: &[f64]) -> Option<(f64, f64)> {
+    let mut sorted: Vec<f64> = values.iter().copied().filter(|v| v.is_finite()).collect();
+    let center = median(&mut sorted)?;
+    let mut deviations: Vec<f64> = sorted.iter().map(|v| (v - center).abs()).collect();
+    let mad = median(&mut deviations)? * MAD_SCALE;
+    Some((center, mad))
+}
+
+fn detect_mad_spikes(closes: &[f64], threshold: f64) -> Vec<(usize, f64)> {
+    if closes.len() < 3 {
+        return Vec::new();
+    }
+
+    let returns: Vec<f64> = closes
+        .windows(2)
+        .map(|w| {
+            if w[0] > 0.0 && w[1] > 0.0 {
+                (w[1] / w[0]).ln()
+            } else {
+                f64::NAN
+            }
+        })
+        .collect();
+
+    let (center, mad) = match median_and_mad(&returns) {
+        Some(stats) => stats,
+        None => return Vec::new(),
+    };
+    if mad <= 0.0 {
+        return Vec::new();
+    }
+
+    let is_outlier = |r: f64| r.is_finite() && (r - center).abs() > threshold * mad;
+
+    let mut spikes = Vec::new();
+    for i in 1..closes.len() - 1 {
+        let into = returns[i - 1];
+        let out_of = returns[i];
+        if is_outlier(into) && is_outlier(out_of) && into.signum() != out_of.signum() {
+            // Geometric midpoint of the neighbouring closes
+            spikes.push((i, (closes[i - 1] * closes[i + 1]).sqrt()));
+        }
+    }
+
+    spikes
+}
+
+fn detect_hampel_spikes(closes: &[f64], half_window: usize, threshold: f64) -> Vec<(usize, f64)> {
+    let mut spikes = Vec::new();
+
+    for i in 0..closes.len() {
+        if !closes[i].is_finite() {
+            continue;
+        }
+
+        let start = i.saturating_sub(half_window);
+        let end = (i + half_window + 1).min(closes.len());
+        if end - start < 3 {
+            continue;
+        }
+
+        let (center, mad) = match median_and_mad(&closes[start..end]) {
+            Some(stats) => stats,
+            None => continue,
+        };
+
+        if mad > 0.0 && (closes[i] - center).abs() > threshold * mad {
+            spikes.push((i, center));
+        }
+    }
+
+    spikes
+}
+
+/// Detects frozen prices: consecutive bars with the same close and no range
+#[derive(Debug, Clone)]
+pub struct StalePriceDetector {
+    max_repeats: usize,
+    action: FaultAction,
+}
+
+impl StalePriceDetector {
+    /// Create a detector that handles bars repeating for more than `max_repeats` bars
+    ///
+    /// `FaultAction::Repair` is treated like `FaultAction::Drop`, since a stale
+    /// price has no better value to repair to.
+    pub fn new(max_repeats: usize, action: FaultAction) -> Result<Self, String> {
+        crate::utils::validate_period(max_repeats, 1)?;
+        Ok(Self {
+            max_repeats,
+            action,
+        })
+    }
+
+    /// Flag bars repeating for more than `max_repeats` bars
+    pub fn flag(max_repeats: usize) -> Result<Self, String> {
+        Self::new(max_repeats, FaultAction::Flag)
+    }
+
+    /// Drop bars repeating for more than `max_repeats` bars
+    pub fn drop(max_repeats: usize) -> Result<Self, String> {
+        Self::new(max_repeats, FaultAction::Drop)
+    }
+}
+
+impl<B: Bar> CleaningStage<B> for StalePriceDetector {
+    fn name(&self) -> &str {
+        "stale_price"
+    }
+
+    fn apply(&self, bars: Vec<B>, log: &mut AuditLog) -> Result<Vec<B>, TradeError> {
+        let stage = CleaningStage::<B>::name(self);
+        let mut cleaned: Vec<B> = Vec::with_capacity(bars.len());
+        let mut run_length = 0usize;
+        let mut previous: Option<OhlcvData> = None;
+
+        for bar in bars {
+            let d = bar.ohlcv();
+            let flat = d.high == d.low && d.open == d.close && d.close == d.high;
+            let repeated = previous.as_ref().is_some_and(|p| p.close == d.close);
+
+            run_length = if flat && repeated { run_length + 1 } else { 0 };
+            previous = Some(d.clone());
+
+            if run_length < self.max_repeats {
+                cleaned.push(bar);
+                continue;
+            }
+
+            let detail = format!("price {:.4} unchanged for {} bars", d.close, run_length + 1);
+            if self.action == FaultAction::Flag {
+                log.record(stage, bar.timestamp(), AuditAction::Flagged, detail);
+                cleaned.push(bar);
+            } else {
+                log.record(stage, bar.timestamp(), AuditAction::Removed, detail);
+            }
+        }
+
+        Ok(cleaned)
+    }
+}
+
+/// How [`GapFill`] handles missing values and missing bars
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GapPolicy {
+    /// Repeat the previous close with zero volume
+    ForwardFill,
+    /// Remove bars with missing values and leave gaps in place
+    Drop,
+    /// Linearly interpolate between the surrounding closes
+    Interpolate,
+}
+
+/// Expected spacing between bars
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BarFrequency {
+    /// One bar per weekday that is not a holiday
+    Daily,
+    /// One bar every `n` minutes within the same calendar day
+    Minutes(u32),
+}
+
+/// Fills missing values (NaN prices) and missing bars
+///
+/// Daily bars are expected on every weekday except the holidays given to
+/// [`GapFill::with_holidays`]; without them, exchange holidays such as Good
+/// Friday look like missing bars. Gaps longer than `max_gap` bars are never
+/// filled and are flagged in the audit log instead.
+#[derive(Debug, Clone)]
+pub struct GapFill {
+    policy: GapPolicy,
+    frequency: BarFrequency,
+    max_gap: usize,
+    holidays: BTreeSet<NaiveDate>,
+}
+
+impl GapFill {
+    /// Create a gap filler that inserts at most `max_gap` bars per gap
+    pub fn new(policy: GapPolicy, frequency: BarFrequency, max_gap: usize) -> Self {
+        Self {
+            policy,
+            frequency,
+            max_gap,
+            holidays: BTreeSet::new(),
+        }
+    }
+
+    /// Weekdays without a session, which daily bars are not expected on
+    pub fn with_holidays(mut self, holidays: impl IntoIterator<Item = NaiveDate>) -> Self {
+        self.holidays.extend(holidays);
+        self
+    }
+
+    /// Gap filler for daily bars, filling up to a week of missing weekdays
+    pub fn daily(policy: GapPolicy) -> Self {
+        Self::new(policy, BarFrequency::Daily, 5)
+    }
+
+    /// Gap filler for one-minute bars, filling up to 30 missing minutes
+    pub fn minute(policy: GapPolicy) -> Self {
+        Self::new(policy, BarFrequency::Minutes(1), 30)
+    }
+
+    /// Expected timestamps strictly between two bars
+    fn missing_between(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+        let mut missing = Vec::new();
+
+        match self.frequency {
+            BarFrequency::Daily => {
+                let mut current = from + Duration::days(1);
+                while current < to {
+                    let weekend = matches!(current.weekday(), Weekday::Sat | Weekday::Sun);
+                    if !weekend && !self.holidays.contains(&current.date_naive()) {
+                        missing.push(current);
+                    }
+                    current += Duration::days(1);
+                }
+            }
+            BarFrequency::Minutes(step) => {
+                // Never fill across sessions
+                if from.date_naive() != to.date_naive() || step == 0 {
+                    return missing;
+                }
+                let step = Duration::minutes(step as i64);
+                let mut current = from + step;
+                while current < to {
+                    missing.push(current);
+                    current += step;
+                }
+            }
+        }
+
+        missing
+    }
+
+    /// Audit detail for a gap of `missing` bars
+    fn describe_gap(&self, missing: usize) -> String {
+        if missing > self.max_gap {
+            format!(
+                "{} missing bars before this bar, more than the {} that are filled",
+                missing, self.max_gap
+            )
+        } else {
+            format!("{} missing bars before this bar", missing)
+        }
+    }
+}
+
+impl<B: Bar> CleaningStage<B> for GapFill {
+    fn name(&self) -> &str {
+        match self.policy {
+            GapPolicy::ForwardFill => "gap_fill_forward",
+            GapPolicy::Drop => "gap_fill_drop",
+            GapPolicy::Interpolate => "gap_fill_interpolate",
+        }
+    }
+
+    fn apply(&self, bars: Vec<B>, log: &mut AuditLog) -> Result<Vec<B>, TradeError> {
+        let stage = CleaningStage::<B>::name(self);
+        let has_missing = |d: &OhlcvData| {
+            d.open.is_nan() || d.high.is_nan() || d.low.is_nan() || d.close.is_nan()
+        };
+
+        if self.policy == GapPolicy::Drop {
+            let mut cleaned: Vec<B> = Vec::with_capacity(bars.len());
+            for bar in bars {
+                if has_missing(bar.ohlcv()) {
+                    log.record(
+                        stage,
+                        bar.timestamp(),
+                        AuditAction::Removed,
+                        "missing values",
+                    );
+                    continue;
+                }
+
+                let gap = cleaned.last().map_or(0, |prev| {
+                    self.missing_between(prev.timestamp(), bar.timestamp())
+                        .len()
+                });
+                if gap > 0 {
+                    log.record(
+                        stage,
+                        bar.timestamp(),
+                        AuditAction::Flagged,
+                        self.describe_gap(gap),
+                    );
+                }
+                cleaned.push(bar);
+            }
+            return Ok(cleaned);
+        }
+
+        // Fill missing values in place first, then insert missing bars
+        let mut filled: Vec<B> = Vec::with_capacity(bars.len());
+        // Index in `bars` of the last bar kept, so runs of missing values are
+        // interpolated by position like missing bars are
+        let mut previous_index: Option<usize> = None;
+        for i in 0..bars.len() {
+            let mut bar = bars[i].clone();
+            if has_missing(bar.ohlcv()) {
+                let previous = filled.last().map(|b| b.ohlcv().close);
+                let next = bars[i + 1..]
+                    .iter()
+                    .position(|b| b.ohlcv().close.is_finite())
+                    .map(|k| (i + 1 + k, bars[i + 1 + k].ohlcv().close));
+
+                let value = match (self.policy, previous, previous_index, next) {
+                    (GapPolicy::Interpolate, Some(p), Some(pi), Some((ni, n))) => {
+                        p + (n - p) * (i - pi) as f64 / (ni - pi) as f64
+                    }
+                    (_, Some(p), _, _) => p,
+                    (_, None, _, Some((_, n))) => n,
+                    _ => {
+                        log.record(
+                            stage,
+                            bar.timestamp(),
+                            AuditAction::Removed,
+                            "no value to fill from",
+                        );
+                        continue;
+                    }
+                };
+
+                let data = bar.ohlcv_mut();
+                for price in [
+                    &mut data.open,
+                    &mut data.high,
+                    &mut data.low,
+                    &mut data.close,
+                ] {
+                    if price.is_nan() {
+                        *price = value;
+                    }
+                }
+                data.high = data.high.max(data.open).max(data.close);
+                data.low = data.low.min(data.open).min(data.close);
+                log.record(
+                    stage,
+                    bar.timestamp(),
+                    AuditAction::Modified,
+                    format!("filled missing values with {:.4}", value),
+                );
+            }
+
+            if let Some(previous) = filled.last().cloned() {
+                let missing = self.missing_between(previous.timestamp(), bar.timestamp());
+                if missing.len() > self.max_gap {
+                    log.record(
+                        stage,
+                        bar.timestamp(),
+                        AuditAction::Flagged,
+                        self.describe_gap(missing.len()),
+                    );
+                } else {
+                    let start = previous.ohlcv().close;
+                    let end = bar.ohlcv().close;
+                    let steps = missing.len() + 1;
+
+                    for (k, timestamp) in missing.into_iter().enumerate() {
+                        let price = match self.policy {
+                            GapPolicy::Interpolate => {
+                                start + (end - start) * (k + 1) as f64 / steps as f64
+                            }
+                            _ => start,
+                        };
+                        filled.push(B::from_parts(
+                            timestamp,
+                            OhlcvData {
+                                open: price,
+                                high: price,
+                                low: price,
+                                close: price,
+                                volume: 0,
+                            },
+                        ));
+                        log.record(
+                            stage,
+                            timestamp,
+                            AuditAction::Inserted,
+                            format!("inserted missing bar at {:.4}", price),
+                        );
+                    }
+                }
+            }
+
+            filled.push(bar);
+            previous_index = Some(i);
+        }
+
+        Ok(filled)
+    }
+}
+
+/// Keeps only bars inside a trading session
+///
+/// Times and weekdays are compared in exchange time, which is UTC unless
+/// set with [`SessionFilter::with_utc_offset`]. Bars timestamped in UTC
+/// need the exchange's offset, e.g. -300 for New York in winter, or the
+/// regular session filter drops most of the US session.
+#[derive(Debug, Clone)]
+pub struct SessionFilter {
+    session: Option<(NaiveTime, NaiveTime)>,
+    exclude_weekends: bool,
+    utc_offset: FixedOffset,
+}
+
+impl SessionFilter {
+    /// Keep weekday bars with a time in `[open, close)`
+    pub fn new(open: NaiveTime, close: NaiveTime) -> Result<Self, String> {
+        if open >= close {
+            return Err("Session open must be before session close".to_string());
+        }
+
+        Ok(Self {
+            session: Some((open, close)),
+            exclude_weekends: true,
+            utc_offset: FixedOffset::east_opt(0).unwrap(),
+        })
+    }
+
+    /// Regular US equity session, 9:30 to 16:00
+    pub fn regular_hours() -> Self {
+        Self {
+            session: Some((
+                NaiveTime::from_hms_opt(9, 30, 0).unwrap(),
+                NaiveTime::from_hms_opt(16, 0, 0).unwrap(),
+            )),
+            exclude_weekends: true,
+            utc_offset: FixedOffset::east_opt(0).unwrap(),
+        }
+    }
+
+    /// Only remove weekend bars, keeping any time of day
+    pub fn weekdays() -> Self {
+        Self {
+            session: None,
+            exclude_weekends: true,
+            utc_offset: FixedOffset::east_opt(0).unwrap(),
+        }
+    }
+
+    /// Set whether weekend bars are removed
+    pub fn with_weekends_excluded(mut self, exclude: bool) -> Self {
+        self.exclude_weekends = exclude;
+        self
+    }
+
+    /// Exchange time is `minutes` ahead of UTC, e.g. -300 for New York in
+    /// winter
+    pub fn with_utc_offset(mut self, minutes: i32) -> Result<Self, String> {
+        self.utc_offset = FixedOffset::east_opt(minutes * 60)
+            .ok_or_else(|| "UTC offset must be less than a day".to_string())?;
+        Ok(self)
+    }
+
+    /// Check whether a timestamp falls inside the session
+    pub fn contains(&self, timestamp: DateTime<Utc>) -> bool {
+        let timestamp = timestamp.with_timezone(&self.utc_offset);
+        if self.exclude_weekends && matches!(timestamp.weekday(), Weekday::Sat | Weekday::Sun) {
+            return false;
+        }
+
+        match self.session {
+            Some((open, close)) => {
+                let time = NaiveTime::from_hms_opt(timestamp.hour(), timestamp.minute(), 0)
+                    .unwrap_or(NaiveTime::MIN);
+                time >= open && time < close
+            }
+            None => true,
+        }
+    }
+}
+
+impl<B: Bar> CleaningStage<B> for SessionFilter {
+    fn name(&self) -> &str {
+        "session_filter"
+    }
+
+    fn apply(&self, bars: Vec<B>, log: &mut AuditLog) -> Result<Vec<B>, TradeError> {
+        let stage = CleaningStage::<B>::name(self);
+        let mut cleaned = Vec::with_capacity(bars.len());
+
+        for bar in bars {
+            if self.contains(bar.timestamp()) {
+                cleaned.push(bar);
+            } else {
+                log.record(
+                    stage,
+                    bar.timestamp(),
+                    AuditAction::Removed,
+                    "outside session",
+                );
+            }
+        }
+
+        Ok(cleaned)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, TimeZone};
+
+    fn daily(day: u32, close: f64) -> DailyOhlcv {
+        DailyOhlcv {
+            date: NaiveDate::from_ymd_opt(2023, 1, day).unwrap(),
+            data: OhlcvData {
+                open: close,
+                high: close + 1.0,
+                low: close - 1.0,
+                close,
+                volume: 1000,
+            },
+        }
+    }
+
+    fn minute(hour: u32, min: u32, close: f64) -> MinuteOhlcv {
+        MinuteOhlcv {
+            timestamp: Utc.with_ymd_and_hms(2023, 1, 3, hour, min, 0).unwrap(),
+            data: OhlcvData {
+                open: close,
+                high: close + 0.1,
+                low: close - 0.1,
+                close,
+                volume: 100,
+            },
+        }
+    }
+
+    #[test]
+    fn test_ohlc_consistency_repair_and_drop() {
+        let mut bad = daily(3, 100.0);
+        bad.data.high = 99.0;
+        let data = vec![daily(2, 100.0), bad.clone(), daily(4, 101.0)];
+
+        let result = CleaningPipeline::new()
+            .with_stage(OhlcConsistency::repair())
+            .run(data.clone())
+            .unwrap();
+        assert_eq!(result.data().len(), 3);
+        assert_eq!(result.data()[1].data.high, 100.0);
+        assert_eq!(result.log().count(AuditAction::Modified), 1);
+
+        let result = CleaningPipeline::new()
+            .with_stage(OhlcConsistency::drop())
+            .run(data)
+            .unwrap();
+        assert_eq!(result.data().len(), 2);
+        assert_eq!(result.log().count(AuditAction::Removed), 1);
+    }
+
+    #[test]
+    fn test_spike_filters() {
+        // Weekdays only so no gaps are involved
+        let closes = [
+            100.0, 100.5, 101.0, 100.8, 150.0, 101.2, 101.0, 100.7, 101.1, 100.9,
+        ];
+        let days = [2, 3, 4, 5, 6, 9, 10, 11, 12, 13];
+        let data: Vec<DailyOhlcv> = days.iter().zip(closes).map(|(&d, c)| daily(d, c)).collect();
+
+        for filter in [
+            SpikeFilter::mad(5.0).unwrap(),
+            SpikeFilter::hampel(3, 3.0).unwrap(),
+        ] {
+            let result = CleaningPipeline::new()
+                .with_stage(filter)
+                .run(data.clone())
+                .unwrap();
+            assert_eq!(result.log().count(AuditAction::Modified), 1);
+            assert!(result.data()[4].data.close < 102.0);
+        }
+
+        assert!(SpikeFilter::hampel(0, 3.0).is_err());
+        assert!(SpikeFilter::mad(0.0).is_err());
+    }
+
+    #[test]
+    fn test_mad_keeps_level_shift() {
+        // A permanent jump is not a spike
+        let closes = [100.0, 100.2, 100.1, 100.3, 130.0, 130.2, 130.1, 130.3];
+        let days = [2, 3, 4, 5, 6, 9, 10, 11];
+        let data: Vec<DailyOhlcv> = days.iter().zip(closes).map(|(&d, c)| daily(d, c)).collect();
+
+        let result = CleaningPipeline::new()
+            .with_stage(SpikeFilter::mad(5.0).unwrap())
+            .run(data)
+            .unwrap();
+        assert!(result.audit().is_empty());
+    }
+
+    #[test]
+    fn test_stale_price_detection() {
+        let mut data: Vec<MinuteOhlcv> = (0..6).map(|m| minute(15, m, 100.0)).collect();
+        for bar in &mut data {
+            bar.data.high = 100.0;
+            bar.data.low = 100.0;
+        }
+
+        let result = CleaningPipeline::new()
+            .with_stage(StalePriceDetector::drop(3).unwrap())
+            .run(data)
+            .unwrap();
+        assert_eq!(result.data().len(), 3);
+        assert_eq!(result.log().count(AuditAction::Removed), 3);
+    }
+
+    #[test]
+    fn test_gap_fill_policies() {
+        // Jan 5 and Jan 6 2023 are missing (Thu/Fri); Jan 7-8 is a weekend
+        let mut missing_value = daily(4, 102.0);
+        missing_value.data.close = f64::NAN;
+        let data = vec![
+            daily(2, 100.0),
+            daily(3, 101.0),
+            missing_value,
+            daily(9, 106.0),
+        ];
+
+        let result = CleaningPipeline::new()
+            .with_stage(GapFill::daily(GapPolicy::ForwardFill))
+            .run(data.clone())
+            .unwrap();
+        assert_eq!(result.data().len(), 6);
+        assert_eq!(result.data()[2].data.close, 101.0);
+        assert_eq!(result.data()[3].data.close, 101.0);
+        assert_eq!(result.data()[3].data.volume, 0);
+        assert_eq!(result.log().count(AuditAction::Inserted), 2);
+
+        let result = CleaningPipeline::new()
+            .with_stage(GapFill::daily(GapPolicy::Interpolate))
+            .run(data.clone())
+            .unwrap();
+        assert_eq!(result.data().len(), 6);
+        assert!((result.data()[2].data.close - 103.5).abs() < 1e-9);
+        assert!((result.data()[4].data.close - (103.5 + 2.0 * 2.5 / 3.0)).abs() < 1e-9);
+
+        // A run of missing values lies on the line between its neighbours
+        let mut run = vec![
+            daily(2, 100.0),
+            daily(3, 99.0),
+            daily(4, 99.0),
+            daily(5, 106.0),
+        ];
+        run[1].data.close = f64::NAN;
+        run[2].data.close = f64::NAN;
+        let result = CleaningPipeline::new()
+            .with_stage(GapFill::daily(GapPolicy::Interpolate))
+            .run(run)
+            .unwrap();
+        let closes: Vec<f64> = result.data().iter().map(|d| d.data.close).collect();
+        assert_eq!(closes, [100.0, 102.0, 104.0, 106.0]);
+
+        let result = CleaningPipeline::new()
+            .with_stage(GapFill::daily(GapPolicy::Drop))
+            .run(data)
+            .unwrap();
+        assert_eq!(result.data().len(), 3);
+        assert_eq!(result.log().count(AuditAction::Removed), 1);
+        assert_eq!(result.log().count(AuditAction::Flagged), 1);
+    }
+
+    #[test]
+    fn test_gap_fill_holidays_and_long_gaps() {
+        // Monday Jan 16 2023 is Martin Luther King Jr. Day
+        let data = vec![daily(13, 100.0), daily(17, 101.0)];
+        let holiday = NaiveDate::from_ymd_opt(2023, 1, 16).unwrap();
+
+        let result = CleaningPipeline::new()
+            .with_stage(GapFill::daily(GapPolicy::ForwardFill).with_holidays([holiday]))
+            .run(data.clone())
+            .unwrap();
+        assert_eq!(result.data().len(), 2);
+        assert!(result.audit().is_empty());
+
+        let result = CleaningPipeline::new()
+            .with_stage(GapFill::daily(GapPolicy::ForwardFill))
+            .run(data)
+            .unwrap();
+        assert_eq!(result.log().count(AuditAction::Inserted), 1);
+
+        // Thirteen missing weekdays are more than the five that are filled
+        let data = vec![daily(2, 100.0), daily(20, 101.0)];
+        for policy in [GapPolicy::ForwardFill, GapPolicy::Drop] {
+            let result = CleaningPipeline::new()
+                .with_stage(GapFill::daily(policy))
+                .run(data.clone())
+                .unwrap();
+            assert_eq!(result.data().len(), 2);
+            assert_eq!(result.log().count(AuditAction::Flagged), 1);
+            assert!(result.audit()[0].to_string().contains("13 missing bars"));
+        }
+    }
+
+    #[test]
+    fn test_minute_gaps_not_filled_across_sessions() {
+        let mut data = vec![minute(15, 58, 100.0), minute(15, 59, 100.0)];
+        data.push(MinuteOhlcv {
+            timestamp: Utc.with_ymd_and_hms(2023, 1, 4, 9, 30, 0).unwrap(),
+            data: minute(9, 30, 101.0).data,
+        });
+
+        let result = CleaningPipeline::new()
+            .with_stage(GapFill::minute(GapPolicy::ForwardFill))
+            .run(data)
+            .unwrap();
+        assert_eq!(result.data().len(), 3);
+    }
+
+    #[test]
+    fn test_session_filter() {
+        let data = vec![
+            minute(8, 0, 100.0),
+            minute(9, 30, 100.0),
+            minute(16, 0, 100.0),
+        ];
+
+        let result = CleaningPipeline::new()
+            .with_stage(SessionFilter::regular_hours())
+            .run(data)
+            .unwrap();
+        assert_eq!(result.data().len(), 1);
+        assert_eq!(result.log().count(AuditAction::Removed), 2);
+
+        let saturday = Utc.with_ymd_and_hms(2023, 1, 7, 10, 0, 0).unwrap();
+        assert!(!SessionFilter::weekdays().contains(saturday));
+
+        // 14:30 UTC is the New York open in winter; 01:00 UTC Saturday is
+        // still Friday evening there
+        let new_york = SessionFilter::regular_hours()
+            .with_utc_offset(-300)
+            .unwrap();
+        assert!(new_york.contains(Utc.with_ymd_and_hms(2023, 1, 3, 14, 30, 0).unwrap()));
+        assert!(!new_york.contains(Utc.with_ymd_and_hms(2023, 1, 3, 9, 30, 0).unwrap()));
+        assert!(!new_york.contains(Utc.with_ymd_and_hms(2023, 1, 3, 21, 0, 0).unwrap()));
+        assert!(SessionFilter::weekdays()
+            .with_utc_offset(-300)
+            .unwrap()
+            .contains(Utc.with_ymd_and_hms(2023, 1, 7, 1, 0, 0).unwrap()));
+        assert!(SessionFilter::weekdays().with_utc_offset(24 * 60).is_err());
+        assert!(SessionFilter::new(
+            NaiveTime::from_hms_opt(16, 0, 0).unwrap(),
+            NaiveTime::from_hms_opt(9, 30, 0).unwrap()
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,361 @@
+//! Earnings, dividend and economic release calendars
+//!
+//! Prices react to scheduled events on a known day: earnings released after
+//! the close move the next session's open, a dividend reprices the stock on
+//! its ex-date and a payrolls report moves the whole market. This module
+//! loads an [`EventCalendar`] from a CSV file and finds the first bar whose
+//! prices reflect each event, its *reaction bar*.
+//!
+//! - [`EventBlackout`] stops a strategy from entering around events
+//! - [`FlatBeforeEarnings`] closes a strategy's position before earnings
+//! - [`PostEventDrift`] buys strong event-day reactions and rides the drift
+//! - [`EventStudy`] measures average abnormal returns around events
+//!
+//! Event dates are published ahead of time, so using them in a backtest does
+//! not look ahead; only [`PostEventDrift`] reads prices, and only up to the
+//! reaction bar it signals on.
+//!
+//! # File format
+//!
+//! ```text
+//! date,symbol,event,timing,detail
+//! 2024-02-01,AAPL,earnings,amc,Q1 FY24
+//! 2024-02-09,AAPL,dividend,,0.24
+//! 2024-02-02,,economic,bmo,Nonfarm payrolls
+//! ```
+//!
+//! Timing is `bmo` (before market open), `amc` (after market close), `dmh`
+//! (during market hours) or empty when unknown. An event without a symbol
+//! applies to every symbol.
+//!
+//! # Example
+//!
+//! ```
+//! use day_trade::events::{EventBlackout, EventCalendar};
+//! use day_trade::utils::generate_test_data;
+//! use day_trade::{MacdStrategy, TradingStrategy};
+//!
+//! let calendar = EventCalendar::parse_csv(
+//!     "date,symbol,event,timing,detail\n2023-02-15,TEST,earnings,amc,Q4\n",
+//! )
+//! .unwrap();
+//!
+//! let strategy = EventBlackout::new(MacdStrategy::default(), "TEST", &calendar).with_window(3, 1);
+//! let data = generate_test_data(100, 100.0, 0.02);
+//! let signals = strategy.generate_signals(&data).unwrap();
+//! assert_eq!(signals.len(), data.len());
+//! ```
+
+mod overlays;
+mod study;
+
+pub use overlays::{EventBlackout, FlatBeforeEarnings, PostEventDrift};
+pub use study::{EventReaction, EventStudy, EventStudySummary, ReturnModel};
+
+use crate::TradeError;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Kind of scheduled event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventKind {
+    /// Quarterly earnings release
+    Earnings,
+    /// Dividend ex-date
+    Dividend,
+    /// Economic data release such as payrolls or CPI
+    Economic,
+}
+
+impl EventKind {
+    /// Kind from a name such as `earnings` or `ex_dividend`, in any case
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "earnings" => Some(EventKind::Earnings),
+            "dividend" | "ex_dividend" => Some(EventKind::Dividend),
+            "economic" | "macro" => Some(EventKind::Economic),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for EventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            EventKind::Earnings => "earnings",
+            EventKind::Dividend => "dividend",
+            EventKind::Economic => "economic",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// When in the trading day an event is released
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventTiming {
+    /// Before the open; the session of the event date reacts
+    BeforeOpen,
+    /// During the session
+    DuringMarket,
+    /// After the close; the next session reacts
+    AfterClose,
+    /// Not known; treated like an event before the open
+    #[default]
+    Unknown,
+}
+
+impl EventTiming {
+    /// Timing from `bmo`, `amc`, `dmh` or their long forms; empty is unknown
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "bmo" | "before_open" | "pre" => Some(EventTiming::BeforeOpen),
+            "dmh" | "during_market" | "intraday" => Some(EventTiming::DuringMarket),
+            "amc" | "after_close" | "post" => Some(EventTiming::AfterClose),
+            "" | "unknown" => Some(EventTiming::Unknown),
+            _ => None,
+        }
+    }
+}
+
+/// One scheduled event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarketEvent {
+    /// Date of the release or ex-date
+    pub date: NaiveDate,
+    /// Symbol the event belongs to; `None` for market-wide events
+    pub symbol: Option<String>,
+    /// Kind of event
+    pub kind: EventKind,
+    /// Time of the release
+    pub timing: EventTiming,
+    /// Free text such as the fiscal quarter or dividend amount
+    pub detail: String,
+}
+
+impl MarketEvent {
+    /// Create an event for `symbol`, or a market-wide one for `None`
+    pub fn new(
+        date: NaiveDate,
+        symbol: Option<&str>,
+        kind: EventKind,
+        timing: EventTiming,
+    ) -> Self {
+        Self {
+            date,
+            symbol: symbol.map(|s| s.trim().to_uppercase()),
+            kind,
+            timing,
+            detail: String::new(),
+        }
+    }
+
+    /// Attach free text to the event
+    pub fn with_detail(mut self, detail: &str) -> Self {
+        self.detail = detail.to_string();
+        self
+    }
+
+    /// Whether the event applies to `symbol`
+    pub fn applies_to(&self, symbol: &str) -> bool {
+        self.symbol
+            .as_deref()
+            .is_none_or(|s| s.eq_ignore_ascii_case(symbol.trim()))
+    }
+
+    /// Index of the first of the ascending `dates` whose prices reflect the
+    /// event; `None` when the event falls outside the dates
+    pub fn reaction_index(&self, dates: &[NaiveDate]) -> Option<usize> {
+        if dates.first().is_none_or(|first| self.date < *first) {
+            return None;
+        }
+        let index = match self.timing {
+            EventTiming::AfterClose => dates.partition_point(|d| *d <= self.date),
+            _ => dates.partition_point(|d| *d < self.date),
+        };
+        (index < dates.len()).then_some(index)
+    }
+}
+
+/// Scheduled events sorted by date
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EventCalendar {
+    events: Vec<MarketEvent>,
+}
+
+impl EventCalendar {
+    /// Create a calendar from a list of events
+    pub fn new(mut events: Vec<MarketEvent>) -> Self {
+        events.sort_by_key(|e| e.date);
+        Self { events }
+    }
+
+    /// Load a calendar from a CSV file
+    ///
+    /// See the module documentation for the expected format.
+    pub fn load_csv<P: AsRef<Path>>(path: P) -> Result<Self, TradeError> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| TradeError::DataLoadError(format!("Failed to open file: {}", e)))?;
+
+        Self::parse_csv(&contents)
+    }
+
+    /// Parse a calendar from CSV text with a header row; the detail column
+    /// may contain commas
+    pub fn parse_csv(contents: &str) -> Result<Self, TradeError> {
+        let mut events = Vec::new();
+
+        for (i, line) in contents.lines().enumerate().skip(1) {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.splitn(5, ',').map(|f| f.trim()).collect();
+            if fields.len() < 4 {
+                return Err(TradeError::DataLoadError(format!(
+                    "Invalid CSV format at line {}, expected at least 4 fields",
+                    i + 1
+                )));
+            }
+
+            let date = NaiveDate::parse_from_str(fields[0], "%Y-%m-%d").map_err(|e| {
+                TradeError::DataLoadError(format!("Invalid date at line {}: {}", i + 1, e))
+            })?;
+            let kind = EventKind::parse(fields[2]).ok_or_else(|| {
+                TradeError::DataLoadError(format!(
+                    "Unknown event '{}' at line {}",
+                    fields[2],
+                    i + 1
+                ))
+            })?;
+            let timing = EventTiming::parse(fields[3]).ok_or_else(|| {
+                TradeError::DataLoadError(format!(
+                    "Unknown timing '{}' at line {}",
+                    fields[3],
+                    i + 1
+                ))
+            })?;
+            let symbol = Some(fields[1]).filter(|s| !s.is_empty());
+
+            let event = MarketEvent::new(date, symbol, kind, timing)
+                .with_detail(fields.get(4).copied().unwrap_or_default());
+            events.push(event);
+        }
+
+        Ok(Self::new(events))
+    }
+
+    /// Events sorted by date
+    pub fn events(&self) -> &[MarketEvent] {
+        &self.events
+    }
+
+    /// Number of events
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Check if the calendar is empty
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Events of `symbol` together with market-wide events
+    pub fn for_symbol(&self, symbol: &str) -> EventCalendar {
+        self.filtered(|e| e.applies_to(symbol))
+    }
+
+    /// Events of one kind
+    pub fn of_kind(&self, kind: EventKind) -> EventCalendar {
+        self.filtered(|e| e.kind == kind)
+    }
+
+    /// Events dated from `start` to `end` inclusive
+    pub fn between(&self, start: NaiveDate, end: NaiveDate) -> EventCalendar {
+        self.filtered(|e| e.date >= start && e.date <= end)
+    }
+
+    fn filtered(&self, keep: impl Fn(&MarketEvent) -> bool) -> EventCalendar {
+        EventCalendar {
+            events: self.events.iter().filter(|e| keep(e)).cloned().collect(),
+        }
+    }
+
+    /// Reaction bar index of every event that falls within the ascending
+    /// `dates`, with the event
+    pub fn reactions(&self, dates: &[NaiveDate]) -> Vec<(usize, &MarketEvent)> {
+        self.events
+            .iter()
+            .filter_map(|e| e.reaction_index(dates).map(|i| (i, e)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    #[test]
+    fn test_parse_csv_and_filters() {
+        let csv = "date,symbol,event,timing,detail\n\
+                   2024-03-07,abc,earnings,AMC,Q4, beat\n\
+                   2024-03-05,,economic,bmo,CPI\n\
+                   2024-03-06,XYZ,dividend,,0.25\n";
+        let calendar = EventCalendar::parse_csv(csv).unwrap();
+
+        assert_eq!(calendar.len(), 3);
+        let first = &calendar.events()[0];
+        assert_eq!((first.date, first.symbol.as_deref()), (date(5), None));
+        let earnings = &calendar.events()[2];
+        assert_eq!(earnings.symbol.as_deref(), Some("ABC"));
+        assert_eq!(earnings.timing, EventTiming::AfterClose);
+        assert_eq!(earnings.detail, "Q4, beat");
+
+        assert_eq!(calendar.for_symbol("abc").len(), 2);
+        assert_eq!(calendar.of_kind(EventKind::Dividend).len(), 1);
+        assert_eq!(calendar.between(date(6), date(7)).len(), 2);
+
+        assert!(EventCalendar::parse_csv("h\n2024-03-07,ABC,merger,amc\n").is_err());
+        assert!(EventCalendar::parse_csv("h\n2024-03-07,ABC,earnings,noon\n").is_err());
+        assert!(EventCalendar::parse_csv("h\n03/07/2024,ABC,earnings,amc\n").is_err());
+        assert!(EventCalendar::parse_csv("h\n2024-03-07,ABC\n").is_err());
+    }
+
+    #[test]
+    fn test_reaction_index() {
+        // Trading days around a weekend: Thu 7, Fri 8, Mon 11
+        let dates = [date(7), date(8), date(11)];
+        let event =
+            |day, timing| MarketEvent::new(date(day), Some("ABC"), EventKind::Earnings, timing);
+
+        assert_eq!(
+            event(8, EventTiming::BeforeOpen).reaction_index(&dates),
+            Some(1)
+        );
+        assert_eq!(
+            event(8, EventTiming::AfterClose).reaction_index(&dates),
+            Some(2)
+        );
+        assert_eq!(
+            event(9, EventTiming::Unknown).reaction_index(&dates),
+            Some(2)
+        );
+        assert_eq!(
+            event(11, EventTiming::AfterClose).reaction_index(&dates),
+            None
+        );
+        assert_eq!(
+            event(6, EventTiming::AfterClose).reaction_index(&dates),
+            None
+        );
+    }
+}
@@ -0,0 +1,409 @@
+//! Strategies and strategy wrappers driven by an event calendar
+
+use super::{EventCalendar, EventKind};
+use crate::utils::calculate_basic_performance;
+use crate::{DailyOhlcv, Signal, TradeError, TradingStrategy};
+use chrono::NaiveDate;
+
+fn dates(data: &[DailyOhlcv]) -> Vec<NaiveDate> {
+    data.iter().map(|d| d.date).collect()
+}
+
+/// Reaction bar indices of the events of `calendar`, optionally of one kind
+fn reaction_bars(
+    calendar: &EventCalendar,
+    kind: Option<EventKind>,
+    data: &[DailyOhlcv],
+) -> Vec<usize> {
+    let mut bars: Vec<usize> = calendar
+        .reactions(&dates(data))
+        .into_iter()
+        .filter(|(_, e)| kind.is_none_or(|k| e.kind == k))
+        .map(|(i, _)| i)
+        .collect();
+    bars.dedup();
+    bars
+}
+
+/// No new entries around events
+///
+/// Signals execute at the next bar's open, so a Buy on bar `i` is replaced
+/// with Hold when bar `i + 1` lies from `before` bars before to `after` bars
+/// after an event's reaction bar. Sells and Holds pass through, so open
+/// positions are managed by the inner strategy as usual. Only the events
+/// [`EventCalendar::for_symbol`] selects for the traded symbol count.
+#[derive(Debug, Clone)]
+pub struct EventBlackout<S> {
+    strategy: S,
+    calendar: EventCalendar,
+    before: usize,
+    after: usize,
+}
+
+impl<S> EventBlackout<S> {
+    /// Wrap `strategy` trading `symbol` with a blackout from two bars before
+    /// to one bar after each of its events in `calendar`
+    pub fn new(strategy: S, symbol: &str, calendar: &EventCalendar) -> Self {
+        Self {
+            strategy,
+            calendar: calendar.for_symbol(symbol),
+            before: 2,
+            after: 1,
+        }
+    }
+
+    /// Block fills from `before` bars before to `after` bars after the
+    /// reaction bar
+    pub fn with_window(mut self, before: usize, after: usize) -> Self {
+        self.before = before;
+        self.after = after;
+        self
+    }
+
+    /// The wrapped strategy
+    pub fn inner(&self) -> &S {
+        &self.strategy
+    }
+
+    /// Whether a fill on bar `fill` falls in a blackout
+    fn blocked(&self, reactions: &[usize], fill: usize) -> bool {
+        reactions
+            .iter()
+            .any(|&r| fill + self.before >= r && fill <= r + self.after)
+    }
+}
+
+impl<S: TradingStrategy> TradingStrategy for EventBlackout<S> {
+    fn generate_signals(&self, data: &[DailyOhlcv]) -> Result<Vec<Signal>, TradeError> {
+        let reactions = reaction_bars(&self.calendar, None, data);
+        let mut signals = self.strategy.generate_signals(data)?;
+        for (i, signal) in signals.iter_mut().enumerate() {
+            if *signal == Signal::Buy && self.blocked(&reactions, i + 1) {
+                *signal = Signal::Hold;
+            }
+        }
+        Ok(signals)
+    }
+
+    fn calculate_performance(
+        &self,
+        data: &[DailyOhlcv],
+        signals: &[Signal],
+    ) -> Result<f64, TradeError> {
+        self.strategy.calculate_performance(data, signals)
+    }
+}
+
+/// Close positions ahead of earnings and stay out until they are released
+///
+/// With signals executing at the next bar's open, the position is sold at
+/// the open of the last session before the earnings reaction bar, or
+/// `lead` sessions earlier. Buys that would fill between the exit and the
+/// reaction bar are replaced with Hold; the inner strategy may re-enter from
+/// the reaction bar on. Events of other kinds or other symbols in the
+/// calendar are ignored.
+#[derive(Debug, Clone)]
+pub struct FlatBeforeEarnings<S> {
+    strategy: S,
+    calendar: EventCalendar,
+    lead: usize,
+}
+
+impl<S> FlatBeforeEarnings<S> {
+    /// Wrap `strategy` trading `symbol`, exiting at the open of the session
+    /// before its earnings in `calendar`
+    pub fn new(strategy: S, symbol: &str, calendar: &EventCalendar) -> Self {
+        Self {
+            strategy,
+            calendar: calendar.for_symbol(symbol),
+            lead: 0,
+        }
+    }
+
+    /// Exit `lead` sessions earlier
+    pub fn with_lead(mut self, lead: usize) -> Self {
+        self.lead = lead;
+        self
+    }
+
+    /// The wrapped strategy
+    pub fn inner(&self) -> &S {
+        &self.strategy
+    }
+}
+
+impl<S: TradingStrategy> TradingStrategy for FlatBeforeEarnings<S> {
+    fn generate_signals(&self, data: &[DailyOhlcv]) -> Result<Vec<Signal>, TradeError> {
+        let reactions = reaction_bars(&self.calendar, Some(EventKind::Earnings), data);
+        let mut signals = self.strategy.generate_signals(data)?;
+
+        // Signal bars from the exit up to the last one that would fill before
+        // the reaction bar
+        let flat: Vec<(usize, usize)> = reactions
+            .iter()
+            .filter(|&&r| r >= 2)
+            .map(|&r| ((r - 2).saturating_sub(self.lead), r - 2))
+            .collect();
+
+        let mut holding = false;
+        for (i, signal) in signals.iter_mut().enumerate() {
+            if let Some(&(exit, _)) = flat.iter().find(|(exit, last)| i >= *exit && i <= *last) {
+                *signal = if holding && i == exit {
+                    Signal::Sell
+                } else if *signal == Signal::Buy {
+                    Signal::Hold
+                } else {
+                    *signal
+                };
+            }
+            match signal {
+                Signal::Buy => holding = true,
+                Signal::Sell => holding = false,
+                Signal::Hold => {}
+            }
+        }
+        Ok(signals)
+    }
+
+    fn calculate_performance(
+        &self,
+        data: &[DailyOhlcv],
+        signals: &[Signal],
+    ) -> Result<f64, TradeError> {
+        self.strategy.calculate_performance(data, signals)
+    }
+}
+
+/// Buy after a strong reaction to an event and hold for the drift
+///
+/// The reaction is the close of the reaction bar against the close before
+/// it, so it includes any opening gap. A reaction of at least
+/// `min_reaction` signals Buy on the reaction bar and the position is sold
+/// `holding_bars` bars later, or on the reaction bar of a later event that
+/// moves the price down by at least `min_reaction`. A further strong event
+/// while holding restarts the holding period. Only the events of the traded
+/// symbol and market-wide events are traded.
+#[derive(Debug, Clone)]
+pub struct PostEventDrift {
+    calendar: EventCalendar,
+    kind: EventKind,
+    min_reaction: f64,
+    holding_bars: usize,
+}
+
+impl PostEventDrift {
+    /// Trade reactions of `symbol` to its earnings in `calendar` of at least
+    /// 3%, holding for 20 bars
+    pub fn new(symbol: &str, calendar: &EventCalendar) -> Self {
+        Self {
+            calendar: calendar.for_symbol(symbol),
+            kind: EventKind::Earnings,
+            min_reaction: 0.03,
+            holding_bars: 20,
+        }
+    }
+
+    /// Trade reactions to events of `kind` instead of earnings
+    pub fn with_kind(mut self, kind: EventKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Smallest reaction, as a fraction, that opens a position
+    pub fn with_min_reaction(mut self, fraction: f64) -> Result<Self, String> {
+        if !fraction.is_finite() || fraction < 0.0 {
+            return Err("Minimum reaction must be non-negative".to_string());
+        }
+        self.min_reaction = fraction;
+        Ok(self)
+    }
+
+    /// Bars to hold after the reaction bar
+    pub fn with_holding_bars(mut self, bars: usize) -> Result<Self, String> {
+        if bars == 0 {
+            return Err("Holding period must be at least one bar".to_string());
+        }
+        self.holding_bars = bars;
+        Ok(self)
+    }
+}
+
+impl TradingStrategy for PostEventDrift {
+    fn generate_signals(&self, data: &[DailyOhlcv]) -> Result<Vec<Signal>, TradeError> {
+        let reactions = reaction_bars(&self.calendar, Some(self.kind), data);
+        let mut signals = vec![Signal::Hold; data.len()];
+        let mut exit_at: Option<usize> = None;
+        let mut next = reactions.iter().peekable();
+
+        for (i, signal) in signals.iter_mut().enumerate() {
+            let reaction = match next.next_if(|&&r| r == i) {
+                Some(_) if i > 0 => Some(data[i].data.close / data[i - 1].data.close - 1.0),
+                _ => None,
+            };
+            match reaction {
+                Some(r) if r >= self.min_reaction => {
+                    *signal = Signal::Buy;
+                    exit_at = Some(i + self.holding_bars);
+                }
+                Some(r) if exit_at.is_some() && r <= -self.min_reaction => {
+                    *signal = Signal::Sell;
+                    exit_at = None;
+                }
+                _ if exit_at == Some(i) => {
+                    *signal = Signal::Sell;
+                    exit_at = None;
+                }
+                _ => {}
+            }
+        }
+        Ok(signals)
+    }
+
+    fn calculate_performance(
+        &self,
+        data: &[DailyOhlcv],
+        signals: &[Signal],
+    ) -> Result<f64, TradeError> {
+        calculate_basic_performance(data, signals, 10000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{EventTiming, MarketEvent};
+    use crate::OhlcvData;
+    use Signal::*;
+
+    /// Replays fixed signals
+    struct Fixed(Vec<Signal>);
+
+    impl TradingStrategy for Fixed {
+        fn generate_signals(&self, _data: &[DailyOhlcv]) -> Result<Vec<Signal>, TradeError> {
+            Ok(self.0.clone())
+        }
+
+        fn calculate_performance(
+            &self,
+            data: &[DailyOhlcv],
+            signals: &[Signal],
+        ) -> Result<f64, TradeError> {
+            calculate_basic_performance(data, signals, 10000.0)
+        }
+    }
+
+    fn start() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()
+    }
+
+    fn bars(closes: &[f64]) -> Vec<DailyOhlcv> {
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, &close)| DailyOhlcv {
+                date: start() + chrono::Duration::days(i as i64),
+                data: OhlcvData {
+                    open: close,
+                    high: close,
+                    low: close,
+                    close,
+                    volume: 1000,
+                },
+            })
+            .collect()
+    }
+
+    /// Calendar with one event per `(bar, kind)` released before the open
+    fn calendar(events: &[(i64, EventKind)]) -> EventCalendar {
+        EventCalendar::new(
+            events
+                .iter()
+                .map(|&(bar, kind)| {
+                    MarketEvent::new(
+                        start() + chrono::Duration::days(bar),
+                        Some("ABC"),
+                        kind,
+                        EventTiming::BeforeOpen,
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_blackout_blocks_entries_near_events() {
+        let data = bars(&[100.0; 8]);
+        // The event of another symbol in the calendar blocks nothing
+        let mut events = calendar(&[(4, EventKind::Dividend)]).events().to_vec();
+        events.push(MarketEvent::new(
+            start() + chrono::Duration::days(1),
+            Some("XYZ"),
+            EventKind::Earnings,
+            EventTiming::BeforeOpen,
+        ));
+        let strategy = EventBlackout::new(
+            Fixed(vec![Buy, Buy, Buy, Buy, Sell, Buy, Buy, Buy]),
+            "ABC",
+            &EventCalendar::new(events),
+        )
+        .with_window(1, 1);
+
+        // Fills on bars 3 to 5 are blocked
+        let signals = strategy.generate_signals(&data).unwrap();
+        assert_eq!(signals, vec![Buy, Buy, Hold, Hold, Sell, Buy, Buy, Buy]);
+    }
+
+    #[test]
+    fn test_flat_before_earnings() {
+        let data = bars(&[100.0; 8]);
+        let inner = || Fixed(vec![Buy, Hold, Hold, Buy, Buy, Hold, Hold, Hold]);
+        let events = calendar(&[(5, EventKind::Earnings), (7, EventKind::Dividend)]);
+
+        // Sold at bar 4's open, the session before the reaction bar 5, and
+        // bought back at the reaction bar's open
+        let strategy = FlatBeforeEarnings::new(inner(), "ABC", &events);
+        let signals = strategy.generate_signals(&data).unwrap();
+        assert_eq!(signals, vec![Buy, Hold, Hold, Sell, Buy, Hold, Hold, Hold]);
+
+        let early = FlatBeforeEarnings::new(inner(), "ABC", &events).with_lead(2);
+        let other = FlatBeforeEarnings::new(inner(), "XYZ", &events);
+        assert_eq!(other.generate_signals(&data).unwrap(), inner().0);
+        let signals = early.generate_signals(&data).unwrap();
+        assert_eq!(signals, vec![Buy, Sell, Hold, Hold, Buy, Hold, Hold, Hold]);
+    }
+
+    #[test]
+    fn test_post_event_drift() {
+        let data = bars(&[
+            100.0, 100.0, 106.0, 107.0, 108.0, 109.0, 103.0, 104.0, 104.0,
+        ]);
+        let events = calendar(&[(2, EventKind::Earnings), (6, EventKind::Earnings)]);
+
+        let strategy = PostEventDrift::new("ABC", &events)
+            .with_holding_bars(5)
+            .unwrap();
+        let signals = strategy.generate_signals(&data).unwrap();
+        assert_eq!(
+            signals,
+            vec![Hold, Hold, Buy, Hold, Hold, Hold, Sell, Hold, Hold]
+        );
+
+        let short_hold = PostEventDrift::new("ABC", &events)
+            .with_holding_bars(2)
+            .unwrap();
+        let signals = short_hold.generate_signals(&data).unwrap();
+        assert_eq!(signals[4], Sell);
+        assert_eq!(signals[6], Hold);
+
+        let dividends = PostEventDrift::new("ABC", &calendar(&[(2, EventKind::Earnings)]))
+            .with_kind(EventKind::Dividend);
+        assert!(dividends
+            .generate_signals(&data)
+            .unwrap()
+            .iter()
+            .all(|s| *s == Hold));
+        assert!(PostEventDrift::new("ABC", &EventCalendar::default())
+            .with_min_reaction(-0.1)
+            .is_err());
+    }
+}
@@ -0,0 +1,381 @@
+//! Event studies of abnormal returns around scheduled events
+
+use super::{EventCalendar, MarketEvent};
+use crate::{DailyOhlcv, TradeError};
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Expected return that abnormal returns are measured against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReturnModel {
+    /// The symbol's mean return over the estimation window
+    #[default]
+    MeanAdjusted,
+    /// The benchmark's return on the same day
+    MarketAdjusted,
+    /// Alpha plus beta times the benchmark's return, fitted by least squares
+    /// over the estimation window
+    MarketModel,
+}
+
+impl ReturnModel {
+    /// Whether the model needs benchmark returns
+    pub fn needs_benchmark(&self) -> bool {
+        !matches!(self, ReturnModel::MeanAdjusted)
+    }
+}
+
+/// Abnormal returns of a symbol around one event
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EventReaction {
+    /// The event
+    pub event: MarketEvent,
+    /// Date of the first bar whose prices reflect the event
+    pub reaction_date: NaiveDate,
+    /// Abnormal return on each offset of [`EventStudy::offsets`]
+    pub abnormal_returns: Vec<f64>,
+    /// Sum of the abnormal returns over the event window
+    pub cumulative: f64,
+}
+
+/// Average abnormal returns over many events
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EventStudySummary {
+    /// Bar offsets from the reaction bar
+    pub offsets: Vec<i64>,
+    /// Average abnormal return on each offset
+    pub average_abnormal: Vec<f64>,
+    /// Running sum of the average abnormal returns
+    pub cumulative_average: Vec<f64>,
+    /// Number of events
+    pub events: usize,
+    /// Events with a positive cumulative abnormal return
+    pub positive: usize,
+    /// Cross-sectional t-statistic of the cumulative abnormal returns;
+    /// `None` with fewer than two events or no dispersion
+    pub t_statistic: Option<f64>,
+}
+
+/// Abnormal returns from `pre` bars before to `post` bars after the
+/// reaction bar of each event
+///
+/// Returns are close to close. The expected return of every bar in the
+/// event window comes from the [`ReturnModel`] fitted on the `estimation`
+/// bars that end just before the window, so the event itself never
+/// influences its own benchmark.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventStudy {
+    model: ReturnModel,
+    estimation: usize,
+    pre: usize,
+    post: usize,
+}
+
+impl Default for EventStudy {
+    /// Mean-adjusted returns from 5 bars before to 10 bars after each event,
+    /// estimated over 60 bars
+    fn default() -> Self {
+        Self::new(ReturnModel::default())
+    }
+}
+
+impl EventStudy {
+    /// Study with `model`, a 60-bar estimation window and an event window
+    /// from 5 bars before to 10 bars after the reaction bar
+    pub fn new(model: ReturnModel) -> Self {
+        Self {
+            model,
+            estimation: 60,
+            pre: 5,
+            post: 10,
+        }
+    }
+
+    /// Event window from `pre` bars before to `post` bars after the
+    /// reaction bar
+    pub fn with_window(mut self, pre: usize, post: usize) -> Self {
+        self.pre = pre;
+        self.post = post;
+        self
+    }
+
+    /// Fit the expected returns on `bars` bars before the event window
+    pub fn with_estimation(mut self, bars: usize) -> Result<Self, String> {
+        if bars < 3 {
+            return Err("Estimation window must be at least 3 bars".to_string());
+        }
+        self.estimation = bars;
+        Ok(self)
+    }
+
+    /// The return model
+    pub fn model(&self) -> ReturnModel {
+        self.model
+    }
+
+    /// Bar offsets of the event window relative to the reaction bar
+    pub fn offsets(&self) -> Vec<i64> {
+        (-(self.pre as i64)..=self.post as i64).collect()
+    }
+
+    /// Abnormal returns of `symbol` around each of its events in `calendar`
+    ///
+    /// Only the events [`EventCalendar::for_symbol`] selects count, so one
+    /// calendar can serve every symbol of a study. `benchmark` is required by the market-adjusted and market models and
+    /// is matched to `data` by date; bars missing from either series are
+    /// dropped. Events without a full estimation and event window are left
+    /// out.
+    pub fn analyze(
+        &self,
+        symbol: &str,
+        data: &[DailyOhlcv],
+        benchmark: Option<&[DailyOhlcv]>,
+        calendar: &EventCalendar,
+    ) -> Result<Vec<EventReaction>, TradeError> {
+        if self.model.needs_benchmark() && benchmark.is_none() {
+            return Err(TradeError::InvalidData(format!(
+                "The {:?} model needs benchmark prices",
+                self.model
+            )));
+        }
+
+        // (date, close, benchmark close) of the bars in both series; without a
+        // benchmark a constant stands in so its returns are zero
+        let aligned: Vec<(NaiveDate, f64, f64)> = match benchmark {
+            Some(benchmark) => {
+                let closes: HashMap<NaiveDate, f64> =
+                    benchmark.iter().map(|b| (b.date, b.data.close)).collect();
+                data.iter()
+                    .filter_map(|d| closes.get(&d.date).map(|&m| (d.date, d.data.close, m)))
+                    .collect()
+            }
+            None => data.iter().map(|d| (d.date, d.data.close, 1.0)).collect(),
+        };
+        let dates: Vec<NaiveDate> = aligned.iter().map(|a| a.0).collect();
+
+        // Returns are indexed by the bar they end on; index 0 has none
+        let returns = |pick: fn(&(NaiveDate, f64, f64)) -> f64| -> Vec<f64> {
+            let mut returns = vec![0.0];
+            returns.extend(aligned.windows(2).map(|w| pick(&w[1]) / pick(&w[0]) - 1.0));
+            returns
+        };
+        let stock = returns(|a| a.1);
+        let market = returns(|a| a.2);
+
+        let mut reactions = Vec::new();
+        for (reaction, event) in calendar.for_symbol(symbol).reactions(&dates) {
+            let (Some(start), true) = (
+                reaction.checked_sub(self.pre + self.estimation + 1),
+                reaction + self.post < aligned.len(),
+            ) else {
+                continue;
+            };
+            let estimation = start + 1..reaction - self.pre;
+            let window = reaction - self.pre..=reaction + self.post;
+
+            let expected = self.expected_returns(&stock[estimation.clone()], &market[estimation]);
+            let abnormal_returns: Vec<f64> =
+                window.map(|t| stock[t] - expected(market[t])).collect();
+            reactions.push(EventReaction {
+                event: event.clone(),
+                reaction_date: dates[reaction],
+                cumulative: abnormal_returns.iter().sum(),
+                abnormal_returns,
+            });
+        }
+        Ok(reactions)
+    }
+
+    /// Expected return as a function of the benchmark return, fitted on the
+    /// estimation window
+    fn expected_returns(&self, stock: &[f64], market: &[f64]) -> impl Fn(f64) -> f64 {
+        let n = stock.len() as f64;
+        let stock_mean = stock.iter().sum::<f64>() / n;
+        let market_mean = market.iter().sum::<f64>() / n;
+        let (alpha, beta) = match self.model {
+            ReturnModel::MeanAdjusted => (stock_mean, 0.0),
+            ReturnModel::MarketAdjusted => (0.0, 1.0),
+            ReturnModel::MarketModel => {
+                let covariance: f64 = stock
+                    .iter()
+                    .zip(market)
+                    .map(|(s, m)| (s - stock_mean) * (m - market_mean))
+                    .sum();
+                let variance: f64 = market.iter().map(|m| (m - market_mean).powi(2)).sum();
+                let beta = if variance > 0.0 {
+                    covariance / variance
+                } else {
+                    0.0
+                };
+                (stock_mean - beta * market_mean, beta)
+            }
+        };
+        move |market_return| alpha + beta * market_return
+    }
+
+    /// Average the reactions of one or more symbols
+    pub fn summarize(&self, reactions: &[EventReaction]) -> Result<EventStudySummary, TradeError> {
+        let offsets = self.offsets();
+        if reactions.is_empty() {
+            return Err(TradeError::InsufficientData(
+                "No events with a full estimation and event window".to_string(),
+            ));
+        }
+        if reactions
+            .iter()
+            .any(|r| r.abnormal_returns.len() != offsets.len())
+        {
+            return Err(TradeError::InvalidData(
+                "Reactions were measured over a different event window".to_string(),
+            ));
+        }
+
+        let n = reactions.len() as f64;
+        let average_abnormal: Vec<f64> = (0..offsets.len())
+            .map(|k| reactions.iter().map(|r| r.abnormal_returns[k]).sum::<f64>() / n)
+            .collect();
+        let cumulative_average = average_abnormal
+            .iter()
+            .scan(0.0, |sum, ar| {
+                *sum += ar;
+                Some(*sum)
+            })
+            .collect();
+
+        let mean_car = reactions.iter().map(|r| r.cumulative).sum::<f64>() / n;
+        let t_statistic = (reactions.len() > 1)
+            .then(|| {
+                let variance = reactions
+                    .iter()
+                    .map(|r| (r.cumulative - mean_car).powi(2))
+                    .sum::<f64>()
+                    / (n - 1.0);
+                mean_car / (variance / n).sqrt()
+            })
+            .filter(|t| t.is_finite());
+
+        Ok(EventStudySummary {
+            offsets,
+            average_abnormal,
+            cumulative_average,
+            events: reactions.len(),
+            positive: reactions.iter().filter(|r| r.cumulative > 0.0).count(),
+            t_statistic,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{EventKind, EventTiming};
+    use crate::OhlcvData;
+
+    fn start() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()
+    }
+
+    fn bars(closes: &[f64]) -> Vec<DailyOhlcv> {
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, &close)| DailyOhlcv {
+                date: start() + chrono::Duration::days(i as i64),
+                data: OhlcvData {
+                    open: close,
+                    high: close,
+                    low: close,
+                    close,
+                    volume: 1000,
+                },
+            })
+            .collect()
+    }
+
+    /// Closes compounding `returns` from 100
+    fn series(returns: &[f64]) -> Vec<DailyOhlcv> {
+        let mut closes = vec![100.0];
+        for r in returns {
+            closes.push(closes.last().unwrap() * (1.0 + r));
+        }
+        bars(&closes)
+    }
+
+    fn earnings(bar: i64) -> MarketEvent {
+        MarketEvent::new(
+            start() + chrono::Duration::days(bar),
+            Some("ABC"),
+            EventKind::Earnings,
+            EventTiming::BeforeOpen,
+        )
+    }
+
+    #[test]
+    fn test_mean_adjusted_study() {
+        // 1% a day with a 10% jump on bar 20 and a 5% jump on bar 30
+        let mut returns = vec![0.01; 40];
+        returns[19] = 0.10;
+        returns[29] = 0.05;
+        let data = series(&returns);
+        // The event of another symbol is left out
+        let mut other = earnings(25);
+        other.symbol = Some("XYZ".to_string());
+        let calendar = EventCalendar::new(vec![earnings(20), other, earnings(30), earnings(40)]);
+
+        let study = EventStudy::default()
+            .with_window(1, 1)
+            .with_estimation(5)
+            .unwrap();
+        let reactions = study.analyze("ABC", &data, None, &calendar).unwrap();
+
+        // The event on bar 40 has no bar after it
+        assert_eq!(reactions.len(), 2);
+        assert_eq!(
+            reactions[0].reaction_date,
+            start() + chrono::Duration::days(20)
+        );
+        let abnormal = &reactions[0].abnormal_returns;
+        assert!(abnormal[0].abs() < 1e-9 && abnormal[2].abs() < 1e-9);
+        assert!((abnormal[1] - 0.09).abs() < 1e-9);
+
+        let summary = study.summarize(&reactions).unwrap();
+        assert_eq!(summary.offsets, vec![-1, 0, 1]);
+        assert!((summary.average_abnormal[1] - 0.065).abs() < 1e-9);
+        assert!((summary.cumulative_average[2] - 0.065).abs() < 1e-9);
+        assert_eq!(summary.positive, 2);
+        assert!(summary.t_statistic.unwrap() > 0.0);
+        assert!(study.summarize(&[]).is_err());
+    }
+
+    #[test]
+    fn test_market_model_removes_beta() {
+        // The stock moves twice the market plus 1% on the event day
+        let market_returns: Vec<f64> = (0..30).map(|i| 0.01 * ((i % 5) as f64 - 2.0)).collect();
+        let mut stock_returns: Vec<f64> = market_returns.iter().map(|r| 2.0 * r).collect();
+        stock_returns[24] += 0.01;
+        let (stock, market) = (series(&stock_returns), series(&market_returns));
+        let calendar = EventCalendar::new(vec![earnings(25)]);
+
+        let study = EventStudy::new(ReturnModel::MarketModel)
+            .with_window(2, 2)
+            .with_estimation(15)
+            .unwrap();
+        assert!(study.analyze("ABC", &stock, None, &calendar).is_err());
+
+        let reactions = study
+            .analyze("ABC", &stock, Some(&market), &calendar)
+            .unwrap();
+        let abnormal = &reactions[0].abnormal_returns;
+        assert!((abnormal[2] - 0.01).abs() < 1e-9);
+        assert!(abnormal[0].abs() < 1e-9 && abnormal[4].abs() < 1e-9);
+
+        let adjusted = EventStudy::new(ReturnModel::MarketAdjusted)
+            .with_window(2, 2)
+            .analyze("ABC", &stock, Some(&market), &calendar)
+            .unwrap();
+        // 60 estimation bars do not fit before bar 25
+        assert!(adjusted.is_empty());
+    }
+}
@@ -0,0 +1,408 @@
+//! Backtest ledger that tracks cash, shares and corporate action events
+//!
+//! `calculate_basic_performance` works on a single price series and has no
+//! notion of splits or dividends. The ledger replays the same all-in/all-out
+//! signal logic on raw prices while applying corporate actions as they occur:
+//! splits change the share count, and cash dividends are credited to cash on
+//! the ex-date for any shares held going into it.
+//!
+//! # Example
+//!
+//! ```
+//! use day_trade::corporate_actions::CorporateActions;
+//! use day_trade::ledger::Ledger;
+//! use day_trade::utils::generate_test_data;
+//! use day_trade::{MeanReversionStrategy, TradingStrategy};
+//!
+//! let data = generate_test_data(100, 100.0, 0.05);
+//! let signals = MeanReversionStrategy::default().generate_signals(&data).unwrap();
+//!
+//! let ledger = Ledger::run("ABC", &data, &signals, 10000.0, &CorporateActions::default()).unwrap();
+//! println!("Total return: {:.2}%", ledger.total_return());
+//! ```
+
+use crate::corporate_actions::{CorporateActionKind, CorporateActions};
+use crate::{DailyOhlcv, Signal, TradeError};
+use chrono::NaiveDate;
+
+/// Type of event recorded in the ledger
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedgerEvent {
+    /// Shares bought with available cash
+    Buy,
+    /// Shares sold for cash
+    Sell,
+    /// Share count changed by a stock split
+    Split,
+    /// Cash dividend credited for shares held
+    Dividend,
+}
+
+/// A single ledger entry
+#[derive(Debug, Clone, PartialEq)]
+pub struct LedgerEntry {
+    /// Date of the event
+    pub date: NaiveDate,
+    /// Event type
+    pub event: LedgerEvent,
+    /// Change in share count (negative for sells)
+    pub shares: f64,
+    /// Execution price, split ratio or dividend per share depending on the event
+    pub price: f64,
+    /// Change in cash (negative for buys)
+    pub cash: f64,
+}
+
+/// Commissions, fees and slippage charged on every fill
+///
+/// Slippage moves the fill price against the trade (up for buys, down for
+/// sells); the commission is a fraction of the traded value and the fixed
+/// fee is charged per fill. The default charges nothing.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TradingCosts {
+    commission_rate: f64,
+    slippage_rate: f64,
+    fixed_fee: f64,
+}
+
+impl TradingCosts {
+    /// Costs with a proportional commission, proportional slippage and a
+    /// fixed fee per fill
+    pub fn new(commission_rate: f64, slippage_rate: f64, fixed_fee: f64) -> Result<Self, String> {
+        for (name, rate) in [("Commission", commission_rate), ("Slippage", slippage_rate)] {
+            if !rate.is_finite() || !(0.0..1.0).contains(&rate) {
+                return Err(format!("{} rate must be in [0, 1)", name));
+            }
+        }
+        if !fixed_fee.is_finite() || fixed_fee < 0.0 {
+            return Err("Fixed fee must be non-negative".to_string());
+        }
+        Ok(Self {
+            commission_rate,
+            slippage_rate,
+            fixed_fee,
+        })
+    }
+
+    /// Commission as a fraction of traded value
+    pub fn commission_rate(&self) -> f64 {
+        self.commission_rate
+    }
+
+    /// Slippage as a fraction of the quoted price
+    pub fn slippage_rate(&self) -> f64 {
+        self.slippage_rate
+    }
+
+    /// Fixed fee per fill
+    pub fn fixed_fee(&self) -> f64 {
+        self.fixed_fee
+    }
+}
+
+/// Result of replaying signals through the ledger
+#[derive(Debug, Clone)]
+pub struct Ledger {
+    initial_cash: f64,
+    cash: f64,
+    shares: f64,
+    dividend_income: f64,
+    costs_paid: f64,
+    entries: Vec<LedgerEntry>,
+    equity_curve: Vec<f64>,
+}
+
+impl Ledger {
+    /// Replay `signals` over raw `data` of `symbol`, applying its `actions` as
+    /// their ex-dates pass
+    ///
+    /// Signals are executed at the open of the following bar, matching
+    /// `calculate_basic_performance`. `actions` may cover many symbols; only
+    /// those `CorporateActions::for_symbol` selects for `symbol` are applied.
+    pub fn run(
+        symbol: &str,
+        data: &[DailyOhlcv],
+        signals: &[Signal],
+        initial_cash: f64,
+        actions: &CorporateActions,
+    ) -> Result<Self, TradeError> {
+        Self::run_with_costs(
+            symbol,
+            data,
+            signals,
+            initial_cash,
+            actions,
+            &TradingCosts::default(),
+        )
+    }
+
+    /// Like `run`, charging `costs` on every buy and sell
+    ///
+    /// Entry prices record the fill price after slippage. A buy is skipped
+    /// when the cash does not cover the fixed fee.
+    pub fn run_with_costs(
+        symbol: &str,
+        data: &[DailyOhlcv],
+        signals: &[Signal],
+        initial_cash: f64,
+        actions: &CorporateActions,
+        costs: &TradingCosts,
+    ) -> Result<Self, TradeError> {
+        if data.len() != signals.len() {
+            return Err(TradeError::InvalidData(
+                "Data and signals arrays must be the same length".to_string(),
+            ));
+        }
+
+        if data.len() <= 1 {
+            return Err(TradeError::InsufficientData(
+                "Need at least 2 data points to calculate performance".to_string(),
+            ));
+        }
+
+        if initial_cash <= 0.0 {
+            return Err(TradeError::InvalidData(
+                "Initial cash must be positive".to_string(),
+            ));
+        }
+
+        let actions = actions.for_symbol(symbol);
+        let mut ledger = Self {
+            initial_cash,
+            cash: initial_cash,
+            shares: 0.0,
+            dividend_income: 0.0,
+            costs_paid: 0.0,
+            entries: Vec::new(),
+            equity_curve: Vec::with_capacity(data.len()),
+        };
+        ledger.equity_curve.push(initial_cash);
+
+        for i in 1..data.len() {
+            // Corporate actions take effect before the ex-date open
+            for action in actions
+                .actions()
+                .iter()
+                .filter(|a| a.ex_date > data[i - 1].date && a.ex_date <= data[i].date)
+            {
+                ledger.apply_action(data[i].date, &action.kind);
+            }
+
+            let open = data[i].data.open;
+            match signals[i - 1] {
+                Signal::Buy if ledger.cash > costs.fixed_fee => {
+                    let price = open * (1.0 + costs.slippage_rate);
+                    let invested = (ledger.cash - costs.fixed_fee) / (1.0 + costs.commission_rate);
+                    let bought = invested / price;
+                    ledger.costs_paid += ledger.cash - invested + bought * (price - open);
+                    ledger.entries.push(LedgerEntry {
+                        date: data[i].date,
+                        event: LedgerEvent::Buy,
+                        shares: bought,
+                        price,
+                        cash: -ledger.cash,
+                    });
+                    ledger.shares += bought;
+                    ledger.cash = 0.0;
+                }
+                Signal::Sell if ledger.shares > 0.0 => {
+                    let price = open * (1.0 - costs.slippage_rate);
+                    let gross = ledger.shares * price;
+                    let proceeds = gross * (1.0 - costs.commission_rate) - costs.fixed_fee;
+                    ledger.costs_paid += ledger.shares * open - proceeds;
+                    ledger.entries.push(LedgerEntry {
+                        date: data[i].date,
+                        event: LedgerEvent::Sell,
+                        shares: -ledger.shares,
+                        price,
+                        cash: proceeds,
+                    });
+                    ledger.cash += proceeds;
+                    ledger.shares = 0.0;
+                }
+                _ => {} // Hold or nothing to trade
+            }
+
+            ledger
+                .equity_curve
+                .push(ledger.cash + ledger.shares * data[i].data.close);
+        }
+
+        Ok(ledger)
+    }
+
+    /// Apply a corporate action to the current holdings
+    fn apply_action(&mut self, date: NaiveDate, kind: &CorporateActionKind) {
+        if self.shares <= 0.0 {
+            return;
+        }
+
+        match kind {
+            CorporateActionKind::Split { ratio } => {
+                let added = self.shares * (ratio - 1.0);
+                self.shares *= ratio;
+                self.entries.push(LedgerEntry {
+                    date,
+                    event: LedgerEvent::Split,
+                    shares: added,
+                    price: *ratio,
+                    cash: 0.0,
+                });
+            }
+            CorporateActionKind::CashDividend { amount } => {
+                let payment = self.shares * amount;
+                self.cash += payment;
+                self.dividend_income += payment;
+                self.entries.push(LedgerEntry {
+                    date,
+                    event: LedgerEvent::Dividend,
+                    shares: 0.0,
+                    price: *amount,
+                    cash: payment,
+                });
+            }
+            CorporateActionKind::SymbolChange { .. } => {}
+        }
+    }
+
+    /// Initial cash balance
+    pub fn initial_cash(&self) -> f64 {
+        self.initial_cash
+    }
+
+    /// Cash balance at the end of the run
+    pub fn cash(&self) -> f64 {
+        self.cash
+    }
+
+    /// Shares held at the end of the run
+    pub fn shares(&self) -> f64 {
+        self.shares
+    }
+
+    /// Total dividend cash credited during the run
+    pub fn dividend_income(&self) -> f64 {
+        self.dividend_income
+    }
+
+    /// Total commissions, fees and slippage paid during the run
+    pub fn costs_paid(&self) -> f64 {
+        self.costs_paid
+    }
+
+    /// All ledger entries in chronological order
+    pub fn entries(&self) -> &[LedgerEntry] {
+        &self.entries
+    }
+
+    /// Portfolio value at the close of each bar
+    pub fn equity_curve(&self) -> &[f64] {
+        &self.equity_curve
+    }
+
+    /// Final portfolio value
+    pub fn final_value(&self) -> f64 {
+        *self.equity_curve.last().unwrap_or(&self.initial_cash)
+    }
+
+    /// Total return as a percentage
+    pub fn total_return(&self) -> f64 {
+        (self.final_value() / self.initial_cash - 1.0) * 100.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::corporate_actions::CorporateAction;
+    use crate::utils::calculate_basic_performance;
+    use crate::OhlcvData;
+
+    fn bar(day: u32, price: f64) -> DailyOhlcv {
+        DailyOhlcv {
+            date: NaiveDate::from_ymd_opt(2023, 3, day).unwrap(),
+            data: OhlcvData {
+                open: price,
+                high: price,
+                low: price,
+                close: price,
+                volume: 1000,
+            },
+        }
+    }
+
+    #[test]
+    fn test_matches_basic_performance_without_actions() {
+        let data = vec![bar(1, 100.0), bar(2, 100.0), bar(3, 110.0), bar(4, 120.0)];
+        let signals = vec![Signal::Buy, Signal::Hold, Signal::Sell, Signal::Hold];
+
+        let ledger = Ledger::run(
+            "ABC",
+            &data,
+            &signals,
+            10000.0,
+            &CorporateActions::default(),
+        )
+        .unwrap();
+        let basic = calculate_basic_performance(&data, &signals, 10000.0).unwrap();
+
+        assert!((ledger.total_return() - basic).abs() < 1e-9);
+        assert_eq!(ledger.entries().len(), 2);
+    }
+
+    #[test]
+    fn test_trading_costs_reduce_returns() {
+        let data = vec![bar(1, 100.0), bar(2, 100.0), bar(3, 110.0), bar(4, 120.0)];
+        let signals = vec![Signal::Buy, Signal::Hold, Signal::Sell, Signal::Hold];
+        let costs = TradingCosts::new(0.001, 0.0005, 1.0).unwrap();
+
+        let ledger = Ledger::run_with_costs(
+            "ABC",
+            &data,
+            &signals,
+            10000.0,
+            &CorporateActions::default(),
+            &costs,
+        )
+        .unwrap();
+
+        let buy_price = 100.0 * 1.0005;
+        let shares = (10000.0 - 1.0) / 1.001 / buy_price;
+        let sell_price = 120.0 * 0.9995;
+        let expected = shares * sell_price * 0.999 - 1.0;
+        assert!((ledger.final_value() - expected).abs() < 1e-9);
+        assert!((ledger.entries()[0].price - buy_price).abs() < 1e-12);
+        assert!(ledger.costs_paid() > 0.0 && ledger.final_value() < 12000.0);
+
+        assert!(TradingCosts::new(-0.1, 0.0, 0.0).is_err());
+        assert!(TradingCosts::new(0.0, 0.0, -1.0).is_err());
+    }
+
+    #[test]
+    fn test_split_and_dividend_are_credited() {
+        // 2-for-1 split on day 3 takes the raw price from 100 to 50
+        let data = vec![bar(1, 100.0), bar(2, 100.0), bar(3, 50.0), bar(4, 50.0)];
+        let signals = vec![Signal::Buy, Signal::Hold, Signal::Hold, Signal::Hold];
+        let ex_date = |day| NaiveDate::from_ymd_opt(2023, 3, day).unwrap();
+        // Actions of other symbols in the same calendar are ignored
+        let actions = CorporateActions::new(vec![
+            CorporateAction::split("ABC", ex_date(3), 2.0).unwrap(),
+            CorporateAction::split("XYZ", ex_date(2), 4.0).unwrap(),
+            CorporateAction::cash_dividend("ABC", ex_date(4), 1.0).unwrap(),
+            CorporateAction::cash_dividend("XYZ", ex_date(4), 5.0).unwrap(),
+        ]);
+
+        let ledger = Ledger::run("ABC", &data, &signals, 10000.0, &actions).unwrap();
+
+        assert!((ledger.shares() - 200.0).abs() < 1e-9);
+        assert!((ledger.dividend_income() - 200.0).abs() < 1e-9);
+        // 200 shares * 50 + 200 dividend cash
+        assert!((ledger.final_value() - 10200.0).abs() < 1e-9);
+
+        let events: Vec<LedgerEvent> = ledger.entries().iter().map(|e| e.event).collect();
+        assert_eq!(
+            events,
+            vec![LedgerEvent::Buy, LedgerEvent::Split, LedgerEvent::Dividend]
+        );
+    }
+}
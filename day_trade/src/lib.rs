@@ -36,6 +36,26 @@ use thiserror::Error;
 
 // Strategy modules
 mod strategies;
+// Corporate actions and adjusted price history
+pub mod corporate_actions;
+// Backtest ledger with corporate action handling
+pub mod ledger;
+// Data quality checks and cleaning pipeline
+pub mod data_quality;
+// Seeded synthetic market simulator
+pub mod simulation;
+// Monte Carlo robustness analysis of backtests
+pub mod monte_carlo;
+// Stop-loss, take-profit and holding-period overlays
+pub mod risk;
+// Market regime detection
+pub mod regime;
+// Earnings, dividend and economic event calendars
+pub mod events;
+// Market breadth indicators and breadth-gated strategies
+pub mod breadth;
+// Pre-market gap and overnight mover scanning
+pub mod premarket;
 // Mock indicators that interface with rustalib and oxidiviner
 pub mod mock_indicators;
 // Utility functions
@@ -44,7 +64,7 @@ pub mod utils;
 pub use strategies::{
     AdaptiveMovingAverageStrategy, BollingerBandsStrategy, BreakoutStrategy, CompositeStrategy,
     DualTimeframeStrategy, ForecastingStrategy, GridTradingStrategy, MACrossover, MacdStrategy,
-    MeanReversionStrategy, RsiStrategy, VolumeBasedStrategy, VwapStrategy,
+    MeanReversionStrategy, RegimeSwitchingStrategy, RsiStrategy, VolumeBasedStrategy, VwapStrategy,
 };
 
 /// Errors that can occur in day trading operations
@@ -58,6 +78,9 @@ pub enum TradeError {
 
     #[error("Strategy calculation error: {0}")]
     CalculationError(String),
+
+    #[error("Data loading error: {0}")]
+    DataLoadError(String),
 }
 
 /// Represents OHLCV (Open, High, Low, Close, Volume) data for a specific time period
@@ -130,6 +153,34 @@ pub trait IntradayTradingStrategy {
     ) -> Result<f64, TradeError>;
 }
 
+impl<T: TradingStrategy + ?Sized> TradingStrategy for Box<T> {
+    fn generate_signals(&self, data: &[DailyOhlcv]) -> Result<Vec<Signal>, TradeError> {
+        (**self).generate_signals(data)
+    }
+
+    fn calculate_performance(
+        &self,
+        data: &[DailyOhlcv],
+        signals: &[Signal],
+    ) -> Result<f64, TradeError> {
+        (**self).calculate_performance(data, signals)
+    }
+}
+
+impl<T: IntradayTradingStrategy + ?Sized> IntradayTradingStrategy for Box<T> {
+    fn generate_signals(&self, data: &[MinuteOhlcv]) -> Result<Vec<Signal>, TradeError> {
+        (**self).generate_signals(data)
+    }
+
+    fn calculate_performance(
+        &self,
+        data: &[MinuteOhlcv],
+        signals: &[Signal],
+    ) -> Result<f64, TradeError> {
+        (**self).calculate_performance(data, signals)
+    }
+}
+
 /// Trait defining a realtime trading strategy that generates signals based on streaming data
 pub trait RealtimeTradingStrategy {
     /// Update the strategy with new OHLCV data
@@ -160,7 +211,7 @@ pub trait RealtimeTradingStrategy {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::NaiveDate;
+    use chrono::{Datelike, NaiveDate, Weekday};
 
     fn create_test_data() -> Vec<DailyOhlcv> {
         vec![
@@ -199,11 +250,15 @@ mod tests {
     fn test_generate_test_data() {
         let data = utils::generate_test_data(50, 100.0, 0.05);
         assert_eq!(data.len(), 50);
-        assert!(data[0].data.open == 100.0);
+        // The first bar gaps from the starting price
+        assert!((data[0].data.open / 100.0 - 1.0).abs() < 0.05);
 
-        // Check that dates are sequential
+        // Check that dates are sequential trading days
         for i in 1..data.len() {
             assert!(data[i].date > data[i - 1].date);
         }
+        assert!(data
+            .iter()
+            .all(|d| !matches!(d.date.weekday(), Weekday::Sat | Weekday::Sun)));
     }
 }
@@ -0,0 +1,762 @@
+//! Monte Carlo robustness analysis of backtest results
+//!
+//! A single backtest is one draw from the many histories a strategy could
+//! have lived through. These tools perturb a finished backtest in different
+//! ways and report the spread of outcomes:
+//!
+//! - **Trade reshuffling**: the same round-trip trades in random order. Final
+//!   equity is unchanged; drawdowns show how much the path depended on the
+//!   sequence of wins and losses.
+//! - **Bootstrap**: per-bar returns resampled with replacement, either
+//!   independently or in stationary blocks (Politis-Romano) to keep
+//!   short-range dependence.
+//! - **Entry delay**: every signal executes a random number of bars late.
+//! - **Parameter jitter**: strategy parameters are perturbed and the signals
+//!   regenerated.
+//!
+//! All methods are driven by a seed, so an analysis can be replayed exactly.
+//!
+//! # Example
+//!
+//! ```
+//! use day_trade::monte_carlo::{bar_returns, MonteCarloAnalysis, MonteCarloConfig};
+//! use day_trade::utils::generate_test_data;
+//! use day_trade::{MeanReversionStrategy, TradingStrategy};
+//!
+//! let data = generate_test_data(250, 100.0, 0.03);
+//! let signals = MeanReversionStrategy::default().generate_signals(&data).unwrap();
+//! let returns = bar_returns(&data, &signals, 10000.0).unwrap();
+//!
+//! let analysis = MonteCarloAnalysis::new(MonteCarloConfig::new(500, 7).unwrap());
+//! let result = analysis.stationary_bootstrap(&returns, 10.0).unwrap();
+//!
+//! println!(
+//!     "Final equity 5%-95%: {:.0} - {:.0}, risk of ruin {:.1}%",
+//!     result.final_equity.percentile(5.0),
+//!     result.final_equity.percentile(95.0),
+//!     result.risk_of_ruin * 100.0
+//! );
+//! ```
+
+use crate::corporate_actions::CorporateActions;
+use crate::ledger::Ledger;
+use crate::utils::{seeded_rng, DEFAULT_SEED};
+use crate::{DailyOhlcv, Signal, TradeError};
+use rand::rngs::StdRng;
+use rand::Rng;
+
+/// Percentiles reported in equity bands
+pub const BAND_PERCENTILES: [f64; 5] = [5.0, 25.0, 50.0, 75.0, 95.0];
+
+/// Settings shared by all Monte Carlo methods
+#[derive(Debug, Clone)]
+pub struct MonteCarloConfig {
+    /// Number of simulated paths
+    pub iterations: usize,
+    /// Seed for the random number generator
+    pub seed: u64,
+    /// Starting equity of every path
+    pub initial_cash: f64,
+    /// A path is ruined once equity falls to this fraction of `initial_cash`
+    pub ruin_level: f64,
+    /// Periods per year used to annualize the Sharpe ratio of bar returns
+    pub periods_per_year: f64,
+}
+
+impl MonteCarloConfig {
+    /// Create a new configuration
+    pub fn new(iterations: usize, seed: u64) -> Result<Self, String> {
+        if iterations == 0 {
+            return Err("Iterations must be at least 1".to_string());
+        }
+
+        Ok(Self {
+            iterations,
+            seed,
+            initial_cash: 10000.0,
+            ruin_level: 0.5,
+            periods_per_year: 252.0,
+        })
+    }
+
+    /// Set the starting equity
+    pub fn with_initial_cash(mut self, initial_cash: f64) -> Result<Self, String> {
+        crate::utils::validate_positive(initial_cash, "Initial cash")?;
+        self.initial_cash = initial_cash;
+        Ok(self)
+    }
+
+    /// Set the equity fraction at which a path counts as ruined
+    pub fn with_ruin_level(mut self, ruin_level: f64) -> Result<Self, String> {
+        crate::utils::validate_range(ruin_level, 0.0, 1.0, "Ruin level")?;
+        self.ruin_level = ruin_level;
+        Ok(self)
+    }
+
+    /// Set the number of periods per year for Sharpe annualization
+    pub fn with_periods_per_year(mut self, periods_per_year: f64) -> Result<Self, String> {
+        crate::utils::validate_positive(periods_per_year, "Periods per year")?;
+        self.periods_per_year = periods_per_year;
+        Ok(self)
+    }
+}
+
+impl Default for MonteCarloConfig {
+    /// 1000 iterations with `DEFAULT_SEED`
+    fn default() -> Self {
+        Self::new(1000, DEFAULT_SEED).unwrap()
+    }
+}
+
+/// Outcome of a single simulated path
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathMetrics {
+    /// Equity at the end of the path
+    pub final_equity: f64,
+    /// Largest peak-to-trough decline as a percentage
+    pub max_drawdown: f64,
+    /// Sharpe ratio of the path's returns
+    pub sharpe: f64,
+    /// Whether equity reached the ruin level
+    pub ruined: bool,
+}
+
+/// Sorted sample of a metric across paths
+#[derive(Debug, Clone)]
+pub struct Distribution {
+    values: Vec<f64>,
+}
+
+impl Distribution {
+    /// Build a distribution from unsorted values; NaNs are dropped
+    pub fn new(mut values: Vec<f64>) -> Self {
+        values.retain(|v| !v.is_nan());
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        Self { values }
+    }
+
+    /// Sorted values
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// Number of values
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Whether the distribution is empty
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Arithmetic mean
+    pub fn mean(&self) -> f64 {
+        if self.values.is_empty() {
+            return f64::NAN;
+        }
+        self.values.iter().sum::<f64>() / self.values.len() as f64
+    }
+
+    /// Sample standard deviation
+    pub fn std_dev(&self) -> f64 {
+        if self.values.len() < 2 {
+            return 0.0;
+        }
+        let mean = self.mean();
+        let var = self.values.iter().map(|v| (v - mean).powi(2)).sum::<f64>()
+            / (self.values.len() - 1) as f64;
+        var.sqrt()
+    }
+
+    /// Percentile (0-100) with linear interpolation between ranks
+    pub fn percentile(&self, p: f64) -> f64 {
+        percentile_of_sorted(&self.values, p)
+    }
+
+    /// Smallest value
+    pub fn min(&self) -> f64 {
+        self.values.first().copied().unwrap_or(f64::NAN)
+    }
+
+    /// Largest value
+    pub fn max(&self) -> f64 {
+        self.values.last().copied().unwrap_or(f64::NAN)
+    }
+}
+
+/// Percentile bands of equity at each step across all paths
+#[derive(Debug, Clone)]
+pub struct EquityBands {
+    /// Percentiles of each band, see `BAND_PERCENTILES`
+    pub percentiles: Vec<f64>,
+    /// `bands[k][t]` is percentile `percentiles[k]` of equity at step `t`
+    pub bands: Vec<Vec<f64>>,
+}
+
+impl EquityBands {
+    /// Band for a given percentile, if it was computed
+    pub fn band(&self, percentile: f64) -> Option<&[f64]> {
+        self.percentiles
+            .iter()
+            .position(|p| (p - percentile).abs() < 1e-9)
+            .map(|k| self.bands[k].as_slice())
+    }
+}
+
+/// Aggregated result of a Monte Carlo run
+#[derive(Debug, Clone)]
+pub struct MonteCarloResult {
+    /// Metrics of every simulated path
+    pub paths: Vec<PathMetrics>,
+    /// Distribution of final equity
+    pub final_equity: Distribution,
+    /// Distribution of maximum drawdown percentages
+    pub max_drawdown: Distribution,
+    /// Distribution of Sharpe ratios
+    pub sharpe: Distribution,
+    /// Equity percentile bands over time
+    pub equity_bands: EquityBands,
+    /// Fraction of paths that reached the ruin level
+    pub risk_of_ruin: f64,
+}
+
+impl MonteCarloResult {
+    fn from_curves(curves: Vec<Vec<f64>>, config: &MonteCarloConfig, annualization: f64) -> Self {
+        let ruin_equity = config.initial_cash * config.ruin_level;
+        let paths: Vec<PathMetrics> = curves
+            .iter()
+            .map(|curve| PathMetrics {
+                final_equity: *curve.last().unwrap_or(&config.initial_cash),
+                max_drawdown: max_drawdown(curve),
+                sharpe: sharpe_ratio(&curve_returns(curve), annualization),
+                ruined: curve.iter().any(|v| *v <= ruin_equity),
+            })
+            .collect();
+
+        let steps = curves.iter().map(|c| c.len()).min().unwrap_or(0);
+        let bands = BAND_PERCENTILES
+            .iter()
+            .map(|p| {
+                (0..steps)
+                    .map(|t| {
+                        let mut column: Vec<f64> = curves.iter().map(|c| c[t]).collect();
+                        column.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                        percentile_of_sorted(&column, *p)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let ruined = paths.iter().filter(|p| p.ruined).count();
+        Self {
+            final_equity: Distribution::new(paths.iter().map(|p| p.final_equity).collect()),
+            max_drawdown: Distribution::new(paths.iter().map(|p| p.max_drawdown).collect()),
+            sharpe: Distribution::new(paths.iter().map(|p| p.sharpe).collect()),
+            equity_bands: EquityBands {
+                percentiles: BAND_PERCENTILES.to_vec(),
+                bands,
+            },
+            risk_of_ruin: ruined as f64 / paths.len().max(1) as f64,
+            paths,
+        }
+    }
+}
+
+/// Monte Carlo analysis runner
+#[derive(Debug, Clone)]
+pub struct MonteCarloAnalysis {
+    config: MonteCarloConfig,
+}
+
+impl MonteCarloAnalysis {
+    /// Create a runner with the given configuration
+    pub fn new(config: MonteCarloConfig) -> Self {
+        Self { config }
+    }
+
+    /// Configuration in use
+    pub fn config(&self) -> &MonteCarloConfig {
+        &self.config
+    }
+
+    fn rng(&self) -> StdRng {
+        seeded_rng(self.config.seed)
+    }
+
+    /// Replay round-trip trade returns in random order
+    ///
+    /// `trade_returns` are fractional returns per trade (see `trade_returns`).
+    /// Sharpe ratios are per trade and not annualized.
+    pub fn trade_reshuffle(&self, trade_returns: &[f64]) -> Result<MonteCarloResult, TradeError> {
+        require_samples(trade_returns, 2, "trades")?;
+        let mut rng = self.rng();
+        let mut order = trade_returns.to_vec();
+
+        let curves = (0..self.config.iterations)
+            .map(|_| {
+                shuffle(&mut order, &mut rng);
+                self.compound(&order)
+            })
+            .collect();
+
+        Ok(MonteCarloResult::from_curves(curves, &self.config, 1.0))
+    }
+
+    /// Resample per-bar returns independently with replacement
+    pub fn bootstrap(&self, returns: &[f64]) -> Result<MonteCarloResult, TradeError> {
+        require_samples(returns, 2, "returns")?;
+        let mut rng = self.rng();
+
+        let curves = (0..self.config.iterations)
+            .map(|_| {
+                let sample: Vec<f64> = (0..returns.len())
+                    .map(|_| returns[rng.random_range(0..returns.len())])
+                    .collect();
+                self.compound(&sample)
+            })
+            .collect();
+
+        Ok(MonteCarloResult::from_curves(
+            curves,
+            &self.config,
+            self.config.periods_per_year,
+        ))
+    }
+
+    /// Stationary block bootstrap of per-bar returns
+    ///
+    /// Blocks start at random positions and have geometrically distributed
+    /// lengths with mean `mean_block_length`, wrapping around the end of the
+    /// series. A mean block length of 1 is the plain bootstrap.
+    pub fn stationary_bootstrap(
+        &self,
+        returns: &[f64],
+        mean_block_length: f64,
+    ) -> Result<MonteCarloResult, TradeError> {
+        require_samples(returns, 2, "returns")?;
+        if mean_block_length < 1.0 {
+            return Err(TradeError::InvalidData(
+                "Mean block length must be at least 1".to_string(),
+            ));
+        }
+
+        let n = returns.len();
+        let restart = 1.0 / mean_block_length;
+        let mut rng = self.rng();
+
+        let curves = (0..self.config.iterations)
+            .map(|_| {
+                let mut sample = Vec::with_capacity(n);
+                let mut pos = rng.random_range(0..n);
+                while sample.len() < n {
+                    sample.push(returns[pos]);
+                    pos = if rng.random::<f64>() < restart {
+                        rng.random_range(0..n)
+                    } else {
+                        (pos + 1) % n
+                    };
+                }
+                self.compound(&sample)
+            })
+            .collect();
+
+        Ok(MonteCarloResult::from_curves(
+            curves,
+            &self.config,
+            self.config.periods_per_year,
+        ))
+    }
+
+    /// Execute every non-hold signal between 0 and `max_delay` bars late
+    ///
+    /// Delays never reorder or drop signals: a signal lands strictly after
+    /// the one before it, and signals near the end of the data are delayed
+    /// less so that all of them still fit.
+    pub fn entry_delay(
+        &self,
+        data: &[DailyOhlcv],
+        signals: &[Signal],
+        max_delay: usize,
+    ) -> Result<MonteCarloResult, TradeError> {
+        // Validates lengths and cash once up front
+        Ledger::run(
+            "",
+            data,
+            signals,
+            self.config.initial_cash,
+            &CorporateActions::default(),
+        )?;
+        let mut rng = self.rng();
+
+        let mut curves = Vec::with_capacity(self.config.iterations);
+        for _ in 0..self.config.iterations {
+            let delayed = delay_signals(signals, max_delay, &mut rng);
+            curves.push(self.equity_curve(data, &delayed)?);
+        }
+
+        Ok(MonteCarloResult::from_curves(
+            curves,
+            &self.config,
+            self.config.periods_per_year,
+        ))
+    }
+
+    /// Regenerate signals with each parameter scaled by a random factor
+    ///
+    /// Each parameter in `base_params` is multiplied by a factor drawn
+    /// uniformly from `[1 - jitter, 1 + jitter]` and passed to `generate`.
+    /// Parameter sets that `generate` rejects are skipped; an error is
+    /// returned only if every set is rejected.
+    pub fn parameter_jitter<F>(
+        &self,
+        data: &[DailyOhlcv],
+        base_params: &[f64],
+        jitter: f64,
+        generate: F,
+    ) -> Result<MonteCarloResult, TradeError>
+    where
+        F: Fn(&[f64]) -> Result<Vec<Signal>, TradeError>,
+    {
+        if !(0.0..1.0).contains(&jitter) {
+            return Err(TradeError::InvalidData(
+                "Jitter must be in [0, 1)".to_string(),
+            ));
+        }
+        let mut rng = self.rng();
+
+        let mut curves = Vec::with_capacity(self.config.iterations);
+        for _ in 0..self.config.iterations {
+            let params: Vec<f64> = base_params
+                .iter()
+                .map(|p| p * (1.0 + jitter * (2.0 * rng.random::<f64>() - 1.0)))
+                .collect();
+            if let Ok(signals) = generate(&params) {
+                curves.push(self.equity_curve(data, &signals)?);
+            }
+        }
+
+        if curves.is_empty() {
+            return Err(TradeError::CalculationError(
+                "No jittered parameter set produced signals".to_string(),
+            ));
+        }
+
+        Ok(MonteCarloResult::from_curves(
+            curves,
+            &self.config,
+            self.config.periods_per_year,
+        ))
+    }
+
+    /// Equity curve from compounding fractional returns
+    fn compound(&self, returns: &[f64]) -> Vec<f64> {
+        let mut equity = self.config.initial_cash;
+        let mut curve = Vec::with_capacity(returns.len() + 1);
+        curve.push(equity);
+        for r in returns {
+            equity = (equity * (1.0 + r)).max(0.0);
+            curve.push(equity);
+        }
+        curve
+    }
+
+    fn equity_curve(
+        &self,
+        data: &[DailyOhlcv],
+        signals: &[Signal],
+    ) -> Result<Vec<f64>, TradeError> {
+        let ledger = Ledger::run(
+            "",
+            data,
+            signals,
+            self.config.initial_cash,
+            &CorporateActions::default(),
+        )?;
+        Ok(ledger.equity_curve().to_vec())
+    }
+}
+
+/// Per-bar fractional returns of the all-in/all-out backtest of `signals`
+pub fn bar_returns(
+    data: &[DailyOhlcv],
+    signals: &[Signal],
+    initial_cash: f64,
+) -> Result<Vec<f64>, TradeError> {
+    let ledger = Ledger::run(
+        "",
+        data,
+        signals,
+        initial_cash,
+        &CorporateActions::default(),
+    )?;
+    Ok(curve_returns(ledger.equity_curve()))
+}
+
+/// Fractional return of each round-trip trade in the backtest of `signals`
+///
+/// Trades enter and exit at the next bar's open, as in
+/// `calculate_basic_performance`. A position still open at the end is closed
+/// at the last close.
+pub fn trade_returns(data: &[DailyOhlcv], signals: &[Signal]) -> Result<Vec<f64>, TradeError> {
+    if data.len() != signals.len() {
+        return Err(TradeError::InvalidData(
+            "Data and signals arrays must be the same length".to_string(),
+        ));
+    }
+
+    let mut trades = Vec::new();
+    let mut entry: Option<f64> = None;
+    for i in 1..data.len() {
+        match (signals[i - 1], entry) {
+            (Signal::Buy, None) => entry = Some(data[i].data.open),
+            (Signal::Sell, Some(price)) => {
+                trades.push(data[i].data.open / price - 1.0);
+                entry = None;
+            }
+            _ => {}
+        }
+    }
+    if let (Some(price), Some(last)) = (entry, data.last()) {
+        trades.push(last.data.close / price - 1.0);
+    }
+
+    Ok(trades)
+}
+
+/// Largest peak-to-trough decline of an equity curve as a percentage
+pub fn max_drawdown(curve: &[f64]) -> f64 {
+    let mut peak = f64::MIN;
+    let mut worst: f64 = 0.0;
+    for value in curve {
+        peak = peak.max(*value);
+        if peak > 0.0 {
+            worst = worst.max((peak - value) / peak);
+        }
+    }
+    worst * 100.0
+}
+
+/// Sharpe ratio (zero risk-free rate) scaled by `sqrt(periods_per_year)`
+pub fn sharpe_ratio(returns: &[f64], periods_per_year: f64) -> f64 {
+    if returns.len() < 2 {
+        return 0.0;
+    }
+    let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+    let var = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() - 1) as f64;
+    if var <= 0.0 {
+        return 0.0;
+    }
+    mean / var.sqrt() * periods_per_year.sqrt()
+}
+
+fn curve_returns(curve: &[f64]) -> Vec<f64> {
+    curve
+        .windows(2)
+        .map(|w| if w[0] > 0.0 { w[1] / w[0] - 1.0 } else { 0.0 })
+        .collect()
+}
+
+fn percentile_of_sorted(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return f64::NAN;
+    }
+    let rank = (p.clamp(0.0, 100.0) / 100.0) * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+/// Move each non-hold signal between 0 and `max_delay` bars later, keeping
+/// their count and order
+fn delay_signals(signals: &[Signal], max_delay: usize, rng: &mut StdRng) -> Vec<Signal> {
+    let mut delayed = vec![Signal::Hold; signals.len()];
+    let mut remaining = signals.iter().filter(|s| **s != Signal::Hold).count();
+    let mut next_free = 0;
+    for (i, signal) in signals.iter().enumerate() {
+        if *signal == Signal::Hold {
+            continue;
+        }
+        // Leave a bar for each signal still to come
+        let latest = signals.len() - remaining;
+        let at = (i + rng.random_range(0..=max_delay))
+            .min(latest)
+            .max(next_free);
+        delayed[at] = *signal;
+        next_free = at + 1;
+        remaining -= 1;
+    }
+    delayed
+}
+
+fn shuffle<T>(values: &mut [T], rng: &mut StdRng) {
+    for i in (1..values.len()).rev() {
+        values.swap(i, rng.random_range(0..=i));
+    }
+}
+
+fn require_samples(values: &[f64], min: usize, name: &str) -> Result<(), TradeError> {
+    if values.len() < min {
+        return Err(TradeError::InsufficientData(format!(
+            "Need at least {} {} for Monte Carlo analysis",
+            min, name
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::generate_test_data;
+    use crate::{MeanReversionStrategy, TradingStrategy};
+
+    fn analysis(iterations: usize) -> MonteCarloAnalysis {
+        MonteCarloAnalysis::new(MonteCarloConfig::new(iterations, 3).unwrap())
+    }
+
+    #[test]
+    fn test_reshuffle_keeps_final_equity() {
+        let trades = vec![0.1, -0.05, 0.02, -0.08, 0.04];
+        let result = analysis(200).trade_reshuffle(&trades).unwrap();
+
+        let expected = trades.iter().fold(10000.0, |eq, r| eq * (1.0 + r));
+        assert!((result.final_equity.min() - expected).abs() < 1e-6);
+        assert!((result.final_equity.max() - expected).abs() < 1e-6);
+        // Drawdown depends on the order
+        assert!(result.max_drawdown.max() > result.max_drawdown.min());
+    }
+
+    #[test]
+    fn test_same_seed_is_reproducible() {
+        let returns: Vec<f64> = (0..100)
+            .map(|i| ((i * 37) % 11) as f64 / 500.0 - 0.01)
+            .collect();
+        let a = analysis(100).stationary_bootstrap(&returns, 5.0).unwrap();
+        let b = analysis(100).stationary_bootstrap(&returns, 5.0).unwrap();
+        assert_eq!(a.final_equity.values(), b.final_equity.values());
+    }
+
+    #[test]
+    fn test_percentiles_and_bands_are_ordered() {
+        let returns: Vec<f64> = (0..60)
+            .map(|i| if i % 3 == 0 { -0.02 } else { 0.012 })
+            .collect();
+        let result = analysis(300).bootstrap(&returns).unwrap();
+
+        let p5 = result.final_equity.percentile(5.0);
+        let p50 = result.final_equity.percentile(50.0);
+        let p95 = result.final_equity.percentile(95.0);
+        assert!(p5 <= p50 && p50 <= p95);
+
+        let low = result.equity_bands.band(5.0).unwrap();
+        let high = result.equity_bands.band(95.0).unwrap();
+        assert_eq!(low.len(), returns.len() + 1);
+        assert!(low.iter().zip(high).all(|(l, h)| l <= h));
+    }
+
+    #[test]
+    fn test_risk_of_ruin() {
+        let winners = vec![0.01, 0.02, 0.015];
+        assert_eq!(analysis(50).bootstrap(&winners).unwrap().risk_of_ruin, 0.0);
+
+        let losers = vec![-0.5, -0.6, -0.7];
+        assert_eq!(analysis(50).bootstrap(&losers).unwrap().risk_of_ruin, 1.0);
+    }
+
+    #[test]
+    fn test_entry_delay_zero_matches_backtest() {
+        let data = generate_test_data(120, 100.0, 0.04);
+        let signals = MeanReversionStrategy::default()
+            .generate_signals(&data)
+            .unwrap();
+        let result = analysis(5).entry_delay(&data, &signals, 0).unwrap();
+
+        let ledger =
+            Ledger::run("", &data, &signals, 10000.0, &CorporateActions::default()).unwrap();
+        for path in &result.paths {
+            assert!((path.final_equity - ledger.final_value()).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_entry_delay_keeps_signal_order() {
+        let mut signals = vec![Signal::Hold; 12];
+        for (i, signal) in [
+            (0, Signal::Buy),
+            (1, Signal::Sell),
+            (2, Signal::Buy),
+            (9, Signal::Sell),
+            (10, Signal::Buy),
+            (11, Signal::Sell),
+        ] {
+            signals[i] = signal;
+        }
+        let trades: Vec<Signal> = signals
+            .iter()
+            .copied()
+            .filter(|s| *s != Signal::Hold)
+            .collect();
+
+        let mut rng = seeded_rng(7);
+        for _ in 0..200 {
+            let delayed = delay_signals(&signals, 4, &mut rng);
+            let kept: Vec<Signal> = delayed
+                .iter()
+                .copied()
+                .filter(|s| *s != Signal::Hold)
+                .collect();
+            assert_eq!(kept, trades);
+            // Never earlier than the original bar nor more than 4 bars late
+            let bars = |s: &[Signal]| -> Vec<usize> {
+                (0..s.len()).filter(|&i| s[i] != Signal::Hold).collect()
+            };
+            for (d, o) in bars(&delayed).into_iter().zip(bars(&signals)) {
+                assert!(d >= o && d <= o + 4);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parameter_jitter() {
+        let data = generate_test_data(120, 100.0, 0.04);
+        let result = analysis(20)
+            .parameter_jitter(&data, &[20.0, 2.0], 0.2, |p| {
+                MeanReversionStrategy::new(p[0].round() as usize, p[1], 0.1, 0.9)
+                    .map_err(TradeError::InvalidData)?
+                    .generate_signals(&data)
+            })
+            .unwrap();
+        assert_eq!(result.paths.len(), 20);
+
+        assert!(analysis(5)
+            .parameter_jitter(&data, &[20.0], 1.5, |_| Ok(vec![]))
+            .is_err());
+    }
+
+    #[test]
+    fn test_trade_returns() {
+        let bar = |price: f64| DailyOhlcv {
+            date: chrono::NaiveDate::from_ymd_opt(2023, 1, 2).unwrap(),
+            data: crate::OhlcvData {
+                open: price,
+                high: price,
+                low: price,
+                close: price,
+                volume: 100,
+            },
+        };
+        let data: Vec<DailyOhlcv> = [100.0, 100.0, 110.0, 120.0, 60.0].map(bar).to_vec();
+        let signals = vec![
+            Signal::Buy,
+            Signal::Sell,
+            Signal::Buy,
+            Signal::Hold,
+            Signal::Hold,
+        ];
+        let trades = trade_returns(&data, &signals).unwrap();
+        assert_eq!(trades.len(), 2);
+        assert!((trades[0] - 0.1).abs() < 1e-9);
+        assert!((trades[1] + 0.5).abs() < 1e-9);
+    }
+}
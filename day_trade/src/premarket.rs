@@ -0,0 +1,733 @@
+//! Pre-market gap and overnight mover scanning
+//!
+//! Minute data that includes extended hours is split into pre-market,
+//! regular and after-hours phases per session. From that the scanner works
+//! out, for the latest session of each symbol:
+//!
+//! - the overnight gap from the previous regular close to the latest
+//!   pre-market price, and the after-hours move that led into it
+//! - pre-market volume relative to the average of earlier sessions up to the
+//!   same time of day, so a scan at 8:00 is not compared with full
+//!   pre-market sessions
+//! - the pre-market high and low as levels for the open
+//! - how often earlier gaps of at least the same size and direction were
+//!   filled during the regular session, and how quickly
+//!
+//! and ranks the symbols by gap size weighted by relative volume.
+//!
+//! Session times are compared in the exchange's time zone, so daylight
+//! saving time is applied date by date. The default zone is UTC, treating
+//! timestamps as exchange-local times like the bars from `simulation`.
+//!
+//! # Example
+//!
+//! ```
+//! use day_trade::premarket::PremarketScanner;
+//! use day_trade::{MinuteOhlcv, OhlcvData};
+//! use chrono::NaiveDate;
+//!
+//! let bar = |day: u32, hour: u32, minute: u32, price: f64, volume: u64| MinuteOhlcv {
+//!     timestamp: NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+//!         .and_hms_opt(hour, minute, 0).unwrap().and_utc(),
+//!     data: OhlcvData { open: price, high: price, low: price, close: price, volume },
+//! };
+//! let data = vec![
+//!     bar(4, 9, 30, 100.0, 5000),
+//!     bar(4, 15, 59, 100.0, 5000),
+//!     bar(5, 8, 0, 104.0, 2000),
+//! ];
+//!
+//! let scanner = PremarketScanner::default().with_min_gap(0.02).unwrap();
+//! let scan = scanner.scan([("ABC", data.as_slice())]);
+//! assert_eq!(scan.movers[0].symbol, "ABC");
+//! assert!((scan.movers[0].gap - 0.04).abs() < 1e-12);
+//! ```
+
+use crate::{MinuteOhlcv, TradeError};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+
+/// Part of the trading day a bar falls in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum SessionPhase {
+    /// Before the regular open
+    PreMarket,
+    /// Regular trading hours
+    Regular,
+    /// After the regular close
+    AfterHours,
+    /// Outside extended hours
+    Closed,
+}
+
+/// Extended and regular trading hours of an exchange
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionHours {
+    premarket_open: NaiveTime,
+    regular_open: NaiveTime,
+    regular_close: NaiveTime,
+    after_hours_close: NaiveTime,
+    timezone: Tz,
+}
+
+impl Default for SessionHours {
+    /// US equities: pre-market from 4:00, regular 9:30 to 16:00 and after
+    /// hours until 20:00, with timestamps in exchange time
+    fn default() -> Self {
+        Self {
+            premarket_open: NaiveTime::from_hms_opt(4, 0, 0).unwrap(),
+            regular_open: NaiveTime::from_hms_opt(9, 30, 0).unwrap(),
+            regular_close: NaiveTime::from_hms_opt(16, 0, 0).unwrap(),
+            after_hours_close: NaiveTime::from_hms_opt(20, 0, 0).unwrap(),
+            timezone: Tz::UTC,
+        }
+    }
+}
+
+impl SessionHours {
+    /// Create session hours; the four times must be strictly increasing
+    pub fn new(
+        premarket_open: NaiveTime,
+        regular_open: NaiveTime,
+        regular_close: NaiveTime,
+        after_hours_close: NaiveTime,
+    ) -> Result<Self, String> {
+        if !(premarket_open < regular_open
+            && regular_open < regular_close
+            && regular_close < after_hours_close)
+        {
+            return Err(
+                "Session times must increase from pre-market open to after-hours close".to_string(),
+            );
+        }
+        Ok(Self {
+            premarket_open,
+            regular_open,
+            regular_close,
+            after_hours_close,
+            timezone: Tz::UTC,
+        })
+    }
+
+    /// Exchange time zone, e.g. `America/New_York` for bars timestamped in
+    /// UTC; the offset follows daylight saving time on each date
+    pub fn with_timezone(mut self, timezone: Tz) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// Regular session open
+    pub fn regular_open(&self) -> NaiveTime {
+        self.regular_open
+    }
+
+    fn local(&self, timestamp: &DateTime<Utc>) -> NaiveDateTime {
+        timestamp.with_timezone(&self.timezone).naive_local()
+    }
+
+    /// Exchange date of a timestamp
+    pub fn session_date(&self, timestamp: &DateTime<Utc>) -> NaiveDate {
+        self.local(timestamp).date()
+    }
+
+    /// Phase a timestamp falls in
+    pub fn phase(&self, timestamp: &DateTime<Utc>) -> SessionPhase {
+        let time = self.local(timestamp).time();
+        if time < self.premarket_open || time >= self.after_hours_close {
+            SessionPhase::Closed
+        } else if time < self.regular_open {
+            SessionPhase::PreMarket
+        } else if time < self.regular_close {
+            SessionPhase::Regular
+        } else {
+            SessionPhase::AfterHours
+        }
+    }
+}
+
+/// Open, high, low, close and volume of one phase of a session
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct PhaseRange {
+    /// First bar's open
+    pub open: f64,
+    /// Highest high
+    pub high: f64,
+    /// Lowest low
+    pub low: f64,
+    /// Last bar's close
+    pub close: f64,
+    /// Total volume
+    pub volume: u64,
+    /// Number of bars
+    pub bars: usize,
+}
+
+impl PhaseRange {
+    fn of(bars: &[&MinuteOhlcv]) -> Option<Self> {
+        let first = bars.first()?;
+        let last = bars.last()?;
+        Some(Self {
+            open: first.data.open,
+            high: bars.iter().map(|b| b.data.high).fold(f64::MIN, f64::max),
+            low: bars.iter().map(|b| b.data.low).fold(f64::MAX, f64::min),
+            close: last.data.close,
+            volume: bars.iter().map(|b| b.data.volume).sum(),
+            bars: bars.len(),
+        })
+    }
+}
+
+/// Gap between a regular close and the next regular open
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct OvernightGap {
+    /// Previous session's regular close
+    pub previous_close: f64,
+    /// Regular open
+    pub open: f64,
+    /// `open / previous_close - 1`
+    pub gap: f64,
+    /// Whether the regular session traded back to the previous close
+    pub filled: bool,
+    /// Bars after the open until the fill, if filled
+    pub minutes_to_fill: Option<usize>,
+}
+
+/// One session's phases and opening gap
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionSummary {
+    /// Exchange date
+    pub date: NaiveDate,
+    /// Pre-market bars
+    pub premarket: Option<PhaseRange>,
+    /// Regular-hours bars
+    pub regular: Option<PhaseRange>,
+    /// After-hours bars
+    pub after_hours: Option<PhaseRange>,
+    /// Gap at the regular open, when both this and an earlier session
+    /// traded during regular hours
+    pub gap: Option<OvernightGap>,
+}
+
+/// Bars of one session by phase
+struct Session<'a> {
+    date: NaiveDate,
+    premarket: Vec<&'a MinuteOhlcv>,
+    regular: Vec<&'a MinuteOhlcv>,
+    after_hours: Vec<&'a MinuteOhlcv>,
+}
+
+fn group_sessions<'a>(data: &'a [MinuteOhlcv], hours: &SessionHours) -> Vec<Session<'a>> {
+    let mut sessions: Vec<Session<'a>> = Vec::new();
+    for bar in data {
+        let phase = hours.phase(&bar.timestamp);
+        if phase == SessionPhase::Closed {
+            continue;
+        }
+        let date = hours.session_date(&bar.timestamp);
+        if sessions.last().is_none_or(|s| s.date != date) {
+            sessions.push(Session {
+                date,
+                premarket: Vec::new(),
+                regular: Vec::new(),
+                after_hours: Vec::new(),
+            });
+        }
+        let session = sessions.last_mut().expect("session was just pushed");
+        match phase {
+            SessionPhase::PreMarket => session.premarket.push(bar),
+            SessionPhase::Regular => session.regular.push(bar),
+            SessionPhase::AfterHours => session.after_hours.push(bar),
+            SessionPhase::Closed => {}
+        }
+    }
+    sessions
+}
+
+fn opening_gap(previous_close: f64, regular: &[&MinuteOhlcv]) -> Option<OvernightGap> {
+    let open = regular.first()?.data.open;
+    if previous_close <= 0.0 {
+        return None;
+    }
+    let gap = open / previous_close - 1.0;
+    let minutes_to_fill = if gap > 0.0 {
+        regular.iter().position(|b| b.data.low <= previous_close)
+    } else if gap < 0.0 {
+        regular.iter().position(|b| b.data.high >= previous_close)
+    } else {
+        Some(0)
+    };
+    Some(OvernightGap {
+        previous_close,
+        open,
+        gap,
+        filled: minutes_to_fill.is_some(),
+        minutes_to_fill,
+    })
+}
+
+/// Split minute bars into sessions with their phases and opening gaps
+///
+/// Bars outside extended hours are ignored. Data must be in time order.
+pub fn session_summaries(data: &[MinuteOhlcv], hours: &SessionHours) -> Vec<SessionSummary> {
+    let mut previous_close = None;
+    group_sessions(data, hours)
+        .into_iter()
+        .map(|s| {
+            let gap = previous_close.and_then(|close| opening_gap(close, &s.regular));
+            if let Some(last) = s.regular.last() {
+                previous_close = Some(last.data.close);
+            }
+            SessionSummary {
+                date: s.date,
+                premarket: PhaseRange::of(&s.premarket),
+                regular: PhaseRange::of(&s.regular),
+                after_hours: PhaseRange::of(&s.after_hours),
+                gap,
+            }
+        })
+        .collect()
+}
+
+/// Direction of an overnight gap
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum GapDirection {
+    /// Indicated above the previous close
+    Up,
+    /// Indicated below the previous close
+    Down,
+}
+
+/// How earlier gaps like today's played out
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct GapFillStats {
+    /// Earlier gaps in the same direction and at least the minimum size
+    pub gaps: usize,
+    /// How many of them were filled during their regular session
+    pub filled: usize,
+    /// `filled / gaps`, or `None` without earlier gaps
+    pub fill_rate: Option<f64>,
+    /// Median bars from the open until the fill
+    pub median_minutes_to_fill: Option<f64>,
+}
+
+impl GapFillStats {
+    fn from_gaps<'a>(gaps: impl Iterator<Item = &'a OvernightGap>) -> Self {
+        let gaps: Vec<&OvernightGap> = gaps.collect();
+        let mut minutes: Vec<f64> = gaps
+            .iter()
+            .filter_map(|g| g.minutes_to_fill)
+            .map(|m| m as f64)
+            .collect();
+        minutes.sort_by(f64::total_cmp);
+        let median = match minutes.len() {
+            0 => None,
+            n if n % 2 == 1 => Some(minutes[n / 2]),
+            n => Some((minutes[n / 2 - 1] + minutes[n / 2]) / 2.0),
+        };
+        Self {
+            gaps: gaps.len(),
+            filled: minutes.len(),
+            fill_rate: (!gaps.is_empty()).then(|| minutes.len() as f64 / gaps.len() as f64),
+            median_minutes_to_fill: median,
+        }
+    }
+}
+
+/// A symbol's pre-market picture for its latest session
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PremarketMover {
+    /// Symbol
+    pub symbol: String,
+    /// Session being scanned
+    pub date: NaiveDate,
+    /// Previous session's regular close
+    pub previous_close: f64,
+    /// Latest pre-market price
+    pub last_price: f64,
+    /// `last_price / previous_close - 1`
+    pub gap: f64,
+    /// Gap direction
+    pub direction: GapDirection,
+    /// After-hours move of the previous session relative to its close
+    pub after_hours_change: Option<f64>,
+    /// Pre-market high so far
+    pub premarket_high: f64,
+    /// Pre-market low so far
+    pub premarket_low: f64,
+    /// Pre-market volume so far
+    pub premarket_volume: u64,
+    /// Average pre-market volume of earlier sessions up to the same time
+    pub average_premarket_volume: Option<f64>,
+    /// `premarket_volume / average_premarket_volume`
+    pub relative_volume: Option<f64>,
+    /// Fill history of comparable earlier gaps
+    pub history: GapFillStats,
+    /// Ranking score: gap in percent times relative volume (1 when unknown)
+    pub score: f64,
+}
+
+/// Result of scanning a universe
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PremarketScan {
+    /// Symbols passing the filters, highest score first
+    pub movers: Vec<PremarketMover>,
+    /// Symbols that could not be analysed, with the reason
+    pub skipped: Vec<(String, String)>,
+}
+
+/// Ranks symbols by their pre-market gap and volume
+#[derive(Debug, Clone)]
+pub struct PremarketScanner {
+    hours: SessionHours,
+    min_gap: f64,
+    min_relative_volume: f64,
+    volume_lookback: usize,
+    max_results: usize,
+}
+
+impl Default for PremarketScanner {
+    /// US session hours, gaps of at least 1%, no volume filter, volume
+    /// averaged over 20 sessions and the top 20 movers
+    fn default() -> Self {
+        Self {
+            hours: SessionHours::default(),
+            min_gap: 0.01,
+            min_relative_volume: 0.0,
+            volume_lookback: 20,
+            max_results: 20,
+        }
+    }
+}
+
+impl PremarketScanner {
+    /// Scanner with the default settings and the given session hours
+    pub fn new(hours: SessionHours) -> Self {
+        Self {
+            hours,
+            ..Self::default()
+        }
+    }
+
+    /// Only report gaps of at least `fraction` in either direction; also the
+    /// smallest earlier gap counted in the fill statistics
+    pub fn with_min_gap(mut self, fraction: f64) -> Result<Self, String> {
+        if !fraction.is_finite() || fraction < 0.0 {
+            return Err("Minimum gap must be zero or positive".to_string());
+        }
+        self.min_gap = fraction;
+        Ok(self)
+    }
+
+    /// Only report symbols trading at least `ratio` times their usual
+    /// pre-market volume
+    pub fn with_min_relative_volume(mut self, ratio: f64) -> Result<Self, String> {
+        if !ratio.is_finite() || ratio < 0.0 {
+            return Err("Minimum relative volume must be zero or positive".to_string());
+        }
+        self.min_relative_volume = ratio;
+        Ok(self)
+    }
+
+    /// Average pre-market volume over the last `sessions` sessions
+    pub fn with_volume_lookback(mut self, sessions: usize) -> Result<Self, String> {
+        if sessions == 0 {
+            return Err("Volume lookback must be at least one session".to_string());
+        }
+        self.volume_lookback = sessions;
+        Ok(self)
+    }
+
+    /// Keep at most `count` movers
+    pub fn with_max_results(mut self, count: usize) -> Self {
+        self.max_results = count;
+        self
+    }
+
+    /// Session hours
+    pub fn hours(&self) -> &SessionHours {
+        &self.hours
+    }
+
+    /// Analyse the latest session of one symbol without filtering
+    ///
+    /// The latest session is pending until its regular session opens: it
+    /// must have pre-market bars and no regular-hours bars, and an earlier
+    /// session must have traded during regular hours.
+    pub fn analyze(
+        &self,
+        symbol: &str,
+        data: &[MinuteOhlcv],
+    ) -> Result<PremarketMover, TradeError> {
+        let sessions = group_sessions(data, &self.hours);
+        let (today, history) = sessions
+            .split_last()
+            .ok_or_else(|| TradeError::InsufficientData("No extended-hours bars".to_string()))?;
+        if !today.regular.is_empty() {
+            return Err(TradeError::InsufficientData(format!(
+                "Regular session of {} has already opened",
+                today.date
+            )));
+        }
+        let premarket = PhaseRange::of(&today.premarket).ok_or_else(|| {
+            TradeError::InsufficientData(format!("No pre-market bars on {}", today.date))
+        })?;
+        let previous = history
+            .iter()
+            .rev()
+            .find(|s| !s.regular.is_empty())
+            .ok_or_else(|| {
+                TradeError::InsufficientData("No earlier regular session".to_string())
+            })?;
+        let previous_close = previous.regular.last().expect("regular bars").data.close;
+        if previous_close <= 0.0 {
+            return Err(TradeError::InvalidData(
+                "Previous close must be positive".to_string(),
+            ));
+        }
+
+        let gap = premarket.close / previous_close - 1.0;
+        let direction = if gap >= 0.0 {
+            GapDirection::Up
+        } else {
+            GapDirection::Down
+        };
+        let after_hours_change =
+            PhaseRange::of(&previous.after_hours).map(|r| r.close / previous_close - 1.0);
+
+        // Volume up to the same time of day in earlier pre-market sessions
+        let cutoff = self
+            .hours
+            .local(&today.premarket.last().expect("pre-market bars").timestamp)
+            .time();
+        let earlier: Vec<f64> = history
+            .iter()
+            .rev()
+            .filter(|s| !s.premarket.is_empty())
+            .take(self.volume_lookback)
+            .map(|s| {
+                s.premarket
+                    .iter()
+                    .filter(|b| self.hours.local(&b.timestamp).time() <= cutoff)
+                    .map(|b| b.data.volume as f64)
+                    .sum()
+            })
+            .collect();
+        let average_premarket_volume =
+            (!earlier.is_empty()).then(|| earlier.iter().sum::<f64>() / earlier.len() as f64);
+        let relative_volume = average_premarket_volume
+            .filter(|&v| v > 0.0)
+            .map(|v| premarket.volume as f64 / v);
+
+        let mut previous_close_so_far = None;
+        let mut gaps = Vec::new();
+        for s in history {
+            if let Some(g) = previous_close_so_far.and_then(|c| opening_gap(c, &s.regular)) {
+                gaps.push(g);
+            }
+            if let Some(last) = s.regular.last() {
+                previous_close_so_far = Some(last.data.close);
+            }
+        }
+        let history = GapFillStats::from_gaps(gaps.iter().filter(|g| {
+            g.gap.abs() >= self.min_gap.max(f64::EPSILON)
+                && (g.gap > 0.0) == (direction == GapDirection::Up)
+        }));
+
+        Ok(PremarketMover {
+            symbol: symbol.to_string(),
+            date: today.date,
+            previous_close,
+            last_price: premarket.close,
+            gap,
+            direction,
+            after_hours_change,
+            premarket_high: premarket.high,
+            premarket_low: premarket.low,
+            premarket_volume: premarket.volume,
+            average_premarket_volume,
+            relative_volume,
+            history,
+            score: gap.abs() * 100.0 * relative_volume.unwrap_or(1.0),
+        })
+    }
+
+    /// Analyse every symbol, filter by gap and relative volume and rank by
+    /// score
+    pub fn scan<'a, S, I>(&self, universe: I) -> PremarketScan
+    where
+        S: AsRef<str>,
+        I: IntoIterator<Item = (S, &'a [MinuteOhlcv])>,
+    {
+        let mut scan = PremarketScan::default();
+        for (symbol, data) in universe {
+            match self.analyze(symbol.as_ref(), data) {
+                Ok(mover) => {
+                    let volume_ok = self.min_relative_volume == 0.0
+                        || mover
+                            .relative_volume
+                            .is_some_and(|r| r >= self.min_relative_volume);
+                    if mover.gap.abs() >= self.min_gap && volume_ok {
+                        scan.movers.push(mover);
+                    }
+                }
+                Err(e) => scan
+                    .skipped
+                    .push((symbol.as_ref().to_string(), e.to_string())),
+            }
+        }
+        scan.movers
+            .sort_by(|a, b| b.score.total_cmp(&a.score).then(a.symbol.cmp(&b.symbol)));
+        scan.movers.truncate(self.max_results);
+        scan
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OhlcvData;
+
+    fn bar(day: u32, hour: u32, minute: u32, low: f64, high: f64, volume: u64) -> MinuteOhlcv {
+        MinuteOhlcv {
+            timestamp: NaiveDate::from_ymd_opt(2024, 3, day)
+                .unwrap()
+                .and_hms_opt(hour, minute, 0)
+                .unwrap()
+                .and_utc(),
+            data: OhlcvData {
+                open: low,
+                high,
+                low,
+                close: high,
+                volume,
+            },
+        }
+    }
+
+    /// Three sessions: a 5% gap up filled after two bars, a 5% gap up that
+    /// holds, then a pre-market gap of 6% on heavy volume
+    fn history() -> Vec<MinuteOhlcv> {
+        vec![
+            bar(4, 8, 0, 99.0, 99.0, 100),
+            bar(4, 9, 30, 99.0, 100.0, 1000),
+            bar(4, 15, 59, 100.0, 100.0, 1000),
+            bar(4, 17, 0, 101.0, 101.0, 50),
+            bar(5, 8, 0, 105.0, 105.0, 100),
+            bar(5, 9, 30, 105.0, 106.0, 1000),
+            bar(5, 9, 31, 102.0, 103.0, 1000),
+            bar(5, 9, 32, 99.5, 101.0, 1000),
+            bar(5, 15, 59, 100.0, 100.0, 1000),
+            bar(5, 17, 0, 102.0, 102.0, 80),
+            bar(6, 7, 0, 104.0, 104.0, 100),
+            bar(6, 8, 30, 105.0, 105.0, 100),
+            bar(6, 9, 30, 105.0, 106.0, 1000),
+            bar(6, 15, 59, 106.0, 106.0, 1000),
+            bar(7, 4, 30, 110.0, 111.0, 300),
+            bar(7, 8, 0, 111.0, 113.0, 300),
+            bar(7, 9, 0, 112.0, 112.36, 300),
+        ]
+    }
+
+    #[test]
+    fn test_sessions_and_gap_fills() {
+        let hours = SessionHours::default();
+        assert_eq!(
+            hours.phase(&bar(4, 3, 59, 1.0, 1.0, 0).timestamp),
+            SessionPhase::Closed
+        );
+        assert_eq!(
+            hours.phase(&bar(4, 16, 0, 1.0, 1.0, 0).timestamp),
+            SessionPhase::AfterHours
+        );
+
+        let summaries = session_summaries(&history(), &hours);
+        assert_eq!(summaries.len(), 4);
+        assert!(summaries[0].gap.is_none());
+        let first = summaries[1].gap.unwrap();
+        assert!((first.gap - 0.05).abs() < 1e-12);
+        assert_eq!(first.minutes_to_fill, Some(2));
+        let second = summaries[2].gap.unwrap();
+        assert!(!second.filled);
+        assert_eq!(summaries[1].after_hours.unwrap().volume, 80);
+
+        // New York moves to daylight saving time on March 10 2024
+        let new_york = SessionHours::default().with_timezone(chrono_tz::America::New_York);
+        assert_eq!(
+            new_york.phase(&bar(8, 14, 30, 1.0, 1.0, 0).timestamp),
+            SessionPhase::Regular
+        );
+        assert_eq!(
+            new_york.phase(&bar(11, 13, 30, 1.0, 1.0, 0).timestamp),
+            SessionPhase::Regular
+        );
+        assert_eq!(
+            new_york.phase(&bar(11, 13, 29, 1.0, 1.0, 0).timestamp),
+            SessionPhase::PreMarket
+        );
+        assert_eq!(
+            new_york.session_date(&bar(11, 3, 0, 1.0, 1.0, 0).timestamp),
+            NaiveDate::from_ymd_opt(2024, 3, 10).unwrap()
+        );
+        assert!(SessionHours::new(
+            hours.regular_open(),
+            hours.regular_open(),
+            NaiveTime::from_hms_opt(16, 0, 0).unwrap(),
+            NaiveTime::from_hms_opt(20, 0, 0).unwrap(),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_scan_ranks_movers() {
+        let scanner = PremarketScanner::default().with_min_gap(0.03).unwrap();
+        let mover = scanner.analyze("ABC", &history()).unwrap();
+        assert_eq!(mover.direction, GapDirection::Up);
+        assert_eq!(mover.previous_close, 106.0);
+        assert!((mover.gap - 0.06).abs() < 1e-9);
+        assert_eq!(mover.premarket_high, 113.0);
+        assert_eq!(mover.premarket_low, 110.0);
+        assert_eq!(mover.premarket_volume, 900);
+        // Earlier pre-markets up to 9:00 traded 100, 100 and 200
+        assert!((mover.relative_volume.unwrap() - 900.0 / (400.0 / 3.0)).abs() < 1e-9);
+        assert_eq!(mover.history.gaps, 2);
+        assert_eq!(mover.history.filled, 1);
+        assert_eq!(mover.history.fill_rate, Some(0.5));
+        assert_eq!(mover.history.median_minutes_to_fill, Some(2.0));
+        assert!(mover.after_hours_change.is_none());
+
+        // A flat symbol, and one without a previous session
+        let flat: Vec<MinuteOhlcv> = history()
+            .into_iter()
+            .map(|mut b| {
+                b.data = OhlcvData {
+                    open: 50.0,
+                    high: 50.0,
+                    low: 50.0,
+                    close: 50.0,
+                    volume: 10,
+                };
+                b
+            })
+            .collect();
+        let lonely = vec![bar(7, 8, 0, 10.0, 10.0, 5)];
+        let data = history();
+        let scan = scanner.scan([
+            ("FLAT", flat.as_slice()),
+            ("ABC", data.as_slice()),
+            ("NEW", lonely.as_slice()),
+        ]);
+        assert_eq!(scan.movers.len(), 1);
+        assert_eq!(scan.movers[0].symbol, "ABC");
+        assert_eq!(scan.skipped.len(), 1);
+        assert_eq!(scan.skipped[0].0, "NEW");
+
+        // Once the regular session opens there is no pre-market to scan
+        let mut opened = history();
+        opened.push(bar(7, 9, 30, 112.0, 112.5, 1000));
+        assert!(scanner.analyze("ABC", &opened).is_err());
+
+        let strict = scanner.with_min_relative_volume(10.0).unwrap();
+        assert!(strict.scan([("ABC", data.as_slice())]).movers.is_empty());
+    }
+}
@@ -0,0 +1,537 @@
+//! Gaussian hidden Markov models
+//!
+//! `GaussianHmm` is a univariate HMM with normal emissions, estimated by
+//! Baum-Welch (expectation-maximisation over forward-backward state
+//! probabilities, with per-step scaling against underflow).
+//! `HmmRegimeDetector` fits one on a training window and labels later bars
+//! with the most probable state given the bars so far.
+
+use super::{log_returns, rolling_volatility, MarketRegime, RegimeDetector};
+use crate::{OhlcvData, TradeError};
+use std::f64::consts::PI;
+
+/// Smallest emission density, keeping probabilities away from zero
+const MIN_DENSITY: f64 = 1e-300;
+
+/// Univariate hidden Markov model with Gaussian emissions
+#[derive(Debug, Clone)]
+pub struct GaussianHmm {
+    initial: Vec<f64>,
+    transition: Vec<Vec<f64>>,
+    means: Vec<f64>,
+    variances: Vec<f64>,
+    log_likelihood: f64,
+    iterations: usize,
+}
+
+impl GaussianHmm {
+    /// Fit a model with `states` hidden states by Baum-Welch
+    ///
+    /// Stops after `max_iterations` or once the log-likelihood improves by
+    /// less than `tolerance`.
+    pub fn fit(
+        observations: &[f64],
+        states: usize,
+        max_iterations: usize,
+        tolerance: f64,
+    ) -> Result<Self, TradeError> {
+        if states == 0 {
+            return Err(TradeError::InvalidData(
+                "An HMM needs at least one state".to_string(),
+            ));
+        }
+        if observations.len() < 10 * states {
+            return Err(TradeError::InsufficientData(format!(
+                "Need at least {} observations to fit {} states",
+                10 * states,
+                states
+            )));
+        }
+        if observations.iter().any(|o| !o.is_finite()) {
+            return Err(TradeError::InvalidData(
+                "Observations must be finite".to_string(),
+            ));
+        }
+
+        let mut model = Self::initial_guess(observations, states);
+        let variance_floor = 1e-6 * model.variances.iter().cloned().fold(0.0, f64::max);
+        let mut previous = f64::NEG_INFINITY;
+
+        for iteration in 1..=max_iterations.max(1) {
+            let (alpha, scales) = model.forward(observations);
+            let beta = model.backward(observations, &scales);
+            model.log_likelihood = scales.iter().map(|c| c.ln()).sum();
+            model.iterations = iteration;
+            if model.log_likelihood - previous < tolerance {
+                break;
+            }
+            previous = model.log_likelihood;
+            model.reestimate(observations, &alpha, &beta, &scales, variance_floor);
+        }
+        Ok(model)
+    }
+
+    /// Means spread over the quantiles of the data, variances from calm to
+    /// turbulent, and persistent states
+    fn initial_guess(observations: &[f64], states: usize) -> Self {
+        let n = observations.len() as f64;
+        let mean = observations.iter().sum::<f64>() / n;
+        let variance = observations.iter().map(|o| (o - mean).powi(2)).sum::<f64>() / n;
+        let mut sorted = observations.to_vec();
+        sorted.sort_by(f64::total_cmp);
+
+        let means = (0..states)
+            .map(|k| {
+                let q = sorted[((k as f64 + 0.5) / states as f64 * n) as usize];
+                0.5 * (mean + q)
+            })
+            .collect();
+        let variances = (0..states)
+            .map(|k| variance.max(f64::MIN_POSITIVE) * (k as f64 + 0.5) * 2.0 / states as f64)
+            .collect();
+        let stay = if states == 1 { 1.0 } else { 0.9 };
+        let transition = (0..states)
+            .map(|i| {
+                (0..states)
+                    .map(|j| {
+                        if i == j {
+                            stay
+                        } else {
+                            (1.0 - stay) / (states - 1) as f64
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            initial: vec![1.0 / states as f64; states],
+            transition,
+            means,
+            variances,
+            log_likelihood: f64::NEG_INFINITY,
+            iterations: 0,
+        }
+    }
+
+    fn density(&self, state: usize, observation: f64) -> f64 {
+        let variance = self.variances[state];
+        let z = observation - self.means[state];
+        ((-0.5 * z * z / variance).exp() / (2.0 * PI * variance).sqrt()).max(MIN_DENSITY)
+    }
+
+    /// Scaled forward probabilities and their scale factors
+    fn forward(&self, observations: &[f64]) -> (Vec<Vec<f64>>, Vec<f64>) {
+        let states = self.states();
+        let mut alpha = Vec::with_capacity(observations.len());
+        let mut scales = Vec::with_capacity(observations.len());
+        for (t, &o) in observations.iter().enumerate() {
+            let mut row: Vec<f64> = (0..states)
+                .map(|j| {
+                    let prior = if t == 0 {
+                        self.initial[j]
+                    } else {
+                        let previous: &Vec<f64> = &alpha[t - 1];
+                        (0..states)
+                            .map(|i| previous[i] * self.transition[i][j])
+                            .sum()
+                    };
+                    prior * self.density(j, o)
+                })
+                .collect();
+            let scale: f64 = row.iter().sum();
+            row.iter_mut().for_each(|a| *a /= scale);
+            alpha.push(row);
+            scales.push(scale);
+        }
+        (alpha, scales)
+    }
+
+    /// Backward probabilities scaled by the forward scale factors
+    fn backward(&self, observations: &[f64], scales: &[f64]) -> Vec<Vec<f64>> {
+        let states = self.states();
+        let n = observations.len();
+        let mut beta = vec![vec![1.0; states]; n];
+        for t in (0..n - 1).rev() {
+            for i in 0..states {
+                beta[t][i] = (0..states)
+                    .map(|j| {
+                        self.transition[i][j]
+                            * self.density(j, observations[t + 1])
+                            * beta[t + 1][j]
+                    })
+                    .sum::<f64>()
+                    / scales[t + 1];
+            }
+        }
+        beta
+    }
+
+    fn reestimate(
+        &mut self,
+        observations: &[f64],
+        alpha: &[Vec<f64>],
+        beta: &[Vec<f64>],
+        scales: &[f64],
+        variance_floor: f64,
+    ) {
+        let states = self.states();
+        let n = observations.len();
+        let gamma: Vec<Vec<f64>> = alpha
+            .iter()
+            .zip(beta)
+            .map(|(a, b)| {
+                let row: Vec<f64> = a.iter().zip(b).map(|(x, y)| x * y).collect();
+                let total: f64 = row.iter().sum();
+                row.into_iter().map(|g| g / total).collect()
+            })
+            .collect();
+
+        let mut transitions = vec![vec![0.0; states]; states];
+        for t in 0..n - 1 {
+            for (i, row) in transitions.iter_mut().enumerate() {
+                for (j, cell) in row.iter_mut().enumerate() {
+                    *cell += alpha[t][i]
+                        * self.transition[i][j]
+                        * self.density(j, observations[t + 1])
+                        * beta[t + 1][j]
+                        / scales[t + 1];
+                }
+            }
+        }
+        for (i, row) in transitions.iter().enumerate() {
+            let total: f64 = row.iter().sum();
+            if total > 0.0 {
+                self.transition[i] = row.iter().map(|x| x / total).collect();
+            }
+        }
+
+        for k in 0..states {
+            let weight: f64 = gamma.iter().map(|g| g[k]).sum();
+            if weight <= 0.0 {
+                continue;
+            }
+            let mean = gamma
+                .iter()
+                .zip(observations)
+                .map(|(g, o)| g[k] * o)
+                .sum::<f64>()
+                / weight;
+            let variance = gamma
+                .iter()
+                .zip(observations)
+                .map(|(g, o)| g[k] * (o - mean).powi(2))
+                .sum::<f64>()
+                / weight;
+            self.means[k] = mean;
+            self.variances[k] = variance.max(variance_floor);
+        }
+        self.initial = gamma[0].clone();
+    }
+
+    /// Probability of each state given the observations up to each step
+    pub fn filter(&self, observations: &[f64]) -> Vec<Vec<f64>> {
+        self.forward(observations).0
+    }
+
+    /// Number of hidden states
+    pub fn states(&self) -> usize {
+        self.means.len()
+    }
+
+    /// Emission mean of each state
+    pub fn means(&self) -> &[f64] {
+        &self.means
+    }
+
+    /// Emission variance of each state
+    pub fn variances(&self) -> &[f64] {
+        &self.variances
+    }
+
+    /// State transition probabilities, `transition[i][j]` = P(i -> j)
+    pub fn transition(&self) -> &[Vec<f64>] {
+        &self.transition
+    }
+
+    /// Log-likelihood of the training observations
+    pub fn log_likelihood(&self) -> f64 {
+        self.log_likelihood
+    }
+
+    /// Baum-Welch iterations run
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+}
+
+/// Series the HMM is fitted to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HmmObservation {
+    /// Close-to-close log returns; states differ in variance
+    Returns,
+    /// Log of rolling return volatility over a window; states differ in mean
+    LogVolatility(usize),
+}
+
+/// Volatility regimes from a Gaussian HMM
+///
+/// The model is fitted once on the first `training_window` observations and
+/// then filtered forward. Its parameters are only known once the training
+/// window has closed, so every bar up to and including the last training
+/// observation is `Unknown`, and later bars are labelled without look-ahead.
+/// States are ranked from calm to turbulent: two states map to low and high
+/// volatility, three add normal volatility in between.
+#[derive(Debug, Clone)]
+pub struct HmmRegimeDetector {
+    states: usize,
+    training_window: usize,
+    observation: HmmObservation,
+    max_iterations: usize,
+}
+
+impl Default for HmmRegimeDetector {
+    fn default() -> Self {
+        Self {
+            states: 2,
+            training_window: 250,
+            observation: HmmObservation::Returns,
+            max_iterations: 200,
+        }
+    }
+}
+
+impl HmmRegimeDetector {
+    /// Create a detector with 2 or 3 states fitted on `training_window`
+    /// observations
+    pub fn new(states: usize, training_window: usize) -> Result<Self, String> {
+        if !(2..=3).contains(&states) {
+            return Err("HMM regime detection supports 2 or 3 states".to_string());
+        }
+        if training_window < 10 * states {
+            return Err(format!(
+                "Training window must be at least {} observations",
+                10 * states
+            ));
+        }
+        Ok(Self {
+            states,
+            training_window,
+            ..Self::default()
+        })
+    }
+
+    /// Set the series the model is fitted to
+    pub fn with_observation(mut self, observation: HmmObservation) -> Result<Self, String> {
+        if let HmmObservation::LogVolatility(window) = observation {
+            if window < 2 {
+                return Err("Volatility window must be at least 2".to_string());
+            }
+        }
+        self.observation = observation;
+        Ok(self)
+    }
+
+    /// Set the maximum number of Baum-Welch iterations
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations.max(1);
+        self
+    }
+
+    /// Observation series and the bar index of its first element
+    fn observations(&self, bars: &[OhlcvData]) -> Result<(Vec<f64>, usize), TradeError> {
+        let returns = log_returns(bars)?;
+        match self.observation {
+            HmmObservation::Returns => Ok((returns, 1)),
+            HmmObservation::LogVolatility(window) => {
+                let volatility: Vec<f64> = rolling_volatility(&returns, window)
+                    .into_iter()
+                    .flatten()
+                    .map(|v| v.max(1e-12).ln())
+                    .collect();
+                Ok((volatility, window))
+            }
+        }
+    }
+
+    fn labels(&self) -> &'static [MarketRegime] {
+        if self.states == 2 {
+            &[MarketRegime::LowVolatility, MarketRegime::HighVolatility]
+        } else {
+            &[
+                MarketRegime::LowVolatility,
+                MarketRegime::NormalVolatility,
+                MarketRegime::HighVolatility,
+            ]
+        }
+    }
+
+    /// Fit the model to the training window of `bars`
+    pub fn fit(&self, bars: &[OhlcvData]) -> Result<GaussianHmm, TradeError> {
+        let (observations, _) = self.observations(bars)?;
+        if observations.len() < self.training_window {
+            return Err(TradeError::InsufficientData(format!(
+                "Need {} observations to train the HMM, have {}",
+                self.training_window,
+                observations.len()
+            )));
+        }
+        GaussianHmm::fit(
+            &observations[..self.training_window],
+            self.states,
+            self.max_iterations,
+            1e-6,
+        )
+    }
+}
+
+impl RegimeDetector for HmmRegimeDetector {
+    fn detect(&self, bars: &[OhlcvData]) -> Result<Vec<MarketRegime>, TradeError> {
+        let model = self.fit(bars)?;
+        let (observations, offset) = self.observations(bars)?;
+
+        // Rank states by how turbulent they are
+        let key: &[f64] = match self.observation {
+            HmmObservation::Returns => model.variances(),
+            HmmObservation::LogVolatility(_) => model.means(),
+        };
+        let mut order: Vec<usize> = (0..model.states()).collect();
+        order.sort_by(|&a, &b| key[a].total_cmp(&key[b]));
+        let mut label = vec![MarketRegime::Unknown; model.states()];
+        for (rank, &state) in order.iter().enumerate() {
+            label[state] = self.labels()[rank];
+        }
+
+        // The filter runs over the training window to carry the state
+        // probabilities forward, but those bars were used to fit the model
+        let mut regimes = vec![MarketRegime::Unknown; bars.len()];
+        let filtered = model.filter(&observations);
+        for (i, probabilities) in filtered.iter().enumerate().skip(self.training_window) {
+            let state = (0..probabilities.len())
+                .max_by(|&a, &b| probabilities[a].total_cmp(&probabilities[b]))
+                .unwrap();
+            regimes[i + offset] = label[state];
+        }
+        Ok(regimes)
+    }
+
+    fn name(&self) -> &str {
+        "HMM Regime Detector"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::{MarketSimulator, PriceProcess, Regime};
+
+    fn switching_bars() -> Vec<OhlcvData> {
+        let calm = Regime {
+            drift: 0.1,
+            volatility: 0.08,
+        };
+        let stormy = Regime {
+            drift: -0.2,
+            volatility: 0.6,
+        };
+        MarketSimulator::new(PriceProcess::bull_bear(calm, stormy, 0.02), 100.0)
+            .unwrap()
+            .with_seed(11)
+            .generate_daily(1000)
+            .into_iter()
+            .map(|d| d.data)
+            .collect()
+    }
+
+    #[test]
+    fn test_baum_welch_separates_variances() {
+        let bars = switching_bars();
+        let returns = log_returns(&bars).unwrap();
+        let model = GaussianHmm::fit(&returns, 2, 200, 1e-8).unwrap();
+
+        let mut vols: Vec<f64> = model
+            .variances()
+            .iter()
+            .map(|v| (v * 252.0).sqrt())
+            .collect();
+        vols.sort_by(f64::total_cmp);
+        assert!((vols[0] - 0.08).abs() < 0.04, "calm vol {}", vols[0]);
+        assert!((vols[1] - 0.6).abs() < 0.15, "stormy vol {}", vols[1]);
+
+        // States are persistent
+        assert!(model
+            .transition()
+            .iter()
+            .enumerate()
+            .all(|(i, r)| r[i] > 0.8));
+        let total: f64 = model.filter(&returns)[500].iter().sum();
+        assert!((total - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_detector_labels_turbulent_bars() {
+        let bars = switching_bars();
+        let returns = log_returns(&bars).unwrap();
+        let regimes = HmmRegimeDetector::new(2, 500)
+            .unwrap()
+            .detect(&bars)
+            .unwrap();
+        assert_eq!(regimes.len(), bars.len());
+
+        let mean_abs = |label: MarketRegime| {
+            let picked: Vec<f64> = (1..bars.len())
+                .filter(|&t| regimes[t] == label)
+                .map(|t| returns[t - 1].abs())
+                .collect();
+            picked.iter().sum::<f64>() / picked.len() as f64
+        };
+        assert!(
+            mean_abs(MarketRegime::HighVolatility) > 3.0 * mean_abs(MarketRegime::LowVolatility)
+        );
+
+        // Labels only depend on the past
+        let early = HmmRegimeDetector::new(2, 500)
+            .unwrap()
+            .detect(&bars[..800])
+            .unwrap();
+        assert_eq!(early[..], regimes[..800]);
+    }
+
+    #[test]
+    fn test_training_window_is_unknown() {
+        let bars = switching_bars();
+        let regimes = HmmRegimeDetector::new(2, 500)
+            .unwrap()
+            .detect(&bars)
+            .unwrap();
+        // Observation 499 is the return into bar 500
+        assert!(regimes[..=500].iter().all(|r| *r == MarketRegime::Unknown));
+        assert!(regimes[501..].iter().all(|r| *r != MarketRegime::Unknown));
+
+        let regimes = HmmRegimeDetector::new(2, 400)
+            .unwrap()
+            .with_observation(HmmObservation::LogVolatility(10))
+            .unwrap()
+            .detect(&bars)
+            .unwrap();
+        assert!(regimes[..410].iter().all(|r| *r == MarketRegime::Unknown));
+        assert_ne!(regimes[410], MarketRegime::Unknown);
+    }
+
+    #[test]
+    fn test_log_volatility_observations_and_validation() {
+        let bars = switching_bars();
+        let regimes = HmmRegimeDetector::new(3, 400)
+            .unwrap()
+            .with_observation(HmmObservation::LogVolatility(10))
+            .unwrap()
+            .detect(&bars)
+            .unwrap();
+        assert!(regimes[..410].iter().all(|r| *r == MarketRegime::Unknown));
+        assert!(regimes.contains(&MarketRegime::NormalVolatility));
+
+        assert!(HmmRegimeDetector::new(4, 400).is_err());
+        assert!(HmmRegimeDetector::new(2, 5).is_err());
+        assert!(HmmRegimeDetector::default().detect(&bars[..100]).is_err());
+    }
+}
@@ -0,0 +1,100 @@
+//! Market regime detection
+//!
+//! Strategies tend to work in some market conditions and fail in others:
+//! mean reversion in quiet ranges, breakouts and trend following when prices
+//! move. A `RegimeDetector` labels every bar with a `MarketRegime` so that
+//! `RegimeSwitchingStrategy` can hand each bar to the strategy suited to it.
+//!
+//! Detectors:
+//!
+//! - **Hidden Markov model**: a Gaussian HMM fitted by Baum-Welch to returns
+//!   or log volatility, with states ranked from calm to turbulent.
+//! - **Volatility quantiles**: rolling volatility compared with its own
+//!   history.
+//! - **ADX and Hurst exponent**: trend strength and persistence, separating
+//!   trending from ranging markets.
+//!
+//! The regime of a bar only depends on that bar and the bars before it, so
+//! detected regimes can drive a backtest without look-ahead.
+//!
+//! # Example
+//!
+//! ```
+//! use day_trade::regime::{MarketRegime, RegimeDetector, VolatilityRegimeDetector};
+//! use day_trade::simulation::{MarketSimulator, PriceProcess};
+//! use day_trade::OhlcvData;
+//!
+//! let daily = MarketSimulator::new(PriceProcess::gbm(0.05, 0.2), 100.0)
+//!     .unwrap()
+//!     .generate_daily(300);
+//! let bars: Vec<OhlcvData> = daily.iter().map(|d| d.data.clone()).collect();
+//!
+//! let regimes = VolatilityRegimeDetector::default().detect(&bars).unwrap();
+//! assert_eq!(regimes.len(), bars.len());
+//! assert_eq!(regimes[0], MarketRegime::Unknown);
+//! ```
+
+pub mod hmm;
+pub mod trend;
+pub mod volatility;
+
+pub use hmm::{GaussianHmm, HmmObservation, HmmRegimeDetector};
+pub use trend::{adx, hurst_exponent, AdxRegimeDetector, HurstRegimeDetector};
+pub use volatility::VolatilityRegimeDetector;
+
+use crate::{OhlcvData, TradeError};
+
+/// Market condition of a bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MarketRegime {
+    /// Prices move persistently in one direction
+    Trending,
+    /// Prices oscillate around a level
+    Ranging,
+    /// Volatility is low relative to its history
+    LowVolatility,
+    /// Volatility is typical
+    NormalVolatility,
+    /// Volatility is high relative to its history
+    HighVolatility,
+    /// Not enough history to tell
+    Unknown,
+}
+
+/// Labels bars with market regimes
+pub trait RegimeDetector {
+    /// Regime of every bar, each using only that bar and earlier ones
+    fn detect(&self, bars: &[OhlcvData]) -> Result<Vec<MarketRegime>, TradeError>;
+
+    /// Name of the detector
+    fn name(&self) -> &str;
+}
+
+/// Close-to-close log returns; element `i` is the return into bar `i + 1`
+pub(crate) fn log_returns(bars: &[OhlcvData]) -> Result<Vec<f64>, TradeError> {
+    if bars.iter().any(|b| b.close.is_nan() || b.close <= 0.0) {
+        return Err(TradeError::InvalidData(
+            "Close prices must be positive".to_string(),
+        ));
+    }
+    Ok(bars
+        .windows(2)
+        .map(|w| (w[1].close / w[0].close).ln())
+        .collect())
+}
+
+/// Sample standard deviation of each trailing window of `window` values,
+/// `None` until a full window is available
+pub(crate) fn rolling_volatility(values: &[f64], window: usize) -> Vec<Option<f64>> {
+    (0..values.len())
+        .map(|i| {
+            (i + 1 >= window).then(|| {
+                let slice = &values[i + 1 - window..=i];
+                let mean = slice.iter().sum::<f64>() / window as f64;
+                let variance =
+                    slice.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (window - 1) as f64;
+                variance.sqrt()
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,374 @@
+//! Trending versus ranging markets
+//!
+//! Two classifiers:
+//!
+//! - **ADX**: Wilder's average directional index measures trend strength
+//!   regardless of direction. High readings mean a trend, low readings a
+//!   range.
+//! - **Hurst exponent**: from rescaled-range analysis of returns. Above 0.5
+//!   returns are persistent (trending), below 0.5 they are anti-persistent
+//!   (mean reverting).
+//!
+//! Both use two thresholds with a dead band between them: a reading inside
+//! the band keeps the previous label, which stops the regime flickering
+//! around a single cut-off.
+
+use super::{log_returns, MarketRegime, RegimeDetector};
+use crate::{OhlcvData, TradeError};
+
+/// Wilder's average directional index (0-100) at every bar, `None` for the
+/// first `2 * period - 1` bars
+pub fn adx(bars: &[OhlcvData], period: usize) -> Vec<Option<f64>> {
+    let mut values = vec![None; bars.len()];
+    if period == 0 || bars.len() < 2 * period {
+        return values;
+    }
+    let p = period as f64;
+
+    let mut smoothed_tr = 0.0;
+    let mut smoothed_plus = 0.0;
+    let mut smoothed_minus = 0.0;
+    let mut dx_sum = 0.0;
+    let mut average: Option<f64> = None;
+
+    for i in 1..bars.len() {
+        let (bar, previous) = (&bars[i], &bars[i - 1]);
+        let true_range = (bar.high - bar.low)
+            .max((bar.high - previous.close).abs())
+            .max((bar.low - previous.close).abs());
+        let up = bar.high - previous.high;
+        let down = previous.low - bar.low;
+        let plus_dm = if up > down && up > 0.0 { up } else { 0.0 };
+        let minus_dm = if down > up && down > 0.0 { down } else { 0.0 };
+
+        if i <= period {
+            smoothed_tr += true_range;
+            smoothed_plus += plus_dm;
+            smoothed_minus += minus_dm;
+            if i < period {
+                continue;
+            }
+        } else {
+            smoothed_tr += true_range - smoothed_tr / p;
+            smoothed_plus += plus_dm - smoothed_plus / p;
+            smoothed_minus += minus_dm - smoothed_minus / p;
+        }
+
+        let (plus_di, minus_di) = if smoothed_tr > 0.0 {
+            (
+                100.0 * smoothed_plus / smoothed_tr,
+                100.0 * smoothed_minus / smoothed_tr,
+            )
+        } else {
+            (0.0, 0.0)
+        };
+        let di_sum = plus_di + minus_di;
+        let dx = if di_sum > 0.0 {
+            100.0 * (plus_di - minus_di).abs() / di_sum
+        } else {
+            0.0
+        };
+
+        // The first ADX averages `period` DX values
+        average = match average {
+            Some(previous) => Some((previous * (p - 1.0) + dx) / p),
+            None => {
+                dx_sum += dx;
+                (i == 2 * period - 1).then(|| dx_sum / p)
+            }
+        };
+        values[i] = average;
+    }
+    values
+}
+
+/// Hurst exponent of a return series by rescaled-range analysis
+///
+/// Uses block sizes 8, 16, 32, ... up to half the series, and the
+/// Anis-Lloyd-Peters expected R/S so that white noise scores close to 0.5.
+/// Returns `None` for fewer than 32 returns or a constant series.
+pub fn hurst_exponent(returns: &[f64]) -> Option<f64> {
+    let mut points = Vec::new();
+    let mut size = 8;
+    while size <= returns.len() / 2 {
+        let ratios: Vec<f64> = returns
+            .chunks_exact(size)
+            .filter_map(rescaled_range)
+            .collect();
+        if !ratios.is_empty() {
+            let observed = ratios.iter().sum::<f64>() / ratios.len() as f64;
+            let n = size as f64;
+            points.push((n.ln(), observed.ln() - expected_rescaled_range(size).ln()));
+        }
+        size *= 2;
+    }
+    if points.len() < 2 {
+        return None;
+    }
+
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / points.len() as f64;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / points.len() as f64;
+    let covariance: f64 = points
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    let variance: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    Some(0.5 + covariance / variance)
+}
+
+/// Range of cumulative deviations over the standard deviation
+fn rescaled_range(block: &[f64]) -> Option<f64> {
+    let n = block.len() as f64;
+    let mean = block.iter().sum::<f64>() / n;
+    let std_dev = (block.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n).sqrt();
+    if std_dev == 0.0 {
+        return None;
+    }
+    let mut cumulative = 0.0;
+    let (mut low, mut high) = (0.0_f64, 0.0_f64);
+    for v in block {
+        cumulative += v - mean;
+        low = low.min(cumulative);
+        high = high.max(cumulative);
+    }
+    Some((high - low) / std_dev)
+}
+
+/// Expected R/S of white noise (Anis-Lloyd with Peters' correction, using
+/// the large-sample form of the gamma-function ratio)
+fn expected_rescaled_range(size: usize) -> f64 {
+    let n = size as f64;
+    let sum: f64 = (1..size).map(|i| ((n - i as f64) / i as f64).sqrt()).sum();
+    (n - 0.5) / n / (n * std::f64::consts::PI / 2.0).sqrt() * sum
+}
+
+/// Apply a dead band: above `high` is trending, below `low` ranging, and in
+/// between the previous label
+fn classify(values: &[Option<f64>], low: f64, high: f64) -> Vec<MarketRegime> {
+    let mut current = MarketRegime::Unknown;
+    values
+        .iter()
+        .map(|value| {
+            if let Some(v) = value {
+                if *v >= high {
+                    current = MarketRegime::Trending;
+                } else if *v <= low || current == MarketRegime::Unknown {
+                    current = MarketRegime::Ranging;
+                }
+            }
+            if value.is_some() {
+                current
+            } else {
+                MarketRegime::Unknown
+            }
+        })
+        .collect()
+}
+
+/// Trending or ranging from the ADX
+#[derive(Debug, Clone)]
+pub struct AdxRegimeDetector {
+    period: usize,
+    trending_threshold: f64,
+    ranging_threshold: f64,
+}
+
+impl Default for AdxRegimeDetector {
+    /// ADX(14), trending from 25 and ranging below 20
+    fn default() -> Self {
+        Self {
+            period: 14,
+            trending_threshold: 25.0,
+            ranging_threshold: 20.0,
+        }
+    }
+}
+
+impl AdxRegimeDetector {
+    /// Create a detector with an ADX period and thresholds
+    pub fn new(
+        period: usize,
+        ranging_threshold: f64,
+        trending_threshold: f64,
+    ) -> Result<Self, String> {
+        if period < 2 {
+            return Err("ADX period must be at least 2".to_string());
+        }
+        if !(0.0 <= ranging_threshold
+            && ranging_threshold <= trending_threshold
+            && trending_threshold <= 100.0)
+        {
+            return Err("Thresholds must satisfy 0 <= ranging <= trending <= 100".to_string());
+        }
+        Ok(Self {
+            period,
+            trending_threshold,
+            ranging_threshold,
+        })
+    }
+}
+
+impl RegimeDetector for AdxRegimeDetector {
+    fn detect(&self, bars: &[OhlcvData]) -> Result<Vec<MarketRegime>, TradeError> {
+        Ok(classify(
+            &adx(bars, self.period),
+            self.ranging_threshold,
+            self.trending_threshold,
+        ))
+    }
+
+    fn name(&self) -> &str {
+        "ADX Regime Detector"
+    }
+}
+
+/// Trending or ranging from a rolling Hurst exponent of returns
+#[derive(Debug, Clone)]
+pub struct HurstRegimeDetector {
+    window: usize,
+    trending_threshold: f64,
+    ranging_threshold: f64,
+}
+
+impl Default for HurstRegimeDetector {
+    /// 128 returns, trending from 0.55 and ranging below 0.45
+    fn default() -> Self {
+        Self {
+            window: 128,
+            trending_threshold: 0.55,
+            ranging_threshold: 0.45,
+        }
+    }
+}
+
+impl HurstRegimeDetector {
+    /// Create a detector over `window` returns with thresholds
+    pub fn new(
+        window: usize,
+        ranging_threshold: f64,
+        trending_threshold: f64,
+    ) -> Result<Self, String> {
+        if window < 32 {
+            return Err("Hurst window must be at least 32 returns".to_string());
+        }
+        if ranging_threshold > trending_threshold {
+            return Err("Ranging threshold must not exceed the trending threshold".to_string());
+        }
+        Ok(Self {
+            window,
+            trending_threshold,
+            ranging_threshold,
+        })
+    }
+}
+
+impl RegimeDetector for HurstRegimeDetector {
+    fn detect(&self, bars: &[OhlcvData]) -> Result<Vec<MarketRegime>, TradeError> {
+        let returns = log_returns(bars)?;
+        let mut values = vec![None; bars.len()];
+        for end in self.window..=returns.len() {
+            // Returns up to index end - 1 end at bar `end`
+            values[end] = hurst_exponent(&returns[end - self.window..end]);
+        }
+        Ok(classify(
+            &values,
+            self.ranging_threshold,
+            self.trending_threshold,
+        ))
+    }
+
+    fn name(&self) -> &str {
+        "Hurst Regime Detector"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::{MarketSimulator, PriceProcess};
+
+    fn noise(days: usize, seed: u64) -> Vec<f64> {
+        MarketSimulator::new(PriceProcess::gbm(0.0, 0.2), 100.0)
+            .unwrap()
+            .with_seed(seed)
+            .daily_returns(days)
+    }
+
+    /// Bars from a path of closes, with a fixed intrabar range
+    fn bars_from(closes: &[f64]) -> Vec<OhlcvData> {
+        closes
+            .iter()
+            .map(|&c| OhlcvData {
+                open: c,
+                high: c * 1.005,
+                low: c * 0.995,
+                close: c,
+                volume: 1000,
+            })
+            .collect()
+    }
+
+    fn ar_returns(phi: f64, days: usize, seed: u64) -> Vec<f64> {
+        let mut previous = 0.0;
+        noise(days, seed)
+            .into_iter()
+            .map(|e| {
+                previous = phi * previous + e;
+                previous
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_hurst_exponent() {
+        let white = hurst_exponent(&noise(2048, 1)).unwrap();
+        let persistent = hurst_exponent(&ar_returns(0.6, 2048, 2)).unwrap();
+        let reverting = hurst_exponent(&ar_returns(-0.6, 2048, 3)).unwrap();
+
+        assert!((white - 0.5).abs() < 0.08, "white noise {}", white);
+        assert!(persistent > 0.55, "persistent {}", persistent);
+        assert!(reverting < 0.45, "anti-persistent {}", reverting);
+        assert!(hurst_exponent(&[0.01; 20]).is_none());
+    }
+
+    #[test]
+    fn test_adx_separates_trend_from_range() {
+        let trend: Vec<f64> = (0..100).map(|i| 100.0 * 1.01_f64.powi(i)).collect();
+        let range: Vec<f64> = (0..100)
+            .map(|i| 100.0 + 2.0 * (i as f64 * 0.8).sin())
+            .collect();
+
+        let trending = adx(&bars_from(&trend), 14);
+        assert!(trending[..27].iter().all(Option::is_none));
+        assert!(trending[99].unwrap() > 50.0);
+        assert!(adx(&bars_from(&range), 14)[99].unwrap() < 20.0);
+
+        let detector = AdxRegimeDetector::default();
+        let mut closes = range.clone();
+        let last = *closes.last().unwrap();
+        closes.extend((1..60).map(|i| last * 1.01_f64.powi(i)));
+        let regimes = detector.detect(&bars_from(&closes)).unwrap();
+        assert_eq!(regimes[10], MarketRegime::Unknown);
+        assert_eq!(regimes[90], MarketRegime::Ranging);
+        assert_eq!(regimes[158], MarketRegime::Trending);
+    }
+
+    #[test]
+    fn test_hurst_detector_and_validation() {
+        let mut price = 100.0;
+        let closes: Vec<f64> = std::iter::once(price)
+            .chain(ar_returns(0.7, 400, 4).into_iter().map(|r| {
+                price *= r.exp();
+                price
+            }))
+            .collect();
+        let regimes = HurstRegimeDetector::default()
+            .detect(&bars_from(&closes))
+            .unwrap();
+        assert!(regimes[..128].iter().all(|r| *r == MarketRegime::Unknown));
+        assert_eq!(regimes[400], MarketRegime::Trending);
+
+        assert!(HurstRegimeDetector::new(16, 0.45, 0.55).is_err());
+        assert!(AdxRegimeDetector::new(14, 30.0, 20.0).is_err());
+    }
+}
@@ -0,0 +1,166 @@
+//! Volatility-quantile regimes
+//!
+//! Rolling volatility is ranked against its own trailing history: below the
+//! low quantile is low volatility, above the high quantile is high
+//! volatility, and anything in between is normal.
+
+use super::{log_returns, rolling_volatility, MarketRegime, RegimeDetector};
+use crate::{OhlcvData, TradeError};
+
+/// Regimes from rolling volatility quantiles
+#[derive(Debug, Clone)]
+pub struct VolatilityRegimeDetector {
+    window: usize,
+    lookback: usize,
+    min_history: usize,
+    low_quantile: f64,
+    high_quantile: f64,
+}
+
+impl Default for VolatilityRegimeDetector {
+    /// 20-bar volatility against the last 250 values, split into terciles
+    fn default() -> Self {
+        Self {
+            window: 20,
+            lookback: 250,
+            min_history: 60,
+            low_quantile: 1.0 / 3.0,
+            high_quantile: 2.0 / 3.0,
+        }
+    }
+}
+
+impl VolatilityRegimeDetector {
+    /// Create a detector measuring volatility over `window` bars and ranking
+    /// it against the last `lookback` volatility values
+    pub fn new(window: usize, lookback: usize) -> Result<Self, String> {
+        if window < 2 {
+            return Err("Volatility window must be at least 2".to_string());
+        }
+        if lookback < 10 {
+            return Err("Lookback must be at least 10".to_string());
+        }
+        Ok(Self {
+            window,
+            lookback,
+            min_history: lookback.min(Self::default().min_history),
+            ..Self::default()
+        })
+    }
+
+    /// Set the quantiles separating low, normal and high volatility
+    pub fn with_quantiles(mut self, low: f64, high: f64) -> Result<Self, String> {
+        if !(0.0 < low && low < high && high < 1.0) {
+            return Err("Quantiles must satisfy 0 < low < high < 1".to_string());
+        }
+        self.low_quantile = low;
+        self.high_quantile = high;
+        Ok(self)
+    }
+
+    /// Set how many volatility values are needed before labelling bars
+    pub fn with_min_history(mut self, min_history: usize) -> Result<Self, String> {
+        if min_history < 2 || min_history > self.lookback {
+            return Err("Minimum history must be between 2 and the lookback".to_string());
+        }
+        self.min_history = min_history;
+        Ok(self)
+    }
+}
+
+impl RegimeDetector for VolatilityRegimeDetector {
+    fn detect(&self, bars: &[OhlcvData]) -> Result<Vec<MarketRegime>, TradeError> {
+        let returns = log_returns(bars)?;
+        let volatility = rolling_volatility(&returns, self.window);
+
+        let mut regimes = vec![MarketRegime::Unknown; bars.len()];
+        let mut history: Vec<f64> = Vec::new();
+        for (i, vol) in volatility.into_iter().enumerate() {
+            let Some(vol) = vol else { continue };
+            history.push(vol);
+            if history.len() < self.min_history {
+                continue;
+            }
+
+            let start = history.len().saturating_sub(self.lookback);
+            let mut recent = history[start..].to_vec();
+            recent.sort_by(f64::total_cmp);
+            let low = quantile(&recent, self.low_quantile);
+            let high = quantile(&recent, self.high_quantile);
+
+            // Return i ends at bar i + 1
+            regimes[i + 1] = if vol < low {
+                MarketRegime::LowVolatility
+            } else if vol > high {
+                MarketRegime::HighVolatility
+            } else {
+                MarketRegime::NormalVolatility
+            };
+        }
+        Ok(regimes)
+    }
+
+    fn name(&self) -> &str {
+        "Volatility Quantile Regime Detector"
+    }
+}
+
+/// Linearly interpolated quantile of sorted values
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let position = q * (sorted.len() - 1) as f64;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (position - lower as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::{MarketSimulator, PriceProcess};
+
+    fn bars(volatility: f64, days: usize, seed: u64) -> Vec<OhlcvData> {
+        MarketSimulator::new(PriceProcess::gbm(0.0, volatility), 100.0)
+            .unwrap()
+            .with_seed(seed)
+            .generate_daily(days)
+            .into_iter()
+            .map(|d| d.data)
+            .collect()
+    }
+
+    #[test]
+    fn test_volatility_spike_is_high_regime() {
+        // Calm year followed by a turbulent quarter, rescaled to join up
+        let mut data = bars(0.1, 250, 1);
+        let last = data.last().unwrap().close;
+        let stormy = bars(0.6, 60, 2);
+        let scale = last / stormy[0].close;
+        data.extend(stormy.into_iter().skip(1).map(|mut b| {
+            b.open *= scale;
+            b.high *= scale;
+            b.low *= scale;
+            b.close *= scale;
+            b
+        }));
+
+        let regimes = VolatilityRegimeDetector::default().detect(&data).unwrap();
+        assert_eq!(regimes.len(), data.len());
+        assert!(regimes[..79].iter().all(|r| *r == MarketRegime::Unknown));
+        assert!(regimes[280..]
+            .iter()
+            .all(|r| *r == MarketRegime::HighVolatility));
+        assert!(regimes[100..250].contains(&MarketRegime::LowVolatility));
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(VolatilityRegimeDetector::new(1, 100).is_err());
+        assert!(VolatilityRegimeDetector::default()
+            .with_quantiles(0.7, 0.3)
+            .is_err());
+        assert!(VolatilityRegimeDetector::default()
+            .with_min_history(1000)
+            .is_err());
+        assert_eq!(quantile(&[1.0, 2.0, 3.0, 4.0], 0.5), 2.5);
+    }
+}
@@ -0,0 +1,280 @@
+//! Risk overlays that add protective exits to any strategy
+//!
+//! A [`RiskOverlay`] wraps a strategy and replaces Hold signals with Sell
+//! when an open position hits a stop-loss, take-profit or trailing stop, or
+//! has been held for too long. Positions are tracked the way
+//! `ledger::Ledger` executes signals: a Buy opens a position at the next
+//! bar's open and exits are checked against each following close.
+//!
+//! # Example
+//!
+//! ```
+//! use day_trade::risk::RiskOverlay;
+//! use day_trade::utils::generate_test_data;
+//! use day_trade::{MacdStrategy, TradingStrategy};
+//!
+//! let strategy = RiskOverlay::new(MacdStrategy::default())
+//!     .with_stop_loss(0.05)
+//!     .unwrap()
+//!     .with_trailing_stop(0.08)
+//!     .unwrap();
+//!
+//! let data = generate_test_data(200, 100.0, 0.02);
+//! let signals = strategy.generate_signals(&data).unwrap();
+//! assert_eq!(signals.len(), data.len());
+//! ```
+
+use crate::{
+    DailyOhlcv, IntradayTradingStrategy, MinuteOhlcv, OhlcvData, Signal, TradeError,
+    TradingStrategy,
+};
+
+/// Protective exits layered over a strategy
+///
+/// Limits are fractions of the entry price: a stop-loss of 0.05 sells once
+/// a close is 5% below the entry. After a forced exit the overlay waits for
+/// the inner strategy to stop signalling Buy before it lets a new Buy
+/// through, so a persistent Buy signal does not re-enter immediately.
+#[derive(Debug, Clone)]
+pub struct RiskOverlay<S> {
+    strategy: S,
+    stop_loss: Option<f64>,
+    take_profit: Option<f64>,
+    trailing_stop: Option<f64>,
+    max_holding_bars: Option<usize>,
+}
+
+fn validate_fraction(name: &str, value: f64) -> Result<f64, String> {
+    if !value.is_finite() || value <= 0.0 || value >= 1.0 {
+        return Err(format!("{} must be between 0 and 1 exclusive", name));
+    }
+    Ok(value)
+}
+
+impl<S> RiskOverlay<S> {
+    /// Wrap `strategy` without any limits
+    pub fn new(strategy: S) -> Self {
+        Self {
+            strategy,
+            stop_loss: None,
+            take_profit: None,
+            trailing_stop: None,
+            max_holding_bars: None,
+        }
+    }
+
+    /// Sell when a close falls `fraction` below the entry price
+    pub fn with_stop_loss(mut self, fraction: f64) -> Result<Self, String> {
+        self.stop_loss = Some(validate_fraction("Stop-loss", fraction)?);
+        Ok(self)
+    }
+
+    /// Sell when a close rises `fraction` above the entry price
+    pub fn with_take_profit(mut self, fraction: f64) -> Result<Self, String> {
+        if !fraction.is_finite() || fraction <= 0.0 {
+            return Err("Take-profit must be positive".to_string());
+        }
+        self.take_profit = Some(fraction);
+        Ok(self)
+    }
+
+    /// Sell when a close falls `fraction` below the highest close since entry
+    pub fn with_trailing_stop(mut self, fraction: f64) -> Result<Self, String> {
+        self.trailing_stop = Some(validate_fraction("Trailing stop", fraction)?);
+        Ok(self)
+    }
+
+    /// Sell once a position has been held for `bars` bars
+    pub fn with_max_holding(mut self, bars: usize) -> Result<Self, String> {
+        if bars == 0 {
+            return Err("Maximum holding period must be at least one bar".to_string());
+        }
+        self.max_holding_bars = Some(bars);
+        Ok(self)
+    }
+
+    /// The wrapped strategy
+    pub fn inner(&self) -> &S {
+        &self.strategy
+    }
+
+    /// Whether any limit is set
+    pub fn has_limits(&self) -> bool {
+        self.stop_loss.is_some()
+            || self.take_profit.is_some()
+            || self.trailing_stop.is_some()
+            || self.max_holding_bars.is_some()
+    }
+
+    /// Apply the limits to the inner strategy's signals
+    fn apply(&self, bars: &[&OhlcvData], signals: Vec<Signal>) -> Vec<Signal> {
+        // (entry index, entry price, highest close) of the open position;
+        // the entry is the bar after the Buy signal
+        let mut position: Option<(usize, f64, f64)> = None;
+        let mut pending_entry = false;
+        let mut waiting_for_reset = false;
+        let mut output = Vec::with_capacity(signals.len());
+
+        for (i, signal) in signals.into_iter().enumerate() {
+            if pending_entry {
+                let open = bars[i].open;
+                position = Some((i, open, open));
+                pending_entry = false;
+            }
+
+            if waiting_for_reset {
+                if signal == Signal::Buy {
+                    output.push(Signal::Hold);
+                    continue;
+                }
+                waiting_for_reset = false;
+            }
+
+            let signal = match position.as_mut() {
+                Some((entry_index, entry, highest)) => {
+                    let close = bars[i].close;
+                    *highest = highest.max(close);
+                    let stopped = self.stop_loss.is_some_and(|f| close <= *entry * (1.0 - f))
+                        || self
+                            .take_profit
+                            .is_some_and(|f| close >= *entry * (1.0 + f))
+                        || self
+                            .trailing_stop
+                            .is_some_and(|f| close <= *highest * (1.0 - f))
+                        || self
+                            .max_holding_bars
+                            .is_some_and(|n| i - *entry_index + 1 >= n);
+                    if stopped && signal != Signal::Sell {
+                        waiting_for_reset = true;
+                        Signal::Sell
+                    } else {
+                        signal
+                    }
+                }
+                None => signal,
+            };
+
+            match signal {
+                Signal::Buy if position.is_none() => pending_entry = true,
+                Signal::Sell => {
+                    position = None;
+                    pending_entry = false;
+                }
+                _ => {}
+            }
+            output.push(signal);
+        }
+        output
+    }
+}
+
+impl<S: TradingStrategy> TradingStrategy for RiskOverlay<S> {
+    fn generate_signals(&self, data: &[DailyOhlcv]) -> Result<Vec<Signal>, TradeError> {
+        let signals = self.strategy.generate_signals(data)?;
+        let bars: Vec<&OhlcvData> = data.iter().map(|d| &d.data).collect();
+        Ok(self.apply(&bars, signals))
+    }
+
+    fn calculate_performance(
+        &self,
+        data: &[DailyOhlcv],
+        signals: &[Signal],
+    ) -> Result<f64, TradeError> {
+        self.strategy.calculate_performance(data, signals)
+    }
+}
+
+impl<S: IntradayTradingStrategy> IntradayTradingStrategy for RiskOverlay<S> {
+    fn generate_signals(&self, data: &[MinuteOhlcv]) -> Result<Vec<Signal>, TradeError> {
+        let signals = self.strategy.generate_signals(data)?;
+        let bars: Vec<&OhlcvData> = data.iter().map(|d| &d.data).collect();
+        Ok(self.apply(&bars, signals))
+    }
+
+    fn calculate_performance(
+        &self,
+        data: &[MinuteOhlcv],
+        signals: &[Signal],
+    ) -> Result<f64, TradeError> {
+        self.strategy.calculate_performance(data, signals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::calculate_basic_performance;
+    use chrono::NaiveDate;
+
+    /// Replays fixed signals
+    struct Fixed(Vec<Signal>);
+
+    impl TradingStrategy for Fixed {
+        fn generate_signals(&self, _data: &[DailyOhlcv]) -> Result<Vec<Signal>, TradeError> {
+            Ok(self.0.clone())
+        }
+
+        fn calculate_performance(
+            &self,
+            data: &[DailyOhlcv],
+            signals: &[Signal],
+        ) -> Result<f64, TradeError> {
+            calculate_basic_performance(data, signals, 10000.0)
+        }
+    }
+
+    fn bars(closes: &[f64]) -> Vec<DailyOhlcv> {
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, &close)| DailyOhlcv {
+                date: start + chrono::Duration::days(i as i64),
+                data: OhlcvData {
+                    open: close,
+                    high: close,
+                    low: close,
+                    close,
+                    volume: 1000,
+                },
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_stop_loss_and_reset() {
+        use Signal::*;
+        let data = bars(&[100.0, 100.0, 98.0, 94.0, 93.0, 95.0, 96.0, 97.0]);
+        let inner = Fixed(vec![Buy, Buy, Buy, Buy, Buy, Hold, Buy, Hold]);
+        let overlay = RiskOverlay::new(inner).with_stop_loss(0.05).unwrap();
+
+        // Entry at bar 1's open (100); bar 3 closes at 94, 6% below
+        let signals = overlay.generate_signals(&data).unwrap();
+        assert_eq!(signals, vec![Buy, Buy, Buy, Sell, Hold, Hold, Buy, Hold]);
+    }
+
+    #[test]
+    fn test_trailing_take_profit_and_holding_limits() {
+        use Signal::*;
+        let data = bars(&[100.0, 100.0, 110.0, 120.0, 113.0, 112.0]);
+        let inner = || Fixed(vec![Buy, Hold, Hold, Hold, Hold, Hold]);
+
+        let trailing = RiskOverlay::new(inner()).with_trailing_stop(0.05).unwrap();
+        assert_eq!(trailing.generate_signals(&data).unwrap()[4], Sell);
+
+        let target = RiskOverlay::new(inner()).with_take_profit(0.15).unwrap();
+        let signals = target.generate_signals(&data).unwrap();
+        assert_eq!(signals.iter().position(|s| *s == Sell), Some(3));
+
+        let holding = RiskOverlay::new(inner()).with_max_holding(2).unwrap();
+        let signals = holding.generate_signals(&data).unwrap();
+        assert_eq!(signals.iter().position(|s| *s == Sell), Some(2));
+
+        let plain = RiskOverlay::new(inner());
+        assert!(!plain.has_limits());
+        assert!(!plain.generate_signals(&data).unwrap().contains(&Sell));
+
+        assert!(RiskOverlay::new(inner()).with_stop_loss(1.5).is_err());
+        assert!(RiskOverlay::new(inner()).with_max_holding(0).is_err());
+    }
+}
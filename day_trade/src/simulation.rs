@@ -0,0 +1,774 @@
+//! Seeded synthetic market simulator
+//!
+//! `utils::generate_test_data` draws a plain random walk from this simulator,
+//! which is fine for smoke tests but has none of the features strategies are
+//! meant to exploit or survive: fat tails, volatility clustering, trends that
+//! come and go, mean reversion. The simulator generates daily and minute OHLCV series
+//! from a chosen price process, dated on weekdays only, and is fully
+//! reproducible from its seed.
+//!
+//! Annualized parameters assume 252 trading days per year. GARCH parameters
+//! are in daily return units, as they would be when fitted to daily data.
+//!
+//! # Example
+//!
+//! ```
+//! use day_trade::simulation::{MarketSimulator, PriceProcess};
+//!
+//! let simulator = MarketSimulator::new(PriceProcess::gbm(0.08, 0.2), 100.0)
+//!     .unwrap()
+//!     .with_seed(7);
+//!
+//! let daily = simulator.generate_daily(250);
+//! let minutes = simulator.generate_minute(5);
+//!
+//! assert_eq!(daily.len(), 250);
+//! assert_eq!(minutes.len(), 5 * 390);
+//! // Same seed, same series
+//! assert_eq!(daily[249].data.close, simulator.generate_daily(250)[249].data.close);
+//! ```
+
+use crate::utils::{seeded_rng, DEFAULT_SEED};
+use crate::{DailyOhlcv, MinuteOhlcv, OhlcvData};
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Weekday};
+use rand::rngs::StdRng;
+use rand::Rng;
+
+/// Trading days per year used to convert annualized parameters
+pub const TRADING_DAYS_PER_YEAR: f64 = 252.0;
+
+/// One state of a regime-switching process
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Regime {
+    /// Annualized drift of log returns
+    pub drift: f64,
+    /// Annualized volatility of log returns
+    pub volatility: f64,
+}
+
+/// Stochastic process driving the simulated close prices
+#[derive(Debug, Clone, PartialEq)]
+pub enum PriceProcess {
+    /// Geometric Brownian motion
+    Gbm {
+        /// Annualized drift
+        drift: f64,
+        /// Annualized volatility
+        volatility: f64,
+    },
+    /// Merton jump-diffusion: GBM plus Poisson-arriving normal log jumps
+    JumpDiffusion {
+        /// Annualized drift of the diffusion part
+        drift: f64,
+        /// Annualized volatility of the diffusion part
+        volatility: f64,
+        /// Expected number of jumps per year
+        jump_intensity: f64,
+        /// Mean log jump size
+        jump_mean: f64,
+        /// Standard deviation of the log jump size
+        jump_std: f64,
+    },
+    /// GARCH(1,1) volatility clustering on daily log returns
+    Garch {
+        /// Daily drift
+        drift: f64,
+        /// Constant term of the variance equation
+        omega: f64,
+        /// Weight of the previous squared shock
+        alpha: f64,
+        /// Weight of the previous variance
+        beta: f64,
+    },
+    /// Markov chain switching between regimes at the end of each day
+    RegimeSwitching {
+        /// Regimes the chain moves between; the first is the starting regime
+        regimes: Vec<Regime>,
+        /// Daily transition probabilities, `transition[i][j]` = P(i -> j)
+        transition: Vec<Vec<f64>>,
+    },
+    /// Ornstein-Uhlenbeck process on the log price
+    OrnsteinUhlenbeck {
+        /// Long-run mean price
+        mean: f64,
+        /// Annualized speed of reversion towards the mean
+        reversion_speed: f64,
+        /// Annualized volatility
+        volatility: f64,
+    },
+}
+
+impl PriceProcess {
+    /// Geometric Brownian motion with annualized drift and volatility
+    pub fn gbm(drift: f64, volatility: f64) -> Self {
+        Self::Gbm { drift, volatility }
+    }
+
+    /// Merton jump-diffusion
+    pub fn jump_diffusion(
+        drift: f64,
+        volatility: f64,
+        jump_intensity: f64,
+        jump_mean: f64,
+        jump_std: f64,
+    ) -> Self {
+        Self::JumpDiffusion {
+            drift,
+            volatility,
+            jump_intensity,
+            jump_mean,
+            jump_std,
+        }
+    }
+
+    /// GARCH(1,1) on daily log returns
+    pub fn garch(drift: f64, omega: f64, alpha: f64, beta: f64) -> Self {
+        Self::Garch {
+            drift,
+            omega,
+            alpha,
+            beta,
+        }
+    }
+
+    /// Two-state bull/bear regime switching with a given daily switch probability
+    pub fn bull_bear(bull: Regime, bear: Regime, switch_probability: f64) -> Self {
+        let stay = 1.0 - switch_probability;
+        Self::RegimeSwitching {
+            regimes: vec![bull, bear],
+            transition: vec![
+                vec![stay, switch_probability],
+                vec![switch_probability, stay],
+            ],
+        }
+    }
+
+    /// Ornstein-Uhlenbeck process on the log price
+    pub fn ornstein_uhlenbeck(mean: f64, reversion_speed: f64, volatility: f64) -> Self {
+        Self::OrnsteinUhlenbeck {
+            mean,
+            reversion_speed,
+            volatility,
+        }
+    }
+
+    /// Check that the process parameters are usable
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Self::Gbm { volatility, .. } => non_negative(*volatility, "Volatility"),
+            Self::JumpDiffusion {
+                volatility,
+                jump_intensity,
+                jump_std,
+                ..
+            } => {
+                non_negative(*volatility, "Volatility")?;
+                non_negative(*jump_intensity, "Jump intensity")?;
+                non_negative(*jump_std, "Jump standard deviation")
+            }
+            Self::Garch {
+                omega, alpha, beta, ..
+            } => {
+                if *omega <= 0.0 {
+                    return Err("GARCH omega must be positive".to_string());
+                }
+                non_negative(*alpha, "GARCH alpha")?;
+                non_negative(*beta, "GARCH beta")?;
+                if alpha + beta >= 1.0 {
+                    return Err("GARCH alpha + beta must be less than 1".to_string());
+                }
+                Ok(())
+            }
+            Self::RegimeSwitching {
+                regimes,
+                transition,
+            } => {
+                if regimes.is_empty() {
+                    return Err("At least one regime is required".to_string());
+                }
+                for regime in regimes {
+                    non_negative(regime.volatility, "Regime volatility")?;
+                }
+                if transition.len() != regimes.len()
+                    || transition.iter().any(|row| row.len() != regimes.len())
+                {
+                    return Err(
+                        "Transition matrix must be square with one row per regime".to_string()
+                    );
+                }
+                for row in transition {
+                    if row.iter().any(|p| *p < 0.0) || (row.iter().sum::<f64>() - 1.0).abs() > 1e-9
+                    {
+                        return Err("Transition probabilities must be non-negative and sum to 1"
+                            .to_string());
+                    }
+                }
+                Ok(())
+            }
+            Self::OrnsteinUhlenbeck {
+                mean,
+                reversion_speed,
+                volatility,
+            } => {
+                if *mean <= 0.0 {
+                    return Err("Mean price must be positive".to_string());
+                }
+                non_negative(*reversion_speed, "Reversion speed")?;
+                non_negative(*volatility, "Volatility")
+            }
+        }
+    }
+
+    /// Daily volatility the process starts from
+    fn initial_daily_volatility(&self) -> f64 {
+        let dt = 1.0 / TRADING_DAYS_PER_YEAR;
+        match self {
+            Self::Gbm { volatility, .. }
+            | Self::JumpDiffusion { volatility, .. }
+            | Self::OrnsteinUhlenbeck { volatility, .. } => volatility * dt.sqrt(),
+            Self::Garch {
+                omega, alpha, beta, ..
+            } => (omega / (1.0 - alpha - beta)).sqrt(),
+            Self::RegimeSwitching { regimes, .. } => regimes[0].volatility * dt.sqrt(),
+        }
+    }
+}
+
+fn non_negative(value: f64, name: &str) -> Result<(), String> {
+    if value < 0.0 || !value.is_finite() {
+        Err(format!("{} must be non-negative", name))
+    } else {
+        Ok(())
+    }
+}
+
+/// Shape of volatility and volume over a trading session
+///
+/// Both follow a U-shape: highest at the open and close, lowest at midday.
+/// A smile of 0 gives a flat profile; a smile of 2 makes the session edges
+/// three times as active as the middle.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntradayProfile {
+    /// Time of the first bar
+    pub session_open: NaiveTime,
+    /// Number of one-minute bars per session
+    pub minutes_per_session: usize,
+    /// Extra volatility at the session edges relative to midday
+    pub volatility_smile: f64,
+    /// Extra volume at the session edges relative to midday
+    pub volume_smile: f64,
+}
+
+impl IntradayProfile {
+    /// Create a new profile
+    pub fn new(
+        session_open: NaiveTime,
+        minutes_per_session: usize,
+        volatility_smile: f64,
+        volume_smile: f64,
+    ) -> Result<Self, String> {
+        if minutes_per_session == 0 {
+            return Err("Session must contain at least one minute".to_string());
+        }
+        non_negative(volatility_smile, "Volatility smile")?;
+        non_negative(volume_smile, "Volume smile")?;
+
+        Ok(Self {
+            session_open,
+            minutes_per_session,
+            volatility_smile,
+            volume_smile,
+        })
+    }
+
+    /// Per-minute weights summing to 1 for a U-shape with the given smile
+    fn weights(&self, smile: f64) -> Vec<f64> {
+        let n = self.minutes_per_session;
+        let mid = (n as f64 - 1.0) / 2.0;
+        let raw: Vec<f64> = (0..n)
+            .map(|m| {
+                let x = if mid > 0.0 {
+                    (m as f64 - mid) / mid
+                } else {
+                    0.0
+                };
+                1.0 + smile * x * x
+            })
+            .collect();
+        let total: f64 = raw.iter().sum();
+        raw.into_iter().map(|w| w / total).collect()
+    }
+
+    /// Share of daily variance falling in each minute
+    pub fn volatility_weights(&self) -> Vec<f64> {
+        self.weights(self.volatility_smile)
+    }
+
+    /// Share of daily volume falling in each minute
+    pub fn volume_weights(&self) -> Vec<f64> {
+        self.weights(self.volume_smile)
+    }
+}
+
+impl Default for IntradayProfile {
+    /// US equity regular session, 9:30 to 16:00
+    fn default() -> Self {
+        Self {
+            session_open: NaiveTime::from_hms_opt(9, 30, 0).unwrap(),
+            minutes_per_session: 390,
+            volatility_smile: 1.5,
+            volume_smile: 2.0,
+        }
+    }
+}
+
+/// Dynamics of a single simulated day
+#[derive(Debug, Clone, Copy)]
+struct DayStep {
+    drift: f64,
+    volatility: f64,
+    jump: f64,
+}
+
+/// Mutable state of a process while a path is generated
+struct ProcessState<'a> {
+    process: &'a PriceProcess,
+    variance: f64,
+    regime: usize,
+}
+
+impl<'a> ProcessState<'a> {
+    fn new(process: &'a PriceProcess) -> Self {
+        let vol = process.initial_daily_volatility();
+        Self {
+            process,
+            variance: vol * vol,
+            regime: 0,
+        }
+    }
+
+    /// Drift, volatility and jump for the next day given the current log price
+    fn step(&mut self, log_price: f64, rng: &mut StdRng) -> DayStep {
+        let dt = 1.0 / TRADING_DAYS_PER_YEAR;
+        match self.process {
+            PriceProcess::Gbm { drift, volatility } => DayStep {
+                drift: (drift - 0.5 * volatility * volatility) * dt,
+                volatility: volatility * dt.sqrt(),
+                jump: 0.0,
+            },
+            PriceProcess::JumpDiffusion {
+                drift,
+                volatility,
+                jump_intensity,
+                jump_mean,
+                jump_std,
+            } => {
+                let jumps = poisson(rng, jump_intensity * dt);
+                let mut jump = 0.0;
+                for _ in 0..jumps {
+                    jump += jump_mean + jump_std * standard_normal(rng);
+                }
+                // Compensate the drift so `drift` remains the expected return
+                let kappa = (jump_mean + 0.5 * jump_std * jump_std).exp() - 1.0;
+                DayStep {
+                    drift: (drift - 0.5 * volatility * volatility - jump_intensity * kappa) * dt,
+                    volatility: volatility * dt.sqrt(),
+                    jump,
+                }
+            }
+            PriceProcess::Garch { drift, .. } => DayStep {
+                drift: *drift,
+                volatility: self.variance.sqrt(),
+                jump: 0.0,
+            },
+            PriceProcess::RegimeSwitching { regimes, .. } => {
+                let regime = regimes[self.regime];
+                DayStep {
+                    drift: (regime.drift - 0.5 * regime.volatility * regime.volatility) * dt,
+                    volatility: regime.volatility * dt.sqrt(),
+                    jump: 0.0,
+                }
+            }
+            PriceProcess::OrnsteinUhlenbeck {
+                mean,
+                reversion_speed,
+                volatility,
+            } => DayStep {
+                drift: reversion_speed * (mean.ln() - log_price) * dt,
+                volatility: volatility * dt.sqrt(),
+                jump: 0.0,
+            },
+        }
+    }
+
+    /// Update the state once the day's realized log return is known
+    fn advance(&mut self, step: &DayStep, log_return: f64, rng: &mut StdRng) {
+        match self.process {
+            PriceProcess::Garch {
+                omega, alpha, beta, ..
+            } => {
+                let shock = log_return - step.drift;
+                self.variance = omega + alpha * shock * shock + beta * self.variance;
+            }
+            PriceProcess::RegimeSwitching { transition, .. } => {
+                let u: f64 = rng.random();
+                let row = &transition[self.regime];
+                let mut cumulative = 0.0;
+                for (next, p) in row.iter().enumerate() {
+                    cumulative += p;
+                    if u < cumulative {
+                        self.regime = next;
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Draw a standard normal variate (Box-Muller)
+fn standard_normal(rng: &mut StdRng) -> f64 {
+    let u1: f64 = 1.0 - rng.random::<f64>(); // (0, 1] to keep ln finite
+    let u2: f64 = rng.random();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+/// Draw a Poisson variate with small mean (Knuth)
+fn poisson(rng: &mut StdRng, lambda: f64) -> usize {
+    if lambda <= 0.0 {
+        return 0;
+    }
+    let limit = (-lambda).exp();
+    let mut product: f64 = rng.random();
+    let mut count = 0;
+    while product > limit {
+        product *= rng.random::<f64>();
+        count += 1;
+    }
+    count
+}
+
+/// The `count` weekdays starting at `start` (or the first weekday after it)
+///
+/// Exchange holidays are not modeled; every Monday to Friday is a session.
+pub fn trading_days(start: NaiveDate, count: usize) -> Vec<NaiveDate> {
+    let mut days = Vec::with_capacity(count);
+    let mut date = start;
+    while days.len() < count {
+        if !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+            days.push(date);
+        }
+        date += Duration::days(1);
+    }
+    days
+}
+
+/// Generator for synthetic OHLCV series
+#[derive(Debug, Clone)]
+pub struct MarketSimulator {
+    process: PriceProcess,
+    start_price: f64,
+    start_date: NaiveDate,
+    seed: u64,
+    base_volume: f64,
+    intraday: IntradayProfile,
+}
+
+impl MarketSimulator {
+    /// Create a new simulator for `process` starting at `start_price`
+    pub fn new(process: PriceProcess, start_price: f64) -> Result<Self, String> {
+        process.validate()?;
+        if start_price <= 0.0 || !start_price.is_finite() {
+            return Err("Start price must be positive".to_string());
+        }
+
+        Ok(Self {
+            process,
+            start_price,
+            start_date: NaiveDate::from_ymd_opt(2023, 1, 2).unwrap(),
+            seed: DEFAULT_SEED,
+            base_volume: 1_000_000.0,
+            intraday: IntradayProfile::default(),
+        })
+    }
+
+    /// Use a different random seed
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Date of the first generated session
+    pub fn with_start_date(mut self, start_date: NaiveDate) -> Self {
+        self.start_date = start_date;
+        self
+    }
+
+    /// Typical daily volume
+    pub fn with_base_volume(mut self, base_volume: f64) -> Self {
+        self.base_volume = base_volume.max(0.0);
+        self
+    }
+
+    /// Intraday session shape used by `generate_minute`
+    pub fn with_intraday_profile(mut self, intraday: IntradayProfile) -> Self {
+        self.intraday = intraday;
+        self
+    }
+
+    /// Seed in use
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Price process in use
+    pub fn process(&self) -> &PriceProcess {
+        &self.process
+    }
+
+    /// Close-to-close log returns for `days` sessions
+    pub fn daily_returns(&self, days: usize) -> Vec<f64> {
+        let mut rng = seeded_rng(self.seed);
+        let mut state = ProcessState::new(&self.process);
+        let mut log_price = self.start_price.ln();
+
+        (0..days)
+            .map(|_| {
+                let step = state.step(log_price, &mut rng);
+                let r = step.drift + step.volatility * standard_normal(&mut rng) + step.jump;
+                state.advance(&step, r, &mut rng);
+                log_price += r;
+                r
+            })
+            .collect()
+    }
+
+    /// Generate `days` daily bars on consecutive trading days
+    pub fn generate_daily(&self, days: usize) -> Vec<DailyOhlcv> {
+        let mut rng = seeded_rng(self.seed);
+        let mut state = ProcessState::new(&self.process);
+        let mut prev_close = self.start_price;
+
+        trading_days(self.start_date, days)
+            .into_iter()
+            .map(|date| {
+                let step = state.step(prev_close.ln(), &mut rng);
+                let r = step.drift + step.volatility * standard_normal(&mut rng) + step.jump;
+                state.advance(&step, r, &mut rng);
+
+                // Part of the day's move happens overnight
+                let gap = 0.3 * step.volatility * standard_normal(&mut rng);
+                let open = prev_close * gap.exp();
+                let close = prev_close * r.exp();
+                let high = open.max(close)
+                    * (0.5 * step.volatility * standard_normal(&mut rng).abs()).exp();
+                let low = open.min(close)
+                    * (-0.5 * step.volatility * standard_normal(&mut rng).abs()).exp();
+                let volume = self.volume(step.volatility, r, 1.0, &mut rng);
+
+                prev_close = close;
+                DailyOhlcv {
+                    date,
+                    data: OhlcvData {
+                        open,
+                        high,
+                        low,
+                        close,
+                        volume,
+                    },
+                }
+            })
+            .collect()
+    }
+
+    /// Generate one-minute bars for `days` sessions
+    ///
+    /// Each session's variance and volume are spread over its minutes
+    /// following the intraday profile; any jump lands at a random minute.
+    pub fn generate_minute(&self, days: usize) -> Vec<MinuteOhlcv> {
+        let mut rng = seeded_rng(self.seed);
+        let mut state = ProcessState::new(&self.process);
+        let vol_weights = self.intraday.volatility_weights();
+        let volume_weights = self.intraday.volume_weights();
+        let n = self.intraday.minutes_per_session;
+        let mut prev_close = self.start_price;
+        let mut bars = Vec::with_capacity(days * n);
+
+        for date in trading_days(self.start_date, days) {
+            let step = state.step(prev_close.ln(), &mut rng);
+            let jump_minute = rng.random_range(0..n);
+            let session_start = date.and_time(self.intraday.session_open).and_utc();
+            let day_open = prev_close;
+
+            for m in 0..n {
+                let minute_vol = step.volatility * vol_weights[m].sqrt();
+                let mut r = step.drift / n as f64 + minute_vol * standard_normal(&mut rng);
+                if m == jump_minute {
+                    r += step.jump;
+                }
+
+                let open = prev_close;
+                let close = open * r.exp();
+                let high =
+                    open.max(close) * (0.5 * minute_vol * standard_normal(&mut rng).abs()).exp();
+                let low =
+                    open.min(close) * (-0.5 * minute_vol * standard_normal(&mut rng).abs()).exp();
+                let volume = self.volume(minute_vol, r, volume_weights[m], &mut rng);
+
+                bars.push(MinuteOhlcv {
+                    timestamp: session_start + Duration::minutes(m as i64),
+                    data: OhlcvData {
+                        open,
+                        high,
+                        low,
+                        close,
+                        volume,
+                    },
+                });
+                prev_close = close;
+            }
+
+            state.advance(&step, (prev_close / day_open).ln(), &mut rng);
+        }
+
+        bars
+    }
+
+    /// Volume rising with the size of the move relative to its volatility
+    fn volume(&self, volatility: f64, log_return: f64, share: f64, rng: &mut StdRng) -> u64 {
+        let surprise = if volatility > 0.0 {
+            (log_return.abs() / volatility).min(5.0)
+        } else {
+            0.0
+        };
+        let noise = (0.25 * standard_normal(rng)).exp();
+        (self.base_volume * share * (0.6 + 0.4 * surprise) * noise).round() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Timelike;
+
+    fn std_dev(values: &[f64]) -> f64 {
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64).sqrt()
+    }
+
+    #[test]
+    fn test_same_seed_reproduces_series() {
+        let sim = MarketSimulator::new(PriceProcess::gbm(0.05, 0.2), 100.0).unwrap();
+        let a = sim.clone().with_seed(1).generate_daily(50);
+        let b = sim.clone().with_seed(1).generate_daily(50);
+        let c = sim.with_seed(2).generate_daily(50);
+
+        let closes = |d: &[DailyOhlcv]| d.iter().map(|b| b.data.close).collect::<Vec<_>>();
+        assert_eq!(closes(&a), closes(&b));
+        assert_ne!(closes(&a), closes(&c));
+    }
+
+    #[test]
+    fn test_daily_dates_are_weekdays() {
+        // 2023-01-06 is a Friday
+        let sim = MarketSimulator::new(PriceProcess::gbm(0.0, 0.1), 50.0)
+            .unwrap()
+            .with_start_date(NaiveDate::from_ymd_opt(2023, 1, 6).unwrap());
+        let data = sim.generate_daily(60);
+
+        assert_eq!(data[1].date, NaiveDate::from_ymd_opt(2023, 1, 9).unwrap());
+        for pair in data.windows(2) {
+            assert!(pair[1].date > pair[0].date);
+        }
+        assert!(data
+            .iter()
+            .all(|d| !matches!(d.date.weekday(), Weekday::Sat | Weekday::Sun)));
+    }
+
+    #[test]
+    fn test_bars_are_consistent() {
+        let process = PriceProcess::jump_diffusion(0.05, 0.25, 10.0, -0.02, 0.05);
+        let sim = MarketSimulator::new(process, 100.0).unwrap();
+
+        for bar in sim.generate_daily(200) {
+            let d = bar.data;
+            assert!(d.high >= d.open.max(d.close));
+            assert!(d.low <= d.open.min(d.close));
+            assert!(d.low > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_gbm_volatility_matches_parameter() {
+        let sim = MarketSimulator::new(PriceProcess::gbm(0.0, 0.3), 100.0).unwrap();
+        let returns = sim.daily_returns(5000);
+        let annualized = std_dev(&returns) * TRADING_DAYS_PER_YEAR.sqrt();
+        assert!((annualized - 0.3).abs() < 0.02, "got {}", annualized);
+    }
+
+    #[test]
+    fn test_garch_returns_cluster() {
+        let sim = MarketSimulator::new(PriceProcess::garch(0.0, 2e-6, 0.1, 0.88), 100.0).unwrap();
+        let squared: Vec<f64> = sim.daily_returns(5000).iter().map(|r| r * r).collect();
+
+        // Squared returns should be positively autocorrelated
+        let mean = squared.iter().sum::<f64>() / squared.len() as f64;
+        let cov: f64 = squared
+            .windows(2)
+            .map(|w| (w[0] - mean) * (w[1] - mean))
+            .sum();
+        let var: f64 = squared.iter().map(|s| (s - mean).powi(2)).sum();
+        assert!(cov / var > 0.05);
+    }
+
+    #[test]
+    fn test_ou_reverts_to_mean() {
+        let process = PriceProcess::ornstein_uhlenbeck(100.0, 20.0, 0.1);
+        let sim = MarketSimulator::new(process, 150.0).unwrap();
+        let data = sim.generate_daily(250);
+        let tail: Vec<f64> = data[150..].iter().map(|d| d.data.close).collect();
+        let mean = tail.iter().sum::<f64>() / tail.len() as f64;
+        assert!((mean - 100.0).abs() < 5.0, "got {}", mean);
+    }
+
+    #[test]
+    fn test_regime_switching_validation() {
+        let calm = Regime {
+            drift: 0.1,
+            volatility: 0.1,
+        };
+        let bad = PriceProcess::RegimeSwitching {
+            regimes: vec![calm, calm],
+            transition: vec![vec![0.5, 0.4], vec![0.5, 0.5]],
+        };
+        assert!(MarketSimulator::new(bad, 100.0).is_err());
+
+        let stormy = Regime {
+            drift: -0.2,
+            volatility: 0.4,
+        };
+        let process = PriceProcess::bull_bear(calm, stormy, 0.02);
+        assert!(MarketSimulator::new(process, 100.0).is_ok());
+    }
+
+    #[test]
+    fn test_minute_sessions_follow_profile() {
+        let sim = MarketSimulator::new(PriceProcess::gbm(0.0, 0.2), 100.0).unwrap();
+        let bars = sim.generate_minute(20);
+        assert_eq!(bars.len(), 20 * 390);
+
+        let first = &bars[0].timestamp;
+        assert_eq!((first.hour(), first.minute()), (9, 30));
+        assert_eq!(bars[389].timestamp.hour(), 15);
+        assert_eq!(bars[389].timestamp.minute(), 59);
+
+        // Volume near the open is higher than at midday on average
+        let volume_at = |minute: usize| -> f64 {
+            (0..20)
+                .map(|d| bars[d * 390 + minute].data.volume as f64)
+                .sum::<f64>()
+        };
+        assert!(volume_at(0) > volume_at(195));
+    }
+}
@@ -210,11 +210,14 @@ impl RealtimeTradingStrategy for ForecastingStrategy {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::seeded_rng;
     use chrono::TimeZone;
+    use rand::Rng;
 
     #[test]
     fn test_forecasting_strategy() {
         let mut strategy = ForecastingStrategy::new().unwrap();
+        let mut rng = seeded_rng(7);
 
         // Add a series of prices with an uptrend
         let base_time = Utc.timestamp_opt(1609459200, 0).unwrap(); // 2021-01-01
@@ -222,7 +225,7 @@ mod tests {
         // Generate some test data with an uptrend
         for i in 0..25 {
             let timestamp = base_time + chrono::Duration::days(i);
-            let price = 100.0 + (i as f64 * 2.0) + (rand::random::<f64>() * 5.0 - 2.5);
+            let price = 100.0 + (i as f64 * 2.0) + (rng.random::<f64>() * 5.0 - 2.5);
 
             strategy
                 .update(timestamp, price, price + 1.0, price - 1.0, price, 1000.0)
@@ -253,7 +256,7 @@ mod tests {
 
         for i in 0..25 {
             let timestamp = base_time + chrono::Duration::days(i);
-            let price = 150.0 - (i as f64 * 2.0) + (rand::random::<f64>() * 5.0 - 2.5);
+            let price = 150.0 - (i as f64 * 2.0) + (rng.random::<f64>() * 5.0 - 2.5);
 
             strategy
                 .update(timestamp, price, price + 1.0, price - 1.0, price, 1000.0)
@@ -7,6 +7,7 @@ mod dual_timeframe;
 mod forecasting_strategy;
 mod grid_trading_strategy;
 mod multi_indicator_strategy;
+mod regime_switching;
 mod vwap;
 
 pub use bollinger_bands::BollingerBandsStrategy;
@@ -14,4 +15,5 @@ pub use dual_timeframe::DualTimeframeStrategy;
 pub use forecasting_strategy::ForecastingStrategy;
 pub use grid_trading_strategy::GridTradingStrategy;
 pub use multi_indicator_strategy::CompositeStrategy;
+pub use regime_switching::RegimeSwitchingStrategy;
 pub use vwap::VwapStrategy;
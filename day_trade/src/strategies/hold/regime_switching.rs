@@ -0,0 +1,303 @@
+//! Regime-switching meta-strategy
+//!
+//! Routes each bar to a child strategy chosen by the market regime of that
+//! bar, e.g. Bollinger mean reversion in quiet markets and volatility
+//! breakouts in turbulent ones.
+
+use crate::regime::{MarketRegime, RegimeDetector};
+use crate::utils::calculate_basic_performance;
+use crate::{
+    DailyOhlcv, IntradayTradingStrategy, MinuteOhlcv, OhlcvData, Signal, TradeError,
+    TradingStrategy,
+};
+
+/// Strategy that switches between child strategies by detected regime
+///
+/// Every child generates signals over the whole series; on each bar the
+/// signal of the child mapped to that bar's regime is used. Regimes without
+/// a child use the fallback strategy, or hold when there is none. When the
+/// active child changes while a position is open, the position is closed
+/// unless the new child is buying, so a child never inherits a position it
+/// did not open.
+///
+/// Children of different types can be combined as
+/// `RegimeSwitchingStrategy<Box<dyn IntradayTradingStrategy>>`.
+pub struct RegimeSwitchingStrategy<S> {
+    detector: Box<dyn RegimeDetector>,
+    routes: Vec<(MarketRegime, S)>,
+    fallback: Option<S>,
+    flatten_on_switch: bool,
+}
+
+impl<S> RegimeSwitchingStrategy<S> {
+    /// Create a strategy with no children, driven by `detector`
+    pub fn new(detector: impl RegimeDetector + 'static) -> Self {
+        Self {
+            detector: Box::new(detector),
+            routes: Vec::new(),
+            fallback: None,
+            flatten_on_switch: true,
+        }
+    }
+
+    /// Trade `strategy` while the market is in `regime`, replacing any
+    /// strategy already mapped to it
+    pub fn with_strategy(mut self, regime: MarketRegime, strategy: S) -> Self {
+        self.routes.retain(|(r, _)| *r != regime);
+        self.routes.push((regime, strategy));
+        self
+    }
+
+    /// Trade `strategy` in regimes without their own strategy
+    pub fn with_fallback(mut self, strategy: S) -> Self {
+        self.fallback = Some(strategy);
+        self
+    }
+
+    /// Whether to close the position when the active strategy changes
+    /// (default true)
+    pub fn with_flatten_on_switch(mut self, flatten: bool) -> Self {
+        self.flatten_on_switch = flatten;
+        self
+    }
+
+    /// Name of the regime detector
+    pub fn detector_name(&self) -> &str {
+        self.detector.name()
+    }
+
+    /// Regime of every bar
+    pub fn regimes(&self, bars: &[OhlcvData]) -> Result<Vec<MarketRegime>, TradeError> {
+        let regimes = self.detector.detect(bars)?;
+        if regimes.len() != bars.len() {
+            return Err(TradeError::CalculationError(format!(
+                "{} returned {} regimes for {} bars",
+                self.detector.name(),
+                regimes.len(),
+                bars.len()
+            )));
+        }
+        Ok(regimes)
+    }
+
+    /// Index of the child for `regime`: routes first, then the fallback
+    /// (index `routes.len()`), or `None` to stay out of the market
+    fn child_for(&self, regime: MarketRegime) -> Option<usize> {
+        self.routes
+            .iter()
+            .position(|(r, _)| *r == regime)
+            .or_else(|| self.fallback.as_ref().map(|_| self.routes.len()))
+    }
+
+    fn children(&self) -> impl Iterator<Item = &S> {
+        self.routes
+            .iter()
+            .map(|(_, s)| s)
+            .chain(self.fallback.iter())
+    }
+
+    /// Merge child signals bar by bar according to the regimes
+    fn combine(&self, regimes: &[MarketRegime], child_signals: &[Vec<Signal>]) -> Vec<Signal> {
+        let mut signals = Vec::with_capacity(regimes.len());
+        let mut active: Option<usize> = None;
+        let mut in_position = false;
+
+        for (i, regime) in regimes.iter().enumerate() {
+            let child = self.child_for(*regime);
+            let mut signal = child.map_or(Signal::Hold, |c| child_signals[c][i]);
+
+            if child != active {
+                if in_position
+                    && signal != Signal::Buy
+                    && (self.flatten_on_switch || child.is_none())
+                {
+                    signal = Signal::Sell;
+                }
+                active = child;
+            }
+
+            match signal {
+                Signal::Buy => in_position = true,
+                Signal::Sell => in_position = false,
+                Signal::Hold => {}
+            }
+            signals.push(signal);
+        }
+        signals
+    }
+}
+
+impl<S: TradingStrategy> TradingStrategy for RegimeSwitchingStrategy<S> {
+    fn generate_signals(&self, data: &[DailyOhlcv]) -> Result<Vec<Signal>, TradeError> {
+        let bars: Vec<OhlcvData> = data.iter().map(|d| d.data.clone()).collect();
+        let regimes = self.regimes(&bars)?;
+        let child_signals = self
+            .children()
+            .map(|s| s.generate_signals(data))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(self.combine(&regimes, &child_signals))
+    }
+
+    fn calculate_performance(
+        &self,
+        data: &[DailyOhlcv],
+        signals: &[Signal],
+    ) -> Result<f64, TradeError> {
+        calculate_basic_performance(data, signals, 10000.0)
+    }
+}
+
+impl<S: IntradayTradingStrategy> IntradayTradingStrategy for RegimeSwitchingStrategy<S> {
+    fn generate_signals(&self, data: &[MinuteOhlcv]) -> Result<Vec<Signal>, TradeError> {
+        let bars: Vec<OhlcvData> = data.iter().map(|d| d.data.clone()).collect();
+        let regimes = self.regimes(&bars)?;
+        let child_signals = self
+            .children()
+            .map(|s| s.generate_signals(data))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(self.combine(&regimes, &child_signals))
+    }
+
+    fn calculate_performance(
+        &self,
+        data: &[MinuteOhlcv],
+        signals: &[Signal],
+    ) -> Result<f64, TradeError> {
+        if data.len() != signals.len() {
+            return Err(TradeError::InvalidData(
+                "Data and signals count mismatch".to_string(),
+            ));
+        }
+
+        let initial_value = 1000.0;
+        let mut cash = initial_value;
+        let mut shares = 0.0;
+
+        for (bar, signal) in data.iter().zip(signals) {
+            match signal {
+                Signal::Buy if cash > 0.0 => {
+                    shares = cash / bar.data.close;
+                    cash = 0.0;
+                }
+                Signal::Sell if shares > 0.0 => {
+                    cash = shares * bar.data.close;
+                    shares = 0.0;
+                }
+                _ => {}
+            }
+        }
+
+        let final_value = cash + shares * data.last().map(|d| d.data.close).unwrap_or(0.0);
+        Ok((final_value - initial_value) / initial_value * 100.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regime::VolatilityRegimeDetector;
+    use crate::simulation::{MarketSimulator, PriceProcess};
+    use crate::BollingerBandsStrategy;
+
+    /// Detector labelling bars from a fixed list
+    struct FixedRegimes(Vec<MarketRegime>);
+
+    impl RegimeDetector for FixedRegimes {
+        fn detect(&self, _bars: &[OhlcvData]) -> Result<Vec<MarketRegime>, TradeError> {
+            Ok(self.0.clone())
+        }
+
+        fn name(&self) -> &str {
+            "Fixed"
+        }
+    }
+
+    /// Child emitting a fixed signal list
+    struct FixedSignals(Vec<Signal>);
+
+    impl TradingStrategy for FixedSignals {
+        fn generate_signals(&self, _data: &[DailyOhlcv]) -> Result<Vec<Signal>, TradeError> {
+            Ok(self.0.clone())
+        }
+
+        fn calculate_performance(
+            &self,
+            data: &[DailyOhlcv],
+            signals: &[Signal],
+        ) -> Result<f64, TradeError> {
+            calculate_basic_performance(data, signals, 10000.0)
+        }
+    }
+
+    #[test]
+    fn test_routes_signals_by_regime() {
+        use MarketRegime::{HighVolatility as High, LowVolatility as Low, Unknown};
+        use Signal::{Buy, Hold, Sell};
+
+        let data = crate::utils::generate_test_data(6, 100.0, 0.01);
+        let regimes = vec![Unknown, Low, Low, High, High, Low];
+        let calm = FixedSignals(vec![Buy, Buy, Hold, Hold, Hold, Sell]);
+        let stormy = FixedSignals(vec![Sell, Sell, Sell, Hold, Buy, Hold]);
+
+        let strategy = RegimeSwitchingStrategy::new(FixedRegimes(regimes.clone()))
+            .with_strategy(Low, calm)
+            .with_strategy(High, stormy);
+        // Unknown holds; the switch to High closes the position opened by
+        // the calm child
+        assert_eq!(
+            TradingStrategy::generate_signals(&strategy, &data).unwrap(),
+            vec![Hold, Buy, Hold, Sell, Buy, Sell]
+        );
+
+        let strategy = RegimeSwitchingStrategy::new(FixedRegimes(regimes))
+            .with_strategy(Low, FixedSignals(vec![Buy, Buy, Hold, Hold, Hold, Sell]))
+            .with_fallback(FixedSignals(vec![Hold; 6]))
+            .with_flatten_on_switch(false);
+        assert_eq!(
+            TradingStrategy::generate_signals(&strategy, &data).unwrap(),
+            vec![Hold, Buy, Hold, Hold, Hold, Sell]
+        );
+    }
+
+    #[test]
+    fn test_switches_bollinger_modes_on_volatility() {
+        let data = MarketSimulator::new(PriceProcess::gbm(0.0, 0.3), 100.0)
+            .unwrap()
+            .with_seed(5)
+            .generate_minute(2);
+        let detector = VolatilityRegimeDetector::new(30, 200).unwrap();
+
+        let breakout: Box<dyn IntradayTradingStrategy> =
+            Box::new(BollingerBandsStrategy::volatility_breakout());
+        let reversion: Box<dyn IntradayTradingStrategy> =
+            Box::new(BollingerBandsStrategy::mean_reversion());
+        let strategy = RegimeSwitchingStrategy::new(detector)
+            .with_strategy(MarketRegime::HighVolatility, breakout)
+            .with_fallback(reversion);
+        assert_eq!(
+            strategy.detector_name(),
+            "Volatility Quantile Regime Detector"
+        );
+
+        let signals = IntradayTradingStrategy::generate_signals(&strategy, &data).unwrap();
+        assert_eq!(signals.len(), data.len());
+        assert!(signals.iter().any(|s| *s != Signal::Hold));
+        // While the market stays turbulent the breakout child trades alone
+        let bars: Vec<OhlcvData> = data.iter().map(|d| d.data.clone()).collect();
+        let regimes = strategy.regimes(&bars).unwrap();
+        let breakout_signals = BollingerBandsStrategy::volatility_breakout()
+            .generate_signals(&data)
+            .unwrap();
+        let high = MarketRegime::HighVolatility;
+        assert!(regimes.contains(&high));
+        for i in 1..data.len() {
+            if regimes[i] == high && regimes[i - 1] == high {
+                assert_eq!(signals[i], breakout_signals[i]);
+            }
+        }
+
+        let performance =
+            IntradayTradingStrategy::calculate_performance(&strategy, &data, &signals).unwrap();
+        assert!(performance.is_finite());
+    }
+}
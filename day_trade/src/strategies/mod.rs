@@ -13,6 +13,6 @@ pub mod sell;
 pub use buy::{AdaptiveMovingAverageStrategy, BreakoutStrategy, MACrossover, MacdStrategy};
 pub use hold::{
     BollingerBandsStrategy, CompositeStrategy, DualTimeframeStrategy, ForecastingStrategy,
-    GridTradingStrategy, VwapStrategy,
+    GridTradingStrategy, RegimeSwitchingStrategy, VwapStrategy,
 };
 pub use sell::{MeanReversionStrategy, RsiStrategy, VolumeBasedStrategy};
@@ -3,6 +3,8 @@
 //! Contains common implementations and utilities used across multiple strategies
 
 use crate::{DailyOhlcv, Signal, TradeError};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
 /// Calculate strategy performance based on signals and data
 ///
@@ -61,12 +63,26 @@ pub fn calculate_basic_performance(
     Ok(performance)
 }
 
+/// Seed used by the generators that do not take an explicit RNG
+pub const DEFAULT_SEED: u64 = 42;
+
+/// Create a deterministic RNG from a seed
+///
+/// Every randomized API in the crate either takes one of these (or any other
+/// `Rng`) or a seed, so a run can be replayed exactly.
+pub fn seeded_rng(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
+}
+
 /// Generate dummy OHLCV data for testing purposes
 ///
+/// Uses `DEFAULT_SEED`, so repeated calls return the same series. Use
+/// `generate_test_data_with_rng` to vary or control the randomness.
+///
 /// # Arguments
 /// * `num_points` - Number of data points to generate
 /// * `starting_price` - Initial price for the first data point
-/// * `volatility` - Price volatility factor (0.0-1.0)
+/// * `volatility` - Standard deviation of daily returns, e.g. 0.02
 ///
 /// # Returns
 /// * Vector of DailyOhlcv data points
@@ -75,48 +91,48 @@ pub fn generate_test_data(
     starting_price: f64,
     volatility: f64,
 ) -> Vec<DailyOhlcv> {
-    use chrono::NaiveDate;
-    use rand::{thread_rng, Rng};
-
-    let mut rng = thread_rng();
-    let mut data = Vec::with_capacity(num_points);
-    let mut current_price = starting_price;
-
-    let base_date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
-
-    for i in 0..num_points {
-        // Create a random price movement
-        let price_change = current_price * volatility * (rng.random::<f64>() - 0.5);
-        let open = current_price;
-        let close = open + price_change;
-
-        // High and low based on open/close with some randomness
-        let high = open.max(close) + rng.random::<f64>() * volatility * open * 0.5;
-        let low = open.min(close) - rng.random::<f64>() * volatility * open * 0.5;
-
-        // Random volume between 1000 and 10000
-        let volume = rng.random_range(1000..10000);
-
-        // Create the data point
-        let date = base_date
-            .checked_add_days(chrono::Days::new(i as u64))
-            .unwrap();
-        data.push(DailyOhlcv {
-            date,
-            data: crate::OhlcvData {
-                open,
-                high,
-                low,
-                close,
-                volume,
-            },
-        });
-
-        // Update current price for next iteration
-        current_price = close;
-    }
+    generate_test_data_with_rng(
+        num_points,
+        starting_price,
+        volatility,
+        &mut seeded_rng(DEFAULT_SEED),
+    )
+}
 
-    data
+/// Generate dummy OHLCV data using the given random number generator
+///
+/// The bars come from a driftless geometric Brownian motion in
+/// [`MarketSimulator`](crate::simulation::MarketSimulator), seeded from
+/// `rng`, so they have normal returns and fall on weekdays starting on
+/// 2023-01-02. `volatility` is the standard deviation of daily returns.
+///
+/// # Panics
+///
+/// If `starting_price` is not positive.
+///
+/// # Example
+///
+/// ```
+/// use day_trade::utils::{generate_test_data_with_rng, seeded_rng};
+///
+/// let a = generate_test_data_with_rng(50, 100.0, 0.02, &mut seeded_rng(7));
+/// let b = generate_test_data_with_rng(50, 100.0, 0.02, &mut seeded_rng(7));
+/// assert_eq!(a[49].data.close, b[49].data.close);
+/// ```
+pub fn generate_test_data_with_rng<R: Rng + ?Sized>(
+    num_points: usize,
+    starting_price: f64,
+    volatility: f64,
+    rng: &mut R,
+) -> Vec<DailyOhlcv> {
+    use crate::simulation::{MarketSimulator, PriceProcess, TRADING_DAYS_PER_YEAR};
+
+    let annualized = volatility.max(0.0) * TRADING_DAYS_PER_YEAR.sqrt();
+    MarketSimulator::new(PriceProcess::gbm(0.0, annualized), starting_price)
+        .expect("Starting price must be positive")
+        .with_seed(rng.random())
+        .with_base_volume(5000.0)
+        .generate_daily(num_points)
 }
 
 /// Basic validation for strategy parameters
@@ -149,20 +165,38 @@ pub mod data_generation {
     use rand::Rng;
 
     /// Generate test data with a given trend and volatility
+    ///
+    /// Uses `DEFAULT_SEED`; see `generate_daily_data_with_rng`.
     pub fn generate_daily_data(
         days: usize,
         starting_price: f64,
         volatility: f64,
         trend: f64,
+    ) -> Vec<DailyOhlcv> {
+        generate_daily_data_with_rng(
+            days,
+            starting_price,
+            volatility,
+            trend,
+            &mut super::seeded_rng(super::DEFAULT_SEED),
+        )
+    }
+
+    /// Generate trending test data using the given random number generator
+    pub fn generate_daily_data_with_rng<R: Rng + ?Sized>(
+        days: usize,
+        starting_price: f64,
+        volatility: f64,
+        trend: f64,
+        rng: &mut R,
     ) -> Vec<DailyOhlcv> {
         let mut data = Vec::with_capacity(days);
         let mut current_price = starting_price;
 
-        // Use rng instead of thread_rng
-        use rand::thread_rng;
-        let mut rng = thread_rng();
+        let dates =
+            crate::simulation::trading_days(NaiveDate::from_ymd_opt(2023, 1, 2).unwrap(), days);
 
-        for i in 0..days {
+        for (i, date) in dates.into_iter().enumerate() {
             // Add a small random component to the price change, influenced by volatility
             let price_change = current_price * volatility * (rng.random::<f64>() - 0.5);
 
@@ -184,9 +218,6 @@ pub mod data_generation {
             // Generate a plausible volume
             let volume = rng.random_range(1000..10000);
 
-            // Create a date for this candle (just for testing)
-            let date = NaiveDate::from_ymd_opt(2023, 1, i as u32 % 28 + 1).unwrap();
-
             data.push(DailyOhlcv {
                 date,
                 data: crate::OhlcvData {
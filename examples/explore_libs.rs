@@ -19,13 +19,8 @@ fn main() {
 
 fn show_day_trade_examples() {
     // Display what day trading functionality is available
-    // You can use the commented lines once you have a version() function or similar
-
     println!("Available day trading strategies:");
-    println!("- Forecasting Strategy: Predict future prices using time series analysis");
-    println!("- Moving Average Strategy: Trade based on MA crossovers");
-    println!("- VWAP Strategy: Trade based on Volume Weighted Average Price");
-    println!("- Grid Trading Strategy: Set up a grid of buy/sell orders");
+    list_strategies("day_trade");
 
     println!("\nUsage example (see day_trade/examples for more):");
     println!(
@@ -47,12 +42,8 @@ fn show_day_trade_examples() {
 
 fn show_minute_trade_examples() {
     // Display what minute trading functionality is available
-
     println!("Available minute trading strategies:");
-    println!("- Scalping Strategy: Ultra-short term trading");
-    println!("- Momentum Breakout: Trade momentum with volume confirmation");
-    println!("- Volatility Breakout: Enter after low volatility periods");
-    println!("- Mean Reversion: Trade when prices deviate from mean");
+    list_strategies("minute_trade");
 
     println!("\nUsage example (see minute_trade/examples for more):");
     println!(
@@ -75,6 +66,19 @@ fn show_minute_trade_examples() {
     );
 }
 
+// List the registered strategies from one crate with their categories
+fn list_strategies(source: &str) {
+    let registry = nyxs_owl::strategies::StrategyRegistry::default();
+    for info in registry.iter().filter(|info| info.source() == source) {
+        println!(
+            "- {} ({}): {}",
+            info.name(),
+            info.category(),
+            info.description()
+        );
+    }
+}
+
 fn show_trade_math_examples() {
     // Display what trade math utilities are available
 
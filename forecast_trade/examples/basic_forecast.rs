@@ -1,7 +1,7 @@
 use chrono::{Datelike, Duration, TimeZone, Timelike, Utc};
 use forecast_trade::data::TimeSeriesData;
 use forecast_trade::models::exponential_smoothing::ExponentialSmoothing;
-use forecast_trade::models::{ForecastModel, TrainedForecastModel};
+use forecast_trade::models::{FittedModel, ForecastModel};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Forecast Trade: Basic Forecasting Example");
@@ -23,11 +23,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Daily model - longer smoothing (less reactive)
     let daily_model = ExponentialSmoothing::new(0.2)?;
-    let trained_daily_model = daily_model.train(&daily_data)?;
+    let trained_daily_model = daily_model.fit(&daily_data)?;
 
     // Minute model - shorter smoothing (more reactive)
     let minute_model = ExponentialSmoothing::new(0.4)?;
-    let trained_minute_model = minute_model.train(&minute_data)?;
+    let trained_minute_model = minute_model.fit(&minute_data)?;
 
     println!("Models trained successfully\n");
 
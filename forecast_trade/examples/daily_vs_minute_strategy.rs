@@ -29,8 +29,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let minute_model = ExponentialSmoothing::new(0.4)?;
 
     // Train models - but we won't use the trained models directly in this example
-    let _trained_daily_model = daily_model.train(&daily_data)?;
-    let _trained_minute_model = minute_model.train(&minute_data)?;
+    let _trained_daily_model = daily_model.fit(&daily_data)?;
+    let _trained_minute_model = minute_model.fit(&minute_data)?;
 
     // Create strategies for both timeframes
     println!("Creating strategies...");
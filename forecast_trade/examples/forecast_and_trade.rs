@@ -1,9 +1,8 @@
-use day_trade::strategies::hold::moving_average::MovingAverageCrossoverStrategy;
-use day_trade::{DailyOhlcv, Signal};
+use day_trade::{MACrossover, TradingStrategy};
 use forecast_trade::models::arima::ArimaModel;
 use forecast_trade::strategies::ForecastStrategy;
 use forecast_trade::strategies::TrendFollowingStrategy;
-use forecast_trade::{DataLoader, ForecastModel};
+use forecast_trade::{DataLoader, FittedModel, ForecastModel};
 use std::path::PathBuf;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -26,28 +25,36 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Historical data: {} days", historical_data.len());
     println!("Test data: {} days", test_data.len());
 
-    // Create and fit a forecasting model
-    let mut forecast_model = ArimaModel::new(2, 1, 2);
-    forecast_model.fit(&data.close_prices()[..history_len])?;
+    // Create and fit a forecasting model on the history
+    let history = data.slice(0, Some(history_len))?;
+    let forecast_model = ArimaModel::new(2, 1, 2)?.fit(&history)?;
 
-    // Create a forecast-based trading strategy
-    let forecast_strategy = TrendFollowingStrategy::new(1.5);
-
-    // Generate forecasts for the future
+    // Generate forecasts for the test period
     let forecast_horizon = 5; // Forecast 5 days ahead
     let forecast = forecast_model.forecast(forecast_horizon)?;
 
-    // Generate trading signals based on the forecast
-    let signals = forecast_strategy.generate_signals(&forecast)?;
-
     println!("Forecast values for the next {} days:", forecast_horizon);
-    for (i, value) in forecast.values.iter().enumerate() {
-        println!("Day {}: {:.2} -> {:?}", i + 1, value, signals[i]);
+    for (i, (value, bar)) in forecast.values().iter().zip(test_data).enumerate() {
+        println!(
+            "Day {}: forecast {:.2}, actual {:.2}",
+            i + 1,
+            value,
+            bar.data.close
+        );
+    }
+
+    // Generate trading signals from a forecast-based strategy
+    let forecast_strategy = TrendFollowingStrategy::new(1.5);
+    let signals = forecast_strategy.generate_signals(&history)?;
+
+    println!("\nLast 5 forecast strategy signals:");
+    for i in signals.len().saturating_sub(5)..signals.len() {
+        println!("Day {}: {:?}", historical_data[i].date, signals[i]);
     }
 
     // Now compare with a traditional moving average strategy
-    let ma_strategy = MovingAverageCrossoverStrategy::new(10, 30);
-    let ma_signals = ma_strategy.generate_signals(&historical_data)?;
+    let ma_strategy = MACrossover::new(5, 10);
+    let ma_signals = ma_strategy.generate_signals(historical_data)?;
 
     println!("\nLast 5 MA strategy signals:");
     for i in ma_signals.len().saturating_sub(5)..ma_signals.len() {
@@ -1,5 +1,5 @@
-use forecast_trade::models::moving_average::SimpleMA;
-use forecast_trade::{DataLoader, ForecastModel};
+use forecast_trade::models::moving_average::MovingAverage;
+use forecast_trade::{DataLoader, FittedModel, ForecastModel};
 use std::path::PathBuf;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -17,15 +17,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Loaded {} data points", prices.len());
 
     // Create and fit a Simple Moving Average model
-    let mut model = SimpleMA::new(20)?;
-    model.fit(&prices)?;
+    let model = MovingAverage::new(20)?;
+    let fitted = model.fit_series(&prices)?;
 
     // Forecast the next 5 days
-    let forecast = model.forecast(5)?;
+    let forecast = fitted.forecast(5)?;
 
     // Display the results
     println!("Forecast for the next 5 days using {}:", model.name());
-    for (i, value) in forecast.values.iter().enumerate() {
+    for (i, value) in forecast.values().iter().enumerate() {
         println!("Day {}: {:.2}", i + 1, value);
     }
 
@@ -1,10 +1,10 @@
-use chrono::{Duration, TimeZone, Utc};
+use chrono::{Datelike, Duration, TimeZone, Utc};
 use forecast_trade::data::TimeSeriesData;
 use forecast_trade::models::exponential_smoothing::ExponentialSmoothing;
-use forecast_trade::models::ForecastModel;
+use forecast_trade::models::{FittedModel, ForecastModel};
 use forecast_trade::strategies::mean_reversion::MeanReversionStrategy;
 use forecast_trade::strategies::trend_following::TrendFollowingStrategy;
-use forecast_trade::strategies::volatility_breakout::{VolatilityBreakoutConfig, VolatilityBreakoutStrategy};
+use forecast_trade::strategies::volatility_breakout::VolatilityBreakoutStrategy;
 use forecast_trade::strategies::{ForecastStrategy, TimeGranularity, TradingSignal};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -29,8 +29,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let daily_es = ExponentialSmoothing::new(0.2)?;
     let minute_es = ExponentialSmoothing::new_minute(0.4)?;
 
-    println!("Exponential Smoothing - Daily alpha: {}", daily_es.clone().train(&daily_data)?.forecast(&daily_data, 1)?.values[0]);
-    println!("Exponential Smoothing - Minute alpha: {}\n", minute_es.clone().train(&minute_data)?.forecast(&minute_data, 1)?.values[0]);
+    println!("Exponential Smoothing - Daily alpha: {}", daily_es.fit(&daily_data)?.forecast(1)?.values()[0]);
+    println!("Exponential Smoothing - Minute alpha: {}\n", minute_es.fit(&minute_data)?.forecast(1)?.values()[0]);
 
     // === Test strategies with different granularities ===
     println!("\n2. TESTING STRATEGIES WITH DIFFERENT GRANULARITIES");
@@ -54,11 +54,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Test Trend Following Strategy
     println!("\nTrend Following Strategy:");
-    let daily_tf = TrendFollowingStrategy::new(daily_es.clone(), 0.5)?;
+    let daily_tf = TrendFollowingStrategy::new(daily_es.clone(), 5)?;
 
     let minute_tf = TrendFollowingStrategy::new_with_granularity(
         minute_es.clone(),
-        0.2,
+        15,
         TimeGranularity::Minute
     )?;
 
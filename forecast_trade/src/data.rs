@@ -31,6 +31,7 @@ impl DataLoader {
         let df = CsvReader::new(file)
             .infer_schema(None)
             .has_header(true)
+            .with_try_parse_dates(true)
             .finish()?;
 
         Self::detect_and_create_time_series(df)
@@ -318,6 +319,31 @@ impl TimeSeriesData {
         }
     }
 
+    /// Get the high prices, if the data has a high column
+    pub fn high_prices(&self) -> Option<Vec<f64>> {
+        self.optional_price_column("high")
+    }
+
+    /// Get the low prices, if the data has a low column
+    pub fn low_prices(&self) -> Option<Vec<f64>> {
+        self.optional_price_column("low")
+    }
+
+    /// Price column whose name contains `name`, as f64 values
+    fn optional_price_column(&self, name: &str) -> Option<Vec<f64>> {
+        let column = self
+            .price_columns
+            .iter()
+            .find(|c| c.to_lowercase().contains(name))?;
+        self.column_as_f64(column).ok()
+    }
+
+    /// Get the volumes, if the data has a volume column
+    pub fn volumes(&self) -> Option<Vec<f64>> {
+        let column = self.volume_column.as_ref()?;
+        self.column_as_f64(column).ok()
+    }
+
     /// Get the timestamps as a vector
     pub fn timestamps(&self) -> Vec<DateTime<Utc>> {
         let col = self.df.column(&self.time_column).unwrap();
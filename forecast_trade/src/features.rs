@@ -0,0 +1,737 @@
+//! Feature engineering over bars for machine-learning forecast models
+//!
+//! Features are computed by feeding bars one at a time through a
+//! `FeatureStream`, so the value of a feature at bar `t` only depends on bars
+//! `0..=t`, and a new bar costs the same however long the history is. The prediction target at bar `t` is the simple return from the
+//! close of bar `t` to the close of bar `t + 1`.
+
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use trade_math::moving_averages::SimpleMovingAverage;
+use trade_math::oscillators::{Macd, RelativeStrengthIndex};
+use trade_math::volatility::BollingerBands;
+use trade_math::volume::VolumeMovingAverage;
+
+use crate::data::TimeSeriesData;
+use crate::error::{ForecastError, Result};
+
+/// Closing prices with optional volumes and timestamps
+#[derive(Debug, Clone)]
+pub struct Bars {
+    close: Vec<f64>,
+    volume: Option<Vec<f64>>,
+    timestamps: Option<Vec<DateTime<Utc>>>,
+}
+
+impl Bars {
+    /// Create bars from strictly positive closing prices
+    pub fn new(close: Vec<f64>) -> Result<Self> {
+        if close.iter().any(|c| !(c.is_finite() && *c > 0.0)) {
+            return Err(ForecastError::DataError(
+                "Closing prices must be finite and positive".to_string(),
+            ));
+        }
+        Ok(Self {
+            close,
+            volume: None,
+            timestamps: None,
+        })
+    }
+
+    /// Attach non-negative volumes, one per bar
+    pub fn with_volume(mut self, volume: Vec<f64>) -> Result<Self> {
+        if volume.len() != self.close.len() {
+            return Err(ForecastError::DataError(format!(
+                "Volume length ({}) doesn't match price length ({})",
+                volume.len(),
+                self.close.len()
+            )));
+        }
+        if volume.iter().any(|v| !(v.is_finite() && *v >= 0.0)) {
+            return Err(ForecastError::DataError(
+                "Volumes must be finite and non-negative".to_string(),
+            ));
+        }
+        self.volume = Some(volume);
+        Ok(self)
+    }
+
+    /// Attach timestamps, one per bar
+    pub fn with_timestamps(mut self, timestamps: Vec<DateTime<Utc>>) -> Result<Self> {
+        if timestamps.len() != self.close.len() {
+            return Err(ForecastError::DataError(format!(
+                "Timestamp length ({}) doesn't match price length ({})",
+                timestamps.len(),
+                self.close.len()
+            )));
+        }
+        self.timestamps = Some(timestamps);
+        Ok(self)
+    }
+
+    /// Bars from time series data, with volumes and timestamps when the data
+    /// provides them
+    pub fn from_data(data: &TimeSeriesData) -> Result<Self> {
+        let mut bars = Self::new(data.close_prices())?;
+        if let Some(volume) = data.volumes().filter(|v| v.len() == bars.len()) {
+            bars = bars.with_volume(volume)?;
+        }
+        let timestamps = data.timestamps();
+        if timestamps.len() == bars.len() {
+            bars = bars.with_timestamps(timestamps)?;
+        }
+        Ok(bars)
+    }
+
+    /// Number of bars
+    pub fn len(&self) -> usize {
+        self.close.len()
+    }
+
+    /// Whether there are no bars
+    pub fn is_empty(&self) -> bool {
+        self.close.is_empty()
+    }
+
+    /// Closing prices
+    pub fn close(&self) -> &[f64] {
+        &self.close
+    }
+
+    /// Volumes, if known
+    pub fn volume(&self) -> Option<&[f64]> {
+        self.volume.as_deref()
+    }
+
+    /// Timestamps, if known
+    pub fn timestamps(&self) -> Option<&[DateTime<Utc>]> {
+        self.timestamps.as_deref()
+    }
+
+    /// Close, volume and timestamp of the bar at `index`
+    pub(crate) fn bar(&self, index: usize) -> (f64, Option<f64>, Option<DateTime<Utc>>) {
+        (
+            self.close[index],
+            self.volume.as_ref().map(|v| v[index]),
+            self.timestamps.as_ref().map(|ts| ts[index]),
+        )
+    }
+
+    /// Append a bar
+    ///
+    /// `volume` and `timestamp` must be given exactly when the bars carry
+    /// volumes and timestamps.
+    pub fn push(
+        &mut self,
+        close: f64,
+        volume: Option<f64>,
+        timestamp: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        if !(close.is_finite() && close > 0.0) {
+            return Err(ForecastError::DataError(
+                "Closing prices must be finite and positive".to_string(),
+            ));
+        }
+        if volume.is_some_and(|v| !(v.is_finite() && v >= 0.0)) {
+            return Err(ForecastError::DataError(
+                "Volumes must be finite and non-negative".to_string(),
+            ));
+        }
+        if self.volume.is_some() != volume.is_some() {
+            return Err(ForecastError::DataError(
+                "A new bar has a volume exactly when the bars carry volumes".to_string(),
+            ));
+        }
+        if self.timestamps.is_some() != timestamp.is_some() {
+            return Err(ForecastError::DataError(
+                "A new bar has a timestamp exactly when the bars carry timestamps".to_string(),
+            ));
+        }
+
+        self.close.push(close);
+        if let (Some(volumes), Some(v)) = (&mut self.volume, volume) {
+            volumes.push(v);
+        }
+        if let (Some(timestamps), Some(ts)) = (&mut self.timestamps, timestamp) {
+            timestamps.push(ts);
+        }
+        Ok(())
+    }
+}
+
+/// A single engineered feature
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Feature {
+    /// One-bar return `lag` bars back, 1 being the latest bar
+    LaggedReturn(usize),
+    /// Return over the last `period` bars
+    Momentum(usize),
+    /// Standard deviation of one-bar returns over `period` bars
+    Volatility(usize),
+    /// Relative strength index over `period` bars, scaled to [0, 1]
+    Rsi(usize),
+    /// MACD histogram relative to the close
+    MacdHistogram {
+        /// Fast EMA period
+        fast: usize,
+        /// Slow EMA period
+        slow: usize,
+        /// Signal EMA period
+        signal: usize,
+    },
+    /// Close relative to its simple moving average, minus one
+    SmaDistance(usize),
+    /// Distance from the middle Bollinger band in band half-widths
+    BollingerPosition {
+        /// Moving average period
+        period: usize,
+        /// Band width in standard deviations
+        width: f64,
+    },
+    /// Log of volume relative to its moving average
+    RelativeVolume(usize),
+    /// Day of the week, 0 for Monday
+    DayOfWeek,
+    /// Hour of the day with minutes as a fraction
+    TimeOfDay,
+    /// Month of the year, 1 to 12
+    Month,
+}
+
+impl Feature {
+    /// Short descriptive name
+    pub fn name(&self) -> String {
+        match *self {
+            Self::LaggedReturn(lag) => format!("return_lag_{}", lag),
+            Self::Momentum(period) => format!("momentum_{}", period),
+            Self::Volatility(period) => format!("volatility_{}", period),
+            Self::Rsi(period) => format!("rsi_{}", period),
+            Self::MacdHistogram { fast, slow, signal } => {
+                format!("macd_histogram_{}_{}_{}", fast, slow, signal)
+            }
+            Self::SmaDistance(period) => format!("sma_distance_{}", period),
+            Self::BollingerPosition { period, width } => {
+                format!("bollinger_position_{}_{}", period, width)
+            }
+            Self::RelativeVolume(period) => format!("relative_volume_{}", period),
+            Self::DayOfWeek => "day_of_week".to_string(),
+            Self::TimeOfDay => "time_of_day".to_string(),
+            Self::Month => "month".to_string(),
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        let valid = match *self {
+            Self::LaggedReturn(n)
+            | Self::Momentum(n)
+            | Self::Rsi(n)
+            | Self::SmaDistance(n)
+            | Self::RelativeVolume(n) => n > 0,
+            Self::Volatility(n) => n > 1,
+            Self::MacdHistogram { fast, slow, signal } => fast > 0 && fast < slow && signal > 0,
+            Self::BollingerPosition { period, width } => period > 1 && width > 0.0,
+            Self::DayOfWeek | Self::TimeOfDay | Self::Month => true,
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(ForecastError::InvalidParameter(format!(
+                "Invalid feature parameters: {:?}",
+                self
+            )))
+        }
+    }
+
+    /// Whether the feature reads volumes
+    pub fn needs_volume(&self) -> bool {
+        matches!(self, Self::RelativeVolume(_))
+    }
+
+    /// Whether the feature reads timestamps
+    pub fn needs_timestamps(&self) -> bool {
+        matches!(self, Self::DayOfWeek | Self::TimeOfDay | Self::Month)
+    }
+
+    /// Closes the feature looks back over, including the current one
+    fn lookback(&self) -> usize {
+        match *self {
+            Self::LaggedReturn(n) | Self::Momentum(n) | Self::Volatility(n) => n + 1,
+            _ => 1,
+        }
+    }
+
+    fn state(&self) -> Result<FeatureState> {
+        Ok(match *self {
+            Self::LaggedReturn(_) | Self::Momentum(_) | Self::Volatility(_) => {
+                FeatureState::History
+            }
+            Self::Rsi(period) => {
+                FeatureState::Rsi(RelativeStrengthIndex::new(period).map_err(math_error)?)
+            }
+            Self::MacdHistogram { fast, slow, signal } => {
+                FeatureState::Macd(Macd::new(fast, slow, signal).map_err(math_error)?)
+            }
+            Self::SmaDistance(period) => {
+                FeatureState::Sma(SimpleMovingAverage::new(period).map_err(math_error)?)
+            }
+            Self::BollingerPosition { period, width } => {
+                FeatureState::Bollinger(BollingerBands::new(period, width).map_err(math_error)?)
+            }
+            Self::RelativeVolume(period) => {
+                FeatureState::Volume(VolumeMovingAverage::new(period).map_err(math_error)?)
+            }
+            Self::DayOfWeek | Self::TimeOfDay | Self::Month => FeatureState::Calendar,
+        })
+    }
+
+    /// Value at the newest bar, `None` while the feature is warming up
+    ///
+    /// `closes` holds the most recent closes, newest last.
+    fn next(
+        &self,
+        state: &mut FeatureState,
+        closes: &VecDeque<f64>,
+        volume: Option<f64>,
+        timestamp: Option<DateTime<Utc>>,
+    ) -> Result<Option<f64>> {
+        let m = closes.len();
+        let price = closes[m - 1];
+        let value = match state {
+            FeatureState::History => match *self {
+                Self::LaggedReturn(lag) => {
+                    (m > lag).then(|| closes[m - lag] / closes[m - lag - 1] - 1.0)
+                }
+                Self::Momentum(period) => {
+                    (m > period).then(|| price / closes[m - 1 - period] - 1.0)
+                }
+                Self::Volatility(period) => (m > period).then(|| {
+                    let returns: Vec<f64> = closes
+                        .range(m - 1 - period..)
+                        .zip(closes.range(m - period..))
+                        .map(|(previous, current)| current / previous - 1.0)
+                        .collect();
+                    let mean = returns.iter().sum::<f64>() / period as f64;
+                    let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>()
+                        / (period - 1) as f64;
+                    variance.sqrt()
+                }),
+                // Only the features above keep a history state
+                _ => None,
+            },
+            FeatureState::Rsi(rsi) => {
+                rsi.update(price).map_err(math_error)?;
+                rsi.value().ok().map(|v| v / 100.0)
+            }
+            FeatureState::Macd(macd) => {
+                macd.update(price).map_err(math_error)?;
+                macd.histogram().ok().map(|h| h / price)
+            }
+            FeatureState::Sma(sma) => {
+                sma.update(price).map_err(math_error)?;
+                sma.value().ok().map(|average| price / average - 1.0)
+            }
+            FeatureState::Bollinger(bands) => {
+                bands.update(price).map_err(math_error)?;
+                match (bands.middle_band(), bands.upper_band()) {
+                    (Ok(middle), Ok(upper)) if upper > middle => {
+                        Some((price - middle) / (upper - middle))
+                    }
+                    (Ok(_), Ok(_)) => Some(0.0),
+                    _ => None,
+                }
+            }
+            FeatureState::Volume(average) => {
+                let v = volume.ok_or_else(|| {
+                    ForecastError::DataError(format!("{} requires volumes", self.name()))
+                })?;
+                average.update(v).map_err(math_error)?;
+                average
+                    .value()
+                    .ok()
+                    .filter(|a| *a > 0.0)
+                    .map(|a| (v.max(f64::MIN_POSITIVE) / a).ln())
+            }
+            FeatureState::Calendar => {
+                let ts = timestamp.ok_or_else(|| {
+                    ForecastError::DataError(format!("{} requires timestamps", self.name()))
+                })?;
+                Some(match self {
+                    Self::DayOfWeek => ts.weekday().num_days_from_monday() as f64,
+                    Self::TimeOfDay => ts.hour() as f64 + ts.minute() as f64 / 60.0,
+                    _ => ts.month() as f64,
+                })
+            }
+        };
+        Ok(value)
+    }
+}
+
+/// Indicator state carried between bars
+#[derive(Debug, Clone)]
+enum FeatureState {
+    /// Computed from the recent closes alone
+    History,
+    Rsi(RelativeStrengthIndex),
+    Macd(Macd),
+    Sma(SimpleMovingAverage),
+    Bollinger(BollingerBands),
+    Volume(VolumeMovingAverage),
+    Calendar,
+}
+
+/// A feature set computed one bar at a time
+///
+/// Holds the indicator states and as many recent closes as the features
+/// look back over, so the feature row of each new bar is computed without
+/// revisiting the history.
+#[derive(Debug, Clone)]
+pub struct FeatureStream {
+    features: Vec<(Feature, FeatureState)>,
+    closes: VecDeque<f64>,
+    lookback: usize,
+}
+
+impl FeatureStream {
+    /// Start a stream with no bars
+    pub fn new(set: &FeatureSet) -> Result<Self> {
+        if set.is_empty() {
+            return Err(ForecastError::ValidationError(
+                "Feature set is empty".to_string(),
+            ));
+        }
+        let features = set
+            .features()
+            .iter()
+            .map(|f| Ok((*f, f.state()?)))
+            .collect::<Result<Vec<_>>>()?;
+        let lookback = set
+            .features()
+            .iter()
+            .map(Feature::lookback)
+            .max()
+            .unwrap_or(1);
+        Ok(Self {
+            features,
+            closes: VecDeque::with_capacity(lookback + 1),
+            lookback,
+        })
+    }
+
+    /// Feed the next bar and return its feature row, or `None` while any
+    /// feature is warming up
+    pub fn push(
+        &mut self,
+        close: f64,
+        volume: Option<f64>,
+        timestamp: Option<DateTime<Utc>>,
+    ) -> Result<Option<Vec<f64>>> {
+        if !(close.is_finite() && close > 0.0) {
+            return Err(ForecastError::DataError(
+                "Closing prices must be finite and positive".to_string(),
+            ));
+        }
+        self.closes.push_back(close);
+        if self.closes.len() > self.lookback {
+            self.closes.pop_front();
+        }
+
+        let closes = &self.closes;
+        let values = self
+            .features
+            .iter_mut()
+            .map(|(feature, state)| feature.next(state, closes, volume, timestamp))
+            .collect::<Result<Vec<_>>>()?;
+        let row: Option<Vec<f64>> = values.into_iter().collect();
+        Ok(row.filter(|r| r.iter().all(|v| v.is_finite())))
+    }
+}
+
+fn math_error(err: trade_math::MathError) -> ForecastError {
+    ForecastError::MathError(err.to_string())
+}
+
+/// Ordered collection of features
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeatureSet {
+    features: Vec<Feature>,
+}
+
+impl Default for FeatureSet {
+    /// Price-only features: five lagged returns, 10-bar momentum, 20-bar
+    /// volatility, RSI(14), MACD(12, 26, 9) and distance from the 20-bar SMA
+    fn default() -> Self {
+        let mut features: Vec<Feature> = (1..=5).map(Feature::LaggedReturn).collect();
+        features.extend([
+            Feature::Momentum(10),
+            Feature::Volatility(20),
+            Feature::Rsi(14),
+            Feature::MacdHistogram {
+                fast: 12,
+                slow: 26,
+                signal: 9,
+            },
+            Feature::SmaDistance(20),
+        ]);
+        Self { features }
+    }
+}
+
+impl FeatureSet {
+    /// Create an empty feature set
+    pub fn new() -> Self {
+        Self {
+            features: Vec::new(),
+        }
+    }
+
+    /// Add a feature
+    pub fn with_feature(mut self, feature: Feature) -> Result<Self> {
+        feature.validate()?;
+        self.features.push(feature);
+        Ok(self)
+    }
+
+    /// Features in column order
+    pub fn features(&self) -> &[Feature] {
+        &self.features
+    }
+
+    /// Feature names in column order
+    pub fn names(&self) -> Vec<String> {
+        self.features.iter().map(Feature::name).collect()
+    }
+
+    /// Number of features
+    pub fn len(&self) -> usize {
+        self.features.len()
+    }
+
+    /// Whether the set has no features
+    pub fn is_empty(&self) -> bool {
+        self.features.is_empty()
+    }
+
+    /// Whether any feature reads volumes
+    pub fn needs_volume(&self) -> bool {
+        self.features.iter().any(Feature::needs_volume)
+    }
+
+    /// Whether any feature reads timestamps
+    pub fn needs_timestamps(&self) -> bool {
+        self.features.iter().any(Feature::needs_timestamps)
+    }
+
+    /// Compute the feature matrix over `bars`
+    ///
+    /// Bars where any feature is still warming up are left out.
+    pub fn build(&self, bars: &Bars) -> Result<FeatureMatrix> {
+        Ok(self.build_stream(bars)?.0)
+    }
+
+    /// Compute the feature matrix over `bars`, and the stream positioned
+    /// after the last bar
+    pub(crate) fn build_stream(&self, bars: &Bars) -> Result<(FeatureMatrix, FeatureStream)> {
+        let mut stream = FeatureStream::new(self)?;
+        let close = bars.close();
+        let mut matrix = FeatureMatrix {
+            index: Vec::new(),
+            rows: Vec::new(),
+            targets: Vec::new(),
+        };
+        for t in 0..bars.len() {
+            let (price, volume, timestamp) = bars.bar(t);
+            let Some(row) = stream.push(price, volume, timestamp)? else {
+                continue;
+            };
+            matrix.index.push(t);
+            matrix.rows.push(row);
+            matrix
+                .targets
+                .push(close.get(t + 1).map(|next| next / close[t] - 1.0));
+        }
+        Ok((matrix, stream))
+    }
+}
+
+/// Feature rows with their next-bar return targets
+#[derive(Debug, Clone)]
+pub struct FeatureMatrix {
+    index: Vec<usize>,
+    rows: Vec<Vec<f64>>,
+    targets: Vec<Option<f64>>,
+}
+
+impl FeatureMatrix {
+    /// Bar index of each row
+    pub fn index(&self) -> &[usize] {
+        &self.index
+    }
+
+    /// Feature values, one row per bar
+    pub fn rows(&self) -> &[Vec<f64>] {
+        &self.rows
+    }
+
+    /// Next-bar return of each row, `None` for the last bar
+    pub fn targets(&self) -> &[Option<f64>] {
+        &self.targets
+    }
+
+    /// Number of rows
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Whether there are no rows
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Rows whose target is known, with their targets
+    pub fn training_set(&self) -> (Vec<Vec<f64>>, Vec<f64>) {
+        self.rows
+            .iter()
+            .zip(&self.targets)
+            .filter_map(|(row, target)| target.map(|y| (row.clone(), y)))
+            .unzip()
+    }
+
+    /// Row for the bar at `index`, if its features are available
+    pub fn row_at(&self, index: usize) -> Option<&[f64]> {
+        self.index
+            .binary_search(&index)
+            .ok()
+            .map(|i| self.rows[i].as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn bars(n: usize) -> Bars {
+        let close = (0..n)
+            .map(|i| 100.0 + 5.0 * (i as f64 * 0.3).sin() + 0.1 * i as f64)
+            .collect();
+        let volume = (0..n).map(|i| 1000.0 + (i % 7) as f64 * 100.0).collect();
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 14, 30, 0).unwrap();
+        let timestamps = (0..n).map(|i| start + Duration::days(i as i64)).collect();
+        Bars::new(close)
+            .unwrap()
+            .with_volume(volume)
+            .unwrap()
+            .with_timestamps(timestamps)
+            .unwrap()
+    }
+
+    fn full_set() -> FeatureSet {
+        [
+            Feature::RelativeVolume(5),
+            Feature::BollingerPosition {
+                period: 10,
+                width: 2.0,
+            },
+            Feature::DayOfWeek,
+            Feature::TimeOfDay,
+            Feature::Month,
+        ]
+        .into_iter()
+        .try_fold(FeatureSet::default(), |set, f| set.with_feature(f))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_features_do_not_look_ahead() {
+        let base = bars(80);
+        let matrix = full_set().build(&base).unwrap();
+
+        // Changing the future must leave every earlier row untouched
+        let mut close = base.close().to_vec();
+        let mut volume = base.volume().unwrap().to_vec();
+        for t in 60..80 {
+            close[t] *= 1.5;
+            volume[t] *= 3.0;
+        }
+        let shocked = Bars::new(close)
+            .unwrap()
+            .with_volume(volume)
+            .unwrap()
+            .with_timestamps(base.timestamps().unwrap().to_vec())
+            .unwrap();
+        let shocked_matrix = full_set().build(&shocked).unwrap();
+
+        for t in 0..60 {
+            assert_eq!(matrix.row_at(t), shocked_matrix.row_at(t));
+        }
+        // Only the target of bar 59 sees bar 60
+        let row = matrix.index().iter().position(|&t| t == 59).unwrap();
+        assert_ne!(matrix.targets()[row], shocked_matrix.targets()[row]);
+        assert_eq!(matrix.targets()[row - 1], shocked_matrix.targets()[row - 1]);
+    }
+
+    #[test]
+    fn test_matrix_layout() {
+        let b = bars(80);
+        let set = full_set();
+        let matrix = set.build(&b).unwrap();
+
+        // MACD needs the slow EMA and the signal EMA to warm up
+        assert_eq!(matrix.index()[0], 33);
+        assert_eq!(matrix.rows()[0].len(), set.len());
+        assert_eq!(matrix.targets().last().unwrap(), &None);
+        assert_eq!(matrix.training_set().0.len(), matrix.len() - 1);
+
+        let t = matrix.index()[0];
+        let row = matrix.row_at(t).unwrap();
+        let close = b.close();
+        assert!((row[0] - (close[t] / close[t - 1] - 1.0)).abs() < 1e-12);
+        assert_eq!(row[set.len() - 2], 14.5);
+    }
+
+    #[test]
+    fn test_missing_inputs_and_validation() {
+        let prices = Bars::new(bars(40).close().to_vec()).unwrap();
+        let volume = FeatureSet::new()
+            .with_feature(Feature::RelativeVolume(5))
+            .unwrap();
+        assert!(volume.build(&prices).is_err());
+        assert!(FeatureSet::new().build(&prices).is_err());
+        assert!(FeatureSet::new()
+            .with_feature(Feature::MacdHistogram {
+                fast: 26,
+                slow: 12,
+                signal: 9
+            })
+            .is_err());
+        assert!(Bars::new(vec![1.0, -1.0]).is_err());
+
+        // New bars carry exactly the fields the bars have
+        let mut b = bars(3);
+        let ts = b.timestamps().unwrap()[2] + Duration::days(1);
+        assert!(b.push(101.0, None, Some(ts)).is_err());
+        assert!(b.push(101.0, Some(900.0), None).is_err());
+        b.push(101.0, Some(900.0), Some(ts)).unwrap();
+        assert_eq!(b.len(), 4);
+        assert_eq!(b.volume().unwrap()[3], 900.0);
+        assert_eq!(b.timestamps().unwrap()[3], ts);
+        assert!(!FeatureSet::default().needs_volume());
+        assert!(full_set().needs_volume() && full_set().needs_timestamps());
+    }
+
+    #[test]
+    fn test_stream_matches_matrix() {
+        let b = bars(80);
+        let set = full_set();
+        let matrix = set.build(&b).unwrap();
+
+        let mut stream = FeatureStream::new(&set).unwrap();
+        for t in 0..b.len() {
+            let (close, volume, timestamp) = b.bar(t);
+            let row = stream.push(close, volume, timestamp).unwrap();
+            assert_eq!(row.as_deref(), matrix.row_at(t));
+        }
+        assert!(stream.push(100.0, None, None).is_err());
+    }
+}
@@ -5,7 +5,22 @@
 //! ## Features
 //!
 //! - Time series data handling (OHLCV data)
-//! - Forecasting models (Exponential Smoothing, Moving Average, ARIMA)
+//! - Forecasting models (Exponential Smoothing, Moving Average, ARIMA, GARCH)
+//!   sharing one configure / fit / forecast / update lifecycle
+//! - Maximum-likelihood ARIMA/SARIMA with exogenous regressors and automatic
+//!   order selection (`auto_arima`) guided by KPSS/ADF tests
+//! - GARCH, GJR-GARCH and EGARCH volatility models with normal or Student-t
+//!   innovations, feeding volatility breakouts and volatility-targeted sizing
+//! - Model-based prediction intervals and quantile forecasts: analytic
+//!   variance paths for ETS/ARIMA, simulated distributions for GARCH
+//! - ETS models (additive/multiplicative error, damped trend, seasonality)
+//!   including Holt-Winters, with automatic selection by AICc (`auto_ets`)
+//! - Rolling-origin and expanding-window cross-validation with per-horizon
+//!   error metrics and Diebold-Mariano model comparison
+//! - Forecast ensembles combining models by equal, inverse-error, stacked or
+//!   median weighting
+//! - Machine-learning models (ridge, lasso, gradient boosting, random forest,
+//!   k-NN) predicting next-bar returns from look-ahead-free engineered features
 //! - Trading strategies (Mean Reversion, Trend Following, Volatility Breakout)
 //! - Strategy backtesting with performance metrics
 //! - Support for both daily and minute-level data
@@ -29,17 +44,22 @@
 //!
 //! ## Quick Start
 //!
-//! ```rust
+//! ```rust,no_run
 //! use forecast_trade::data::DataLoader;
 //! use forecast_trade::models::exponential_smoothing::ExponentialSmoothing;
+//! use forecast_trade::models::{FittedModel, ForecastModel};
 //! use forecast_trade::strategies::mean_reversion::MeanReversionStrategy;
 //! use forecast_trade::strategies::{ForecastStrategy, TimeGranularity};
 //!
+//! # fn main() -> forecast_trade::error::Result<()> {
 //! // Load data
 //! let data = DataLoader::from_csv("data.csv")?;
 //!
-//! // Create a forecasting model
+//! // Configure a forecasting model, fit it and forecast five steps ahead
 //! let model = ExponentialSmoothing::new(0.7)?;
+//! let fitted = model.fit(&data)?;
+//! let forecast = fitted.forecast(5)?;
+//! println!("Next values: {:?}", forecast.values());
 //!
 //! // Create a trading strategy for daily data
 //! let daily_strategy = MeanReversionStrategy::new_with_granularity(
@@ -60,17 +80,27 @@
 //!
 //! // Run backtest
 //! let results = daily_strategy.backtest(&data, 10000.0)?;
+//! # Ok(())
+//! # }
 //! ```
 
 pub mod data;
 pub mod error;
+pub mod features;
+pub mod linalg;
+pub mod metrics;
 pub mod models;
+pub mod optimization;
+pub mod stationarity;
 pub mod strategies;
+pub mod utils;
+pub mod validation;
+pub mod volatility;
 
 // Re-export commonly used types
 pub use crate::data::{DataLoader, TimeSeriesData};
 pub use crate::error::ForecastError;
-pub use crate::models::{ForecastModel, ForecastResult};
+pub use crate::models::{FittedModel, ForecastModel, ForecastResult};
 pub use crate::strategies::ForecastStrategy;
 
 // Version information
@@ -0,0 +1,222 @@
+//! Small dense linear algebra helpers used by the estimators
+//!
+//! Matrices are row-major `Vec<Vec<f64>>`. The problems solved here are
+//! small (a handful of regressors), so plain Gaussian elimination is used.
+
+use crate::error::{ForecastError, Result};
+
+/// Pivots smaller than this are treated as zero
+const SINGULAR_TOLERANCE: f64 = 1e-12;
+
+/// Solve `a * x = b` by Gaussian elimination with partial pivoting
+pub fn solve_linear_system(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Result<Vec<f64>> {
+    let n = b.len();
+    if a.len() != n || a.iter().any(|row| row.len() != n) {
+        return Err(ForecastError::ValidationError(
+            "Matrix must be square and match the right-hand side".to_string(),
+        ));
+    }
+
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))
+            .unwrap();
+        if a[pivot][col].abs() < SINGULAR_TOLERANCE {
+            return Err(ForecastError::MathError(
+                "Singular matrix in linear system".to_string(),
+            ));
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+
+        let pivot_row = a[col].clone();
+        for row in col + 1..n {
+            let factor = a[row][col] / pivot_row[col];
+            for (value, pivot) in a[row][col..].iter_mut().zip(&pivot_row[col..]) {
+                *value -= factor * pivot;
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let sum: f64 = (row + 1..n).map(|c| a[row][c] * x[c]).sum();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+    Ok(x)
+}
+
+/// Invert a square matrix by Gauss-Jordan elimination
+pub fn invert_matrix(a: &[Vec<f64>]) -> Result<Vec<Vec<f64>>> {
+    let n = a.len();
+    if a.iter().any(|row| row.len() != n) {
+        return Err(ForecastError::ValidationError(
+            "Matrix must be square".to_string(),
+        ));
+    }
+
+    // Augment with the identity
+    let mut m: Vec<Vec<f64>> = a
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let mut augmented = row.clone();
+            augmented.extend((0..n).map(|j| if i == j { 1.0 } else { 0.0 }));
+            augmented
+        })
+        .collect();
+
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&i, &j| m[i][col].abs().total_cmp(&m[j][col].abs()))
+            .unwrap();
+        if m[pivot][col].abs() < SINGULAR_TOLERANCE {
+            return Err(ForecastError::MathError(
+                "Singular matrix cannot be inverted".to_string(),
+            ));
+        }
+        m.swap(col, pivot);
+
+        let scale = m[col][col];
+        for value in m[col].iter_mut() {
+            *value /= scale;
+        }
+
+        let pivot_row = m[col].clone();
+        for (row, values) in m.iter_mut().enumerate() {
+            let factor = values[col];
+            if row == col || factor == 0.0 {
+                continue;
+            }
+            for (value, pivot) in values.iter_mut().zip(&pivot_row) {
+                *value -= factor * pivot;
+            }
+        }
+    }
+
+    Ok(m.into_iter().map(|row| row[n..].to_vec()).collect())
+}
+
+/// Ordinary least squares fit
+#[derive(Debug, Clone)]
+pub struct LeastSquares {
+    coefficients: Vec<f64>,
+    standard_errors: Vec<f64>,
+    residuals: Vec<f64>,
+    sigma2: f64,
+}
+
+impl LeastSquares {
+    /// Estimated coefficients, one per regressor
+    pub fn coefficients(&self) -> &[f64] {
+        &self.coefficients
+    }
+
+    /// Standard errors of the coefficients
+    pub fn standard_errors(&self) -> &[f64] {
+        &self.standard_errors
+    }
+
+    /// Residuals, one per observation
+    pub fn residuals(&self) -> &[f64] {
+        &self.residuals
+    }
+
+    /// Residual variance with a degrees-of-freedom correction
+    pub fn sigma2(&self) -> f64 {
+        self.sigma2
+    }
+}
+
+/// Fit `y = X b + e` by ordinary least squares
+///
+/// Each row of `x` holds the regressors of one observation; include a column
+/// of ones for an intercept.
+pub fn least_squares(x: &[Vec<f64>], y: &[f64]) -> Result<LeastSquares> {
+    let n = y.len();
+    let k = x.first().map_or(0, Vec::len);
+    if x.len() != n || x.iter().any(|row| row.len() != k) {
+        return Err(ForecastError::ValidationError(
+            "Regressor rows must match the observations and have equal length".to_string(),
+        ));
+    }
+    if n <= k {
+        return Err(ForecastError::ValidationError(format!(
+            "Need more than {} observations for {} regressors",
+            k, k
+        )));
+    }
+
+    let mut xtx = vec![vec![0.0; k]; k];
+    let mut xty = vec![0.0; k];
+    for (row, &target) in x.iter().zip(y) {
+        for a in 0..k {
+            xty[a] += row[a] * target;
+            for b in 0..k {
+                xtx[a][b] += row[a] * row[b];
+            }
+        }
+    }
+
+    let inverse = invert_matrix(&xtx)?;
+    let coefficients: Vec<f64> = inverse
+        .iter()
+        .map(|row| row.iter().zip(&xty).map(|(a, b)| a * b).sum())
+        .collect();
+
+    let residuals: Vec<f64> = x
+        .iter()
+        .zip(y)
+        .map(|(row, &target)| {
+            target
+                - row
+                    .iter()
+                    .zip(&coefficients)
+                    .map(|(a, b)| a * b)
+                    .sum::<f64>()
+        })
+        .collect();
+    let sigma2 = residuals.iter().map(|r| r * r).sum::<f64>() / (n - k) as f64;
+    let standard_errors = (0..k)
+        .map(|i| (sigma2 * inverse[i][i]).max(0.0).sqrt())
+        .collect();
+
+    Ok(LeastSquares {
+        coefficients,
+        standard_errors,
+        residuals,
+        sigma2,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve_and_invert() {
+        let a = vec![vec![2.0, 1.0], vec![1.0, 3.0]];
+        let x = solve_linear_system(a.clone(), vec![3.0, 5.0]).unwrap();
+        assert!((x[0] - 0.8).abs() < 1e-12 && (x[1] - 1.4).abs() < 1e-12);
+
+        let inverse = invert_matrix(&a).unwrap();
+        assert!((inverse[0][0] - 0.6).abs() < 1e-12);
+        assert!((inverse[0][1] + 0.2).abs() < 1e-12);
+        assert!(invert_matrix(&[vec![1.0, 2.0], vec![2.0, 4.0]]).is_err());
+    }
+
+    #[test]
+    fn test_least_squares_recovers_line() {
+        let x: Vec<Vec<f64>> = (0..20).map(|i| vec![1.0, i as f64]).collect();
+        let y: Vec<f64> = (0..20)
+            .map(|i| 3.0 + 0.5 * i as f64 + if i % 2 == 0 { 0.1 } else { -0.1 })
+            .collect();
+
+        let fit = least_squares(&x, &y).unwrap();
+        assert!((fit.coefficients()[0] - 3.0).abs() < 0.1);
+        assert!((fit.coefficients()[1] - 0.5).abs() < 0.01);
+        assert!(fit.standard_errors().iter().all(|&se| se > 0.0));
+        assert_eq!(fit.residuals().len(), 20);
+    }
+}
@@ -1,7 +1,8 @@
 //! Metrics for evaluating forecast performance
 
+use crate::data::TimeSeriesData;
 use crate::error::{ForecastError, Result};
-use crate::models::ForecastModel;
+use crate::models::{FittedModel, ForecastModel};
 use crate::utils::forecast_accuracy;
 use day_trade::Signal;
 
@@ -46,22 +47,25 @@ pub fn evaluate_forecast(forecast: &[f64], actual: &[f64]) -> Result<ForecastMet
 }
 
 /// Evaluate forecast model on a training and test set
+///
+/// Fits `model` on `train_data`, forecasts `horizon` steps and compares the
+/// forecast with the first values of `test_data`.
 pub fn evaluate_model<M: ForecastModel>(
-    model: &mut M, 
-    train_data: &[f64], 
-    test_data: &[f64], 
-    horizon: usize
+    model: &M,
+    train_data: &TimeSeriesData,
+    test_data: &[f64],
+    horizon: usize,
 ) -> Result<ForecastMetrics> {
     // Fit model to training data
-    model.fit(train_data)?;
-    
+    let fitted = model.fit(train_data)?;
+
     // Generate forecast
-    let forecast = model.forecast(horizon)?;
-    
+    let forecast = fitted.forecast(horizon)?;
+
     // Evaluate forecast against test data (limiting to min length)
-    let min_len = forecast.values.len().min(test_data.len());
-    
-    evaluate_forecast(&forecast.values[..min_len], &test_data[..min_len])
+    let min_len = forecast.values().len().min(test_data.len());
+
+    evaluate_forecast(&forecast.values()[..min_len], &test_data[..min_len])
 }
 
 /// Evaluate strategy performance based on signals
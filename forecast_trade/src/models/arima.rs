@@ -1,33 +1,47 @@
-//! ARIMA models for time series forecasting
+//! ARIMA and seasonal ARIMA models for time series forecasting
+//!
+//! A model is configured with its orders, optionally a seasonal part, and an
+//! estimation method. Coefficients are estimated by conditional sum of
+//! squares (CSS) or by exact Gaussian maximum likelihood, evaluated with a
+//! Kalman filter and started from the CSS estimates. Estimates are
+//! constrained to stationary, invertible models.
+//!
+//! Exogenous regressors are handled as a regression with ARIMA errors
+//! (ARIMAX): `y[t] = b' x[t] + n[t]`, where `n` follows the ARIMA model.
+//! Fitted values, residuals and forecasts use the conditional recursion with
+//! the estimated coefficients.
 
-use crate::data::TimeSeriesData;
 use crate::error::{ForecastError, Result};
-use crate::models::{ForecastModel, ForecastResult, TrainedForecastModel};
+use crate::linalg::{least_squares, solve_linear_system};
+use crate::models::{FittedModel, ForecastModel, ForecastResult, InformationCriterion};
+use crate::optimization::NelderMead;
 
-/// ARIMA model (AutoRegressive Integrated Moving Average)
-#[derive(Debug, Clone)]
-pub struct ArimaModel {
-    /// Name of the model
-    name: String,
-    /// AR order (p)
-    p: usize,
-    /// Differencing order (d)
-    d: usize,
-    /// MA order (q)
-    q: usize,
-    /// Fitted AR coefficients
-    ar_coefficients: Vec<f64>,
-    /// Fitted MA coefficients
-    ma_coefficients: Vec<f64>,
-    /// Fitted data
-    data: Vec<f64>,
-    /// Residuals from fitting
-    residuals: Vec<f64>,
+/// Seasonal part (P, D, Q) of a SARIMA model with its period
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeasonalOrder {
+    /// Seasonal AR order (P)
+    pub p: usize,
+    /// Seasonal differencing order (D)
+    pub d: usize,
+    /// Seasonal MA order (Q)
+    pub q: usize,
+    /// Number of observations per season
+    pub period: usize,
+}
+
+/// How ARIMA coefficients are estimated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EstimationMethod {
+    /// Minimise the conditional sum of squared innovations
+    ConditionalSumOfSquares,
+    /// Maximise the exact Gaussian likelihood, starting from the CSS estimates
+    #[default]
+    ExactLikelihood,
 }
 
-/// Trained ARIMA model
+/// ARIMA model (AutoRegressive Integrated Moving Average)
 #[derive(Debug, Clone)]
-pub struct TrainedArimaModel {
+pub struct ArimaModel {
     /// Name of the model
     name: String,
     /// AR order (p)
@@ -36,178 +50,1262 @@ pub struct TrainedArimaModel {
     d: usize,
     /// MA order (q)
     q: usize,
-    /// Fitted AR coefficients
-    ar_coefficients: Vec<f64>,
-    /// Fitted MA coefficients
-    ma_coefficients: Vec<f64>,
-    /// Historical data
-    historical_data: Vec<f64>,
-    /// Residuals from fitting
-    residuals: Vec<f64>,
+    /// Seasonal orders, if any
+    seasonal: Option<SeasonalOrder>,
+    /// Estimation method
+    method: EstimationMethod,
+    /// Whether to estimate a mean (or drift); `None` decides from the
+    /// differencing orders
+    constant: Option<bool>,
 }
 
 impl ArimaModel {
-    /// Create a new ARIMA model
-    pub fn new(p: usize, d: usize, q: usize) -> Self {
-        Self {
+    /// Create a new ARIMA(p, d, q) model
+    pub fn new(p: usize, d: usize, q: usize) -> Result<Self> {
+        if d > 2 {
+            return Err(ForecastError::InvalidParameter(
+                "Differencing order must be at most 2".to_string(),
+            ));
+        }
+
+        Ok(Self {
             name: format!("ARIMA({},{},{})", p, d, q),
             p,
             d,
             q,
-            ar_coefficients: Vec::new(),
-            ma_coefficients: Vec::new(),
-            data: Vec::new(),
-            residuals: Vec::new(),
-        }
+            seasonal: None,
+            method: EstimationMethod::default(),
+            constant: None,
+        })
     }
 
-    /// Helper function to forecast using AR process
-    fn forecast_ar(&self, data: &[f64], coefficients: &[f64], horizon: usize) -> Vec<f64> {
-        if data.is_empty() || coefficients.is_empty() {
-            return vec![0.0; horizon];
+    /// Add a seasonal (P, D, Q) part with the given period
+    pub fn with_seasonal(mut self, p: usize, d: usize, q: usize, period: usize) -> Result<Self> {
+        if period < 2 {
+            return Err(ForecastError::InvalidParameter(
+                "Seasonal period must be at least 2".to_string(),
+            ));
         }
+        if d > 1 {
+            return Err(ForecastError::InvalidParameter(
+                "Seasonal differencing order must be at most 1".to_string(),
+            ));
+        }
+
+        self.seasonal = Some(SeasonalOrder { p, d, q, period });
+        self.name = format!(
+            "SARIMA({},{},{})({},{},{})[{}]",
+            self.p, self.d, self.q, p, d, q, period
+        );
+        Ok(self)
+    }
 
-        let mut forecasts = Vec::with_capacity(horizon);
-        let p = coefficients.len();
+    /// Set the estimation method
+    pub fn with_method(mut self, method: EstimationMethod) -> Self {
+        self.method = method;
+        self
+    }
 
-        // Use last p values of the data for initial forecasting
-        let mut history = data[data.len().saturating_sub(p)..].to_vec();
+    /// Include or exclude the mean (drift when differenced)
+    ///
+    /// By default a constant is included when the total differencing order
+    /// is at most one.
+    pub fn with_constant(mut self, constant: bool) -> Self {
+        self.constant = Some(constant);
+        self
+    }
 
-        for _ in 0..horizon {
-            // Calculate forecast using AR formula
-            let mut forecast = 0.0;
-            for i in 0..p {
-                forecast += coefficients[i] * history[history.len() - 1 - i];
-            }
+    /// Model orders as (p, d, q)
+    pub fn order(&self) -> (usize, usize, usize) {
+        (self.p, self.d, self.q)
+    }
+
+    /// Seasonal orders, if the model is seasonal
+    pub fn seasonal_order(&self) -> Option<SeasonalOrder> {
+        self.seasonal
+    }
+
+    /// Estimation method
+    pub fn method(&self) -> EstimationMethod {
+        self.method
+    }
+
+    /// Whether a mean (or drift) is estimated
+    pub fn includes_constant(&self) -> bool {
+        let seasonal_d = self.seasonal.map_or(0, |s| s.d);
+        self.constant.unwrap_or(self.d + seasonal_d <= 1)
+    }
+
+    /// Minimum number of observations needed to fit the model
+    pub fn min_observations(&self) -> usize {
+        let layout = self.layout(0);
+        // Differencing and conditioning lags, plus one more observation
+        // than there are parameters
+        self.differencing_polynomial().len() - 1 + layout.conditioning() + layout.len() + 2
+    }
+
+    /// Fit a regression on exogenous variables with ARIMA errors
+    ///
+    /// `exogenous` holds one row of regressors per observation.
+    pub fn fit_with_exogenous(
+        &self,
+        values: &[f64],
+        exogenous: &[Vec<f64>],
+    ) -> Result<FittedArima> {
+        self.estimate(values, Some(exogenous))
+    }
 
-            // Add forecast to history
-            history.push(forecast);
-            forecasts.push(forecast);
+    fn layout(&self, exogenous: usize) -> Layout {
+        let seasonal = self.seasonal.unwrap_or(SeasonalOrder {
+            p: 0,
+            d: 0,
+            q: 0,
+            period: 1,
+        });
+        Layout {
+            p: self.p,
+            q: self.q,
+            seasonal_p: seasonal.p,
+            seasonal_q: seasonal.q,
+            period: seasonal.period,
+            constant: self.includes_constant(),
+            exogenous,
         }
+    }
 
-        forecasts
+    /// Coefficients of `(1 - B)^d (1 - B^s)^D`
+    fn differencing_polynomial(&self) -> Vec<f64> {
+        let mut polynomial = vec![1.0];
+        for _ in 0..self.d {
+            polynomial = multiply_polynomials(&polynomial, &[1.0, -1.0]);
+        }
+        if let Some(seasonal) = self.seasonal {
+            let mut factor = vec![0.0; seasonal.period + 1];
+            factor[0] = 1.0;
+            factor[seasonal.period] = -1.0;
+            for _ in 0..seasonal.d {
+                polynomial = multiply_polynomials(&polynomial, &factor);
+            }
+        }
+        polynomial
     }
-}
 
-impl ForecastModel for ArimaModel {
-    type Trained = TrainedArimaModel;
+    fn estimate(&self, values: &[f64], exogenous: Option<&[Vec<f64>]>) -> Result<FittedArima> {
+        let exogenous = exogenous.unwrap_or(&[]);
+        let width = exogenous.first().map_or(0, Vec::len);
+        if !exogenous.is_empty()
+            && (exogenous.len() != values.len() || exogenous.iter().any(|row| row.len() != width))
+        {
+            return Err(ForecastError::ValidationError(
+                "Exogenous regressors need one row of equal length per observation".to_string(),
+            ));
+        }
 
-    fn train(&self, data: &TimeSeriesData) -> Result<TrainedArimaModel> {
-        let prices = data.close_prices();
-        if prices.len() < self.p + self.d + self.q + 1 {
+        let layout = self.layout(width);
+        let required = self.min_observations() + width;
+        if values.len() < required {
             return Err(ForecastError::ValidationError(format!(
-                "Insufficient data for ARIMA({},{},{}). Need at least {} observations.",
-                self.p,
-                self.d,
-                self.q,
-                self.p + self.d + self.q + 1
+                "Insufficient data for {}. Need at least {} observations.",
+                self.name, required
             )));
         }
 
-        // For simplicity, we'll just use a naive AR estimation
-        // Real ARIMA would involve differencing, proper estimation, etc.
+        // Difference the series and each regressor
+        let delta = self.differencing_polynomial();
+        let w = apply_polynomial(values, &delta);
+        let regressors: Vec<Vec<f64>> = (0..width)
+            .map(|j| {
+                let column: Vec<f64> = exogenous.iter().map(|row| row[j]).collect();
+                apply_polynomial(&column, &delta)
+            })
+            .collect();
+        let problem = Problem {
+            layout,
+            w: &w,
+            regressors: &regressors,
+        };
 
-        // For simplicity, just set some default coefficients
-        // In a real implementation, these would be estimated from data
-        let ar_coefficients = vec![0.8; self.p];
-        let ma_coefficients = vec![0.2; self.q];
+        let start = problem.initial_parameters()?;
+        let steps = problem.initial_steps(&start);
+        let optimizer = NelderMead::default();
 
-        // In a real implementation, we would calculate residuals properly
-        let residuals = vec![0.0; prices.len()];
+        // Without ARMA terms the least-squares start is already optimal
+        let mut params = start;
+        if layout.arma_len() > 0 {
+            params = optimizer
+                .minimize(|x| problem.css_objective(x), &params, &steps)
+                .point;
+            if self.method == EstimationMethod::ExactLikelihood {
+                params = optimizer
+                    .minimize(|x| problem.exact_objective(x), &params, &steps)
+                    .point;
+            }
+        }
 
-        Ok(TrainedArimaModel {
-            name: self.name.clone(),
-            p: self.p,
-            d: self.d,
-            q: self.q,
-            ar_coefficients,
-            ma_coefficients,
-            historical_data: prices.to_vec(),
-            residuals,
-        })
+        let (log_likelihood, sigma2, observations) = match self.method {
+            EstimationMethod::ConditionalSumOfSquares => problem.css_likelihood(&params),
+            EstimationMethod::ExactLikelihood => problem.exact_likelihood(&params),
+        }
+        .ok_or_else(|| {
+            ForecastError::ForecastingError(format!(
+                "{} estimation did not find a stationary, invertible model",
+                self.name
+            ))
+        })?;
+
+        let split = layout.split(&params);
+        let regression: Vec<f64> = exogenous
+            .iter()
+            .map(|row| dot(row, split.beta))
+            .chain(std::iter::repeat(0.0))
+            .take(values.len())
+            .collect();
+
+        let mut fitted = FittedArima {
+            specification: self.clone(),
+            ar: split.ar.to_vec(),
+            ma: split.ma.to_vec(),
+            seasonal_ar: split.seasonal_ar.to_vec(),
+            seasonal_ma: split.seasonal_ma.to_vec(),
+            full_ar: expand_ar(split.ar, split.seasonal_ar, layout.period),
+            full_ma: expand_ma(split.ma, split.seasonal_ma, layout.period),
+            mean: split.mean,
+            beta: split.beta.to_vec(),
+            delta,
+            sigma2,
+            log_likelihood,
+            observations,
+            parameters: layout.len() + 1,
+            values: Vec::with_capacity(values.len()),
+            z: Vec::with_capacity(w.len()),
+            innovations: Vec::with_capacity(w.len()),
+            fitted_values: Vec::with_capacity(values.len()),
+            residuals: Vec::with_capacity(values.len()),
+        };
+        let errors: Vec<f64> = values.iter().zip(&regression).map(|(y, r)| y - r).collect();
+        fitted.push(&errors, &regression);
+
+        Ok(fitted)
     }
+}
+
+impl ForecastModel for ArimaModel {
+    type Fitted = FittedArima;
 
     fn name(&self) -> &str {
         &self.name
     }
+
+    fn fit_series(&self, values: &[f64]) -> Result<FittedArima> {
+        self.estimate(values, None)
+    }
 }
 
-impl TrainedForecastModel for TrainedArimaModel {
-    fn forecast(&self, horizon: usize) -> Result<ForecastResult> {
-        if self.historical_data.is_empty() {
+/// ARIMA model fitted to a series
+#[derive(Debug, Clone)]
+pub struct FittedArima {
+    specification: ArimaModel,
+    ar: Vec<f64>,
+    ma: Vec<f64>,
+    seasonal_ar: Vec<f64>,
+    seasonal_ma: Vec<f64>,
+    /// AR polynomial with the seasonal part multiplied in
+    full_ar: Vec<f64>,
+    /// MA polynomial with the seasonal part multiplied in
+    full_ma: Vec<f64>,
+    /// Mean of the differenced series (drift when differenced)
+    mean: f64,
+    /// Coefficients of the exogenous regressors
+    beta: Vec<f64>,
+    /// Coefficients of the differencing polynomial
+    delta: Vec<f64>,
+    sigma2: f64,
+    log_likelihood: f64,
+    /// Observations entering the likelihood
+    observations: usize,
+    /// Estimated parameters, including the innovation variance
+    parameters: usize,
+    /// Observations net of the regression on exogenous variables
+    values: Vec<f64>,
+    /// Differenced, demeaned series
+    z: Vec<f64>,
+    /// One-step innovations of `z`
+    innovations: Vec<f64>,
+    fitted_values: Vec<f64>,
+    residuals: Vec<f64>,
+}
+
+impl FittedArima {
+    /// The configured model that was fitted
+    pub fn specification(&self) -> &ArimaModel {
+        &self.specification
+    }
+
+    /// Estimated AR coefficients
+    pub fn ar_coefficients(&self) -> &[f64] {
+        &self.ar
+    }
+
+    /// Estimated MA coefficients
+    pub fn ma_coefficients(&self) -> &[f64] {
+        &self.ma
+    }
+
+    /// Estimated seasonal AR coefficients
+    pub fn seasonal_ar_coefficients(&self) -> &[f64] {
+        &self.seasonal_ar
+    }
+
+    /// Estimated seasonal MA coefficients
+    pub fn seasonal_ma_coefficients(&self) -> &[f64] {
+        &self.seasonal_ma
+    }
+
+    /// Mean of the differenced series (zero without a constant)
+    pub fn intercept(&self) -> f64 {
+        self.mean
+    }
+
+    /// Estimated coefficients of the exogenous regressors
+    pub fn exogenous_coefficients(&self) -> &[f64] {
+        &self.beta
+    }
+
+    /// Estimated innovation variance
+    pub fn sigma2(&self) -> f64 {
+        self.sigma2
+    }
+
+    /// Maximised log-likelihood (approximate for CSS fits)
+    pub fn log_likelihood(&self) -> f64 {
+        self.log_likelihood
+    }
+
+    /// Akaike information criterion
+    pub fn aic(&self) -> f64 {
+        self.information_criterion(InformationCriterion::Aic)
+    }
+
+    /// Small-sample corrected Akaike information criterion
+    pub fn aicc(&self) -> f64 {
+        self.information_criterion(InformationCriterion::Aicc)
+    }
+
+    /// Bayesian information criterion
+    pub fn bic(&self) -> f64 {
+        self.information_criterion(InformationCriterion::Bic)
+    }
+
+    /// Value of an information criterion for this fit
+    pub fn information_criterion(&self, criterion: InformationCriterion) -> f64 {
+        criterion.evaluate(self.log_likelihood, self.parameters, self.observations)
+    }
+
+    /// Whether the AR polynomial (with its seasonal part) is stationary
+    pub fn is_stationary(&self) -> bool {
+        is_stationary(&self.full_ar)
+    }
+
+    /// Whether the MA polynomial (with its seasonal part) is invertible
+    pub fn is_invertible(&self) -> bool {
+        is_invertible(&self.full_ma)
+    }
+
+    /// First `horizon` weights of the infinite MA representation of the
+    /// original (undifferenced) series
+    ///
+    /// These are the coefficients of `theta(B) / (phi(B) delta(B))`, with the
+    /// seasonal and differencing parts multiplied in; `psi_0 = 1`.
+    pub fn psi_weights(&self, horizon: usize) -> Vec<f64> {
+        let ar = multiply_polynomials(&lag_polynomial(&self.full_ar, 1, -1.0), &self.delta);
+        let mut psi: Vec<f64> = Vec::with_capacity(horizon);
+        for j in 0..horizon {
+            if j == 0 {
+                psi.push(1.0);
+                continue;
+            }
+            let ma = self.full_ma.get(j - 1).copied().unwrap_or(0.0);
+            let feedback: f64 = (1..ar.len().min(j + 1)).map(|i| ar[i] * psi[j - i]).sum();
+            psi.push(ma - feedback);
+        }
+        psi
+    }
+
+    /// Forecast error variances for the next `horizon` values
+    ///
+    /// The `h`-step variance is `sigma2 * sum_{j < h} psi_j^2`, ignoring
+    /// parameter uncertainty.
+    pub fn forecast_variance(&self, horizon: usize) -> Vec<f64> {
+        self.psi_weights(horizon)
+            .iter()
+            .scan(0.0, |total, psi| {
+                *total += psi * psi;
+                Some(self.sigma2 * *total)
+            })
+            .collect()
+    }
+
+    /// Append new observations together with their exogenous regressors
+    pub fn update_with_exogenous(
+        &mut self,
+        observations: &[f64],
+        exogenous: &[Vec<f64>],
+    ) -> Result<()> {
+        let regression = self.regression(exogenous, observations.len())?;
+        let errors: Vec<f64> = observations
+            .iter()
+            .zip(&regression)
+            .map(|(y, r)| y - r)
+            .collect();
+        self.push(&errors, &regression);
+        Ok(())
+    }
+
+    /// Forecast given the future values of the exogenous regressors
+    pub fn forecast_with_exogenous(
+        &self,
+        horizon: usize,
+        exogenous: &[Vec<f64>],
+    ) -> Result<ForecastResult> {
+        let regression = self.regression(exogenous, horizon)?;
+        self.forecast_path(&regression)
+    }
+
+    /// Regression contribution of exogenous rows, validating their shape
+    fn regression(&self, exogenous: &[Vec<f64>], expected: usize) -> Result<Vec<f64>> {
+        if exogenous.len() != expected || exogenous.iter().any(|row| row.len() != self.beta.len()) {
+            return Err(ForecastError::ValidationError(format!(
+                "Expected {} rows of {} exogenous values",
+                expected,
+                self.beta.len()
+            )));
+        }
+        Ok(exogenous.iter().map(|row| dot(row, &self.beta)).collect())
+    }
+
+    fn require_no_exogenous(&self) -> Result<()> {
+        if self.beta.is_empty() {
+            Ok(())
+        } else {
+            Err(ForecastError::ForecastingError(
+                "Model has exogenous regressors; supply their values".to_string(),
+            ))
+        }
+    }
+
+    /// One-step prediction of the next `z` given the current history
+    fn predict_next(&self, z: &[f64], innovations: &[f64]) -> f64 {
+        let lagged = |series: &[f64], lag: usize| {
+            series
+                .len()
+                .checked_sub(lag)
+                .map(|i| series[i])
+                .unwrap_or(0.0)
+        };
+        let ar: f64 = (0..self.full_ar.len())
+            .map(|i| self.full_ar[i] * lagged(z, i + 1))
+            .sum();
+        let ma: f64 = (0..self.full_ma.len())
+            .map(|j| self.full_ma[j] * lagged(innovations, j + 1))
+            .sum();
+        ar + ma
+    }
+
+    /// Append regression errors; `regression` is what was subtracted
+    fn push(&mut self, errors: &[f64], regression: &[f64]) {
+        let lag = self.delta.len() - 1;
+        for (&error, &offset) in errors.iter().zip(regression) {
+            self.values.push(error);
+            let value = error + offset;
+
+            // The first observations only seed the differencing
+            if self.values.len() <= lag {
+                self.fitted_values.push(value);
+                self.residuals.push(0.0);
+                continue;
+            }
+
+            let tail = &self.values[self.values.len() - 1 - lag..];
+            let w: f64 = self
+                .delta
+                .iter()
+                .enumerate()
+                .map(|(i, coefficient)| coefficient * tail[lag - i])
+                .sum();
+            let z = w - self.mean;
+
+            let innovation = z - self.predict_next(&self.z, &self.innovations);
+            self.z.push(z);
+            self.innovations.push(innovation);
+
+            // One-step errors are the same on the original and differenced scales
+            self.fitted_values.push(value - innovation);
+            self.residuals.push(innovation);
+        }
+    }
+
+    /// Forecast with the given future regression contributions
+    fn forecast_path(&self, regression: &[f64]) -> Result<ForecastResult> {
+        if self.values.is_empty() {
             return Err(ForecastError::ForecastingError(
                 "Model has not been fitted to data".to_string(),
             ));
         }
 
-        // For simplicity, just use AR part for forecasting
-        // Real ARIMA would involve more complex forecasting logic
+        // Forecast the differenced series with future innovations set to zero
+        let mut z = self.z.clone();
+        let mut innovations = self.innovations.clone();
+        let mut values = self.values.clone();
+        let mut forecasts = Vec::with_capacity(regression.len());
+        for &offset in regression {
+            let next = self.predict_next(&z, &innovations);
+            z.push(next);
+            innovations.push(0.0);
 
-        let forecasts = self.forecast_ar(&self.historical_data, &self.ar_coefficients, horizon);
+            // Undo the differencing: delta(B) n[t] = w[t]
+            let t = values.len();
+            let integrated: f64 = self
+                .delta
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(i, coefficient)| coefficient * values[t - i])
+                .sum();
+            let error = next + self.mean - integrated;
+            values.push(error);
+            forecasts.push(error + offset);
+        }
 
-        Ok(ForecastResult::new(forecasts, horizon)?)
+        ForecastResult::new(forecasts, regression.len())?
+            .with_variances(self.forecast_variance(regression.len()))
     }
+}
 
-    fn predict(&self, data: &TimeSeriesData) -> Result<ForecastResult> {
-        let prices = data.close_prices();
-        if prices.is_empty() {
-            return Err(ForecastError::DataError(
-                "Empty time series data".to_string(),
-            ));
+impl FittedModel for FittedArima {
+    fn name(&self) -> &str {
+        &self.specification.name
+    }
+
+    fn forecast(&self, horizon: usize) -> Result<ForecastResult> {
+        self.require_no_exogenous()?;
+        self.forecast_path(&vec![0.0; horizon])
+    }
+
+    fn update(&mut self, observations: &[f64]) -> Result<()> {
+        self.require_no_exogenous()?;
+        self.push(observations, &vec![0.0; observations.len()]);
+        Ok(())
+    }
+
+    fn fitted_values(&self) -> &[f64] {
+        &self.fitted_values
+    }
+
+    fn residuals(&self) -> &[f64] {
+        &self.residuals
+    }
+}
+
+/// Whether AR coefficients `phi` (for `1 - phi_1 B - ... - phi_p B^p`)
+/// describe a stationary process
+///
+/// Uses the Schur-Cohn step-down recursion: the process is stationary when
+/// every implied partial autocorrelation is inside (-1, 1).
+pub fn is_stationary(phi: &[f64]) -> bool {
+    let mut coefficients = phi.to_vec();
+    while let Some(&kappa) = coefficients.last() {
+        if kappa.is_nan() || kappa.abs() >= 1.0 {
+            return false;
         }
+        let k = coefficients.len() - 1;
+        let scale = 1.0 - kappa * kappa;
+        coefficients = (0..k)
+            .map(|j| (coefficients[j] + kappa * coefficients[k - 1 - j]) / scale)
+            .collect();
+    }
+    true
+}
+
+/// Whether MA coefficients `theta` (for `1 + theta_1 B + ... + theta_q B^q`)
+/// describe an invertible process
+pub fn is_invertible(theta: &[f64]) -> bool {
+    let negated: Vec<f64> = theta.iter().map(|t| -t).collect();
+    is_stationary(&negated)
+}
+
+/// Lengths of the blocks of the parameter vector
+/// `[ar, ma, seasonal_ar, seasonal_ma, mean?, beta]`
+#[derive(Debug, Clone, Copy)]
+struct Layout {
+    p: usize,
+    q: usize,
+    seasonal_p: usize,
+    seasonal_q: usize,
+    period: usize,
+    constant: bool,
+    exogenous: usize,
+}
 
-        // For simplicity, we'll just produce one-step-ahead forecasts
-        // Real ARIMA would involve proper prediction
+impl Layout {
+    fn arma_len(&self) -> usize {
+        self.p + self.q + self.seasonal_p + self.seasonal_q
+    }
 
-        let mut predictions = Vec::with_capacity(prices.len());
+    fn len(&self) -> usize {
+        self.arma_len() + usize::from(self.constant) + self.exogenous
+    }
 
-        // For the first p points, just use the actual values
-        for i in 0..self.p.min(prices.len()) {
-            predictions.push(prices[i]);
+    /// Order of the full AR polynomial, the lags CSS conditions on
+    fn conditioning(&self) -> usize {
+        self.p + self.period * self.seasonal_p
+    }
+
+    fn split<'a>(&self, params: &'a [f64]) -> Split<'a> {
+        let (ar, rest) = params.split_at(self.p);
+        let (ma, rest) = rest.split_at(self.q);
+        let (seasonal_ar, rest) = rest.split_at(self.seasonal_p);
+        let (seasonal_ma, rest) = rest.split_at(self.seasonal_q);
+        let (mean, beta) = if self.constant {
+            (rest[0], &rest[1..])
+        } else {
+            (0.0, rest)
+        };
+        Split {
+            ar,
+            ma,
+            seasonal_ar,
+            seasonal_ma,
+            mean,
+            beta,
         }
+    }
+}
+
+/// Parameter vector split into its blocks
+struct Split<'a> {
+    ar: &'a [f64],
+    ma: &'a [f64],
+    seasonal_ar: &'a [f64],
+    seasonal_ma: &'a [f64],
+    mean: f64,
+    beta: &'a [f64],
+}
+
+/// Differenced data the objectives are evaluated on
+struct Problem<'a> {
+    layout: Layout,
+    /// Differenced series
+    w: &'a [f64],
+    /// Differenced exogenous regressors, one vector per regressor
+    regressors: &'a [Vec<f64>],
+}
+
+impl Problem<'_> {
+    /// Least-squares mean and regression coefficients, then Hannan-Rissanen
+    /// estimates for non-seasonal ARMA terms
+    fn initial_parameters(&self) -> Result<Vec<f64>> {
+        let layout = self.layout;
+        let columns = usize::from(layout.constant) + layout.exogenous;
+        let regression = if columns == 0 {
+            Vec::new()
+        } else {
+            let rows: Vec<Vec<f64>> = (0..self.w.len())
+                .map(|t| {
+                    let mut row = Vec::with_capacity(columns);
+                    if layout.constant {
+                        row.push(1.0);
+                    }
+                    row.extend(self.regressors.iter().map(|column| column[t]));
+                    row
+                })
+                .collect();
+            least_squares(&rows, self.w)?.coefficients().to_vec()
+        };
+
+        let mut params = vec![0.0; layout.arma_len()];
+        params.extend(regression);
 
-        // For the rest, use AR model to predict
-        for i in self.p..prices.len() {
-            let mut prediction = 0.0;
-            for j in 0..self.p {
-                prediction += self.ar_coefficients[j] * prices[i - j - 1];
+        let u = self.demeaned(&layout.split(&params));
+        if let Ok((ar, ma)) = hannan_rissanen(&u, layout.p, layout.q) {
+            if is_stationary(&ar) && is_invertible(&ma) {
+                params[..layout.p].copy_from_slice(&ar);
+                params[layout.p..layout.p + layout.q].copy_from_slice(&ma);
             }
-            predictions.push(prediction);
         }
+        Ok(params)
+    }
 
-        Ok(ForecastResult::new(predictions.clone(), predictions.len())?)
+    /// Initial simplex steps scaled to each parameter
+    fn initial_steps(&self, start: &[f64]) -> Vec<f64> {
+        let scale = standard_deviation(self.w).max(1e-8);
+        let arma = self.layout.arma_len();
+        let mut steps = vec![0.1; arma];
+        if self.layout.constant {
+            steps.push(0.1 * scale);
+        }
+        let offset = arma + usize::from(self.layout.constant);
+        for (j, column) in self.regressors.iter().enumerate() {
+            let spread = standard_deviation(column);
+            let step = if spread > 0.0 {
+                0.1 * scale / spread
+            } else {
+                0.1
+            };
+            steps.push(step.max(0.1 * start[offset + j].abs()));
+        }
+        steps
     }
 
-    fn name(&self) -> &str {
-        &self.name
+    /// Differenced series net of mean and regressors
+    fn demeaned(&self, split: &Split) -> Vec<f64> {
+        (0..self.w.len())
+            .map(|t| {
+                let regression: f64 = self
+                    .regressors
+                    .iter()
+                    .zip(split.beta)
+                    .map(|(column, b)| column[t] * b)
+                    .sum();
+                self.w[t] - split.mean - regression
+            })
+            .collect()
+    }
+
+    /// Expanded AR and MA polynomials, if stationary and invertible
+    fn polynomials(&self, split: &Split) -> Option<(Vec<f64>, Vec<f64>)> {
+        let feasible = is_stationary(split.ar)
+            && is_stationary(split.seasonal_ar)
+            && is_invertible(split.ma)
+            && is_invertible(split.seasonal_ma);
+        feasible.then(|| {
+            (
+                expand_ar(split.ar, split.seasonal_ar, self.layout.period),
+                expand_ma(split.ma, split.seasonal_ma, self.layout.period),
+            )
+        })
+    }
+
+    /// (log-likelihood, innovation variance, observations) under CSS
+    fn css_likelihood(&self, params: &[f64]) -> Option<(f64, f64, usize)> {
+        let split = self.layout.split(params);
+        let (ar, ma) = self.polynomials(&split)?;
+        let u = self.demeaned(&split);
+
+        let start = ar.len();
+        let mut innovations = vec![0.0; u.len()];
+        for t in start..u.len() {
+            let mut prediction: f64 = (0..ar.len()).map(|i| ar[i] * u[t - 1 - i]).sum();
+            prediction += (0..ma.len().min(t))
+                .map(|j| ma[j] * innovations[t - 1 - j])
+                .sum::<f64>();
+            innovations[t] = u[t] - prediction;
+        }
+
+        let observations = u.len() - start;
+        let sum_sq: f64 = innovations[start..].iter().map(|e| e * e).sum();
+        let sigma2 = (sum_sq / observations as f64).max(f64::MIN_POSITIVE);
+        let n = observations as f64;
+        let log_likelihood = -0.5 * n * ((2.0 * std::f64::consts::PI * sigma2).ln() + 1.0);
+        Some((log_likelihood, sigma2, observations))
+    }
+
+    /// (log-likelihood, innovation variance, observations) under the exact
+    /// Gaussian likelihood
+    fn exact_likelihood(&self, params: &[f64]) -> Option<(f64, f64, usize)> {
+        let split = self.layout.split(params);
+        let (ar, ma) = self.polynomials(&split)?;
+        let u = self.demeaned(&split);
+        let (log_likelihood, sigma2) = arma_exact_likelihood(&u, &ar, &ma)?;
+        Some((log_likelihood, sigma2, u.len()))
+    }
+
+    fn css_objective(&self, params: &[f64]) -> f64 {
+        self.css_likelihood(params)
+            .map_or(f64::INFINITY, |(ll, _, n)| -ll / n as f64)
+    }
+
+    fn exact_objective(&self, params: &[f64]) -> f64 {
+        self.exact_likelihood(params)
+            .map_or(f64::INFINITY, |(ll, _, n)| -ll / n as f64)
+    }
+}
+
+/// Exact Gaussian log-likelihood of a zero-mean ARMA series, concentrated
+/// over the innovation variance
+///
+/// Runs a Kalman filter on the state-space form with state dimension
+/// `max(p, q + 1)`, initialised at the stationary covariance. Returns the
+/// log-likelihood and the innovation variance estimate, or `None` if the
+/// filter breaks down.
+fn arma_exact_likelihood(u: &[f64], ar: &[f64], ma: &[f64]) -> Option<(f64, f64)> {
+    let r = ar.len().max(ma.len() + 1);
+    let mut phi = ar.to_vec();
+    phi.resize(r, 0.0);
+    let mut loading = vec![0.0; r];
+    loading[0] = 1.0;
+    loading[1..=ma.len()].copy_from_slice(ma);
+    let noise: Vec<Vec<f64>> = loading
+        .iter()
+        .map(|a| loading.iter().map(|b| a * b).collect())
+        .collect();
+
+    let mut covariance = stationary_covariance(&phi, &noise)?;
+    let mut state = vec![0.0; r];
+    let mut sum_sq = 0.0;
+    let mut sum_log = 0.0;
+    let mut previous_variance = f64::NAN;
+    let mut steady = false;
+
+    for &observation in u {
+        let variance = covariance[0][0];
+        if !(variance > 0.0 && variance.is_finite()) {
+            return None;
+        }
+        let innovation = observation - state[0];
+        sum_sq += innovation * innovation / variance;
+        sum_log += variance.ln();
+
+        // Measurement update
+        let column: Vec<f64> = covariance.iter().map(|row| row[0]).collect();
+        for i in 0..r {
+            state[i] += column[i] / variance * innovation;
+        }
+
+        // Time update; once the innovation variance has settled the
+        // covariance no longer changes
+        let first = state[0];
+        for i in 0..r {
+            let next = state.get(i + 1).copied().unwrap_or(0.0);
+            state[i] = phi[i] * first + next;
+        }
+        if !steady {
+            for (i, row) in covariance.iter_mut().enumerate() {
+                for (j, value) in row.iter_mut().enumerate() {
+                    *value -= column[i] * column[j] / variance;
+                }
+            }
+            covariance = predict_covariance(&covariance, &phi, &noise);
+            steady = (variance - previous_variance).abs() < 1e-10 * variance;
+        }
+        previous_variance = variance;
+    }
+
+    let n = u.len() as f64;
+    let sigma2 = (sum_sq / n).max(f64::MIN_POSITIVE);
+    let log_likelihood = -0.5 * (n * (2.0 * std::f64::consts::PI * sigma2).ln() + sum_log + n);
+    Some((log_likelihood, sigma2))
+}
+
+/// `T P T' + Q` for the companion transition `T` with first column `phi`
+fn predict_covariance(covariance: &[Vec<f64>], phi: &[f64], noise: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let r = phi.len();
+    // (T P)[i][j] = phi[i] P[0][j] + P[i + 1][j]
+    let tp: Vec<Vec<f64>> = (0..r)
+        .map(|i| {
+            (0..r)
+                .map(|j| {
+                    let below = covariance.get(i + 1).map_or(0.0, |row| row[j]);
+                    phi[i] * covariance[0][j] + below
+                })
+                .collect()
+        })
+        .collect();
+    // (T P T')[i][j] = (T P)[i][0] phi[j] + (T P)[i][j + 1]
+    (0..r)
+        .map(|i| {
+            (0..r)
+                .map(|j| {
+                    let shifted = tp[i].get(j + 1).copied().unwrap_or(0.0);
+                    tp[i][0] * phi[j] + shifted + noise[i][j]
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Solve `P = T P T' + Q` by the doubling algorithm
+fn stationary_covariance(phi: &[f64], noise: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
+    let r = phi.len();
+    let mut transition = vec![vec![0.0; r]; r];
+    for (i, row) in transition.iter_mut().enumerate() {
+        row[0] = phi[i];
+        if i + 1 < r {
+            row[i + 1] = 1.0;
+        }
+    }
+
+    let mut covariance = noise.to_vec();
+    for _ in 0..100 {
+        let increment = multiply(&multiply(&transition, &covariance), &transpose(&transition));
+        let size = max_abs(&covariance);
+        let change = max_abs(&increment);
+        for (row, increment_row) in covariance.iter_mut().zip(&increment) {
+            for (value, delta) in row.iter_mut().zip(increment_row) {
+                *value += delta;
+            }
+        }
+        if !change.is_finite() {
+            return None;
+        }
+        if change <= 1e-12 * (1.0 + size) {
+            return Some(covariance);
+        }
+        transition = multiply(&transition, &transition);
+    }
+    None
+}
+
+fn max_abs(matrix: &[Vec<f64>]) -> f64 {
+    matrix
+        .iter()
+        .flatten()
+        .fold(0.0_f64, |acc, v| acc.max(v.abs()))
+}
+
+fn multiply(a: &[Vec<f64>], b: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    a.iter()
+        .map(|row| {
+            (0..b[0].len())
+                .map(|j| row.iter().zip(b).map(|(x, b_row)| x * b_row[j]).sum())
+                .collect()
+        })
+        .collect()
+}
+
+fn transpose(a: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    (0..a[0].len())
+        .map(|j| a.iter().map(|row| row[j]).collect())
+        .collect()
+}
+
+/// AR coefficients of `(1 - sum phi B^i)(1 - sum Phi B^(s j))`
+fn expand_ar(ar: &[f64], seasonal_ar: &[f64], period: usize) -> Vec<f64> {
+    let polynomial = multiply_polynomials(
+        &lag_polynomial(ar, 1, -1.0),
+        &lag_polynomial(seasonal_ar, period, -1.0),
+    );
+    polynomial[1..].iter().map(|c| -c).collect()
+}
+
+/// MA coefficients of `(1 + sum theta B^i)(1 + sum Theta B^(s j))`
+fn expand_ma(ma: &[f64], seasonal_ma: &[f64], period: usize) -> Vec<f64> {
+    let polynomial = multiply_polynomials(
+        &lag_polynomial(ma, 1, 1.0),
+        &lag_polynomial(seasonal_ma, period, 1.0),
+    );
+    polynomial[1..].to_vec()
+}
+
+/// Polynomial `1 + sign * sum c_i B^(spacing i)`
+fn lag_polynomial(coefficients: &[f64], spacing: usize, sign: f64) -> Vec<f64> {
+    let mut polynomial = vec![0.0; coefficients.len() * spacing + 1];
+    polynomial[0] = 1.0;
+    for (i, c) in coefficients.iter().enumerate() {
+        polynomial[(i + 1) * spacing] = sign * c;
     }
+    polynomial
 }
 
-impl TrainedArimaModel {
-    /// Helper function to forecast using AR process
-    fn forecast_ar(&self, data: &[f64], coefficients: &[f64], horizon: usize) -> Vec<f64> {
-        if data.is_empty() || coefficients.is_empty() {
-            return vec![0.0; horizon];
+fn multiply_polynomials(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut product = vec![0.0; a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            product[i + j] += x * y;
         }
+    }
+    product
+}
+
+/// Apply a lag polynomial: `sum_i c_i values[t - i]` for each full window
+fn apply_polynomial(values: &[f64], polynomial: &[f64]) -> Vec<f64> {
+    let lag = polynomial.len() - 1;
+    (lag..values.len())
+        .map(|t| {
+            polynomial
+                .iter()
+                .enumerate()
+                .map(|(i, c)| c * values[t - i])
+                .sum()
+        })
+        .collect()
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn standard_deviation(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64).sqrt()
+}
 
-        let mut forecasts = Vec::with_capacity(horizon);
-        let p = coefficients.len();
+/// Hannan-Rissanen ARMA(p, q) estimates for a zero-mean series: a long
+/// autoregression supplies innovation estimates, then the coefficients are
+/// found by least squares on lagged values and lagged innovations
+fn hannan_rissanen(z: &[f64], p: usize, q: usize) -> Result<(Vec<f64>, Vec<f64>)> {
+    if q == 0 {
+        let ar = if p == 0 {
+            Vec::new()
+        } else {
+            regress_on_lags(z, &[], p, 0, p)?
+        };
+        return Ok((ar, Vec::new()));
+    }
 
-        // Use last p values of the data for initial forecasting
-        let mut history = data[data.len().saturating_sub(p)..].to_vec();
+    // Stage 1: long AR to estimate the innovations
+    let m = (p + q + 1).max((z.len() / 4).min(20));
+    let long_ar = regress_on_lags(z, &[], m, 0, m)?;
+    let mut innovations = vec![0.0; z.len()];
+    for t in m..z.len() {
+        let predicted: f64 = (0..m).map(|i| long_ar[i] * z[t - 1 - i]).sum();
+        innovations[t] = z[t] - predicted;
+    }
 
-        for _ in 0..horizon {
-            // Calculate forecast using AR formula
-            let mut forecast = 0.0;
-            for i in 0..p {
-                forecast += coefficients[i] * history[history.len() - 1 - i];
+    // Stage 2: regress on lagged values and lagged innovations
+    let coefficients = regress_on_lags(z, &innovations, p, q, m + q)?;
+    let (ar, ma) = coefficients.split_at(p);
+    Ok((ar.to_vec(), ma.to_vec()))
+}
+
+/// Least squares regression of `y[t]` on `p` lags of `y` and `q` lags of `x`
+/// for `t >= start`, without an intercept
+fn regress_on_lags(y: &[f64], x: &[f64], p: usize, q: usize, start: usize) -> Result<Vec<f64>> {
+    let k = p + q;
+    if y.len() <= start + k {
+        return Err(ForecastError::ValidationError(
+            "Not enough observations for the regression".to_string(),
+        ));
+    }
+
+    let mut xtx = vec![vec![0.0; k]; k];
+    let mut xty = vec![0.0; k];
+    for t in start..y.len() {
+        let row: Vec<f64> = (1..=p)
+            .map(|i| y[t - i])
+            .chain((1..=q).map(|j| x[t - j]))
+            .collect();
+        for a in 0..k {
+            xty[a] += row[a] * y[t];
+            for b in 0..k {
+                xtx[a][b] += row[a] * row[b];
             }
+        }
+    }
+
+    solve_linear_system(xtx, xty)
+}
 
-            // Add forecast to history
-            history.push(forecast);
-            forecasts.push(forecast);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::tests::noise;
+
+    /// ARMA(1, 1) around `mean`
+    fn arma_series(n: usize, phi: f64, theta: f64, mean: f64, seed: u64) -> Vec<f64> {
+        let e = noise(n, seed);
+        let mut values = vec![mean + e[0]];
+        for t in 1..n {
+            let next = mean + phi * (values[t - 1] - mean) + e[t] + theta * e[t - 1];
+            values.push(next);
         }
+        values
+    }
+
+    #[test]
+    fn test_recovers_ar_coefficient() {
+        let series = arma_series(2000, 0.6, 0.0, 0.0, 1);
+        for method in [
+            EstimationMethod::ConditionalSumOfSquares,
+            EstimationMethod::ExactLikelihood,
+        ] {
+            let fitted = ArimaModel::new(1, 0, 0)
+                .unwrap()
+                .with_method(method)
+                .fit_series(&series)
+                .unwrap();
+            assert!((fitted.ar_coefficients()[0] - 0.6).abs() < 0.05);
+            assert!((fitted.sigma2() - 1.0).abs() < 0.1);
+            assert_eq!(fitted.residuals().len(), series.len());
+        }
+    }
+
+    #[test]
+    fn test_exact_likelihood_of_ar1_matches_closed_form() {
+        let u = arma_series(50, 0.5, 0.0, 0.0, 2);
+        let phi = 0.5;
+        let (log_likelihood, sigma2) = arma_exact_likelihood(&u, &[phi], &[]).unwrap();
+
+        // Stationary first observation, then conditional one-step errors
+        let weighted: f64 = (1.0 - phi * phi) * u[0] * u[0]
+            + u.windows(2)
+                .map(|w| (w[1] - phi * w[0]).powi(2))
+                .sum::<f64>();
+        let n = u.len() as f64;
+        let expected_sigma2 = weighted / n;
+        let expected = -0.5
+            * (n * (2.0 * std::f64::consts::PI * expected_sigma2).ln() - (1.0 - phi * phi).ln()
+                + n);
+
+        assert!((sigma2 - expected_sigma2).abs() < 1e-9);
+        assert!((log_likelihood - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_arma_fit_and_update() {
+        let series = arma_series(800, 0.5, 0.3, 10.0, 3);
+        let model = ArimaModel::new(1, 0, 1).unwrap();
+
+        let mut fitted = model.fit_series(&series[..700]).unwrap();
+        assert!((fitted.ar_coefficients()[0] - 0.5).abs() < 0.15);
+        assert!((fitted.ma_coefficients()[0] - 0.3).abs() < 0.15);
+        assert!((fitted.intercept() - 10.0).abs() < 0.3);
+        assert!(fitted.is_stationary() && fitted.is_invertible());
+
+        fitted.update(&series[700..]).unwrap();
+        assert_eq!(fitted.fitted_values().len(), series.len());
+
+        let forecast = fitted.forecast(30).unwrap();
+        assert_eq!(forecast.horizons(), 30);
+        // Forecasts decay towards the mean
+        assert!((forecast.values()[29] - fitted.intercept()).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_information_criteria_prefer_true_order() {
+        let series = arma_series(500, 0.7, 0.0, 0.0, 4);
+        let fit = |p| {
+            ArimaModel::new(p, 0, 0)
+                .unwrap()
+                .fit_series(&series)
+                .unwrap()
+        };
+        let (white, ar1) = (fit(0), fit(1));
+
+        assert!(ar1.aic() < white.aic());
+        assert!(ar1.bic() < white.bic());
+        assert!(ar1.aicc() > ar1.aic());
+    }
+
+    #[test]
+    fn test_seasonal_ar() {
+        let e = noise(600, 5);
+        let mut series = vec![0.0; 600];
+        for t in 0..600 {
+            series[t] = e[t] + if t >= 4 { 0.7 * series[t - 4] } else { 0.0 };
+        }
+
+        let model = ArimaModel::new(0, 0, 0)
+            .unwrap()
+            .with_seasonal(1, 0, 0, 4)
+            .unwrap();
+        assert_eq!(model.name(), "SARIMA(0,0,0)(1,0,0)[4]");
+
+        let fitted = model.fit_series(&series).unwrap();
+        assert!((fitted.seasonal_ar_coefficients()[0] - 0.7).abs() < 0.1);
+
+        // The forecast one step ahead scales the value one season back
+        let forecast = fitted.forecast(4).unwrap();
+        let expected = fitted.intercept()
+            + fitted.seasonal_ar_coefficients()[0] * (series[596] - fitted.intercept());
+        assert!((forecast.values()[0] - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_seasonal_differencing_repeats_pattern() {
+        let pattern = [5.0, -3.0, 1.0, -3.0];
+        let series: Vec<f64> = (0..40).map(|t| 100.0 + pattern[t % 4]).collect();
+
+        let fitted = ArimaModel::new(0, 0, 0)
+            .unwrap()
+            .with_seasonal(0, 1, 0, 4)
+            .unwrap()
+            .fit_series(&series)
+            .unwrap();
+        let forecast = fitted.forecast(8).unwrap();
+        for (h, value) in forecast.values().iter().enumerate() {
+            assert!((value - series[36 + h % 4]).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_exogenous_regression() {
+        let e = arma_series(400, 0.5, 0.0, 0.0, 6);
+        let x: Vec<Vec<f64>> = noise(400, 7).iter().map(|v| vec![*v]).collect();
+        let series: Vec<f64> = (0..400).map(|t| 3.0 + 2.0 * x[t][0] + e[t]).collect();
+
+        let model = ArimaModel::new(1, 0, 0).unwrap();
+        let mut fitted = model.fit_with_exogenous(&series[..380], &x[..380]).unwrap();
+        assert!((fitted.exogenous_coefficients()[0] - 2.0).abs() < 0.1);
+        assert!(fitted.forecast(1).is_err());
+
+        fitted
+            .update_with_exogenous(&series[380..], &x[380..])
+            .unwrap();
+        let future = vec![vec![1.0], vec![-1.0]];
+        let forecast = fitted.forecast_with_exogenous(2, &future).unwrap();
+        assert!((forecast.values()[0] - forecast.values()[1] - 4.0).abs() < 0.5);
+        assert!(fitted.forecast_with_exogenous(2, &future[..1]).is_err());
+    }
+
+    #[test]
+    fn test_differenced_forecast_follows_trend() {
+        let series: Vec<f64> = (0..60).map(|i| 100.0 + 2.0 * i as f64).collect();
+        let fitted = ArimaModel::new(0, 1, 0)
+            .unwrap()
+            .fit_series(&series)
+            .unwrap();
+
+        let forecast = fitted.forecast(3).unwrap();
+        assert!((forecast.values()[0] - 220.0).abs() < 1e-9);
+        assert!((forecast.values()[2] - 224.0).abs() < 1e-9);
+        assert!(fitted.residuals()[1..].iter().all(|r| r.abs() < 1e-9));
+    }
+
+    #[test]
+    fn test_forecast_variance_from_psi_weights() {
+        let series = arma_series(400, 0.6, 0.0, 0.0, 19);
+        let fitted = ArimaModel::new(1, 1, 0)
+            .unwrap()
+            .fit_series(&series)
+            .unwrap();
+        let phi = fitted.ar_coefficients()[0];
+
+        // (1 - phi B)(1 - B) gives psi_1 = 1 + phi, psi_2 = (1 + phi)^2 - phi
+        let psi = fitted.psi_weights(3);
+        assert!((psi[1] - (1.0 + phi)).abs() < 1e-12);
+        assert!((psi[2] - ((1.0 + phi).powi(2) - phi)).abs() < 1e-12);
+
+        let forecast = fitted.forecast(3).unwrap();
+        let se = forecast.standard_errors().unwrap();
+        assert!((se[0] - fitted.sigma2().sqrt()).abs() < 1e-12);
+        let expected = fitted.sigma2() * (1.0 + psi[1] * psi[1] + psi[2] * psi[2]);
+        assert!((se[2] * se[2] - expected).abs() < 1e-12);
+
+        let median = forecast.quantile(0.5).unwrap();
+        assert!((median[2] - forecast.values()[2]).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_stationarity_and_invertibility_checks() {
+        assert!(is_stationary(&[0.6]));
+        assert!(is_stationary(&[1.2, -0.5]));
+        assert!(!is_stationary(&[1.0]));
+        assert!(!is_stationary(&[0.5, 0.6]));
+        assert!(is_invertible(&[0.9]));
+        assert!(!is_invertible(&[-1.2]));
+    }
 
-        forecasts
+    #[test]
+    fn test_validation() {
+        assert!(ArimaModel::new(1, 3, 0).is_err());
+        assert!(ArimaModel::new(1, 0, 0)
+            .unwrap()
+            .with_seasonal(1, 0, 0, 1)
+            .is_err());
+        let model = ArimaModel::new(2, 1, 2).unwrap();
+        assert!(model.fit_series(&[1.0, 2.0, 3.0]).is_err());
     }
 }
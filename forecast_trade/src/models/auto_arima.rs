@@ -0,0 +1,478 @@
+//! Automatic ARIMA order selection
+//!
+//! Follows the Hyndman-Khandakar procedure: the seasonal differencing order
+//! comes from the strength of the seasonal pattern, the ordinary
+//! differencing order from repeated KPSS (or ADF) tests, and the ARMA orders
+//! from a stepwise (or exhaustive) search minimising an information
+//! criterion.
+
+use std::collections::HashSet;
+
+use crate::error::{ForecastError, Result};
+use crate::linalg::least_squares;
+use crate::models::arima::{ArimaModel, EstimationMethod, FittedArima};
+use crate::models::{ForecastModel, InformationCriterion};
+use crate::stationarity::{difference, ndiffs, nsdiffs, StationarityTestKind};
+
+/// Maximum number of models fitted by the stepwise search
+const MAX_STEPWISE_MODELS: usize = 94;
+
+/// ARMA orders and constant of a candidate model
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Candidate {
+    p: usize,
+    q: usize,
+    seasonal_p: usize,
+    seasonal_q: usize,
+    constant: bool,
+}
+
+/// ARIMA model whose orders are selected from the data when fitted
+#[derive(Debug, Clone)]
+pub struct AutoArima {
+    max_p: usize,
+    max_q: usize,
+    max_seasonal_p: usize,
+    max_seasonal_q: usize,
+    max_d: usize,
+    max_seasonal_d: usize,
+    /// Maximum of p + q + P + Q
+    max_order: usize,
+    seasonal_period: Option<usize>,
+    criterion: InformationCriterion,
+    unit_root_test: StationarityTestKind,
+    significance: f64,
+    stepwise: bool,
+    method: EstimationMethod,
+}
+
+impl Default for AutoArima {
+    fn default() -> Self {
+        Self {
+            max_p: 5,
+            max_q: 5,
+            max_seasonal_p: 2,
+            max_seasonal_q: 2,
+            max_d: 2,
+            max_seasonal_d: 1,
+            max_order: 5,
+            seasonal_period: None,
+            criterion: InformationCriterion::default(),
+            unit_root_test: StationarityTestKind::default(),
+            significance: 0.05,
+            stepwise: true,
+            method: EstimationMethod::default(),
+        }
+    }
+}
+
+impl AutoArima {
+    /// Create a search with the default limits: p, q up to 5, P, Q up to 2,
+    /// d up to 2, D up to 1, p + q + P + Q up to 5, AICc, KPSS at 5%
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the largest non-seasonal AR and MA orders
+    pub fn with_max_orders(mut self, p: usize, q: usize) -> Self {
+        self.max_p = p;
+        self.max_q = q;
+        self
+    }
+
+    /// Set the largest seasonal AR and MA orders
+    pub fn with_max_seasonal_orders(mut self, p: usize, q: usize) -> Self {
+        self.max_seasonal_p = p;
+        self.max_seasonal_q = q;
+        self
+    }
+
+    /// Set the largest ordinary and seasonal differencing orders
+    pub fn with_max_differencing(mut self, d: usize, seasonal_d: usize) -> Result<Self> {
+        if d > 2 || seasonal_d > 1 {
+            return Err(ForecastError::InvalidParameter(
+                "Differencing orders are limited to d <= 2 and D <= 1".to_string(),
+            ));
+        }
+        self.max_d = d;
+        self.max_seasonal_d = seasonal_d;
+        Ok(self)
+    }
+
+    /// Set the largest total order p + q + P + Q
+    pub fn with_max_order(mut self, max_order: usize) -> Self {
+        self.max_order = max_order;
+        self
+    }
+
+    /// Search seasonal models with the given period
+    pub fn with_seasonal_period(mut self, period: usize) -> Result<Self> {
+        if period < 2 {
+            return Err(ForecastError::InvalidParameter(
+                "Seasonal period must be at least 2".to_string(),
+            ));
+        }
+        self.seasonal_period = Some(period);
+        Ok(self)
+    }
+
+    /// Set the information criterion minimised by the search
+    pub fn with_criterion(mut self, criterion: InformationCriterion) -> Self {
+        self.criterion = criterion;
+        self
+    }
+
+    /// Set the test and significance level (0.01, 0.05 or 0.10) used to
+    /// choose the differencing order
+    pub fn with_unit_root_test(
+        mut self,
+        kind: StationarityTestKind,
+        significance: f64,
+    ) -> Result<Self> {
+        if ![0.01, 0.05, 0.10]
+            .iter()
+            .any(|level| (level - significance).abs() < 1e-9)
+        {
+            return Err(ForecastError::InvalidParameter(
+                "Significance must be 0.01, 0.05 or 0.10".to_string(),
+            ));
+        }
+        self.unit_root_test = kind;
+        self.significance = significance;
+        Ok(self)
+    }
+
+    /// Use the stepwise search (default) or try every admissible order
+    pub fn with_stepwise(mut self, stepwise: bool) -> Self {
+        self.stepwise = stepwise;
+        self
+    }
+
+    /// Set the estimation method of the candidate models
+    pub fn with_method(mut self, method: EstimationMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Choose the ordinary and seasonal differencing orders (d, D)
+    pub fn differencing_orders(&self, values: &[f64]) -> Result<(usize, usize)> {
+        let seasonal_d = match self.seasonal_period {
+            Some(period) if self.max_seasonal_d > 0 => {
+                nsdiffs(values, period, self.max_seasonal_d)?
+            }
+            _ => 0,
+        };
+
+        let mut series = values.to_vec();
+        if let Some(period) = self.seasonal_period {
+            for _ in 0..seasonal_d {
+                series = difference(&series, period);
+            }
+        }
+        let d = ndiffs(&series, self.unit_root_test, self.significance, self.max_d)?;
+        Ok((d, seasonal_d))
+    }
+
+    /// Select and fit a regression with ARIMA errors
+    ///
+    /// Differencing orders are chosen from the residuals of a least-squares
+    /// regression of `values` on the regressors.
+    pub fn fit_with_exogenous(
+        &self,
+        values: &[f64],
+        exogenous: &[Vec<f64>],
+    ) -> Result<FittedArima> {
+        let rows: Vec<Vec<f64>> = exogenous
+            .iter()
+            .map(|row| std::iter::once(1.0).chain(row.iter().copied()).collect())
+            .collect();
+        let residuals = least_squares(&rows, values)?.residuals().to_vec();
+        self.search(values, &residuals, Some(exogenous))
+    }
+
+    fn search(
+        &self,
+        values: &[f64],
+        differencing_basis: &[f64],
+        exogenous: Option<&[Vec<f64>]>,
+    ) -> Result<FittedArima> {
+        let (d, seasonal_d) = self.differencing_orders(differencing_basis)?;
+        let constant_allowed = d + seasonal_d <= 1;
+        let seasonal = self.seasonal_period.is_some();
+
+        let mut search = Search {
+            auto: self,
+            values,
+            exogenous,
+            d,
+            seasonal_d,
+            visited: HashSet::new(),
+            best: None,
+        };
+
+        if self.stepwise {
+            let (sp, sq) = if seasonal { (1, 1) } else { (0, 0) };
+            let starts = [(2, 2, sp, sq), (0, 0, 0, 0), (1, 0, sp, 0), (0, 1, 0, sq)];
+            for (p, q, seasonal_p, seasonal_q) in starts {
+                search.consider(Candidate {
+                    p: p.min(self.max_p),
+                    q: q.min(self.max_q),
+                    seasonal_p: seasonal_p.min(self.max_seasonal_p),
+                    seasonal_q: seasonal_q.min(self.max_seasonal_q),
+                    constant: constant_allowed,
+                });
+            }
+
+            // Move to the first improving neighbour until none improves
+            while let Some(current) = search.best.as_ref().map(|(_, _, c)| *c) {
+                if search.visited.len() >= MAX_STEPWISE_MODELS {
+                    break;
+                }
+                let improved = self
+                    .neighbours(current, constant_allowed)
+                    .into_iter()
+                    .any(|candidate| search.consider(candidate));
+                if !improved {
+                    break;
+                }
+            }
+        } else {
+            let (max_sp, max_sq) = if seasonal {
+                (self.max_seasonal_p, self.max_seasonal_q)
+            } else {
+                (0, 0)
+            };
+            let constants: &[bool] = if constant_allowed {
+                &[true, false]
+            } else {
+                &[false]
+            };
+            for p in 0..=self.max_p {
+                for q in 0..=self.max_q {
+                    for seasonal_p in 0..=max_sp {
+                        for seasonal_q in 0..=max_sq {
+                            for &constant in constants {
+                                search.consider(Candidate {
+                                    p,
+                                    q,
+                                    seasonal_p,
+                                    seasonal_q,
+                                    constant,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        search.best.map(|(_, fitted, _)| fitted).ok_or_else(|| {
+            ForecastError::ForecastingError("No ARIMA model could be fitted".to_string())
+        })
+    }
+
+    /// Stepwise neighbours: each order moved by one, p and q (or P and Q)
+    /// moved together, and the constant toggled
+    fn neighbours(&self, c: Candidate, constant_allowed: bool) -> Vec<Candidate> {
+        let steps: [(isize, isize, isize, isize); 12] = [
+            (-1, 0, 0, 0),
+            (1, 0, 0, 0),
+            (0, -1, 0, 0),
+            (0, 1, 0, 0),
+            (-1, -1, 0, 0),
+            (1, 1, 0, 0),
+            (0, 0, -1, 0),
+            (0, 0, 1, 0),
+            (0, 0, 0, -1),
+            (0, 0, 0, 1),
+            (0, 0, -1, -1),
+            (0, 0, 1, 1),
+        ];
+        let seasonal = self.seasonal_period.is_some();
+        let shift = |value: usize, by: isize, max: usize| {
+            value
+                .checked_add_signed(by)
+                .filter(|&shifted| shifted <= max)
+        };
+
+        let mut neighbours: Vec<Candidate> = steps
+            .iter()
+            .filter(|(_, _, dsp, dsq)| seasonal || (*dsp == 0 && *dsq == 0))
+            .filter_map(|&(dp, dq, dsp, dsq)| {
+                Some(Candidate {
+                    p: shift(c.p, dp, self.max_p)?,
+                    q: shift(c.q, dq, self.max_q)?,
+                    seasonal_p: shift(c.seasonal_p, dsp, self.max_seasonal_p)?,
+                    seasonal_q: shift(c.seasonal_q, dsq, self.max_seasonal_q)?,
+                    constant: c.constant,
+                })
+            })
+            .collect();
+        if constant_allowed {
+            neighbours.push(Candidate {
+                constant: !c.constant,
+                ..c
+            });
+        }
+        neighbours
+    }
+}
+
+impl ForecastModel for AutoArima {
+    type Fitted = FittedArima;
+
+    fn name(&self) -> &str {
+        "Auto ARIMA"
+    }
+
+    fn fit_series(&self, values: &[f64]) -> Result<FittedArima> {
+        self.search(values, values, None)
+    }
+}
+
+/// Select and fit an ARIMA model with the default search settings
+pub fn auto_arima(values: &[f64]) -> Result<FittedArima> {
+    AutoArima::default().fit_series(values)
+}
+
+/// State of one order search
+struct Search<'a> {
+    auto: &'a AutoArima,
+    values: &'a [f64],
+    exogenous: Option<&'a [Vec<f64>]>,
+    d: usize,
+    seasonal_d: usize,
+    visited: HashSet<Candidate>,
+    best: Option<(f64, FittedArima, Candidate)>,
+}
+
+impl Search<'_> {
+    /// Fit a candidate if it is new and admissible; returns whether it
+    /// became the best model so far
+    fn consider(&mut self, candidate: Candidate) -> bool {
+        let order = candidate.p + candidate.q + candidate.seasonal_p + candidate.seasonal_q;
+        if order > self.auto.max_order || !self.visited.insert(candidate) {
+            return false;
+        }
+
+        let Ok(fitted) = self.fit(candidate) else {
+            return false;
+        };
+        let score = fitted.information_criterion(self.auto.criterion);
+        if !score.is_finite() {
+            return false;
+        }
+
+        let improves = self.best.as_ref().is_none_or(|(best, _, _)| score < *best);
+        if improves {
+            self.best = Some((score, fitted, candidate));
+        }
+        improves
+    }
+
+    fn fit(&self, candidate: Candidate) -> Result<FittedArima> {
+        let mut model = ArimaModel::new(candidate.p, self.d, candidate.q)?
+            .with_method(self.auto.method)
+            .with_constant(candidate.constant);
+        if let Some(period) = self.auto.seasonal_period {
+            model = model.with_seasonal(
+                candidate.seasonal_p,
+                self.seasonal_d,
+                candidate.seasonal_q,
+                period,
+            )?;
+        }
+
+        match self.exogenous {
+            Some(exogenous) => model.fit_with_exogenous(self.values, exogenous),
+            None => model.fit_series(self.values),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::FittedModel;
+    use crate::utils::tests::noise;
+
+    #[test]
+    fn test_selects_stationary_ar() {
+        let e = noise(400, 21);
+        let mut series = vec![50.0];
+        for t in 1..400 {
+            series.push(50.0 + 0.5 * (series[t - 1] - 50.0) + e[t]);
+        }
+
+        let fitted = auto_arima(&series).unwrap();
+        let (p, d, _) = fitted.specification().order();
+        assert_eq!(d, 0);
+        assert!(p >= 1);
+        assert!((fitted.intercept() - 50.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_differences_random_walk() {
+        let series: Vec<f64> = noise(300, 12)
+            .iter()
+            .scan(100.0, |level, e| {
+                *level += e;
+                Some(*level)
+            })
+            .collect();
+
+        let fitted = AutoArima::new()
+            .with_unit_root_test(StationarityTestKind::Adf, 0.05)
+            .unwrap()
+            .fit_series(&series)
+            .unwrap();
+        assert_eq!(fitted.specification().order().1, 1);
+        assert_eq!(fitted.forecast(5).unwrap().horizons(), 5);
+    }
+
+    #[test]
+    fn test_stepwise_matches_exhaustive_on_simple_series() {
+        let e = noise(300, 13);
+        let series: Vec<f64> = (0..300)
+            .map(|t| e[t] + if t > 0 { 0.6 * e[t - 1] } else { 0.0 })
+            .collect();
+
+        let search = AutoArima::new().with_max_orders(2, 2).with_max_order(3);
+        let stepwise = search.clone().fit_series(&series).unwrap();
+        let exhaustive = search.with_stepwise(false).fit_series(&series).unwrap();
+
+        assert!(stepwise.aicc() >= exhaustive.aicc() - 1e-9);
+        assert!(exhaustive.specification().order().2 >= 1);
+    }
+
+    #[test]
+    fn test_seasonal_search() {
+        let e = noise(240, 14);
+        let pattern = [3.0, -1.0, 2.0, -4.0];
+        let series: Vec<f64> = (0..240)
+            .map(|t| 20.0 + pattern[t % 4] + 0.3 * e[t])
+            .collect();
+
+        let search = AutoArima::new().with_seasonal_period(4).unwrap();
+        assert_eq!(search.differencing_orders(&series).unwrap(), (0, 1));
+
+        let fitted = search.fit_series(&series).unwrap();
+        let seasonal = fitted.specification().seasonal_order().unwrap();
+        assert_eq!((seasonal.d, seasonal.period), (1, 4));
+
+        let forecast = fitted.forecast(4).unwrap();
+        for (h, value) in forecast.values().iter().enumerate() {
+            assert!((value - (20.0 + pattern[(240 + h) % 4])).abs() < 1.0);
+        }
+    }
+
+    #[test]
+    fn test_exogenous_search() {
+        let x: Vec<Vec<f64>> = noise(300, 15).iter().map(|v| vec![*v]).collect();
+        let e = noise(300, 16);
+        let series: Vec<f64> = (0..300).map(|t| 1.5 * x[t][0] + 0.5 * e[t]).collect();
+
+        let fitted = AutoArima::new().fit_with_exogenous(&series, &x).unwrap();
+        assert!((fitted.exogenous_coefficients()[0] - 1.5).abs() < 0.1);
+    }
+}
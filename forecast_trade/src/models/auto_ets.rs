@@ -0,0 +1,196 @@
+//! Automatic ETS model selection
+//!
+//! Fits every admissible combination of error, trend and seasonal
+//! components and keeps the one minimising an information criterion.
+//! Multiplicative components are only tried on strictly positive data, and
+//! additive errors are not combined with multiplicative seasonality, whose
+//! likelihood is numerically unstable.
+
+use crate::error::{ForecastError, Result};
+use crate::models::ets::{ErrorType, EtsModel, FittedEts, SeasonalType, TrendType};
+use crate::models::{ForecastModel, InformationCriterion};
+
+/// ETS model whose components are selected from the data when fitted
+#[derive(Debug, Clone)]
+pub struct AutoEts {
+    seasonal_period: Option<usize>,
+    criterion: InformationCriterion,
+    allow_multiplicative: bool,
+    allow_damped: bool,
+}
+
+impl Default for AutoEts {
+    fn default() -> Self {
+        Self {
+            seasonal_period: None,
+            criterion: InformationCriterion::default(),
+            allow_multiplicative: true,
+            allow_damped: true,
+        }
+    }
+}
+
+impl AutoEts {
+    /// Create a search over all non-seasonal models, selected by AICc
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also consider seasonal models with this season length, e.g. 390 for
+    /// the minutes of a regular US equity session
+    pub fn with_seasonal_period(mut self, period: usize) -> Result<Self> {
+        if period < 2 {
+            return Err(ForecastError::InvalidParameter(
+                "Seasonal period must be at least 2".to_string(),
+            ));
+        }
+        self.seasonal_period = Some(period);
+        Ok(self)
+    }
+
+    /// Set the criterion used to compare candidate models
+    pub fn with_criterion(mut self, criterion: InformationCriterion) -> Self {
+        self.criterion = criterion;
+        self
+    }
+
+    /// Whether multiplicative errors and seasonality are considered
+    pub fn with_multiplicative(mut self, allow: bool) -> Self {
+        self.allow_multiplicative = allow;
+        self
+    }
+
+    /// Whether damped trends are considered
+    pub fn with_damped(mut self, allow: bool) -> Self {
+        self.allow_damped = allow;
+        self
+    }
+
+    /// Candidate models for a series
+    pub fn candidates(&self, values: &[f64]) -> Result<Vec<EtsModel>> {
+        let multiplicative = self.allow_multiplicative && values.iter().all(|&y| y > 0.0);
+
+        let mut errors = vec![ErrorType::Additive];
+        let mut trends = vec![TrendType::None, TrendType::Additive];
+        let mut seasonals = vec![SeasonalType::None];
+        if multiplicative {
+            errors.push(ErrorType::Multiplicative);
+        }
+        if self.allow_damped {
+            trends.push(TrendType::Damped);
+        }
+        if self.seasonal_period.is_some() {
+            seasonals.push(SeasonalType::Additive);
+            if multiplicative {
+                seasonals.push(SeasonalType::Multiplicative);
+            }
+        }
+
+        let period = self.seasonal_period.unwrap_or(1);
+        let mut models = Vec::new();
+        for &error in &errors {
+            for &trend in &trends {
+                for &seasonal in &seasonals {
+                    if error == ErrorType::Additive && seasonal == SeasonalType::Multiplicative {
+                        continue;
+                    }
+                    let model = EtsModel::new(error, trend, seasonal, period)?;
+                    if values.len() >= model.min_observations() {
+                        models.push(model);
+                    }
+                }
+            }
+        }
+        Ok(models)
+    }
+}
+
+impl ForecastModel for AutoEts {
+    type Fitted = FittedEts;
+
+    fn name(&self) -> &str {
+        "Auto ETS"
+    }
+
+    fn fit_series(&self, values: &[f64]) -> Result<FittedEts> {
+        let mut best: Option<(f64, FittedEts)> = None;
+        for model in self.candidates(values)? {
+            // Candidates that fail to fit are skipped
+            let Ok(fitted) = model.fit_series(values) else {
+                continue;
+            };
+            let score = fitted.information_criterion(self.criterion);
+            if score.is_finite() && best.as_ref().is_none_or(|(s, _)| score < *s) {
+                best = Some((score, fitted));
+            }
+        }
+
+        best.map(|(_, fitted)| fitted).ok_or_else(|| {
+            ForecastError::ForecastingError("No ETS model could be fitted".to_string())
+        })
+    }
+}
+
+/// Select and fit an ETS model, seasonal with `period` if given
+pub fn auto_ets(values: &[f64], period: Option<usize>) -> Result<FittedEts> {
+    let search = match period {
+        Some(period) => AutoEts::new().with_seasonal_period(period)?,
+        None => AutoEts::new(),
+    };
+    search.fit_series(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::FittedModel;
+    use crate::utils::tests::noise;
+
+    #[test]
+    fn test_selects_seasonal_model_for_intraday_pattern() {
+        // Eight bars a session with a U-shaped intraday profile
+        let profile = [1.3, 1.0, 0.8, 0.7, 0.7, 0.8, 1.0, 1.4];
+        let series: Vec<f64> = noise(160, 21)
+            .iter()
+            .enumerate()
+            .map(|(t, e)| 50.0 * profile[t % 8] * (1.0 + 0.02 * e))
+            .collect();
+
+        let fitted = auto_ets(&series, Some(8)).unwrap();
+        let spec = fitted.specification();
+        assert_eq!(spec.seasonal_type(), SeasonalType::Multiplicative);
+        assert_eq!(spec.period(), 8);
+
+        let forecast = fitted.forecast(8).unwrap();
+        assert!(forecast.values()[7] > forecast.values()[3]);
+    }
+
+    #[test]
+    fn test_selects_trend_for_trending_series() {
+        let series: Vec<f64> = noise(120, 5)
+            .iter()
+            .enumerate()
+            .map(|(t, e)| 10.0 + 0.8 * t as f64 + e)
+            .collect();
+
+        let fitted = AutoEts::new()
+            .with_multiplicative(false)
+            .fit_series(&series)
+            .unwrap();
+        assert_ne!(fitted.specification().trend_type(), TrendType::None);
+        assert!(fitted.forecast(1).unwrap().values()[0] > series[119] - 3.0);
+    }
+
+    #[test]
+    fn test_candidates() {
+        let positive = vec![1.0; 40];
+        assert_eq!(AutoEts::new().candidates(&positive).unwrap().len(), 6);
+        let seasonal = AutoEts::new().with_seasonal_period(4).unwrap();
+        // A,M errors x N,A,Ad trends x N,A,M seasons without (A, *, M)
+        assert_eq!(seasonal.candidates(&positive).unwrap().len(), 15);
+
+        let negative = vec![-1.0; 40];
+        assert_eq!(seasonal.candidates(&negative).unwrap().len(), 6);
+        assert!(AutoEts::new().with_seasonal_period(1).is_err());
+    }
+}
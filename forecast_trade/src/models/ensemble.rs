@@ -0,0 +1,550 @@
+//! Forecast combinations of several models
+//!
+//! An `EnsembleModel` holds models of different types, fits each of them to
+//! the same series and combines their forecasts. It implements
+//! `ForecastModel` itself, so the strategies can use it like any single
+//! model.
+//!
+//! Inverse-error and stacking weights are estimated out of sample: each
+//! member is refitted at every origin of a holdout at the end of the
+//! series (see `validation::CrossValidation`) and forecasts the next
+//! observation. The weights are kept fixed when the ensemble is updated.
+
+use std::fmt::Debug;
+
+use crate::error::{ForecastError, Result};
+use crate::linalg::least_squares;
+use crate::models::{FittedModel, ForecastDistribution, ForecastModel, ForecastResult};
+use crate::validation::CrossValidation;
+
+/// How member forecasts are combined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CombinationMethod {
+    /// Simple average
+    #[default]
+    Equal,
+    /// Weights proportional to the inverse mean squared error of the
+    /// holdout forecasts
+    InverseError,
+    /// Least-squares regression of the holdout observations on the member
+    /// forecasts, with an intercept
+    Stacking,
+    /// Median of the member forecasts at each horizon
+    Median,
+}
+
+/// Object-safe view of a `ForecastModel`
+///
+/// Implemented for every forecast model, so that models of different types
+/// can be stored together.
+pub trait DynForecastModel: Debug {
+    /// Name of the model
+    fn name(&self) -> &str;
+
+    /// Fit the model to a raw series
+    fn fit_dyn(&self, values: &[f64]) -> Result<Box<dyn FittedModel>>;
+
+    /// Clone into a new box
+    fn clone_dyn(&self) -> Box<dyn DynForecastModel>;
+}
+
+impl<M> DynForecastModel for M
+where
+    M: ForecastModel + 'static,
+    M::Fitted: 'static,
+{
+    fn name(&self) -> &str {
+        ForecastModel::name(self)
+    }
+
+    fn fit_dyn(&self, values: &[f64]) -> Result<Box<dyn FittedModel>> {
+        Ok(Box::new(self.fit_series(values)?))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn DynForecastModel> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn DynForecastModel> {
+    fn clone(&self) -> Self {
+        self.clone_dyn()
+    }
+}
+
+/// Combination of several forecast models
+#[derive(Debug, Clone)]
+pub struct EnsembleModel {
+    name: String,
+    members: Vec<Box<dyn DynForecastModel>>,
+    method: CombinationMethod,
+    /// Trailing observations forecast out of sample to estimate weights
+    holdout: Option<usize>,
+}
+
+impl EnsembleModel {
+    /// Create an empty ensemble combining with `method`
+    pub fn new(method: CombinationMethod) -> Self {
+        let name = match method {
+            CombinationMethod::Equal => "Equal-Weight Ensemble",
+            CombinationMethod::InverseError => "Inverse-Error Ensemble",
+            CombinationMethod::Stacking => "Stacked Ensemble",
+            CombinationMethod::Median => "Median Ensemble",
+        };
+        Self {
+            name: name.to_string(),
+            members: Vec::new(),
+            method,
+            holdout: None,
+        }
+    }
+
+    /// Add a member model
+    pub fn with_model<M>(mut self, model: M) -> Self
+    where
+        M: ForecastModel + 'static,
+        M::Fitted: 'static,
+    {
+        self.members.push(Box::new(model));
+        self
+    }
+
+    /// Estimate weights from forecasts of the last `holdout` observations
+    /// (a quarter of the series by default)
+    pub fn with_holdout(mut self, holdout: usize) -> Result<Self> {
+        if holdout < 2 {
+            return Err(ForecastError::InvalidParameter(
+                "Holdout must contain at least 2 observations".to_string(),
+            ));
+        }
+        self.holdout = Some(holdout);
+        Ok(self)
+    }
+
+    /// Combination method
+    pub fn method(&self) -> CombinationMethod {
+        self.method
+    }
+
+    /// Names of the member models
+    pub fn member_names(&self) -> Vec<&str> {
+        self.members.iter().map(|m| m.name()).collect()
+    }
+
+    /// One-step forecasts of the holdout observations by each member,
+    /// refitted on all observations before each of them, and the
+    /// observations themselves
+    fn holdout_forecasts(&self, values: &[f64]) -> Result<(Vec<Vec<f64>>, Vec<f64>)> {
+        let holdout = self.holdout.unwrap_or(values.len() / 4).max(2);
+        if holdout + 3 > values.len() {
+            return Err(ForecastError::ValidationError(format!(
+                "A holdout of {} observations leaves fewer than 3 to fit on",
+                holdout
+            )));
+        }
+
+        let origins = CrossValidation::new(values.len() - holdout, 1)?.origins(values.len());
+        let forecasts = self
+            .members
+            .iter()
+            .map(|m| {
+                origins
+                    .iter()
+                    .map(|&origin| Ok(m.fit_dyn(&values[..origin])?.forecast(1)?.values()[0]))
+                    .collect::<Result<Vec<f64>>>()
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok((forecasts, values[values.len() - holdout..].to_vec()))
+    }
+
+    /// Combination weights and intercept
+    fn estimate_weights(&self, values: &[f64]) -> Result<Combiner> {
+        let k = self.members.len();
+        let (forecasts, targets) = match self.method {
+            CombinationMethod::Equal => {
+                return Ok(Combiner::Linear {
+                    weights: vec![1.0 / k as f64; k],
+                    intercept: 0.0,
+                })
+            }
+            CombinationMethod::Median => return Ok(Combiner::Median),
+            CombinationMethod::InverseError | CombinationMethod::Stacking => {
+                self.holdout_forecasts(values)?
+            }
+        };
+
+        if self.method == CombinationMethod::InverseError {
+            let inverse: Vec<f64> = forecasts
+                .iter()
+                .map(|f| {
+                    let mse = f
+                        .iter()
+                        .zip(&targets)
+                        .map(|(f, y)| (y - f).powi(2))
+                        .sum::<f64>()
+                        / targets.len() as f64;
+                    1.0 / mse.max(f64::MIN_POSITIVE)
+                })
+                .collect();
+            let total: f64 = inverse.iter().sum();
+            return Ok(Combiner::Linear {
+                weights: inverse.iter().map(|w| w / total).collect(),
+                intercept: 0.0,
+            });
+        }
+
+        if targets.len() <= k + 1 {
+            return Err(ForecastError::ValidationError(format!(
+                "Stacking {} models needs a holdout of more than {} observations",
+                k,
+                k + 1
+            )));
+        }
+        let rows: Vec<Vec<f64>> = (0..targets.len())
+            .map(|t| {
+                std::iter::once(1.0)
+                    .chain(forecasts.iter().map(|f| f[t]))
+                    .collect()
+            })
+            .collect();
+        let fit = least_squares(&rows, &targets)?;
+        let coefficients = fit.coefficients();
+        Ok(Combiner::Linear {
+            weights: coefficients[1..].to_vec(),
+            intercept: coefficients[0],
+        })
+    }
+}
+
+impl ForecastModel for EnsembleModel {
+    type Fitted = FittedEnsemble;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn fit_series(&self, values: &[f64]) -> Result<FittedEnsemble> {
+        if self.members.is_empty() {
+            return Err(ForecastError::ValidationError(
+                "Ensemble has no member models".to_string(),
+            ));
+        }
+        if values.len() < 3 {
+            return Err(ForecastError::ValidationError(
+                "Ensemble needs at least 3 observations".to_string(),
+            ));
+        }
+
+        let members = self
+            .members
+            .iter()
+            .map(|m| m.fit_dyn(values))
+            .collect::<Result<Vec<_>>>()?;
+
+        // Members may predict fewer observations (e.g. after differencing);
+        // align them on the most recent ones
+        let aligned = members
+            .iter()
+            .map(|m| m.fitted_values().len())
+            .min()
+            .unwrap_or(0)
+            .min(values.len());
+        if aligned < 3 {
+            return Err(ForecastError::ValidationError(
+                "Members produced too few in-sample predictions".to_string(),
+            ));
+        }
+        let predictions: Vec<Vec<f64>> = members
+            .iter()
+            .map(|m| {
+                let fitted = m.fitted_values();
+                fitted[fitted.len() - aligned..].to_vec()
+            })
+            .collect();
+        let targets = &values[values.len() - aligned..];
+
+        let combiner = self.estimate_weights(values)?;
+        let fitted_values: Vec<f64> = (0..aligned)
+            .map(|t| {
+                let point: Vec<f64> = predictions.iter().map(|p| p[t]).collect();
+                combiner.combine(&point)
+            })
+            .collect();
+        let residuals = targets
+            .iter()
+            .zip(&fitted_values)
+            .map(|(y, f)| y - f)
+            .collect();
+
+        Ok(FittedEnsemble {
+            name: self.name.clone(),
+            members,
+            combiner,
+            fitted_values,
+            residuals,
+        })
+    }
+}
+
+/// How fitted members are combined
+#[derive(Debug, Clone)]
+enum Combiner {
+    Linear { weights: Vec<f64>, intercept: f64 },
+    Median,
+}
+
+impl Combiner {
+    fn combine(&self, values: &[f64]) -> f64 {
+        match self {
+            Self::Linear { weights, intercept } => {
+                intercept + weights.iter().zip(values).map(|(w, v)| w * v).sum::<f64>()
+            }
+            Self::Median => {
+                let mut sorted = values.to_vec();
+                sorted.sort_by(f64::total_cmp);
+                let mid = sorted.len() / 2;
+                if sorted.len().is_multiple_of(2) {
+                    (sorted[mid - 1] + sorted[mid]) / 2.0
+                } else {
+                    sorted[mid]
+                }
+            }
+        }
+    }
+}
+
+/// Ensemble fitted to a series
+#[derive(Debug)]
+pub struct FittedEnsemble {
+    name: String,
+    members: Vec<Box<dyn FittedModel>>,
+    combiner: Combiner,
+    fitted_values: Vec<f64>,
+    residuals: Vec<f64>,
+}
+
+impl FittedEnsemble {
+    /// Fitted member models
+    pub fn members(&self) -> &[Box<dyn FittedModel>] {
+        &self.members
+    }
+
+    /// Combination weights, one per member (`None` for the median)
+    pub fn weights(&self) -> Option<&[f64]> {
+        match &self.combiner {
+            Combiner::Linear { weights, .. } => Some(weights),
+            Combiner::Median => None,
+        }
+    }
+
+    /// Intercept of the combination (non-zero only for stacking)
+    pub fn intercept(&self) -> f64 {
+        match &self.combiner {
+            Combiner::Linear { intercept, .. } => *intercept,
+            Combiner::Median => 0.0,
+        }
+    }
+}
+
+impl FittedModel for FittedEnsemble {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Combined forecasts
+    ///
+    /// For linear combinations whose members all report standard errors,
+    /// the result carries `sum |w_i| se_i`, which assumes perfectly
+    /// correlated member errors and so errs on the wide side.
+    fn forecast(&self, horizon: usize) -> Result<ForecastResult> {
+        let forecasts = self
+            .members
+            .iter()
+            .map(|m| m.forecast(horizon))
+            .collect::<Result<Vec<_>>>()?;
+
+        let values = (0..horizon)
+            .map(|h| {
+                let point: Vec<f64> = forecasts.iter().map(|f| f.values()[h]).collect();
+                self.combiner.combine(&point)
+            })
+            .collect();
+        let result = ForecastResult::new(values, horizon)?;
+
+        let errors: Option<Vec<Vec<f64>>> = forecasts.iter().map(|f| f.standard_errors()).collect();
+        match (&self.combiner, errors) {
+            (Combiner::Linear { weights, .. }, Some(errors)) => {
+                let combined = (0..horizon)
+                    .map(|h| {
+                        weights
+                            .iter()
+                            .zip(&errors)
+                            .map(|(w, se)| w.abs() * se[h])
+                            .sum()
+                    })
+                    .collect();
+                result.with_standard_errors(combined, ForecastDistribution::Normal)
+            }
+            _ => Ok(result),
+        }
+    }
+
+    fn update(&mut self, observations: &[f64]) -> Result<()> {
+        for &value in observations {
+            let point = self
+                .members
+                .iter()
+                .map(|m| Ok(m.forecast(1)?.values()[0]))
+                .collect::<Result<Vec<f64>>>()?;
+            let prediction = self.combiner.combine(&point);
+
+            for member in &mut self.members {
+                member.update(&[value])?;
+            }
+            self.fitted_values.push(prediction);
+            self.residuals.push(value - prediction);
+        }
+        Ok(())
+    }
+
+    fn fitted_values(&self) -> &[f64] {
+        &self.fitted_values
+    }
+
+    fn residuals(&self) -> &[f64] {
+        &self.residuals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::arima::ArimaModel;
+    use crate::models::exponential_smoothing::ExponentialSmoothing;
+    use crate::models::moving_average::MovingAverage;
+
+    fn series() -> Vec<f64> {
+        (0..80)
+            .map(|i| 100.0 + 0.5 * i as f64 + 3.0 * (i as f64 * 0.7).sin())
+            .collect()
+    }
+
+    fn members(method: CombinationMethod) -> EnsembleModel {
+        EnsembleModel::new(method)
+            .with_model(ExponentialSmoothing::new(0.5).unwrap())
+            .with_model(MovingAverage::new(10).unwrap())
+            .with_model(ArimaModel::new(1, 1, 0).unwrap())
+    }
+
+    #[test]
+    fn test_equal_weights_average_members() {
+        let data = series();
+        let fitted = members(CombinationMethod::Equal).fit_series(&data).unwrap();
+        assert_eq!(fitted.members().len(), 3);
+
+        let forecast = fitted.forecast(3).unwrap();
+        let average: f64 = fitted
+            .members()
+            .iter()
+            .map(|m| m.forecast(3).unwrap().values()[2])
+            .sum::<f64>()
+            / 3.0;
+        assert!((forecast.values()[2] - average).abs() < 1e-9);
+        assert!(forecast.standard_errors().is_some());
+    }
+
+    #[test]
+    fn test_inverse_error_favours_accurate_member() {
+        let data = series();
+        let fitted = members(CombinationMethod::InverseError)
+            .with_holdout(20)
+            .unwrap()
+            .fit_series(&data)
+            .unwrap();
+
+        // The lagging 10-period average is the least accurate on a trend
+        let weights = fitted.weights().unwrap();
+        assert!((weights.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!(weights[1] < weights[0] && weights[1] < weights[2]);
+    }
+
+    #[test]
+    fn test_weights_use_out_of_sample_forecasts() {
+        let data = series();
+        let fitted = members(CombinationMethod::InverseError)
+            .with_holdout(10)
+            .unwrap()
+            .fit_series(&data)
+            .unwrap();
+
+        // Same folds as cross-validating each member on the last 10 values
+        let cv = CrossValidation::new(70, 1).unwrap();
+        let inverse = [
+            cv.evaluate(&ExponentialSmoothing::new(0.5).unwrap(), &data),
+            cv.evaluate(&MovingAverage::new(10).unwrap(), &data),
+            cv.evaluate(&ArimaModel::new(1, 1, 0).unwrap(), &data),
+        ]
+        .map(|result| 1.0 / result.unwrap().horizon(1).unwrap().rmse.powi(2));
+        let total: f64 = inverse.iter().sum();
+        for (weight, expected) in fitted.weights().unwrap().iter().zip(inverse) {
+            assert!((weight - expected / total).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_stacking_beats_equal_weights_out_of_sample() {
+        let data = series();
+        // Residuals after fitting are one-step forecast errors
+        let mse = |method: CombinationMethod| {
+            let mut fitted = members(method).fit_series(&data[..60]).unwrap();
+            fitted.update(&data[60..]).unwrap();
+            let r = &fitted.residuals()[60..];
+            r.iter().map(|e| e * e).sum::<f64>() / r.len() as f64
+        };
+
+        let stacked = members(CombinationMethod::Stacking)
+            .fit_series(&data)
+            .unwrap();
+        assert_eq!(stacked.weights().unwrap().len(), 3);
+        assert!(mse(CombinationMethod::Stacking) < mse(CombinationMethod::Equal));
+    }
+
+    #[test]
+    fn test_median_and_update() {
+        let data = series();
+        let mut fitted = members(CombinationMethod::Median)
+            .fit_series(&data[..70])
+            .unwrap();
+        let points: Vec<f64> = fitted
+            .members()
+            .iter()
+            .map(|m| m.forecast(1).unwrap().values()[0])
+            .collect();
+        let mut sorted = points.clone();
+        sorted.sort_by(f64::total_cmp);
+
+        let forecast = fitted.forecast(1).unwrap();
+        assert_eq!(forecast.values()[0], sorted[1]);
+        assert!(fitted.weights().is_none());
+
+        fitted.update(&data[70..]).unwrap();
+        assert_eq!(fitted.residuals().len(), 80);
+        assert_eq!(fitted.fitted_values()[70], sorted[1]);
+    }
+
+    #[test]
+    fn test_validation() {
+        let empty = EnsembleModel::new(CombinationMethod::Equal);
+        assert!(empty.fit_series(&series()).is_err());
+        assert!(members(CombinationMethod::Equal).with_holdout(1).is_err());
+        assert!(members(CombinationMethod::Stacking)
+            .with_holdout(4)
+            .unwrap()
+            .fit_series(&series())
+            .is_err());
+        assert_eq!(
+            members(CombinationMethod::Median).member_names(),
+            vec!["Exponential Smoothing", "Moving Average", "ARIMA(1,1,0)"]
+        );
+    }
+}
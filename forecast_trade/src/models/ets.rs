@@ -0,0 +1,889 @@
+//! ETS (error, trend, seasonal) exponential smoothing models
+//!
+//! Covers the innovations state space models of Hyndman et al. with additive
+//! or multiplicative errors, no, additive or damped trend, and no, additive
+//! or multiplicative seasonality. Holt-Winters is ETS(A,A,A) or ETS(M,A,M).
+//!
+//! Smoothing parameters and the initial level and trend are estimated by
+//! maximum likelihood; the initial seasonal states come from the first two
+//! seasons. Models with additive errors and no multiplicative seasonality
+//! have analytic prediction intervals; the others are simulated.
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rand_distr::{Distribution, Normal};
+use std::f64::consts::PI;
+
+use crate::error::{ForecastError, Result};
+use crate::models::{FittedModel, ForecastModel, ForecastResult, InformationCriterion};
+use crate::optimization::NelderMead;
+use crate::utils::DEFAULT_SEED;
+
+/// Bounds on the damping parameter
+const MIN_PHI: f64 = 0.8;
+const MAX_PHI: f64 = 0.98;
+
+/// Smallest smoothing parameter considered
+const MIN_SMOOTHING: f64 = 1e-4;
+
+/// Simulated paths behind the prediction intervals of non-linear models
+const DEFAULT_SIMULATION_PATHS: usize = 1000;
+
+/// Form of the error term
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorType {
+    /// Errors add to the one-step forecast
+    #[default]
+    Additive,
+    /// Errors are relative to the one-step forecast
+    Multiplicative,
+}
+
+/// Form of the trend component
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TrendType {
+    /// No trend
+    #[default]
+    None,
+    /// Linear trend
+    Additive,
+    /// Linear trend damped towards a flat line
+    Damped,
+}
+
+/// Form of the seasonal component
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SeasonalType {
+    /// No seasonality
+    #[default]
+    None,
+    /// Seasonal effects add to the level
+    Additive,
+    /// Seasonal effects scale the level
+    Multiplicative,
+}
+
+/// Estimated smoothing parameters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SmoothingParameters {
+    /// Level smoothing
+    pub alpha: f64,
+    /// Trend smoothing, if the model has a trend
+    pub beta: Option<f64>,
+    /// Seasonal smoothing, if the model is seasonal
+    pub gamma: Option<f64>,
+    /// Damping, if the trend is damped
+    pub phi: Option<f64>,
+}
+
+/// ETS model specification
+#[derive(Debug, Clone)]
+pub struct EtsModel {
+    name: String,
+    error: ErrorType,
+    trend: TrendType,
+    seasonal: SeasonalType,
+    /// Season length (1 without seasonality)
+    period: usize,
+    simulation_paths: usize,
+    seed: u64,
+}
+
+impl EtsModel {
+    /// Create an ETS model; `period` is the season length and is ignored
+    /// without seasonality
+    pub fn new(
+        error: ErrorType,
+        trend: TrendType,
+        seasonal: SeasonalType,
+        period: usize,
+    ) -> Result<Self> {
+        if seasonal != SeasonalType::None && period < 2 {
+            return Err(ForecastError::InvalidParameter(
+                "Seasonal period must be at least 2".to_string(),
+            ));
+        }
+        let period = if seasonal == SeasonalType::None {
+            1
+        } else {
+            period
+        };
+
+        let name = format!(
+            "ETS({},{},{})",
+            if error == ErrorType::Additive {
+                "A"
+            } else {
+                "M"
+            },
+            match trend {
+                TrendType::None => "N",
+                TrendType::Additive => "A",
+                TrendType::Damped => "Ad",
+            },
+            match seasonal {
+                SeasonalType::None => "N",
+                SeasonalType::Additive => "A",
+                SeasonalType::Multiplicative => "M",
+            }
+        );
+
+        Ok(Self {
+            name,
+            error,
+            trend,
+            seasonal,
+            period,
+            simulation_paths: DEFAULT_SIMULATION_PATHS,
+            seed: DEFAULT_SEED,
+        })
+    }
+
+    /// Additive Holt-Winters, ETS(A,A,A)
+    pub fn holt_winters_additive(period: usize) -> Result<Self> {
+        Self::new(
+            ErrorType::Additive,
+            TrendType::Additive,
+            SeasonalType::Additive,
+            period,
+        )
+    }
+
+    /// Multiplicative Holt-Winters, ETS(M,A,M)
+    pub fn holt_winters_multiplicative(period: usize) -> Result<Self> {
+        Self::new(
+            ErrorType::Multiplicative,
+            TrendType::Additive,
+            SeasonalType::Multiplicative,
+            period,
+        )
+    }
+
+    /// Simulate `paths` future paths from `seed` for prediction intervals of
+    /// models without analytic ones
+    pub fn with_simulation(mut self, paths: usize, seed: u64) -> Result<Self> {
+        if paths == 0 {
+            return Err(ForecastError::InvalidParameter(
+                "Number of simulated paths must be greater than 0".to_string(),
+            ));
+        }
+        self.simulation_paths = paths;
+        self.seed = seed;
+        Ok(self)
+    }
+
+    /// Error type
+    pub fn error_type(&self) -> ErrorType {
+        self.error
+    }
+
+    /// Trend type
+    pub fn trend_type(&self) -> TrendType {
+        self.trend
+    }
+
+    /// Seasonal type
+    pub fn seasonal_type(&self) -> SeasonalType {
+        self.seasonal
+    }
+
+    /// Season length (1 without seasonality)
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Whether the model needs strictly positive data
+    pub fn is_multiplicative(&self) -> bool {
+        self.error == ErrorType::Multiplicative || self.seasonal == SeasonalType::Multiplicative
+    }
+
+    /// Estimated parameters, including initial states and the error variance
+    pub fn parameter_count(&self) -> usize {
+        let trend = usize::from(self.trend != TrendType::None);
+        let damped = usize::from(self.trend == TrendType::Damped);
+        let seasonal = usize::from(self.seasonal != SeasonalType::None);
+        // Smoothing, damping, initial level and trend, seasonal states, sigma2
+        (1 + trend + seasonal) + damped + (1 + trend) + seasonal * (self.period - 1) + 1
+    }
+
+    /// Fewest observations the model can be fitted to
+    pub fn min_observations(&self) -> usize {
+        let seasons = if self.seasonal == SeasonalType::None {
+            0
+        } else {
+            2 * self.period
+        };
+        seasons.max(self.parameter_count() + 2)
+    }
+
+    /// Heuristic initial level, trend and seasonal states
+    fn initial_states(&self, values: &[f64]) -> State {
+        let m = self.period;
+        let mut seasonal = Vec::new();
+        if self.seasonal != SeasonalType::None {
+            let means: Vec<f64> = (0..2)
+                .map(|j| values[j * m..(j + 1) * m].iter().sum::<f64>() / m as f64)
+                .collect();
+            // Deviations from the within-season trend implied by the means
+            let slope = (means[1] - means[0]) / m as f64;
+            seasonal = (0..m)
+                .map(|i| {
+                    (0..2)
+                        .map(|j| {
+                            let base = means[j] + slope * (i as f64 - (m - 1) as f64 / 2.0);
+                            match self.seasonal {
+                                SeasonalType::Multiplicative => values[j * m + i] / base,
+                                _ => values[j * m + i] - base,
+                            }
+                        })
+                        .sum::<f64>()
+                        / 2.0
+                })
+                .collect();
+            // Normalise to sum to zero or average one
+            let mean = seasonal.iter().sum::<f64>() / m as f64;
+            for s in &mut seasonal {
+                match self.seasonal {
+                    SeasonalType::Multiplicative => *s /= mean,
+                    _ => *s -= mean,
+                }
+            }
+        }
+
+        // Regress the first deseasonalised values on time
+        let k = values.len().min(10.max(m));
+        let deseasonalised: Vec<f64> = values[..k]
+            .iter()
+            .enumerate()
+            .map(|(t, y)| match self.seasonal {
+                SeasonalType::None => *y,
+                SeasonalType::Additive => y - seasonal[t % m],
+                SeasonalType::Multiplicative => y / seasonal[t % m],
+            })
+            .collect();
+        let mean_t = (k + 1) as f64 / 2.0;
+        let mean_y = deseasonalised.iter().sum::<f64>() / k as f64;
+        let (level, trend) = if self.trend == TrendType::None {
+            (mean_y, 0.0)
+        } else {
+            let (mut sxy, mut sxx) = (0.0, 0.0);
+            for (t, y) in deseasonalised.iter().enumerate() {
+                let dt = (t + 1) as f64 - mean_t;
+                sxy += dt * (y - mean_y);
+                sxx += dt * dt;
+            }
+            let slope = sxy / sxx;
+            (mean_y - slope * mean_t, slope)
+        };
+
+        State {
+            level,
+            trend,
+            seasonal,
+            t: 0,
+        }
+    }
+
+    /// Map an optimizer vector `[alpha, beta, gamma, phi, level, trend]`
+    /// (absent components skipped) to dynamics and initial state
+    fn unpack(&self, params: &[f64], template: &State) -> Option<(Dynamics, State)> {
+        let mut next = params.iter().copied();
+        let alpha = next.next()?;
+        let beta = if self.trend == TrendType::None {
+            0.0
+        } else {
+            next.next()?
+        };
+        let gamma = if self.seasonal == SeasonalType::None {
+            0.0
+        } else {
+            next.next()?
+        };
+        let phi = match self.trend {
+            TrendType::Damped => next.next()?,
+            _ => 1.0,
+        };
+
+        let admissible = (MIN_SMOOTHING..1.0 - MIN_SMOOTHING).contains(&alpha)
+            && (self.trend == TrendType::None || (MIN_SMOOTHING..alpha).contains(&beta))
+            && (self.seasonal == SeasonalType::None
+                || (MIN_SMOOTHING..1.0 - alpha).contains(&gamma))
+            && (self.trend != TrendType::Damped || (MIN_PHI..=MAX_PHI).contains(&phi));
+        if !admissible {
+            return None;
+        }
+
+        let mut state = template.clone();
+        state.level = next.next()?;
+        if self.trend != TrendType::None {
+            state.trend = next.next()?;
+        }
+
+        let dynamics = Dynamics {
+            error: self.error,
+            seasonal: self.seasonal,
+            period: self.period,
+            alpha,
+            beta,
+            gamma,
+            phi,
+        };
+        Some((dynamics, state))
+    }
+}
+
+impl ForecastModel for EtsModel {
+    type Fitted = FittedEts;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn fit_series(&self, values: &[f64]) -> Result<FittedEts> {
+        if values.len() < self.min_observations() {
+            return Err(ForecastError::ValidationError(format!(
+                "Insufficient data for {}. Need at least {} observations.",
+                self.name,
+                self.min_observations()
+            )));
+        }
+        if self.is_multiplicative() && values.iter().any(|&y| y <= 0.0) {
+            return Err(ForecastError::ValidationError(format!(
+                "{} needs strictly positive data",
+                self.name
+            )));
+        }
+
+        let initial = self.initial_states(values);
+        let scale = standard_deviation(values).max(1e-8 * initial.level.abs().max(1.0));
+
+        let mut start = vec![0.3];
+        let mut steps = vec![0.1];
+        if self.trend != TrendType::None {
+            start.push(0.03);
+            steps.push(0.02);
+        }
+        if self.seasonal != SeasonalType::None {
+            start.push(0.1);
+            steps.push(0.05);
+        }
+        if self.trend == TrendType::Damped {
+            start.push(0.95);
+            steps.push(-0.05);
+        }
+        start.push(initial.level);
+        steps.push(0.1 * scale);
+        if self.trend != TrendType::None {
+            start.push(initial.trend);
+            steps.push(0.01 * scale);
+        }
+
+        let n = values.len() as f64;
+        let objective = |params: &[f64]| {
+            self.unpack(params, &initial)
+                .and_then(|(dynamics, state)| dynamics.log_likelihood(state, values))
+                .map_or(f64::INFINITY, |(ll, _)| -ll / n)
+        };
+
+        let optimizer = NelderMead::new(4000, 1e-12)?;
+        let first = optimizer.minimize(objective, &start, &steps);
+        let best = optimizer.minimize(objective, &first.point, &steps);
+
+        let (dynamics, state) = self
+            .unpack(&best.point, &initial)
+            .filter(|(dynamics, state)| dynamics.log_likelihood(state.clone(), values).is_some())
+            .ok_or_else(|| {
+                ForecastError::ForecastingError(format!(
+                    "{} estimation did not find admissible parameters",
+                    self.name
+                ))
+            })?;
+
+        let mut fitted = FittedEts {
+            specification: self.clone(),
+            dynamics,
+            state,
+            sigma2: 0.0,
+            log_likelihood: -best.value * n,
+            fitted_values: Vec::with_capacity(values.len()),
+            residuals: Vec::with_capacity(values.len()),
+            scaled_errors: Vec::with_capacity(values.len()),
+        };
+        fitted.update(values)?;
+        let errors = &fitted.scaled_errors;
+        fitted.sigma2 = errors.iter().map(|e| e * e).sum::<f64>() / errors.len() as f64;
+
+        Ok(fitted)
+    }
+}
+
+/// Level, trend and seasonal states
+#[derive(Debug, Clone)]
+struct State {
+    level: f64,
+    trend: f64,
+    /// Seasonal states, indexed by time modulo the period
+    seasonal: Vec<f64>,
+    /// Number of observations processed
+    t: usize,
+}
+
+/// Smoothing recursions of a specified model
+#[derive(Debug, Clone)]
+struct Dynamics {
+    error: ErrorType,
+    seasonal: SeasonalType,
+    period: usize,
+    alpha: f64,
+    beta: f64,
+    gamma: f64,
+    phi: f64,
+}
+
+impl Dynamics {
+    /// Seasonal state applying `steps` periods after the last observation
+    fn season(&self, state: &State, steps: usize) -> f64 {
+        match self.seasonal {
+            SeasonalType::None => 0.0,
+            _ => state.seasonal[(state.t + steps - 1) % self.period],
+        }
+    }
+
+    /// Level plus the damped trend accumulated over `steps` periods
+    fn trend_path(&self, state: &State, steps: usize) -> f64 {
+        let damping: f64 = (1..=steps as i32).map(|i| self.phi.powi(i)).sum();
+        state.level + damping * state.trend
+    }
+
+    /// Point forecast `steps` periods ahead
+    fn forecast(&self, state: &State, steps: usize) -> f64 {
+        let base = self.trend_path(state, steps);
+        match self.seasonal {
+            SeasonalType::None => base,
+            SeasonalType::Additive => base + self.season(state, steps),
+            SeasonalType::Multiplicative => base * self.season(state, steps),
+        }
+    }
+
+    /// Process one observation; returns the one-step forecast, or `None` if
+    /// a multiplicative component breaks down
+    ///
+    /// The error-correction form with the raw error `y - forecast` is the
+    /// same for additive and multiplicative errors.
+    fn advance(&self, state: &mut State, y: f64) -> Option<f64> {
+        let base = self.trend_path(state, 1);
+        let season = self.season(state, 1);
+        let prediction = self.forecast(state, 1);
+        if !prediction.is_finite() || (self.error == ErrorType::Multiplicative && prediction <= 0.0)
+        {
+            return None;
+        }
+
+        let e = y - prediction;
+        let slot = state.t % self.period;
+        match self.seasonal {
+            SeasonalType::Multiplicative => {
+                if season <= 0.0 || base <= 0.0 {
+                    return None;
+                }
+                state.level = base + self.alpha * e / season;
+                state.trend = self.phi * state.trend + self.beta * e / season;
+                state.seasonal[slot] = season + self.gamma * e / base;
+            }
+            SeasonalType::Additive => {
+                state.level = base + self.alpha * e;
+                state.trend = self.phi * state.trend + self.beta * e;
+                state.seasonal[slot] = season + self.gamma * e;
+            }
+            SeasonalType::None => {
+                state.level = base + self.alpha * e;
+                state.trend = self.phi * state.trend + self.beta * e;
+            }
+        }
+        state.t += 1;
+        Some(prediction)
+    }
+
+    /// Innovation on the scale of the error distribution
+    fn scaled_error(&self, y: f64, prediction: f64) -> f64 {
+        match self.error {
+            ErrorType::Additive => y - prediction,
+            ErrorType::Multiplicative => (y - prediction) / prediction,
+        }
+    }
+
+    /// Concentrated Gaussian log-likelihood and error variance
+    fn log_likelihood(&self, mut state: State, values: &[f64]) -> Option<(f64, f64)> {
+        let mut sum_squares = 0.0;
+        let mut sum_log = 0.0;
+        for &y in values {
+            let prediction = self.advance(&mut state, y)?;
+            sum_squares += self.scaled_error(y, prediction).powi(2);
+            if self.error == ErrorType::Multiplicative {
+                sum_log += prediction.ln();
+            }
+        }
+
+        let n = values.len() as f64;
+        let sigma2 = sum_squares / n;
+        if !(sigma2 > 0.0 && sigma2.is_finite()) {
+            return None;
+        }
+        Some((
+            -0.5 * n * ((2.0 * PI * sigma2).ln() + 1.0) - sum_log,
+            sigma2,
+        ))
+    }
+}
+
+/// ETS model fitted to a series
+#[derive(Debug, Clone)]
+pub struct FittedEts {
+    specification: EtsModel,
+    dynamics: Dynamics,
+    state: State,
+    sigma2: f64,
+    log_likelihood: f64,
+    fitted_values: Vec<f64>,
+    residuals: Vec<f64>,
+    /// Innovations on the scale of the error distribution
+    scaled_errors: Vec<f64>,
+}
+
+impl FittedEts {
+    /// The configured model that was fitted
+    pub fn specification(&self) -> &EtsModel {
+        &self.specification
+    }
+
+    /// Estimated smoothing parameters
+    pub fn parameters(&self) -> SmoothingParameters {
+        let spec = &self.specification;
+        SmoothingParameters {
+            alpha: self.dynamics.alpha,
+            beta: (spec.trend != TrendType::None).then_some(self.dynamics.beta),
+            gamma: (spec.seasonal != SeasonalType::None).then_some(self.dynamics.gamma),
+            phi: (spec.trend == TrendType::Damped).then_some(self.dynamics.phi),
+        }
+    }
+
+    /// Current level
+    pub fn level(&self) -> f64 {
+        self.state.level
+    }
+
+    /// Current trend (zero without a trend)
+    pub fn trend(&self) -> f64 {
+        self.state.trend
+    }
+
+    /// Seasonal states for the next full season, starting with the next
+    /// period (empty without seasonality)
+    pub fn seasonal(&self) -> Vec<f64> {
+        (1..=self.state.seasonal.len())
+            .map(|steps| self.dynamics.season(&self.state, steps))
+            .collect()
+    }
+
+    /// Estimated variance of the innovations (relative for multiplicative
+    /// errors)
+    pub fn sigma2(&self) -> f64 {
+        self.sigma2
+    }
+
+    /// Maximised log-likelihood
+    pub fn log_likelihood(&self) -> f64 {
+        self.log_likelihood
+    }
+
+    /// Akaike information criterion
+    pub fn aic(&self) -> f64 {
+        self.information_criterion(InformationCriterion::Aic)
+    }
+
+    /// Small-sample corrected Akaike information criterion
+    pub fn aicc(&self) -> f64 {
+        self.information_criterion(InformationCriterion::Aicc)
+    }
+
+    /// Bayesian information criterion
+    pub fn bic(&self) -> f64 {
+        self.information_criterion(InformationCriterion::Bic)
+    }
+
+    /// Value of an information criterion for this fit
+    pub fn information_criterion(&self, criterion: InformationCriterion) -> f64 {
+        criterion.evaluate(
+            self.log_likelihood,
+            self.specification.parameter_count(),
+            self.scaled_errors.len(),
+        )
+    }
+
+    /// Analytic forecast error variances, for models with additive errors
+    /// and no multiplicative seasonality
+    ///
+    /// The `h`-step variance is `sigma2 * (1 + sum_{j < h} c_j^2)` with
+    /// `c_j = alpha + beta (phi + ... + phi^j) + gamma [j is a multiple of
+    /// the period]`.
+    pub fn forecast_variance(&self, horizon: usize) -> Option<Vec<f64>> {
+        let spec = &self.specification;
+        if spec.is_multiplicative() {
+            return None;
+        }
+
+        let d = &self.dynamics;
+        let mut total = 1.0;
+        let mut damping = 0.0;
+        let mut variances = Vec::with_capacity(horizon);
+        for j in 0..horizon {
+            if j > 0 {
+                damping += d.phi.powi(j as i32);
+                let seasonal = if spec.seasonal != SeasonalType::None && j % spec.period == 0 {
+                    d.gamma
+                } else {
+                    0.0
+                };
+                total += (d.alpha + d.beta * damping + seasonal).powi(2);
+            }
+            variances.push(self.sigma2 * total);
+        }
+        Some(variances)
+    }
+
+    /// Simulate `paths` future sample paths of `horizon` values
+    pub fn simulate(&self, horizon: usize, paths: usize, seed: u64) -> Vec<Vec<f64>> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let noise = Normal::new(0.0, self.sigma2.sqrt()).unwrap();
+
+        (0..paths)
+            .map(|_| {
+                let mut state = self.state.clone();
+                let mut path = Vec::with_capacity(horizon);
+                for _ in 0..horizon {
+                    let prediction = self.dynamics.forecast(&state, 1);
+                    let e: f64 = noise.sample(&mut rng);
+                    let y = match self.dynamics.error {
+                        ErrorType::Additive => prediction + e,
+                        ErrorType::Multiplicative => prediction * (1.0 + e),
+                    };
+                    // A path whose components break down stays flat
+                    if self.dynamics.advance(&mut state, y).is_none() {
+                        state.t += 1;
+                    }
+                    path.push(y);
+                }
+                path
+            })
+            .collect()
+    }
+}
+
+impl FittedModel for FittedEts {
+    fn name(&self) -> &str {
+        &self.specification.name
+    }
+
+    fn forecast(&self, horizon: usize) -> Result<ForecastResult> {
+        let values = (1..=horizon)
+            .map(|steps| self.dynamics.forecast(&self.state, steps))
+            .collect();
+        let result = ForecastResult::new(values, horizon)?;
+
+        match self.forecast_variance(horizon) {
+            Some(variances) => result.with_variances(variances),
+            None => {
+                let spec = &self.specification;
+                let paths = self.simulate(horizon, spec.simulation_paths, spec.seed);
+                let samples = (0..horizon)
+                    .map(|h| paths.iter().map(|path| path[h]).collect())
+                    .collect();
+                result.with_samples(samples)
+            }
+        }
+    }
+
+    fn update(&mut self, observations: &[f64]) -> Result<()> {
+        for &y in observations {
+            let prediction = self.dynamics.advance(&mut self.state, y).ok_or_else(|| {
+                ForecastError::MathError(format!(
+                    "{} produced a non-positive forecast",
+                    self.specification.name
+                ))
+            })?;
+            self.fitted_values.push(prediction);
+            self.residuals.push(y - prediction);
+            self.scaled_errors
+                .push(self.dynamics.scaled_error(y, prediction));
+        }
+        Ok(())
+    }
+
+    fn fitted_values(&self) -> &[f64] {
+        &self.fitted_values
+    }
+
+    fn residuals(&self) -> &[f64] {
+        &self.residuals
+    }
+}
+
+fn standard_deviation(values: &[f64]) -> f64 {
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::tests::noise;
+
+    /// Trend plus a repeating pattern, scaled (multiplicative) or shifted
+    fn seasonal_series(n: usize, multiplicative: bool, seed: u64) -> Vec<f64> {
+        let pattern = [1.2, 1.0, 0.7, 1.1];
+        noise(n, seed)
+            .iter()
+            .enumerate()
+            .map(|(t, e)| {
+                let base = 100.0 + 0.5 * t as f64;
+                let s = pattern[t % 4];
+                if multiplicative {
+                    base * s * (1.0 + 0.01 * e)
+                } else {
+                    base + 20.0 * (s - 1.0) + e
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_simple_model_smooths_level() {
+        let e = noise(300, 4);
+        let mut level = 50.0;
+        let series: Vec<f64> = e
+            .windows(2)
+            .map(|w| {
+                level += 0.4 * w[0];
+                level + w[1]
+            })
+            .collect();
+
+        let model =
+            EtsModel::new(ErrorType::Additive, TrendType::None, SeasonalType::None, 1).unwrap();
+        assert_eq!(model.name(), "ETS(A,N,N)");
+        let fitted = model.fit_series(&series).unwrap();
+
+        let alpha = fitted.parameters().alpha;
+        assert!(alpha > 0.15 && alpha < 0.6, "alpha = {}", alpha);
+        let forecast = fitted.forecast(3).unwrap();
+        assert!((forecast.values()[2] - fitted.level()).abs() < 1e-12);
+
+        // ETS(A,N,N) variances grow by alpha^2 sigma2 per step
+        let se = forecast.standard_errors().unwrap();
+        let expected = fitted.sigma2() * (1.0 + 2.0 * alpha * alpha);
+        assert!((se[2] * se[2] - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_additive_holt_winters_forecasts_pattern() {
+        let series = seasonal_series(124, false, 8);
+        let fitted = EtsModel::holt_winters_additive(4)
+            .unwrap()
+            .fit_series(&series[..120])
+            .unwrap();
+
+        let forecast = fitted.forecast(4).unwrap();
+        for (f, actual) in forecast.values().iter().zip(&series[120..]) {
+            assert!((f - actual).abs() < 4.0, "{} vs {}", f, actual);
+        }
+        assert!(fitted.trend() > 0.3 && fitted.trend() < 0.7);
+        assert!(forecast.uncertainty().is_some());
+    }
+
+    #[test]
+    fn test_multiplicative_holt_winters_simulates_intervals() {
+        let series = seasonal_series(124, true, 12);
+        let model = EtsModel::holt_winters_multiplicative(4)
+            .unwrap()
+            .with_simulation(500, 3)
+            .unwrap();
+        assert_eq!(model.name(), "ETS(M,A,M)");
+        let fitted = model.fit_series(&series[..120]).unwrap();
+
+        let seasonal = fitted.seasonal();
+        assert_eq!(seasonal.len(), 4);
+        assert!(seasonal[0] > seasonal[2]);
+        assert!(fitted.forecast_variance(4).is_none());
+
+        let forecast = fitted.forecast(8).unwrap();
+        let intervals = forecast.confidence_intervals(0.95).unwrap();
+        for (f, actual) in forecast.values().iter().zip(&series[120..]) {
+            assert!((f / actual - 1.0).abs() < 0.05, "{} vs {}", f, actual);
+        }
+        // Same season a period later, with more accumulated uncertainty
+        assert!(intervals[4].1 - intervals[4].0 > intervals[0].1 - intervals[0].0);
+    }
+
+    #[test]
+    fn test_damped_trend_flattens() {
+        let series: Vec<f64> = noise(150, 6)
+            .iter()
+            .enumerate()
+            .map(|(t, e)| 100.0 + 20.0 * (1.0 - 0.97_f64.powi(t as i32)) + 0.5 * e)
+            .collect();
+        let fitted = EtsModel::new(
+            ErrorType::Additive,
+            TrendType::Damped,
+            SeasonalType::None,
+            1,
+        )
+        .unwrap()
+        .fit_series(&series)
+        .unwrap();
+
+        let phi = fitted.parameters().phi.unwrap();
+        assert!((MIN_PHI..=MAX_PHI).contains(&phi));
+        let forecast = fitted.forecast(200).unwrap();
+        let tail = forecast.values()[199] - forecast.values()[150];
+        assert!(tail.abs() < 0.1);
+    }
+
+    #[test]
+    fn test_update_extends_state() {
+        let series = seasonal_series(80, false, 2);
+        let mut fitted = EtsModel::holt_winters_additive(4)
+            .unwrap()
+            .fit_series(&series[..76])
+            .unwrap();
+        let next = fitted.forecast(1).unwrap().values()[0];
+
+        fitted.update(&series[76..]).unwrap();
+        assert_eq!(fitted.residuals().len(), 80);
+        assert!((fitted.fitted_values()[76] - next).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(EtsModel::new(
+            ErrorType::Additive,
+            TrendType::None,
+            SeasonalType::Additive,
+            1
+        )
+        .is_err());
+        assert!(EtsModel::holt_winters_additive(4)
+            .unwrap()
+            .fit_series(&[1.0; 7])
+            .is_err());
+
+        let negative: Vec<f64> = (0..40).map(|t| t as f64 - 20.5).collect();
+        assert!(EtsModel::holt_winters_multiplicative(4)
+            .unwrap()
+            .fit_series(&negative)
+            .is_err());
+        assert!(EtsModel::holt_winters_additive(4)
+            .unwrap()
+            .with_simulation(0, 1)
+            .is_err());
+    }
+}
@@ -1,8 +1,7 @@
 //! Exponential smoothing model for time series forecasting
 
-use crate::data::TimeSeriesData;
 use crate::error::{ForecastError, Result};
-use crate::models::{ErrorMetrics, ForecastModel, ForecastResult};
+use crate::models::{FittedModel, ForecastModel, ForecastResult};
 use crate::strategies::TimeGranularity;
 
 /// Simple Exponential Smoothing model
@@ -12,8 +11,6 @@ pub struct ExponentialSmoothing {
     alpha: f64,
     /// Time granularity
     time_granularity: TimeGranularity,
-    /// Current smoothed value
-    level: Option<f64>,
 }
 
 impl ExponentialSmoothing {
@@ -28,7 +25,6 @@ impl ExponentialSmoothing {
         Ok(Self {
             alpha,
             time_granularity: TimeGranularity::Daily,
-            level: None,
         })
     }
 
@@ -41,170 +37,188 @@ impl ExponentialSmoothing {
 
     /// Create a new exponential smoothing model with default parameters for the given granularity
     pub fn with_default_params(granularity: TimeGranularity) -> Result<Self> {
-        match granularity {
-            TimeGranularity::Daily => Self::new(0.2), // Lower alpha for daily data (less responsive)
-            TimeGranularity::Minute => Self::new(0.4), // Higher alpha for minute data (more responsive)
-        }
+        let mut model = match granularity {
+            TimeGranularity::Daily => Self::new(0.2)?, // Lower alpha for daily data (less responsive)
+            TimeGranularity::Minute => Self::new(0.4)?, // Higher alpha for minute data (more responsive)
+        };
+        model.time_granularity = granularity;
+        Ok(model)
+    }
+
+    /// Smoothing factor
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    /// Get the model's time granularity
+    pub fn time_granularity(&self) -> TimeGranularity {
+        self.time_granularity
+    }
+
+    /// Set the model's time granularity, adjusting alpha if needed
+    pub fn with_granularity(mut self, granularity: TimeGranularity) -> Self {
+        self.adjust_for_granularity(granularity);
+        self
+    }
+
+    /// Adjust alpha based on time granularity
+    pub fn adjust_for_granularity(&mut self, granularity: TimeGranularity) {
+        self.time_granularity = granularity;
+
+        self.alpha = match granularity {
+            // If alpha is too high for daily data, reduce it
+            TimeGranularity::Daily if self.alpha > 0.3 => 0.2,
+            // If alpha is too low for minute data, increase it
+            TimeGranularity::Minute if self.alpha < 0.3 => 0.4,
+            _ => self.alpha,
+        };
     }
 }
 
 impl ForecastModel for ExponentialSmoothing {
-    fn train(&self, data: &TimeSeriesData) -> Result<Box<dyn ForecastModel>> {
-        if data.is_empty() {
+    type Fitted = FittedExponentialSmoothing;
+
+    fn name(&self) -> &str {
+        "Exponential Smoothing"
+    }
+
+    fn fit_series(&self, values: &[f64]) -> Result<FittedExponentialSmoothing> {
+        if values.is_empty() {
             return Err(ForecastError::DataError("Empty data".to_string()));
         }
 
-        let prices = data.close_prices();
-        let mut model = self.clone();
-        
         // Initialize level with first observation
-        let mut level = prices[0];
-        
-        // Apply exponential smoothing
-        for &price in &prices[1..] {
-            level = self.alpha * price + (1.0 - self.alpha) * level;
-        }
-        
-        model.level = Some(level);
-        
-        Ok(Box::new(model))
-    }
-    
-    fn forecast(&self, data: &TimeSeriesData, periods: usize) -> Result<ForecastResult> {
-        if data.is_empty() {
-            return Err(ForecastError::DataError("Empty data".to_string()));
-        }
-        
-        // If not trained, use the last value as the level
-        let level = self.level.unwrap_or_else(|| {
-            let prices = data.close_prices();
-            prices[prices.len() - 1]
-        });
-        
-        // For simple exponential smoothing, forecast is constant
-        let values = vec![level; periods];
-        
-        // Calculate in-sample error metrics for the provided data
-        let prices = data.close_prices();
-        let mut forecasts = Vec::with_capacity(prices.len());
-        let mut current_level = prices[0];
-        forecasts.push(current_level);
-        
-        for i in 1..prices.len() {
-            current_level = self.alpha * prices[i] + (1.0 - self.alpha) * current_level;
-            forecasts.push(current_level);
-        }
-        
-        // Calculate error metrics
-        let mut sum_abs_error = 0.0;
-        let mut sum_squared_error = 0.0;
-        let mut sum_abs_pct_error = 0.0;
-        
-        for i in 1..prices.len() {
-            let error = prices[i] - forecasts[i - 1];
-            sum_abs_error += error.abs();
-            sum_squared_error += error * error;
-            
-            if prices[i] != 0.0 {
-                sum_abs_pct_error += (error / prices[i]).abs();
-            }
-        }
-        
-        let n = prices.len() - 1;
-        let mae = sum_abs_error / n as f64;
-        let mse = sum_squared_error / n as f64;
-        let rmse = mse.sqrt();
-        let mape = sum_abs_pct_error / n as f64 * 100.0;
-        
-        let error_metrics = ErrorMetrics {
-            mae,
-            mse,
-            rmse,
-            mape,
+        let mut fitted = FittedExponentialSmoothing {
+            alpha: self.alpha,
+            level: values[0],
+            fitted_values: vec![values[0]],
+            residuals: vec![0.0],
         };
-        
-        Ok(ForecastResult {
-            values,
-            confidence_intervals: None,
-            error_metrics: Some(error_metrics),
-        })
+        fitted.update(&values[1..])?;
+
+        Ok(fitted)
     }
-    
-    fn validate(&self, train_data: &TimeSeriesData, test_data: &TimeSeriesData) -> Result<ErrorMetrics> {
-        if train_data.is_empty() || test_data.is_empty() {
-            return Err(ForecastError::DataError("Empty data".to_string()));
-        }
-        
-        // Train on training data
-        let trained_model = self.train(train_data)?;
-        
-        // Forecast for the test period
-        let forecast_result = trained_model.forecast(train_data, test_data.len())?;
-        
-        // Calculate error metrics
-        let test_prices = test_data.close_prices();
-        let forecasts = forecast_result.values;
-        
-        let mut sum_abs_error = 0.0;
-        let mut sum_squared_error = 0.0;
-        let mut sum_abs_pct_error = 0.0;
-        
-        for i in 0..test_prices.len() {
-            let error = test_prices[i] - forecasts[i];
-            sum_abs_error += error.abs();
-            sum_squared_error += error * error;
-            
-            if test_prices[i] != 0.0 {
-                sum_abs_pct_error += (error / test_prices[i]).abs();
-            }
+}
+
+/// Exponential smoothing model fitted to a series
+#[derive(Debug, Clone)]
+pub struct FittedExponentialSmoothing {
+    alpha: f64,
+    level: f64,
+    fitted_values: Vec<f64>,
+    residuals: Vec<f64>,
+}
+
+impl FittedExponentialSmoothing {
+    /// Current smoothed level
+    pub fn level(&self) -> f64 {
+        self.level
+    }
+
+    /// One-step innovation variance, estimated from the residuals after the
+    /// first observation
+    pub fn sigma2(&self) -> f64 {
+        let errors = &self.residuals[1..];
+        if errors.is_empty() {
+            return 0.0;
         }
-        
-        let n = test_prices.len();
-        let mae = sum_abs_error / n as f64;
-        let mse = sum_squared_error / n as f64;
-        let rmse = mse.sqrt();
-        let mape = sum_abs_pct_error / n as f64 * 100.0;
-        
-        Ok(ErrorMetrics {
-            mae,
-            mse,
-            rmse,
-            mape,
-        })
+        errors.iter().map(|e| e * e).sum::<f64>() / errors.len() as f64
     }
-    
-    fn time_granularity(&self) -> TimeGranularity {
-        self.time_granularity
+
+    /// Forecast error variances for the next `horizon` values
+    ///
+    /// Simple exponential smoothing is ETS(A,N,N), for which the
+    /// `h`-step variance is `sigma2 * (1 + (h - 1) * alpha^2)`.
+    pub fn forecast_variance(&self, horizon: usize) -> Vec<f64> {
+        let sigma2 = self.sigma2();
+        (0..horizon)
+            .map(|h| sigma2 * (1.0 + h as f64 * self.alpha * self.alpha))
+            .collect()
     }
-    
-    fn with_granularity(mut self, granularity: TimeGranularity) -> Self {
-        let _ = self.adjust_for_granularity(granularity);
-        self
+}
+
+impl FittedModel for FittedExponentialSmoothing {
+    fn name(&self) -> &str {
+        "Exponential Smoothing"
     }
-    
-    fn adjust_for_granularity(&mut self, granularity: TimeGranularity) -> Result<()> {
-        self.time_granularity = granularity;
-        
-        // Adjust alpha based on granularity
-        self.alpha = match granularity {
-            TimeGranularity::Daily => {
-                // If alpha is too high for daily data, reduce it
-                if self.alpha > 0.3 {
-                    0.2
-                } else {
-                    self.alpha
-                }
-            }
-            TimeGranularity::Minute => {
-                // If alpha is too low for minute data, increase it
-                if self.alpha < 0.3 {
-                    0.4
-                } else {
-                    self.alpha
-                }
-            }
-        };
-        
+
+    fn forecast(&self, horizon: usize) -> Result<ForecastResult> {
+        // For simple exponential smoothing, forecast is constant
+        ForecastResult::new(vec![self.level; horizon], horizon)?
+            .with_variances(self.forecast_variance(horizon))
+    }
+
+    fn update(&mut self, observations: &[f64]) -> Result<()> {
+        for &value in observations {
+            self.fitted_values.push(self.level);
+            self.residuals.push(value - self.level);
+            self.level = self.alpha * value + (1.0 - self.alpha) * self.level;
+        }
         Ok(())
     }
+
+    fn fitted_values(&self) -> &[f64] {
+        &self.fitted_values
+    }
+
+    fn residuals(&self) -> &[f64] {
+        &self.residuals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_and_forecast() {
+        let model = ExponentialSmoothing::new(0.5).unwrap();
+        let fitted = model.fit_series(&[10.0, 12.0, 14.0]).unwrap();
+
+        // Levels: 10 -> 11 -> 12.5
+        assert!((fitted.level() - 12.5).abs() < 1e-12);
+        assert_eq!(fitted.fitted_values(), &[10.0, 10.0, 11.0]);
+        assert_eq!(fitted.residuals(), &[0.0, 2.0, 3.0]);
+        assert_eq!(fitted.forecast(3).unwrap().values(), &[12.5, 12.5, 12.5]);
+    }
+
+    #[test]
+    fn test_update_matches_refit() {
+        let model = ExponentialSmoothing::new(0.3).unwrap();
+        let series = [5.0, 6.0, 5.5, 7.0, 6.5];
+
+        let mut updated = model.fit_series(&series[..3]).unwrap();
+        updated.update(&series[3..]).unwrap();
+        let refit = model.fit_series(&series).unwrap();
+
+        assert_eq!(updated.residuals(), refit.residuals());
+        assert!((updated.level() - refit.level()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_prediction_intervals_widen() {
+        let model = ExponentialSmoothing::new(0.5).unwrap();
+        let fitted = model.fit_series(&[10.0, 12.0, 14.0]).unwrap();
+
+        // Residuals 2 and 3 give sigma2 = 6.5
+        assert!((fitted.sigma2() - 6.5).abs() < 1e-12);
+        let forecast = fitted.forecast(3).unwrap();
+        let se = forecast.standard_errors().unwrap();
+        assert!((se[0] - 6.5_f64.sqrt()).abs() < 1e-12);
+        assert!((se[2] - (6.5 * 1.5_f64).sqrt()).abs() < 1e-12);
+
+        let intervals = forecast.confidence_intervals(0.95).unwrap();
+        assert!((intervals[0].1 - 12.5 - 1.959964 * se[0]).abs() < 1e-5);
+        assert!(intervals[2].1 - intervals[2].0 > intervals[0].1 - intervals[0].0);
+    }
+
+    #[test]
+    fn test_invalid_alpha() {
+        assert!(ExponentialSmoothing::new(1.5).is_err());
+        assert!(ExponentialSmoothing::new(0.0).is_err());
+        assert!(ExponentialSmoothing::new(0.5)
+            .unwrap()
+            .fit_series(&[])
+            .is_err());
+    }
 }
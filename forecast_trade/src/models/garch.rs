@@ -1,185 +1,884 @@
-//! GARCH models for time series forecasting
+//! GARCH-family models for volatility forecasting
+//!
+//! Unlike the price models, a GARCH model describes returns. `fit` converts
+//! the close prices of a `TimeSeriesData` to simple returns, while
+//! `fit_series` and `FittedModel::update` take returns directly. Forecasts
+//! are conditional volatilities (standard deviations of returns).
+//!
+//! Three variance equations are supported, each with normal or
+//! standardized Student-t innovations and a constant mean, all estimated by
+//! maximum likelihood:
+//!
+//! - GARCH(p, q): `s2[t] = w + sum a_i e2[t-i] + sum b_j s2[t-j]`
+//! - GJR-GARCH(p, q): adds `g_i I(e[t-i] < 0) e2[t-i]`, so negative shocks
+//!   can raise volatility more than positive ones
+//! - EGARCH(p, q): `ln s2[t] = w + sum a_i (|z[t-i]| - E|z|) + g_i z[t-i]
+//!   + sum b_j ln s2[t-j]`
+//!
+//! `q` is the ARCH order and `p` the GARCH order. Pre-sample squared shocks
+//! and variances are set to the sample variance.
 
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rand_distr::{Distribution, StandardNormal, StudentT};
+use statrs::function::gamma::ln_gamma;
+use std::f64::consts::PI;
+
+use crate::data::TimeSeriesData;
 use crate::error::{ForecastError, Result};
-use crate::models::{ForecastModel, ForecastResult};
+use crate::models::arima::is_stationary;
+use crate::models::{FittedModel, ForecastModel, ForecastResult, InformationCriterion};
+use crate::optimization::NelderMead;
+use crate::utils::DEFAULT_SEED;
+
+/// Bounds on the Student-t degrees of freedom
+const MIN_DEGREES_OF_FREEDOM: f64 = 2.05;
+const MAX_DEGREES_OF_FREEDOM: f64 = 200.0;
+
+/// Simulated paths behind the uncertainty of forecasts
+const DEFAULT_SIMULATION_PATHS: usize = 1000;
+
+/// Variance equation of a GARCH-family model
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GarchVariant {
+    /// Symmetric GARCH
+    #[default]
+    Garch,
+    /// Glosten-Jagannathan-Runkle GARCH with a leverage term
+    Gjr,
+    /// Exponential GARCH on the log variance
+    Egarch,
+}
+
+/// Distribution of the standardized innovations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Innovation {
+    /// Standard normal
+    #[default]
+    Normal,
+    /// Student-t scaled to unit variance, with estimated degrees of freedom
+    StudentT,
+}
 
 /// GARCH model implementation
+#[derive(Debug, Clone)]
 pub struct GarchModel {
     /// Model name
     name: String,
+    /// Variance equation
+    variant: GarchVariant,
+    /// Innovation distribution
+    distribution: Innovation,
     /// GARCH order (p)
     p: usize,
     /// ARCH order (q)
     q: usize,
-    /// Historical returns
-    returns: Vec<f64>,
-    /// Volatility estimates
-    volatility: Vec<f64>,
-    /// ARCH parameters
-    alpha: Vec<f64>,
-    /// GARCH parameters
-    beta: Vec<f64>,
-    /// Long run variance
-    omega: f64,
+    /// Number of simulated paths for forecast distributions
+    simulation_paths: usize,
+    /// Seed of the forecast simulations
+    seed: u64,
 }
 
 impl GarchModel {
-    /// Create a new GARCH model
-    pub fn new(p: usize, q: usize) -> Self {
-        Self {
-            name: format!("GARCH({},{})", p, q),
+    /// Create a new GARCH(p, q) model
+    pub fn new(p: usize, q: usize) -> Result<Self> {
+        Self::with_variant(GarchVariant::Garch, p, q)
+    }
+
+    /// Create a GJR-GARCH(p, q) model
+    pub fn gjr(p: usize, q: usize) -> Result<Self> {
+        Self::with_variant(GarchVariant::Gjr, p, q)
+    }
+
+    /// Create an EGARCH(p, q) model
+    pub fn egarch(p: usize, q: usize) -> Result<Self> {
+        Self::with_variant(GarchVariant::Egarch, p, q)
+    }
+
+    fn with_variant(variant: GarchVariant, p: usize, q: usize) -> Result<Self> {
+        if q == 0 {
+            return Err(ForecastError::InvalidParameter(
+                "ARCH order must be at least 1".to_string(),
+            ));
+        }
+
+        let mut model = Self {
+            name: String::new(),
+            variant,
+            distribution: Innovation::Normal,
             p,
             q,
-            returns: Vec::new(),
-            volatility: Vec::new(),
-            alpha: vec![0.1; q], // Simple default values
-            beta: vec![0.8; p],  // Simple default values
-            omega: 0.05,         // Simple default value
+            simulation_paths: DEFAULT_SIMULATION_PATHS,
+            seed: DEFAULT_SEED,
+        };
+        model.name = model.describe();
+        Ok(model)
+    }
+
+    /// Use the given innovation distribution (normal by default)
+    pub fn with_distribution(mut self, distribution: Innovation) -> Self {
+        self.distribution = distribution;
+        self.name = self.describe();
+        self
+    }
+
+    /// Simulate `paths` future paths from `seed` when forecasting
+    ///
+    /// Forecast quantiles and intervals come from these simulations.
+    pub fn with_simulation(mut self, paths: usize, seed: u64) -> Result<Self> {
+        if paths == 0 {
+            return Err(ForecastError::InvalidParameter(
+                "Number of simulated paths must be greater than 0".to_string(),
+            ));
         }
+        self.simulation_paths = paths;
+        self.seed = seed;
+        Ok(self)
+    }
+
+    /// Model orders as (p, q)
+    pub fn order(&self) -> (usize, usize) {
+        (self.p, self.q)
+    }
+
+    /// Variance equation
+    pub fn variant(&self) -> GarchVariant {
+        self.variant
+    }
+
+    /// Innovation distribution
+    pub fn distribution(&self) -> Innovation {
+        self.distribution
+    }
+
+    fn describe(&self) -> String {
+        let prefix = match self.variant {
+            GarchVariant::Garch => "GARCH",
+            GarchVariant::Gjr => "GJR-GARCH",
+            GarchVariant::Egarch => "EGARCH",
+        };
+        let suffix = match self.distribution {
+            Innovation::Normal => "",
+            Innovation::StudentT => " Student-t",
+        };
+        format!("{}({},{}){}", prefix, self.p, self.q, suffix)
     }
-    
+
     /// Calculate returns from price series
-    fn calculate_returns(&self, prices: &[f64]) -> Vec<f64> {
-        if prices.len() <= 1 {
-            return Vec::new();
-        }
-        
-        prices.windows(2)
-            .map(|w| (w[1] / w[0]) - 1.0)
-            .collect()
+    fn calculate_returns(prices: &[f64]) -> Vec<f64> {
+        prices.windows(2).map(|w| (w[1] / w[0]) - 1.0).collect()
     }
-    
-    /// Estimate simple GARCH parameters
-    fn estimate_parameters(&mut self, returns: &[f64]) {
-        // This is a very simplified parameter estimation
-        // In reality, GARCH parameters should be estimated via maximum likelihood
-        
-        // Calculate sample variance for returns
-        let mean = returns.iter().sum::<f64>() / returns.len() as f64;
-        let variance = returns.iter()
-            .map(|&r| (r - mean).powi(2))
-            .sum::<f64>() / returns.len() as f64;
-        
-        // Set omega to a portion of the variance
-        self.omega = 0.05 * variance;
-        
-        // Alpha parameters sum to about 0.15 in typical financial data
-        if !self.alpha.is_empty() {
-            let alpha_sum = 0.15;
-            let alpha_i = alpha_sum / self.alpha.len() as f64;
-            for a in &mut self.alpha {
-                *a = alpha_i;
-            }
+
+    /// Number of leverage coefficients
+    fn leverage_terms(&self) -> usize {
+        match self.variant {
+            GarchVariant::Garch => 0,
+            GarchVariant::Gjr | GarchVariant::Egarch => self.q,
         }
-        
-        // Beta parameters typically sum to about 0.8
-        if !self.beta.is_empty() {
-            let beta_sum = 0.8;
-            let beta_i = beta_sum / self.beta.len() as f64;
-            for b in &mut self.beta {
-                *b = beta_i;
+    }
+
+    /// Number of estimated parameters
+    fn parameter_count(&self) -> usize {
+        let shape = usize::from(self.distribution == Innovation::StudentT);
+        2 + self.q + self.leverage_terms() + self.p + shape
+    }
+
+    /// Map an optimizer vector `[mean, omega, alpha, gamma, beta, shape]` to
+    /// coefficients, or `None` if it violates the constraints
+    ///
+    /// Omega is on a log scale for GARCH and GJR, and the degrees of freedom
+    /// are `2 + exp(shape)`.
+    fn coefficients(&self, params: &[f64]) -> Option<Coefficients> {
+        let (alpha, rest) = params[2..].split_at(self.q);
+        let (gamma, rest) = rest.split_at(self.leverage_terms());
+        let (beta, rest) = rest.split_at(self.p);
+        let degrees_of_freedom = match self.distribution {
+            Innovation::Normal => None,
+            Innovation::StudentT => {
+                let nu = 2.0 + rest[0].exp();
+                if !(MIN_DEGREES_OF_FREEDOM..=MAX_DEGREES_OF_FREEDOM).contains(&nu) {
+                    return None;
+                }
+                Some(nu)
             }
-        }
+        };
+
+        let coefficients = Coefficients {
+            variant: self.variant,
+            mean: params[0],
+            omega: match self.variant {
+                GarchVariant::Egarch => params[1],
+                GarchVariant::Garch | GarchVariant::Gjr => params[1].exp(),
+            },
+            alpha: alpha.to_vec(),
+            gamma: gamma.to_vec(),
+            beta: beta.to_vec(),
+            degrees_of_freedom,
+        };
+        coefficients.is_admissible().then_some(coefficients)
     }
-    
-    /// Forecast volatility for the next periods
-    fn forecast_volatility(&self, horizon: usize) -> Vec<f64> {
-        if self.volatility.is_empty() {
-            return vec![0.0; horizon];
-        }
-        
-        let current_var = *self.volatility.last().unwrap();
-        
-        // For GARCH, volatility tends toward the long-run average
-        let alpha_sum: f64 = self.alpha.iter().sum();
-        let beta_sum: f64 = self.beta.iter().sum();
-        let persistence = alpha_sum + beta_sum;
-        
-        // Long-run variance
-        let long_run_var = if persistence < 1.0 {
-            self.omega / (1.0 - persistence)
+
+    /// Starting point and initial simplex steps for the optimizer
+    fn initial_parameters(&self, mean: f64, variance: f64) -> (Vec<f64>, Vec<f64>) {
+        let (alpha, beta) = match self.variant {
+            GarchVariant::Egarch => (0.1, 0.9),
+            GarchVariant::Garch | GarchVariant::Gjr => (0.05, 0.85),
+        };
+        let alpha = alpha / self.q as f64;
+        let beta = if self.p == 0 {
+            0.0
         } else {
-            current_var // If persistence >= 1, model is non-stationary
+            beta / self.p as f64
         };
-        
-        let mut forecasts = Vec::with_capacity(horizon);
-        let mut forecast_var = current_var;
-        
-        for _ in 0..horizon {
-            // Simple GARCH variance forecast formula
-            forecast_var = self.omega + persistence * (forecast_var - long_run_var) + long_run_var;
-            forecasts.push(forecast_var.sqrt()); // Convert variance to volatility
+        let omega = match self.variant {
+            GarchVariant::Egarch => variance.ln() * (1.0 - beta * self.p as f64),
+            GarchVariant::Garch | GarchVariant::Gjr => {
+                let persistence = alpha * self.q as f64 + beta * self.p as f64;
+                (variance * (1.0 - persistence)).ln()
+            }
+        };
+
+        let mut start = vec![mean, omega];
+        let mut steps = vec![0.1 * variance.sqrt(), 0.5];
+        start.extend(std::iter::repeat_n(alpha, self.q));
+        steps.extend(std::iter::repeat_n(0.05, self.q));
+        start.extend(std::iter::repeat_n(0.0, self.leverage_terms()));
+        steps.extend(std::iter::repeat_n(0.05, self.leverage_terms()));
+        start.extend(std::iter::repeat_n(beta, self.p));
+        steps.extend(std::iter::repeat_n(-0.05, self.p));
+        if self.distribution == Innovation::StudentT {
+            // Eight degrees of freedom
+            start.push(6.0_f64.ln());
+            steps.push(0.5);
         }
-        
-        forecasts
+        (start, steps)
     }
 }
 
 impl ForecastModel for GarchModel {
+    type Fitted = FittedGarch;
+
     fn name(&self) -> &str {
         &self.name
     }
-    
-    fn fit(&mut self, data: &[f64]) -> Result<()> {
-        if data.len() < self.p + self.q + 1 {
+
+    fn fit_series(&self, returns: &[f64]) -> Result<FittedGarch> {
+        let minimum = (self.p.max(self.q) + 2).max(2 * self.parameter_count());
+        if returns.len() < minimum {
             return Err(ForecastError::ValidationError(format!(
-                "Insufficient data for GARCH({},{}). Need at least {} observations.",
-                self.p, self.q, self.p + self.q + 1
+                "Insufficient data for {}. Need at least {} returns.",
+                self.name, minimum
             )));
         }
-        
-        // Calculate returns from prices
-        let returns = self.calculate_returns(data);
-        
+
+        let n = returns.len() as f64;
+        let mean = returns.iter().sum::<f64>() / n;
+        let variance = returns.iter().map(|&r| (r - mean).powi(2)).sum::<f64>() / n;
+        if returns.iter().all(|&r| r == returns[0]) || !variance.is_finite() {
+            return Err(ForecastError::ValidationError(
+                "Returns must vary to fit a volatility model".to_string(),
+            ));
+        }
+
+        let objective = |params: &[f64]| {
+            self.coefficients(params)
+                .and_then(|c| c.log_likelihood(returns, variance))
+                .map_or(f64::INFINITY, |ll| -ll / n)
+        };
+
+        // Restart from the first optimum to escape a prematurely collapsed
+        // simplex
+        let (start, steps) = self.initial_parameters(mean, variance);
+        let optimizer = NelderMead::new(4000, 1e-12)?;
+        let first = optimizer.minimize(objective, &start, &steps);
+        let best = optimizer.minimize(objective, &first.point, &steps);
+
+        let coefficients = self.coefficients(&best.point).ok_or_else(|| {
+            ForecastError::ForecastingError(format!(
+                "{} estimation did not find admissible parameters",
+                self.name
+            ))
+        })?;
+
+        let mut fitted = FittedGarch {
+            name: self.name.clone(),
+            coefficients,
+            backcast: variance,
+            log_likelihood: -best.value * n,
+            observations: returns.len(),
+            parameters: self.parameter_count(),
+            simulation_paths: self.simulation_paths,
+            seed: self.seed,
+            shocks: Vec::with_capacity(returns.len()),
+            variances: Vec::with_capacity(returns.len()),
+            fitted_values: Vec::with_capacity(returns.len()),
+        };
+        fitted.update(returns)?;
+
+        Ok(fitted)
+    }
+
+    fn fit(&self, data: &TimeSeriesData) -> Result<FittedGarch> {
+        let returns = Self::calculate_returns(&data.close_prices());
         if returns.is_empty() {
-            return Err(ForecastError::ValidationError("Could not calculate returns from provided data".to_string()));
-        }
-        
-        // Store returns for later use
-        self.returns = returns.clone();
-        
-        // Estimate GARCH parameters
-        self.estimate_parameters(&returns);
-        
-        // Calculate historical volatility estimates
-        let squared_returns: Vec<f64> = self.returns.iter().map(|r| r.powi(2)).collect();
-        
-        // Initialize volatility with unconditional variance
-        let mean_sq_return = squared_returns.iter().sum::<f64>() / squared_returns.len() as f64;
-        self.volatility = vec![mean_sq_return; self.returns.len()];
-        
-        // Run the GARCH filter
-        for t in (self.q + self.p)..self.returns.len() {
-            let mut arch_component = self.omega;
-            
-            // ARCH terms
-            for i in 0..self.q {
-                arch_component += self.alpha[i] * squared_returns[t - i - 1];
+            return Err(ForecastError::ValidationError(
+                "Could not calculate returns from provided data".to_string(),
+            ));
+        }
+        self.fit_series(&returns)
+    }
+}
+
+/// Estimated coefficients of a GARCH-family model
+#[derive(Debug, Clone)]
+struct Coefficients {
+    variant: GarchVariant,
+    mean: f64,
+    omega: f64,
+    alpha: Vec<f64>,
+    gamma: Vec<f64>,
+    beta: Vec<f64>,
+    degrees_of_freedom: Option<f64>,
+}
+
+impl Coefficients {
+    /// Positivity and stationarity constraints
+    fn is_admissible(&self) -> bool {
+        match self.variant {
+            GarchVariant::Egarch => self.omega.is_finite() && is_stationary(&self.beta),
+            GarchVariant::Garch | GarchVariant::Gjr => {
+                let gamma = self.gamma.iter().chain(std::iter::repeat(&0.0));
+                self.omega > 0.0
+                    && self
+                        .alpha
+                        .iter()
+                        .zip(gamma)
+                        .all(|(a, g)| *a >= 0.0 && a + g >= 0.0)
+                    && self.beta.iter().all(|b| *b >= 0.0)
+                    && self.persistence() < 1.0
             }
-            
-            // GARCH terms
-            for i in 0..self.p {
-                arch_component += self.beta[i] * self.volatility[t - i - 1];
+        }
+    }
+
+    fn persistence(&self) -> f64 {
+        let alpha: f64 = self.alpha.iter().sum();
+        let gamma: f64 = self.gamma.iter().sum();
+        let beta: f64 = self.beta.iter().sum();
+        match self.variant {
+            GarchVariant::Garch => alpha + beta,
+            GarchVariant::Gjr => alpha + 0.5 * gamma + beta,
+            GarchVariant::Egarch => beta,
+        }
+    }
+
+    /// E|z| for the standardized innovations
+    fn expected_abs_innovation(&self) -> f64 {
+        match self.degrees_of_freedom {
+            None => (2.0 / PI).sqrt(),
+            Some(nu) => {
+                let ratio = (ln_gamma((nu + 1.0) / 2.0) - ln_gamma(nu / 2.0)).exp();
+                2.0 * (nu - 2.0).sqrt() * ratio / ((nu - 1.0) * PI.sqrt())
             }
-            
-            self.volatility[t] = arch_component;
         }
-        
-        Ok(())
     }
-    
+
+    /// Conditional variance after `variances.len()` periods
+    ///
+    /// Shocks beyond `shocks.len()` are future ones and enter through their
+    /// expectation; lags before the first period use `backcast`.
+    fn next_variance(&self, shocks: &[f64], variances: &[f64], backcast: f64) -> f64 {
+        let t = variances.len();
+        let lagged_variance = |lag: usize| t.checked_sub(lag).map_or(backcast, |i| variances[i]);
+        let lagged_shock = |lag: usize| t.checked_sub(lag).and_then(|i| shocks.get(i).copied());
+
+        match self.variant {
+            GarchVariant::Garch | GarchVariant::Gjr => {
+                let mut variance = self.omega;
+                for (i, a) in self.alpha.iter().enumerate() {
+                    let g = self.gamma.get(i).copied().unwrap_or(0.0);
+                    variance += match lagged_shock(i + 1) {
+                        Some(e) if e < 0.0 => (a + g) * e * e,
+                        Some(e) => a * e * e,
+                        // Symmetric innovations are negative half the time
+                        None => (a + 0.5 * g) * lagged_variance(i + 1),
+                    };
+                }
+                for (j, b) in self.beta.iter().enumerate() {
+                    variance += b * lagged_variance(j + 1);
+                }
+                variance
+            }
+            GarchVariant::Egarch => {
+                let expected_abs = self.expected_abs_innovation();
+                let mut log_variance = self.omega;
+                for (i, a) in self.alpha.iter().enumerate() {
+                    // Unknown shocks contribute zero in expectation
+                    if let Some(e) = lagged_shock(i + 1) {
+                        let z = e / lagged_variance(i + 1).sqrt();
+                        log_variance += a * (z.abs() - expected_abs) + self.gamma[i] * z;
+                    }
+                }
+                for (j, b) in self.beta.iter().enumerate() {
+                    log_variance += b * lagged_variance(j + 1).ln();
+                }
+                log_variance.exp()
+            }
+        }
+    }
+
+    /// Log density of a shock with the given conditional variance
+    fn log_density(&self, shock: f64, variance: f64) -> f64 {
+        match self.degrees_of_freedom {
+            None => -0.5 * ((2.0 * PI).ln() + variance.ln() + shock * shock / variance),
+            Some(nu) => {
+                ln_gamma((nu + 1.0) / 2.0)
+                    - ln_gamma(nu / 2.0)
+                    - 0.5 * (PI * (nu - 2.0)).ln()
+                    - 0.5 * variance.ln()
+                    - 0.5 * (nu + 1.0) * (1.0 + shock * shock / (variance * (nu - 2.0))).ln()
+            }
+        }
+    }
+
+    /// Log-likelihood of a return series, or `None` if the variance
+    /// recursion breaks down
+    fn log_likelihood(&self, returns: &[f64], backcast: f64) -> Option<f64> {
+        let mut shocks = Vec::with_capacity(returns.len());
+        let mut variances = Vec::with_capacity(returns.len());
+        let mut total = 0.0;
+        for &r in returns {
+            let variance = self.next_variance(&shocks, &variances, backcast);
+            if !(variance > 0.0 && variance.is_finite()) {
+                return None;
+            }
+            let shock = r - self.mean;
+            total += self.log_density(shock, variance);
+            shocks.push(shock);
+            variances.push(variance);
+        }
+        total.is_finite().then_some(total)
+    }
+}
+
+/// GARCH-family model fitted to a return series
+#[derive(Debug, Clone)]
+pub struct FittedGarch {
+    name: String,
+    coefficients: Coefficients,
+    /// Pre-sample variance
+    backcast: f64,
+    log_likelihood: f64,
+    observations: usize,
+    parameters: usize,
+    simulation_paths: usize,
+    seed: u64,
+    /// Demeaned returns (the residuals)
+    shocks: Vec<f64>,
+    /// Conditional variance of each return
+    variances: Vec<f64>,
+    /// Conditional volatility of each return
+    fitted_values: Vec<f64>,
+}
+
+impl FittedGarch {
+    /// Constant of the variance equation (of the log variance for EGARCH)
+    pub fn omega(&self) -> f64 {
+        self.coefficients.omega
+    }
+
+    /// ARCH coefficients
+    pub fn alpha(&self) -> &[f64] {
+        &self.coefficients.alpha
+    }
+
+    /// Leverage coefficients, empty for symmetric GARCH
+    pub fn gamma(&self) -> &[f64] {
+        &self.coefficients.gamma
+    }
+
+    /// GARCH coefficients
+    pub fn beta(&self) -> &[f64] {
+        &self.coefficients.beta
+    }
+
+    /// Mean return
+    pub fn mean(&self) -> f64 {
+        self.coefficients.mean
+    }
+
+    /// Estimated Student-t degrees of freedom, if applicable
+    pub fn degrees_of_freedom(&self) -> Option<f64> {
+        self.coefficients.degrees_of_freedom
+    }
+
+    /// Persistence of shocks to the variance
+    ///
+    /// `sum a + sum b` for GARCH, plus half the leverage terms for GJR, and
+    /// `sum b` for EGARCH.
+    pub fn persistence(&self) -> f64 {
+        self.coefficients.persistence()
+    }
+
+    /// Long-run variance the forecasts revert to, if the process is
+    /// stationary
+    ///
+    /// For EGARCH this is the exponential of the long-run log variance.
+    pub fn unconditional_variance(&self) -> Option<f64> {
+        let persistence = self.persistence();
+        if persistence >= 1.0 {
+            return None;
+        }
+        Some(match self.coefficients.variant {
+            GarchVariant::Egarch => (self.omega() / (1.0 - persistence)).exp(),
+            GarchVariant::Garch | GarchVariant::Gjr => self.omega() / (1.0 - persistence),
+        })
+    }
+
+    /// Maximised log-likelihood
+    pub fn log_likelihood(&self) -> f64 {
+        self.log_likelihood
+    }
+
+    /// Akaike information criterion
+    pub fn aic(&self) -> f64 {
+        InformationCriterion::Aic.evaluate(self.log_likelihood, self.parameters, self.observations)
+    }
+
+    /// Bayesian information criterion
+    pub fn bic(&self) -> f64 {
+        InformationCriterion::Bic.evaluate(self.log_likelihood, self.parameters, self.observations)
+    }
+
+    /// In-sample conditional variances
+    pub fn conditional_variances(&self) -> &[f64] {
+        &self.variances
+    }
+
+    /// Residuals divided by their conditional volatility
+    pub fn standardized_residuals(&self) -> Vec<f64> {
+        self.shocks
+            .iter()
+            .zip(&self.variances)
+            .map(|(e, v)| e / v.sqrt())
+            .collect()
+    }
+
+    /// Forecast conditional variances for the next `horizon` returns
+    ///
+    /// Future shocks are replaced by their expectations. EGARCH forecasts
+    /// the log variance, so beyond one step this is the exponential of its
+    /// expectation rather than the expected variance.
+    pub fn forecast_variance(&self, horizon: usize) -> Vec<f64> {
+        let mut variances = self.variances.clone();
+        let mut forecasts = Vec::with_capacity(horizon);
+
+        for _ in 0..horizon {
+            let variance = self
+                .coefficients
+                .next_variance(&self.shocks, &variances, self.backcast);
+            variances.push(variance);
+            forecasts.push(variance);
+        }
+
+        forecasts
+    }
+
+    /// Forecast conditional volatilities for the next `horizon` returns
+    pub fn forecast_volatility(&self, horizon: usize) -> Vec<f64> {
+        self.forecast_variance(horizon)
+            .into_iter()
+            .map(f64::sqrt)
+            .collect()
+    }
+
+    /// Simulate `paths` future paths of `horizon` returns
+    ///
+    /// Innovations are drawn from the fitted distribution, so the paths
+    /// carry the volatility clustering and tails of the model.
+    pub fn simulate(&self, horizon: usize, paths: usize, seed: u64) -> Vec<SimulatedPath> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let student = self
+            .coefficients
+            .degrees_of_freedom
+            .map(|nu| (StudentT::new(nu).unwrap(), ((nu - 2.0) / nu).sqrt()));
+
+        // Only the most recent lags enter the recursion
+        let lags = self.alpha().len().max(self.beta().len());
+        let start = self.variances.len().saturating_sub(lags);
+        (0..paths)
+            .map(|_| {
+                let mut shocks = self.shocks[start..].to_vec();
+                let mut variances = self.variances[start..].to_vec();
+                let mut path = SimulatedPath {
+                    returns: Vec::with_capacity(horizon),
+                    volatilities: Vec::with_capacity(horizon),
+                };
+                for _ in 0..horizon {
+                    let variance =
+                        self.coefficients
+                            .next_variance(&shocks, &variances, self.backcast);
+                    let z = match &student {
+                        Some((t, scale)) => scale * t.sample(&mut rng),
+                        None => StandardNormal.sample(&mut rng),
+                    };
+                    let shock = variance.sqrt() * z;
+                    shocks.push(shock);
+                    variances.push(variance);
+                    path.returns.push(self.coefficients.mean + shock);
+                    path.volatilities.push(variance.sqrt());
+                }
+                path
+            })
+            .collect()
+    }
+
+    /// Forecast returns, with simulated return distributions per horizon
+    ///
+    /// Point forecasts are the mean return; quantiles of the result give
+    /// value-at-risk style bounds.
+    pub fn forecast_returns(&self, horizon: usize) -> Result<ForecastResult> {
+        let paths = self.simulate(horizon, self.simulation_paths, self.seed);
+        let samples = (0..horizon)
+            .map(|h| paths.iter().map(|path| path.returns[h]).collect())
+            .collect();
+        ForecastResult::new(vec![self.coefficients.mean; horizon], horizon)?.with_samples(samples)
+    }
+}
+
+/// One simulated future path of a GARCH-family model
+#[derive(Debug, Clone)]
+pub struct SimulatedPath {
+    /// Simulated returns
+    pub returns: Vec<f64>,
+    /// Conditional volatility of each simulated return
+    pub volatilities: Vec<f64>,
+}
+
+impl FittedModel for FittedGarch {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
     fn forecast(&self, horizon: usize) -> Result<ForecastResult> {
-        if self.returns.is_empty() || self.volatility.is_empty() {
-            return Err(ForecastError::ForecastingError("Model has not been fitted to data".to_string()));
-        }
-        
-        // Get volatility forecasts
-        let values = self.forecast_volatility(horizon);
-        
-        Ok(ForecastResult {
-            values,
-            intervals: None,
-            timestamps: None,
+        if self.variances.is_empty() {
+            return Err(ForecastError::ForecastingError(
+                "Model has not been fitted to data".to_string(),
+            ));
+        }
+
+        // Volatility beyond one step depends on the shocks in between
+        let paths = self.simulate(horizon, self.simulation_paths, self.seed);
+        let samples = (0..horizon)
+            .map(|h| paths.iter().map(|path| path.volatilities[h]).collect())
+            .collect();
+        ForecastResult::new(self.forecast_volatility(horizon), horizon)?.with_samples(samples)
+    }
+
+    fn update(&mut self, returns: &[f64]) -> Result<()> {
+        for &r in returns {
+            let variance =
+                self.coefficients
+                    .next_variance(&self.shocks, &self.variances, self.backcast);
+            if !(variance > 0.0 && variance.is_finite()) {
+                return Err(ForecastError::MathError(format!(
+                    "{} produced an invalid conditional variance",
+                    self.name
+                )));
+            }
+
+            self.shocks.push(r - self.coefficients.mean);
+            self.variances.push(variance);
+            self.fitted_values.push(variance.sqrt());
+        }
+        Ok(())
+    }
+
+    /// In-sample conditional volatilities
+    fn fitted_values(&self) -> &[f64] {
+        &self.fitted_values
+    }
+
+    /// Demeaned returns
+    fn residuals(&self) -> &[f64] {
+        &self.shocks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use rand_distr::{Distribution, Normal, StudentT};
+
+    fn returns() -> Vec<f64> {
+        (0..200)
+            .map(|i| {
+                let scale = if (50..100).contains(&i) { 0.03 } else { 0.01 };
+                if i % 2 == 0 {
+                    scale
+                } else {
+                    -scale
+                }
+            })
+            .collect()
+    }
+
+    /// Simulate `n` returns from a GJR-GARCH(1,1) process with unit-variance
+    /// innovations drawn by `draw`
+    fn simulate(
+        n: usize,
+        (omega, alpha, gamma, beta): (f64, f64, f64, f64),
+        mut draw: impl FnMut() -> f64,
+    ) -> Vec<f64> {
+        let mut variance = omega / (1.0 - alpha - 0.5 * gamma - beta);
+        let mut returns = Vec::with_capacity(n);
+        for _ in 0..n {
+            let shock = variance.sqrt() * draw();
+            let leverage = if shock < 0.0 { gamma } else { 0.0 };
+            variance = omega + (alpha + leverage) * shock * shock + beta * variance;
+            returns.push(shock);
+        }
+        returns
+    }
+
+    fn normal_returns(seed: u64, gamma: f64) -> Vec<f64> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        simulate(3000, (2e-6, 0.08, gamma, 0.88 - 0.5 * gamma), || {
+            normal.sample(&mut rng)
         })
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_volatility_tracks_regime() {
+        let fitted = GarchModel::new(1, 1)
+            .unwrap()
+            .fit_series(&returns())
+            .unwrap();
+        let vol = fitted.fitted_values();
+
+        assert_eq!(vol.len(), 200);
+        assert!(vol[99] > vol[49]);
+        assert!(vol[99] > vol[199]);
+    }
+
+    #[test]
+    fn test_recovers_garch_parameters() {
+        let data = normal_returns(7, 0.0);
+        let fitted = GarchModel::new(1, 1).unwrap().fit_series(&data).unwrap();
+
+        assert!((fitted.alpha()[0] - 0.08).abs() < 0.04);
+        assert!((fitted.beta()[0] - 0.88).abs() < 0.06);
+        assert!(fitted.persistence() < 1.0);
+        assert!(fitted.gamma().is_empty());
+    }
+
+    #[test]
+    fn test_asymmetric_models_detect_leverage() {
+        let data = normal_returns(13, 0.12);
+
+        let gjr = GarchModel::gjr(1, 1).unwrap().fit_series(&data).unwrap();
+        assert!(gjr.gamma()[0] > 0.04);
+        let garch = GarchModel::new(1, 1).unwrap().fit_series(&data).unwrap();
+        assert!(gjr.aic() < garch.aic());
+
+        // Negative shocks raise EGARCH log variance through a negative gamma
+        let egarch = GarchModel::egarch(1, 1).unwrap().fit_series(&data).unwrap();
+        assert!(egarch.gamma()[0] < 0.0);
+        assert!(egarch.beta()[0] > 0.8 && egarch.beta()[0] < 1.0);
+    }
+
+    #[test]
+    fn test_student_t_innovations() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let t = StudentT::new(5.0).unwrap();
+        // Scale to unit variance
+        let scale = (3.0_f64 / 5.0).sqrt();
+        let data = simulate(3000, (2e-6, 0.08, 0.0, 0.88), || scale * t.sample(&mut rng));
+
+        let model = GarchModel::new(1, 1)
+            .unwrap()
+            .with_distribution(Innovation::StudentT);
+        assert_eq!(model.name(), "GARCH(1,1) Student-t");
+        let student = model.fit_series(&data).unwrap();
+        let normal = GarchModel::new(1, 1).unwrap().fit_series(&data).unwrap();
+
+        let nu = student.degrees_of_freedom().unwrap();
+        assert!(nu > 3.0 && nu < 10.0, "nu = {}", nu);
+        assert!(student.log_likelihood() > normal.log_likelihood());
+        assert!(normal.degrees_of_freedom().is_none());
+    }
+
+    #[test]
+    fn test_forecast_reverts_to_unconditional_variance() {
+        for model in [
+            GarchModel::new(1, 1).unwrap(),
+            GarchModel::gjr(1, 1).unwrap(),
+        ] {
+            let fitted = model.fit_series(&normal_returns(5, 0.1)).unwrap();
+            let long_run = fitted.unconditional_variance().unwrap();
+
+            let variances = fitted.forecast_variance(2000);
+            assert!((variances[1999] - long_run).abs() < 1e-3 * long_run);
+            assert!((fitted.forecast_volatility(1)[0] - variances[0].sqrt()).abs() < 1e-15);
+        }
+    }
+
+    #[test]
+    fn test_simulated_forecast_distribution() {
+        let data = normal_returns(5, 0.0);
+        let fitted = GarchModel::new(1, 1)
+            .unwrap()
+            .with_simulation(2000, 9)
+            .unwrap()
+            .fit_series(&data)
+            .unwrap();
+
+        // The next variance is known, later ones spread out
+        let forecast = fitted.forecast(10).unwrap();
+        let intervals = forecast.confidence_intervals(0.9).unwrap();
+        assert!((intervals[0].1 - intervals[0].0).abs() < 1e-15);
+        assert!(intervals[9].0 < forecast.values()[9] && forecast.values()[9] < intervals[9].1);
+
+        // Simulated one-step returns match the analytic volatility
+        let returns = fitted.forecast_returns(1).unwrap();
+        let se = returns.standard_errors().unwrap()[0];
+        assert!((se / fitted.forecast_volatility(1)[0] - 1.0).abs() < 0.1);
+        let var_5 = returns.quantile(0.05).unwrap()[0] - fitted.mean();
+        assert!((var_5 / se + 1.645).abs() < 0.2);
+
+        // Same seed, same paths
+        let again = fitted.forecast_returns(1).unwrap();
+        assert_eq!(
+            again.quantile(0.05).unwrap(),
+            returns.quantile(0.05).unwrap()
+        );
+        assert!(GarchModel::new(1, 1)
+            .unwrap()
+            .with_simulation(0, 1)
+            .is_err());
+    }
+
+    #[test]
+    fn test_update_extends_residuals() {
+        let data = returns();
+        let mut fitted = GarchModel::new(1, 1).unwrap().fit_series(&data).unwrap();
+        fitted.update(&[0.05]).unwrap();
+
+        assert_eq!(fitted.residuals().len(), 201);
+        assert_eq!(fitted.standardized_residuals().len(), 201);
+        assert!(fitted.forecast(1).unwrap().values()[0] > fitted.fitted_values()[200]);
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(GarchModel::new(1, 0).is_err());
+        assert!(GarchModel::egarch(1, 0).is_err());
+        assert!(GarchModel::new(1, 1).unwrap().fit_series(&[0.01]).is_err());
+        assert!(GarchModel::new(1, 1)
+            .unwrap()
+            .fit_series(&[0.01; 50])
+            .is_err());
+    }
+}
@@ -0,0 +1,148 @@
+//! k-nearest-neighbour regression
+//!
+//! Predicts the average target of the `k` training rows closest to the
+//! query in Euclidean distance over standardised features.
+
+use crate::error::{ForecastError, Result};
+use crate::models::ml::{check_training_set, FittedRegressor, Regressor, Standardizer};
+
+/// k-nearest-neighbour regression
+#[derive(Debug, Clone)]
+pub struct KNearestNeighbors {
+    k: usize,
+    distance_weighted: bool,
+}
+
+impl KNearestNeighbors {
+    /// Create a regression averaging the `k` nearest rows
+    pub fn new(k: usize) -> Result<Self> {
+        if k == 0 {
+            return Err(ForecastError::InvalidParameter(
+                "Number of neighbours must be positive".to_string(),
+            ));
+        }
+        Ok(Self {
+            k,
+            distance_weighted: false,
+        })
+    }
+
+    /// Weight neighbours by inverse distance instead of equally
+    pub fn with_distance_weighting(mut self, distance_weighted: bool) -> Self {
+        self.distance_weighted = distance_weighted;
+        self
+    }
+
+    /// Number of neighbours
+    pub fn k(&self) -> usize {
+        self.k
+    }
+}
+
+impl Regressor for KNearestNeighbors {
+    type Fitted = FittedKNearestNeighbors;
+
+    fn name(&self) -> &str {
+        "k-NN"
+    }
+
+    fn fit(&self, x: &[Vec<f64>], y: &[f64]) -> Result<FittedKNearestNeighbors> {
+        check_training_set(x, y, self.k)?;
+        let scaler = Standardizer::fit(x);
+        Ok(FittedKNearestNeighbors {
+            k: self.k,
+            distance_weighted: self.distance_weighted,
+            rows: x.iter().map(|row| scaler.transform(row)).collect(),
+            targets: y.to_vec(),
+            scaler,
+        })
+    }
+}
+
+/// Fitted k-nearest-neighbour regression
+#[derive(Debug, Clone)]
+pub struct FittedKNearestNeighbors {
+    k: usize,
+    distance_weighted: bool,
+    scaler: Standardizer,
+    rows: Vec<Vec<f64>>,
+    targets: Vec<f64>,
+}
+
+impl FittedKNearestNeighbors {
+    /// Indices of the training rows nearest to `row`, closest first
+    pub fn neighbours(&self, row: &[f64]) -> Vec<usize> {
+        self.nearest(row).into_iter().map(|(i, _)| i).collect()
+    }
+
+    fn nearest(&self, row: &[f64]) -> Vec<(usize, f64)> {
+        let query = self.scaler.transform(row);
+        let mut distances: Vec<(usize, f64)> = self
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(i, r)| {
+                let squared: f64 = r.iter().zip(&query).map(|(a, b)| (a - b).powi(2)).sum();
+                (i, squared.sqrt())
+            })
+            .collect();
+        distances.sort_by(|a, b| a.1.total_cmp(&b.1));
+        distances.truncate(self.k);
+        distances
+    }
+}
+
+impl FittedRegressor for FittedKNearestNeighbors {
+    fn predict(&self, row: &[f64]) -> f64 {
+        let nearest = self.nearest(row);
+        if self.distance_weighted {
+            // An exact match takes all the weight
+            if let Some(&(i, _)) = nearest.iter().find(|(_, d)| *d == 0.0) {
+                return self.targets[i];
+            }
+            let (sum, weights) = nearest.iter().fold((0.0, 0.0), |(s, w), &(i, d)| {
+                (s + self.targets[i] / d, w + 1.0 / d)
+            });
+            sum / weights
+        } else {
+            nearest.iter().map(|&(i, _)| self.targets[i]).sum::<f64>() / nearest.len() as f64
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_neighbours_on_standardised_scale() {
+        // The second feature has a much larger scale but the same spread in
+        // standard deviations
+        let x: Vec<Vec<f64>> = (0..10)
+            .map(|i| vec![i as f64, 1000.0 * (i % 2) as f64])
+            .collect();
+        let y: Vec<f64> = (0..10).map(|i| i as f64).collect();
+        let fitted = KNearestNeighbors::new(3).unwrap().fit(&x, &y).unwrap();
+
+        assert_eq!(fitted.neighbours(&[4.0, 0.0])[0], 4);
+        assert!((fitted.predict(&[4.0, 0.0]) - 4.0).abs() < 1.5);
+    }
+
+    #[test]
+    fn test_distance_weighting() {
+        let x = vec![vec![0.0], vec![1.0], vec![10.0]];
+        let y = vec![0.0, 1.0, 10.0];
+        let uniform = KNearestNeighbors::new(2).unwrap().fit(&x, &y).unwrap();
+        let weighted = KNearestNeighbors::new(2)
+            .unwrap()
+            .with_distance_weighting(true)
+            .fit(&x, &y)
+            .unwrap();
+
+        assert_eq!(uniform.predict(&[0.2]), 0.5);
+        assert!(weighted.predict(&[0.2]) < 0.5);
+        assert_eq!(weighted.predict(&[1.0]), 1.0);
+        assert!(KNearestNeighbors::new(0).is_err());
+        assert!(KNearestNeighbors::new(5).unwrap().fit(&x, &y).is_err());
+    }
+}
@@ -0,0 +1,489 @@
+//! Machine-learning forecast models on engineered features
+//!
+//! An `MlModel` pairs a `FeatureSet` with a regressor that predicts the
+//! next-bar return from the features of the current bar. Training only uses
+//! bars whose next bar is known, and every prediction is made from features
+//! of bars up to the one being predicted from, so there is no look-ahead.
+//! Multi-step forecasts are built recursively from predicted closes, so they
+//! need price-only features: the volume and timestamp of a predicted bar
+//! are unknown.
+
+pub mod knn;
+pub mod linear;
+pub mod tree;
+
+use std::fmt::Debug;
+
+use crate::data::TimeSeriesData;
+use crate::error::{ForecastError, Result};
+use crate::features::{Bars, FeatureSet, FeatureStream};
+use crate::models::{FittedModel, ForecastModel, ForecastResult};
+
+/// Learner mapping feature rows to a target
+pub trait Regressor: Debug + Clone {
+    /// The fitted learner
+    type Fitted: FittedRegressor;
+
+    /// Name of the learner
+    fn name(&self) -> &str;
+
+    /// Fit to feature rows `x` and targets `y`
+    fn fit(&self, x: &[Vec<f64>], y: &[f64]) -> Result<Self::Fitted>;
+}
+
+/// Fitted learner
+pub trait FittedRegressor: Debug {
+    /// Predict the target for one feature row
+    fn predict(&self, row: &[f64]) -> f64;
+}
+
+/// Check a training set and return its number of features
+pub(crate) fn check_training_set(x: &[Vec<f64>], y: &[f64], min_rows: usize) -> Result<usize> {
+    if x.len() != y.len() {
+        return Err(ForecastError::ValidationError(format!(
+            "Feature rows ({}) don't match targets ({})",
+            x.len(),
+            y.len()
+        )));
+    }
+    if x.len() < min_rows {
+        return Err(ForecastError::DataError(format!(
+            "Need at least {} training rows, have {}",
+            min_rows,
+            x.len()
+        )));
+    }
+    let width = x[0].len();
+    if width == 0 || x.iter().any(|row| row.len() != width) {
+        return Err(ForecastError::ValidationError(
+            "Feature rows must be non-empty and of equal length".to_string(),
+        ));
+    }
+    if x.iter().flatten().chain(y).any(|v| !v.is_finite()) {
+        return Err(ForecastError::DataError(
+            "Training data contains non-finite values".to_string(),
+        ));
+    }
+    Ok(width)
+}
+
+/// Per-feature centring and scaling to unit variance
+#[derive(Debug, Clone)]
+pub(crate) struct Standardizer {
+    means: Vec<f64>,
+    scales: Vec<f64>,
+}
+
+impl Standardizer {
+    pub(crate) fn fit(x: &[Vec<f64>]) -> Self {
+        let n = x.len() as f64;
+        let width = x[0].len();
+        let means: Vec<f64> = (0..width)
+            .map(|j| x.iter().map(|row| row[j]).sum::<f64>() / n)
+            .collect();
+        let scales = (0..width)
+            .map(|j| {
+                let variance = x.iter().map(|row| (row[j] - means[j]).powi(2)).sum::<f64>() / n;
+                // Constant features are left unscaled
+                if variance > 0.0 {
+                    variance.sqrt()
+                } else {
+                    1.0
+                }
+            })
+            .collect();
+        Self { means, scales }
+    }
+
+    pub(crate) fn transform(&self, row: &[f64]) -> Vec<f64> {
+        row.iter()
+            .zip(self.means.iter().zip(&self.scales))
+            .map(|(v, (m, s))| (v - m) / s)
+            .collect()
+    }
+
+    pub(crate) fn means(&self) -> &[f64] {
+        &self.means
+    }
+
+    pub(crate) fn scales(&self) -> &[f64] {
+        &self.scales
+    }
+}
+
+/// Forecast model predicting next-bar returns from engineered features
+#[derive(Debug, Clone)]
+pub struct MlModel<R: Regressor> {
+    name: String,
+    features: FeatureSet,
+    regressor: R,
+    min_training: usize,
+}
+
+impl<R: Regressor> MlModel<R> {
+    /// Create a model from a feature set and a regressor
+    pub fn new(features: FeatureSet, regressor: R) -> Result<Self> {
+        if features.is_empty() {
+            return Err(ForecastError::InvalidParameter(
+                "Feature set is empty".to_string(),
+            ));
+        }
+        Ok(Self {
+            name: format!("{} ({} features)", regressor.name(), features.len()),
+            min_training: 2 * features.len() + 10,
+            features,
+            regressor,
+        })
+    }
+
+    /// Require at least `rows` training rows (twice the feature count plus
+    /// ten by default)
+    pub fn with_min_training(mut self, rows: usize) -> Result<Self> {
+        if rows == 0 {
+            return Err(ForecastError::InvalidParameter(
+                "Minimum training size must be positive".to_string(),
+            ));
+        }
+        self.min_training = rows;
+        Ok(self)
+    }
+
+    /// Feature set
+    pub fn features(&self) -> &FeatureSet {
+        &self.features
+    }
+
+    /// Regressor
+    pub fn regressor(&self) -> &R {
+        &self.regressor
+    }
+
+    /// Fit to bars carrying any volumes and timestamps the features need
+    ///
+    /// Only the fields the features read are kept for updates.
+    pub fn fit_bars(&self, bars: Bars) -> Result<FittedMlModel<R::Fitted>> {
+        let (matrix, stream) = self.features.build_stream(&bars)?;
+        let (x, y) = matrix.training_set();
+        if x.len() < self.min_training {
+            return Err(ForecastError::DataError(format!(
+                "Need at least {} training rows after feature warm-up, have {}",
+                self.min_training,
+                x.len()
+            )));
+        }
+        let regressor = self.regressor.fit(&x, &y)?;
+
+        // Bars without a prediction are their own fitted value
+        let close = bars.close();
+        let mut fitted_values = close.to_vec();
+        for (&t, row) in matrix.index().iter().zip(matrix.rows()) {
+            if t + 1 < close.len() {
+                fitted_values[t + 1] = close[t] * (1.0 + regressor.predict(row));
+            }
+        }
+        let residuals = close
+            .iter()
+            .zip(&fitted_values)
+            .map(|(y, f)| y - f)
+            .collect();
+
+        let mut kept = Bars::new(bars.close().to_vec())?;
+        if let Some(volume) = bars.volume().filter(|_| self.features.needs_volume()) {
+            kept = kept.with_volume(volume.to_vec())?;
+        }
+        if let Some(timestamps) = bars
+            .timestamps()
+            .filter(|_| self.features.needs_timestamps())
+        {
+            kept = kept.with_timestamps(timestamps.to_vec())?;
+        }
+
+        Ok(FittedMlModel {
+            name: self.name.clone(),
+            features: self.features.clone(),
+            regressor,
+            training_rows: x.len(),
+            last_row: matrix.row_at(bars.len() - 1).map(<[f64]>::to_vec),
+            stream,
+            bars: kept,
+            fitted_values,
+            residuals,
+        })
+    }
+}
+
+impl<R: Regressor> ForecastModel for MlModel<R> {
+    type Fitted = FittedMlModel<R::Fitted>;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Fit on closing prices only; volume and calendar features fail
+    fn fit_series(&self, values: &[f64]) -> Result<Self::Fitted> {
+        self.fit_bars(Bars::new(values.to_vec())?)
+    }
+
+    fn fit(&self, data: &TimeSeriesData) -> Result<Self::Fitted> {
+        self.fit_bars(Bars::from_data(data)?)
+    }
+}
+
+/// Machine-learning model fitted to bars
+#[derive(Debug)]
+pub struct FittedMlModel<F: FittedRegressor> {
+    name: String,
+    features: FeatureSet,
+    regressor: F,
+    training_rows: usize,
+    /// Feature state after the last bar
+    stream: FeatureStream,
+    /// Features of the last bar, `None` while warming up
+    last_row: Option<Vec<f64>>,
+    bars: Bars,
+    fitted_values: Vec<f64>,
+    residuals: Vec<f64>,
+}
+
+impl<F: FittedRegressor> FittedMlModel<F> {
+    /// Fitted regressor
+    pub fn regressor(&self) -> &F {
+        &self.regressor
+    }
+
+    /// Bars seen so far, including updates, with the fields the features
+    /// read
+    pub fn bars(&self) -> &Bars {
+        &self.bars
+    }
+
+    /// Number of rows the regressor was trained on
+    pub fn training_rows(&self) -> usize {
+        self.training_rows
+    }
+
+    /// Predicted return from the last bar to the next
+    pub fn predicted_return(&self) -> Result<f64> {
+        Self::predict(&self.regressor, self.last_row.as_deref())
+    }
+
+    /// Predicted one-bar returns over `horizon` bars, each step treating the
+    /// previous predicted close as observed
+    ///
+    /// More than one step needs price-only features.
+    pub fn predicted_returns(&self, horizon: usize) -> Result<Vec<f64>> {
+        if horizon > 1 && (self.features.needs_volume() || self.features.needs_timestamps()) {
+            return Err(ForecastError::ForecastingError(
+                "Multi-step forecasts need price-only features; the volume and time of \
+                 predicted bars are unknown"
+                    .to_string(),
+            ));
+        }
+
+        let mut stream = self.stream.clone();
+        let mut row = self.last_row.clone();
+        let mut price = *self.bars.close().last().unwrap();
+        let mut returns = Vec::with_capacity(horizon);
+        for step in 0..horizon {
+            let r = Self::predict(&self.regressor, row.as_deref())?;
+            returns.push(r);
+            if step + 1 < horizon {
+                price *= 1.0 + r;
+                row = stream.push(price, None, None)?;
+            }
+        }
+        Ok(returns)
+    }
+
+    /// Append observed bars without retraining
+    ///
+    /// `bars` must carry the volumes and timestamps the features read.
+    pub fn update_bars(&mut self, bars: &Bars) -> Result<()> {
+        for t in 0..bars.len() {
+            let (close, volume, timestamp) = bars.bar(t);
+            let volume = volume.filter(|_| self.features.needs_volume());
+            let timestamp = timestamp.filter(|_| self.features.needs_timestamps());
+
+            let last = *self.bars.close().last().unwrap();
+            let prediction = last * (1.0 + self.predicted_return()?);
+            self.bars.push(close, volume, timestamp)?;
+            self.last_row = self.stream.push(close, volume, timestamp)?;
+            self.fitted_values.push(prediction);
+            self.residuals.push(close - prediction);
+        }
+        Ok(())
+    }
+
+    fn predict(regressor: &F, row: Option<&[f64]>) -> Result<f64> {
+        let row = row.ok_or_else(|| {
+            ForecastError::ForecastingError("Features of the last bar are unavailable".to_string())
+        })?;
+        Ok(regressor.predict(row))
+    }
+}
+
+impl<F: FittedRegressor> FittedModel for FittedMlModel<F> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Closes implied by the predicted returns
+    fn forecast(&self, horizon: usize) -> Result<ForecastResult> {
+        let mut price = *self.bars.close().last().unwrap();
+        let values = self
+            .predicted_returns(horizon)?
+            .into_iter()
+            .map(|r| {
+                price *= 1.0 + r;
+                price
+            })
+            .collect();
+        ForecastResult::new(values, horizon)
+    }
+
+    /// Append observed closes without retraining
+    ///
+    /// Models with volume or calendar features are updated with
+    /// `update_bars` instead.
+    fn update(&mut self, observations: &[f64]) -> Result<()> {
+        if self.features.needs_volume() || self.features.needs_timestamps() {
+            return Err(ForecastError::ValidationError(format!(
+                "{} reads volumes or timestamps; update it with update_bars",
+                self.name
+            )));
+        }
+        self.update_bars(&Bars::new(observations.to_vec())?)
+    }
+
+    /// One-step predictions; bars without one (warm-up) repeat the close
+    fn fitted_values(&self) -> &[f64] {
+        &self.fitted_values
+    }
+
+    fn residuals(&self) -> &[f64] {
+        &self.residuals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::linear::RidgeRegression;
+    use super::*;
+    use crate::features::Feature;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use rand_distr::{Distribution, Normal};
+
+    /// Prices whose returns follow an AR(1) with coefficient 0.5
+    fn ar_prices(n: usize) -> Vec<f64> {
+        let mut rng = StdRng::seed_from_u64(3);
+        let normal = Normal::new(0.0, 0.01).unwrap();
+        let mut prices = vec![100.0];
+        let mut r = 0.0;
+        for _ in 1..n {
+            r = 0.5 * r + normal.sample(&mut rng);
+            prices.push(prices.last().unwrap() * (1.0 + r));
+        }
+        prices
+    }
+
+    fn model() -> MlModel<RidgeRegression> {
+        let features = FeatureSet::new()
+            .with_feature(Feature::LaggedReturn(1))
+            .unwrap()
+            .with_feature(Feature::LaggedReturn(2))
+            .unwrap();
+        MlModel::new(features, RidgeRegression::new(0.1).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_learns_return_autocorrelation() {
+        let prices = ar_prices(400);
+        let fitted = model().fit_series(&prices).unwrap();
+        let coefficients = fitted.regressor().coefficients();
+        assert!((coefficients[0] - 0.5).abs() < 0.15);
+        assert!(coefficients[1].abs() < 0.15);
+
+        let r = fitted.predicted_return().unwrap();
+        let last = prices[399] / prices[398] - 1.0;
+        assert!(
+            (r - fitted
+                .regressor()
+                .predict(&[last, prices[398] / prices[397] - 1.0]))
+            .abs()
+                < 1e-12
+        );
+
+        let forecast = fitted.forecast(3).unwrap();
+        assert!((forecast.values()[0] - prices[399] * (1.0 + r)).abs() < 1e-9);
+        assert_eq!(fitted.fitted_values().len(), prices.len());
+        assert_eq!(fitted.fitted_values()[0], prices[0]);
+    }
+
+    #[test]
+    fn test_fitted_values_use_past_bars_only() {
+        let prices = ar_prices(300);
+        let fitted = model().fit_series(&prices).unwrap();
+        for t in [50, 150, 298] {
+            let past = Bars::new(prices[..=t].to_vec()).unwrap();
+            let matrix = model().features().build(&past).unwrap();
+            let row = matrix.row_at(t).unwrap();
+            let expected = prices[t] * (1.0 + fitted.regressor().predict(row));
+            assert!((fitted.fitted_values()[t + 1] - expected).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_update_appends_predictions() {
+        let prices = ar_prices(200);
+        let mut fitted = model().fit_series(&prices[..190]).unwrap();
+        let next = fitted.forecast(1).unwrap().values()[0];
+        fitted.update(&prices[190..]).unwrap();
+        assert_eq!(fitted.fitted_values().len(), 200);
+        assert_eq!(fitted.fitted_values()[190], next);
+        assert_eq!(fitted.bars().len(), 200);
+    }
+
+    #[test]
+    fn test_volume_features_update_with_bars() {
+        let prices = ar_prices(120);
+        let volume: Vec<f64> = (0..120).map(|i| 1000.0 + (i % 5) as f64 * 50.0).collect();
+        let bars = |range: std::ops::Range<usize>| {
+            Bars::new(prices[range.clone()].to_vec())
+                .unwrap()
+                .with_volume(volume[range].to_vec())
+                .unwrap()
+        };
+        let features = FeatureSet::new()
+            .with_feature(Feature::LaggedReturn(1))
+            .unwrap()
+            .with_feature(Feature::RelativeVolume(5))
+            .unwrap();
+        let model = MlModel::new(features, RidgeRegression::new(0.1).unwrap()).unwrap();
+
+        let mut fitted = model.fit_bars(bars(0..110)).unwrap();
+        // Closes alone would leave the volume of the new bars unknown
+        assert!(fitted.update(&prices[110..]).is_err());
+        assert!(fitted.forecast(2).is_err());
+
+        fitted.update_bars(&bars(110..120)).unwrap();
+        assert_eq!(fitted.fitted_values().len(), 120);
+
+        // Same prediction as building the features over all bars
+        let matrix = model.features().build(&bars(0..120)).unwrap();
+        let expected = fitted.regressor().predict(matrix.row_at(119).unwrap());
+        assert!((fitted.predicted_return().unwrap() - expected).abs() < 1e-12);
+        assert!(fitted
+            .update_bars(&Bars::new(vec![101.0]).unwrap())
+            .is_err());
+    }
+
+    #[test]
+    fn test_validation() {
+        let ridge = RidgeRegression::new(1.0).unwrap();
+        assert!(MlModel::new(FeatureSet::new(), ridge.clone()).is_err());
+        let m = MlModel::new(FeatureSet::default(), ridge).unwrap();
+        assert!(m.fit_series(&ar_prices(40)).is_err());
+        assert!(m.with_min_training(0).is_err());
+    }
+}
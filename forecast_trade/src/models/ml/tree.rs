@@ -0,0 +1,507 @@
+//! Regression trees and tree ensembles
+//!
+//! Trees are grown greedily by the split that most reduces the squared
+//! error. Gradient boosting fits shallow trees to the residuals of the
+//! trees before it; a random forest averages deep trees grown on bootstrap
+//! samples with a random subset of features tried at each split.
+
+use rand::rngs::StdRng;
+use rand::seq::index::sample;
+use rand::{Rng, SeedableRng};
+
+use crate::error::{ForecastError, Result};
+use crate::models::ml::{check_training_set, FittedRegressor, Regressor};
+use crate::utils::DEFAULT_SEED;
+
+/// Single regression tree
+#[derive(Debug, Clone)]
+pub struct RegressionTree {
+    max_depth: usize,
+    min_samples_leaf: usize,
+}
+
+impl RegressionTree {
+    /// Create a tree grown to at most `max_depth` levels of splits
+    pub fn new(max_depth: usize) -> Result<Self> {
+        if max_depth == 0 {
+            return Err(ForecastError::InvalidParameter(
+                "Maximum depth must be positive".to_string(),
+            ));
+        }
+        Ok(Self {
+            max_depth,
+            min_samples_leaf: 5,
+        })
+    }
+
+    /// Set the minimum number of rows in a leaf (5 by default)
+    pub fn with_min_samples_leaf(mut self, min_samples_leaf: usize) -> Result<Self> {
+        if min_samples_leaf == 0 {
+            return Err(ForecastError::InvalidParameter(
+                "Minimum leaf size must be positive".to_string(),
+            ));
+        }
+        self.min_samples_leaf = min_samples_leaf;
+        Ok(self)
+    }
+
+    /// Grow a tree on the rows in `indices`, trying `max_features` random
+    /// features at each split when given
+    fn grow(
+        &self,
+        x: &[Vec<f64>],
+        y: &[f64],
+        indices: Vec<usize>,
+        max_features: Option<(usize, &mut StdRng)>,
+    ) -> FittedRegressionTree {
+        let mut builder = TreeBuilder {
+            x,
+            y,
+            max_depth: self.max_depth,
+            min_samples_leaf: self.min_samples_leaf,
+            max_features,
+            nodes: Vec::new(),
+        };
+        builder.build(indices, 0);
+        FittedRegressionTree {
+            nodes: builder.nodes,
+        }
+    }
+}
+
+impl Regressor for RegressionTree {
+    type Fitted = FittedRegressionTree;
+
+    fn name(&self) -> &str {
+        "Regression Tree"
+    }
+
+    fn fit(&self, x: &[Vec<f64>], y: &[f64]) -> Result<FittedRegressionTree> {
+        check_training_set(x, y, 2 * self.min_samples_leaf)?;
+        Ok(self.grow(x, y, (0..x.len()).collect(), None))
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Leaf(f64),
+    Split {
+        feature: usize,
+        threshold: f64,
+        left: usize,
+        right: usize,
+    },
+}
+
+struct TreeBuilder<'a> {
+    x: &'a [Vec<f64>],
+    y: &'a [f64],
+    max_depth: usize,
+    min_samples_leaf: usize,
+    max_features: Option<(usize, &'a mut StdRng)>,
+    nodes: Vec<Node>,
+}
+
+impl TreeBuilder<'_> {
+    /// Build the subtree over `indices` and return its node index
+    fn build(&mut self, indices: Vec<usize>, depth: usize) -> usize {
+        let mean = indices.iter().map(|&i| self.y[i]).sum::<f64>() / indices.len() as f64;
+        let node = self.nodes.len();
+        self.nodes.push(Node::Leaf(mean));
+
+        if depth >= self.max_depth || indices.len() < 2 * self.min_samples_leaf {
+            return node;
+        }
+        let Some((feature, threshold)) = self.best_split(&indices) else {
+            return node;
+        };
+
+        let (left, right): (Vec<usize>, Vec<usize>) = indices
+            .into_iter()
+            .partition(|&i| self.x[i][feature] <= threshold);
+        let left = self.build(left, depth + 1);
+        let right = self.build(right, depth + 1);
+        self.nodes[node] = Node::Split {
+            feature,
+            threshold,
+            left,
+            right,
+        };
+        node
+    }
+
+    /// Feature and threshold with the largest reduction in squared error
+    fn best_split(&mut self, indices: &[usize]) -> Option<(usize, f64)> {
+        let width = self.x[0].len();
+        let candidates: Vec<usize> = match &mut self.max_features {
+            Some((m, rng)) if *m < width => sample(*rng, width, *m).into_vec(),
+            _ => (0..width).collect(),
+        };
+
+        let n = indices.len();
+        let total: f64 = indices.iter().map(|&i| self.y[i]).sum();
+        let base = total * total / n as f64;
+        let mut best: Option<(f64, usize, f64)> = None;
+        let mut sorted = indices.to_vec();
+
+        for feature in candidates {
+            sorted.sort_by(|&a, &b| self.x[a][feature].total_cmp(&self.x[b][feature]));
+            let mut left_sum = 0.0;
+            for split in 1..n {
+                left_sum += self.y[sorted[split - 1]];
+                let below = self.x[sorted[split - 1]][feature];
+                let above = self.x[sorted[split]][feature];
+                if split < self.min_samples_leaf || n - split < self.min_samples_leaf {
+                    continue;
+                }
+                if below == above {
+                    continue;
+                }
+                let right_sum = total - left_sum;
+                let gain = left_sum * left_sum / split as f64
+                    + right_sum * right_sum / (n - split) as f64
+                    - base;
+                if gain > 1e-12 && best.is_none_or(|(g, _, _)| gain > g) {
+                    best = Some((gain, feature, (below + above) / 2.0));
+                }
+            }
+        }
+        best.map(|(_, feature, threshold)| (feature, threshold))
+    }
+}
+
+/// Fitted regression tree
+#[derive(Debug, Clone)]
+pub struct FittedRegressionTree {
+    nodes: Vec<Node>,
+}
+
+impl FittedRegressionTree {
+    /// Number of leaves
+    pub fn leaf_count(&self) -> usize {
+        self.nodes
+            .iter()
+            .filter(|n| matches!(n, Node::Leaf(_)))
+            .count()
+    }
+}
+
+impl FittedRegressor for FittedRegressionTree {
+    fn predict(&self, row: &[f64]) -> f64 {
+        let mut node = 0;
+        loop {
+            match self.nodes[node] {
+                Node::Leaf(value) => return value,
+                Node::Split {
+                    feature,
+                    threshold,
+                    left,
+                    right,
+                } => {
+                    node = if row[feature] <= threshold {
+                        left
+                    } else {
+                        right
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Gradient-boosted regression trees with squared loss
+#[derive(Debug, Clone)]
+pub struct GradientBoosting {
+    n_estimators: usize,
+    learning_rate: f64,
+    tree: RegressionTree,
+    subsample: f64,
+    seed: u64,
+}
+
+impl GradientBoosting {
+    /// Create a booster of `n_estimators` trees of depth `max_depth`, each
+    /// scaled by `learning_rate`
+    pub fn new(n_estimators: usize, learning_rate: f64, max_depth: usize) -> Result<Self> {
+        if n_estimators == 0 {
+            return Err(ForecastError::InvalidParameter(
+                "Number of estimators must be positive".to_string(),
+            ));
+        }
+        if !(learning_rate > 0.0 && learning_rate <= 1.0) {
+            return Err(ForecastError::InvalidParameter(
+                "Learning rate must be in (0, 1]".to_string(),
+            ));
+        }
+        Ok(Self {
+            n_estimators,
+            learning_rate,
+            tree: RegressionTree::new(max_depth)?,
+            subsample: 1.0,
+            seed: DEFAULT_SEED,
+        })
+    }
+
+    /// Fit each tree on a random fraction of the rows
+    pub fn with_subsample(mut self, fraction: f64) -> Result<Self> {
+        if !(fraction > 0.0 && fraction <= 1.0) {
+            return Err(ForecastError::InvalidParameter(
+                "Subsample fraction must be in (0, 1]".to_string(),
+            ));
+        }
+        self.subsample = fraction;
+        Ok(self)
+    }
+
+    /// Set the minimum number of rows in a leaf (5 by default)
+    pub fn with_min_samples_leaf(mut self, min_samples_leaf: usize) -> Result<Self> {
+        self.tree = self.tree.with_min_samples_leaf(min_samples_leaf)?;
+        Ok(self)
+    }
+
+    /// Set the seed for row subsampling
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+impl Regressor for GradientBoosting {
+    type Fitted = FittedGradientBoosting;
+
+    fn name(&self) -> &str {
+        "Gradient Boosting"
+    }
+
+    fn fit(&self, x: &[Vec<f64>], y: &[f64]) -> Result<FittedGradientBoosting> {
+        check_training_set(x, y, 2 * self.tree.min_samples_leaf)?;
+        let n = x.len();
+        let rows = ((n as f64 * self.subsample).round() as usize).clamp(1, n);
+        let mut rng = StdRng::seed_from_u64(self.seed);
+
+        let initial = y.iter().sum::<f64>() / n as f64;
+        let mut predictions = vec![initial; n];
+        let mut trees = Vec::with_capacity(self.n_estimators);
+        for _ in 0..self.n_estimators {
+            let residuals: Vec<f64> = y.iter().zip(&predictions).map(|(t, p)| t - p).collect();
+            let indices = if rows < n {
+                sample(&mut rng, n, rows).into_vec()
+            } else {
+                (0..n).collect()
+            };
+            let tree = self.tree.grow(x, &residuals, indices, None);
+            for (prediction, row) in predictions.iter_mut().zip(x) {
+                *prediction += self.learning_rate * tree.predict(row);
+            }
+            trees.push(tree);
+        }
+
+        Ok(FittedGradientBoosting {
+            initial,
+            learning_rate: self.learning_rate,
+            trees,
+        })
+    }
+}
+
+/// Fitted gradient-boosted trees
+#[derive(Debug, Clone)]
+pub struct FittedGradientBoosting {
+    initial: f64,
+    learning_rate: f64,
+    trees: Vec<FittedRegressionTree>,
+}
+
+impl FittedGradientBoosting {
+    /// Fitted trees in boosting order
+    pub fn trees(&self) -> &[FittedRegressionTree] {
+        &self.trees
+    }
+}
+
+impl FittedRegressor for FittedGradientBoosting {
+    fn predict(&self, row: &[f64]) -> f64 {
+        self.initial + self.learning_rate * self.trees.iter().map(|t| t.predict(row)).sum::<f64>()
+    }
+}
+
+/// Random forest of regression trees
+#[derive(Debug, Clone)]
+pub struct RandomForest {
+    n_trees: usize,
+    tree: RegressionTree,
+    max_features: Option<usize>,
+    seed: u64,
+}
+
+impl RandomForest {
+    /// Create a forest of `n_trees` trees of depth at most `max_depth`
+    pub fn new(n_trees: usize, max_depth: usize) -> Result<Self> {
+        if n_trees == 0 {
+            return Err(ForecastError::InvalidParameter(
+                "Number of trees must be positive".to_string(),
+            ));
+        }
+        Ok(Self {
+            n_trees,
+            tree: RegressionTree::new(max_depth)?,
+            max_features: None,
+            seed: DEFAULT_SEED,
+        })
+    }
+
+    /// Features tried at each split (a third of them by default)
+    pub fn with_max_features(mut self, max_features: usize) -> Result<Self> {
+        if max_features == 0 {
+            return Err(ForecastError::InvalidParameter(
+                "Maximum features must be positive".to_string(),
+            ));
+        }
+        self.max_features = Some(max_features);
+        Ok(self)
+    }
+
+    /// Set the minimum number of rows in a leaf (5 by default)
+    pub fn with_min_samples_leaf(mut self, min_samples_leaf: usize) -> Result<Self> {
+        self.tree = self.tree.with_min_samples_leaf(min_samples_leaf)?;
+        Ok(self)
+    }
+
+    /// Set the seed for bootstrapping and feature sampling
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+impl Regressor for RandomForest {
+    type Fitted = FittedRandomForest;
+
+    fn name(&self) -> &str {
+        "Random Forest"
+    }
+
+    fn fit(&self, x: &[Vec<f64>], y: &[f64]) -> Result<FittedRandomForest> {
+        let width = check_training_set(x, y, 2 * self.tree.min_samples_leaf)?;
+        let n = x.len();
+        let max_features = self.max_features.unwrap_or(width / 3).clamp(1, width);
+        let mut rng = StdRng::seed_from_u64(self.seed);
+
+        let trees = (0..self.n_trees)
+            .map(|_| {
+                let bootstrap = (0..n).map(|_| rng.gen_range(0..n)).collect();
+                self.tree
+                    .grow(x, y, bootstrap, Some((max_features, &mut rng)))
+            })
+            .collect();
+        Ok(FittedRandomForest { trees })
+    }
+}
+
+/// Fitted random forest
+#[derive(Debug, Clone)]
+pub struct FittedRandomForest {
+    trees: Vec<FittedRegressionTree>,
+}
+
+impl FittedRandomForest {
+    /// Fitted trees
+    pub fn trees(&self) -> &[FittedRegressionTree] {
+        &self.trees
+    }
+}
+
+impl FittedRegressor for FittedRandomForest {
+    fn predict(&self, row: &[f64]) -> f64 {
+        self.trees.iter().map(|t| t.predict(row)).sum::<f64>() / self.trees.len() as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_distr::{Distribution, Normal};
+
+    /// Step function of x0 plus a smooth effect of x1, x2 irrelevant
+    fn data(n: usize, seed: u64) -> (Vec<Vec<f64>>, Vec<f64>) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let noise = Normal::new(0.0, 0.1).unwrap();
+        let x: Vec<Vec<f64>> = (0..n)
+            .map(|_| (0..3).map(|_| rng.gen_range(-1.0..1.0)).collect())
+            .collect();
+        let y = x
+            .iter()
+            .map(|r| {
+                let step = if r[0] > 0.2 { 2.0 } else { -1.0 };
+                step + r[1] * r[1] + noise.sample(&mut rng)
+            })
+            .collect();
+        (x, y)
+    }
+
+    fn test_mse<F: FittedRegressor>(model: &F) -> f64 {
+        let (x, y) = data(200, 99);
+        x.iter()
+            .zip(&y)
+            .map(|(r, t)| (model.predict(r) - t).powi(2))
+            .sum::<f64>()
+            / y.len() as f64
+    }
+
+    #[test]
+    fn test_tree_finds_step() {
+        let (x, y) = data(300, 1);
+        let stump = RegressionTree::new(1).unwrap().fit(&x, &y).unwrap();
+        assert_eq!(stump.leaf_count(), 2);
+        assert!(stump.predict(&[0.9, 0.0, 0.0]) > 1.5);
+        assert!(stump.predict(&[-0.9, 0.0, 0.0]) < -0.5);
+
+        let deep = RegressionTree::new(6).unwrap().fit(&x, &y).unwrap();
+        assert!(test_mse(&deep) < test_mse(&stump));
+    }
+
+    #[test]
+    fn test_boosting_improves_on_single_tree() {
+        let (x, y) = data(300, 2);
+        let tree = RegressionTree::new(2).unwrap().fit(&x, &y).unwrap();
+        let boosted = GradientBoosting::new(100, 0.1, 2)
+            .unwrap()
+            .with_subsample(0.8)
+            .unwrap()
+            .fit(&x, &y)
+            .unwrap();
+        assert_eq!(boosted.trees().len(), 100);
+        assert!(test_mse(&boosted) < test_mse(&tree));
+        assert!(test_mse(&boosted) < 0.05);
+    }
+
+    #[test]
+    fn test_random_forest_is_reproducible() {
+        let (x, y) = data(300, 3);
+        let forest = RandomForest::new(50, 8)
+            .unwrap()
+            .with_max_features(2)
+            .unwrap();
+        let a = forest.fit(&x, &y).unwrap();
+        let b = forest.fit(&x, &y).unwrap();
+        let row = [0.5, -0.3, 0.1];
+        assert_eq!(a.predict(&row), b.predict(&row));
+        assert!(test_mse(&a) < 0.1);
+
+        let other = forest.clone().with_seed(7).fit(&x, &y).unwrap();
+        assert_ne!(a.predict(&row), other.predict(&row));
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(RegressionTree::new(0).is_err());
+        assert!(GradientBoosting::new(10, 0.0, 2).is_err());
+        assert!(GradientBoosting::new(10, 0.1, 2)
+            .unwrap()
+            .with_subsample(1.5)
+            .is_err());
+        assert!(RandomForest::new(0, 3).is_err());
+        let (x, y) = data(6, 4);
+        assert!(RegressionTree::new(2).unwrap().fit(&x, &y).is_err());
+    }
+}
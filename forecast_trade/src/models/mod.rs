@@ -1,9 +1,47 @@
 //! Forecasting models for time series data
 
 use crate::data::TimeSeriesData;
-use crate::error::Result;
+use crate::error::{ForecastError, Result};
+use statrs::distribution::{ContinuousCDF, Normal, StudentsT};
 use std::fmt::Debug;
 
+/// Distribution of standardized forecast errors
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ForecastDistribution {
+    /// Standard normal
+    #[default]
+    Normal,
+    /// Student-t with the given degrees of freedom
+    StudentT(f64),
+}
+
+impl ForecastDistribution {
+    /// Quantile function at probability `p`
+    pub fn inverse_cdf(&self, p: f64) -> Result<f64> {
+        validate_probability(p)?;
+        match *self {
+            Self::Normal => Ok(Normal::new(0.0, 1.0).unwrap().inverse_cdf(p)),
+            Self::StudentT(degrees_of_freedom) => StudentsT::new(0.0, 1.0, degrees_of_freedom)
+                .map(|t| t.inverse_cdf(p))
+                .map_err(|e| ForecastError::InvalidParameter(e.to_string())),
+        }
+    }
+}
+
+/// Uncertainty around a point forecast
+#[derive(Debug, Clone)]
+pub enum ForecastUncertainty {
+    /// Standard errors per horizon, scaling a standardized distribution
+    Analytic {
+        /// Standard error of each forecast
+        standard_errors: Vec<f64>,
+        /// Distribution of the standardized errors
+        distribution: ForecastDistribution,
+    },
+    /// Sorted simulated draws of the forecast quantity, one set per horizon
+    Simulated(Vec<Vec<f64>>),
+}
+
 /// Forecast result containing predicted values
 #[derive(Debug, Clone)]
 pub struct ForecastResult {
@@ -15,6 +53,8 @@ pub struct ForecastResult {
     pub(crate) intervals: Option<Vec<(f64, f64)>>,
     /// Timestamps (optional)
     pub(crate) timestamps: Option<Vec<chrono::DateTime<chrono::Utc>>>,
+    /// Forecast uncertainty (optional)
+    uncertainty: Option<ForecastUncertainty>,
 }
 
 impl ForecastResult {
@@ -33,6 +73,7 @@ impl ForecastResult {
             horizons,
             intervals: None,
             timestamps: None,
+            uncertainty: None,
         })
     }
 
@@ -63,9 +104,70 @@ impl ForecastResult {
             horizons,
             intervals: Some(intervals),
             timestamps: None,
+            uncertainty: None,
         })
     }
 
+    /// Attach standard errors of the forecast errors
+    ///
+    /// Quantiles are `value + standard_error * distribution.inverse_cdf(p)`.
+    pub fn with_standard_errors(
+        mut self,
+        standard_errors: Vec<f64>,
+        distribution: ForecastDistribution,
+    ) -> Result<Self> {
+        self.check_horizons(standard_errors.len(), "Standard errors")?;
+        if standard_errors
+            .iter()
+            .any(|se| !(*se >= 0.0 && se.is_finite()))
+        {
+            return Err(ForecastError::ValidationError(
+                "Standard errors must be finite and non-negative".to_string(),
+            ));
+        }
+
+        self.uncertainty = Some(ForecastUncertainty::Analytic {
+            standard_errors,
+            distribution,
+        });
+        Ok(self)
+    }
+
+    /// Attach forecast error variances, with normal errors
+    pub fn with_variances(self, variances: Vec<f64>) -> Result<Self> {
+        let standard_errors = variances.into_iter().map(f64::sqrt).collect();
+        self.with_standard_errors(standard_errors, ForecastDistribution::Normal)
+    }
+
+    /// Attach simulated draws of the forecast quantity, one set per horizon
+    pub fn with_samples(mut self, mut samples: Vec<Vec<f64>>) -> Result<Self> {
+        self.check_horizons(samples.len(), "Samples")?;
+        if samples
+            .iter()
+            .any(|draws| draws.is_empty() || draws.iter().any(|x| !x.is_finite()))
+        {
+            return Err(ForecastError::ValidationError(
+                "Each horizon needs at least one finite sample".to_string(),
+            ));
+        }
+
+        for draws in &mut samples {
+            draws.sort_by(f64::total_cmp);
+        }
+        self.uncertainty = Some(ForecastUncertainty::Simulated(samples));
+        Ok(self)
+    }
+
+    fn check_horizons(&self, len: usize, what: &str) -> Result<()> {
+        if len != self.horizons {
+            return Err(ForecastError::ValidationError(format!(
+                "{} length ({}) doesn't match horizons ({})",
+                what, len, self.horizons
+            )));
+        }
+        Ok(())
+    }
+
     /// Get the forecasted values
     pub fn values(&self) -> &[f64] {
         &self.values
@@ -86,37 +188,119 @@ impl ForecastResult {
         self.timestamps.as_deref()
     }
 
-    /// Generate confidence intervals for the forecast
+    /// Get the forecast uncertainty, if the model provided one
+    pub fn uncertainty(&self) -> Option<&ForecastUncertainty> {
+        self.uncertainty.as_ref()
+    }
+
+    /// Standard errors of the forecasts
+    ///
+    /// Simulated forecasts report the standard deviation of their draws.
+    pub fn standard_errors(&self) -> Option<Vec<f64>> {
+        match self.uncertainty.as_ref()? {
+            ForecastUncertainty::Analytic {
+                standard_errors, ..
+            } => Some(standard_errors.clone()),
+            ForecastUncertainty::Simulated(samples) => Some(
+                samples
+                    .iter()
+                    .map(|draws| {
+                        let n = draws.len() as f64;
+                        let mean = draws.iter().sum::<f64>() / n;
+                        (draws.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n).sqrt()
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Quantile forecast at probability `p` for every horizon
+    ///
+    /// Simulated forecasts interpolate linearly between order statistics.
+    pub fn quantile(&self, p: f64) -> Result<Vec<f64>> {
+        validate_probability(p)?;
+        match self.uncertainty.as_ref() {
+            Some(ForecastUncertainty::Analytic {
+                standard_errors,
+                distribution,
+            }) => {
+                let score = distribution.inverse_cdf(p)?;
+                Ok(self
+                    .values
+                    .iter()
+                    .zip(standard_errors)
+                    .map(|(v, se)| v + score * se)
+                    .collect())
+            }
+            Some(ForecastUncertainty::Simulated(samples)) => Ok(samples
+                .iter()
+                .map(|draws| {
+                    let position = p * (draws.len() - 1) as f64;
+                    let lower = position.floor() as usize;
+                    let upper = position.ceil() as usize;
+                    let weight = position - lower as f64;
+                    draws[lower] + weight * (draws[upper] - draws[lower])
+                })
+                .collect()),
+            None => Err(ForecastError::ForecastingError(
+                "Forecast has no uncertainty estimate".to_string(),
+            )),
+        }
+    }
+
+    /// Central prediction intervals at the given confidence level
     pub fn confidence_intervals(&self, confidence_level: f64) -> Result<Vec<(f64, f64)>> {
         if confidence_level <= 0.0 || confidence_level >= 1.0 {
-            return Err(crate::error::ForecastError::ValidationError(
+            return Err(ForecastError::ValidationError(
                 "Confidence level must be between 0 and 1".to_string(),
             ));
         }
 
-        // For simplicity, use a fixed standard deviation multiplier based on confidence level
-        // In a real implementation, this would be based on the t-distribution or normal distribution
-        let z_score = match confidence_level {
-            c if c >= 0.99 => 2.576, // 99% confidence
-            c if c >= 0.95 => 1.96,  // 95% confidence
-            c if c >= 0.90 => 1.645, // 90% confidence
-            _ => 1.0,                // Default fallback
-        };
-
-        // Assume a simple model with constant variance for the forecast
-        // In a real implementation, this would be model-specific
-        let std_dev = 0.05; // 5% standard deviation
-
-        let intervals: Vec<(f64, f64)> = self
-            .values
-            .iter()
-            .map(|v| {
-                let margin = z_score * std_dev * v.abs();
-                (*v - margin, *v + margin)
-            })
-            .collect();
+        let tail = (1.0 - confidence_level) / 2.0;
+        let lower = self.quantile(tail)?;
+        let upper = self.quantile(1.0 - tail)?;
+        Ok(lower.into_iter().zip(upper).collect())
+    }
 
-        Ok(intervals)
+    /// Probability that the forecast quantity ends above `threshold`, per
+    /// horizon
+    pub fn probability_above(&self, threshold: f64) -> Result<Vec<f64>> {
+        match self.uncertainty.as_ref() {
+            Some(ForecastUncertainty::Analytic {
+                standard_errors,
+                distribution,
+            }) => self
+                .values
+                .iter()
+                .zip(standard_errors)
+                .map(|(v, se)| {
+                    if *se == 0.0 {
+                        return Ok(if *v > threshold { 1.0 } else { 0.0 });
+                    }
+                    let score = (threshold - v) / se;
+                    Ok(match *distribution {
+                        ForecastDistribution::Normal => {
+                            1.0 - Normal::new(0.0, 1.0).unwrap().cdf(score)
+                        }
+                        ForecastDistribution::StudentT(nu) => {
+                            1.0 - StudentsT::new(0.0, 1.0, nu)
+                                .map_err(|e| ForecastError::InvalidParameter(e.to_string()))?
+                                .cdf(score)
+                        }
+                    })
+                })
+                .collect(),
+            Some(ForecastUncertainty::Simulated(samples)) => Ok(samples
+                .iter()
+                .map(|draws| {
+                    let above = draws.iter().filter(|&&x| x > threshold).count();
+                    above as f64 / draws.len() as f64
+                })
+                .collect()),
+            None => Err(ForecastError::ForecastingError(
+                "Forecast has no uncertainty estimate".to_string(),
+            )),
+        }
     }
 
     /// Calculate mean absolute error between forecast and actual values
@@ -160,30 +344,123 @@ impl ForecastResult {
     }
 }
 
-/// Trained forecast model
-pub trait TrainedForecastModel: Debug {
-    /// Generate forecast for future periods
-    fn forecast(&self, horizons: usize) -> Result<ForecastResult>;
+fn validate_probability(p: f64) -> Result<()> {
+    if p > 0.0 && p < 1.0 {
+        Ok(())
+    } else {
+        Err(ForecastError::ValidationError(
+            "Probability must be between 0 and 1".to_string(),
+        ))
+    }
+}
 
-    /// Predict values for the training data
-    fn predict(&self, data: &TimeSeriesData) -> Result<ForecastResult>;
+/// Information criterion used to compare fitted models
+///
+/// Criteria are only comparable between models fitted to the same
+/// observations, e.g. ARIMA models with the same differencing orders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InformationCriterion {
+    /// Akaike information criterion
+    Aic,
+    /// Akaike information criterion with small-sample correction
+    #[default]
+    Aicc,
+    /// Bayesian (Schwarz) information criterion
+    Bic,
+}
 
+impl InformationCriterion {
+    /// Value of the criterion for a log-likelihood with `parameters`
+    /// estimated parameters from `observations` observations
+    pub fn evaluate(&self, log_likelihood: f64, parameters: usize, observations: usize) -> f64 {
+        let k = parameters as f64;
+        let n = observations as f64;
+        let aic = -2.0 * log_likelihood + 2.0 * k;
+        match self {
+            Self::Aic => aic,
+            Self::Aicc if n - k - 1.0 > 0.0 => aic + 2.0 * k * (k + 1.0) / (n - k - 1.0),
+            Self::Aicc => f64::INFINITY,
+            Self::Bic => -2.0 * log_likelihood + k * n.ln(),
+        }
+    }
+}
+
+/// A forecast model fitted to a series
+///
+/// Produced by `ForecastModel::fit`. Holds the estimated state and the
+/// in-sample one-step-ahead predictions, and can be rolled forward with new
+/// observations without refitting the parameters.
+pub trait FittedModel: Debug {
     /// Name of the model
     fn name(&self) -> &str;
+
+    /// Forecast the next `horizon` values after the last observation
+    fn forecast(&self, horizon: usize) -> Result<ForecastResult>;
+
+    /// Append new observations, keeping the fitted parameters
+    ///
+    /// Observations are in the same units as the series the model was
+    /// fitted on. Residuals and fitted values grow accordingly.
+    fn update(&mut self, observations: &[f64]) -> Result<()>;
+
+    /// In-sample one-step-ahead predictions, one per observation
+    fn fitted_values(&self) -> &[f64];
+
+    /// In-sample one-step-ahead residuals (observed minus fitted)
+    fn residuals(&self) -> &[f64];
 }
 
-/// Forecast model that can be trained on time series data
+/// A configured forecast model that can be fitted to data
+///
+/// The lifecycle is: configure the model through its constructor, `fit` it
+/// on a `TimeSeriesData` (or a raw series with `fit_series`), then use the
+/// returned `FittedModel` to forecast, update and inspect residuals. The
+/// configured model itself is never mutated, so it can be fitted repeatedly.
+///
+/// # Example
+///
+/// ```
+/// use chrono::{Duration, TimeZone, Utc};
+/// use forecast_trade::data::TimeSeriesData;
+/// use forecast_trade::models::exponential_smoothing::ExponentialSmoothing;
+/// use forecast_trade::models::{FittedModel, ForecastModel};
+///
+/// let start = Utc.with_ymd_and_hms(2023, 1, 2, 0, 0, 0).unwrap();
+/// let dates = (0..30).map(|i| start + Duration::days(i)).collect();
+/// let prices = (0..30).map(|i| 100.0 + i as f64 * 0.5).collect();
+/// let data = TimeSeriesData::new(dates, prices).unwrap();
+///
+/// let mut fitted = ExponentialSmoothing::new(0.5).unwrap().fit(&data).unwrap();
+/// assert_eq!(fitted.residuals().len(), 30);
+///
+/// fitted.update(&[116.0]).unwrap();
+/// let forecast = fitted.forecast(5).unwrap();
+/// assert_eq!(forecast.horizons(), 5);
+/// ```
 pub trait ForecastModel: Debug + Clone {
-    /// The type of trained model produced
-    type Trained: TrainedForecastModel;
-
-    /// Train the model on time series data
-    fn train(&self, data: &TimeSeriesData) -> Result<Self::Trained>;
+    /// The type of fitted model produced
+    type Fitted: FittedModel;
 
     /// Get the name of the model
     fn name(&self) -> &str;
+
+    /// Fit the model to a raw series of observations
+    fn fit_series(&self, values: &[f64]) -> Result<Self::Fitted>;
+
+    /// Fit the model on time series data
+    ///
+    /// Uses the close prices unless the model documents otherwise.
+    fn fit(&self, data: &TimeSeriesData) -> Result<Self::Fitted> {
+        self.fit_series(&data.close_prices())
+    }
 }
 
 pub mod arima;
+pub mod auto_arima;
+pub mod auto_ets;
+pub mod ensemble;
+pub mod ets;
 pub mod exponential_smoothing;
+pub mod garch;
+pub mod ml;
 pub mod moving_average;
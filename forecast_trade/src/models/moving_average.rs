@@ -1,8 +1,7 @@
 //! Moving average models for time series forecasting
 
-use crate::data::TimeSeriesData;
 use crate::error::{ForecastError, Result};
-use crate::models::{ErrorMetrics, ForecastModel, ForecastResult};
+use crate::models::{FittedModel, ForecastDistribution, ForecastModel, ForecastResult};
 use crate::strategies::TimeGranularity;
 
 /// Simple Moving Average model
@@ -39,177 +38,190 @@ impl MovingAverage {
     /// Create a new moving average with default parameters for the given granularity
     pub fn with_default_params(granularity: TimeGranularity) -> Result<Self> {
         match granularity {
-            TimeGranularity::Daily => Self::new(20),   // 20-day moving average
-            TimeGranularity::Minute => Self::new(60),  // 60-minute moving average
+            TimeGranularity::Daily => Self::new(20), // 20-day moving average
+            TimeGranularity::Minute => Self::new_minute(60), // 60-minute moving average
         }
     }
+
+    /// Window size
+    pub fn window_size(&self) -> usize {
+        self.window_size
+    }
+
+    /// Get the model's time granularity
+    pub fn time_granularity(&self) -> TimeGranularity {
+        self.time_granularity
+    }
+
+    /// Set the model's time granularity, adjusting the window if needed
+    pub fn with_granularity(mut self, granularity: TimeGranularity) -> Self {
+        self.adjust_for_granularity(granularity);
+        self
+    }
+
+    /// Adjust window size based on time granularity
+    pub fn adjust_for_granularity(&mut self, granularity: TimeGranularity) {
+        self.time_granularity = granularity;
+
+        self.window_size = match granularity {
+            // If window is too large for daily data, reduce it
+            TimeGranularity::Daily if self.window_size > 60 => 20,
+            // If window is too small for minute data, increase it
+            TimeGranularity::Minute if self.window_size < 20 => 60,
+            _ => self.window_size,
+        };
+    }
 }
 
 impl ForecastModel for MovingAverage {
-    fn train(&self, data: &TimeSeriesData) -> Result<Box<dyn ForecastModel>> {
-        if data.is_empty() {
-            return Err(ForecastError::DataError("Empty data".to_string()));
-        }
+    type Fitted = FittedMovingAverage;
 
-        if data.len() < self.window_size {
-            return Err(ForecastError::DataError(format!(
-                "Not enough data points. Need at least {} points for the window size.",
-                self.window_size
-            )));
-        }
-
-        // No additional training needed for moving average
-        Ok(Box::new(self.clone()))
+    fn name(&self) -> &str {
+        "Moving Average"
     }
 
-    fn forecast(&self, data: &TimeSeriesData, periods: usize) -> Result<ForecastResult> {
-        if data.is_empty() {
+    fn fit_series(&self, values: &[f64]) -> Result<FittedMovingAverage> {
+        if values.is_empty() {
             return Err(ForecastError::DataError("Empty data".to_string()));
         }
 
-        if data.len() < self.window_size {
+        if values.len() < self.window_size {
             return Err(ForecastError::DataError(format!(
                 "Not enough data points. Need at least {} points for the window size.",
                 self.window_size
             )));
         }
 
-        let prices = data.close_prices();
-        
-        // Calculate the last moving average
-        let last_window = &prices[prices.len() - self.window_size..];
-        let last_ma = last_window.iter().sum::<f64>() / self.window_size as f64;
-        
-        // For simple moving average, forecast is constant
-        let values = vec![last_ma; periods];
-        
-        // Calculate in-sample error metrics
-        let mut forecasts = Vec::with_capacity(prices.len());
-        
-        // Fill initial points with NaN equivalent (not enough data for MA)
-        for _ in 0..self.window_size - 1 {
-            forecasts.push(f64::NAN);
-        }
-        
-        // Calculate moving averages
-        for i in self.window_size - 1..prices.len() {
-            let window = &prices[i - (self.window_size - 1)..=i];
-            let ma = window.iter().sum::<f64>() / self.window_size as f64;
-            forecasts.push(ma);
-        }
-        
-        // Calculate error metrics (skipping initial NaN values)
-        let mut sum_abs_error = 0.0;
-        let mut sum_squared_error = 0.0;
-        let mut sum_abs_pct_error = 0.0;
-        let mut count = 0;
-        
-        for i in self.window_size..prices.len() {
-            let error = prices[i] - forecasts[i - 1];
-            sum_abs_error += error.abs();
-            sum_squared_error += error * error;
-            
-            if prices[i] != 0.0 {
-                sum_abs_pct_error += (error / prices[i]).abs();
-            }
-            
-            count += 1;
-        }
-        
-        let mae = sum_abs_error / count as f64;
-        let mse = sum_squared_error / count as f64;
-        let rmse = mse.sqrt();
-        let mape = sum_abs_pct_error / count as f64 * 100.0;
-        
-        let error_metrics = ErrorMetrics {
-            mae,
-            mse,
-            rmse,
-            mape,
+        let mut fitted = FittedMovingAverage {
+            window_size: self.window_size,
+            history: Vec::with_capacity(values.len()),
+            fitted_values: Vec::with_capacity(values.len()),
+            residuals: Vec::with_capacity(values.len()),
         };
-        
-        Ok(ForecastResult {
-            values,
-            confidence_intervals: None,
-            error_metrics: Some(error_metrics),
-        })
+        fitted.update(values)?;
+
+        Ok(fitted)
     }
-    
-    fn validate(&self, train_data: &TimeSeriesData, test_data: &TimeSeriesData) -> Result<ErrorMetrics> {
-        if train_data.is_empty() || test_data.is_empty() {
-            return Err(ForecastError::DataError("Empty data".to_string()));
+}
+
+/// Moving average model fitted to a series
+#[derive(Debug, Clone)]
+pub struct FittedMovingAverage {
+    window_size: usize,
+    history: Vec<f64>,
+    fitted_values: Vec<f64>,
+    residuals: Vec<f64>,
+}
+
+impl FittedMovingAverage {
+    /// Mean of the last `window_size` observations (fewer at the start)
+    fn current_average(&self) -> Option<f64> {
+        if self.history.is_empty() {
+            return None;
         }
-        
-        // Train on training data (no actual training for MA)
-        let trained_model = self.train(train_data)?;
-        
-        // Forecast for the test period
-        let forecast_result = trained_model.forecast(train_data, test_data.len())?;
-        
-        // Calculate error metrics
-        let test_prices = test_data.close_prices();
-        let forecasts = forecast_result.values;
-        
-        let mut sum_abs_error = 0.0;
-        let mut sum_squared_error = 0.0;
-        let mut sum_abs_pct_error = 0.0;
-        
-        for i in 0..test_prices.len() {
-            let error = test_prices[i] - forecasts[i];
-            sum_abs_error += error.abs();
-            sum_squared_error += error * error;
-            
-            if test_prices[i] != 0.0 {
-                sum_abs_pct_error += (error / test_prices[i]).abs();
-            }
+        let window = &self.history[self.history.len().saturating_sub(self.window_size)..];
+        Some(window.iter().sum::<f64>() / window.len() as f64)
+    }
+
+    /// Standard error of the forecast and the distribution it scales
+    ///
+    /// Treats the window as an i.i.d. sample, so a new value differs from
+    /// its mean with variance `s2 * (1 + 1 / n)` and a Student-t with
+    /// `n - 1` degrees of freedom. A window of one falls back to the
+    /// in-sample residuals and the normal distribution.
+    fn standard_error(&self) -> (f64, ForecastDistribution) {
+        let window = &self.history[self.history.len().saturating_sub(self.window_size)..];
+        let n = window.len() as f64;
+        if window.len() < 2 {
+            let errors = &self.residuals[1.min(self.residuals.len())..];
+            let sigma2 = if errors.is_empty() {
+                0.0
+            } else {
+                errors.iter().map(|e| e * e).sum::<f64>() / errors.len() as f64
+            };
+            return (sigma2.sqrt(), ForecastDistribution::Normal);
         }
-        
-        let n = test_prices.len();
-        let mae = sum_abs_error / n as f64;
-        let mse = sum_squared_error / n as f64;
-        let rmse = mse.sqrt();
-        let mape = sum_abs_pct_error / n as f64 * 100.0;
-        
-        Ok(ErrorMetrics {
-            mae,
-            mse,
-            rmse,
-            mape,
-        })
+
+        let mean = window.iter().sum::<f64>() / n;
+        let s2 = window.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        (
+            (s2 * (1.0 + 1.0 / n)).sqrt(),
+            ForecastDistribution::StudentT(n - 1.0),
+        )
     }
-    
-    fn time_granularity(&self) -> TimeGranularity {
-        self.time_granularity
+}
+
+impl FittedModel for FittedMovingAverage {
+    fn name(&self) -> &str {
+        "Moving Average"
     }
-    
-    fn with_granularity(mut self, granularity: TimeGranularity) -> Self {
-        let _ = self.adjust_for_granularity(granularity);
-        self
+
+    fn forecast(&self, horizon: usize) -> Result<ForecastResult> {
+        let average = self.current_average().ok_or_else(|| {
+            ForecastError::ForecastingError("Model has not been fitted to data".to_string())
+        })?;
+
+        // For simple moving average, forecast and its uncertainty are constant
+        let (standard_error, distribution) = self.standard_error();
+        ForecastResult::new(vec![average; horizon], horizon)?
+            .with_standard_errors(vec![standard_error; horizon], distribution)
     }
-    
-    fn adjust_for_granularity(&mut self, granularity: TimeGranularity) -> Result<()> {
-        self.time_granularity = granularity;
-        
-        // Adjust window size based on granularity
-        self.window_size = match granularity {
-            TimeGranularity::Daily => {
-                // If window is too large for daily data, reduce it
-                if self.window_size > 60 {
-                    20 // Default to 20-day MA
-                } else {
-                    self.window_size
-                }
-            }
-            TimeGranularity::Minute => {
-                // If window is too small for minute data, increase it
-                if self.window_size < 20 {
-                    60 // Default to 60-minute MA
-                } else {
-                    self.window_size
-                }
-            }
-        };
-        
+
+    fn update(&mut self, observations: &[f64]) -> Result<()> {
+        for &value in observations {
+            // The first observation has no history and is its own prediction
+            let prediction = self.current_average().unwrap_or(value);
+            self.fitted_values.push(prediction);
+            self.residuals.push(value - prediction);
+            self.history.push(value);
+        }
         Ok(())
     }
+
+    fn fitted_values(&self) -> &[f64] {
+        &self.fitted_values
+    }
+
+    fn residuals(&self) -> &[f64] {
+        &self.residuals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_and_forecast() {
+        let model = MovingAverage::new(2).unwrap();
+        let mut fitted = model.fit_series(&[1.0, 3.0, 5.0, 7.0]).unwrap();
+
+        assert_eq!(fitted.fitted_values(), &[1.0, 1.0, 2.0, 4.0]);
+        assert_eq!(fitted.residuals(), &[0.0, 2.0, 3.0, 3.0]);
+        assert_eq!(fitted.forecast(2).unwrap().values(), &[6.0, 6.0]);
+
+        fitted.update(&[9.0]).unwrap();
+        assert_eq!(fitted.forecast(1).unwrap().values(), &[8.0]);
+    }
+
+    #[test]
+    fn test_student_t_prediction_interval() {
+        let model = MovingAverage::new(4).unwrap();
+        let fitted = model.fit_series(&[1.0, 3.0, 5.0, 7.0]).unwrap();
+        let forecast = fitted.forecast(2).unwrap();
+
+        // s2 = 20 / 3 and t(3) 97.5% quantile = 3.182446
+        let margin = 3.182446 * (20.0 / 3.0 * 1.25_f64).sqrt();
+        let intervals = forecast.confidence_intervals(0.95).unwrap();
+        assert!((intervals[1].0 - (4.0 - margin)).abs() < 1e-4);
+        assert!((intervals[1].1 - (4.0 + margin)).abs() < 1e-4);
+        assert!((forecast.probability_above(4.0).unwrap()[0] - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_requires_full_window() {
+        let model = MovingAverage::new(5).unwrap();
+        assert!(model.fit_series(&[1.0, 2.0]).is_err());
+        assert!(MovingAverage::new(0).is_err());
+    }
 }
@@ -0,0 +1,208 @@
+//! Derivative-free numerical optimization
+//!
+//! Likelihood-based estimators minimise a negative log-likelihood with the
+//! Nelder-Mead simplex method. Constraints are expressed by returning
+//! `f64::INFINITY` (or NaN) for infeasible points.
+
+use crate::error::{ForecastError, Result};
+
+/// Result of a minimization
+#[derive(Debug, Clone)]
+pub struct Minimum {
+    /// Best point found
+    pub point: Vec<f64>,
+    /// Objective value at `point`
+    pub value: f64,
+    /// Number of iterations performed
+    pub iterations: usize,
+    /// Whether the simplex collapsed within the tolerance
+    pub converged: bool,
+}
+
+/// Nelder-Mead simplex minimizer
+#[derive(Debug, Clone)]
+pub struct NelderMead {
+    max_iterations: usize,
+    tolerance: f64,
+}
+
+impl Default for NelderMead {
+    fn default() -> Self {
+        Self {
+            max_iterations: 2000,
+            tolerance: 1e-10,
+        }
+    }
+}
+
+impl NelderMead {
+    /// Create a minimizer with an iteration cap and a tolerance on the
+    /// spread of objective values across the simplex
+    pub fn new(max_iterations: usize, tolerance: f64) -> Result<Self> {
+        if max_iterations == 0 {
+            return Err(ForecastError::InvalidParameter(
+                "Maximum iterations must be greater than 0".to_string(),
+            ));
+        }
+        if tolerance <= 0.0 {
+            return Err(ForecastError::InvalidParameter(
+                "Tolerance must be positive".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            max_iterations,
+            tolerance,
+        })
+    }
+
+    /// Minimise `objective` starting from `start`
+    ///
+    /// The initial simplex displaces each coordinate by the matching entry
+    /// of `steps`.
+    pub fn minimize<F: Fn(&[f64]) -> f64>(
+        &self,
+        objective: F,
+        start: &[f64],
+        steps: &[f64],
+    ) -> Minimum {
+        let evaluate = |x: &[f64]| {
+            let value = objective(x);
+            if value.is_nan() {
+                f64::INFINITY
+            } else {
+                value
+            }
+        };
+
+        let n = start.len();
+        let mut simplex: Vec<(Vec<f64>, f64)> = Vec::with_capacity(n + 1);
+        simplex.push((start.to_vec(), evaluate(start)));
+        for i in 0..n {
+            let mut vertex = start.to_vec();
+            let step = steps.get(i).copied().unwrap_or(0.1);
+            vertex[i] += if step != 0.0 { step } else { 0.1 };
+            let value = evaluate(&vertex);
+            simplex.push((vertex, value));
+        }
+
+        let mut iterations = 0;
+        let mut converged = n == 0;
+        while iterations < self.max_iterations && !converged {
+            iterations += 1;
+            simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+            let best = simplex[0].1;
+            let worst = simplex[n].1;
+            if worst.is_finite() && worst - best <= self.tolerance * (1.0 + best.abs()) {
+                converged = true;
+                break;
+            }
+
+            // Centroid of all vertices but the worst
+            let mut centroid = vec![0.0; n];
+            for (vertex, _) in &simplex[..n] {
+                for (c, v) in centroid.iter_mut().zip(vertex) {
+                    *c += v / n as f64;
+                }
+            }
+            let towards = |from: &[f64], coefficient: f64| -> Vec<f64> {
+                centroid
+                    .iter()
+                    .zip(from)
+                    .map(|(c, v)| c + coefficient * (v - c))
+                    .collect()
+            };
+
+            let reflected = towards(&simplex[n].0, -1.0);
+            let reflected_value = evaluate(&reflected);
+
+            if reflected_value < best {
+                let expanded = towards(&simplex[n].0, -2.0);
+                let expanded_value = evaluate(&expanded);
+                simplex[n] = if expanded_value < reflected_value {
+                    (expanded, expanded_value)
+                } else {
+                    (reflected, reflected_value)
+                };
+                continue;
+            }
+
+            if reflected_value < simplex[n - 1].1 {
+                simplex[n] = (reflected, reflected_value);
+                continue;
+            }
+
+            // Contract outside the simplex if the reflection beat the worst
+            // vertex, inside otherwise
+            let outside = reflected_value < worst;
+            let contracted = if outside {
+                towards(&reflected, 0.5)
+            } else {
+                towards(&simplex[n].0, 0.5)
+            };
+            let contracted_value = evaluate(&contracted);
+            let accepted = if outside {
+                contracted_value <= reflected_value
+            } else {
+                contracted_value < worst
+            };
+            if accepted {
+                simplex[n] = (contracted, contracted_value);
+                continue;
+            }
+
+            // Shrink towards the best vertex
+            let anchor = simplex[0].0.clone();
+            for (vertex, value) in simplex.iter_mut().skip(1) {
+                for (v, a) in vertex.iter_mut().zip(&anchor) {
+                    *v = a + 0.5 * (*v - a);
+                }
+                *value = evaluate(vertex);
+            }
+        }
+
+        simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let (point, value) = simplex.swap_remove(0);
+        Minimum {
+            point,
+            value,
+            iterations,
+            converged,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minimizes_rosenbrock() {
+        let rosenbrock = |x: &[f64]| (1.0 - x[0]).powi(2) + 100.0 * (x[1] - x[0] * x[0]).powi(2);
+        let minimum =
+            NelderMead::new(5000, 1e-14)
+                .unwrap()
+                .minimize(rosenbrock, &[-1.2, 1.0], &[0.5, 0.5]);
+
+        assert!(minimum.converged);
+        assert!((minimum.point[0] - 1.0).abs() < 1e-3);
+        assert!((minimum.point[1] - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_respects_infeasible_region() {
+        // Minimum of the unconstrained parabola lies outside x < 0.5
+        let objective = |x: &[f64]| {
+            if x[0] >= 0.5 {
+                f64::INFINITY
+            } else {
+                (x[0] - 1.0).powi(2)
+            }
+        };
+        let minimum = NelderMead::default().minimize(objective, &[0.0], &[0.1]);
+
+        assert!(minimum.point[0] < 0.5);
+        assert!(minimum.point[0] > 0.49);
+    }
+}
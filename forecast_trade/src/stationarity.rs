@@ -0,0 +1,401 @@
+//! Stationarity tests and differencing order selection
+//!
+//! Provides the augmented Dickey-Fuller (ADF) test, whose null hypothesis is
+//! a unit root, and the KPSS test, whose null hypothesis is level
+//! stationarity. `ndiffs` and `nsdiffs` use them (and a seasonal strength
+//! measure) to choose how often a series should be differenced before an
+//! ARMA model is fitted.
+
+use crate::error::{ForecastError, Result};
+use crate::linalg::least_squares;
+
+/// Significance levels with tabulated critical values
+const SIGNIFICANCE_LEVELS: [f64; 3] = [0.01, 0.05, 0.10];
+
+/// MacKinnon (2010) response surface coefficients for the ADF test with a
+/// constant: critical value = b0 + b1 / T + b2 / T^2
+const ADF_RESPONSE_SURFACE: [[f64; 3]; 3] = [
+    [-3.43035, -6.5393, -16.786],
+    [-2.86154, -2.8903, -4.234],
+    [-2.56677, -1.5384, -2.809],
+];
+
+/// Kwiatkowski et al. (1992) critical values for the level KPSS test
+const KPSS_CRITICAL_VALUES: [f64; 3] = [0.739, 0.463, 0.347];
+
+/// Seasonal strength above which a seasonal difference is taken
+const SEASONAL_STRENGTH_THRESHOLD: f64 = 0.64;
+
+/// Stationarity test to run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StationarityTestKind {
+    /// Augmented Dickey-Fuller test (null: unit root)
+    Adf,
+    /// KPSS test (null: level stationarity)
+    #[default]
+    Kpss,
+}
+
+/// Outcome of a stationarity test
+#[derive(Debug, Clone)]
+pub struct StationarityTest {
+    kind: StationarityTestKind,
+    statistic: f64,
+    lags: usize,
+    observations: usize,
+    critical_values: [f64; 3],
+}
+
+impl StationarityTest {
+    /// Which test was run
+    pub fn kind(&self) -> StationarityTestKind {
+        self.kind
+    }
+
+    /// Test statistic
+    pub fn statistic(&self) -> f64 {
+        self.statistic
+    }
+
+    /// Number of lags used (augmentation lags for ADF, bandwidth for KPSS)
+    pub fn lags(&self) -> usize {
+        self.lags
+    }
+
+    /// Number of observations in the test regression
+    pub fn observations(&self) -> usize {
+        self.observations
+    }
+
+    /// Critical value at a significance level of 0.01, 0.05 or 0.10
+    pub fn critical_value(&self, significance: f64) -> Result<f64> {
+        SIGNIFICANCE_LEVELS
+            .iter()
+            .position(|&level| (level - significance).abs() < 1e-9)
+            .map(|i| self.critical_values[i])
+            .ok_or_else(|| {
+                ForecastError::InvalidParameter(
+                    "Significance must be 0.01, 0.05 or 0.10".to_string(),
+                )
+            })
+    }
+
+    /// Whether the test points to a stationary series at `significance`
+    ///
+    /// For ADF this means the unit root is rejected; for KPSS that
+    /// stationarity is not rejected.
+    pub fn is_stationary(&self, significance: f64) -> Result<bool> {
+        let critical = self.critical_value(significance)?;
+        Ok(match self.kind {
+            StationarityTestKind::Adf => self.statistic < critical,
+            StationarityTestKind::Kpss => self.statistic <= critical,
+        })
+    }
+}
+
+/// Augmented Dickey-Fuller test with a constant
+///
+/// Regresses the differences on a constant, the lagged level and `lags`
+/// lagged differences. The default number of lags is `floor((n - 1)^(1/3))`.
+pub fn adf_test(values: &[f64], lags: Option<usize>) -> Result<StationarityTest> {
+    let n = values.len();
+    let lags = lags.unwrap_or_else(|| ((n.saturating_sub(1)) as f64).cbrt() as usize);
+
+    let differences = difference(values, 1);
+    if differences.len() <= lags + (lags + 2) {
+        return Err(ForecastError::ValidationError(format!(
+            "Insufficient data for the ADF test with {} lags",
+            lags
+        )));
+    }
+
+    let mut rows = Vec::with_capacity(differences.len() - lags);
+    let mut targets = Vec::with_capacity(differences.len() - lags);
+    for t in lags..differences.len() {
+        let mut row = vec![1.0, values[t]];
+        row.extend((1..=lags).map(|i| differences[t - i]));
+        rows.push(row);
+        targets.push(differences[t]);
+    }
+
+    let fit = least_squares(&rows, &targets)?;
+    let statistic = fit.coefficients()[1] / fit.standard_errors()[1];
+    let observations = targets.len();
+    let t = observations as f64;
+    let critical_values = ADF_RESPONSE_SURFACE.map(|[b0, b1, b2]| b0 + b1 / t + b2 / (t * t));
+
+    Ok(StationarityTest {
+        kind: StationarityTestKind::Adf,
+        statistic,
+        lags,
+        observations,
+        critical_values,
+    })
+}
+
+/// KPSS test for level stationarity
+///
+/// The long-run variance uses a Bartlett kernel with `lags` lags, by default
+/// `floor(4 * (n / 100)^(1/4))`.
+pub fn kpss_test(values: &[f64], lags: Option<usize>) -> Result<StationarityTest> {
+    let n = values.len();
+    if n < 3 {
+        return Err(ForecastError::ValidationError(
+            "Insufficient data for the KPSS test".to_string(),
+        ));
+    }
+    let lags = lags
+        .unwrap_or_else(|| (4.0 * (n as f64 / 100.0).powf(0.25)) as usize)
+        .min(n - 1);
+
+    let mean = values.iter().sum::<f64>() / n as f64;
+    let residuals: Vec<f64> = values.iter().map(|v| v - mean).collect();
+
+    let mut partial_sum = 0.0;
+    let mut eta = 0.0;
+    for r in &residuals {
+        partial_sum += r;
+        eta += partial_sum * partial_sum;
+    }
+    eta /= (n * n) as f64;
+
+    let mut long_run_variance = residuals.iter().map(|r| r * r).sum::<f64>();
+    for lag in 1..=lags {
+        let weight = 1.0 - lag as f64 / (lags + 1) as f64;
+        let autocovariance: f64 = (lag..n).map(|t| residuals[t] * residuals[t - lag]).sum();
+        long_run_variance += 2.0 * weight * autocovariance;
+    }
+    long_run_variance /= n as f64;
+
+    if long_run_variance <= 0.0 {
+        return Err(ForecastError::MathError(
+            "Series has no variation".to_string(),
+        ));
+    }
+
+    Ok(StationarityTest {
+        kind: StationarityTestKind::Kpss,
+        statistic: eta / long_run_variance,
+        lags,
+        observations: n,
+        critical_values: KPSS_CRITICAL_VALUES,
+    })
+}
+
+/// Run a stationarity test with its default lag selection
+pub fn stationarity_test(values: &[f64], kind: StationarityTestKind) -> Result<StationarityTest> {
+    match kind {
+        StationarityTestKind::Adf => adf_test(values, None),
+        StationarityTestKind::Kpss => kpss_test(values, None),
+    }
+}
+
+/// Difference a series once at the given lag
+pub fn difference(values: &[f64], lag: usize) -> Vec<f64> {
+    if lag == 0 || values.len() <= lag {
+        return Vec::new();
+    }
+    (lag..values.len())
+        .map(|t| values[t] - values[t - lag])
+        .collect()
+}
+
+/// Number of first differences needed to make a series stationary
+///
+/// Differences until the chosen test indicates stationarity at
+/// `significance`, up to `max_d` times.
+pub fn ndiffs(
+    values: &[f64],
+    kind: StationarityTestKind,
+    significance: f64,
+    max_d: usize,
+) -> Result<usize> {
+    let mut series = values.to_vec();
+    for d in 0..max_d {
+        if is_constant(&series) {
+            return Ok(d);
+        }
+        match stationarity_test(&series, kind) {
+            Ok(test) if test.is_stationary(significance)? => return Ok(d),
+            Ok(_) => {}
+            // Too short to test after differencing: stop here
+            Err(ForecastError::ValidationError(_)) if d > 0 => return Ok(d),
+            Err(e) => return Err(e),
+        }
+        series = difference(&series, 1);
+    }
+    Ok(max_d)
+}
+
+/// Strength of the seasonal pattern, between 0 and 1
+///
+/// Uses a classical decomposition: the trend is a centred moving average
+/// over one period, the seasonal component the average detrended value at
+/// each position in the period. Strength is `1 - Var(remainder) /
+/// Var(seasonal + remainder)`.
+pub fn seasonal_strength(values: &[f64], period: usize) -> Result<f64> {
+    if period < 2 {
+        return Err(ForecastError::InvalidParameter(
+            "Seasonal period must be at least 2".to_string(),
+        ));
+    }
+    if values.len() < 2 * period + 1 {
+        return Err(ForecastError::ValidationError(format!(
+            "Need at least {} observations for period {}",
+            2 * period + 1,
+            period
+        )));
+    }
+
+    // Centred moving average; even periods use a 2 x period average
+    let half = period / 2;
+    let trend = |t: usize| -> f64 {
+        if period % 2 == 1 {
+            values[t - half..=t + half].iter().sum::<f64>() / period as f64
+        } else {
+            let inner: f64 = values[t - half + 1..t + half].iter().sum();
+            (inner + 0.5 * (values[t - half] + values[t + half])) / period as f64
+        }
+    };
+    let positions: Vec<usize> = (half..values.len() - half).collect();
+    let detrended: Vec<(usize, f64)> = positions
+        .iter()
+        .map(|&t| (t, values[t] - trend(t)))
+        .collect();
+
+    let mut sums = vec![0.0; period];
+    let mut counts = vec![0usize; period];
+    for &(t, value) in &detrended {
+        sums[t % period] += value;
+        counts[t % period] += 1;
+    }
+    let mut seasonal: Vec<f64> = sums
+        .iter()
+        .zip(&counts)
+        .map(|(s, &c)| if c > 0 { s / c as f64 } else { 0.0 })
+        .collect();
+    let offset = seasonal.iter().sum::<f64>() / period as f64;
+    seasonal.iter_mut().for_each(|s| *s -= offset);
+
+    let detrended_values: Vec<f64> = detrended.iter().map(|&(_, v)| v).collect();
+    let remainder: Vec<f64> = detrended
+        .iter()
+        .map(|&(t, v)| v - seasonal[t % period])
+        .collect();
+
+    let total = variance(&detrended_values);
+    if total <= 0.0 {
+        return Ok(0.0);
+    }
+    Ok((1.0 - variance(&remainder) / total).max(0.0))
+}
+
+/// Number of seasonal differences (0 or 1 per round) needed
+///
+/// Takes a seasonal difference while the seasonal strength exceeds 0.64, up
+/// to `max_d` times.
+pub fn nsdiffs(values: &[f64], period: usize, max_d: usize) -> Result<usize> {
+    let mut series = values.to_vec();
+    for d in 0..max_d {
+        if series.len() < 2 * period + 1 {
+            return Ok(d);
+        }
+        if seasonal_strength(&series, period)? <= SEASONAL_STRENGTH_THRESHOLD {
+            return Ok(d);
+        }
+        series = difference(&series, period);
+    }
+    Ok(max_d)
+}
+
+fn variance(values: &[f64]) -> f64 {
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64
+}
+
+fn is_constant(values: &[f64]) -> bool {
+    values.len() < 2 || variance(values) <= 1e-12 * (1.0 + values[0].abs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use rand_distr::{Distribution, Normal};
+
+    fn white_noise(n: usize, seed: u64) -> Vec<f64> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        (0..n).map(|_| normal.sample(&mut rng)).collect()
+    }
+
+    fn random_walk(n: usize, seed: u64) -> Vec<f64> {
+        white_noise(n, seed)
+            .iter()
+            .scan(100.0, |level, e| {
+                *level += e;
+                Some(*level)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_adf_distinguishes_unit_root() {
+        let noise = adf_test(&white_noise(300, 1), None).unwrap();
+        assert!(noise.is_stationary(0.05).unwrap());
+
+        let walk = adf_test(&random_walk(300, 2), None).unwrap();
+        assert!(!walk.is_stationary(0.05).unwrap());
+        assert!(walk.critical_value(0.05).unwrap() < -2.8);
+    }
+
+    #[test]
+    fn test_kpss_distinguishes_unit_root() {
+        let noise = kpss_test(&white_noise(300, 3), None).unwrap();
+        assert!(noise.is_stationary(0.05).unwrap());
+
+        let walk = kpss_test(&random_walk(300, 4), None).unwrap();
+        assert!(!walk.is_stationary(0.05).unwrap());
+        assert!(walk.critical_value(0.2).is_err());
+    }
+
+    #[test]
+    fn test_ndiffs() {
+        let walk = random_walk(300, 5);
+        assert_eq!(
+            ndiffs(&walk, StationarityTestKind::Kpss, 0.05, 2).unwrap(),
+            1
+        );
+        assert_eq!(
+            ndiffs(&walk, StationarityTestKind::Adf, 0.05, 2).unwrap(),
+            1
+        );
+
+        let noise = white_noise(300, 6);
+        assert_eq!(
+            ndiffs(&noise, StationarityTestKind::Kpss, 0.05, 2).unwrap(),
+            0
+        );
+
+        let trend: Vec<f64> = (0..100).map(|i| i as f64).collect();
+        assert_eq!(
+            ndiffs(&trend, StationarityTestKind::Kpss, 0.05, 2).unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_seasonal_differencing() {
+        let noise = white_noise(120, 7);
+        let seasonal: Vec<f64> = noise
+            .iter()
+            .enumerate()
+            .map(|(t, e)| 10.0 * [1.0, -1.0, 2.0, -2.0][t % 4] + 0.5 * e)
+            .collect();
+
+        assert!(seasonal_strength(&seasonal, 4).unwrap() > 0.9);
+        assert_eq!(nsdiffs(&seasonal, 4, 1).unwrap(), 1);
+        assert_eq!(nsdiffs(&noise, 4, 1).unwrap(), 0);
+    }
+}
@@ -1,6 +1,6 @@
 use crate::data::TimeSeriesData;
 use crate::error::{ForecastError, Result};
-use crate::models::{ForecastModel, TrainedForecastModel};
+use crate::models::{FittedModel, ForecastModel};
 use crate::strategies::{BacktestResults, ForecastStrategy, TimeGranularity, TradingSignal};
 use std::marker::PhantomData;
 
@@ -114,17 +114,17 @@ impl<M: ForecastModel> ForecastStrategy for MeanReversionStrategy<M> {
             )));
         }
 
-        // Train the model on the data
-        let trained_model = self.model.train(data)?;
+        // Fit the model on the data
+        let fitted_model = self.model.fit(data)?;
 
-        // Get the model's predictions
-        let predictions = trained_model.predict(data)?;
+        // Get the model's one-step-ahead predictions
+        let predictions = fitted_model.fitted_values();
 
         // Calculate the standard deviation
         let mut std_dev = 0.0;
         let mut sum_squared_diff = 0.0;
         for i in 0..prices.len() {
-            let diff = prices[i] - predictions.values()[i];
+            let diff = prices[i] - predictions[i];
             sum_squared_diff += diff * diff;
         }
         std_dev = (sum_squared_diff / prices.len() as f64).sqrt();
@@ -133,7 +133,7 @@ impl<M: ForecastModel> ForecastStrategy for MeanReversionStrategy<M> {
         let mut signals = Vec::with_capacity(prices.len());
 
         for i in 0..prices.len() {
-            let diff = prices[i] - predictions.values()[i];
+            let diff = prices[i] - predictions[i];
             let normalized_diff = diff / std_dev;
 
             if normalized_diff < -self.config.threshold {
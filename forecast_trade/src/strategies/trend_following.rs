@@ -1,6 +1,6 @@
 use crate::data::TimeSeriesData;
 use crate::error::{ForecastError, Result};
-use crate::models::{ForecastModel, TrainedForecastModel};
+use crate::models::{FittedModel, ForecastModel};
 use crate::strategies::{BacktestResults, ForecastStrategy, TimeGranularity, TradingSignal};
 
 /// Trend following strategy configuration
@@ -110,11 +110,11 @@ impl<M: ForecastModel> ForecastStrategy for TrendFollowingStrategy<M> {
             )));
         }
 
-        // Train the model on the data
-        let trained_model = self.model.train(data)?;
+        // Fit the model on the data
+        let fitted_model = self.model.fit(data)?;
 
         // Generate forecast
-        let forecast = trained_model.forecast(1)?;
+        let forecast = fitted_model.forecast(1)?;
 
         // Generate signals based on the trend
         let mut signals = Vec::with_capacity(prices.len());
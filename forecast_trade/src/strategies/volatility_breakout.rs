@@ -1,7 +1,11 @@
 use crate::data::TimeSeriesData;
 use crate::error::{ForecastError, Result};
-use crate::models::ForecastModel;
-use crate::strategies::{BacktestResults, ForecastStrategy, PerformanceMetrics, TimeGranularity, TradingSignal};
+use crate::models::garch::GarchModel;
+use crate::models::{FittedModel, ForecastModel};
+use crate::strategies::{
+    BacktestResults, ForecastStrategy, PerformanceMetrics, TimeGranularity, TradingSignal,
+};
+use crate::volatility::{calculate_returns, volatility_target_position};
 
 /// Configuration for volatility breakout strategy
 #[derive(Debug, Clone)]
@@ -16,6 +20,11 @@ pub struct VolatilityBreakoutConfig {
     pub use_trailing_stop: bool,
     /// Trailing stop multiplier
     pub trailing_stop_multiplier: f64,
+    /// GARCH-family model whose conditional volatility replaces ATR or
+    /// standard deviation, if set
+    pub volatility_model: Option<GarchModel>,
+    /// Number of leading returns the volatility model is fitted on
+    pub volatility_training_window: usize,
 }
 
 impl VolatilityBreakoutConfig {
@@ -27,9 +36,11 @@ impl VolatilityBreakoutConfig {
             use_atr: true,
             use_trailing_stop: true,
             trailing_stop_multiplier: 2.0,
+            volatility_model: None,
+            volatility_training_window: 0,
         }
     }
-    
+
     /// Create a default configuration for minute data
     pub fn default_minute() -> Self {
         Self {
@@ -38,9 +49,11 @@ impl VolatilityBreakoutConfig {
             use_atr: true,
             use_trailing_stop: true,
             trailing_stop_multiplier: 1.5, // Tighter stops for minute data
+            volatility_model: None,
+            volatility_training_window: 0,
         }
     }
-    
+
     /// Create a configuration based on time granularity
     pub fn for_granularity(granularity: TimeGranularity) -> Self {
         match granularity {
@@ -48,6 +61,16 @@ impl VolatilityBreakoutConfig {
             TimeGranularity::Minute => Self::default_minute(),
         }
     }
+
+    /// Measure volatility with the conditional volatility of a GARCH-family
+    /// model fitted on the first `training_window` returns
+    ///
+    /// No breakouts are signalled until the training window has closed.
+    pub fn with_volatility_model(mut self, model: GarchModel, training_window: usize) -> Self {
+        self.volatility_model = Some(model);
+        self.volatility_training_window = training_window;
+        self
+    }
 }
 
 /// Volatility breakout strategy
@@ -120,10 +143,10 @@ impl<M: ForecastModel> VolatilityBreakoutStrategy<M> {
         let close_prices = data.close_prices();
         let high_prices = data.high_prices().unwrap_or_else(|| close_prices.clone());
         let low_prices = data.low_prices().unwrap_or_else(|| close_prices.clone());
-        
+
         let mut true_ranges = Vec::with_capacity(data.len());
         true_ranges.push(high_prices[0] - low_prices[0]); // First TR is just the range
-        
+
         for i in 1..data.len() {
             // True Range is max of:
             // 1. Current High - Current Low
@@ -132,10 +155,10 @@ impl<M: ForecastModel> VolatilityBreakoutStrategy<M> {
             let tr = (high_prices[i] - low_prices[i])
                 .max((high_prices[i] - close_prices[i - 1]).abs())
                 .max((low_prices[i] - close_prices[i - 1]).abs());
-                
+
             true_ranges.push(tr);
         }
-        
+
         // Calculate moving average of true ranges
         let mut atr = Vec::with_capacity(data.len());
         for i in 0..data.len() {
@@ -147,9 +170,80 @@ impl<M: ForecastModel> VolatilityBreakoutStrategy<M> {
                 atr.push(true_ranges[i - (period - 1)..=i].iter().sum::<f64>() / period as f64);
             }
         }
-        
+
         atr
     }
+
+    /// Conditional volatility of each price move in price units
+    ///
+    /// Entry `i` is the volatility of the move from `i - 1` to `i` given
+    /// returns up to `i - 1`. The model is fitted once on the training
+    /// window and then filtered forward, so entries up to the end of the
+    /// training window are zero and later entries use no later prices.
+    fn conditional_volatility(&self, model: &GarchModel, prices: &[f64]) -> Result<Vec<f64>> {
+        let returns = calculate_returns(prices);
+        let training = self.config.volatility_training_window;
+        if training >= returns.len() {
+            return Err(ForecastError::DataError(format!(
+                "Insufficient data. The volatility training window of {} returns leaves none to trade",
+                training
+            )));
+        }
+        let mut fitted = model.fit_series(&returns[..training])?;
+        fitted.update(&returns[training..])?;
+
+        let mut volatility = vec![0.0; prices.len()];
+        for (i, variance) in fitted
+            .conditional_variances()
+            .iter()
+            .enumerate()
+            .skip(training)
+        {
+            volatility[i + 1] = prices[i] * variance.sqrt();
+        }
+        Ok(volatility)
+    }
+
+    /// Position size in units that targets `target_volatility` per period
+    ///
+    /// The one-step volatility forecast comes from the configured GARCH
+    /// model, or from the standard deviation of returns over the lookback
+    /// period if none is set. Leverage is capped at `max_leverage`.
+    pub fn position_size(
+        &self,
+        data: &TimeSeriesData,
+        capital: f64,
+        target_volatility: f64,
+        max_leverage: f64,
+    ) -> Result<f64> {
+        let prices = data.close_prices();
+        if prices.len() < self.config.lookback_period.max(2) {
+            return Err(ForecastError::DataError(format!(
+                "Insufficient data. Need at least {} data points",
+                self.config.lookback_period.max(2)
+            )));
+        }
+
+        let returns = calculate_returns(&prices);
+        let forecast_volatility = match &self.config.volatility_model {
+            Some(model) => model.fit_series(&returns)?.forecast_volatility(1)[0],
+            None => {
+                let window = &returns[returns.len().saturating_sub(self.config.lookback_period)..];
+                let mean = window.iter().sum::<f64>() / window.len() as f64;
+                let variance =
+                    window.iter().map(|&r| (r - mean).powi(2)).sum::<f64>() / window.len() as f64;
+                variance.sqrt()
+            }
+        };
+
+        volatility_target_position(
+            capital,
+            prices[prices.len() - 1],
+            forecast_volatility,
+            target_volatility,
+            max_leverage,
+        )
+    }
 }
 
 impl<M: ForecastModel> ForecastStrategy for VolatilityBreakoutStrategy<M> {
@@ -160,12 +254,14 @@ impl<M: ForecastModel> ForecastStrategy for VolatilityBreakoutStrategy<M> {
                 self.config.lookback_period
             )));
         }
-        
+
         let prices = data.close_prices();
         let mut signals = vec![TradingSignal::Hold; prices.len()];
-        
-        // Calculate volatility measure (ATR or standard deviation)
-        let volatility = if self.config.use_atr {
+
+        // Calculate volatility measure (GARCH, ATR or standard deviation)
+        let volatility = if let Some(model) = &self.config.volatility_model {
+            self.conditional_volatility(model, &prices)?
+        } else if self.config.use_atr {
             self.calculate_atr(data, self.config.lookback_period)
         } else {
             // Calculate rolling standard deviation
@@ -175,31 +271,39 @@ impl<M: ForecastModel> ForecastStrategy for VolatilityBreakoutStrategy<M> {
                     std_devs.push(0.0);
                     continue;
                 }
-                
+
                 let window = &prices[i - (self.config.lookback_period - 1)..=i];
                 let mean = window.iter().sum::<f64>() / window.len() as f64;
-                let variance = window.iter()
-                    .map(|&x| (x - mean).powi(2))
-                    .sum::<f64>() / window.len() as f64;
-                    
+                let variance =
+                    window.iter().map(|&x| (x - mean).powi(2)).sum::<f64>() / window.len() as f64;
+
                 std_devs.push(variance.sqrt());
             }
             std_devs
         };
-        
+
         // Use the model to get a forecast
-        let model_result = self.model.train(data)?;
-        let forecast_result = model_result.forecast(data, 1)?;
+        let fitted_model = self.model.fit(data)?;
+        let forecast_result = fitted_model.forecast(1)?;
         let forecast = if !forecast_result.values.is_empty() {
             forecast_result.values[0]
         } else {
             prices[prices.len() - 1] // Use last price if no forecast
         };
-        
+
+        // A volatility model has no estimate before its training window closes
+        let first = match self.config.volatility_model {
+            Some(_) => self
+                .config
+                .lookback_period
+                .max(self.config.volatility_training_window + 1),
+            None => self.config.lookback_period,
+        };
+
         // Generate signals based on volatility breakouts
-        for i in self.config.lookback_period..prices.len() {
+        for i in first..prices.len() {
             let breakout_threshold = volatility[i] * self.config.volatility_multiplier;
-            
+
             // Calculate if we have a breakout
             if prices[i] > prices[i - 1] + breakout_threshold {
                 // Upward breakout
@@ -208,25 +312,27 @@ impl<M: ForecastModel> ForecastStrategy for VolatilityBreakoutStrategy<M> {
                 // Downward breakout
                 signals[i] = TradingSignal::Sell;
             }
-            
+
             // Apply trailing stops if enabled
             if self.config.use_trailing_stop && i > 0 {
                 let trail_threshold = volatility[i] * self.config.trailing_stop_multiplier;
-                
+
                 // Check if we need to exit a long position
-                if signals[i - 1] == TradingSignal::Buy && 
-                   prices[i] < prices[i - 1] - trail_threshold {
+                if signals[i - 1] == TradingSignal::Buy
+                    && prices[i] < prices[i - 1] - trail_threshold
+                {
                     signals[i] = TradingSignal::Sell;
                 }
-                
+
                 // Check if we need to exit a short position
-                if signals[i - 1] == TradingSignal::Sell && 
-                   prices[i] > prices[i - 1] + trail_threshold {
+                if signals[i - 1] == TradingSignal::Sell
+                    && prices[i] > prices[i - 1] + trail_threshold
+                {
                     signals[i] = TradingSignal::Buy;
                 }
             }
         }
-        
+
         Ok(signals)
     }
 
@@ -4,6 +4,9 @@ use crate::error::{ForecastError, Result};
 use crate::data::TimeSeriesData;
 use chrono::{DateTime, Duration, Utc};
 
+/// Seed used by simulations when none is given
+pub const DEFAULT_SEED: u64 = 42;
+
 /// Split time series data into training and test sets
 pub fn train_test_split(data: &[f64], test_ratio: f64) -> (Vec<f64>, Vec<f64>) {
     if data.is_empty() || test_ratio <= 0.0 || test_ratio >= 1.0 {
@@ -121,4 +124,18 @@ impl std::fmt::Display for ForecastAccuracy {
         writeln!(f, "  SMAPE: {:.4}%", self.smape)?;
         Ok(())
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use rand_distr::{Distribution, Normal};
+
+    /// `n` standard normal draws, reproducible from `seed`
+    pub(crate) fn noise(n: usize, seed: u64) -> Vec<f64> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        (0..n).map(|_| normal.sample(&mut rng)).collect()
+    }
+}
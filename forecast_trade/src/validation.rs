@@ -0,0 +1,540 @@
+//! Time-series cross-validation of forecast models
+//!
+//! A model is refitted at a sequence of forecast origins and its forecasts
+//! are compared with the values that followed. With an expanding window
+//! every fold trains on all data before the origin; with a rolling window
+//! each fold trains on a fixed number of the most recent observations.
+//! Errors are aggregated per forecast horizon, and two models evaluated on
+//! the same folds can be compared with the Diebold-Mariano test.
+
+use statrs::distribution::{ContinuousCDF, StudentsT};
+
+use crate::error::{ForecastError, Result};
+use crate::models::{FittedModel, ForecastModel};
+
+/// How the training window moves between folds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WindowType {
+    /// Train on all observations before the origin
+    #[default]
+    Expanding,
+    /// Train on the `initial_window` observations before the origin
+    Rolling,
+}
+
+/// Loss applied to forecast errors in the Diebold-Mariano test
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Loss {
+    /// Squared error
+    #[default]
+    Squared,
+    /// Absolute error
+    Absolute,
+}
+
+impl Loss {
+    fn apply(&self, error: f64) -> f64 {
+        match self {
+            Self::Squared => error * error,
+            Self::Absolute => error.abs(),
+        }
+    }
+}
+
+/// Rolling-origin cross-validation settings
+#[derive(Debug, Clone)]
+pub struct CrossValidation {
+    initial_window: usize,
+    horizon: usize,
+    step: usize,
+    window: WindowType,
+    seasonal_period: usize,
+}
+
+impl CrossValidation {
+    /// Evaluate `horizon`-step forecasts, first trained on `initial_window`
+    /// observations, with an expanding window and one origin per observation
+    pub fn new(initial_window: usize, horizon: usize) -> Result<Self> {
+        if initial_window < 2 {
+            return Err(ForecastError::InvalidParameter(
+                "Initial window must contain at least 2 observations".to_string(),
+            ));
+        }
+        if horizon == 0 {
+            return Err(ForecastError::InvalidParameter(
+                "Horizon must be greater than 0".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            initial_window,
+            horizon,
+            step: 1,
+            window: WindowType::default(),
+            seasonal_period: 1,
+        })
+    }
+
+    /// Move the origin forward by `step` observations between folds
+    pub fn with_step(mut self, step: usize) -> Result<Self> {
+        if step == 0 {
+            return Err(ForecastError::InvalidParameter(
+                "Step must be greater than 0".to_string(),
+            ));
+        }
+        self.step = step;
+        Ok(self)
+    }
+
+    /// Set the training window type
+    pub fn with_window(mut self, window: WindowType) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Scale MASE by the seasonal naive forecast with this period instead of
+    /// the naive forecast
+    pub fn with_seasonal_period(mut self, period: usize) -> Result<Self> {
+        if period == 0 || period >= self.initial_window {
+            return Err(ForecastError::InvalidParameter(
+                "Seasonal period must be positive and shorter than the initial window".to_string(),
+            ));
+        }
+        self.seasonal_period = period;
+        Ok(self)
+    }
+
+    /// Forecast horizon
+    pub fn horizon(&self) -> usize {
+        self.horizon
+    }
+
+    /// Forecast origins for a series of `len` observations; each is the
+    /// number of observations before the first forecast
+    pub fn origins(&self, len: usize) -> Vec<usize> {
+        (self.initial_window..len).step_by(self.step).collect()
+    }
+
+    /// Cross-validate `model` on `values`
+    ///
+    /// Folds near the end of the series compare fewer than `horizon`
+    /// forecasts.
+    pub fn evaluate<M: ForecastModel>(
+        &self,
+        model: &M,
+        values: &[f64],
+    ) -> Result<CrossValidationResult> {
+        let origins = self.origins(values.len());
+        if origins.is_empty() {
+            return Err(ForecastError::ValidationError(format!(
+                "Need more than {} observations for cross-validation",
+                self.initial_window
+            )));
+        }
+
+        let mut folds = Vec::with_capacity(origins.len());
+        for origin in origins {
+            let start = match self.window {
+                WindowType::Expanding => 0,
+                WindowType::Rolling => origin - self.initial_window,
+            };
+            let train = &values[start..origin];
+            let end = (origin + self.horizon).min(values.len());
+
+            let forecast = model.fit_series(train)?.forecast(end - origin)?;
+            folds.push(Fold {
+                origin,
+                last_observation: train[train.len() - 1],
+                scale: naive_scale(train, self.seasonal_period),
+                forecasts: forecast.values().to_vec(),
+                actuals: values[origin..end].to_vec(),
+            });
+        }
+
+        let horizons = (1..=self.horizon)
+            .map(|h| HorizonMetrics::from_folds(h, &folds))
+            .collect();
+
+        Ok(CrossValidationResult {
+            model: model.name().to_string(),
+            folds,
+            horizons,
+        })
+    }
+}
+
+/// Mean absolute error of the (seasonal) naive forecast in a training set
+fn naive_scale(train: &[f64], period: usize) -> f64 {
+    if train.len() <= period {
+        return f64::NAN;
+    }
+    let errors: Vec<f64> = train
+        .windows(period + 1)
+        .map(|w| (w[period] - w[0]).abs())
+        .collect();
+    errors.iter().sum::<f64>() / errors.len() as f64
+}
+
+/// Forecasts and outcomes from one forecast origin
+#[derive(Debug, Clone)]
+pub struct Fold {
+    /// Number of observations before the first forecast
+    pub origin: usize,
+    /// Last training observation
+    pub last_observation: f64,
+    /// Mean absolute naive error of the training data, used by MASE
+    pub scale: f64,
+    /// Forecasts for horizons 1, 2, ...
+    pub forecasts: Vec<f64>,
+    /// Observed values for the same horizons
+    pub actuals: Vec<f64>,
+}
+
+impl Fold {
+    /// Forecast error (actual minus forecast) at horizon `h`, if observed
+    pub fn error(&self, h: usize) -> Option<f64> {
+        let i = h.checked_sub(1)?;
+        Some(self.actuals.get(i)? - self.forecasts.get(i)?)
+    }
+}
+
+/// Error metrics of the forecasts at one horizon, across folds
+///
+/// Percentage metrics are in percent, as in `metrics::ForecastMetrics`.
+#[derive(Debug, Clone)]
+pub struct HorizonMetrics {
+    /// Steps ahead
+    pub horizon: usize,
+    /// Number of folds with an observed value at this horizon
+    pub count: usize,
+    /// Mean absolute error
+    pub mae: f64,
+    /// Root mean squared error
+    pub rmse: f64,
+    /// Mean absolute percentage error, over non-zero actuals
+    pub mape: f64,
+    /// Symmetric mean absolute percentage error
+    pub smape: f64,
+    /// Mean absolute scaled error
+    pub mase: f64,
+    /// Percentage of forecasts that got the direction of the move from the
+    /// last training observation right, among moves that were not flat
+    pub directional_accuracy: f64,
+}
+
+impl HorizonMetrics {
+    fn from_folds(h: usize, folds: &[Fold]) -> Self {
+        let (mut abs, mut sq, mut scaled) = (0.0, 0.0, 0.0);
+        let (mut pct, mut pct_count, mut spct) = (0.0, 0usize, 0.0);
+        let (mut hits, mut moves) = (0usize, 0usize);
+        let mut count = 0;
+
+        for fold in folds {
+            let Some(error) = fold.error(h) else {
+                continue;
+            };
+            let actual = fold.actuals[h - 1];
+            let forecast = fold.forecasts[h - 1];
+            count += 1;
+            abs += error.abs();
+            sq += error * error;
+            scaled += error.abs() / fold.scale;
+            if actual != 0.0 {
+                pct += 100.0 * error.abs() / actual.abs();
+                pct_count += 1;
+            }
+            let denominator = actual.abs() + forecast.abs();
+            if denominator > 0.0 {
+                spct += 200.0 * error.abs() / denominator;
+            }
+
+            let actual_move = actual - fold.last_observation;
+            if actual_move != 0.0 {
+                moves += 1;
+                let predicted_move = forecast - fold.last_observation;
+                if predicted_move.signum() == actual_move.signum() && predicted_move != 0.0 {
+                    hits += 1;
+                }
+            }
+        }
+
+        let n = count as f64;
+        Self {
+            horizon: h,
+            count,
+            mae: abs / n,
+            rmse: (sq / n).sqrt(),
+            mape: if pct_count > 0 {
+                pct / pct_count as f64
+            } else {
+                f64::NAN
+            },
+            smape: spct / n,
+            mase: scaled / n,
+            directional_accuracy: if moves > 0 {
+                100.0 * hits as f64 / moves as f64
+            } else {
+                f64::NAN
+            },
+        }
+    }
+}
+
+/// Outcome of cross-validating one model
+#[derive(Debug, Clone)]
+pub struct CrossValidationResult {
+    model: String,
+    folds: Vec<Fold>,
+    horizons: Vec<HorizonMetrics>,
+}
+
+impl CrossValidationResult {
+    /// Name of the evaluated model
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// All folds, in order of their origins
+    pub fn folds(&self) -> &[Fold] {
+        &self.folds
+    }
+
+    /// Metrics for horizons 1, 2, ...
+    pub fn per_horizon(&self) -> &[HorizonMetrics] {
+        &self.horizons
+    }
+
+    /// Metrics at horizon `h` (1-based)
+    pub fn horizon(&self, h: usize) -> Option<&HorizonMetrics> {
+        h.checked_sub(1).and_then(|i| self.horizons.get(i))
+    }
+
+    /// Forecast errors at horizon `h`, one per fold that observed it
+    pub fn errors(&self, h: usize) -> Vec<f64> {
+        self.folds.iter().filter_map(|fold| fold.error(h)).collect()
+    }
+
+    /// Diebold-Mariano test of this model against `other` at horizon `h`
+    ///
+    /// Both results must come from the same cross-validation of the same
+    /// series. A negative statistic favours this model.
+    pub fn compare(
+        &self,
+        other: &CrossValidationResult,
+        h: usize,
+        loss: Loss,
+    ) -> Result<DieboldMarianoTest> {
+        let same_folds = self.folds.len() == other.folds.len()
+            && self
+                .folds
+                .iter()
+                .zip(&other.folds)
+                .all(|(a, b)| a.origin == b.origin);
+        if !same_folds {
+            return Err(ForecastError::ValidationError(
+                "Cross-validation results must share their forecast origins".to_string(),
+            ));
+        }
+        diebold_mariano(&self.errors(h), &other.errors(h), h, loss)
+    }
+}
+
+/// Result of a Diebold-Mariano test of equal predictive accuracy
+#[derive(Debug, Clone)]
+pub struct DieboldMarianoTest {
+    /// Mean loss of the first model minus mean loss of the second
+    pub mean_loss_difference: f64,
+    /// Test statistic with the Harvey-Leybourne-Newbold correction
+    pub statistic: f64,
+    /// Two-sided p-value from the t distribution with n - 1 degrees of
+    /// freedom
+    pub p_value: f64,
+    /// Number of paired forecast errors
+    pub observations: usize,
+}
+
+impl DieboldMarianoTest {
+    /// Whether the models differ in accuracy at significance level `alpha`
+    pub fn is_significant(&self, alpha: f64) -> bool {
+        self.p_value < alpha
+    }
+}
+
+/// Diebold-Mariano test on paired forecast errors of two models
+///
+/// `horizon` sets how many autocovariances of the loss differential enter
+/// its long-run variance (`horizon - 1`), since `h`-step errors from
+/// consecutive origins overlap.
+pub fn diebold_mariano(
+    errors_a: &[f64],
+    errors_b: &[f64],
+    horizon: usize,
+    loss: Loss,
+) -> Result<DieboldMarianoTest> {
+    if errors_a.len() != errors_b.len() {
+        return Err(ForecastError::ValidationError(
+            "Forecast error series must have the same length".to_string(),
+        ));
+    }
+    if horizon == 0 {
+        return Err(ForecastError::InvalidParameter(
+            "Horizon must be greater than 0".to_string(),
+        ));
+    }
+    let n = errors_a.len();
+    if n < horizon.max(2) + 1 {
+        return Err(ForecastError::ValidationError(format!(
+            "Need more than {} paired errors",
+            horizon.max(2)
+        )));
+    }
+
+    let d: Vec<f64> = errors_a
+        .iter()
+        .zip(errors_b)
+        .map(|(a, b)| loss.apply(*a) - loss.apply(*b))
+        .collect();
+    let nf = n as f64;
+    let mean = d.iter().sum::<f64>() / nf;
+    let autocovariance = |lag: usize| {
+        (lag..n)
+            .map(|t| (d[t] - mean) * (d[t - lag] - mean))
+            .sum::<f64>()
+            / nf
+    };
+
+    let mut long_run = autocovariance(0);
+    for lag in 1..horizon {
+        long_run += 2.0 * autocovariance(lag);
+    }
+    // Truncated kernels can give a negative estimate; fall back to the
+    // variance
+    if long_run <= 0.0 {
+        long_run = autocovariance(0);
+    }
+    if long_run <= 0.0 {
+        return Err(ForecastError::MathError(
+            "Loss differential has no variance".to_string(),
+        ));
+    }
+
+    let h = horizon as f64;
+    let correction = ((nf + 1.0 - 2.0 * h + h * (h - 1.0) / nf) / nf).sqrt();
+    let statistic = correction * mean / (long_run / nf).sqrt();
+    let t =
+        StudentsT::new(0.0, 1.0, nf - 1.0).map_err(|e| ForecastError::MathError(e.to_string()))?;
+    let p_value = 2.0 * (1.0 - t.cdf(statistic.abs()));
+
+    Ok(DieboldMarianoTest {
+        mean_loss_difference: mean,
+        statistic,
+        p_value,
+        observations: n,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::exponential_smoothing::ExponentialSmoothing;
+    use crate::models::moving_average::MovingAverage;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use rand_distr::{Distribution, Normal};
+
+    fn random_walk(n: usize, seed: u64) -> Vec<f64> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        let mut level = 100.0;
+        (0..n)
+            .map(|_| {
+                level += normal.sample(&mut rng);
+                level
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_folds_and_window_types() {
+        let series: Vec<f64> = (0..20).map(|i| i as f64).collect();
+        let cv = CrossValidation::new(10, 3).unwrap().with_step(4).unwrap();
+        assert_eq!(cv.origins(series.len()), vec![10, 14, 18]);
+
+        let model = MovingAverage::new(2).unwrap();
+        let result = cv.evaluate(&model, &series).unwrap();
+        assert_eq!(result.folds().len(), 3);
+        // The last fold only observes two values
+        assert_eq!(result.folds()[2].actuals, vec![18.0, 19.0]);
+        assert_eq!(result.horizon(3).unwrap().count, 2);
+
+        // A 2-period average lags a unit trend by 1.5 at horizon 1, and its
+        // flat forecasts never predict the rise
+        let h1 = result.horizon(1).unwrap();
+        assert!((h1.mae - 1.5).abs() < 1e-12);
+        assert!((h1.mase - 1.5).abs() < 1e-12);
+        assert_eq!(h1.directional_accuracy, 0.0);
+        assert!((result.horizon(2).unwrap().rmse - 2.5).abs() < 1e-12);
+
+        let rolling = cv
+            .with_window(WindowType::Rolling)
+            .evaluate(&model, &series)
+            .unwrap();
+        assert_eq!(rolling.errors(1), result.errors(1));
+    }
+
+    #[test]
+    fn test_diebold_mariano_detects_better_model() {
+        let series = random_walk(300, 17);
+        let cv = CrossValidation::new(100, 1).unwrap();
+
+        // On a random walk, tracking the last value beats a slow average
+        let fast = cv
+            .evaluate(&ExponentialSmoothing::new(0.95).unwrap(), &series)
+            .unwrap();
+        let slow = cv
+            .evaluate(&MovingAverage::new(50).unwrap(), &series)
+            .unwrap();
+        let test = fast.compare(&slow, 1, Loss::Squared).unwrap();
+
+        assert_eq!(test.observations, 200);
+        assert!(test.statistic < 0.0);
+        assert!(test.is_significant(0.05), "p = {}", test.p_value);
+
+        // A model compared with itself shows no difference in loss
+        let identical = diebold_mariano(
+            &[1.0, -2.0, 0.5, 3.0],
+            &[1.0, -2.0, 0.5, 3.0],
+            1,
+            Loss::Absolute,
+        );
+        assert!(identical.is_err());
+    }
+
+    #[test]
+    fn test_seasonal_scale_and_validation() {
+        let series: Vec<f64> = (0..40).map(|i| [1.0, 5.0, 3.0, 7.0][i % 4]).collect();
+        let cv = CrossValidation::new(12, 4)
+            .unwrap()
+            .with_seasonal_period(4)
+            .unwrap();
+        let result = cv
+            .evaluate(&MovingAverage::new(4).unwrap(), &series)
+            .unwrap();
+        // A perfectly seasonal training set has no seasonal naive error
+        assert!(result.horizon(1).unwrap().mase.is_infinite());
+
+        assert!(CrossValidation::new(1, 1).is_err());
+        assert!(CrossValidation::new(10, 0).is_err());
+        assert!(CrossValidation::new(10, 1).unwrap().with_step(0).is_err());
+        assert!(CrossValidation::new(10, 1)
+            .unwrap()
+            .evaluate(&MovingAverage::new(2).unwrap(), &series[..10])
+            .is_err());
+
+        let other = CrossValidation::new(20, 4)
+            .unwrap()
+            .evaluate(&MovingAverage::new(4).unwrap(), &series)
+            .unwrap();
+        assert!(result.compare(&other, 1, Loss::Squared).is_err());
+    }
+}
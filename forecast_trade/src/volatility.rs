@@ -1,28 +1,26 @@
 //! Volatility forecasting and analysis
 
-use crate::error::Result;
+use crate::error::{ForecastError, Result};
 use crate::models::garch::GarchModel;
-use crate::models::ForecastModel;
+use crate::models::{FittedModel, ForecastModel};
 
 /// Calculate historical volatility over a time period
 pub fn historical_volatility(returns: &[f64], window: usize) -> Vec<f64> {
     if returns.len() < window || window == 0 {
         return vec![0.0; returns.len()];
     }
-    
+
     let mut volatility = vec![0.0; returns.len()];
-    
+
     for i in window..returns.len() {
         // Calculate variance in the window
         let window_data = &returns[i - window..i];
         let mean = window_data.iter().sum::<f64>() / window as f64;
-        let variance = window_data.iter()
-            .map(|&x| (x - mean).powi(2))
-            .sum::<f64>() / window as f64;
-        
+        let variance = window_data.iter().map(|&x| (x - mean).powi(2)).sum::<f64>() / window as f64;
+
         volatility[i] = variance;
     }
-    
+
     volatility
 }
 
@@ -31,24 +29,21 @@ pub fn calculate_returns(prices: &[f64]) -> Vec<f64> {
     if prices.len() < 2 {
         return Vec::new();
     }
-    
-    prices.windows(2)
-        .map(|w| (w[1] / w[0]) - 1.0)
-        .collect()
+
+    prices.windows(2).map(|w| (w[1] / w[0]) - 1.0).collect()
 }
 
 /// Forecast volatility using GARCH(1,1)
 pub fn forecast_volatility(prices: &[f64], forecast_horizon: usize) -> Result<Vec<f64>> {
     // Calculate returns
     let returns = calculate_returns(prices);
-    
+
     // Fit GARCH(1,1) model
-    let mut model = GarchModel::new(1, 1);
-    model.fit(&returns)?;
-    
+    let fitted = GarchModel::new(1, 1)?.fit_series(&returns)?;
+
     // Forecast volatility
-    let forecast = model.forecast(forecast_horizon)?;
-    
+    let forecast = fitted.forecast(forecast_horizon)?;
+
     Ok(forecast.values)
 }
 
@@ -62,16 +57,42 @@ pub fn ewma_volatility(returns: &[f64], lambda: f64) -> Vec<f64> {
     if returns.is_empty() {
         return Vec::new();
     }
-    
+
     let mut volatility = vec![0.0; returns.len()];
-    
+
     // Initialize with squared first return
     volatility[0] = returns[0].powi(2);
-    
+
     // Update with EWMA formula
     for i in 1..returns.len() {
-        volatility[i] = lambda * volatility[i-1] + (1.0 - lambda) * returns[i].powi(2);
+        volatility[i] = lambda * volatility[i - 1] + (1.0 - lambda) * returns[i].powi(2);
     }
-    
+
     volatility
-} 
\ No newline at end of file
+}
+/// Number of units to hold so that the position's volatility matches a target
+///
+/// Exposure is `capital * target_volatility / forecast_volatility`, capped at
+/// `max_leverage` times capital. Volatilities are per-period standard
+/// deviations of returns, e.g. from `FittedGarch::forecast_volatility`.
+pub fn volatility_target_position(
+    capital: f64,
+    price: f64,
+    forecast_volatility: f64,
+    target_volatility: f64,
+    max_leverage: f64,
+) -> Result<f64> {
+    if capital < 0.0 || price <= 0.0 {
+        return Err(ForecastError::InvalidParameter(
+            "Capital must be non-negative and price positive".to_string(),
+        ));
+    }
+    if forecast_volatility <= 0.0 || target_volatility <= 0.0 || max_leverage <= 0.0 {
+        return Err(ForecastError::InvalidParameter(
+            "Volatilities and maximum leverage must be positive".to_string(),
+        ));
+    }
+
+    let leverage = (target_volatility / forecast_volatility).min(max_leverage);
+    Ok(capital * leverage / price)
+}
@@ -1,12 +1,11 @@
+use chrono::{Duration, TimeZone, Utc};
 use forecast_trade::models::exponential_smoothing::ExponentialSmoothing;
 use forecast_trade::strategies::mean_reversion::MeanReversionStrategy;
-use forecast_trade::utils::date_parser;
-use forecast_trade::volatility::historical_volatility;
+use forecast_trade::volatility::{calculate_returns, historical_volatility};
 use forecast_trade::{
-    DataLoader, ForecastError, ForecastModel, ForecastResult, ForecastStrategy, TimeSeriesData,
+    DataLoader, FittedModel, ForecastError, ForecastModel, ForecastStrategy, TimeSeriesData,
 };
 use std::io::Write;
-use std::path::Path;
 use tempfile::NamedTempFile;
 
 // Helper function to create a simple test dataset
@@ -14,16 +13,11 @@ fn create_sample_data() -> NamedTempFile {
     let mut file = NamedTempFile::new().unwrap();
 
     writeln!(file, "date,price").unwrap();
-    writeln!(file, "2023-01-01,100.0").unwrap();
-    writeln!(file, "2023-01-02,102.0").unwrap();
-    writeln!(file, "2023-01-03,101.0").unwrap();
-    writeln!(file, "2023-01-04,103.0").unwrap();
-    writeln!(file, "2023-01-05,102.0").unwrap();
-    writeln!(file, "2023-01-06,104.0").unwrap();
-    writeln!(file, "2023-01-07,103.0").unwrap();
-    writeln!(file, "2023-01-08,105.0").unwrap();
-    writeln!(file, "2023-01-09,104.0").unwrap();
-    writeln!(file, "2023-01-10,106.0").unwrap();
+    for day in 1..=30 {
+        // Zigzag around an upward drift
+        let price = 100.0 + day as f64 * 0.5 + if day % 2 == 0 { 1.0 } else { -1.0 };
+        writeln!(file, "2023-01-{:02},{:.1}", day, price).unwrap();
+    }
 
     file
 }
@@ -36,24 +30,24 @@ fn test_full_forecast_workflow() {
 
     // 2. Load data
     let data = DataLoader::from_csv(file_path).unwrap();
-    assert_eq!(data.len(), 10);
+    assert_eq!(data.len(), 30);
 
-    // 3. Create and train a forecasting model
+    // 3. Create and fit a forecasting model
     let model = ExponentialSmoothing::new(0.7).unwrap();
-    let trained_model = model.train(&data).unwrap();
+    let fitted = model.fit(&data).unwrap();
 
     // 4. Generate forecast
-    let forecast = trained_model.forecast(3).unwrap();
+    let forecast = fitted.forecast(3).unwrap();
     assert_eq!(forecast.horizons(), 3);
 
-    // 5. Evaluate forecast accuracy on existing data
-    let predicted = trained_model.predict(&data).unwrap();
-    let mse = predicted.mean_squared_error(&data).unwrap();
+    // 5. Evaluate in-sample accuracy
+    let mse = fitted.residuals().iter().map(|r| r * r).sum::<f64>() / data.len() as f64;
     assert!(mse >= 0.0);
 
-    // 6. Calculate volatility
-    let volatility = historical_volatility(&data, 5).unwrap();
-    assert_eq!(volatility.len(), 6); // 10 - 5 + 1
+    // 6. Calculate volatility of the returns
+    let returns = calculate_returns(&data.close_prices());
+    let volatility = historical_volatility(&returns, 5);
+    assert_eq!(volatility.len(), 29);
 
     // 7. Create and test a trading strategy
     let strategy = MeanReversionStrategy::new(model, 1.5).unwrap();
@@ -76,16 +70,8 @@ fn test_full_forecast_workflow() {
 #[test]
 fn test_create_custom_strategy() {
     // Create a simple dataset
-    let dates = vec![
-        "2023-01-01",
-        "2023-01-02",
-        "2023-01-03",
-        "2023-01-04",
-        "2023-01-05",
-    ]
-    .into_iter()
-    .map(|s| date_parser::parse_date(s).unwrap())
-    .collect();
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let dates = (0..5).map(|i| start + Duration::days(i)).collect();
 
     let values = vec![100.0, 102.0, 101.0, 103.0, 102.0];
     let data = TimeSeriesData::new(dates, values).unwrap();
@@ -94,30 +80,25 @@ fn test_create_custom_strategy() {
     let model = ExponentialSmoothing::new(0.5).unwrap();
 
     // Test the combined forecasting and volatility approach
-    let trained_model = model.train(&data).unwrap();
-    let forecast = trained_model.forecast(2).unwrap();
-    let volatility = historical_volatility(&data, 3).unwrap();
+    let fitted = model.fit(&data).unwrap();
+    let forecast = fitted.forecast(2).unwrap();
+    let returns = calculate_returns(&data.close_prices());
+    let volatility = historical_volatility(&returns, 3);
 
     // Verify forecast
     assert_eq!(forecast.horizons(), 2);
     assert!(!forecast.values().is_empty());
 
     // Verify volatility
-    assert_eq!(volatility.len(), 3);
-    assert!(volatility.iter().all(|v| *v > 0.0));
+    assert_eq!(volatility.len(), 4);
+    assert!(volatility[3] > 0.0);
 
     // Create a confidence interval based on volatility
     let forecast_values = forecast.values();
+    let vol = volatility[volatility.len() - 1].sqrt() * forecast_values[0];
     let confidence_intervals: Vec<(f64, f64)> = forecast_values
         .iter()
-        .enumerate()
-        .map(|(i, v)| {
-            // Use the last volatility value for simplicity
-            let vol = volatility[volatility.len() - 1];
-            let lower = v - 1.96 * vol;
-            let upper = v + 1.96 * vol;
-            (lower, upper)
-        })
+        .map(|v| (v - 1.96 * vol, v + 1.96 * vol))
         .collect();
 
     // Verify confidence intervals
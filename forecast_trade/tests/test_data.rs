@@ -1,6 +1,6 @@
+use chrono::{Duration, TimeZone, Utc};
 use forecast_trade::data::{DataLoader, TimeSeriesData};
 use std::io::Write;
-use std::path::Path;
 use tempfile::NamedTempFile;
 
 #[test]
@@ -17,15 +17,19 @@ fn test_data_loader_from_csv() {
 
     assert_eq!(data.len(), 3);
     assert!(!data.is_empty());
+    assert_eq!(data.close_prices(), [103.0, 106.0, 108.0]);
+
+    // Dates are parsed, so the data converts to daily bars
+    let bars = data.to_daily_ohlcv().unwrap();
+    assert_eq!(bars[2].date.to_string(), "2023-01-03");
+    assert_eq!(bars[2].data.volume, 1500);
 }
 
 #[test]
 fn test_time_series_data_operations() {
     // Create test data
-    let dates = vec!["2023-01-01", "2023-01-02", "2023-01-03"]
-        .into_iter()
-        .map(|s| s.parse().unwrap())
-        .collect();
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let dates = (0..3).map(|i| start + Duration::days(i)).collect();
 
     let values = vec![100.0, 103.0, 106.0];
 
@@ -36,7 +40,7 @@ fn test_time_series_data_operations() {
     assert!(!data.is_empty());
 
     // Test slicing
-    let subset = data.slice(1, 3).unwrap();
+    let subset = data.slice(1, Some(3)).unwrap();
     assert_eq!(subset.len(), 2);
 
     // Test statistical methods
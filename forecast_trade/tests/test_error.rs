@@ -1,6 +1,6 @@
 use forecast_trade::error::ForecastError;
+use std::fs::File;
 use std::io;
-use std::path::Path;
 
 #[test]
 fn test_error_conversion() {
@@ -9,17 +9,17 @@ fn test_error_conversion() {
     let forecast_error = ForecastError::from(io_error);
 
     match forecast_error {
-        ForecastError::IoError(_) => assert!(true),
+        ForecastError::IoError(_) => {}
         _ => panic!("Expected IoError variant"),
     }
 
-    // Test parse error
-    let parse_error = "invalid data".parse::<i32>().unwrap_err();
-    let forecast_error = ForecastError::from(parse_error);
+    // Test Polars error conversion
+    let polars_error = polars::prelude::PolarsError::NoData("empty frame".into());
+    let forecast_error = ForecastError::from(polars_error);
 
     match forecast_error {
-        ForecastError::ParseError(_) => assert!(true),
-        _ => panic!("Expected ParseError variant"),
+        ForecastError::PolarsError(msg) => assert!(msg.contains("empty frame")),
+        _ => panic!("Expected PolarsError variant"),
     }
 }
 
@@ -44,12 +44,12 @@ fn test_error_display() {
 fn test_error_creation() {
     // Test creating different error types
     let data_error = ForecastError::DataError("Empty time series".to_string());
-    let model_error = ForecastError::ModelError("Failed to converge".to_string());
+    let model_error = ForecastError::ForecastingError("Failed to converge".to_string());
     let parameter_error = ForecastError::InvalidParameter("Invalid window size".to_string());
 
     // Verify they are different types
     assert!(matches!(data_error, ForecastError::DataError(_)));
-    assert!(matches!(model_error, ForecastError::ModelError(_)));
+    assert!(matches!(model_error, ForecastError::ForecastingError(_)));
     assert!(matches!(
         parameter_error,
         ForecastError::InvalidParameter(_)
@@ -67,17 +67,17 @@ fn test_error_creation() {
 fn test_result_mapping() {
     // Test using map_err with Result
     let result: Result<(), &str> = Err("test error");
-    let mapped = result.map_err(|e| ForecastError::Other(e.to_string()));
+    let mapped = result.map_err(|e| ForecastError::ValidationError(e.to_string()));
 
     assert!(mapped.is_err());
-    if let Err(ForecastError::Other(msg)) = mapped {
+    if let Err(ForecastError::ValidationError(msg)) = mapped {
         assert_eq!(msg, "test error");
     } else {
         panic!("Wrong error variant");
     }
 
     // Test with a simulated file operation
-    let file_result = Path::new("/nonexistent/path").try_exists();
+    let file_result = File::open("/nonexistent/path");
     let mapped = file_result.map_err(ForecastError::from);
 
     assert!(mapped.is_err());
@@ -1,99 +1,100 @@
-use assert_approx_eq::assert_approx_eq;
+use chrono::{Duration, TimeZone, Utc};
+use day_trade::Signal;
 use forecast_trade::data::TimeSeriesData;
 use forecast_trade::metrics::{
-    accuracy_score, f1_score, mean_absolute_error, mean_absolute_percentage_error,
-    mean_squared_error, precision_score, recall_score, root_mean_squared_error,
-    symmetric_mean_absolute_percentage_error,
+    evaluate_forecast, evaluate_model, evaluate_strategy, maximum_drawdown,
 };
+use forecast_trade::models::moving_average::MovingAverage;
+
+fn create_data(values: Vec<f64>) -> TimeSeriesData {
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let dates = (0..values.len() as i64)
+        .map(|i| start + Duration::days(i))
+        .collect();
+    TimeSeriesData::new(dates, values).unwrap()
+}
 
 #[test]
 fn test_regression_metrics() {
     let actual = vec![10.0, 20.0, 30.0, 40.0, 50.0];
     let predicted = vec![12.0, 18.0, 33.0, 37.0, 52.0];
 
+    let metrics = evaluate_forecast(&predicted, &actual).unwrap();
+
     // Test MAE
-    let mae = mean_absolute_error(&actual, &predicted);
-    assert_approx_eq!(mae, 2.8, 0.01);
+    assert!((metrics.mae - 2.4).abs() < 0.01);
 
     // Test MSE
-    let mse = mean_squared_error(&actual, &predicted);
-    assert_approx_eq!(mse, 10.0, 0.01);
+    assert!((metrics.mse - 6.0).abs() < 0.01);
 
     // Test RMSE
-    let rmse = root_mean_squared_error(&actual, &predicted);
-    assert_approx_eq!(rmse, 3.16, 0.01);
+    assert!((metrics.rmse - 6.0_f64.sqrt()).abs() < 0.01);
 
-    // Test MAPE
-    let mape = mean_absolute_percentage_error(&actual, &predicted);
-    assert!(mape > 0.0 && mape < 0.15);
+    // MAPE and SMAPE are in percent
+    assert!(metrics.mape > 0.0 && metrics.mape < 15.0);
+    assert!(metrics.smape > 0.0 && metrics.smape < 15.0);
 
-    // Test SMAPE
-    let smape = symmetric_mean_absolute_percentage_error(&actual, &predicted);
-    assert!(smape > 0.0 && smape < 0.15);
+    // Every step moves in the same direction as the actual values
+    assert_eq!(metrics.direction_accuracy, 100.0);
 }
 
 #[test]
-fn test_classification_metrics() {
-    let actual = vec![1.0, 0.0, 1.0, 1.0, 0.0, 1.0, 0.0, 1.0];
-    let predicted = vec![1.0, 0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 1.0];
-
-    // Test accuracy
-    let accuracy = accuracy_score(&actual, &predicted);
-    assert_approx_eq!(accuracy, 0.75, 0.01);
-
-    // Test precision
-    let precision = precision_score(&actual, &predicted);
-    assert_approx_eq!(precision, 0.8, 0.01);
-
-    // Test recall
-    let recall = recall_score(&actual, &predicted);
-    assert_approx_eq!(recall, 0.8, 0.01);
-
-    // Test F1 score
-    let f1 = f1_score(&actual, &predicted);
-    assert_approx_eq!(f1, 0.8, 0.01);
+fn test_strategy_metrics() {
+    let prices = vec![100.0, 110.0, 121.0, 108.9, 119.79];
+    let signals = vec![
+        Signal::Buy,
+        Signal::Hold,
+        Signal::Hold,
+        Signal::Hold,
+        Signal::Hold,
+    ];
+
+    let metrics = evaluate_strategy(&signals, &prices).unwrap();
+
+    // Long through +10%, +10%, -10%, +10%
+    assert!((metrics.total_return - 0.2).abs() < 1e-9);
+    assert!((metrics.win_rate - 75.0).abs() < 1e-9);
+    assert!((metrics.max_drawdown - 0.1).abs() < 1e-9);
+    assert!(metrics.sharpe_ratio > 0.0);
+
+    assert!((maximum_drawdown(&[0.1, -0.5, 0.2]) - 0.5).abs() < 1e-9);
+    assert_eq!(maximum_drawdown(&[]), 0.0);
 }
 
 #[test]
 fn test_error_handling() {
     // Test with empty vectors
     let empty: Vec<f64> = vec![];
-    let actual = vec![1.0, 2.0];
-
-    let result = mean_absolute_error(&empty, &actual);
-    assert!(result.is_nan());
+    assert!(evaluate_forecast(&empty, &empty).is_err());
 
     // Test with mismatched lengths
     let actual = vec![1.0, 2.0, 3.0];
     let predicted = vec![1.0, 2.0];
+    assert!(evaluate_forecast(&predicted, &actual).is_err());
 
-    let result = mean_squared_error(&actual, &predicted);
-    assert!(result.is_nan());
+    assert!(evaluate_strategy(&[Signal::Buy], &[1.0, 2.0]).is_err());
 }
 
 #[test]
-fn test_metrics_with_timeseries_data() {
-    // Create two TimeSeriesData objects
-    let dates1 = vec!["2023-01-01", "2023-01-02", "2023-01-03"]
-        .into_iter()
-        .map(|s| s.parse().unwrap())
-        .collect();
-
-    let values1 = vec![100.0, 102.0, 104.0];
-    let data1 = TimeSeriesData::new(dates1, values1).unwrap();
-
-    let dates2 = vec!["2023-01-01", "2023-01-02", "2023-01-03"]
-        .into_iter()
-        .map(|s| s.parse().unwrap())
-        .collect();
+fn test_evaluate_model() {
+    let train = create_data((0..20).map(|i| 100.0 + i as f64).collect());
+    let test = [120.0, 121.0, 122.0];
+
+    let model = MovingAverage::new(3).unwrap();
+    let metrics = evaluate_model(&model, &train, &test, 3).unwrap();
+    assert!(metrics.mae > 0.0);
+    assert!((metrics.rmse - metrics.mse.sqrt()).abs() < 1e-12);
+}
 
-    let values2 = vec![101.0, 103.0, 103.0];
-    let data2 = TimeSeriesData::new(dates2, values2).unwrap();
+#[test]
+fn test_metrics_with_timeseries_data() {
+    let data1 = create_data(vec![100.0, 102.0, 104.0]);
+    let data2 = create_data(vec![101.0, 103.0, 103.0]);
 
     // Calculate metrics
     let mae = data1.mean_absolute_error(&data2).unwrap();
-    assert_approx_eq!(mae, 1.0);
+    assert!((mae - 1.0).abs() < 1e-12);
 
     let mse = data1.mean_squared_error(&data2).unwrap();
-    assert_approx_eq!(mse, 1.33, 0.01);
+    assert!((mse - 1.0).abs() < 1e-12);
 }
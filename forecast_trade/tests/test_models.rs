@@ -1,23 +1,18 @@
-use assert_approx_eq::assert_approx_eq;
+use chrono::{Duration, TimeZone, Utc};
 use forecast_trade::data::TimeSeriesData;
-use forecast_trade::models::arima::ARIMA;
+use forecast_trade::models::arima::ArimaModel;
 use forecast_trade::models::exponential_smoothing::ExponentialSmoothing;
 use forecast_trade::models::moving_average::MovingAverage;
-use forecast_trade::models::{ForecastModel, ForecastResult};
+use forecast_trade::models::{FittedModel, ForecastModel, ForecastResult};
 
 fn create_test_data() -> TimeSeriesData {
-    let dates = vec![
-        "2023-01-01",
-        "2023-01-02",
-        "2023-01-03",
-        "2023-01-04",
-        "2023-01-05",
-    ]
-    .into_iter()
-    .map(|s| s.parse().unwrap())
-    .collect();
-
-    let values = vec![100.0, 102.0, 104.0, 103.0, 105.0];
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let dates = (0..30).map(|i| start + Duration::days(i)).collect();
+
+    // Upward drift with a small oscillation
+    let values = (0..30)
+        .map(|i| 100.0 + i as f64 * 0.5 + (i as f64 * 0.8).sin())
+        .collect();
 
     TimeSeriesData::new(dates, values).unwrap()
 }
@@ -27,21 +22,21 @@ fn test_exponential_smoothing() {
     let data = create_test_data();
     let model = ExponentialSmoothing::new(0.7).unwrap();
 
-    // Train the model
-    let trained_model = model.train(&data).unwrap();
+    // Fit the model
+    let fitted = model.fit(&data).unwrap();
 
     // Forecast future values
-    let forecast = trained_model.forecast(3).unwrap();
+    let forecast = fitted.forecast(3).unwrap();
 
     assert_eq!(forecast.horizons(), 3);
     assert!(!forecast.values().is_empty());
 
-    // Test prediction accuracy
-    let predicted = trained_model.predict(&data).unwrap();
-    assert_eq!(predicted.len(), data.len());
+    // One in-sample prediction per observation
+    assert_eq!(fitted.fitted_values().len(), data.len());
+    assert_eq!(fitted.residuals().len(), data.len());
 
     // Calculate error metrics
-    let mse = predicted.mean_squared_error(&data).unwrap();
+    let mse = fitted.residuals().iter().map(|r| r * r).sum::<f64>() / data.len() as f64;
     assert!(mse >= 0.0);
 }
 
@@ -50,11 +45,11 @@ fn test_moving_average() {
     let data = create_test_data();
     let model = MovingAverage::new(3).unwrap();
 
-    // Train the model
-    let trained_model = model.train(&data).unwrap();
+    // Fit the model
+    let fitted = model.fit(&data).unwrap();
 
     // Forecast future values
-    let forecast = trained_model.forecast(2).unwrap();
+    let forecast = fitted.forecast(2).unwrap();
 
     assert_eq!(forecast.horizons(), 2);
     assert!(!forecast.values().is_empty());
@@ -62,31 +57,57 @@ fn test_moving_average() {
     // Verify forecast values are reasonable
     let values = forecast.values();
     for value in values {
-        assert!(*value > 100.0 && *value < 110.0);
+        assert!(*value > 100.0 && *value < 120.0);
     }
 }
 
 #[test]
 fn test_arima_model() {
     let data = create_test_data();
-    let model = ARIMA::new(1, 0, 1).unwrap();
+    let model = ArimaModel::new(1, 0, 1).unwrap();
 
-    // Train the model
-    let trained_model = model.train(&data).unwrap();
+    // Fit the model
+    let fitted = model.fit(&data).unwrap();
 
     // Forecast future values
-    let forecast = trained_model.forecast(1).unwrap();
+    let forecast = fitted.forecast(1).unwrap();
 
     assert_eq!(forecast.horizons(), 1);
-    assert!(!forecast.values().is_empty());
+    assert!(forecast.values()[0].is_finite());
+}
+
+#[test]
+fn test_confidence_intervals() {
+    let data = create_test_data();
+    let fitted = ArimaModel::new(1, 1, 0).unwrap().fit(&data).unwrap();
 
-    // Test confidence intervals
+    // Model forecasts carry their standard errors
+    let forecast = fitted.forecast(3).unwrap();
     let intervals = forecast.confidence_intervals(0.95).unwrap();
     assert_eq!(intervals.len(), forecast.horizons());
 
-    for (lower, upper) in intervals {
-        assert!(lower < upper);
+    for ((lower, upper), value) in intervals.iter().zip(forecast.values()) {
+        assert!(lower < value && value < upper);
     }
+    // Uncertainty grows with the horizon
+    assert!(intervals[2].1 - intervals[2].0 > intervals[0].1 - intervals[0].0);
+
+    // A point forecast has no intervals
+    let point = ForecastResult::new(vec![105.0, 106.0], 2).unwrap();
+    assert!(point.confidence_intervals(0.95).is_err());
+}
+
+#[test]
+fn test_update_extends_fitted_model() {
+    let data = create_test_data();
+    let mut fitted = ExponentialSmoothing::new(0.5)
+        .unwrap()
+        .fit_series(&data.close_prices())
+        .unwrap();
+
+    fitted.update(&[116.0, 117.0]).unwrap();
+    assert_eq!(fitted.fitted_values().len(), data.len() + 2);
+    assert_eq!(fitted.forecast(4).unwrap().horizons(), 4);
 }
 
 #[test]
@@ -97,14 +118,13 @@ fn test_forecast_result_operations() {
     assert_eq!(forecast.horizons(), 3);
     assert_eq!(forecast.values(), &values);
 
-    // Test serialization methods
-    let json = forecast.to_json().unwrap();
-    assert!(!json.is_empty());
+    // Horizons must match the number of values
+    assert!(ForecastResult::new(values, 2).is_err());
 
     // Test comparison methods
     let actual = vec![106.0, 107.0, 108.0];
     let error = forecast.mean_absolute_error(&actual).unwrap();
-    assert_approx_eq!(error, 1.0);
+    assert!((error - 1.0).abs() < 1e-12);
 }
 
 #[test]
@@ -116,6 +136,6 @@ fn test_model_parameter_validation() {
     let result = MovingAverage::new(0);
     assert!(result.is_err());
 
-    let result = ARIMA::new(-1, 0, 1);
+    let result = ArimaModel::new(1, 3, 1);
     assert!(result.is_err());
 }
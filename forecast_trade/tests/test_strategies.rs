@@ -1,10 +1,21 @@
 use chrono::{TimeZone, Utc};
 use forecast_trade::data::TimeSeriesData;
+use forecast_trade::features::FeatureSet;
+use forecast_trade::models::ensemble::{CombinationMethod, EnsembleModel};
 use forecast_trade::models::exponential_smoothing::ExponentialSmoothing;
+use forecast_trade::models::garch::GarchModel;
+use forecast_trade::models::ml::tree::GradientBoosting;
+use forecast_trade::models::ml::MlModel;
+use forecast_trade::models::moving_average::MovingAverage;
 use forecast_trade::strategies::mean_reversion::MeanReversionStrategy;
 use forecast_trade::strategies::trend_following::TrendFollowingStrategy;
-use forecast_trade::strategies::volatility_breakout::VolatilityBreakoutStrategy;
-use forecast_trade::strategies::{ForecastStrategy, TimeGranularity};
+use forecast_trade::strategies::volatility_breakout::{
+    VolatilityBreakoutConfig, VolatilityBreakoutStrategy,
+};
+use forecast_trade::strategies::{ForecastStrategy, TimeGranularity, TradingSignal};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rand_distr::{Distribution, Normal};
 
 /// Helper function to create sample daily data
 fn create_sample_daily_data() -> TimeSeriesData {
@@ -73,6 +84,36 @@ fn test_trend_following_strategy() {
     assert_eq!(signals.len(), data.len());
 }
 
+#[test]
+fn test_strategies_with_ensemble() {
+    let data = create_sample_daily_data();
+    let model = EnsembleModel::new(CombinationMethod::InverseError)
+        .with_model(ExponentialSmoothing::new(0.2).unwrap())
+        .with_model(MovingAverage::new(5).unwrap());
+
+    let trend = TrendFollowingStrategy::new(model.clone(), 10).unwrap();
+    assert_eq!(trend.generate_signals(&data).unwrap().len(), data.len());
+
+    let reversion = MeanReversionStrategy::new(model, 2.0).unwrap();
+    assert_eq!(reversion.generate_signals(&data).unwrap().len(), data.len());
+}
+
+#[test]
+fn test_strategies_with_ml_model() {
+    let data = create_sample_daily_data();
+    let model = MlModel::new(
+        FeatureSet::default(),
+        GradientBoosting::new(20, 0.1, 2).unwrap(),
+    )
+    .unwrap();
+
+    let trend = TrendFollowingStrategy::new(model.clone(), 10).unwrap();
+    assert_eq!(trend.generate_signals(&data).unwrap().len(), data.len());
+
+    let reversion = MeanReversionStrategy::new(model, 2.0).unwrap();
+    assert_eq!(reversion.generate_signals(&data).unwrap().len(), data.len());
+}
+
 #[test]
 fn test_volatility_breakout_strategy() {
     let data = create_sample_daily_data();
@@ -85,6 +126,82 @@ fn test_volatility_breakout_strategy() {
     assert_eq!(signals.len(), data.len());
 }
 
+#[test]
+fn test_volatility_breakout_with_garch() {
+    let start_date = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let dates = (0..300)
+        .map(|i| start_date + chrono::Duration::days(i))
+        .collect();
+    let mut price = 100.0;
+    let prices = (0..300)
+        .map(|i| {
+            let scale = if (100..150).contains(&i) { 0.03 } else { 0.01 };
+            price *= 1.0 + scale * (i as f64 * 2.1).sin();
+            price
+        })
+        .collect();
+    let data = TimeSeriesData::new(dates, prices).unwrap();
+
+    let config = VolatilityBreakoutConfig::default_daily()
+        .with_volatility_model(GarchModel::new(1, 1).unwrap(), 200);
+    let model = ExponentialSmoothing::new(0.2).unwrap();
+    let strategy = VolatilityBreakoutStrategy::new_with_config(model, config);
+
+    let signals = strategy.generate_signals(&data).unwrap();
+    assert_eq!(signals.len(), data.len());
+    // No breakouts until the model has been fitted
+    assert!(signals[..=200].iter().all(|s| *s == TradingSignal::Hold));
+
+    // Positions scale with the volatility target until leverage is capped
+    let price = data.close_prices()[299];
+    let full = strategy
+        .position_size(&data, 10_000.0, 0.02, 100.0)
+        .unwrap();
+    let half = strategy
+        .position_size(&data, 10_000.0, 0.01, 100.0)
+        .unwrap();
+    let capped = strategy.position_size(&data, 10_000.0, 0.02, 0.1).unwrap();
+    assert!(full > 0.0);
+    assert!((full - 2.0 * half).abs() < 1e-9 * full);
+    assert!((capped - 1_000.0 / price).abs() < 1e-9);
+}
+
+#[test]
+fn test_volatility_breakout_has_no_look_ahead() {
+    let start_date = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let dates: Vec<_> = (0..300)
+        .map(|i| start_date + chrono::Duration::days(i))
+        .collect();
+    let mut rng = StdRng::seed_from_u64(11);
+    let noise = Normal::new(0.0, 0.01).unwrap();
+    let mut prices = vec![100.0];
+    for _ in 1..300 {
+        let last = prices[prices.len() - 1];
+        prices.push(last * (1.0 + noise.sample(&mut rng)));
+    }
+    let data = TimeSeriesData::new(dates.clone(), prices.clone()).unwrap();
+
+    let mut config = VolatilityBreakoutConfig::default_daily()
+        .with_volatility_model(GarchModel::new(1, 1).unwrap(), 200);
+    config.volatility_multiplier = 0.5;
+    let strategy = VolatilityBreakoutStrategy::new_with_config(
+        ExponentialSmoothing::new(0.2).unwrap(),
+        config,
+    );
+    let signals = strategy.generate_signals(&data).unwrap();
+    assert!(signals[201..=250].iter().any(|s| *s != TradingSignal::Hold));
+
+    // A far more turbulent tail leaves every earlier signal unchanged
+    for i in 251..300 {
+        prices[i] = prices[i - 1] * (1.0 + 8.0 * noise.sample(&mut rng));
+    }
+    let changed = TimeSeriesData::new(dates, prices).unwrap();
+    assert_eq!(
+        strategy.generate_signals(&changed).unwrap()[..=250],
+        signals[..=250]
+    );
+}
+
 #[test]
 fn test_backtest_results() {
     let data = create_sample_daily_data();
@@ -1,160 +1,106 @@
-use forecast_trade::data::TimeSeriesData;
-use forecast_trade::utils::date_parser;
 use forecast_trade::volatility::{
-    exponential_volatility, garch_volatility, historical_volatility, parkinson_volatility,
+    annualize_daily_volatility, calculate_returns, ewma_volatility, forecast_volatility,
+    historical_volatility, volatility_target_position,
 };
 
-fn create_test_data() -> TimeSeriesData {
-    let dates = vec![
-        "2023-01-01",
-        "2023-01-02",
-        "2023-01-03",
-        "2023-01-04",
-        "2023-01-05",
-        "2023-01-06",
-        "2023-01-07",
-        "2023-01-08",
-        "2023-01-09",
-        "2023-01-10",
-    ]
-    .into_iter()
-    .map(|s| date_parser::parse_date(s).unwrap())
-    .collect();
-
+fn create_test_prices() -> Vec<f64> {
     // Create a price series with increasing volatility
-    let values = vec![
+    vec![
         100.0, 101.0, 100.5, 102.0, 100.0, 103.0, 99.0, 105.0, 98.0, 106.0,
-    ];
-
-    TimeSeriesData::new(dates, values).unwrap()
-}
-
-fn create_ohlc_data() -> TimeSeriesData {
-    let dates = vec![
-        "2023-01-01",
-        "2023-01-02",
-        "2023-01-03",
-        "2023-01-04",
-        "2023-01-05",
     ]
-    .into_iter()
-    .map(|s| date_parser::parse_date(s).unwrap())
-    .collect();
-
-    // Create high-low data for Parkinson volatility
-    // Each entry has (open, high, low, close)
-    let ohlc_data = vec![
-        (100.0, 102.0, 98.0, 101.0),
-        (101.0, 104.0, 100.0, 103.0),
-        (103.0, 106.0, 101.0, 102.0),
-        (102.0, 105.0, 99.0, 100.0),
-        (100.0, 103.0, 97.0, 102.0),
-    ];
-
-    TimeSeriesData::new_ohlc(dates, ohlc_data).unwrap()
 }
 
-#[test]
-fn test_historical_volatility() {
-    let data = create_test_data();
-
-    // Test with window size of 5
-    let vol = historical_volatility(&data, 5).unwrap();
-
-    // Should return volatility for each point after the window
-    assert_eq!(vol.len(), data.len() - 5 + 1);
-
-    // Volatility should be positive
-    for v in vol {
-        assert!(v > 0.0);
-    }
-
-    // Test with window size larger than data
-    let result = historical_volatility(&data, 20);
-    assert!(result.is_err());
+/// Prices with a calm first half and a volatile second half
+fn create_long_prices() -> Vec<f64> {
+    let mut price = 100.0;
+    (0..200)
+        .map(|i| {
+            let scale = if i < 100 { 0.005 } else { 0.02 };
+            price *= 1.0 + scale * (i as f64 * 2.3).sin();
+            price
+        })
+        .collect()
 }
 
 #[test]
-fn test_exponential_volatility() {
-    let data = create_test_data();
-
-    // Test with lambda = 0.94 (common value)
-    let vol = exponential_volatility(&data, 0.94).unwrap();
+fn test_calculate_returns() {
+    let returns = calculate_returns(&[100.0, 110.0, 99.0]);
 
-    // Should return volatility for each point
-    assert_eq!(vol.len(), data.len());
+    assert_eq!(returns.len(), 2);
+    assert!((returns[0] - 0.1).abs() < 1e-12);
+    assert!((returns[1] + 0.1).abs() < 1e-12);
 
-    // Volatility should be increasing (our test data has increasing volatility)
-    for i in 1..vol.len() {
-        if i > 5 {
-            // Allow for initialization period
-            assert!(vol[i] > vol[1]);
-        }
-    }
-
-    // Test with invalid lambda
-    let result = exponential_volatility(&data, 1.5);
-    assert!(result.is_err());
+    assert!(calculate_returns(&[100.0]).is_empty());
 }
 
 #[test]
-fn test_garch_volatility() {
-    let data = create_test_data();
+fn test_historical_volatility() {
+    let returns = calculate_returns(&create_test_prices());
 
-    // Test with standard GARCH(1,1) parameters
-    let vol = garch_volatility(&data, 0.01, 0.1, 0.89).unwrap();
+    // Test with window size of 5
+    let vol = historical_volatility(&returns, 5);
 
-    // Should return volatility for each point
-    assert_eq!(vol.len(), data.len());
+    // One value per return, zero until the window is full
+    assert_eq!(vol.len(), returns.len());
+    assert!(vol[..5].iter().all(|v| *v == 0.0));
+    assert!(vol[5..].iter().all(|v| *v > 0.0));
 
-    // Volatility should be positive
-    for v in vol {
-        assert!(v > 0.0);
-    }
+    // Later windows are more volatile
+    assert!(vol[8] > vol[5]);
 
-    // Test with invalid parameters (sum > 1)
-    let result = garch_volatility(&data, 0.2, 0.5, 0.5);
-    assert!(result.is_err());
+    // Test with window size larger than data
+    let vol = historical_volatility(&returns, 20);
+    assert!(vol.iter().all(|v| *v == 0.0));
 }
 
 #[test]
-fn test_parkinson_volatility() {
-    let data = create_ohlc_data();
+fn test_ewma_volatility() {
+    let returns = calculate_returns(&create_test_prices());
 
-    // Test Parkinson volatility
-    let vol = parkinson_volatility(&data).unwrap();
+    // Test with lambda = 0.94 (common value)
+    let vol = ewma_volatility(&returns, 0.94);
 
     // Should return volatility for each point
-    assert_eq!(vol.len(), data.len());
+    assert_eq!(vol.len(), returns.len());
+    assert!(vol.iter().all(|v| *v > 0.0));
 
-    // Volatility should be positive
-    for v in vol {
-        assert!(v > 0.0);
-    }
+    // Volatility should be increasing (our test data has increasing volatility)
+    assert!(vol[vol.len() - 1] > vol[1]);
 
-    // Test with invalid data (no high-low)
-    let regular_data = create_test_data();
-    let result = parkinson_volatility(&regular_data);
-    assert!(result.is_err());
+    assert!(ewma_volatility(&[], 0.94).is_empty());
 }
 
 #[test]
 fn test_volatility_forecast() {
-    let data = create_test_data();
-
-    // Create initial volatility estimates
-    let hist_vol = historical_volatility(&data, 5).unwrap();
+    let prices = create_long_prices();
 
     // Use GARCH to forecast future volatility
-    let forecast =
-        forecast_trade::volatility::forecast_volatility(&data, &hist_vol, 3, 0.01, 0.1, 0.89)
-            .unwrap();
+    let forecast = forecast_volatility(&prices, 3).unwrap();
 
     // Should return forecast for requested horizon
     assert_eq!(forecast.len(), 3);
 
     // Forecasted volatility should be positive
     for v in forecast {
-        assert!(v > 0.0);
+        assert!(v > 0.0 && v.is_finite());
     }
+
+    // Too few prices to fit the model
+    assert!(forecast_volatility(&create_test_prices()[..3], 3).is_err());
+}
+
+#[test]
+fn test_annualize_and_target_position() {
+    // Daily variance of 0.0001 is 1% a day
+    let annual = annualize_daily_volatility(0.0001);
+    assert!((annual - 0.01 * 252_f64.sqrt()).abs() < 1e-12);
+
+    // Half the target volatility doubles the exposure, up to the leverage cap
+    let units = volatility_target_position(10000.0, 50.0, 0.01, 0.02, 3.0).unwrap();
+    assert!((units - 400.0).abs() < 1e-9);
+    let capped = volatility_target_position(10000.0, 50.0, 0.001, 0.02, 3.0).unwrap();
+    assert!((capped - 600.0).abs() < 1e-9);
+
+    assert!(volatility_target_position(10000.0, 0.0, 0.01, 0.02, 3.0).is_err());
+    assert!(volatility_target_position(10000.0, 50.0, 0.0, 0.02, 3.0).is_err());
 }
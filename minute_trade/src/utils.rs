@@ -5,6 +5,8 @@
 
 use crate::{MinuteOhlcv, OhlcvData, PerformanceMetrics, Signal, Trade, TradeError};
 use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Timelike, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::Path;
@@ -92,8 +94,19 @@ pub fn load_minute_data<P: AsRef<Path>>(file_path: P) -> Result<Vec<MinuteOhlcv>
     Ok(data)
 }
 
+/// Seed used by the generators that do not take an explicit RNG
+pub const DEFAULT_SEED: u64 = 42;
+
+/// Create a deterministic RNG from a seed
+pub fn seeded_rng(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
+}
+
 /// Generate synthetic minute-level data for testing strategies
 ///
+/// Uses `DEFAULT_SEED`, so repeated calls return the same series. Use
+/// `generate_minute_data_with_rng` to vary or control the randomness.
+///
 /// # Arguments
 /// * `days` - Number of trading days to generate
 /// * `points_per_day` - Number of data points per day (e.g., 390 for a 6.5 hour trading day)
@@ -110,9 +123,25 @@ pub fn generate_minute_data(
     volatility: f64,
     trend: f64,
 ) -> Vec<MinuteOhlcv> {
-    use rand::{thread_rng, Rng};
+    generate_minute_data_with_rng(
+        days,
+        points_per_day,
+        base_price,
+        volatility,
+        trend,
+        &mut seeded_rng(DEFAULT_SEED),
+    )
+}
 
-    let mut random = thread_rng();
+/// Generate synthetic minute-level data using the given random number generator
+pub fn generate_minute_data_with_rng<R: Rng + ?Sized>(
+    days: usize,
+    points_per_day: usize,
+    base_price: f64,
+    volatility: f64,
+    trend: f64,
+    random: &mut R,
+) -> Vec<MinuteOhlcv> {
     let mut data = Vec::with_capacity(days * points_per_day);
     let mut current_price = base_price;
 
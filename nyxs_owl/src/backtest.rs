@@ -0,0 +1,286 @@
+//! Backtests of daily strategies with summary statistics and a trade list
+//!
+//! Signals are replayed through `day_trade`'s ledger: all-in on Buy, all-out
+//! on Sell, executed at the next bar's open. A position still open at the end
+//! is valued at the last close and listed as an open trade.
+
+use crate::error::{OwlError, Result};
+use chrono::NaiveDate;
+use day_trade::corporate_actions::CorporateActions;
+use day_trade::ledger::{Ledger, LedgerEvent, TradingCosts};
+use day_trade::monte_carlo::{max_drawdown, sharpe_ratio};
+use day_trade::{DailyOhlcv, Signal, TradingStrategy};
+use serde::Serialize;
+
+/// Trading days per year used to annualise the Sharpe ratio
+const PERIODS_PER_YEAR: f64 = 252.0;
+
+/// One round trip
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TradeRecord {
+    /// Entry date
+    pub entry_date: NaiveDate,
+    /// Entry price
+    pub entry_price: f64,
+    /// Exit date, or the last bar's date for an open trade
+    pub exit_date: NaiveDate,
+    /// Exit price, or the last close for an open trade
+    pub exit_price: f64,
+    /// Return of the trade in percent
+    pub return_pct: f64,
+    /// Whether the position was still open at the end of the data
+    pub open: bool,
+}
+
+/// Headline statistics of a backtest
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BacktestSummary {
+    /// Strategy label
+    pub strategy: String,
+    /// Number of bars
+    pub bars: usize,
+    /// First bar date
+    pub start: NaiveDate,
+    /// Last bar date
+    pub end: NaiveDate,
+    /// Starting cash
+    pub initial_cash: f64,
+    /// Final portfolio value
+    pub final_value: f64,
+    /// Total return in percent
+    pub total_return: f64,
+    /// Commissions, fees and slippage paid
+    pub costs_paid: f64,
+    /// Return of holding from the first open to the last close, in percent
+    pub buy_and_hold_return: f64,
+    /// Largest peak-to-trough decline of the equity curve, in percent
+    pub max_drawdown: f64,
+    /// Annualised Sharpe ratio of daily equity returns
+    pub sharpe_ratio: f64,
+    /// Number of round trips, including an open one
+    pub trades: usize,
+    /// Share of closed trades with a positive return, in percent
+    pub win_rate: f64,
+}
+
+/// Full result of a backtest
+#[derive(Debug, Clone, Serialize)]
+pub struct Backtest {
+    /// Headline statistics
+    pub summary: BacktestSummary,
+    /// Bar dates
+    pub dates: Vec<NaiveDate>,
+    /// Signal of every bar
+    #[serde(skip)]
+    pub signals: Vec<Signal>,
+    /// Portfolio value after every bar
+    pub equity: Vec<f64>,
+    /// Round trips in order
+    pub trades: Vec<TradeRecord>,
+}
+
+impl Backtest {
+    /// Render the summary and trade list as Markdown
+    pub fn to_markdown(&self) -> String {
+        let s = &self.summary;
+        let mut out = format!(
+            "# Backtest: {}\n\n{} to {}, {} bars.\n\n",
+            s.strategy, s.start, s.end, s.bars
+        );
+        out.push_str("| Metric | Value |\n|---|---:|\n");
+        for (metric, value) in [
+            ("Initial cash", format!("{:.2}", s.initial_cash)),
+            ("Final value", format!("{:.2}", s.final_value)),
+            ("Total return", format!("{:+.2}%", s.total_return)),
+            ("Costs paid", format!("{:.2}", s.costs_paid)),
+            ("Buy and hold", format!("{:+.2}%", s.buy_and_hold_return)),
+            ("Max drawdown", format!("{:.2}%", s.max_drawdown)),
+            ("Sharpe ratio", format!("{:.2}", s.sharpe_ratio)),
+            ("Trades", s.trades.to_string()),
+            ("Win rate", format!("{:.1}%", s.win_rate)),
+        ] {
+            out.push_str(&format!("| {} | {} |\n", metric, value));
+        }
+
+        out.push_str("\n## Trades\n\n");
+        if self.trades.is_empty() {
+            out.push_str("No trades.\n");
+            return out;
+        }
+        out.push_str("| # | Entry | Entry price | Exit | Exit price | Return |\n");
+        out.push_str("|---:|---|---:|---|---:|---:|\n");
+        for (i, t) in self.trades.iter().enumerate() {
+            out.push_str(&format!(
+                "| {} | {} | {:.2} | {}{} | {:.2} | {:+.2}% |\n",
+                i + 1,
+                t.entry_date,
+                t.entry_price,
+                t.exit_date,
+                if t.open { " (open)" } else { "" },
+                t.exit_price,
+                t.return_pct
+            ));
+        }
+        out
+    }
+}
+
+/// Run `strategy` over `data` starting with `initial_cash`, without costs
+pub fn run_backtest(
+    label: &str,
+    strategy: &dyn TradingStrategy,
+    data: &[DailyOhlcv],
+    initial_cash: f64,
+) -> Result<Backtest> {
+    run_backtest_with_costs(
+        label,
+        strategy,
+        data,
+        initial_cash,
+        &TradingCosts::default(),
+    )
+}
+
+/// Run `strategy` over `data`, charging `costs` on every fill
+pub fn run_backtest_with_costs(
+    label: &str,
+    strategy: &dyn TradingStrategy,
+    data: &[DailyOhlcv],
+    initial_cash: f64,
+    costs: &TradingCosts,
+) -> Result<Backtest> {
+    let signals = strategy.generate_signals(data)?;
+    backtest_signals(label, data, signals, initial_cash, costs)
+}
+
+/// Backtest precomputed `signals` over `data`
+pub fn backtest_signals(
+    label: &str,
+    data: &[DailyOhlcv],
+    signals: Vec<Signal>,
+    initial_cash: f64,
+    costs: &TradingCosts,
+) -> Result<Backtest> {
+    let ledger = Ledger::run_with_costs(
+        "",
+        data,
+        &signals,
+        initial_cash,
+        &CorporateActions::default(),
+        costs,
+    )?;
+    let (first, last) = match (data.first(), data.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return Err(OwlError::DataLoad("No bars to backtest".to_string())),
+    };
+
+    let mut trades = Vec::new();
+    let mut entry: Option<(NaiveDate, f64)> = None;
+    for event in ledger.entries() {
+        match (event.event, entry) {
+            (LedgerEvent::Buy, None) => entry = Some((event.date, event.price)),
+            (LedgerEvent::Sell, Some((date, price))) => {
+                trades.push(TradeRecord {
+                    entry_date: date,
+                    entry_price: price,
+                    exit_date: event.date,
+                    exit_price: event.price,
+                    return_pct: (event.price / price - 1.0) * 100.0,
+                    open: false,
+                });
+                entry = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some((date, price)) = entry {
+        trades.push(TradeRecord {
+            entry_date: date,
+            entry_price: price,
+            exit_date: last.date,
+            exit_price: last.data.close,
+            return_pct: (last.data.close / price - 1.0) * 100.0,
+            open: true,
+        });
+    }
+
+    let closed: Vec<&TradeRecord> = trades.iter().filter(|t| !t.open).collect();
+    let win_rate = if closed.is_empty() {
+        0.0
+    } else {
+        closed.iter().filter(|t| t.return_pct > 0.0).count() as f64 / closed.len() as f64 * 100.0
+    };
+    let equity = ledger.equity_curve().to_vec();
+    let returns: Vec<f64> = equity.windows(2).map(|w| w[1] / w[0] - 1.0).collect();
+
+    Ok(Backtest {
+        summary: BacktestSummary {
+            strategy: label.to_string(),
+            bars: data.len(),
+            start: first.date,
+            end: last.date,
+            initial_cash,
+            final_value: ledger.final_value(),
+            total_return: ledger.total_return(),
+            costs_paid: ledger.costs_paid(),
+            buy_and_hold_return: (last.data.close / first.data.open - 1.0) * 100.0,
+            max_drawdown: max_drawdown(&equity),
+            sharpe_ratio: sharpe_ratio(&returns, PERIODS_PER_YEAR),
+            trades: trades.len(),
+            win_rate,
+        },
+        dates: data.iter().map(|d| d.date).collect(),
+        signals,
+        equity,
+        trades,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use day_trade::utils::generate_test_data;
+
+    #[test]
+    fn test_trades_match_signals() {
+        let data = generate_test_data(10, 100.0, 0.02);
+        let mut signals = vec![Signal::Hold; 10];
+        signals[1] = Signal::Buy;
+        signals[4] = Signal::Sell;
+        signals[6] = Signal::Buy;
+
+        let result =
+            backtest_signals("manual", &data, signals, 1000.0, &TradingCosts::default()).unwrap();
+        assert_eq!(result.trades.len(), 2);
+        let first = &result.trades[0];
+        assert_eq!(first.entry_date, data[2].date);
+        assert_eq!(first.entry_price, data[2].data.open);
+        assert_eq!(first.exit_price, data[5].data.open);
+        assert!(!first.open);
+        assert!(result.trades[1].open);
+        assert_eq!(result.trades[1].exit_price, data[9].data.close);
+
+        let summary = &result.summary;
+        assert_eq!(summary.trades, 2);
+        assert!(summary.win_rate == 0.0 || summary.win_rate == 100.0);
+        assert_eq!(result.equity.len(), data.len());
+        let expected =
+            1000.0 * (1.0 + first.return_pct / 100.0) * (1.0 + result.trades[1].return_pct / 100.0);
+        assert!((summary.final_value - expected).abs() < 1e-6);
+
+        let markdown = result.to_markdown();
+        assert!(markdown.starts_with("# Backtest: manual"));
+        assert!(markdown.contains("| 2 | "));
+        assert!(markdown.contains("(open)"));
+    }
+
+    #[test]
+    fn test_run_backtest_with_strategy() {
+        let data = generate_test_data(200, 100.0, 0.02);
+        let result =
+            run_backtest("MACD", &day_trade::MacdStrategy::default(), &data, 10000.0).unwrap();
+        assert_eq!(result.summary.bars, 200);
+        assert!(result.summary.max_drawdown >= 0.0);
+        assert!(result.summary.sharpe_ratio.is_finite());
+    }
+}
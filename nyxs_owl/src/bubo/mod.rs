@@ -0,0 +1,465 @@
+//! Bubo, the nightly scan
+//!
+//! After the close Bubo loads the latest bars of every symbol on a
+//! watchlist, asks each configured signal source for its opinion, combines
+//! the opinions into a weighted score and ranks the symbols. The strongest
+//! become candidates in the dawn report, each with an entry at the last
+//! close, a stop a multiple of the average true range away and a target at
+//! a fixed reward-to-risk ratio.
+//!
+//! A symbol whose data cannot be loaded, or on which every source fails, is
+//! listed as skipped rather than aborting the night's scan.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use nyxs_owl::bubo::{Bubo, DailyStrategySource, ForecastSource};
+//! use nyxs_owl::data::{MarketDataStore, Watchlist};
+//! use forecast_trade::models::ets::{EtsModel, ErrorType, SeasonalType, TrendType};
+//!
+//! let watchlist = Watchlist::from_file("watchlist.txt").unwrap();
+//! let model = EtsModel::new(ErrorType::Additive, TrendType::Damped, SeasonalType::None, 1).unwrap();
+//! let bubo = Bubo::new(MarketDataStore::new("data"))
+//!     .with_source(DailyStrategySource::new("MACD", day_trade::MacdStrategy::default()), 1.0)
+//!     .unwrap()
+//!     .with_source(ForecastSource::new(model, 5).unwrap(), 2.0)
+//!     .unwrap();
+//!
+//! let report = bubo.run(&watchlist).unwrap();
+//! println!("{}", report.to_markdown());
+//! ```
+
+mod report;
+mod sources;
+
+pub use report::{Candidate, DawnReport, Direction, SkippedSymbol, TradeLevels};
+pub use sources::{
+    DailyStrategySource, ForecastSource, IntradayStrategySource, Opinion, SignalSource,
+};
+
+use crate::data::{MarketDataStore, SymbolData, Watchlist};
+use crate::error::{OwlError, Result};
+use trade_math::volatility::AverageTrueRange;
+
+/// Settings of the nightly scan
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScanConfig {
+    lookback: usize,
+    min_history: usize,
+    atr_period: usize,
+    stop_atr_multiple: f64,
+    reward_risk: f64,
+    min_score: f64,
+    max_candidates: usize,
+    allow_short: bool,
+}
+
+impl Default for ScanConfig {
+    /// A year of bars, 2 ATR(14) stops, 2:1 targets, the top 10 long
+    /// candidates with a score of at least 0.25
+    fn default() -> Self {
+        Self {
+            lookback: 250,
+            min_history: 60,
+            atr_period: 14,
+            stop_atr_multiple: 2.0,
+            reward_risk: 2.0,
+            min_score: 0.25,
+            max_candidates: 10,
+            allow_short: false,
+        }
+    }
+}
+
+impl ScanConfig {
+    /// Load at most `lookback` daily bars per symbol and skip symbols with
+    /// fewer than `min_history`
+    pub fn with_history(mut self, lookback: usize, min_history: usize) -> Result<Self> {
+        if min_history < 2 || min_history > lookback {
+            return Err(OwlError::InvalidConfig(
+                "Minimum history must be between 2 and the lookback".to_string(),
+            ));
+        }
+        self.lookback = lookback;
+        self.min_history = min_history;
+        Ok(self)
+    }
+
+    /// Place stops `multiple` ATR(`period`) from the entry
+    pub fn with_stop(mut self, period: usize, multiple: f64) -> Result<Self> {
+        if period == 0 || !multiple.is_finite() || multiple <= 0.0 {
+            return Err(OwlError::InvalidConfig(
+                "ATR period and stop multiple must be positive".to_string(),
+            ));
+        }
+        self.atr_period = period;
+        self.stop_atr_multiple = multiple;
+        Ok(self)
+    }
+
+    /// Place targets `ratio` times the stop distance from the entry
+    pub fn with_reward_risk(mut self, ratio: f64) -> Result<Self> {
+        if !ratio.is_finite() || ratio <= 0.0 {
+            return Err(OwlError::InvalidConfig(
+                "Reward-to-risk ratio must be positive".to_string(),
+            ));
+        }
+        self.reward_risk = ratio;
+        Ok(self)
+    }
+
+    /// Minimum absolute score for a candidate, between 0 and 1
+    pub fn with_min_score(mut self, score: f64) -> Result<Self> {
+        if !(0.0..=1.0).contains(&score) {
+            return Err(OwlError::InvalidConfig(
+                "Minimum score must be between 0 and 1".to_string(),
+            ));
+        }
+        self.min_score = score;
+        Ok(self)
+    }
+
+    /// Keep at most `count` candidates
+    pub fn with_max_candidates(mut self, count: usize) -> Self {
+        self.max_candidates = count;
+        self
+    }
+
+    /// Whether bearish symbols become short candidates (default false)
+    pub fn with_short_candidates(mut self, allow: bool) -> Self {
+        self.allow_short = allow;
+        self
+    }
+
+    /// Daily bars loaded per symbol
+    pub fn lookback(&self) -> usize {
+        self.lookback
+    }
+}
+
+/// The nightly scan pipeline
+pub struct Bubo {
+    store: MarketDataStore,
+    config: ScanConfig,
+    sources: Vec<(Box<dyn SignalSource>, f64)>,
+}
+
+impl Bubo {
+    /// Create a pipeline reading from `store` with the default settings and
+    /// no sources
+    pub fn new(store: MarketDataStore) -> Self {
+        Self {
+            store,
+            config: ScanConfig::default(),
+            sources: Vec::new(),
+        }
+    }
+
+    /// Replace the scan settings
+    pub fn with_config(mut self, config: ScanConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Consult `source` for every symbol, weighting its bias by `weight`
+    pub fn with_source(mut self, source: impl SignalSource + 'static, weight: f64) -> Result<Self> {
+        if !weight.is_finite() || weight <= 0.0 {
+            return Err(OwlError::InvalidConfig(format!(
+                "Weight of {} must be positive",
+                source.name()
+            )));
+        }
+        self.sources.push((Box::new(source), weight));
+        Ok(self)
+    }
+
+    /// Scan settings
+    pub fn config(&self) -> &ScanConfig {
+        &self.config
+    }
+
+    /// Names of the configured sources
+    pub fn source_names(&self) -> Vec<&str> {
+        self.sources.iter().map(|(s, _)| s.name()).collect()
+    }
+
+    /// Scan the watchlist and rank the candidates
+    pub fn run(&self, watchlist: &Watchlist) -> Result<DawnReport> {
+        if self.sources.is_empty() {
+            return Err(OwlError::InvalidConfig(
+                "Bubo needs at least one signal source".to_string(),
+            ));
+        }
+
+        let mut candidates = Vec::new();
+        let mut skipped = Vec::new();
+        let mut as_of = None;
+        for symbol in watchlist.symbols() {
+            let outcome = self
+                .store
+                .load(symbol, self.config.lookback)
+                .and_then(|data| {
+                    let date = data.last_bar().timestamp.date_naive();
+                    as_of = as_of.max(Some(date));
+                    self.evaluate(&data)
+                });
+            match outcome {
+                Ok(Some(candidate)) => candidates.push(candidate),
+                Ok(None) => {}
+                Err(e) => skipped.push(SkippedSymbol {
+                    symbol: symbol.clone(),
+                    reason: e.to_string(),
+                }),
+            }
+        }
+
+        candidates.sort_by(|a, b| {
+            b.score
+                .abs()
+                .total_cmp(&a.score.abs())
+                .then_with(|| a.symbol.cmp(&b.symbol))
+        });
+        candidates.truncate(self.config.max_candidates);
+
+        Ok(DawnReport {
+            as_of,
+            scanned: watchlist.len(),
+            candidates,
+            skipped,
+        })
+    }
+
+    /// Evaluate one symbol; `Ok(None)` when its score is too weak
+    pub fn evaluate(&self, data: &SymbolData) -> Result<Option<Candidate>> {
+        if data.daily().len() < self.config.min_history {
+            return Err(OwlError::DataLoad(format!(
+                "Only {} daily bars, need {}",
+                data.daily().len(),
+                self.config.min_history
+            )));
+        }
+
+        let mut opinions = Vec::new();
+        let mut warnings = Vec::new();
+        let mut weighted = 0.0;
+        let mut total_weight = 0.0;
+        for (source, weight) in &self.sources {
+            match source.evaluate(data) {
+                Ok(Some(opinion)) => {
+                    weighted += weight * opinion.bias.clamp(-1.0, 1.0);
+                    total_weight += weight;
+                    opinions.push(opinion);
+                }
+                Ok(None) => {}
+                Err(e) => warnings.push(format!("{} failed: {}", source.name(), e)),
+            }
+        }
+        if opinions.is_empty() {
+            return Err(OwlError::Strategy(if warnings.is_empty() {
+                "No source applies to this symbol".to_string()
+            } else {
+                warnings.join("; ")
+            }));
+        }
+
+        let score = weighted / total_weight;
+        let direction = if score >= self.config.min_score {
+            Direction::Long
+        } else if self.config.allow_short && score <= -self.config.min_score {
+            Direction::Short
+        } else {
+            return Ok(None);
+        };
+
+        let atr = self.average_true_range(data)?;
+        let last = data.last_bar();
+        Ok(Some(Candidate {
+            symbol: data.symbol().to_string(),
+            direction,
+            score,
+            as_of: last.timestamp.date_naive(),
+            last_close: last.close,
+            atr,
+            levels: TradeLevels::new(
+                direction,
+                last.close,
+                self.config.stop_atr_multiple * atr,
+                self.config.reward_risk,
+            ),
+            opinions,
+            warnings,
+        }))
+    }
+
+    fn average_true_range(&self, data: &SymbolData) -> Result<f64> {
+        let mut atr = AverageTrueRange::new(self.config.atr_period)
+            .map_err(|e| OwlError::InvalidConfig(e.to_string()))?;
+        for bar in data.daily() {
+            atr.update(bar.high, bar.low, bar.close)
+                .map_err(|e| OwlError::DataLoad(e.to_string()))?;
+        }
+        atr.value().map_err(|e| OwlError::DataLoad(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Bar;
+    use chrono::{Duration, TimeZone, Utc};
+    use forecast_trade::models::ets::{ErrorType, EtsModel, SeasonalType, TrendType};
+    use std::path::Path;
+
+    /// Source reporting a fixed bias per symbol
+    struct FixedBias(Vec<(&'static str, f64)>);
+
+    impl SignalSource for FixedBias {
+        fn name(&self) -> &str {
+            "Fixed"
+        }
+
+        fn evaluate(&self, data: &SymbolData) -> Result<Option<Opinion>> {
+            Ok(self
+                .0
+                .iter()
+                .find(|(s, _)| *s == data.symbol())
+                .map(|(_, bias)| Opinion {
+                    source: "Fixed".to_string(),
+                    bias: *bias,
+                    detail: String::new(),
+                }))
+        }
+    }
+
+    fn write_symbol(dir: &Path, symbol: &str, drift: f64) {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let mut csv = String::from("date,open,high,low,close,volume\n");
+        for i in 0..120 {
+            // A 30-bar move against the drift, then the trend itself
+            let steps = if i < 30 { -i } else { i - 60 };
+            let close = 100.0 * (1.0 + drift).powi(steps) + (i as f64 * 0.7).sin();
+            let t = (start + Duration::days(i as i64)).format("%Y-%m-%d");
+            csv.push_str(&format!(
+                "{},{},{},{},{},1000\n",
+                t,
+                close,
+                close + 1.0,
+                close - 1.0,
+                close
+            ));
+        }
+        std::fs::write(dir.join(format!("{}_daily_ohlcv.csv", symbol)), csv).unwrap();
+    }
+
+    fn scan_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("bubo_{}_{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        write_symbol(&dir, "UP", 0.01);
+        write_symbol(&dir, "DOWN", -0.005);
+        write_symbol(&dir, "FLAT", 0.0);
+        dir
+    }
+
+    #[test]
+    fn test_ranking_and_levels() {
+        let dir = scan_dir("ranking");
+        let watchlist = Watchlist::new(["UP", "DOWN", "FLAT", "MISSING"]).unwrap();
+        let source = FixedBias(vec![("UP", 0.8), ("DOWN", -0.9), ("FLAT", 0.1)]);
+        let bubo = Bubo::new(MarketDataStore::new(&dir))
+            .with_source(source, 1.0)
+            .unwrap();
+
+        let report = bubo.run(&watchlist).unwrap();
+        assert_eq!(report.scanned, 4);
+        assert_eq!(report.candidates.len(), 1);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.skipped[0].symbol, "MISSING");
+
+        let up = report.candidate("UP").unwrap();
+        assert_eq!(up.direction, Direction::Long);
+        assert!((up.levels.entry - up.last_close).abs() < 1e-12);
+        assert!((up.levels.entry - up.levels.stop - 2.0 * up.atr).abs() < 1e-9);
+        assert!((up.levels.reward_risk() - 2.0).abs() < 1e-9);
+        assert_eq!(report.as_of, Some(up.as_of));
+
+        let bubo = bubo.with_config(ScanConfig::default().with_short_candidates(true));
+        let report = bubo.run(&watchlist).unwrap();
+        let symbols: Vec<&str> = report
+            .candidates
+            .iter()
+            .map(|c| c.symbol.as_str())
+            .collect();
+        assert_eq!(symbols, ["DOWN", "UP"]);
+        let down = &report.candidates[0];
+        assert_eq!(down.direction, Direction::Short);
+        assert!(down.levels.stop > down.levels.entry && down.levels.target < down.levels.entry);
+        assert!(report.to_markdown().contains("| 1 | DOWN | Short |"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_strategy_and_forecast_sources() {
+        let dir = scan_dir("sources");
+        let model = EtsModel::new(
+            ErrorType::Additive,
+            TrendType::Additive,
+            SeasonalType::None,
+            1,
+        )
+        .unwrap();
+        let bubo = Bubo::new(MarketDataStore::new(&dir))
+            .with_source(
+                DailyStrategySource::new("MA crossover", day_trade::MACrossover::new(5, 20))
+                    .with_recency(120)
+                    .unwrap(),
+                1.0,
+            )
+            .unwrap()
+            .with_source(ForecastSource::new(model, 5).unwrap(), 1.0)
+            .unwrap()
+            .with_source(
+                IntradayStrategySource::new(minute_trade::ScalpingStrategy::new(5, 0.1).unwrap()),
+                1.0,
+            )
+            .unwrap();
+        assert_eq!(bubo.source_names().len(), 3);
+
+        let report = bubo.run(&Watchlist::new(["UP", "DOWN"]).unwrap()).unwrap();
+        assert!(report.skipped.is_empty());
+        let up = report.candidate("UP").unwrap();
+        // No minute file, so the intraday source stays silent
+        assert_eq!(up.opinions.len(), 2);
+        assert!(up.opinions.iter().all(|o| o.bias > 0.0));
+        assert!(report.candidate("DOWN").is_none());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_validation() {
+        let bubo = Bubo::new(MarketDataStore::new("."));
+        assert!(bubo.run(&Watchlist::new(["AAPL"]).unwrap()).is_err());
+        assert!(Bubo::new(MarketDataStore::new("."))
+            .with_source(FixedBias(vec![]), 0.0)
+            .is_err());
+        assert!(ScanConfig::default().with_history(50, 100).is_err());
+        assert!(ScanConfig::default().with_min_score(1.5).is_err());
+
+        let short = SymbolData::new(
+            "X",
+            vec![Bar {
+                timestamp: Utc::now(),
+                open: 1.0,
+                high: 1.0,
+                low: 1.0,
+                close: 1.0,
+                volume: 0.0,
+            }],
+            None,
+        )
+        .unwrap();
+        let bubo = bubo.with_source(FixedBias(vec![("X", 1.0)]), 1.0).unwrap();
+        assert!(bubo.evaluate(&short).is_err());
+    }
+}
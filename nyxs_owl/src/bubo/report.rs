@@ -0,0 +1,215 @@
+//! The dawn report: ranked candidates with trade levels
+
+use super::sources::Opinion;
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::fmt;
+
+/// Side of a proposed trade
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Direction {
+    /// Buy, expecting a rise
+    Long,
+    /// Sell short, expecting a fall
+    Short,
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Direction::Long => write!(f, "Long"),
+            Direction::Short => write!(f, "Short"),
+        }
+    }
+}
+
+/// Entry, protective stop and profit target of a proposed trade
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct TradeLevels {
+    /// Entry price
+    pub entry: f64,
+    /// Stop-loss price
+    pub stop: f64,
+    /// Profit target price
+    pub target: f64,
+}
+
+impl TradeLevels {
+    /// Levels `stop_distance` away from `entry` for the stop and
+    /// `reward_risk` times that for the target
+    pub fn new(direction: Direction, entry: f64, stop_distance: f64, reward_risk: f64) -> Self {
+        let sign = match direction {
+            Direction::Long => 1.0,
+            Direction::Short => -1.0,
+        };
+        Self {
+            entry,
+            stop: entry - sign * stop_distance,
+            target: entry + sign * stop_distance * reward_risk,
+        }
+    }
+
+    /// Reward per unit of risk
+    pub fn reward_risk(&self) -> f64 {
+        let risk = (self.entry - self.stop).abs();
+        if risk > 0.0 {
+            (self.target - self.entry).abs() / risk
+        } else {
+            0.0
+        }
+    }
+
+    /// Risk as a fraction of the entry price
+    pub fn risk_fraction(&self) -> f64 {
+        (self.entry - self.stop).abs() / self.entry
+    }
+}
+
+/// A symbol worth attention at the open
+#[derive(Debug, Clone, Serialize)]
+pub struct Candidate {
+    /// Symbol
+    pub symbol: String,
+    /// Proposed side
+    pub direction: Direction,
+    /// Weighted consensus bias of the sources, from -1 to +1
+    pub score: f64,
+    /// Date of the last bar
+    pub as_of: NaiveDate,
+    /// Last close
+    pub last_close: f64,
+    /// Average true range used to place the stop
+    pub atr: f64,
+    /// Proposed levels
+    pub levels: TradeLevels,
+    /// What every source said
+    pub opinions: Vec<Opinion>,
+    /// Sources that failed on this symbol
+    pub warnings: Vec<String>,
+}
+
+/// A symbol left out of the scan and why
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SkippedSymbol {
+    /// Symbol
+    pub symbol: String,
+    /// Reason it was skipped
+    pub reason: String,
+}
+
+/// Bubo's findings for the coming session
+#[derive(Debug, Clone, Serialize)]
+pub struct DawnReport {
+    /// Latest bar date across the scanned symbols
+    pub as_of: Option<NaiveDate>,
+    /// Number of symbols on the watchlist
+    pub scanned: usize,
+    /// Candidates, best first
+    pub candidates: Vec<Candidate>,
+    /// Symbols that could not be evaluated
+    pub skipped: Vec<SkippedSymbol>,
+}
+
+impl DawnReport {
+    /// Candidate for `symbol`, if it made the list
+    pub fn candidate(&self, symbol: &str) -> Option<&Candidate> {
+        self.candidates.iter().find(|c| c.symbol == symbol)
+    }
+
+    /// Render the report as Markdown
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        match self.as_of {
+            Some(date) => out.push_str(&format!("# Dawn report for data through {}\n\n", date)),
+            None => out.push_str("# Dawn report\n\n"),
+        }
+        out.push_str(&format!(
+            "Scanned {} symbols: {} candidates, {} skipped.\n\n",
+            self.scanned,
+            self.candidates.len(),
+            self.skipped.len()
+        ));
+
+        if !self.candidates.is_empty() {
+            out.push_str(
+                "| Rank | Symbol | Side | Score | Close | Entry | Stop | Target | R:R |\n",
+            );
+            out.push_str("|---:|---|---|---:|---:|---:|---:|---:|---:|\n");
+            for (rank, c) in self.candidates.iter().enumerate() {
+                out.push_str(&format!(
+                    "| {} | {} | {} | {:+.2} | {:.2} | {:.2} | {:.2} | {:.2} | {:.1} |\n",
+                    rank + 1,
+                    c.symbol,
+                    c.direction,
+                    c.score,
+                    c.last_close,
+                    c.levels.entry,
+                    c.levels.stop,
+                    c.levels.target,
+                    c.levels.reward_risk()
+                ));
+            }
+
+            for c in &self.candidates {
+                out.push_str(&format!(
+                    "\n## {} ({}, as of {})\n\n",
+                    c.symbol, c.direction, c.as_of
+                ));
+                for opinion in &c.opinions {
+                    out.push_str(&format!(
+                        "- **{}** ({:+.2}): {}\n",
+                        opinion.source, opinion.bias, opinion.detail
+                    ));
+                }
+                for warning in &c.warnings {
+                    out.push_str(&format!("- _{}_\n", warning));
+                }
+            }
+        }
+
+        if !self.skipped.is_empty() {
+            out.push_str("\n## Skipped\n\n");
+            for s in &self.skipped {
+                out.push_str(&format!("- {}: {}\n", s.symbol, s.reason));
+            }
+        }
+        out
+    }
+}
+
+impl fmt::Display for DawnReport {
+    /// Plain-text summary table
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Dawn report{}: {} candidates from {} symbols",
+            self.as_of.map(|d| format!(" ({})", d)).unwrap_or_default(),
+            self.candidates.len(),
+            self.scanned
+        )?;
+        if !self.candidates.is_empty() {
+            writeln!(
+                f,
+                "{:<4} {:<8} {:<5} {:>6} {:>10} {:>10} {:>10}",
+                "#", "Symbol", "Side", "Score", "Entry", "Stop", "Target"
+            )?;
+        }
+        for (rank, c) in self.candidates.iter().enumerate() {
+            writeln!(
+                f,
+                "{:<4} {:<8} {:<5} {:>+6.2} {:>10.2} {:>10.2} {:>10.2}",
+                rank + 1,
+                c.symbol,
+                c.direction,
+                c.score,
+                c.levels.entry,
+                c.levels.stop,
+                c.levels.target
+            )?;
+        }
+        for s in &self.skipped {
+            writeln!(f, "skipped {}: {}", s.symbol, s.reason)?;
+        }
+        Ok(())
+    }
+}
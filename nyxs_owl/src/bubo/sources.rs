@@ -0,0 +1,289 @@
+//! Signal sources consulted for every symbol
+//!
+//! A source turns a symbol's bars into an `Opinion`: a bias from -1
+//! (bearish) to +1 (bullish) and a one-line explanation for the report.
+//! Adapters wrap `day_trade` daily strategies, `minute_trade` intraday
+//! strategies and `forecast_trade` models.
+
+use crate::data::SymbolData;
+use crate::error::{OwlError, Result};
+use forecast_trade::models::ensemble::DynForecastModel;
+use forecast_trade::ForecastModel;
+use serde::Serialize;
+
+/// A source's view on one symbol
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Opinion {
+    /// Name of the source
+    pub source: String,
+    /// Bias from -1 (bearish) to +1 (bullish)
+    pub bias: f64,
+    /// Short explanation
+    pub detail: String,
+}
+
+/// Something Bubo asks about each symbol
+pub trait SignalSource {
+    /// Name of the source
+    fn name(&self) -> &str;
+
+    /// Opinion on the symbol, or `None` when the source does not apply
+    /// (e.g. an intraday strategy for a symbol without minute data)
+    fn evaluate(&self, data: &SymbolData) -> Result<Option<Opinion>>;
+}
+
+/// Bias and description of the most recent actionable signal
+fn latest_signal<T: PartialEq>(signals: &[T], buy: &T, sell: &T, recency: usize) -> (f64, String) {
+    let recent = signals.len().saturating_sub(recency);
+    for (age, signal) in signals[recent..].iter().rev().enumerate() {
+        let (bias, label) = if signal == buy {
+            (1.0, "Buy")
+        } else if signal == sell {
+            (-1.0, "Sell")
+        } else {
+            continue;
+        };
+        let when = match age {
+            0 => "on the last bar".to_string(),
+            1 => "1 bar ago".to_string(),
+            n => format!("{} bars ago", n),
+        };
+        return (bias, format!("{} signal {}", label, when));
+    }
+    (0.0, format!("no signal in the last {} bars", recency))
+}
+
+/// A `day_trade` strategy on daily bars
+///
+/// The latest Buy or Sell within `recency` bars sets the bias; the
+/// strategy's backtest return over the loaded history is quoted alongside.
+pub struct DailyStrategySource<S> {
+    name: String,
+    strategy: S,
+    recency: usize,
+}
+
+impl<S: day_trade::TradingStrategy> DailyStrategySource<S> {
+    /// Wrap `strategy` under a display name
+    pub fn new(name: &str, strategy: S) -> Self {
+        Self {
+            name: name.to_string(),
+            strategy,
+            recency: 3,
+        }
+    }
+
+    /// Number of trailing bars in which a signal still counts (default 3)
+    pub fn with_recency(mut self, bars: usize) -> Result<Self> {
+        if bars == 0 {
+            return Err(OwlError::InvalidConfig(
+                "Signal recency must be at least one bar".to_string(),
+            ));
+        }
+        self.recency = bars;
+        Ok(self)
+    }
+}
+
+impl<S: day_trade::TradingStrategy> SignalSource for DailyStrategySource<S> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn evaluate(&self, data: &SymbolData) -> Result<Option<Opinion>> {
+        use day_trade::Signal;
+
+        let bars = data.daily_ohlcv();
+        let signals = self.strategy.generate_signals(&bars)?;
+        let performance = self.strategy.calculate_performance(&bars, &signals)?;
+        let (bias, detail) = latest_signal(&signals, &Signal::Buy, &Signal::Sell, self.recency);
+        Ok(Some(Opinion {
+            source: self.name.clone(),
+            bias,
+            detail: format!("{} (backtest {:+.1}%)", detail, performance),
+        }))
+    }
+}
+
+/// A `minute_trade` strategy on the latest session's minute bars
+pub struct IntradayStrategySource<S> {
+    strategy: S,
+    recency: usize,
+}
+
+impl<S: minute_trade::IntradayStrategy> IntradayStrategySource<S> {
+    /// Wrap `strategy`; it is skipped for symbols without minute data
+    pub fn new(strategy: S) -> Self {
+        Self {
+            strategy,
+            recency: 30,
+        }
+    }
+
+    /// Number of trailing minutes in which a signal still counts
+    /// (default 30)
+    pub fn with_recency(mut self, minutes: usize) -> Result<Self> {
+        if minutes == 0 {
+            return Err(OwlError::InvalidConfig(
+                "Signal recency must be at least one bar".to_string(),
+            ));
+        }
+        self.recency = minutes;
+        Ok(self)
+    }
+}
+
+impl<S: minute_trade::IntradayStrategy> SignalSource for IntradayStrategySource<S> {
+    fn name(&self) -> &str {
+        self.strategy.name()
+    }
+
+    fn evaluate(&self, data: &SymbolData) -> Result<Option<Opinion>> {
+        use minute_trade::Signal;
+
+        let Some(bars) = data.minute_ohlcv() else {
+            return Ok(None);
+        };
+        let signals = self.strategy.generate_signals(&bars)?;
+        let (bias, detail) = latest_signal(&signals, &Signal::Buy, &Signal::Sell, self.recency);
+        Ok(Some(Opinion {
+            source: self.strategy.name().to_string(),
+            bias,
+            detail: format!("{} in the last session", detail),
+        }))
+    }
+}
+
+/// A `forecast_trade` model fitted to daily closes
+///
+/// The bias is the forecast return over the horizon measured in units of
+/// the close-to-close volatility over the same horizon, squashed with
+/// `tanh`: a forecast one standard deviation up gives a bias of about 0.76.
+#[derive(Debug)]
+pub struct ForecastSource {
+    model: Box<dyn DynForecastModel>,
+    horizon: usize,
+    training_window: usize,
+}
+
+impl ForecastSource {
+    /// Forecast `horizon` bars ahead with `model`
+    pub fn new<M: ForecastModel + 'static>(model: M, horizon: usize) -> Result<Self> {
+        Self::boxed(Box::new(model), horizon)
+    }
+
+    /// Forecast `horizon` bars ahead with a model chosen at run time
+    pub fn boxed(model: Box<dyn DynForecastModel>, horizon: usize) -> Result<Self> {
+        if horizon == 0 {
+            return Err(OwlError::InvalidConfig(
+                "Forecast horizon must be at least one bar".to_string(),
+            ));
+        }
+        Ok(Self {
+            model,
+            horizon,
+            training_window: 250,
+        })
+    }
+
+    /// Fit on at most the last `bars` closes (default 250)
+    pub fn with_training_window(mut self, bars: usize) -> Result<Self> {
+        if bars < 10 {
+            return Err(OwlError::InvalidConfig(
+                "Forecast training window must be at least 10 bars".to_string(),
+            ));
+        }
+        self.training_window = bars;
+        Ok(self)
+    }
+}
+
+impl SignalSource for ForecastSource {
+    fn name(&self) -> &str {
+        self.model.name()
+    }
+
+    fn evaluate(&self, data: &SymbolData) -> Result<Option<Opinion>> {
+        let closes = data.closes();
+        let start = closes.len().saturating_sub(self.training_window);
+        let closes = &closes[start..];
+        if closes.len() < 10 {
+            return Err(OwlError::Forecast(format!(
+                "Need at least 10 closes to forecast, have {}",
+                closes.len()
+            )));
+        }
+
+        let fitted = self.model.fit_dyn(closes)?;
+        let forecast = fitted.forecast(self.horizon)?;
+        let last = closes[closes.len() - 1];
+        let predicted = forecast.values()[self.horizon - 1];
+        let expected = predicted / last - 1.0;
+
+        let returns: Vec<f64> = closes.windows(2).map(|w| w[1] / w[0] - 1.0).collect();
+        let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+        let variance =
+            returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / returns.len() as f64;
+        let scale = variance.sqrt() * (self.horizon as f64).sqrt();
+        // A flat series has no scale, so only the sign of the move counts
+        let bias = if scale > 0.0 {
+            (expected / scale).tanh()
+        } else if expected != 0.0 {
+            expected.signum()
+        } else {
+            0.0
+        };
+
+        Ok(Some(Opinion {
+            source: self.model.name().to_string(),
+            bias,
+            detail: format!(
+                "{}-bar forecast {:.2} ({:+.2}%)",
+                self.horizon,
+                predicted,
+                expected * 100.0
+            ),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latest_signal() {
+        use day_trade::Signal::{Buy, Hold, Sell};
+
+        let (bias, detail) = latest_signal(&[Sell, Buy, Hold], &Buy, &Sell, 3);
+        assert_eq!(bias, 1.0);
+        assert_eq!(detail, "Buy signal 1 bar ago");
+        assert_eq!(latest_signal(&[Sell, Hold, Hold], &Buy, &Sell, 3).0, -1.0);
+        assert_eq!(latest_signal(&[Sell, Hold, Hold], &Buy, &Sell, 2).0, 0.0);
+        assert_eq!(latest_signal(&[Hold], &Buy, &Sell, 5).0, 0.0);
+    }
+
+    #[test]
+    fn test_flat_forecast_has_no_bias() {
+        use crate::data::Bar;
+        use chrono::{Duration, TimeZone, Utc};
+        use forecast_trade::models::moving_average::MovingAverage;
+
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let bars = (0..30)
+            .map(|i| Bar {
+                timestamp: start + Duration::days(i),
+                open: 50.0,
+                high: 50.0,
+                low: 50.0,
+                close: 50.0,
+                volume: 1000.0,
+            })
+            .collect();
+        let data = SymbolData::new("FLAT", bars, None).unwrap();
+
+        let source = ForecastSource::new(MovingAverage::new(3).unwrap(), 5).unwrap();
+        let opinion = source.evaluate(&data).unwrap().unwrap();
+        assert_eq!(opinion.bias, 0.0);
+    }
+}
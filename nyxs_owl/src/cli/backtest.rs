@@ -0,0 +1,107 @@
+//! The `backtest` subcommand
+
+use super::common::{load_daily, StrategyArgs};
+use super::{Output, Table};
+use crate::backtest::{run_backtest_with_costs, BacktestSummary};
+use crate::error::{OwlError, Result};
+use clap::Args;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Arguments of `backtest`
+#[derive(Debug, Args)]
+pub struct BacktestArgs {
+    /// Daily OHLCV CSV files; defaults to the spec's data source
+    #[arg(long, short, num_args = 1..)]
+    pub data: Vec<PathBuf>,
+    /// Strategy to test
+    #[command(flatten)]
+    pub strategy: StrategyArgs,
+    /// Starting cash
+    #[arg(long, default_value_t = 10_000.0)]
+    pub cash: f64,
+}
+
+/// Backtest summary tagged with its symbol
+#[derive(Debug, Serialize)]
+struct SymbolSummary {
+    symbol: String,
+    #[serde(flatten)]
+    summary: BacktestSummary,
+}
+
+pub(super) fn backtest(args: &BacktestArgs) -> Result<Output> {
+    let spec = args.strategy.resolve()?;
+    let strategy = spec.build()?;
+    let costs = spec.costs.to_costs()?;
+    let datasets = if !args.data.is_empty() {
+        args.data
+            .iter()
+            .map(|path| load_daily(path))
+            .collect::<Result<Vec<_>>>()?
+    } else if let Some(source) = &spec.data {
+        source.load_daily()?
+    } else {
+        return Err(OwlError::InvalidConfig(
+            "Give --data files or a data source in the spec".to_string(),
+        ));
+    };
+
+    let mut table = Table::new([
+        "Symbol", "Strategy", "Bars", "Start", "End", "Return%", "B&H%", "MaxDD%", "Sharpe",
+        "Trades", "Win%",
+    ]);
+    let mut results = Vec::new();
+    for (symbol, data) in datasets {
+        let summary =
+            run_backtest_with_costs(&spec.label(), strategy.as_ref(), &data, args.cash, &costs)?
+                .summary;
+        table.push_row([
+            symbol.clone(),
+            summary.strategy.clone(),
+            summary.bars.to_string(),
+            summary.start.to_string(),
+            summary.end.to_string(),
+            format!("{:.2}", summary.total_return),
+            format!("{:.2}", summary.buy_and_hold_return),
+            format!("{:.2}", summary.max_drawdown),
+            format!("{:.2}", summary.sharpe_ratio),
+            summary.trades.to_string(),
+            format!("{:.1}", summary.win_rate),
+        ]);
+        results.push(SymbolSummary { symbol, summary });
+    }
+    Output::tabular(table, &results)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::tests::{run_args, temp_dir, write_series};
+    use std::fs;
+
+    #[test]
+    fn test_spec_files() {
+        let dir = temp_dir("spec");
+        write_series(&dir, "AAA", 200);
+        let spec = dir.join("swing.yaml");
+        fs::write(
+            &spec,
+            "name: swing\ntype: macd\nrisk:\n  stop_loss: 0.03\n\
+             costs:\n  commission: 0.001\ndata:\n  dir: .\n  symbols: [AAA]\n",
+        )
+        .unwrap();
+        let spec = spec.to_str().unwrap();
+
+        let json = run_args(&["--format", "json", "backtest", "--spec", spec]).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed[0]["symbol"], "AAA");
+        assert_eq!(parsed[0]["strategy"], "swing");
+        assert!(parsed[0]["costs_paid"].as_f64().unwrap() > 0.0);
+
+        let overridden =
+            run_args(&["backtest", "--spec", spec, "-p", "fast_period=30"]).unwrap_err();
+        assert!(overridden.to_string().contains("macd"));
+        assert!(run_args(&["backtest", "--spec", spec, "-s", "rsi"]).is_err());
+        fs::remove_dir_all(dir).ok();
+    }
+}
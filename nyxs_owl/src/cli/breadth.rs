@@ -0,0 +1,168 @@
+//! The `breadth` subcommand: market internals of a universe
+
+use super::common::watchlist_of;
+use super::{Output, Table};
+use crate::bubo::SkippedSymbol;
+use crate::data::MarketDataStore;
+use crate::error::Result;
+use clap::Args;
+use day_trade::breadth::{BreadthDay, MarketBreadth};
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Arguments of `breadth`
+#[derive(Debug, Args)]
+pub struct BreadthArgs {
+    /// Directory with `{SYMBOL}_daily_ohlcv.csv` files
+    #[arg(long)]
+    pub data_dir: PathBuf,
+    /// Watchlist file with one symbol per line
+    #[arg(long, conflicts_with = "symbols")]
+    pub watchlist: Option<PathBuf>,
+    /// Comma-separated symbols
+    #[arg(long, value_delimiter = ',')]
+    pub symbols: Vec<String>,
+    /// Bars a new high or low must exceed
+    #[arg(long, default_value_t = 252)]
+    pub high_low_period: usize,
+    /// Most recent days to list
+    #[arg(long, default_value_t = 20)]
+    pub days: usize,
+    /// Daily bars to load per symbol
+    #[arg(long, default_value_t = 500)]
+    pub lookback: usize,
+}
+
+/// Breadth internals of one date
+#[derive(Debug, Serialize)]
+struct BreadthRow {
+    #[serde(flatten)]
+    day: BreadthDay,
+    advance_decline_line: f64,
+    mcclellan_oscillator: f64,
+    mcclellan_summation: f64,
+    trin: Option<f64>,
+}
+
+/// Result of `breadth`
+#[derive(Debug, Serialize)]
+struct BreadthOutput {
+    issues: usize,
+    days: Vec<BreadthRow>,
+    skipped: Vec<SkippedSymbol>,
+}
+
+pub(super) fn breadth(args: &BreadthArgs) -> Result<Output> {
+    let watchlist = watchlist_of(&args.watchlist, &args.symbols)?;
+    let store = MarketDataStore::new(&args.data_dir);
+    let mut universe = Vec::new();
+    let mut skipped = Vec::new();
+    for symbol in watchlist.symbols() {
+        match store.load(symbol, args.lookback) {
+            Ok(data) => universe.push(data.daily_ohlcv()),
+            Err(e) => skipped.push(SkippedSymbol {
+                symbol: symbol.clone(),
+                reason: e.to_string(),
+            }),
+        }
+    }
+    let breadth =
+        MarketBreadth::from_universe(universe.iter().map(Vec::as_slice), args.high_low_period)?;
+
+    let line = breadth.advance_decline_line();
+    let oscillator = breadth.mcclellan_oscillator(false);
+    let summation = breadth.mcclellan_summation(false);
+    let start = breadth.len().saturating_sub(args.days);
+    let days: Vec<BreadthRow> = (start..breadth.len())
+        .map(|i| {
+            let day = breadth.days()[i];
+            BreadthRow {
+                day,
+                advance_decline_line: line[i].1,
+                mcclellan_oscillator: oscillator[i].1,
+                mcclellan_summation: summation[i].1,
+                trin: day.trin(),
+            }
+        })
+        .collect();
+
+    let mut table = Table::new([
+        "Date",
+        "Adv",
+        "Dec",
+        "A/D Line",
+        "McClellan",
+        "Highs",
+        "Lows",
+        "TRIN",
+        "Up/Down Vol",
+    ]);
+    for row in &days {
+        let ratio = |v: Option<f64>| v.map_or("-".to_string(), |v| format!("{:.2}", v));
+        table.push_row([
+            row.day.date.to_string(),
+            row.day.advancers.to_string(),
+            row.day.decliners.to_string(),
+            format!("{:.0}", row.advance_decline_line),
+            format!("{:+.1}", row.mcclellan_oscillator),
+            row.day.new_highs.to_string(),
+            row.day.new_lows.to_string(),
+            ratio(row.trin),
+            ratio(row.day.up_down_volume_ratio()),
+        ]);
+    }
+    Output::tabular(
+        table,
+        &BreadthOutput {
+            issues: universe.len(),
+            days,
+            skipped,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::tests::{run_args, temp_dir, write_series};
+    use std::fs;
+
+    #[test]
+    fn test_breadth() {
+        let dir = temp_dir("breadth");
+        for symbol in ["AAA", "BBB"] {
+            write_series(&dir, symbol, 120);
+        }
+        let data_dir = dir.to_str().unwrap();
+
+        let json = run_args(&[
+            "--format",
+            "json",
+            "breadth",
+            "--data-dir",
+            data_dir,
+            "--symbols",
+            "AAA,BBB,ZZZ",
+            "--high-low-period",
+            "20",
+            "--days",
+            "5",
+        ])
+        .unwrap();
+        let output: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(output["issues"], 2);
+        assert_eq!(output["days"].as_array().unwrap().len(), 5);
+        assert_eq!(output["skipped"][0]["symbol"], "ZZZ");
+        let last = &output["days"][4];
+        assert_eq!(last["date"], "2023-05-01");
+        let tallied = ["advancers", "decliners", "unchanged"]
+            .iter()
+            .map(|k| last[k].as_u64().unwrap())
+            .sum::<u64>();
+        assert_eq!(tallied, 2);
+
+        let table = run_args(&["breadth", "--data-dir", data_dir, "--symbols", "AAA"]).unwrap();
+        assert!(table.starts_with("Date") && table.contains("McClellan"));
+        assert!(run_args(&["breadth", "--data-dir", data_dir, "--symbols", "ZZZ"]).is_err());
+        fs::remove_dir_all(dir).ok();
+    }
+}
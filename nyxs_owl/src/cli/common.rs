@@ -0,0 +1,94 @@
+//! Argument groups and loaders shared by several subcommands
+
+use crate::data::{parse_bars, SymbolData, Watchlist};
+use crate::error::{OwlError, Result};
+use crate::spec::StrategySpec;
+use crate::strategies::{parse_spec, Params};
+use clap::Args;
+use day_trade::DailyOhlcv;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Strategy selection shared by several subcommands
+#[derive(Debug, Args)]
+pub struct StrategyArgs {
+    /// Strategy as `name` or `name:param=value,...`
+    #[arg(long, short, required_unless_present = "spec", conflicts_with = "spec")]
+    pub strategy: Option<String>,
+    /// Strategy spec file (TOML, YAML or JSON)
+    #[arg(long)]
+    pub spec: Option<PathBuf>,
+    /// Parameter override `name=value`; may be repeated
+    #[arg(long = "param", short = 'p')]
+    pub params: Vec<String>,
+}
+
+impl StrategyArgs {
+    /// The selected strategy with parameter overrides merged in
+    pub(super) fn resolve(&self) -> Result<StrategySpec> {
+        let mut spec = match (&self.spec, &self.strategy) {
+            (Some(path), _) => StrategySpec::from_file(path)?,
+            (None, Some(text)) => strategy_spec(text)?,
+            (None, None) => {
+                return Err(OwlError::InvalidConfig(
+                    "Give a --strategy or a --spec file".to_string(),
+                ))
+            }
+        };
+        for extra in &self.params {
+            spec.params.merge(&Params::parse(extra)?);
+        }
+        Ok(spec)
+    }
+}
+
+/// Spec for a `name:param=value,...` argument
+pub(super) fn strategy_spec(text: &str) -> Result<StrategySpec> {
+    let (name, params) = parse_spec(text)?;
+    Ok(StrategySpec::new(&name).with_params(params))
+}
+
+/// Symbol of a data file: the file name without `_daily_ohlcv.csv`
+pub(super) fn symbol_of(path: &Path) -> String {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    stem.strip_suffix("_daily_ohlcv")
+        .map(str::to_string)
+        .unwrap_or(stem)
+}
+
+/// `label` with every character that is not a letter, digit, `-` or `_`
+/// replaced by `_`, for use in a file name
+pub(super) fn file_stem(label: &str) -> String {
+    label
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+pub(super) fn load_daily(path: &Path) -> Result<(String, Vec<DailyOhlcv>)> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| OwlError::DataLoad(format!("Cannot read {}: {}", path.display(), e)))?;
+    let symbol = symbol_of(path);
+    let data = SymbolData::new(&symbol, parse_bars(&contents)?, None)?;
+    Ok((symbol, data.daily_ohlcv()))
+}
+
+/// Watchlist from a `--watchlist` file or `--symbols`
+pub(super) fn watchlist_of(file: &Option<PathBuf>, symbols: &[String]) -> Result<Watchlist> {
+    match file {
+        Some(path) => Watchlist::from_file(path),
+        None if !symbols.is_empty() => Watchlist::new(symbols),
+        None => Err(OwlError::InvalidConfig(
+            "Give a --watchlist file or --symbols".to_string(),
+        )),
+    }
+}
@@ -0,0 +1,177 @@
+//! The `data import` and `data validate` subcommands
+
+use super::{Output, Table};
+use crate::data::{
+    clean_bars, parse_bars, parse_bars_unchecked, validate_bars, write_bars, DataIssue,
+    MarketDataStore,
+};
+use crate::error::{OwlError, Result};
+use chrono::{DateTime, Utc};
+use clap::{Args, Subcommand};
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+
+/// Subcommands of `data`
+#[derive(Debug, Subcommand)]
+pub enum DataCommand {
+    /// Copy a CSV file into a data directory under the standard name
+    Import(ImportArgs),
+    /// Report ordering problems, bad prices, spikes and stale prices
+    Validate(ValidateArgs),
+}
+
+/// Arguments of `data import`
+#[derive(Debug, Args)]
+pub struct ImportArgs {
+    /// CSV file to import
+    #[arg(long, short)]
+    pub input: PathBuf,
+    /// Symbol the file holds
+    #[arg(long)]
+    pub symbol: String,
+    /// Destination data directory
+    #[arg(long)]
+    pub data_dir: PathBuf,
+    /// Sort, deduplicate, repair prices and remove spikes before writing
+    #[arg(long)]
+    pub clean: bool,
+}
+
+/// Arguments of `data validate`
+#[derive(Debug, Args)]
+pub struct ValidateArgs {
+    /// CSV files to check
+    #[arg(long, short, required = true, num_args = 1..)]
+    pub data: Vec<PathBuf>,
+}
+
+/// Outcome of `data import`
+#[derive(Debug, Serialize)]
+struct ImportSummary {
+    symbol: String,
+    path: PathBuf,
+    bars: usize,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    changes: Vec<DataIssue>,
+}
+
+pub(super) fn import(args: &ImportArgs) -> Result<Output> {
+    let contents = fs::read_to_string(&args.input)
+        .map_err(|e| OwlError::DataLoad(format!("Cannot read {}: {}", args.input.display(), e)))?;
+    let (bars, changes) = if args.clean {
+        clean_bars(parse_bars_unchecked(&contents)?)?
+    } else {
+        (parse_bars(&contents)?, Vec::new())
+    };
+    if bars.is_empty() {
+        return Err(OwlError::DataLoad(format!(
+            "No bars in {}",
+            args.input.display()
+        )));
+    }
+
+    fs::create_dir_all(&args.data_dir)?;
+    let symbol = args.symbol.trim().to_uppercase();
+    let path = MarketDataStore::new(&args.data_dir).daily_path(&symbol);
+    write_bars(&path, &bars)?;
+
+    let summary = ImportSummary {
+        symbol,
+        path,
+        bars: bars.len(),
+        start: bars.first().map(|b| b.timestamp),
+        end: bars.last().map(|b| b.timestamp),
+        changes,
+    };
+    let mut table = Table::new(["Symbol", "Bars", "Start", "End", "Changes", "Path"]);
+    let date = |t: Option<DateTime<Utc>>| t.map(|t| t.date_naive().to_string()).unwrap_or_default();
+    table.push_row([
+        summary.symbol.clone(),
+        summary.bars.to_string(),
+        date(summary.start),
+        date(summary.end),
+        summary.changes.len().to_string(),
+        summary.path.display().to_string(),
+    ]);
+    Output::tabular(table, &summary)
+}
+
+/// An issue and the file it was found in
+#[derive(Debug, Serialize)]
+struct FileIssue {
+    file: PathBuf,
+    #[serde(flatten)]
+    issue: DataIssue,
+}
+
+pub(super) fn validate(args: &ValidateArgs) -> Result<Output> {
+    let mut issues = Vec::new();
+    for path in &args.data {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| OwlError::DataLoad(format!("Cannot read {}: {}", path.display(), e)))?;
+        let bars = parse_bars_unchecked(&contents)?;
+        issues.extend(validate_bars(&bars).into_iter().map(|issue| FileIssue {
+            file: path.clone(),
+            issue,
+        }));
+    }
+
+    let mut table = Table::new(["File", "Timestamp", "Check", "Detail"]);
+    for i in &issues {
+        table.push_row([
+            i.file.display().to_string(),
+            i.issue.timestamp.to_rfc3339(),
+            i.issue.check.clone(),
+            i.issue.detail.clone(),
+        ]);
+    }
+    Output::tabular(table, &issues)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::tests::{run_args, temp_dir};
+
+    #[test]
+    fn test_data_import_and_validate() {
+        let dir = temp_dir("data");
+        let raw = dir.join("raw.csv");
+        fs::write(
+            &raw,
+            "date,open,high,low,close,volume\n\
+             2023-01-03,10,11,9,10.5,100\n\
+             2023-01-02,10,11,9,10,100\n\
+             2023-01-04,10,10.2,9,10.8,100\n",
+        )
+        .unwrap();
+        let raw_path = raw.to_str().unwrap();
+
+        let issues = run_args(&["--format", "csv", "data", "validate", "-d", raw_path]).unwrap();
+        assert!(issues.contains("ordering"));
+        assert!(issues.contains("ohlc_consistency"));
+
+        let target = dir.join("store");
+        let target_dir = target.to_str().unwrap();
+        let args = [
+            "data",
+            "import",
+            "-i",
+            raw_path,
+            "--symbol",
+            "abc",
+            "--data-dir",
+            target_dir,
+        ];
+        assert!(run_args(&args).is_err());
+        let mut cleaned = args.to_vec();
+        cleaned.push("--clean");
+        let text = run_args(&cleaned).unwrap();
+        assert!(text.contains("ABC"));
+        let imported = fs::read_to_string(target.join("ABC_daily_ohlcv.csv")).unwrap();
+        assert_eq!(parse_bars(&imported).unwrap().len(), 3);
+        fs::remove_dir_all(dir).ok();
+    }
+}
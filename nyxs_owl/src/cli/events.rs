@@ -0,0 +1,214 @@
+//! The `events` subcommand: abnormal returns around calendar events
+
+use super::common::watchlist_of;
+use super::{Output, Table};
+use crate::bubo::SkippedSymbol;
+use crate::data::MarketDataStore;
+use crate::error::{OwlError, Result};
+use clap::{Args, ValueEnum};
+use day_trade::events::{
+    EventCalendar, EventKind, EventReaction, EventStudy, EventStudySummary, ReturnModel,
+};
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Expected-return model of `events`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum EventModel {
+    /// The symbol's own mean return
+    Mean,
+    /// The benchmark's return
+    Market,
+    /// Alpha and beta fitted against the benchmark
+    MarketModel,
+}
+
+impl From<EventModel> for ReturnModel {
+    fn from(model: EventModel) -> Self {
+        match model {
+            EventModel::Mean => ReturnModel::MeanAdjusted,
+            EventModel::Market => ReturnModel::MarketAdjusted,
+            EventModel::MarketModel => ReturnModel::MarketModel,
+        }
+    }
+}
+
+/// Arguments of `events`
+#[derive(Debug, Args)]
+pub struct EventsArgs {
+    /// Event calendar CSV file
+    #[arg(long)]
+    pub calendar: PathBuf,
+    /// Directory with `{SYMBOL}_daily_ohlcv.csv` files
+    #[arg(long)]
+    pub data_dir: PathBuf,
+    /// Watchlist file with one symbol per line
+    #[arg(long, conflicts_with = "symbols")]
+    pub watchlist: Option<PathBuf>,
+    /// Comma-separated symbols
+    #[arg(long, value_delimiter = ',')]
+    pub symbols: Vec<String>,
+    /// Only events of this kind: earnings, dividend or economic
+    #[arg(long)]
+    pub kind: Option<String>,
+    /// Expected-return model
+    #[arg(long, value_enum, default_value_t = EventModel::Mean)]
+    pub model: EventModel,
+    /// Benchmark symbol in the data directory, required by the market models
+    #[arg(long)]
+    pub benchmark: Option<String>,
+    /// Bars before the reaction bar
+    #[arg(long, default_value_t = 5)]
+    pub pre: usize,
+    /// Bars after the reaction bar
+    #[arg(long, default_value_t = 10)]
+    pub post: usize,
+    /// Bars the expected return is estimated on
+    #[arg(long, default_value_t = 60)]
+    pub estimation: usize,
+    /// Daily bars to load per symbol
+    #[arg(long, default_value_t = 2_000)]
+    pub lookback: usize,
+}
+
+/// Event reactions of one symbol
+#[derive(Debug, Serialize)]
+struct SymbolReactions {
+    symbol: String,
+    reactions: Vec<EventReaction>,
+}
+
+/// Result of `events`
+#[derive(Debug, Serialize)]
+struct EventStudyOutput {
+    summary: EventStudySummary,
+    symbols: Vec<SymbolReactions>,
+    skipped: Vec<SkippedSymbol>,
+}
+
+pub(super) fn events(args: &EventsArgs) -> Result<Output> {
+    let watchlist = watchlist_of(&args.watchlist, &args.symbols)?;
+    let mut calendar = EventCalendar::load_csv(&args.calendar)?;
+    if let Some(kind) = &args.kind {
+        let kind = EventKind::parse(kind)
+            .ok_or_else(|| OwlError::InvalidConfig(format!("Unknown event kind '{}'", kind)))?;
+        calendar = calendar.of_kind(kind);
+    }
+    let study = EventStudy::new(args.model.into())
+        .with_window(args.pre, args.post)
+        .with_estimation(args.estimation)
+        .map_err(OwlError::InvalidConfig)?;
+
+    let store = MarketDataStore::new(&args.data_dir);
+    let benchmark = match &args.benchmark {
+        Some(symbol) => Some(store.load(symbol, args.lookback)?.daily_ohlcv()),
+        None => None,
+    };
+
+    let mut symbols = Vec::new();
+    let mut skipped = Vec::new();
+    for symbol in watchlist.symbols() {
+        let reactions = store.load(symbol, args.lookback).and_then(|data| {
+            Ok(study.analyze(symbol, &data.daily_ohlcv(), benchmark.as_deref(), &calendar)?)
+        });
+        match reactions {
+            Ok(reactions) => symbols.push(SymbolReactions {
+                symbol: symbol.clone(),
+                reactions,
+            }),
+            Err(e) => skipped.push(SkippedSymbol {
+                symbol: symbol.clone(),
+                reason: e.to_string(),
+            }),
+        }
+    }
+
+    let all: Vec<EventReaction> = symbols
+        .iter()
+        .flat_map(|s| s.reactions.iter().cloned())
+        .collect();
+    let summary = study.summarize(&all)?;
+
+    let percent = |v: f64| format!("{:+.2}%", v * 100.0);
+    let mut table = Table::new(["Offset", "Avg Abnormal", "Cumulative"]);
+    for (k, offset) in summary.offsets.iter().enumerate() {
+        table.push_row([
+            offset.to_string(),
+            percent(summary.average_abnormal[k]),
+            percent(summary.cumulative_average[k]),
+        ]);
+    }
+    Output::tabular(
+        table,
+        &EventStudyOutput {
+            summary,
+            symbols,
+            skipped,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::tests::{run_args, temp_dir, write_series};
+    use std::fs;
+
+    #[test]
+    fn test_event_study() {
+        let dir = temp_dir("events");
+        write_series(&dir, "AAA", 250);
+        write_series(&dir, "SPY", 250);
+        let calendar = dir.join("events.csv");
+        fs::write(
+            &calendar,
+            "date,symbol,event,timing,detail\n\
+             2023-05-01,AAA,earnings,amc,Q1\n\
+             2023-07-03,AAA,earnings,bmo,Q2\n\
+             2023-06-14,,economic,,FOMC\n\
+             2023-06-01,BBB,earnings,amc,Q1\n",
+        )
+        .unwrap();
+        let data_dir = dir.to_str().unwrap();
+        let calendar = calendar.to_str().unwrap();
+        let run_study = |extra: &[&str]| {
+            let mut args = vec![
+                "--format",
+                "json",
+                "events",
+                "--calendar",
+                calendar,
+                "--data-dir",
+                data_dir,
+                "--symbols",
+                "AAA,BBB",
+                "--pre",
+                "2",
+                "--post",
+                "3",
+            ];
+            args.extend_from_slice(extra);
+            run_args(&args)
+        };
+
+        let json = run_study(&["--kind", "earnings"]).unwrap();
+        let result: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(result["summary"]["events"], 2);
+        assert_eq!(result["summary"]["offsets"].as_array().unwrap().len(), 6);
+        assert_eq!(
+            result["symbols"][0]["reactions"][0]["reaction_date"],
+            "2023-05-02"
+        );
+        assert_eq!(result["skipped"][0]["symbol"], "BBB");
+
+        let json = run_study(&["--model", "market-model", "--benchmark", "SPY"]).unwrap();
+        let result: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(result["summary"]["events"], 3);
+        // The same prices as the benchmark leave nothing abnormal
+        let car = result["summary"]["cumulative_average"][5].as_f64().unwrap();
+        assert!(car.abs() < 1e-9);
+
+        assert!(run_study(&["--model", "market"]).is_err());
+        assert!(run_study(&["--kind", "merger"]).is_err());
+        fs::remove_dir_all(dir).ok();
+    }
+}
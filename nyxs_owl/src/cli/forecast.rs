@@ -0,0 +1,103 @@
+//! The `forecast` subcommand
+
+use super::common::load_daily;
+use super::{Output, Table};
+use crate::error::Result;
+use crate::strategies::{build_forecast_model, parse_spec, Params};
+use clap::Args;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Arguments of `forecast`
+#[derive(Debug, Args)]
+pub struct ForecastArgs {
+    /// Daily OHLCV CSV file
+    #[arg(long, short)]
+    pub data: PathBuf,
+    /// Model as `name` or `name:param=value,...`
+    #[arg(long, short)]
+    pub model: String,
+    /// Parameter override `name=value`; may be repeated
+    #[arg(long = "param", short = 'p')]
+    pub params: Vec<String>,
+    /// Number of bars to forecast
+    #[arg(long, default_value_t = 5)]
+    pub horizon: usize,
+    /// Confidence level of the prediction intervals
+    #[arg(long, default_value_t = 0.95)]
+    pub confidence: f64,
+}
+
+/// One forecast step
+#[derive(Debug, Serialize)]
+struct ForecastPoint {
+    step: usize,
+    forecast: f64,
+    lower: Option<f64>,
+    upper: Option<f64>,
+}
+
+pub(super) fn forecast(args: &ForecastArgs) -> Result<Output> {
+    let (name, mut params) = parse_spec(&args.model)?;
+    for extra in &args.params {
+        params.merge(&Params::parse(extra)?);
+    }
+    let model = build_forecast_model(&name, &params)?;
+    let (_, data) = load_daily(&args.data)?;
+    let closes: Vec<f64> = data.iter().map(|d| d.data.close).collect();
+
+    let result = model.fit_dyn(&closes)?.forecast(args.horizon)?;
+    // Not every model provides intervals; show the point forecast regardless
+    let intervals = result.confidence_intervals(args.confidence).ok();
+    let points: Vec<ForecastPoint> = result
+        .values()
+        .iter()
+        .enumerate()
+        .map(|(i, &value)| {
+            let bounds = intervals.as_ref().and_then(|iv| iv.get(i));
+            ForecastPoint {
+                step: i + 1,
+                forecast: value,
+                lower: bounds.map(|b| b.0),
+                upper: bounds.map(|b| b.1),
+            }
+        })
+        .collect();
+
+    let mut table = Table::new(["Step", "Forecast", "Lower", "Upper"]);
+    let cell = |v: Option<f64>| v.map(|v| format!("{:.2}", v)).unwrap_or_default();
+    for p in &points {
+        table.push_row([
+            p.step.to_string(),
+            format!("{:.2}", p.forecast),
+            cell(p.lower),
+            cell(p.upper),
+        ]);
+    }
+    Output::tabular(table, &points)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::tests::{run_args, temp_dir, write_series};
+    use std::fs;
+
+    #[test]
+    fn test_forecast() {
+        let dir = temp_dir("forecast");
+        let path = write_series(&dir, "BBB", 150);
+
+        let text = run_args(&[
+            "forecast",
+            "-d",
+            path.to_str().unwrap(),
+            "-m",
+            "ses:alpha=0.5",
+            "--horizon",
+            "3",
+        ])
+        .unwrap();
+        assert_eq!(text.lines().count(), 5);
+        fs::remove_dir_all(dir).ok();
+    }
+}
@@ -0,0 +1,170 @@
+//! The `nyxs_owl` command-line tool
+//!
+//! Every subcommand produces an [`Output`]: a table with the structured
+//! value behind it, written as aligned text, CSV or JSON according to
+//! `--format`, or a finished Markdown document.
+//!
+//! Strategies and models are given as `name` or `name:param=value,...`
+//! (see [`crate::strategies`]) or, for strategies, as a spec file (see
+//! [`crate::spec`]); `--param` adds further overrides.
+
+mod backtest;
+mod breadth;
+mod common;
+mod data;
+mod events;
+mod forecast;
+mod optimize;
+mod output;
+mod premarket;
+mod report;
+mod scan;
+mod screen;
+mod sectors;
+mod strategies;
+
+pub use backtest::BacktestArgs;
+pub use breadth::BreadthArgs;
+pub use common::StrategyArgs;
+pub use data::{DataCommand, ImportArgs, ValidateArgs};
+pub use events::{EventModel, EventsArgs};
+pub use forecast::ForecastArgs;
+pub use optimize::{Metric, OptimizeArgs};
+pub use output::{Output, OutputFormat, Table};
+pub use premarket::PremarketArgs;
+pub use report::ReportArgs;
+pub use scan::ScanArgs;
+pub use screen::ScreenArgs;
+pub use sectors::{RotationArgs, SectorsArgs};
+pub use strategies::StrategiesArgs;
+
+use crate::error::Result;
+use clap::{Parser, Subcommand};
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Command-line arguments
+#[derive(Debug, Parser)]
+#[command(
+    name = "nyxs_owl",
+    version,
+    about = "Backtest, scan and forecast from the command line"
+)]
+pub struct Cli {
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table, global = true)]
+    pub format: OutputFormat,
+    /// Write output to a file instead of standard output
+    #[arg(long, short, global = true)]
+    pub output: Option<PathBuf>,
+    /// Subcommand to run
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+/// Subcommands
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Backtest a daily strategy on one or more CSV files
+    Backtest(BacktestArgs),
+    /// Grid-search strategy parameters on a CSV file
+    Optimize(OptimizeArgs),
+    /// Run the Bubo scan over a watchlist and rank candidates
+    Scan(ScanArgs),
+    /// Rank a watchlist by pre-market gap and volume from minute files
+    Premarket(PremarketArgs),
+    /// Filter and rank a watchlist by indicator expressions
+    Screen(ScreenArgs),
+    /// Measure average abnormal returns around calendar events
+    Events(EventsArgs),
+    /// Rank sectors by relative strength and breadth
+    Sectors(SectorsArgs),
+    /// Backtest rotating into the strongest sectors
+    Rotation(RotationArgs),
+    /// Advance/decline, McClellan, new highs/lows and TRIN of a universe
+    Breadth(BreadthArgs),
+    /// Forecast closing prices with a forecast model
+    Forecast(ForecastArgs),
+    /// Write a Markdown backtest report with the trade list
+    Report(ReportArgs),
+    /// Import and validate data files
+    #[command(subcommand)]
+    Data(DataCommand),
+    /// List registered strategies or show one strategy's parameters
+    Strategies(StrategiesArgs),
+}
+
+/// Run a parsed command line, writing to `out` unless `--output` is given
+pub fn run(cli: &Cli, out: &mut dyn Write) -> Result<()> {
+    let output = execute(&cli.command, cli.format)?;
+    match &cli.output {
+        Some(path) => {
+            let mut file = fs::File::create(path)?;
+            output.write(cli.format, &mut file)
+        }
+        None => output.write(cli.format, out),
+    }
+}
+
+/// Run a subcommand and return its output
+pub fn execute(command: &Command, format: OutputFormat) -> Result<Output> {
+    match command {
+        Command::Backtest(args) => backtest::backtest(args),
+        Command::Optimize(args) => optimize::optimize(args),
+        Command::Scan(args) => scan::scan(args),
+        Command::Premarket(args) => premarket::premarket(args),
+        Command::Screen(args) => screen::screen(args),
+        Command::Events(args) => events::events(args),
+        Command::Sectors(args) => sectors::sectors(args),
+        Command::Rotation(args) => sectors::rotation(args),
+        Command::Breadth(args) => breadth::breadth(args),
+        Command::Forecast(args) => forecast::forecast(args),
+        Command::Report(args) => report::report(args, format),
+        Command::Data(DataCommand::Import(args)) => data::import(args),
+        Command::Data(DataCommand::Validate(args)) => data::validate(args),
+        Command::Strategies(args) => strategies::strategies(args),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::OwlError;
+    use std::path::Path;
+
+    pub(super) fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("owl_cli_{}_{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    pub(super) fn write_series(dir: &Path, symbol: &str, bars: usize) -> PathBuf {
+        let mut csv = String::from("date,open,high,low,close,volume\n");
+        let start = chrono::NaiveDate::from_ymd_opt(2023, 1, 2).unwrap();
+        for i in 0..bars {
+            let close = 100.0 + 10.0 * (i as f64 / 9.0).sin() + i as f64 * 0.05;
+            let date = start + chrono::Duration::days(i as i64);
+            csv.push_str(&format!(
+                "{},{},{},{},{},{}\n",
+                date,
+                close - 0.3,
+                close + 1.0,
+                close - 1.0,
+                close,
+                1_000_000 + i * 100
+            ));
+        }
+        let path = dir.join(format!("{}_daily_ohlcv.csv", symbol));
+        fs::write(&path, csv).unwrap();
+        path
+    }
+
+    pub(super) fn run_args(args: &[&str]) -> Result<String> {
+        let cli = Cli::try_parse_from(std::iter::once("nyxs_owl").chain(args.iter().copied()))
+            .map_err(|e| OwlError::InvalidConfig(e.to_string()))?;
+        let mut buffer = Vec::new();
+        run(&cli, &mut buffer)?;
+        Ok(String::from_utf8(buffer).unwrap())
+    }
+}
@@ -0,0 +1,232 @@
+//! The `optimize` subcommand: grid search over strategy parameters
+
+use super::common::{load_daily, StrategyArgs};
+use super::{Output, Table};
+use crate::backtest::{run_backtest_with_costs, BacktestSummary};
+use crate::error::{OwlError, Result};
+use crate::strategies::Params;
+use clap::{Args, ValueEnum};
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Largest parameter grid `optimize` will evaluate
+const MAX_GRID_SIZE: usize = 10_000;
+
+/// Metric `optimize` ranks parameter sets by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Metric {
+    /// Highest total return
+    TotalReturn,
+    /// Highest Sharpe ratio
+    Sharpe,
+    /// Smallest maximum drawdown
+    MaxDrawdown,
+}
+
+/// Arguments of `optimize`
+#[derive(Debug, Args)]
+pub struct OptimizeArgs {
+    /// Daily OHLCV CSV file
+    #[arg(long, short)]
+    pub data: PathBuf,
+    /// Strategy with its fixed parameters
+    #[command(flatten)]
+    pub strategy: StrategyArgs,
+    /// Parameter range `name=start:end:step` or list `name=v1,v2,...`;
+    /// may be repeated
+    #[arg(long, required = true)]
+    pub grid: Vec<String>,
+    /// Ranking metric
+    #[arg(long, value_enum, default_value_t = Metric::TotalReturn)]
+    pub metric: Metric,
+    /// Number of parameter sets to show
+    #[arg(long, default_value_t = 10)]
+    pub top: usize,
+    /// Starting cash
+    #[arg(long, default_value_t = 10_000.0)]
+    pub cash: f64,
+}
+
+/// Values of one `--grid` axis
+fn parse_grid_axis(spec: &str) -> Result<(String, Vec<f64>)> {
+    let invalid = || {
+        OwlError::InvalidConfig(format!(
+            "Expected name=start:end:step or name=v1,v2,..., got '{}'",
+            spec
+        ))
+    };
+    let (name, values) = spec.split_once('=').ok_or_else(invalid)?;
+    let numbers = |text: &str| -> Result<Vec<f64>> {
+        text.split([':', ','])
+            .map(|v| v.trim().parse::<f64>().map_err(|_| invalid()))
+            .collect()
+    };
+
+    let values = if values.contains(':') {
+        let bounds = numbers(values)?;
+        let [start, end, step] = bounds[..] else {
+            return Err(invalid());
+        };
+        if !step.is_finite() || step <= 0.0 || end < start {
+            return Err(OwlError::InvalidConfig(format!(
+                "Grid range for '{}' needs start <= end and a positive step",
+                name
+            )));
+        }
+        let count = ((end - start) / step + 1e-9).floor() as usize + 1;
+        if count > MAX_GRID_SIZE {
+            return Err(OwlError::InvalidConfig(format!(
+                "Grid range for '{}' has more than {} values",
+                name, MAX_GRID_SIZE
+            )));
+        }
+        (0..count).map(|i| start + i as f64 * step).collect()
+    } else {
+        numbers(values)?
+    };
+    Ok((name.trim().to_string(), values))
+}
+
+/// Result of one parameter set
+#[derive(Debug, Serialize)]
+struct OptimizeResult {
+    params: Params,
+    #[serde(flatten)]
+    summary: BacktestSummary,
+}
+
+pub(super) fn optimize(args: &OptimizeArgs) -> Result<Output> {
+    let mut base = args.strategy.resolve()?;
+    // Results are labelled by their parameters rather than the spec's name
+    base.name = None;
+    base.risk.apply(())?;
+    let costs = base.costs.to_costs()?;
+    let axes = args
+        .grid
+        .iter()
+        .map(|spec| parse_grid_axis(spec))
+        .collect::<Result<Vec<_>>>()?;
+    let size = axes
+        .iter()
+        .try_fold(1usize, |n, (_, values)| n.checked_mul(values.len()))
+        .filter(|&n| n <= MAX_GRID_SIZE)
+        .ok_or_else(|| {
+            OwlError::InvalidConfig(format!(
+                "Parameter grid has more than {} combinations",
+                MAX_GRID_SIZE
+            ))
+        })?;
+
+    let (_, data) = load_daily(&args.data)?;
+    let mut results = Vec::new();
+    let mut rejected = 0;
+    for index in 0..size {
+        let mut spec = base.clone();
+        let mut rest = index;
+        for (axis, values) in &axes {
+            spec.params.set(axis, values[rest % values.len()]);
+            rest /= values.len();
+        }
+        // Combinations the strategy rejects (e.g. fast >= slow) are skipped
+        let strategy = match spec.build() {
+            Ok(strategy) => strategy,
+            Err(OwlError::InvalidConfig(_)) => {
+                rejected += 1;
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        let summary =
+            run_backtest_with_costs(&spec.label(), strategy.as_ref(), &data, args.cash, &costs)?
+                .summary;
+        results.push(OptimizeResult {
+            params: spec.params,
+            summary,
+        });
+    }
+    if results.is_empty() {
+        return Err(OwlError::InvalidConfig(format!(
+            "All {} parameter combinations were rejected",
+            rejected
+        )));
+    }
+
+    results.sort_by(|a, b| match args.metric {
+        Metric::TotalReturn => b.summary.total_return.total_cmp(&a.summary.total_return),
+        Metric::Sharpe => b.summary.sharpe_ratio.total_cmp(&a.summary.sharpe_ratio),
+        Metric::MaxDrawdown => a.summary.max_drawdown.total_cmp(&b.summary.max_drawdown),
+    });
+    results.truncate(args.top);
+
+    let mut table = Table::new([
+        "Rank",
+        "Parameters",
+        "Return%",
+        "MaxDD%",
+        "Sharpe",
+        "Trades",
+    ]);
+    for (rank, r) in results.iter().enumerate() {
+        table.push_row([
+            (rank + 1).to_string(),
+            r.params.to_string(),
+            format!("{:.2}", r.summary.total_return),
+            format!("{:.2}", r.summary.max_drawdown),
+            format!("{:.2}", r.summary.sharpe_ratio),
+            r.summary.trades.to_string(),
+        ]);
+    }
+    Output::tabular(table, &results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::tests::{run_args, temp_dir, write_series};
+    use std::fs;
+
+    #[test]
+    fn test_backtest_and_optimize() {
+        let dir = temp_dir("backtest");
+        let path = write_series(&dir, "AAA", 200);
+        let file = path.to_str().unwrap();
+
+        let text =
+            run_args(&["backtest", "-d", file, "-s", "macd", "-p", "fast_period=8"]).unwrap();
+        assert!(text.starts_with("Symbol"));
+        assert!(text.contains("AAA"));
+        assert!(text.contains("macd:fast_period=8"));
+
+        let json = run_args(&["--format", "json", "backtest", "-d", file, "-s", "rsi"]).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed[0]["symbol"], "AAA");
+        assert_eq!(parsed[0]["bars"], 200);
+
+        // fast_period=30 is rejected against slow_period=26 and skipped
+        let csv = run_args(&[
+            "--format",
+            "csv",
+            "optimize",
+            "-d",
+            file,
+            "-s",
+            "macd",
+            "--grid",
+            "fast_period=6:12:3",
+            "--grid",
+            "signal_period=5,9",
+            "--top",
+            "3",
+        ])
+        .unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "Rank,Parameters,Return%,MaxDD%,Sharpe,Trades");
+        assert_eq!(lines.len(), 4);
+        assert!(lines[1].starts_with("1,\"fast_period="));
+
+        assert!(run_args(&["backtest", "-d", file, "-s", "macd:bogus=1"]).is_err());
+        assert!(parse_grid_axis("fast_period=5:1:1").is_err());
+        assert_eq!(parse_grid_axis("x=1:2:0.5").unwrap().1, vec![1.0, 1.5, 2.0]);
+        fs::remove_dir_all(dir).ok();
+    }
+}
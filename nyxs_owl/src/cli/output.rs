@@ -0,0 +1,188 @@
+//! Console tables and machine-readable output
+
+use crate::error::{OwlError, Result};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::io::Write;
+
+/// How command results are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Aligned columns for the console
+    Table,
+    /// JSON document
+    Json,
+    /// Comma-separated values with a header row
+    Csv,
+}
+
+/// Rows of formatted cells under a header
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Create an empty table with column headers
+    pub fn new<I, S>(headers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            headers: headers.into_iter().map(Into::into).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Append a row; missing cells are left blank
+    pub fn push_row<I, S>(&mut self, cells: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut row: Vec<String> = cells.into_iter().map(Into::into).collect();
+        row.resize(self.headers.len(), String::new());
+        self.rows.push(row);
+    }
+
+    /// Number of rows
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Whether the table has no rows
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Render with columns padded to their widest cell; columns whose
+    /// cells all parse as numbers are right-aligned
+    pub fn render(&self) -> String {
+        let columns = self.headers.len();
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| h.chars().count()).collect();
+        let mut numeric = vec![!self.rows.is_empty(); columns];
+        for row in &self.rows {
+            for (i, cell) in row.iter().enumerate() {
+                widths[i] = widths[i].max(cell.chars().count());
+                let bare = cell.trim_end_matches('%');
+                numeric[i] &= bare.is_empty() || bare.parse::<f64>().is_ok();
+            }
+        }
+
+        let line = |cells: &[String]| {
+            let padded: Vec<String> = cells
+                .iter()
+                .enumerate()
+                .map(|(i, c)| {
+                    if numeric[i] {
+                        format!("{:>width$}", c, width = widths[i])
+                    } else {
+                        format!("{:<width$}", c, width = widths[i])
+                    }
+                })
+                .collect();
+            padded.join("  ").trim_end().to_string()
+        };
+
+        let mut out = line(&self.headers);
+        out.push('\n');
+        let rule: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+        out.push_str(&rule.join("  "));
+        out.push('\n');
+        for row in &self.rows {
+            out.push_str(&line(row));
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Render as CSV, quoting cells that contain commas, quotes or newlines
+    pub fn to_csv(&self) -> String {
+        let escape = |cell: &String| {
+            if cell.contains([',', '"', '\n']) {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell.clone()
+            }
+        };
+        let mut out = String::new();
+        for row in std::iter::once(&self.headers).chain(&self.rows) {
+            let cells: Vec<String> = row.iter().map(escape).collect();
+            out.push_str(&cells.join(","));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Result of a command
+pub enum Output {
+    /// Tabular result with the structured value behind it for JSON
+    Tabular {
+        /// Rows for table and CSV output
+        table: Table,
+        /// Value serialized for JSON output
+        json: serde_json::Value,
+    },
+    /// A finished document (e.g. Markdown) written as-is
+    Document(String),
+}
+
+impl Output {
+    /// Tabular output from a table and any serializable value
+    pub fn tabular<T: Serialize>(table: Table, value: &T) -> Result<Self> {
+        let json = serde_json::to_value(value)
+            .map_err(|e| OwlError::InvalidConfig(format!("Cannot serialize output: {}", e)))?;
+        Ok(Output::Tabular { table, json })
+    }
+
+    /// Write in `format`; documents ignore the format
+    pub fn write(&self, format: OutputFormat, out: &mut dyn Write) -> Result<()> {
+        match self {
+            Output::Tabular { table, json } => match format {
+                OutputFormat::Table => out.write_all(table.render().as_bytes())?,
+                OutputFormat::Csv => out.write_all(table.to_csv().as_bytes())?,
+                OutputFormat::Json => {
+                    let text = serde_json::to_string_pretty(json).map_err(|e| {
+                        OwlError::InvalidConfig(format!("Cannot serialize output: {}", e))
+                    })?;
+                    writeln!(out, "{}", text)?;
+                }
+            },
+            Output::Document(text) => out.write_all(text.as_bytes())?,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_rendering() {
+        let mut table = Table::new(["Symbol", "Return"]);
+        table.push_row(["AAPL", "12.50"]);
+        table.push_row(["MSFT, Inc", "-3.00"]);
+
+        let text = table.render();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "Symbol     Return");
+        assert_eq!(lines[2], "AAPL        12.50");
+        assert_eq!(lines[3], "MSFT, Inc   -3.00");
+        assert_eq!(
+            table.to_csv(),
+            "Symbol,Return\nAAPL,12.50\n\"MSFT, Inc\",-3.00\n"
+        );
+
+        let mut buffer = Vec::new();
+        Output::tabular(table, &vec![("AAPL", 12.5)])
+            .unwrap()
+            .write(OutputFormat::Json, &mut buffer)
+            .unwrap();
+        let parsed: serde_json::Value = serde_json::from_slice(&buffer).unwrap();
+        assert_eq!(parsed[0][1], 12.5);
+    }
+}
@@ -0,0 +1,160 @@
+//! The `premarket` subcommand: gap and volume movers before the open
+
+use super::common::watchlist_of;
+use super::{Output, Table};
+use crate::data::MarketDataStore;
+use crate::error::{OwlError, Result};
+use chrono_tz::Tz;
+use clap::Args;
+use day_trade::premarket::{PremarketScanner, SessionHours};
+use std::path::PathBuf;
+
+/// Arguments of `premarket`
+#[derive(Debug, Args)]
+pub struct PremarketArgs {
+    /// Directory with `{SYMBOL}_minute_ohlcv.csv` files including extended hours
+    #[arg(long)]
+    pub data_dir: PathBuf,
+    /// Watchlist file with one symbol per line
+    #[arg(long, conflicts_with = "symbols")]
+    pub watchlist: Option<PathBuf>,
+    /// Comma-separated symbols
+    #[arg(long, value_delimiter = ',')]
+    pub symbols: Vec<String>,
+    /// Smallest gap in percent
+    #[arg(long, default_value_t = 1.0)]
+    pub min_gap: f64,
+    /// Smallest pre-market volume relative to earlier sessions
+    #[arg(long, default_value_t = 0.0)]
+    pub min_relative_volume: f64,
+    /// Exchange time zone, e.g. America/New_York
+    #[arg(long, default_value = "UTC")]
+    pub timezone: Tz,
+    /// Largest number of movers
+    #[arg(long, default_value_t = 20)]
+    pub top: usize,
+}
+
+pub(super) fn premarket(args: &PremarketArgs) -> Result<Output> {
+    let watchlist = watchlist_of(&args.watchlist, &args.symbols)?;
+    let hours = SessionHours::default().with_timezone(args.timezone);
+    let scanner = PremarketScanner::new(hours)
+        .with_min_gap(args.min_gap / 100.0)
+        .and_then(|s| s.with_min_relative_volume(args.min_relative_volume))
+        .map_err(OwlError::InvalidConfig)?
+        .with_max_results(args.top);
+
+    let store = MarketDataStore::new(&args.data_dir);
+    let mut universe = Vec::new();
+    let mut skipped = Vec::new();
+    for symbol in watchlist.symbols() {
+        match store.load_minute(symbol) {
+            Ok(bars) => universe.push((symbol.clone(), bars)),
+            Err(e) => skipped.push((symbol.clone(), e.to_string())),
+        }
+    }
+    let mut scan = scanner.scan(
+        universe
+            .iter()
+            .map(|(symbol, bars)| (symbol.as_str(), bars.as_slice())),
+    );
+    scan.skipped.extend(skipped);
+
+    let percent = |v: Option<f64>| v.map_or("-".to_string(), |v| format!("{:+.2}%", v * 100.0));
+    let mut table = Table::new([
+        "Rank",
+        "Symbol",
+        "Gap",
+        "Last",
+        "Prev Close",
+        "PM High",
+        "PM Low",
+        "RVol",
+        "AH Move",
+        "Fill Rate",
+        "Gaps",
+    ]);
+    for (rank, m) in scan.movers.iter().enumerate() {
+        table.push_row([
+            (rank + 1).to_string(),
+            m.symbol.clone(),
+            percent(Some(m.gap)),
+            format!("{:.2}", m.last_price),
+            format!("{:.2}", m.previous_close),
+            format!("{:.2}", m.premarket_high),
+            format!("{:.2}", m.premarket_low),
+            m.relative_volume
+                .map_or("-".to_string(), |r| format!("{:.2}", r)),
+            percent(m.after_hours_change),
+            m.history
+                .fill_rate
+                .map_or("-".to_string(), |r| format!("{:.0}%", r * 100.0)),
+            m.history.gaps.to_string(),
+        ]);
+    }
+    Output::tabular(table, &scan)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::tests::{run_args, temp_dir};
+    use std::fs;
+
+    #[test]
+    fn test_premarket_scan() {
+        let dir = temp_dir("premarket");
+        for (symbol, indicated) in [("UP", 105.0), ("FLAT", 100.2)] {
+            let mut csv = String::from("timestamp,open,high,low,close,volume\n");
+            for (time, price, volume) in [
+                ("2024-03-04T08:00:00Z", 99.0, 100),
+                ("2024-03-04T09:30:00Z", 99.0, 1000),
+                ("2024-03-04T15:59:00Z", 100.0, 1000),
+                ("2024-03-05T08:00:00Z", indicated, 400),
+            ] {
+                csv.push_str(&format!(
+                    "{},{},{},{},{},{}\n",
+                    time, price, price, price, price, volume
+                ));
+            }
+            fs::write(dir.join(format!("{}_minute_ohlcv.csv", symbol)), csv).unwrap();
+        }
+        let data_dir = dir.to_str().unwrap();
+
+        let json = run_args(&[
+            "--format",
+            "json",
+            "premarket",
+            "--data-dir",
+            data_dir,
+            "--symbols",
+            "UP,FLAT,NONE",
+        ])
+        .unwrap();
+        let scan: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(scan["movers"].as_array().unwrap().len(), 1);
+        assert_eq!(scan["movers"][0]["symbol"], "UP");
+        assert_eq!(scan["movers"][0]["relative_volume"], 4.0);
+        assert_eq!(scan["skipped"][0][0], "NONE");
+
+        let table = run_args(&["premarket", "--data-dir", data_dir, "--symbols", "UP"]).unwrap();
+        assert!(table.contains("+5.00%"));
+
+        // In New York the 09:30 UTC bar falls before the open
+        let json = run_args(&[
+            "--format",
+            "json",
+            "premarket",
+            "--data-dir",
+            data_dir,
+            "--symbols",
+            "UP",
+            "--timezone",
+            "America/New_York",
+        ])
+        .unwrap();
+        let scan: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(scan["movers"].as_array().unwrap().len(), 0);
+        assert!(run_args(&["premarket", "--data-dir", data_dir, "--timezone", "Mars"]).is_err());
+        fs::remove_dir_all(dir).ok();
+    }
+}
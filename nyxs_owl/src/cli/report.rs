@@ -0,0 +1,93 @@
+//! The `report` subcommand: a Markdown or HTML backtest report
+
+use super::common::{load_daily, StrategyArgs};
+use super::{Output, OutputFormat, Table};
+use crate::backtest::run_backtest_with_costs;
+use crate::error::Result;
+use crate::report::BacktestReport;
+use clap::Args;
+use std::path::PathBuf;
+
+/// Arguments of `report`
+#[derive(Debug, Args)]
+pub struct ReportArgs {
+    /// Daily OHLCV CSV file
+    #[arg(long, short)]
+    pub data: PathBuf,
+    /// Strategy to report on
+    #[command(flatten)]
+    pub strategy: StrategyArgs,
+    /// Starting cash
+    #[arg(long, default_value_t = 10_000.0)]
+    pub cash: f64,
+    /// Also write an HTML report with charts to this file
+    #[arg(long)]
+    pub html: Option<PathBuf>,
+}
+
+pub(super) fn report(args: &ReportArgs, format: OutputFormat) -> Result<Output> {
+    let spec = args.strategy.resolve()?;
+    let strategy = spec.build()?;
+    let (symbol, data) = load_daily(&args.data)?;
+    let label = format!("{} on {}", spec.label(), symbol);
+    let costs = spec.costs.to_costs()?;
+    let result = run_backtest_with_costs(&label, strategy.as_ref(), &data, args.cash, &costs)?;
+    let backtest = BacktestReport::new(result.clone(), &data)?;
+    if let Some(path) = &args.html {
+        backtest.write_html(path)?;
+    }
+
+    match format {
+        OutputFormat::Table => Ok(Output::Document(backtest.to_markdown())),
+        _ => {
+            let mut table = Table::new([
+                "Entry",
+                "EntryPrice",
+                "Exit",
+                "ExitPrice",
+                "Return%",
+                "Open",
+            ]);
+            for t in &result.trades {
+                table.push_row([
+                    t.entry_date.to_string(),
+                    format!("{:.2}", t.entry_price),
+                    t.exit_date.to_string(),
+                    format!("{:.2}", t.exit_price),
+                    format!("{:.2}", t.return_pct),
+                    t.open.to_string(),
+                ]);
+            }
+            Output::tabular(table, &result)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::tests::{run_args, temp_dir, write_series};
+    use std::fs;
+
+    #[test]
+    fn test_report() {
+        let dir = temp_dir("report");
+        let path = write_series(&dir, "BBB", 150);
+
+        let html = dir.join("bbb.html");
+        let markdown = run_args(&[
+            "report",
+            "-d",
+            path.to_str().unwrap(),
+            "-s",
+            "macd",
+            "--html",
+            html.to_str().unwrap(),
+        ])
+        .unwrap();
+        assert!(markdown.starts_with("# Backtest: macd on BBB"));
+        assert!(markdown.contains("## Monthly returns"));
+        let page = fs::read_to_string(&html).unwrap();
+        assert!(page.starts_with("<!DOCTYPE html>") && page.contains("<svg"));
+        fs::remove_dir_all(dir).ok();
+    }
+}
@@ -0,0 +1,194 @@
+//! The `scan` subcommand: the Bubo scan over a watchlist
+
+use super::common::{file_stem, strategy_spec, watchlist_of};
+use super::{Output, Table};
+use crate::backtest::run_backtest_with_costs;
+use crate::bubo::{Bubo, DailyStrategySource, ForecastSource, ScanConfig};
+use crate::data::MarketDataStore;
+use crate::error::Result;
+use crate::report::BacktestReport;
+use crate::spec::StrategySpec;
+use crate::strategies::{build_forecast_model, parse_spec};
+use clap::Args;
+use std::fs;
+use std::path::PathBuf;
+
+/// Arguments of `scan`
+#[derive(Debug, Args)]
+pub struct ScanArgs {
+    /// Directory with `{SYMBOL}_daily_ohlcv.csv` files
+    #[arg(long)]
+    pub data_dir: PathBuf,
+    /// Watchlist file with one symbol per line
+    #[arg(long, conflicts_with = "symbols")]
+    pub watchlist: Option<PathBuf>,
+    /// Comma-separated symbols
+    #[arg(long, value_delimiter = ',')]
+    pub symbols: Vec<String>,
+    /// Daily strategy as `name` or `name:param=value,...`; may be repeated
+    #[arg(long, short)]
+    pub strategy: Vec<String>,
+    /// Strategy spec file; may be repeated
+    #[arg(long)]
+    pub spec: Vec<PathBuf>,
+    /// Forecast model as `name` or `name:param=value,...`
+    #[arg(long)]
+    pub forecast: Option<String>,
+    /// Forecast horizon in bars
+    #[arg(long, default_value_t = 5)]
+    pub horizon: usize,
+    /// Smallest absolute score for a candidate
+    #[arg(long)]
+    pub min_score: Option<f64>,
+    /// Also propose short candidates
+    #[arg(long)]
+    pub allow_short: bool,
+    /// Largest number of candidates
+    #[arg(long)]
+    pub top: Option<usize>,
+    /// Print the dawn report as Markdown
+    #[arg(long)]
+    pub markdown: bool,
+    /// Backtest every strategy on each candidate, write HTML reports to this
+    /// directory and summarize them in the Markdown dawn report
+    #[arg(long)]
+    pub report_dir: Option<PathBuf>,
+}
+
+pub(super) fn scan(args: &ScanArgs) -> Result<Output> {
+    let watchlist = watchlist_of(&args.watchlist, &args.symbols)?;
+
+    let mut config = ScanConfig::default().with_short_candidates(args.allow_short);
+    if let Some(score) = args.min_score {
+        config = config.with_min_score(score)?;
+    }
+    if let Some(top) = args.top {
+        config = config.with_max_candidates(top);
+    }
+    let lookback = config.lookback();
+    let mut bubo = Bubo::new(MarketDataStore::new(&args.data_dir)).with_config(config);
+    let mut specs = args
+        .strategy
+        .iter()
+        .map(|text| strategy_spec(text))
+        .collect::<Result<Vec<_>>>()?;
+    for path in &args.spec {
+        specs.push(StrategySpec::from_file(path)?);
+    }
+    for spec in &specs {
+        let source = DailyStrategySource::new(&spec.label(), spec.build()?);
+        bubo = bubo.with_source(source, 1.0)?;
+    }
+    if let Some(spec) = &args.forecast {
+        let (name, params) = parse_spec(spec)?;
+        let source = ForecastSource::boxed(build_forecast_model(&name, &params)?, args.horizon)?;
+        bubo = bubo.with_source(source, 1.0)?;
+    }
+
+    let report = bubo.run(&watchlist)?;
+    let mut backtests = String::new();
+    if let Some(dir) = &args.report_dir {
+        fs::create_dir_all(dir)?;
+        let store = MarketDataStore::new(&args.data_dir);
+        for candidate in &report.candidates {
+            let data = store.load(&candidate.symbol, lookback)?.daily_ohlcv();
+            for spec in &specs {
+                let label = format!("{} on {}", spec.label(), candidate.symbol);
+                let costs = spec.costs.to_costs()?;
+                let result = run_backtest_with_costs(
+                    &label,
+                    spec.build()?.as_ref(),
+                    &data,
+                    10_000.0,
+                    &costs,
+                )?;
+                let backtest = BacktestReport::new(result, &data)?;
+                let path = dir.join(format!(
+                    "{}_{}.html",
+                    candidate.symbol,
+                    file_stem(&spec.label())
+                ));
+                backtest.write_html(&path)?;
+                backtests.push('\n');
+                backtests
+                    .push_str(&backtest.to_markdown_summary(Some(&path.display().to_string())));
+            }
+        }
+    }
+    if args.markdown {
+        let mut markdown = report.to_markdown();
+        if !backtests.is_empty() {
+            markdown.push_str("\n## Backtests\n");
+            markdown.push_str(&backtests);
+        }
+        return Ok(Output::Document(markdown));
+    }
+    let mut table = Table::new([
+        "Rank", "Symbol", "Side", "Score", "Close", "Entry", "Stop", "Target",
+    ]);
+    for (rank, c) in report.candidates.iter().enumerate() {
+        table.push_row([
+            (rank + 1).to_string(),
+            c.symbol.clone(),
+            c.direction.to_string(),
+            format!("{:+.2}", c.score),
+            format!("{:.2}", c.last_close),
+            format!("{:.2}", c.levels.entry),
+            format!("{:.2}", c.levels.stop),
+            format!("{:.2}", c.levels.target),
+        ]);
+    }
+    Output::tabular(table, &report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::tests::{run_args, temp_dir, write_series};
+
+    #[test]
+    fn test_scan() {
+        let dir = temp_dir("scan");
+        write_series(&dir, "AAA", 150);
+        write_series(&dir, "BBB", 150);
+        let data_dir = dir.to_str().unwrap();
+
+        let json = run_args(&[
+            "--format",
+            "json",
+            "scan",
+            "--data-dir",
+            data_dir,
+            "--symbols",
+            "AAA,BBB,CCC",
+            "-s",
+            "rsi",
+            "--min-score",
+            "0",
+        ])
+        .unwrap();
+        let report: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(report["scanned"], 3);
+        assert_eq!(report["skipped"][0]["symbol"], "CCC");
+
+        let reports = dir.join("reports");
+        let markdown = run_args(&[
+            "scan",
+            "--data-dir",
+            data_dir,
+            "--symbols",
+            "AAA",
+            "-s",
+            "rsi:period=10",
+            "--min-score",
+            "0",
+            "--markdown",
+            "--report-dir",
+            reports.to_str().unwrap(),
+        ])
+        .unwrap();
+        assert!(markdown.contains("## Backtests\n\n### rsi:period=10 on AAA"));
+        assert!(reports.join("AAA_rsi_period_10.html").exists());
+        fs::remove_dir_all(dir).ok();
+    }
+}
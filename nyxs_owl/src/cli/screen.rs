@@ -0,0 +1,152 @@
+//! The `screen` subcommand: indicator filters over a watchlist
+
+use super::common::watchlist_of;
+use super::{Output, Table};
+use crate::data::MarketDataStore;
+use crate::error::Result;
+use crate::screener::{RankOrder, Screener};
+use clap::Args;
+use std::path::PathBuf;
+
+/// Arguments of `screen`
+#[derive(Debug, Args)]
+pub struct ScreenArgs {
+    /// Directory with `{SYMBOL}_daily_ohlcv.csv` files
+    #[arg(long)]
+    pub data_dir: PathBuf,
+    /// Watchlist file with one symbol per line
+    #[arg(long, conflicts_with = "symbols")]
+    pub watchlist: Option<PathBuf>,
+    /// Comma-separated symbols
+    #[arg(long, value_delimiter = ',')]
+    pub symbols: Vec<String>,
+    /// Condition such as `RSI(14) < 30 AND close > SMA(200)`; may be repeated
+    #[arg(long, short)]
+    pub filter: Vec<String>,
+    /// Value to rank matches by, such as `CHANGE(20)`
+    #[arg(long)]
+    pub rank: Option<String>,
+    /// Rank the smallest values first
+    #[arg(long, requires = "rank")]
+    pub ascending: bool,
+    /// Largest number of matches
+    #[arg(long)]
+    pub top: Option<usize>,
+    /// Daily bars to load per symbol
+    #[arg(long, default_value_t = 300)]
+    pub lookback: usize,
+    /// Write the matched symbols to a watchlist file
+    #[arg(long)]
+    pub save_watchlist: Option<PathBuf>,
+}
+
+pub(super) fn screen(args: &ScreenArgs) -> Result<Output> {
+    let watchlist = watchlist_of(&args.watchlist, &args.symbols)?;
+    let mut screener = Screener::new();
+    for filter in &args.filter {
+        screener = screener.with_filter(filter)?;
+    }
+    if let Some(rank) = &args.rank {
+        let order = if args.ascending {
+            RankOrder::Lowest
+        } else {
+            RankOrder::Highest
+        };
+        screener = screener.with_rank(rank, order)?;
+    }
+    if let Some(top) = args.top {
+        screener = screener.with_top(top);
+    }
+
+    let result = screener.screen_store(
+        &MarketDataStore::new(&args.data_dir),
+        &watchlist,
+        args.lookback,
+    );
+    if let Some(path) = &args.save_watchlist {
+        result.watchlist()?.write(path)?;
+    }
+
+    let columns = screener.columns();
+    let mut headers = vec!["Rank", "Symbol", "Date", "Close"];
+    headers.extend(&columns);
+    let mut table = Table::new(headers);
+    for (rank, m) in result.matches.iter().enumerate() {
+        let mut row = vec![
+            (rank + 1).to_string(),
+            m.symbol.clone(),
+            m.date.to_string(),
+            format!("{:.2}", m.close),
+        ];
+        row.extend(columns.iter().map(|column| {
+            m.values
+                .get(*column)
+                .map_or("-".to_string(), |v| format!("{:.2}", v))
+        }));
+        table.push_row(row);
+    }
+    Output::tabular(table, &result)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::tests::{run_args, temp_dir, write_series};
+    use crate::data::Watchlist;
+    use std::fs;
+
+    #[test]
+    fn test_screen() {
+        let dir = temp_dir("screen");
+        write_series(&dir, "AAA", 250);
+        write_series(&dir, "BBB", 30);
+        let data_dir = dir.to_str().unwrap();
+        let saved = dir.join("matches.txt");
+
+        let json = run_args(&[
+            "--format",
+            "json",
+            "screen",
+            "--data-dir",
+            data_dir,
+            "--symbols",
+            "AAA,BBB,CCC",
+            "--filter",
+            "avg volume > 1M AND close > 0",
+            "--rank",
+            "RSI(14)",
+            "--save-watchlist",
+            saved.to_str().unwrap(),
+        ])
+        .unwrap();
+        let result: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(result["screened"], 3);
+        assert_eq!(result["matches"].as_array().unwrap().len(), 2);
+        assert_eq!(result["skipped"][0]["symbol"], "CCC");
+        assert_eq!(Watchlist::from_file(&saved).unwrap().len(), 2);
+
+        let table = run_args(&[
+            "screen",
+            "--data-dir",
+            data_dir,
+            "--symbols",
+            "AAA,BBB",
+            "--filter",
+            "close > SMA(200)",
+        ])
+        .unwrap();
+        assert!(table.contains("SMA(200)"));
+        assert!(!table.contains("BBB"));
+
+        let bad = [
+            "screen",
+            "--data-dir",
+            data_dir,
+            "--symbols",
+            "AAA",
+            "-f",
+            "RSI(14)",
+        ];
+        assert!(run_args(&bad).is_err());
+        fs::remove_dir_all(dir).ok();
+    }
+}
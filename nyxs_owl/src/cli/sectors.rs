@@ -0,0 +1,248 @@
+//! The `sectors` and `rotation` subcommands
+
+use super::{Output, Table};
+use crate::data::MarketDataStore;
+use crate::error::Result;
+use crate::sectors::{
+    RebalanceSchedule, SectorAnalyzer, SectorRotation, SectorUniverse, SymbolMetadata,
+};
+use clap::Args;
+use std::path::PathBuf;
+
+/// Arguments of `sectors`
+#[derive(Debug, Args)]
+pub struct SectorsArgs {
+    /// Metadata CSV file with symbol, sector, industry and benchmark columns
+    #[arg(long)]
+    pub metadata: PathBuf,
+    /// Directory with `{SYMBOL}_daily_ohlcv.csv` files
+    #[arg(long)]
+    pub data_dir: PathBuf,
+    /// Benchmark symbol for sectors and symbols without their own
+    #[arg(long)]
+    pub benchmark: Option<String>,
+    /// Trading days the returns cover
+    #[arg(long, default_value_t = 63)]
+    pub period: usize,
+    /// Moving average period for the share of members above it
+    #[arg(long, default_value_t = 50)]
+    pub ma_period: usize,
+    /// Daily bars to load per symbol
+    #[arg(long, default_value_t = 300)]
+    pub lookback: usize,
+    /// List symbols with their strength against benchmark and sector
+    #[arg(long)]
+    pub by_symbol: bool,
+}
+
+/// Arguments of `rotation`
+#[derive(Debug, Args)]
+pub struct RotationArgs {
+    /// Metadata CSV file with symbol and sector columns
+    #[arg(long)]
+    pub metadata: PathBuf,
+    /// Directory with `{SYMBOL}_daily_ohlcv.csv` files
+    #[arg(long)]
+    pub data_dir: PathBuf,
+    /// Number of sectors to hold
+    #[arg(long, default_value_t = 3)]
+    pub top: usize,
+    /// Trading days of trailing return sectors are ranked by
+    #[arg(long, default_value_t = 63)]
+    pub momentum: usize,
+    /// Rebalance schedule: weekly, monthly, quarterly or a number of days
+    #[arg(long, default_value = "monthly")]
+    pub rebalance: RebalanceSchedule,
+    /// Daily bars to load per symbol
+    #[arg(long, default_value_t = 1_000)]
+    pub lookback: usize,
+}
+
+pub(super) fn sectors(args: &SectorsArgs) -> Result<Output> {
+    let metadata = SymbolMetadata::from_file(&args.metadata)?;
+    let store = MarketDataStore::new(&args.data_dir);
+    let universe = SectorUniverse::load(metadata, &store, args.lookback, args.benchmark.as_deref());
+    let mut analyzer = SectorAnalyzer::default()
+        .with_period(args.period)?
+        .with_ma_period(args.ma_period)?;
+    if let Some(benchmark) = &args.benchmark {
+        analyzer = analyzer.with_benchmark(benchmark);
+    }
+    let report = analyzer.analyze(&universe);
+
+    let percent = |v: Option<f64>| v.map_or("-".to_string(), |v| format!("{:+.2}", v));
+    let table = if args.by_symbol {
+        let mut table = Table::new([
+            "Symbol",
+            "Sector",
+            "Industry",
+            "Return%",
+            "Benchmark",
+            "vs Bench%",
+            "vs Sector%",
+        ]);
+        for s in &report.symbols {
+            table.push_row([
+                s.symbol.clone(),
+                s.sector.clone(),
+                s.industry.clone(),
+                percent(Some(s.period_return)),
+                s.benchmark.clone().unwrap_or_else(|| "-".to_string()),
+                percent(s.vs_benchmark),
+                percent(s.vs_sector),
+            ]);
+        }
+        table
+    } else {
+        let mut table = Table::new([
+            "Rank",
+            "Sector",
+            "Members",
+            "Return%",
+            "vs Bench%",
+            "Above MA%",
+            "Adv",
+            "Dec",
+        ]);
+        for s in &report.sectors {
+            table.push_row([
+                s.rank.to_string(),
+                s.sector.clone(),
+                s.breadth.members.to_string(),
+                percent(Some(s.period_return)),
+                percent(s.vs_benchmark),
+                s.breadth
+                    .percent_above_ma
+                    .map_or("-".to_string(), |p| format!("{:.0}", p)),
+                s.breadth.advancers.to_string(),
+                s.breadth.decliners.to_string(),
+            ]);
+        }
+        table
+    };
+    Output::tabular(table, &report)
+}
+
+pub(super) fn rotation(args: &RotationArgs) -> Result<Output> {
+    let metadata = SymbolMetadata::from_file(&args.metadata)?;
+    let store = MarketDataStore::new(&args.data_dir);
+    let universe = SectorUniverse::load(metadata, &store, args.lookback, None);
+    let result = SectorRotation::default()
+        .with_top(args.top)?
+        .with_lookback(args.momentum)?
+        .with_schedule(args.rebalance)
+        .run(&universe.sector_indices())?;
+
+    let mut table = Table::new([
+        "Start",
+        "End",
+        "Rebalances",
+        "Return%",
+        "Equal Wt%",
+        "MaxDD%",
+        "Sharpe",
+        "Holding",
+    ]);
+    let date = |i: usize| result.equity[i].0.to_string();
+    table.push_row([
+        date(0),
+        date(result.equity.len() - 1),
+        result.rebalances.len().to_string(),
+        format!("{:.2}", result.total_return),
+        format!("{:.2}", result.equal_weight_return),
+        format!("{:.2}", result.max_drawdown),
+        format!("{:.2}", result.sharpe_ratio),
+        result
+            .rebalances
+            .last()
+            .map_or(String::new(), |r| r.holdings.join(", ")),
+    ]);
+    Output::tabular(table, &result)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::tests::{run_args, temp_dir, write_series};
+    use std::fs;
+
+    #[test]
+    fn test_sectors_and_rotation() {
+        let dir = temp_dir("sectors");
+        for symbol in ["AAA", "BBB", "CCC", "SPY"] {
+            write_series(&dir, symbol, 200);
+        }
+        let metadata = dir.join("sectors.csv");
+        fs::write(
+            &metadata,
+            "symbol,sector,industry\nAAA,Tech,Software\nBBB,Tech,Hardware\nCCC,Energy,Oil\nDDD,Energy,Gas\n",
+        )
+        .unwrap();
+        let (data_dir, metadata) = (dir.to_str().unwrap(), metadata.to_str().unwrap());
+
+        let json = run_args(&[
+            "--format",
+            "json",
+            "sectors",
+            "--metadata",
+            metadata,
+            "--data-dir",
+            data_dir,
+            "--benchmark",
+            "SPY",
+        ])
+        .unwrap();
+        let report: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(report["sectors"].as_array().unwrap().len(), 2);
+        assert_eq!(report["symbols"].as_array().unwrap().len(), 3);
+        assert_eq!(report["skipped"][0]["symbol"], "DDD");
+
+        let table = run_args(&[
+            "sectors",
+            "--metadata",
+            metadata,
+            "--data-dir",
+            data_dir,
+            "--by-symbol",
+        ])
+        .unwrap();
+        assert!(table.contains("vs Sector%") && table.contains("Software"));
+
+        let json = run_args(&[
+            "--format",
+            "json",
+            "rotation",
+            "--metadata",
+            metadata,
+            "--data-dir",
+            data_dir,
+            "--top",
+            "1",
+            "--momentum",
+            "20",
+            "--rebalance",
+            "quarterly",
+        ])
+        .unwrap();
+        let result: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(result["rebalances"].as_array().unwrap().len(), 3);
+        assert_eq!(
+            result["rebalances"][0]["holdings"]
+                .as_array()
+                .unwrap()
+                .len(),
+            1
+        );
+
+        let bad = [
+            "rotation",
+            "--metadata",
+            metadata,
+            "--data-dir",
+            data_dir,
+            "--rebalance",
+            "daily",
+        ];
+        assert!(run_args(&bad).is_err());
+        fs::remove_dir_all(dir).ok();
+    }
+}
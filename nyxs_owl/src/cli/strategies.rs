@@ -0,0 +1,129 @@
+//! The `strategies` subcommand: the strategy registry
+
+use super::{Output, Table};
+use crate::error::Result;
+use crate::strategies::{
+    Category, ParamKind, ParamSpec, StrategyInfo, StrategyRegistry, Timeframe,
+};
+use clap::Args;
+use serde::Serialize;
+
+/// Arguments of `strategies`
+#[derive(Debug, Args)]
+pub struct StrategiesArgs {
+    /// Strategy whose parameters to show; lists all strategies if omitted
+    pub name: Option<String>,
+    /// Only strategies in this category
+    #[arg(long, value_enum)]
+    pub category: Option<Category>,
+    /// Only strategies that trade on this timeframe
+    #[arg(long, value_enum)]
+    pub timeframe: Option<Timeframe>,
+}
+
+/// A registry entry in JSON output
+#[derive(Serialize)]
+struct StrategyEntry<'a> {
+    name: &'a str,
+    source: &'a str,
+    category: Category,
+    timeframes: &'a [Timeframe],
+    description: &'a str,
+    params: &'a [ParamSpec],
+}
+
+impl<'a> From<&'a StrategyInfo> for StrategyEntry<'a> {
+    fn from(info: &'a StrategyInfo) -> Self {
+        Self {
+            name: info.name(),
+            source: info.source(),
+            category: info.category(),
+            timeframes: info.timeframes(),
+            description: info.description(),
+            params: info.params(),
+        }
+    }
+}
+
+fn join<T: ToString>(items: impl IntoIterator<Item = T>) -> String {
+    items
+        .into_iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+pub(super) fn strategies(args: &StrategiesArgs) -> Result<Output> {
+    let registry = StrategyRegistry::default();
+
+    if let Some(name) = &args.name {
+        let info = registry.lookup(name)?;
+        let mut table = Table::new(["Parameter", "Kind", "Default", "Range", "Description"]);
+        for p in info.params() {
+            let kind = match p.kind {
+                ParamKind::Integer => "integer",
+                ParamKind::Float => "float",
+                ParamKind::Flag => "flag",
+            };
+            table.push_row([
+                p.name.to_string(),
+                kind.to_string(),
+                p.default.to_string(),
+                p.range_text(),
+                p.description.to_string(),
+            ]);
+        }
+        return Output::tabular(table, &StrategyEntry::from(info));
+    }
+
+    let selected: Vec<&StrategyInfo> = registry
+        .iter()
+        .filter(|info| args.category.is_none_or(|c| info.category() == c))
+        .filter(|info| args.timeframe.is_none_or(|t| info.supports(t)))
+        .collect();
+    let mut table = Table::new([
+        "Strategy",
+        "Source",
+        "Category",
+        "Timeframes",
+        "Parameters",
+        "Description",
+    ]);
+    for info in &selected {
+        table.push_row([
+            info.name().to_string(),
+            info.source().to_string(),
+            info.category().to_string(),
+            join(info.timeframes()),
+            join(info.params().iter().map(|p| p.name)),
+            info.description().to_string(),
+        ]);
+    }
+    let entries: Vec<StrategyEntry> = selected.into_iter().map(StrategyEntry::from).collect();
+    Output::tabular(table, &entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::tests::run_args;
+
+    #[test]
+    fn test_strategies_listing() {
+        let all = run_args(&["strategies"]).unwrap();
+        assert!(all.contains("macd") && all.contains("scalping"));
+
+        let buy = run_args(&["--format", "csv", "strategies", "--category", "buy"]).unwrap();
+        assert!(buy.contains("ma_crossover"));
+        assert!(!buy.contains("scalping"));
+
+        let minute = run_args(&["strategies", "--timeframe", "minute"]).unwrap();
+        assert!(minute.contains("vwap") && !minute.contains("adaptive_ma"));
+
+        let json = run_args(&["--format", "json", "strategies", "rsi"]).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["category"], "sell");
+        assert_eq!(value["params"][0]["name"], "period");
+        assert_eq!(value["params"][0]["min"], 2.0);
+        assert!(run_args(&["strategies", "nope"]).is_err());
+    }
+}
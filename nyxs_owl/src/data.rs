@@ -0,0 +1,630 @@
+//! Watchlists and local market data
+//!
+//! Bubo reads the latest bars for every symbol on a watchlist from a data
+//! directory. Files are CSV with a header row; columns are matched by name
+//! (`timestamp`/`date`, `open`, `high`, `low`, `close`, `volume`, any case),
+//! so exports with extra columns such as VWAP load unchanged.
+
+use crate::error::{OwlError, Result};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A list of symbols to scan
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Watchlist {
+    symbols: Vec<String>,
+}
+
+impl Watchlist {
+    /// Create a watchlist, upper-casing symbols and dropping duplicates
+    pub fn new<I, S>(symbols: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut list: Vec<String> = Vec::new();
+        for symbol in symbols {
+            let symbol = symbol.as_ref().trim().to_uppercase();
+            if symbol.is_empty() {
+                continue;
+            }
+            if symbol.contains(|c: char| c.is_whitespace() || c == '/' || c == '\\') {
+                return Err(OwlError::InvalidConfig(format!(
+                    "Invalid symbol '{}'",
+                    symbol
+                )));
+            }
+            if !list.contains(&symbol) {
+                list.push(symbol);
+            }
+        }
+        if list.is_empty() {
+            return Err(OwlError::InvalidConfig(
+                "Watchlist must contain at least one symbol".to_string(),
+            ));
+        }
+        Ok(Self { symbols: list })
+    }
+
+    /// Parse a watchlist with symbols separated by newlines or commas;
+    /// `#` starts a comment
+    pub fn parse(contents: &str) -> Result<Self> {
+        Self::new(
+            contents
+                .lines()
+                .map(|line| line.split('#').next().unwrap_or(""))
+                .flat_map(|line| line.split(',')),
+        )
+    }
+
+    /// Load a watchlist file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Write the watchlist to a file, one symbol per line
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let mut contents = self.symbols.join("\n");
+        contents.push('\n');
+        fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// Symbols in watchlist order
+    pub fn symbols(&self) -> &[String] {
+        &self.symbols
+    }
+
+    /// Number of symbols
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    /// Whether the watchlist is empty (never true for a constructed list)
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+}
+
+/// One OHLCV bar, independent of the strategy crates' bar types
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Bar {
+    /// Bar timestamp (midnight UTC for daily files with plain dates)
+    pub timestamp: DateTime<Utc>,
+    /// Open price
+    pub open: f64,
+    /// High price
+    pub high: f64,
+    /// Low price
+    pub low: f64,
+    /// Close price
+    pub close: f64,
+    /// Volume
+    pub volume: f64,
+}
+
+impl Bar {
+    fn day_trade_data(&self) -> day_trade::OhlcvData {
+        day_trade::OhlcvData {
+            open: self.open,
+            high: self.high,
+            low: self.low,
+            close: self.close,
+            volume: self.volume.max(0.0).round() as u64,
+        }
+    }
+}
+
+/// Parse OHLCV bars from CSV text
+///
+/// Bars must be in ascending time order with `low <= open, close <= high`.
+pub fn parse_bars(contents: &str) -> Result<Vec<Bar>> {
+    let bars = parse_bars_unchecked(contents)?;
+    for (i, bar) in bars.iter().enumerate() {
+        if !(bar.low <= bar.open.min(bar.close) && bar.open.max(bar.close) <= bar.high) {
+            return Err(OwlError::DataLoad(format!(
+                "Inconsistent OHLC prices at {}",
+                bar.timestamp
+            )));
+        }
+        if i > 0 && bar.timestamp <= bars[i - 1].timestamp {
+            return Err(OwlError::DataLoad(format!(
+                "Bars out of order at {}",
+                bar.timestamp
+            )));
+        }
+    }
+    Ok(bars)
+}
+
+/// Parse OHLCV bars from CSV text without checking price consistency or
+/// ordering, for validation and cleaning of raw files
+pub fn parse_bars_unchecked(contents: &str) -> Result<Vec<Bar>> {
+    let mut lines = contents
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty());
+    let (_, header) = lines
+        .next()
+        .ok_or_else(|| OwlError::DataLoad("Empty CSV file".to_string()))?;
+    let columns: Vec<String> = header.split(',').map(|c| c.trim().to_lowercase()).collect();
+    let find = |names: &[&str]| {
+        columns
+            .iter()
+            .position(|c| names.contains(&c.as_str()))
+            .ok_or_else(|| {
+                OwlError::DataLoad(format!("Missing '{}' column in CSV header", names[0]))
+            })
+    };
+    let time = find(&["timestamp", "date", "datetime", "time"])?;
+    let open = find(&["open"])?;
+    let high = find(&["high"])?;
+    let low = find(&["low"])?;
+    let close = find(&["close"])?;
+    let volume = find(&["volume"])?;
+
+    let mut bars: Vec<Bar> = Vec::new();
+    for (i, line) in lines {
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let line_no = i + 1;
+        let field = |index: usize| {
+            fields
+                .get(index)
+                .copied()
+                .ok_or_else(|| OwlError::DataLoad(format!("Missing field at line {}", line_no)))
+        };
+        let number = |index: usize, name: &str| -> Result<f64> {
+            let value = field(index)?;
+            value.parse::<f64>().map_err(|_| {
+                OwlError::DataLoad(format!("Invalid {} '{}' at line {}", name, value, line_no))
+            })
+        };
+
+        bars.push(Bar {
+            timestamp: parse_timestamp(field(time)?).ok_or_else(|| {
+                OwlError::DataLoad(format!("Invalid timestamp at line {}", line_no))
+            })?,
+            open: number(open, "open")?,
+            high: number(high, "high")?,
+            low: number(low, "low")?,
+            close: number(close, "close")?,
+            volume: number(volume, "volume")?,
+        });
+    }
+    Ok(bars)
+}
+
+/// Write bars as CSV in the layout `parse_bars` reads
+///
+/// Series whose timestamps all fall on midnight are written with a `date`
+/// column, others with RFC 3339 timestamps.
+pub fn write_bars<P: AsRef<Path>>(path: P, bars: &[Bar]) -> Result<()> {
+    let daily = bars
+        .iter()
+        .all(|b| b.timestamp.time() == chrono::NaiveTime::MIN);
+    let mut csv = String::from(if daily {
+        "date,open,high,low,close,volume\n"
+    } else {
+        "timestamp,open,high,low,close,volume\n"
+    });
+    for bar in bars {
+        let time = if daily {
+            bar.timestamp.format("%Y-%m-%d").to_string()
+        } else {
+            bar.timestamp.to_rfc3339()
+        };
+        csv.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            time, bar.open, bar.high, bar.low, bar.close, bar.volume
+        ));
+    }
+    fs::write(path, csv)?;
+    Ok(())
+}
+
+/// A problem found in a data file
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DataIssue {
+    /// Timestamp of the affected bar
+    pub timestamp: DateTime<Utc>,
+    /// Check that raised the issue
+    pub check: String,
+    /// Description
+    pub detail: String,
+}
+
+/// Check bars for ordering problems, inconsistent prices, one-bar spikes
+/// and stale prices without changing them
+pub fn validate_bars(bars: &[Bar]) -> Vec<DataIssue> {
+    use day_trade::data_quality::{
+        CleaningPipeline, FaultAction, OhlcConsistency, SpikeFilter, SpikeMethod,
+        StalePriceDetector,
+    };
+
+    let mut issues = Vec::new();
+    for pair in bars.windows(2) {
+        if pair[1].timestamp <= pair[0].timestamp {
+            issues.push(DataIssue {
+                timestamp: pair[1].timestamp,
+                check: "ordering".to_string(),
+                detail: if pair[1].timestamp == pair[0].timestamp {
+                    "Duplicate timestamp".to_string()
+                } else {
+                    format!("Follows a later bar at {}", pair[0].timestamp)
+                },
+            });
+        }
+    }
+
+    let mut sorted = bars.to_vec();
+    sorted.sort_by_key(|b| b.timestamp);
+    let spikes = SpikeFilter::new(
+        SpikeMethod::Hampel {
+            half_window: 5,
+            threshold: 5.0,
+        },
+        FaultAction::Flag,
+    )
+    .expect("valid spike filter settings");
+    let pipeline = CleaningPipeline::new()
+        .with_stage(OhlcConsistency::new(FaultAction::Flag))
+        .with_stage(spikes)
+        .with_stage(StalePriceDetector::flag(5).expect("valid stale price settings"));
+    // Flag-only stages cannot fail on well-formed input; report if they do
+    match pipeline.run(to_pipeline_bars(&sorted)) {
+        Ok(result) => issues.extend(result.audit().iter().map(|entry| DataIssue {
+            timestamp: entry.timestamp,
+            check: entry.stage.clone(),
+            detail: entry.detail.clone(),
+        })),
+        Err(e) => issues.push(DataIssue {
+            timestamp: sorted.first().map(|b| b.timestamp).unwrap_or_default(),
+            check: "pipeline".to_string(),
+            detail: e.to_string(),
+        }),
+    }
+    issues.sort_by_key(|i| i.timestamp);
+    issues
+}
+
+/// Sort bars, drop duplicate timestamps, repair inconsistent prices and
+/// remove one-bar spikes
+///
+/// Volumes are rounded to whole shares by the cleaning stages. Returns the
+/// cleaned bars and a description of every change.
+pub fn clean_bars(mut bars: Vec<Bar>) -> Result<(Vec<Bar>, Vec<DataIssue>)> {
+    use day_trade::data_quality::{CleaningPipeline, OhlcConsistency, SpikeFilter};
+
+    let mut changes = Vec::new();
+    bars.sort_by_key(|b| b.timestamp);
+    let before = bars.len();
+    bars.dedup_by_key(|b| b.timestamp);
+    if bars.len() < before {
+        changes.push(DataIssue {
+            timestamp: bars.first().map(|b| b.timestamp).unwrap_or_default(),
+            check: "ordering".to_string(),
+            detail: format!("Removed {} duplicate bars", before - bars.len()),
+        });
+    }
+
+    let pipeline = CleaningPipeline::new()
+        .with_stage(OhlcConsistency::repair())
+        .with_stage(SpikeFilter::hampel(5, 5.0).map_err(OwlError::InvalidConfig)?);
+    let result = pipeline.run(to_pipeline_bars(&bars))?;
+    changes.extend(result.audit().iter().map(|entry| DataIssue {
+        timestamp: entry.timestamp,
+        check: entry.stage.clone(),
+        detail: entry.detail.clone(),
+    }));
+    let cleaned = result
+        .data()
+        .iter()
+        .map(|b| Bar {
+            timestamp: b.timestamp,
+            open: b.data.open,
+            high: b.data.high,
+            low: b.data.low,
+            close: b.data.close,
+            volume: b.data.volume as f64,
+        })
+        .collect();
+    Ok((cleaned, changes))
+}
+
+fn to_pipeline_bars(bars: &[Bar]) -> Vec<day_trade::MinuteOhlcv> {
+    bars.iter()
+        .map(|b| day_trade::MinuteOhlcv {
+            timestamp: b.timestamp,
+            data: b.day_trade_data(),
+        })
+        .collect()
+}
+
+/// Parse RFC 3339, `YYYY-MM-DD HH:MM:SS[ UTC]` or plain dates
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(t) = DateTime::parse_from_rfc3339(value) {
+        return Some(t.with_timezone(&Utc));
+    }
+    let trimmed = value.trim_end_matches(" UTC");
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"] {
+        if let Ok(t) = NaiveDateTime::parse_from_str(trimmed, format) {
+            return Some(t.and_utc());
+        }
+    }
+    NaiveDate::parse_from_str(trimmed, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|t| t.and_utc())
+}
+
+/// Latest bars of one symbol
+#[derive(Debug, Clone)]
+pub struct SymbolData {
+    symbol: String,
+    daily: Vec<Bar>,
+    minute: Option<Vec<Bar>>,
+}
+
+impl SymbolData {
+    /// Create symbol data from daily bars and optional minute bars
+    pub fn new(symbol: &str, daily: Vec<Bar>, minute: Option<Vec<Bar>>) -> Result<Self> {
+        if daily.is_empty() {
+            return Err(OwlError::DataLoad(format!("No daily bars for {}", symbol)));
+        }
+        Ok(Self {
+            symbol: symbol.to_string(),
+            daily,
+            minute,
+        })
+    }
+
+    /// Symbol
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// Daily bars, oldest first
+    pub fn daily(&self) -> &[Bar] {
+        &self.daily
+    }
+
+    /// Minute bars, oldest first, when a minute file exists
+    pub fn minute(&self) -> Option<&[Bar]> {
+        self.minute.as_deref()
+    }
+
+    /// Daily closes
+    pub fn closes(&self) -> Vec<f64> {
+        self.daily.iter().map(|b| b.close).collect()
+    }
+
+    /// Most recent daily bar
+    pub fn last_bar(&self) -> &Bar {
+        self.daily.last().expect("symbol data is never empty")
+    }
+
+    /// Daily bars as `day_trade` bars
+    pub fn daily_ohlcv(&self) -> Vec<day_trade::DailyOhlcv> {
+        self.daily
+            .iter()
+            .map(|b| day_trade::DailyOhlcv {
+                date: b.timestamp.date_naive(),
+                data: b.day_trade_data(),
+            })
+            .collect()
+    }
+
+    /// Minute bars as `day_trade` intraday bars
+    pub fn intraday_ohlcv(&self) -> Option<Vec<day_trade::MinuteOhlcv>> {
+        self.minute.as_ref().map(|bars| {
+            bars.iter()
+                .map(|b| day_trade::MinuteOhlcv {
+                    timestamp: b.timestamp,
+                    data: b.day_trade_data(),
+                })
+                .collect()
+        })
+    }
+
+    /// Minute bars as `minute_trade` bars
+    pub fn minute_ohlcv(&self) -> Option<Vec<minute_trade::MinuteOhlcv>> {
+        self.minute.as_ref().map(|bars| {
+            bars.iter()
+                .map(|b| minute_trade::MinuteOhlcv {
+                    timestamp: b.timestamp,
+                    data: minute_trade::OhlcvData {
+                        open: b.open,
+                        high: b.high,
+                        low: b.low,
+                        close: b.close,
+                        volume: b.volume,
+                    },
+                })
+                .collect()
+        })
+    }
+}
+
+/// Directory of per-symbol CSV files
+///
+/// File names come from patterns in which `{symbol}` is replaced by the
+/// symbol; the defaults match the bundled example data,
+/// `{symbol}_daily_ohlcv.csv` and `{symbol}_minute_ohlcv.csv`.
+#[derive(Debug, Clone)]
+pub struct MarketDataStore {
+    root: PathBuf,
+    daily_pattern: String,
+    minute_pattern: String,
+}
+
+impl MarketDataStore {
+    /// Create a store reading from `root`
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+            daily_pattern: "{symbol}_daily_ohlcv.csv".to_string(),
+            minute_pattern: "{symbol}_minute_ohlcv.csv".to_string(),
+        }
+    }
+
+    /// Set the daily file name pattern
+    pub fn with_daily_pattern(mut self, pattern: &str) -> Result<Self> {
+        Self::check_pattern(pattern)?;
+        self.daily_pattern = pattern.to_string();
+        Ok(self)
+    }
+
+    /// Set the minute file name pattern
+    pub fn with_minute_pattern(mut self, pattern: &str) -> Result<Self> {
+        Self::check_pattern(pattern)?;
+        self.minute_pattern = pattern.to_string();
+        Ok(self)
+    }
+
+    fn check_pattern(pattern: &str) -> Result<()> {
+        if pattern.contains("{symbol}") {
+            Ok(())
+        } else {
+            Err(OwlError::InvalidConfig(format!(
+                "File pattern '{}' must contain {{symbol}}",
+                pattern
+            )))
+        }
+    }
+
+    /// Data directory
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Path of the daily file for `symbol`
+    pub fn daily_path(&self, symbol: &str) -> PathBuf {
+        self.root
+            .join(self.daily_pattern.replace("{symbol}", symbol))
+    }
+
+    /// Path of the minute file for `symbol`
+    pub fn minute_path(&self, symbol: &str) -> PathBuf {
+        self.root
+            .join(self.minute_pattern.replace("{symbol}", symbol))
+    }
+
+    /// Load the last `lookback` daily bars of `symbol`, plus its minute bars
+    /// from the latest session when a minute file exists
+    pub fn load(&self, symbol: &str, lookback: usize) -> Result<SymbolData> {
+        let path = self.daily_path(symbol);
+        let contents = fs::read_to_string(&path)
+            .map_err(|e| OwlError::DataLoad(format!("Cannot read {}: {}", path.display(), e)))?;
+        let mut daily = parse_bars(&contents)?;
+        if daily.len() > lookback {
+            daily.drain(..daily.len() - lookback);
+        }
+
+        let minute_path = self.minute_path(symbol);
+        let minute = if minute_path.exists() {
+            let mut bars = parse_bars(&fs::read_to_string(&minute_path)?)?;
+            if let Some(last_day) = bars.last().map(|b| b.timestamp.date_naive()) {
+                bars.retain(|b| b.timestamp.date_naive() == last_day);
+            }
+            Some(bars)
+        } else {
+            None
+        };
+
+        SymbolData::new(symbol, daily, minute)
+    }
+
+    /// Every minute bar of `symbol` as `day_trade` intraday bars, including
+    /// extended hours
+    pub fn load_minute(&self, symbol: &str) -> Result<Vec<day_trade::MinuteOhlcv>> {
+        let path = self.minute_path(symbol);
+        let contents = fs::read_to_string(&path)
+            .map_err(|e| OwlError::DataLoad(format!("Cannot read {}: {}", path.display(), e)))?;
+        Ok(parse_bars(&contents)?
+            .iter()
+            .map(|b| day_trade::MinuteOhlcv {
+                timestamp: b.timestamp,
+                data: b.day_trade_data(),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watchlist_parsing() {
+        let list = Watchlist::parse("aapl, msft\n# tech\nNVDA # chips\n\naapl").unwrap();
+        assert_eq!(list.symbols(), ["AAPL", "MSFT", "NVDA"]);
+        assert!(Watchlist::parse("# nothing").is_err());
+        assert!(Watchlist::new(["../etc"]).is_err());
+
+        let path = std::env::temp_dir().join(format!("owl_watchlist_{}.txt", std::process::id()));
+        list.write(&path).unwrap();
+        assert_eq!(Watchlist::from_file(&path).unwrap(), list);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_parse_bars_formats() {
+        let csv = "Timestamp,Open,High,Low,Close,Volume,VWAP\n\
+                   2022-08-22 04:00:00 UTC,169.69,169.86,167.135,167.57,76355924,168.2\n\
+                   2022-08-23,167.08,168.71,166.65,167.23,58090092,167.6\n\
+                   2022-08-24T13:30:00Z,167.3,168.0,166.9,167.9,1000,167.5\n";
+        let bars = parse_bars(csv).unwrap();
+        assert_eq!(bars.len(), 3);
+        assert_eq!(bars[0].close, 167.57);
+        assert_eq!(
+            bars[1].timestamp.date_naive(),
+            NaiveDate::from_ymd_opt(2022, 8, 23).unwrap()
+        );
+
+        let unordered = "date,open,high,low,close,volume\n\
+                         2022-08-23,1,2,0.5,1.5,10\n2022-08-22,1,2,0.5,1.5,10\n";
+        assert!(parse_bars(unordered).is_err());
+        assert!(parse_bars("date,open,high,low,close\n").is_err());
+        let inconsistent = "date,open,high,low,close,volume\n2022-08-22,1,2,1.2,1.5,10\n";
+        assert!(parse_bars(inconsistent).is_err());
+    }
+
+    #[test]
+    fn test_validate_and_clean() {
+        let csv = "date,open,high,low,close,volume\n\
+                   2024-01-01,10,11,9,10.5,100\n\
+                   2024-01-03,10.5,10,9.5,10.2,100\n\
+                   2024-01-02,10.2,10.8,9.9,10.4,100\n";
+        let bars = parse_bars_unchecked(csv).unwrap();
+        assert!(parse_bars(csv).is_err());
+
+        let issues = validate_bars(&bars);
+        assert!(issues.iter().any(|i| i.check == "ordering"));
+        assert!(issues.iter().any(|i| i.check != "ordering"));
+
+        let (cleaned, changes) = clean_bars(bars).unwrap();
+        assert_eq!(cleaned.len(), 3);
+        assert!(!changes.is_empty());
+        assert!(validate_bars(&cleaned).is_empty());
+
+        let path = std::env::temp_dir().join(format!("owl_write_{}.csv", std::process::id()));
+        write_bars(&path, &cleaned).unwrap();
+        let reread = parse_bars(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(reread, cleaned);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_store_loads_bundled_example() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../examples/csv");
+        let store = MarketDataStore::new(&root);
+        let data = store.load("AAPL", 100).unwrap();
+        assert_eq!(data.daily().len(), 100);
+        assert!(data.minute().is_none());
+        assert_eq!(data.daily_ohlcv().len(), 100);
+        assert!(store.load("ZZZZ", 100).is_err());
+        assert!(store.clone().with_daily_pattern("daily.csv").is_err());
+    }
+}
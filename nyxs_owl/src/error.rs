@@ -0,0 +1,43 @@
+//! Error types for the NyxsOwl pipeline
+
+use thiserror::Error;
+
+/// Errors raised while loading data or running a scan
+#[derive(Error, Debug)]
+pub enum OwlError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Data loading error: {0}")]
+    DataLoad(String),
+
+    #[error("Invalid configuration: {0}")]
+    InvalidConfig(String),
+
+    #[error("Strategy error: {0}")]
+    Strategy(String),
+
+    #[error("Forecast error: {0}")]
+    Forecast(String),
+}
+
+impl From<day_trade::TradeError> for OwlError {
+    fn from(err: day_trade::TradeError) -> Self {
+        OwlError::Strategy(err.to_string())
+    }
+}
+
+impl From<minute_trade::TradeError> for OwlError {
+    fn from(err: minute_trade::TradeError) -> Self {
+        OwlError::Strategy(err.to_string())
+    }
+}
+
+impl From<forecast_trade::ForecastError> for OwlError {
+    fn from(err: forecast_trade::ForecastError) -> Self {
+        OwlError::Forecast(err.to_string())
+    }
+}
+
+/// Result type for NyxsOwl operations
+pub type Result<T> = std::result::Result<T, OwlError>;
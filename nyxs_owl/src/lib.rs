@@ -2,6 +2,21 @@
 //!
 //! `nyxs_owl` is a Rust library that provides utilities for the NyxsOwl project.
 //!
+//! - [`backtest`]: backtests of daily strategies with a trade list
+//! - [`bubo`]: the nightly scan that runs strategies and forecasts over a
+//!   watchlist and ranks the results into a dawn report
+//! - [`cli`]: the `nyxs_owl` command-line tool
+//! - [`data`]: watchlists, per-symbol CSV data directories, validation and
+//!   cleaning
+//! - [`report`]: HTML backtest reports with SVG charts and Markdown
+//!   summaries
+//! - [`screener`]: cross-sectional screens by indicator filter expressions,
+//!   ranked into a watchlist
+//! - [`sectors`]: symbol sectors and benchmarks, relative strength, sector
+//!   breadth and sector rotation
+//! - [`spec`]: strategies declared in TOML, YAML or JSON files
+//! - [`strategies`]: the strategy registry and forecast models by name
+//!
 //! ## Example
 //!
 //! ```
@@ -21,6 +36,19 @@ pub use minute_trade;
 #[cfg(feature = "trading-math")]
 pub use trade_math;
 
+pub mod backtest;
+pub mod bubo;
+pub mod cli;
+pub mod data;
+pub mod error;
+pub mod report;
+pub mod screener;
+pub mod sectors;
+pub mod spec;
+pub mod strategies;
+
+pub use error::{OwlError, Result};
+
 /// An owl from the NyxsOwl project.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Owl {
@@ -0,0 +1,437 @@
+//! Backtest reports with charts, as self-contained HTML or Markdown
+//!
+//! A [`BacktestReport`] pairs a [`Backtest`] with the bars it ran on and
+//! renders:
+//!
+//! - an equity curve against buying and holding
+//! - the drawdown from the running peak
+//! - the closing price with a marker at every entry and exit
+//! - a heatmap of monthly returns
+//! - the trade list
+//!
+//! The charts are SVG written by this module and embedded in the page, so an
+//! HTML report opens in any browser without scripts or network access.
+//! [`BacktestReport::to_markdown_summary`] condenses a report into a section
+//! for the dawn report.
+//!
+//! # Example
+//!
+//! ```
+//! use day_trade::utils::generate_test_data;
+//! use nyxs_owl::backtest::run_backtest;
+//! use nyxs_owl::report::BacktestReport;
+//!
+//! let data = generate_test_data(300, 100.0, 0.02);
+//! let backtest = run_backtest("MACD", &day_trade::MacdStrategy::default(), &data, 10_000.0).unwrap();
+//! let report = BacktestReport::new(backtest, &data).unwrap();
+//!
+//! let html = report.to_html();
+//! assert!(html.starts_with("<!DOCTYPE html>"));
+//! assert!(!report.monthly_returns().is_empty());
+//! ```
+
+mod svg;
+
+use crate::backtest::Backtest;
+use crate::error::{OwlError, Result};
+use chrono::{Datelike, NaiveDate};
+use day_trade::DailyOhlcv;
+use serde::Serialize;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+use svg::{escape, Marker, Series};
+
+const MONTH_NAMES: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em auto;max-width:960px;color:#222}\
+table{border-collapse:collapse;margin:1em 0}\
+th,td{padding:4px 10px;border-bottom:1px solid #ddd;text-align:right}\
+th:first-child,td:first-child{text-align:left}\
+.gain{color:#1a7f37}.loss{color:#cf222e}\
+svg{display:block;max-width:100%;height:auto}";
+
+/// Return of the portfolio over one calendar month
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct MonthlyReturn {
+    /// Year
+    pub year: i32,
+    /// Month, 1 to 12
+    pub month: u32,
+    /// Return in percent, from the last value of the previous month or the
+    /// starting cash
+    pub return_pct: f64,
+}
+
+/// A backtest with the bars it ran on, ready to render
+#[derive(Debug, Clone)]
+pub struct BacktestReport {
+    backtest: Backtest,
+    first_open: f64,
+    closes: Vec<f64>,
+}
+
+impl BacktestReport {
+    /// Report on `backtest`, which must have run on `data`
+    pub fn new(backtest: Backtest, data: &[DailyOhlcv]) -> Result<Self> {
+        let same_dates = backtest.dates.len() == data.len()
+            && backtest
+                .dates
+                .iter()
+                .zip(data)
+                .all(|(d, bar)| *d == bar.date);
+        if !same_dates || backtest.equity.len() != data.len() {
+            return Err(OwlError::InvalidConfig(
+                "Backtest and bars cover different dates".to_string(),
+            ));
+        }
+        Ok(Self {
+            backtest,
+            first_open: data.first().map_or(0.0, |d| d.data.open),
+            closes: data.iter().map(|d| d.data.close).collect(),
+        })
+    }
+
+    /// The backtest reported on
+    pub fn backtest(&self) -> &Backtest {
+        &self.backtest
+    }
+
+    /// Decline of the equity from its running peak after every bar, in
+    /// percent; zero or negative
+    pub fn drawdowns(&self) -> Vec<f64> {
+        let mut peak = f64::NEG_INFINITY;
+        self.backtest
+            .equity
+            .iter()
+            .map(|&value| {
+                peak = peak.max(value);
+                (value / peak - 1.0) * 100.0
+            })
+            .collect()
+    }
+
+    /// Portfolio return of every calendar month with bars, oldest first
+    pub fn monthly_returns(&self) -> Vec<MonthlyReturn> {
+        let b = &self.backtest;
+        let mut months: Vec<MonthlyReturn> = Vec::new();
+        let mut base = b.summary.initial_cash;
+        for (i, (date, value)) in b.dates.iter().zip(&b.equity).enumerate() {
+            let month_ends = b
+                .dates
+                .get(i + 1)
+                .is_none_or(|next| next.month() != date.month() || next.year() != date.year());
+            if month_ends {
+                months.push(MonthlyReturn {
+                    year: date.year(),
+                    month: date.month(),
+                    return_pct: (value / base - 1.0) * 100.0,
+                });
+                base = *value;
+            }
+        }
+        months
+    }
+
+    /// Value of buying at the first open with the starting cash and holding
+    fn buy_and_hold(&self) -> Vec<f64> {
+        let cash = self.backtest.summary.initial_cash;
+        self.closes
+            .iter()
+            .map(|c| cash * c / self.first_open)
+            .collect()
+    }
+
+    /// Render a self-contained HTML page with the summary, charts and trade
+    /// list
+    pub fn to_html(&self) -> String {
+        let b = &self.backtest;
+        let s = &b.summary;
+        let title = format!("Backtest: {}", s.strategy);
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n",
+            escape(&title),
+            STYLE
+        );
+        let _ = write!(
+            html,
+            "<h1>{}</h1>\n<p>{} to {}, {} bars.</p>\n<table>\n",
+            escape(&title),
+            s.start,
+            s.end,
+            s.bars
+        );
+        for (metric, value) in summary_rows(b) {
+            let _ = writeln!(html, "<tr><th>{}</th><td>{}</td></tr>", metric, value);
+        }
+        html.push_str("</table>\n");
+
+        let buy_and_hold = self.buy_and_hold();
+        let equity_chart = svg::line_chart(
+            &b.dates,
+            &[
+                Series {
+                    label: "Strategy",
+                    values: &b.equity,
+                    color: "#0969da",
+                    fill: false,
+                },
+                Series {
+                    label: "Buy and hold",
+                    values: &buy_and_hold,
+                    color: "#8c959f",
+                    fill: false,
+                },
+            ],
+            &[],
+            "",
+        );
+        let drawdown_chart = svg::line_chart(
+            &b.dates,
+            &[Series {
+                label: "Drawdown",
+                values: &self.drawdowns(),
+                color: "#cf222e",
+                fill: true,
+            }],
+            &[],
+            "%",
+        );
+        let markers: Vec<Marker> = b
+            .trades
+            .iter()
+            .flat_map(|t| {
+                let entry = self.index_of(t.entry_date).map(|index| Marker {
+                    index,
+                    price: t.entry_price,
+                    buy: true,
+                });
+                let exit = (!t.open)
+                    .then(|| self.index_of(t.exit_date))
+                    .flatten()
+                    .map(|index| Marker {
+                        index,
+                        price: t.exit_price,
+                        buy: false,
+                    });
+                entry.into_iter().chain(exit)
+            })
+            .collect();
+        let price_chart = svg::line_chart(
+            &b.dates,
+            &[Series {
+                label: "Close",
+                values: &self.closes,
+                color: "#24292f",
+                fill: false,
+            }],
+            &markers,
+            "",
+        );
+        for (heading, chart) in [
+            ("Equity", equity_chart),
+            ("Drawdown", drawdown_chart),
+            ("Price and trades", price_chart),
+            ("Monthly returns", svg::heatmap(&self.monthly_returns())),
+        ] {
+            let _ = write!(html, "<h2>{}</h2>\n{}\n", heading, chart);
+        }
+
+        html.push_str("<h2>Trades</h2>\n");
+        if b.trades.is_empty() {
+            html.push_str("<p>No trades.</p>\n");
+        } else {
+            html.push_str("<table>\n<tr><th>#</th><th>Entry</th><th>Entry price</th><th>Exit</th><th>Exit price</th><th>Return</th></tr>\n");
+            for (i, t) in b.trades.iter().enumerate() {
+                let _ = writeln!(
+                    html,
+                    "<tr><td>{}</td><td>{}</td><td>{:.2}</td><td>{}{}</td><td>{:.2}</td><td class=\"{}\">{:+.2}%</td></tr>",
+                    i + 1,
+                    t.entry_date,
+                    t.entry_price,
+                    t.exit_date,
+                    if t.open { " (open)" } else { "" },
+                    t.exit_price,
+                    if t.return_pct >= 0.0 { "gain" } else { "loss" },
+                    t.return_pct
+                );
+            }
+            html.push_str("</table>\n");
+        }
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    /// Render the summary, monthly returns and trade list as Markdown
+    pub fn to_markdown(&self) -> String {
+        let markdown = self.backtest.to_markdown();
+        let (summary, trades) = markdown
+            .split_once("\n## Trades")
+            .unwrap_or((markdown.as_str(), ""));
+        format!(
+            "{}\n## Monthly returns\n\n{}\n## Trades{}",
+            summary,
+            monthly_table(&self.monthly_returns()),
+            trades
+        )
+    }
+
+    /// Condense the report into a Markdown section for the dawn report,
+    /// linking to the full report at `link` when given
+    pub fn to_markdown_summary(&self, link: Option<&str>) -> String {
+        let s = &self.backtest.summary;
+        let mut out = format!(
+            "### {}\n\n{:+.2}% against {:+.2}% buying and holding from {} to {}; \
+             max drawdown {:.2}%, Sharpe {:.2}, {} trades, {:.0}% won.",
+            s.strategy,
+            s.total_return,
+            s.buy_and_hold_return,
+            s.start,
+            s.end,
+            s.max_drawdown,
+            s.sharpe_ratio,
+            s.trades,
+            s.win_rate
+        );
+        if let Some(link) = link {
+            let _ = write!(out, " [Full report]({})", link);
+        }
+        out.push_str("\n\n");
+        out.push_str(&monthly_table(&self.monthly_returns()));
+        out
+    }
+
+    /// Write the HTML report to `path`
+    pub fn write_html<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        fs::write(path, self.to_html())?;
+        Ok(())
+    }
+
+    fn index_of(&self, date: NaiveDate) -> Option<usize> {
+        self.backtest.dates.binary_search(&date).ok()
+    }
+}
+
+/// Headline metrics as label and formatted value
+fn summary_rows(backtest: &Backtest) -> Vec<(&'static str, String)> {
+    let s = &backtest.summary;
+    vec![
+        ("Initial cash", format!("{:.2}", s.initial_cash)),
+        ("Final value", format!("{:.2}", s.final_value)),
+        ("Total return", format!("{:+.2}%", s.total_return)),
+        ("Costs paid", format!("{:.2}", s.costs_paid)),
+        ("Buy and hold", format!("{:+.2}%", s.buy_and_hold_return)),
+        ("Max drawdown", format!("{:.2}%", s.max_drawdown)),
+        ("Sharpe ratio", format!("{:.2}", s.sharpe_ratio)),
+        ("Trades", s.trades.to_string()),
+        ("Win rate", format!("{:.1}%", s.win_rate)),
+    ]
+}
+
+/// Markdown table of monthly returns with a row per year
+fn monthly_table(months: &[MonthlyReturn]) -> String {
+    let mut out = String::from("| Year |");
+    for name in MONTH_NAMES {
+        let _ = write!(out, " {} |", name);
+    }
+    out.push_str(" Year |\n|---|");
+    out.push_str(&"---:|".repeat(13));
+    out.push('\n');
+
+    let mut years: Vec<i32> = months.iter().map(|m| m.year).collect();
+    years.dedup();
+    for year in years {
+        let mut cells = vec![String::new(); 12];
+        let mut growth = 1.0;
+        for m in months.iter().filter(|m| m.year == year) {
+            cells[m.month as usize - 1] = format!("{:+.1}", m.return_pct);
+            growth *= 1.0 + m.return_pct / 100.0;
+        }
+        let _ = writeln!(
+            out,
+            "| {} | {} | {:+.1} |",
+            year,
+            cells.join(" | "),
+            (growth - 1.0) * 100.0
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtest::backtest_signals;
+    use day_trade::ledger::TradingCosts;
+    use day_trade::{OhlcvData, Signal};
+
+    /// Flat at 100 through January, 110 from February on
+    fn bars() -> Vec<DailyOhlcv> {
+        let start = NaiveDate::from_ymd_opt(2024, 1, 20).unwrap();
+        (0..30)
+            .map(|i| {
+                let date = start + chrono::Duration::days(i);
+                let price = if date.month() == 1 { 100.0 } else { 110.0 };
+                DailyOhlcv {
+                    date,
+                    data: OhlcvData {
+                        open: price,
+                        high: price,
+                        low: price,
+                        close: price,
+                        volume: 1000,
+                    },
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_report_renders_charts_and_months() {
+        let data = bars();
+        let mut signals = vec![Signal::Hold; data.len()];
+        signals[2] = Signal::Buy;
+        signals[20] = Signal::Sell;
+        let backtest = backtest_signals(
+            "Manual <test>",
+            &data,
+            signals,
+            1000.0,
+            &TradingCosts::default(),
+        )
+        .unwrap();
+        let report = BacktestReport::new(backtest.clone(), &data).unwrap();
+
+        let months = report.monthly_returns();
+        assert_eq!(months.len(), 2);
+        assert_eq!((months[0].year, months[0].month), (2024, 1));
+        assert!(months[0].return_pct.abs() < 1e-9);
+        assert!((months[1].return_pct - 10.0).abs() < 1e-9);
+        assert!(report.drawdowns().iter().all(|d| *d <= 0.0));
+        let buy_and_hold = report.buy_and_hold();
+        assert!((buy_and_hold[29] - 1100.0).abs() < 1e-9);
+
+        let html = report.to_html();
+        assert!(html.contains("<title>Backtest: Manual &lt;test&gt;</title>"));
+        assert_eq!(html.matches("<svg").count(), 4);
+        // One entry and one exit marker
+        assert_eq!(html.matches("#1a7f37\"/>").count(), 1);
+        assert_eq!(html.matches("#cf222e\"/>").count(), 1);
+        assert!(html.contains("<td class=\"gain\">+10.00%</td>"));
+        assert!(!html.contains("<script"));
+
+        let markdown = report.to_markdown();
+        assert!(markdown.starts_with("# Backtest: Manual <test>"));
+        let monthly = markdown.find("## Monthly returns").unwrap();
+        assert!(monthly < markdown.find("## Trades").unwrap());
+        assert!(markdown.contains("| 2024 | +0.0 | +10.0 |"));
+
+        let summary = report.to_markdown_summary(Some("manual.html"));
+        assert!(summary.starts_with("### Manual <test>"));
+        assert!(summary.contains("[Full report](manual.html)"));
+
+        assert!(BacktestReport::new(backtest, &data[1..]).is_err());
+    }
+}
@@ -0,0 +1,373 @@
+//! Line charts and heatmaps rendered as inline SVG
+
+use super::MonthlyReturn;
+use chrono::NaiveDate;
+use std::fmt::Write;
+
+const WIDTH: f64 = 900.0;
+const HEIGHT: f64 = 280.0;
+const LEFT: f64 = 70.0;
+const RIGHT: f64 = 20.0;
+const TOP: f64 = 20.0;
+const BOTTOM: f64 = 30.0;
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// One line of a chart
+pub(crate) struct Series<'a> {
+    pub label: &'a str,
+    pub values: &'a [f64],
+    pub color: &'a str,
+    /// Shade the area between the line and zero
+    pub fill: bool,
+}
+
+/// A fill drawn on a price line
+pub(crate) struct Marker {
+    pub index: usize,
+    pub price: f64,
+    pub buy: bool,
+}
+
+/// Escape `text` for use in HTML or SVG
+pub(crate) fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Round steps of 1, 2 or 5 times a power of ten covering `min..=max` in
+/// about five ticks
+fn ticks(min: f64, max: f64) -> Vec<f64> {
+    let raw = (max - min) / 5.0;
+    let magnitude = 10f64.powf(raw.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .iter()
+        .map(|m| m * magnitude)
+        .find(|s| *s >= raw)
+        .unwrap_or(10.0 * magnitude);
+    let first = (min / step).ceil();
+    let mut ticks = Vec::new();
+    for k in 0.. {
+        let tick = (first + k as f64) * step;
+        if tick > max + step * 1e-9 {
+            break;
+        }
+        // Snap values like -0.0000001 to zero
+        ticks.push(if tick.abs() < step * 1e-9 { 0.0 } else { tick });
+    }
+    ticks
+}
+
+fn format_tick(value: f64, unit: &str) -> String {
+    let digits = if value != 0.0 && value.abs() < 10.0 && value.fract() != 0.0 {
+        2
+    } else {
+        0
+    };
+    format!("{:.*}{}", digits, value, unit)
+}
+
+/// Line chart over `dates` with a value axis labelled in `unit`
+pub(crate) fn line_chart(
+    dates: &[NaiveDate],
+    series: &[Series],
+    markers: &[Marker],
+    unit: &str,
+) -> String {
+    let n = dates.len();
+    let values = series
+        .iter()
+        .flat_map(|s| s.values.iter())
+        .chain(markers.iter().map(|m| &m.price))
+        .copied()
+        .filter(|v| v.is_finite());
+    let (mut min, mut max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+        (lo.min(v), hi.max(v))
+    });
+    if series.iter().any(|s| s.fill) {
+        min = min.min(0.0);
+        max = max.max(0.0);
+    }
+    if !min.is_finite() {
+        (min, max) = (0.0, 1.0);
+    }
+    if max - min < 1e-9 {
+        let pad = (max.abs() * 0.01).max(1.0);
+        (min, max) = (min - pad, max + pad);
+    }
+    let pad = (max - min) * 0.05;
+    let (lo, hi) = (if min == 0.0 { 0.0 } else { min - pad }, max + pad);
+
+    let plot_width = WIDTH - LEFT - RIGHT;
+    let plot_height = HEIGHT - TOP - BOTTOM;
+    let x = |i: usize| {
+        if n < 2 {
+            LEFT + plot_width / 2.0
+        } else {
+            LEFT + i as f64 / (n - 1) as f64 * plot_width
+        }
+    };
+    let y = |v: f64| TOP + (hi - v) / (hi - lo) * plot_height;
+
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {w} {h}" width="{w}" height="{h}" font-family="sans-serif" font-size="11">"#,
+        w = WIDTH,
+        h = HEIGHT
+    );
+    for tick in ticks(lo, hi) {
+        let _ = write!(
+            svg,
+            r##"<line x1="{:.1}" y1="{y:.1}" x2="{:.1}" y2="{y:.1}" stroke="#e0e0e0"/><text x="{:.1}" y="{:.1}" text-anchor="end" fill="#555">{}</text>"##,
+            LEFT,
+            WIDTH - RIGHT,
+            LEFT - 6.0,
+            y(tick) + 4.0,
+            escape(&format_tick(tick, unit)),
+            y = y(tick),
+        );
+    }
+    let labels = 6.min(n);
+    let mut previous = None;
+    for k in 0..labels {
+        let i = if labels < 2 {
+            0
+        } else {
+            k * (n - 1) / (labels - 1)
+        };
+        if previous == Some(i) {
+            continue;
+        }
+        previous = Some(i);
+        let anchor = match k {
+            0 => "start",
+            k if k + 1 == labels => "end",
+            _ => "middle",
+        };
+        let _ = write!(
+            svg,
+            r##"<text x="{:.1}" y="{:.1}" text-anchor="{}" fill="#555">{}</text>"##,
+            x(i),
+            HEIGHT - 10.0,
+            anchor,
+            dates[i]
+        );
+    }
+
+    for s in series {
+        let points: Vec<String> = s
+            .values
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| v.is_finite())
+            .map(|(i, v)| format!("{:.1},{:.1}", x(i), y(*v)))
+            .collect();
+        if points.is_empty() {
+            continue;
+        }
+        if s.fill {
+            let base = y(0.0f64.clamp(lo, hi));
+            let _ = write!(
+                svg,
+                r#"<polygon points="{:.1},{base:.1} {} {:.1},{base:.1}" fill="{}" fill-opacity="0.25"/>"#,
+                x(0),
+                points.join(" "),
+                x(s.values.len().saturating_sub(1)),
+                s.color,
+                base = base,
+            );
+        }
+        let _ = write!(
+            svg,
+            r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="1.5"/>"#,
+            points.join(" "),
+            s.color
+        );
+    }
+    for m in markers.iter().filter(|m| m.index < n) {
+        let (cx, cy) = (x(m.index), y(m.price));
+        let (points, color) = if m.buy {
+            (
+                format!(
+                    "{:.1},{:.1} {:.1},{:.1} {:.1},{:.1}",
+                    cx,
+                    cy,
+                    cx - 5.0,
+                    cy + 9.0,
+                    cx + 5.0,
+                    cy + 9.0
+                ),
+                "#1a7f37",
+            )
+        } else {
+            (
+                format!(
+                    "{:.1},{:.1} {:.1},{:.1} {:.1},{:.1}",
+                    cx,
+                    cy,
+                    cx - 5.0,
+                    cy - 9.0,
+                    cx + 5.0,
+                    cy - 9.0
+                ),
+                "#cf222e",
+            )
+        };
+        let _ = write!(svg, r#"<polygon points="{}" fill="{}"/>"#, points, color);
+    }
+
+    if series.len() > 1 {
+        for (k, s) in series.iter().enumerate() {
+            let lx = LEFT + 10.0 + k as f64 * 140.0;
+            let _ = write!(
+                svg,
+                r##"<rect x="{:.1}" y="{:.1}" width="12" height="3" fill="{}"/><text x="{:.1}" y="{:.1}" fill="#333">{}</text>"##,
+                lx,
+                TOP + 4.0,
+                s.color,
+                lx + 16.0,
+                TOP + 9.0,
+                escape(s.label)
+            );
+        }
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Background of a return cell: green for gains, red for losses, deeper for
+/// larger moves relative to `scale`
+fn heat(value: f64, scale: f64) -> String {
+    let strength = (value.abs() / scale).min(1.0);
+    let fade = |full: f64| 255.0 - (255.0 - full) * strength;
+    if value >= 0.0 {
+        format!(
+            "rgb({:.0},{:.0},{:.0})",
+            fade(26.0),
+            fade(127.0),
+            fade(55.0)
+        )
+    } else {
+        format!(
+            "rgb({:.0},{:.0},{:.0})",
+            fade(207.0),
+            fade(34.0),
+            fade(46.0)
+        )
+    }
+}
+
+/// Grid of monthly returns, one row per year with the year's compounded
+/// return in the last column
+pub(crate) fn heatmap(months: &[MonthlyReturn]) -> String {
+    let mut years: Vec<i32> = months.iter().map(|m| m.year).collect();
+    years.dedup();
+    let scale = months
+        .iter()
+        .map(|m| m.return_pct.abs())
+        .fold(0.0, f64::max)
+        .max(1e-9);
+
+    let (cell_width, cell_height, label_width) = (58.0, 26.0, 50.0);
+    let width = label_width + 13.0 * cell_width;
+    let height = cell_height * (years.len() + 1) as f64;
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {w} {h}" width="{w}" height="{h}" font-family="sans-serif" font-size="11">"#,
+        w = width,
+        h = height
+    );
+    for (col, name) in MONTHS.iter().chain(["Year"].iter()).enumerate() {
+        let _ = write!(
+            svg,
+            r##"<text x="{:.1}" y="{:.1}" text-anchor="middle" fill="#555">{}</text>"##,
+            label_width + (col as f64 + 0.5) * cell_width,
+            cell_height * 0.65,
+            name
+        );
+    }
+    for (row, year) in years.iter().enumerate() {
+        let top = cell_height * (row + 1) as f64;
+        let _ = write!(
+            svg,
+            r##"<text x="{:.1}" y="{:.1}" fill="#333">{}</text>"##,
+            4.0,
+            top + cell_height * 0.65,
+            year
+        );
+        let of_year: Vec<&MonthlyReturn> = months.iter().filter(|m| m.year == *year).collect();
+        let total = (of_year
+            .iter()
+            .map(|m| 1.0 + m.return_pct / 100.0)
+            .product::<f64>()
+            - 1.0)
+            * 100.0;
+        let cells = of_year
+            .iter()
+            .map(|m| (m.month as usize - 1, m.return_pct))
+            .chain([(12, total)]);
+        for (col, value) in cells {
+            let left = label_width + col as f64 * cell_width;
+            let _ = write!(
+                svg,
+                r##"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}" stroke="#fff"/><text x="{:.1}" y="{:.1}" text-anchor="middle" fill="#111">{:+.1}</text>"##,
+                left,
+                top,
+                cell_width,
+                cell_height,
+                heat(value, scale),
+                left + cell_width / 2.0,
+                top + cell_height * 0.65,
+                value
+            );
+        }
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ticks_and_chart() {
+        assert_eq!(ticks(0.0, 10.0), [0.0, 2.0, 4.0, 6.0, 8.0, 10.0]);
+        assert_eq!(ticks(-3.2, 0.0), [-3.0, -2.0, -1.0, 0.0]);
+        assert_eq!(escape("<a & 'b'>"), "&lt;a &amp; &#39;b&#39;&gt;");
+
+        let dates: Vec<NaiveDate> = (1..=3)
+            .map(|d| NaiveDate::from_ymd_opt(2024, 1, d).unwrap())
+            .collect();
+        let values = [1.0, 2.0, 3.0];
+        let svg = line_chart(
+            &dates,
+            &[Series {
+                label: "Close",
+                values: &values,
+                color: "#000",
+                fill: false,
+            }],
+            &[Marker {
+                index: 1,
+                price: 2.0,
+                buy: true,
+            }],
+            "",
+        );
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>"));
+        assert_eq!(svg.matches("<polyline").count(), 1);
+        assert_eq!(svg.matches("#1a7f37").count(), 1);
+        assert!(svg.contains("2024-01-03"));
+    }
+}
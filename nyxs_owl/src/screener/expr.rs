@@ -0,0 +1,635 @@
+//! Screening expressions
+//!
+//! Grammar, from loosest to tightest binding:
+//!
+//! ```text
+//! or      := and ("OR" and)*
+//! and     := not ("AND" not)*
+//! not     := "NOT" not | compare
+//! compare := sum (("<" | "<=" | ">" | ">=" | "=" | "!=") sum)?
+//! sum     := product (("+" | "-") product)*
+//! product := unary (("*" | "/") unary)*
+//! unary   := "-" unary | primary
+//! primary := number | field | indicator | "(" or ")"
+//! ```
+//!
+//! Keywords, fields and indicator names are case-insensitive; `&&`, `||`
+//! and `!` may be used for `AND`, `OR` and `NOT`. Numbers take an optional
+//! `K`, `M` or `B` suffix, so `1.5M` is 1,500,000.
+
+use super::indicators::{Field, Indicator};
+use crate::data::Bar;
+use crate::error::{OwlError, Result};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Arithmetic operator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArithOp {
+    /// `+`
+    Add,
+    /// `-`
+    Sub,
+    /// `*`
+    Mul,
+    /// `/`
+    Div,
+}
+
+impl ArithOp {
+    fn symbol(self) -> &'static str {
+        match self {
+            ArithOp::Add => "+",
+            ArithOp::Sub => "-",
+            ArithOp::Mul => "*",
+            ArithOp::Div => "/",
+        }
+    }
+}
+
+/// Comparison operator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    /// `<`
+    Less,
+    /// `<=`
+    LessEqual,
+    /// `>`
+    Greater,
+    /// `>=`
+    GreaterEqual,
+    /// `=`
+    Equal,
+    /// `!=`
+    NotEqual,
+}
+
+impl CompareOp {
+    fn apply(self, a: f64, b: f64) -> bool {
+        match self {
+            CompareOp::Less => a < b,
+            CompareOp::LessEqual => a <= b,
+            CompareOp::Greater => a > b,
+            CompareOp::GreaterEqual => a >= b,
+            CompareOp::Equal => a == b,
+            CompareOp::NotEqual => a != b,
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            CompareOp::Less => "<",
+            CompareOp::LessEqual => "<=",
+            CompareOp::Greater => ">",
+            CompareOp::GreaterEqual => ">=",
+            CompareOp::Equal => "=",
+            CompareOp::NotEqual => "!=",
+        }
+    }
+}
+
+/// A parsed screening expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    /// Constant
+    Number(f64),
+    /// Value of the latest bar
+    Field(Field),
+    /// Indicator on the latest bar
+    Indicator(Indicator),
+    /// Negation
+    Negate(Box<Expr>),
+    /// Arithmetic on two numbers
+    Arithmetic(ArithOp, Box<Expr>, Box<Expr>),
+    /// Comparison of two numbers
+    Compare(CompareOp, Box<Expr>, Box<Expr>),
+    /// Both conditions hold
+    And(Box<Expr>, Box<Expr>),
+    /// Either condition holds
+    Or(Box<Expr>, Box<Expr>),
+    /// The condition does not hold
+    Not(Box<Expr>),
+}
+
+/// Indicator values of one symbol, computed once per name
+pub(super) struct Context<'a> {
+    bars: &'a [Bar],
+    values: HashMap<String, f64>,
+}
+
+impl<'a> Context<'a> {
+    pub(super) fn new(bars: &'a [Bar]) -> Self {
+        Self {
+            bars,
+            values: HashMap::new(),
+        }
+    }
+
+    fn indicator(&mut self, indicator: &Indicator) -> Result<f64> {
+        let key = indicator.to_string();
+        if let Some(value) = self.values.get(&key) {
+            return Ok(*value);
+        }
+        let value = indicator.compute(self.bars)?;
+        self.values.insert(key, value);
+        Ok(value)
+    }
+}
+
+impl Expr {
+    /// Parse a condition such as `RSI(14) < 30 AND close > SMA(200)`
+    pub fn parse_condition(text: &str) -> Result<Self> {
+        let expr = Self::parse(text)?;
+        if !expr.is_condition() {
+            return Err(OwlError::InvalidConfig(format!(
+                "'{}' is a value, not a condition",
+                text.trim()
+            )));
+        }
+        Ok(expr)
+    }
+
+    /// Parse a value such as `CHANGE(20)` or `close / SMA(50)`
+    pub fn parse_value(text: &str) -> Result<Self> {
+        let expr = Self::parse(text)?;
+        if expr.is_condition() {
+            return Err(OwlError::InvalidConfig(format!(
+                "'{}' is a condition, not a value",
+                text.trim()
+            )));
+        }
+        Ok(expr)
+    }
+
+    fn parse(text: &str) -> Result<Self> {
+        let tokens = tokenize(text)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            pos: 0,
+        };
+        let expr = parser.or()?;
+        if let Some(token) = parser.peek() {
+            return Err(parser.error(&format!("unexpected '{}'", token)));
+        }
+        Ok(expr)
+    }
+
+    /// Whether the expression is true or false rather than a number
+    pub fn is_condition(&self) -> bool {
+        matches!(
+            self,
+            Expr::Compare(..) | Expr::And(..) | Expr::Or(..) | Expr::Not(_)
+        )
+    }
+
+    /// Fields and indicators the expression reads, in order of appearance
+    pub fn terms(&self) -> Vec<String> {
+        let mut terms = Vec::new();
+        self.collect_terms(&mut terms);
+        terms
+    }
+
+    fn collect_terms(&self, terms: &mut Vec<String>) {
+        match self {
+            Expr::Number(_) => {}
+            Expr::Field(_) | Expr::Indicator(_) => {
+                let name = self.to_string();
+                if !terms.contains(&name) {
+                    terms.push(name);
+                }
+            }
+            Expr::Negate(e) | Expr::Not(e) => e.collect_terms(terms),
+            Expr::Arithmetic(_, a, b)
+            | Expr::Compare(_, a, b)
+            | Expr::And(a, b)
+            | Expr::Or(a, b) => {
+                a.collect_terms(terms);
+                b.collect_terms(terms);
+            }
+        }
+    }
+
+    /// Numeric value on `context`'s bars
+    pub(super) fn value(&self, context: &mut Context) -> Result<f64> {
+        match self {
+            Expr::Number(n) => Ok(*n),
+            Expr::Field(field) => {
+                let bar = context
+                    .bars
+                    .last()
+                    .ok_or_else(|| OwlError::DataLoad("No bars".to_string()))?;
+                Ok(field.of(bar))
+            }
+            Expr::Indicator(indicator) => context.indicator(indicator),
+            Expr::Negate(e) => Ok(-e.value(context)?),
+            Expr::Arithmetic(op, a, b) => {
+                let (a, b) = (a.value(context)?, b.value(context)?);
+                Ok(match op {
+                    ArithOp::Add => a + b,
+                    ArithOp::Sub => a - b,
+                    ArithOp::Mul => a * b,
+                    ArithOp::Div => a / b,
+                })
+            }
+            _ => Err(OwlError::InvalidConfig(format!(
+                "'{}' is a condition, not a value",
+                self
+            ))),
+        }
+    }
+
+    /// Truth on `context`'s bars; comparisons with a non-finite value are
+    /// false
+    pub(super) fn holds(&self, context: &mut Context) -> Result<bool> {
+        match self {
+            Expr::Compare(op, a, b) => {
+                let (a, b) = (a.value(context)?, b.value(context)?);
+                Ok(a.is_finite() && b.is_finite() && op.apply(a, b))
+            }
+            Expr::And(a, b) => Ok(a.holds(context)? && b.holds(context)?),
+            Expr::Or(a, b) => Ok(a.holds(context)? || b.holds(context)?),
+            Expr::Not(e) => Ok(!e.holds(context)?),
+            _ => Err(OwlError::InvalidConfig(format!(
+                "'{}' is a value, not a condition",
+                self
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Operands that are themselves operations are parenthesised
+        let operand = |e: &Expr| match e {
+            Expr::Number(_) | Expr::Field(_) | Expr::Indicator(_) => e.to_string(),
+            _ => format!("({})", e),
+        };
+        match self {
+            Expr::Number(n) => write!(f, "{}", n),
+            Expr::Field(field) => write!(f, "{}", field),
+            Expr::Indicator(indicator) => write!(f, "{}", indicator),
+            Expr::Negate(e) => write!(f, "-{}", operand(e)),
+            Expr::Arithmetic(op, a, b) => {
+                write!(f, "{} {} {}", operand(a), op.symbol(), operand(b))
+            }
+            Expr::Compare(op, a, b) => write!(f, "{} {} {}", a, op.symbol(), b),
+            Expr::And(a, b) => write!(f, "{} AND {}", operand(a), operand(b)),
+            Expr::Or(a, b) => write!(f, "{} OR {}", operand(a), operand(b)),
+            Expr::Not(e) => write!(f, "NOT {}", operand(e)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    LParen,
+    RParen,
+    Comma,
+    Arith(ArithOp),
+    Compare(CompareOp),
+    And,
+    Or,
+    Not,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(n) => write!(f, "{}", n),
+            Token::Ident(name) => write!(f, "{}", name),
+            Token::LParen => write!(f, "("),
+            Token::RParen => write!(f, ")"),
+            Token::Comma => write!(f, ","),
+            Token::Arith(op) => write!(f, "{}", op.symbol()),
+            Token::Compare(op) => write!(f, "{}", op.symbol()),
+            Token::And => write!(f, "AND"),
+            Token::Or => write!(f, "OR"),
+            Token::Not => write!(f, "NOT"),
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    let error = |i: usize, what: &str| {
+        OwlError::InvalidConfig(format!(
+            "Expression '{}': {} at {}",
+            text.trim(),
+            what,
+            i + 1
+        ))
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '(' | ')' | ',' | '+' | '-' | '*' | '/' => {
+                tokens.push(match c {
+                    '(' => Token::LParen,
+                    ')' => Token::RParen,
+                    ',' => Token::Comma,
+                    '+' => Token::Arith(ArithOp::Add),
+                    '-' => Token::Arith(ArithOp::Sub),
+                    '*' => Token::Arith(ArithOp::Mul),
+                    _ => Token::Arith(ArithOp::Div),
+                });
+                i += 1;
+            }
+            '<' | '>' | '=' | '!' => {
+                let (token, len) = match (c, next) {
+                    ('<', Some('=')) => (Token::Compare(CompareOp::LessEqual), 2),
+                    ('<', Some('>')) => (Token::Compare(CompareOp::NotEqual), 2),
+                    ('<', _) => (Token::Compare(CompareOp::Less), 1),
+                    ('>', Some('=')) => (Token::Compare(CompareOp::GreaterEqual), 2),
+                    ('>', _) => (Token::Compare(CompareOp::Greater), 1),
+                    ('=', Some('=')) => (Token::Compare(CompareOp::Equal), 2),
+                    ('=', _) => (Token::Compare(CompareOp::Equal), 1),
+                    ('!', Some('=')) => (Token::Compare(CompareOp::NotEqual), 2),
+                    _ => (Token::Not, 1),
+                };
+                tokens.push(token);
+                i += len;
+            }
+            '&' | '|' => {
+                if next != Some(c) {
+                    return Err(error(i, &format!("expected '{}{}'", c, c)));
+                }
+                tokens.push(if c == '&' { Token::And } else { Token::Or });
+                i += 2;
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let digits: String = chars[start..i].iter().collect();
+                let mut value: f64 = digits
+                    .parse()
+                    .map_err(|_| error(start, &format!("invalid number '{}'", digits)))?;
+                let suffix = chars.get(i).map(|c| c.to_ascii_uppercase());
+                let after = chars.get(i + 1).copied();
+                if let Some(scale @ ('K' | 'M' | 'B')) = suffix {
+                    if !after.is_some_and(|c| c.is_alphanumeric() || c == '_') {
+                        value *= match scale {
+                            'K' => 1e3,
+                            'M' => 1e6,
+                            _ => 1e9,
+                        };
+                        i += 1;
+                    }
+                }
+                tokens.push(Token::Number(value));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                tokens.push(match word.to_uppercase().as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    _ => Token::Ident(word),
+                });
+            }
+            _ => return Err(error(i, &format!("unexpected '{}'", c))),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn error(&self, message: &str) -> OwlError {
+        OwlError::InvalidConfig(format!(
+            "Expression: {} after {} tokens",
+            message,
+            self.pos.min(self.tokens.len())
+        ))
+    }
+
+    fn condition(&self, expr: Expr, context: &str) -> Result<Box<Expr>> {
+        if expr.is_condition() {
+            Ok(Box::new(expr))
+        } else {
+            Err(self.error(&format!("{} needs a condition, not '{}'", context, expr)))
+        }
+    }
+
+    fn number(&self, expr: Expr, context: &str) -> Result<Box<Expr>> {
+        if expr.is_condition() {
+            Err(self.error(&format!("{} needs a value, not '{}'", context, expr)))
+        } else {
+            Ok(Box::new(expr))
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr> {
+        let mut left = self.and()?;
+        while self.eat(&Token::Or) {
+            let right = self.and()?;
+            left = Expr::Or(self.condition(left, "OR")?, self.condition(right, "OR")?);
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr> {
+        let mut left = self.not()?;
+        while self.eat(&Token::And) {
+            let right = self.not()?;
+            left = Expr::And(self.condition(left, "AND")?, self.condition(right, "AND")?);
+        }
+        Ok(left)
+    }
+
+    fn not(&mut self) -> Result<Expr> {
+        if self.eat(&Token::Not) {
+            let inner = self.not()?;
+            return Ok(Expr::Not(self.condition(inner, "NOT")?));
+        }
+        self.compare()
+    }
+
+    fn compare(&mut self) -> Result<Expr> {
+        let left = self.sum()?;
+        if let Some(Token::Compare(op)) = self.peek().cloned() {
+            self.pos += 1;
+            let right = self.sum()?;
+            let context = op.symbol();
+            return Ok(Expr::Compare(
+                op,
+                self.number(left, context)?,
+                self.number(right, context)?,
+            ));
+        }
+        Ok(left)
+    }
+
+    fn sum(&mut self) -> Result<Expr> {
+        let mut left = self.product()?;
+        while let Some(Token::Arith(op @ (ArithOp::Add | ArithOp::Sub))) = self.peek().cloned() {
+            self.pos += 1;
+            let right = self.product()?;
+            let context = op.symbol();
+            left = Expr::Arithmetic(
+                op,
+                self.number(left, context)?,
+                self.number(right, context)?,
+            );
+        }
+        Ok(left)
+    }
+
+    fn product(&mut self) -> Result<Expr> {
+        let mut left = self.unary()?;
+        while let Some(Token::Arith(op @ (ArithOp::Mul | ArithOp::Div))) = self.peek().cloned() {
+            self.pos += 1;
+            let right = self.unary()?;
+            let context = op.symbol();
+            left = Expr::Arithmetic(
+                op,
+                self.number(left, context)?,
+                self.number(right, context)?,
+            );
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        if self.eat(&Token::Arith(ArithOp::Sub)) {
+            let inner = self.unary()?;
+            return Ok(match inner {
+                Expr::Number(n) => Expr::Number(-n),
+                other => Expr::Negate(self.number(other, "-")?),
+            });
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Expr::Number(n)),
+            Some(Token::LParen) => {
+                let inner = self.or()?;
+                if !self.eat(&Token::RParen) {
+                    return Err(self.error("expected ')'"));
+                }
+                Ok(inner)
+            }
+            Some(Token::Ident(name)) => self.term(&name),
+            Some(token) => Err(self.error(&format!("unexpected '{}'", token))),
+            None => Err(self.error("unexpected end")),
+        }
+    }
+
+    fn term(&mut self, name: &str) -> Result<Expr> {
+        // "avg volume" reads as AVG_VOLUME
+        let mut name = name.to_uppercase();
+        if name == "AVG" {
+            if let Some(Token::Ident(next)) = self.peek() {
+                if next.eq_ignore_ascii_case("volume") {
+                    self.pos += 1;
+                    name = "AVG_VOLUME".to_string();
+                }
+            }
+        }
+
+        let mut args = Vec::new();
+        if self.eat(&Token::LParen) {
+            if !self.eat(&Token::RParen) {
+                loop {
+                    match self.unary()? {
+                        Expr::Number(n) => args.push(n),
+                        other => {
+                            return Err(self.error(&format!(
+                                "arguments of {} must be numbers, not '{}'",
+                                name, other
+                            )))
+                        }
+                    }
+                    if self.eat(&Token::RParen) {
+                        break;
+                    }
+                    if !self.eat(&Token::Comma) {
+                        return Err(self.error(&format!("expected ',' or ')' in {}", name)));
+                    }
+                }
+            }
+        } else if let Some(field) = Field::from_name(&name) {
+            return Ok(Expr::Field(field));
+        }
+
+        Indicator::from_call(&name, &args)
+            .map(Expr::Indicator)
+            .map_err(|e| self.error(&e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display() {
+        let expr =
+            Expr::parse_condition("RSI(14) < 30 and close > sma(200) AND avg volume > 1M").unwrap();
+        assert_eq!(
+            expr.to_string(),
+            "((RSI(14) < 30) AND (CLOSE > SMA(200))) AND (AVG_VOLUME(20) > 1000000)"
+        );
+        assert_eq!(
+            expr.terms(),
+            ["RSI(14)", "CLOSE", "SMA(200)", "AVG_VOLUME(20)"]
+        );
+
+        let expr = Expr::parse_condition("NOT (close - open) / open * 100 >= -2.5 || !volume > 5k")
+            .unwrap();
+        assert!(matches!(expr, Expr::Or(..)));
+        assert!(Expr::parse_value("close / SMA(50)").is_ok());
+
+        for bad in [
+            "RSI(14)",
+            "RSI(14) < ",
+            "close > 1 AND 2",
+            "SMA(close) > 1",
+            "FOO(3) > 1",
+            "RSI(1.5) < 30",
+            "close > (open",
+            "close & open",
+            "close > 1 2",
+        ] {
+            assert!(Expr::parse_condition(bad).is_err(), "{}", bad);
+        }
+        assert!(Expr::parse_value("close > 1").is_err());
+    }
+}
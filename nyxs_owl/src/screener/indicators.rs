@@ -0,0 +1,468 @@
+//! Bar fields and `trade_math` indicators available to screening
+//! expressions
+
+use crate::data::Bar;
+use crate::error::{OwlError, Result};
+use std::fmt;
+use trade_math::moving_averages::{ExponentialMovingAverage, SimpleMovingAverage};
+use trade_math::oscillators::{Macd, RelativeStrengthIndex, StochasticOscillator};
+use trade_math::volatility::{AverageTrueRange, BollingerBands, StandardDeviation};
+use trade_math::volume::VolumeMovingAverage;
+use trade_math::MathError;
+
+/// A value of the latest bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    /// Open price
+    Open,
+    /// High price
+    High,
+    /// Low price
+    Low,
+    /// Close price; also `PRICE`
+    Close,
+    /// Volume
+    Volume,
+}
+
+impl Field {
+    /// Field by upper-case name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "OPEN" => Some(Field::Open),
+            "HIGH" => Some(Field::High),
+            "LOW" => Some(Field::Low),
+            "CLOSE" | "PRICE" => Some(Field::Close),
+            "VOLUME" => Some(Field::Volume),
+            _ => None,
+        }
+    }
+
+    /// Value in `bar`
+    pub fn of(&self, bar: &Bar) -> f64 {
+        match self {
+            Field::Open => bar.open,
+            Field::High => bar.high,
+            Field::Low => bar.low,
+            Field::Close => bar.close,
+            Field::Volume => bar.volume,
+        }
+    }
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Field::Open => "OPEN",
+            Field::High => "HIGH",
+            Field::Low => "LOW",
+            Field::Close => "CLOSE",
+            Field::Volume => "VOLUME",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// An indicator evaluated on the latest bar
+///
+/// Arguments in brackets are optional, with the defaults shown:
+///
+/// | Name | Value |
+/// |------|-------|
+/// | `SMA(n)`, `EMA(n)` | Moving average of closes |
+/// | `RSI(n)` | Relative strength index, 0 to 100 |
+/// | `MACD([12, 26, 9])`, `MACD_SIGNAL(..)`, `MACD_HIST(..)` | MACD line, signal line and histogram |
+/// | `STOCH_K([14, 3])`, `STOCH_D([14, 3])` | Stochastic oscillator |
+/// | `ATR(n)` | Average true range |
+/// | `STDDEV(n)` | Standard deviation of closes |
+/// | `BB_UPPER(n[, 2])`, `BB_LOWER(n[, 2])`, `PERCENT_B(n[, 2])` | Bollinger Bands |
+/// | `AVG_VOLUME([20])` | Average volume; also written `avg volume` |
+/// | `RVOL([20])` | Volume relative to the average of the previous bars |
+/// | `CHANGE(n)` | Change of the close over `n` bars, in percent |
+/// | `HIGHEST(n)`, `LOWEST(n)` | Highest high and lowest low of the last `n` bars |
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Indicator {
+    /// Simple moving average of closes
+    Sma(usize),
+    /// Exponential moving average of closes
+    Ema(usize),
+    /// Relative strength index
+    Rsi(usize),
+    /// MACD line (fast, slow, signal)
+    Macd(usize, usize, usize),
+    /// MACD signal line
+    MacdSignal(usize, usize, usize),
+    /// MACD histogram
+    MacdHistogram(usize, usize, usize),
+    /// Stochastic %K (k period, d period)
+    StochasticK(usize, usize),
+    /// Stochastic %D
+    StochasticD(usize, usize),
+    /// Average true range
+    Atr(usize),
+    /// Standard deviation of closes
+    StdDev(usize),
+    /// Upper Bollinger Band (period, width)
+    BollingerUpper(usize, f64),
+    /// Lower Bollinger Band
+    BollingerLower(usize, f64),
+    /// Position of the close within the bands
+    PercentB(usize, f64),
+    /// Average volume
+    AvgVolume(usize),
+    /// Volume over the average of the previous bars
+    RelativeVolume(usize),
+    /// Percent change of the close
+    Change(usize),
+    /// Highest high
+    Highest(usize),
+    /// Lowest low
+    Lowest(usize),
+}
+
+/// Arguments of an indicator call with defaults for the trailing ones
+struct Args<'a> {
+    name: &'a str,
+    values: &'a [f64],
+}
+
+impl Args<'_> {
+    fn check_count(&self, min: usize, max: usize) -> std::result::Result<(), String> {
+        if self.values.len() < min || self.values.len() > max {
+            let expected = if min == max {
+                min.to_string()
+            } else {
+                format!("{} to {}", min, max)
+            };
+            return Err(format!(
+                "{} takes {} arguments, got {}",
+                self.name,
+                expected,
+                self.values.len()
+            ));
+        }
+        Ok(())
+    }
+
+    fn period(&self, index: usize, default: Option<usize>) -> std::result::Result<usize, String> {
+        match self.values.get(index) {
+            Some(&v) if v >= 1.0 && v.fract() == 0.0 => Ok(v as usize),
+            Some(&v) => Err(format!(
+                "{}: period must be a whole number of at least 1, got {}",
+                self.name, v
+            )),
+            None => default.ok_or_else(|| format!("{} needs a period", self.name)),
+        }
+    }
+
+    fn positive(&self, index: usize, default: f64) -> std::result::Result<f64, String> {
+        match self.values.get(index) {
+            Some(&v) if v.is_finite() && v > 0.0 => Ok(v),
+            Some(&v) => Err(format!("{}: width must be positive, got {}", self.name, v)),
+            None => Ok(default),
+        }
+    }
+}
+
+impl Indicator {
+    /// Indicator from an upper-case name and its arguments
+    pub fn from_call(name: &str, values: &[f64]) -> std::result::Result<Self, String> {
+        let args = Args { name, values };
+        let single = |default: Option<usize>| -> std::result::Result<usize, String> {
+            args.check_count(if default.is_some() { 0 } else { 1 }, 1)?;
+            args.period(0, default)
+        };
+        let macd = || -> std::result::Result<(usize, usize, usize), String> {
+            args.check_count(0, 3)?;
+            let (fast, slow, signal) = (
+                args.period(0, Some(12))?,
+                args.period(1, Some(26))?,
+                args.period(2, Some(9))?,
+            );
+            if fast >= slow {
+                return Err(format!(
+                    "{}: fast period must be below the slow period",
+                    name
+                ));
+            }
+            Ok((fast, slow, signal))
+        };
+        let stochastic = || -> std::result::Result<(usize, usize), String> {
+            args.check_count(0, 2)?;
+            Ok((args.period(0, Some(14))?, args.period(1, Some(3))?))
+        };
+        let bands = || -> std::result::Result<(usize, f64), String> {
+            args.check_count(1, 2)?;
+            Ok((args.period(0, None)?, args.positive(1, 2.0)?))
+        };
+
+        Ok(match name {
+            "SMA" => Indicator::Sma(single(None)?),
+            "EMA" => Indicator::Ema(single(None)?),
+            "RSI" => Indicator::Rsi(single(None)?),
+            "ATR" => Indicator::Atr(single(None)?),
+            "STDDEV" => Indicator::StdDev(single(None)?),
+            "CHANGE" => Indicator::Change(single(None)?),
+            "HIGHEST" => Indicator::Highest(single(None)?),
+            "LOWEST" => Indicator::Lowest(single(None)?),
+            "AVG_VOLUME" => Indicator::AvgVolume(single(Some(20))?),
+            "RVOL" => Indicator::RelativeVolume(single(Some(20))?),
+            "MACD" => {
+                let (f, s, g) = macd()?;
+                Indicator::Macd(f, s, g)
+            }
+            "MACD_SIGNAL" => {
+                let (f, s, g) = macd()?;
+                Indicator::MacdSignal(f, s, g)
+            }
+            "MACD_HIST" => {
+                let (f, s, g) = macd()?;
+                Indicator::MacdHistogram(f, s, g)
+            }
+            "STOCH_K" => {
+                let (k, d) = stochastic()?;
+                Indicator::StochasticK(k, d)
+            }
+            "STOCH_D" => {
+                let (k, d) = stochastic()?;
+                Indicator::StochasticD(k, d)
+            }
+            "BB_UPPER" => {
+                let (n, w) = bands()?;
+                Indicator::BollingerUpper(n, w)
+            }
+            "BB_LOWER" => {
+                let (n, w) = bands()?;
+                Indicator::BollingerLower(n, w)
+            }
+            "PERCENT_B" => {
+                let (n, w) = bands()?;
+                Indicator::PercentB(n, w)
+            }
+            _ => return Err(format!("unknown field or indicator '{}'", name)),
+        })
+    }
+
+    /// Value on the last of `bars`, oldest first
+    pub fn compute(&self, bars: &[Bar]) -> Result<f64> {
+        let closes = || bars.iter().map(|b| b.close);
+        let last = bars
+            .last()
+            .ok_or_else(|| OwlError::DataLoad("No bars".to_string()))?;
+        let value = match *self {
+            Indicator::Sma(n) => feed(
+                SimpleMovingAverage::new(n),
+                closes(),
+                |i, v| i.update(v),
+                |i| i.value(),
+            ),
+            Indicator::Ema(n) => feed(
+                ExponentialMovingAverage::new(n),
+                closes(),
+                |i, v| i.update(v),
+                |i| i.value(),
+            ),
+            Indicator::Rsi(n) => feed(
+                RelativeStrengthIndex::new(n),
+                closes(),
+                |i, v| i.update(v),
+                |i| i.value(),
+            ),
+            Indicator::StdDev(n) => feed(
+                StandardDeviation::new(n),
+                closes(),
+                |i, v| i.update(v),
+                |i| i.value(),
+            ),
+            Indicator::Macd(f, s, g) => feed(
+                Macd::new(f, s, g),
+                closes(),
+                |i, v| i.update(v),
+                |i| i.macd_value(),
+            ),
+            Indicator::MacdSignal(f, s, g) => feed(
+                Macd::new(f, s, g),
+                closes(),
+                |i, v| i.update(v),
+                |i| i.signal_value(),
+            ),
+            Indicator::MacdHistogram(f, s, g) => feed(
+                Macd::new(f, s, g),
+                closes(),
+                |i, v| i.update(v),
+                |i| i.histogram(),
+            ),
+            Indicator::StochasticK(k, d) => feed(
+                StochasticOscillator::new(k, d),
+                bars.iter(),
+                |i, b| i.update(b.high, b.low, b.close),
+                |i| i.k_value(),
+            ),
+            Indicator::StochasticD(k, d) => feed(
+                StochasticOscillator::new(k, d),
+                bars.iter(),
+                |i, b| i.update(b.high, b.low, b.close),
+                |i| i.d_value(),
+            ),
+            Indicator::Atr(n) => feed(
+                AverageTrueRange::new(n),
+                bars.iter(),
+                |i, b| i.update(b.high, b.low, b.close),
+                |i| i.value(),
+            ),
+            Indicator::BollingerUpper(n, w) => feed(
+                BollingerBands::new(n, w),
+                closes(),
+                |i, v| i.update(v),
+                |i| i.upper_band(),
+            ),
+            Indicator::BollingerLower(n, w) => feed(
+                BollingerBands::new(n, w),
+                closes(),
+                |i, v| i.update(v),
+                |i| i.lower_band(),
+            ),
+            Indicator::PercentB(n, w) => feed(
+                BollingerBands::new(n, w),
+                closes(),
+                |i, v| i.update(v),
+                |i| i.percent_b(last.close),
+            ),
+            Indicator::AvgVolume(n) => feed(
+                VolumeMovingAverage::new(n),
+                bars.iter().map(|b| b.volume),
+                |i, v| i.update(v),
+                |i| i.value(),
+            ),
+            Indicator::RelativeVolume(n) => {
+                let previous = &bars[..bars.len() - 1];
+                feed(
+                    VolumeMovingAverage::new(n),
+                    previous.iter().map(|b| b.volume),
+                    |i, v| i.update(v),
+                    |i| i.value(),
+                )
+                .map(|average| last.volume / average)
+            }
+            Indicator::Change(n) => {
+                let past = bars
+                    .len()
+                    .checked_sub(n + 1)
+                    .map(|i| bars[i].close)
+                    .ok_or_else(|| insufficient(self, n + 1, bars.len()))?;
+                Ok((last.close / past - 1.0) * 100.0)
+            }
+            Indicator::Highest(n) | Indicator::Lowest(n) => {
+                if bars.len() < n {
+                    return Err(insufficient(self, n, bars.len()));
+                }
+                let window = &bars[bars.len() - n..];
+                Ok(if matches!(self, Indicator::Highest(_)) {
+                    window.iter().map(|b| b.high).fold(f64::MIN, f64::max)
+                } else {
+                    window.iter().map(|b| b.low).fold(f64::MAX, f64::min)
+                })
+            }
+        };
+        value.map_err(|e| OwlError::Strategy(format!("{}: {}", self, e)))
+    }
+}
+
+fn insufficient(indicator: &Indicator, needed: usize, available: usize) -> OwlError {
+    OwlError::Strategy(format!(
+        "{}: needs {} bars, have {}",
+        indicator, needed, available
+    ))
+}
+
+/// Run an incremental `trade_math` indicator over `inputs` and read it
+fn feed<I, T>(
+    indicator: std::result::Result<I, MathError>,
+    inputs: impl Iterator<Item = T>,
+    update: impl Fn(&mut I, T) -> std::result::Result<(), MathError>,
+    read: impl Fn(&I) -> std::result::Result<f64, MathError>,
+) -> Result<f64> {
+    let mut indicator = indicator.map_err(|e| OwlError::InvalidConfig(e.to_string()))?;
+    for input in inputs {
+        update(&mut indicator, input).map_err(|e| OwlError::Strategy(e.to_string()))?;
+    }
+    read(&indicator).map_err(|e| OwlError::Strategy(e.to_string()))
+}
+
+impl fmt::Display for Indicator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Indicator::Sma(n) => write!(f, "SMA({})", n),
+            Indicator::Ema(n) => write!(f, "EMA({})", n),
+            Indicator::Rsi(n) => write!(f, "RSI({})", n),
+            Indicator::Macd(a, b, c) => write!(f, "MACD({}, {}, {})", a, b, c),
+            Indicator::MacdSignal(a, b, c) => write!(f, "MACD_SIGNAL({}, {}, {})", a, b, c),
+            Indicator::MacdHistogram(a, b, c) => write!(f, "MACD_HIST({}, {}, {})", a, b, c),
+            Indicator::StochasticK(k, d) => write!(f, "STOCH_K({}, {})", k, d),
+            Indicator::StochasticD(k, d) => write!(f, "STOCH_D({}, {})", k, d),
+            Indicator::Atr(n) => write!(f, "ATR({})", n),
+            Indicator::StdDev(n) => write!(f, "STDDEV({})", n),
+            Indicator::BollingerUpper(n, w) => write!(f, "BB_UPPER({}, {})", n, w),
+            Indicator::BollingerLower(n, w) => write!(f, "BB_LOWER({}, {})", n, w),
+            Indicator::PercentB(n, w) => write!(f, "PERCENT_B({}, {})", n, w),
+            Indicator::AvgVolume(n) => write!(f, "AVG_VOLUME({})", n),
+            Indicator::RelativeVolume(n) => write!(f, "RVOL({})", n),
+            Indicator::Change(n) => write!(f, "CHANGE({})", n),
+            Indicator::Highest(n) => write!(f, "HIGHEST({})", n),
+            Indicator::Lowest(n) => write!(f, "LOWEST({})", n),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn bars(closes: &[f64]) -> Vec<Bar> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, &close)| Bar {
+                timestamp: start + Duration::days(i as i64),
+                open: close,
+                high: close + 1.0,
+                low: close - 1.0,
+                close,
+                volume: 1000.0 * (i + 1) as f64,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_indicator_values() {
+        let data = bars(&[10.0, 11.0, 12.0, 13.0, 14.0]);
+        let value = |name: &str, args: &[f64]| {
+            Indicator::from_call(name, args)
+                .unwrap()
+                .compute(&data)
+                .unwrap()
+        };
+        assert_eq!(value("SMA", &[3.0]), 13.0);
+        assert_eq!(value("AVG_VOLUME", &[2.0]), 4500.0);
+        assert_eq!(value("RVOL", &[2.0]), 5000.0 / 3500.0);
+        assert!((value("CHANGE", &[4.0]) - 40.0).abs() < 1e-9);
+        assert_eq!(value("HIGHEST", &[2.0]), 15.0);
+        assert_eq!(value("LOWEST", &[5.0]), 9.0);
+        assert_eq!(value("RSI", &[3.0]), 100.0);
+
+        let too_long = Indicator::from_call("SMA", &[10.0]).unwrap();
+        assert!(too_long.compute(&data).is_err());
+        assert!(Indicator::Change(5).compute(&data).is_err());
+        assert!(Indicator::from_call("MACD", &[26.0, 12.0]).is_err());
+        assert!(Indicator::from_call("SMA", &[]).is_err());
+        assert!(Indicator::from_call("RSI", &[14.0, 3.0]).is_err());
+        assert_eq!(
+            Indicator::from_call("BB_UPPER", &[20.0])
+                .unwrap()
+                .to_string(),
+            "BB_UPPER(20, 2)"
+        );
+    }
+}
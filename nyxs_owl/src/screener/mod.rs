@@ -0,0 +1,346 @@
+//! Cross-sectional screening
+//!
+//! A [`Screener`] evaluates filter expressions such as
+//! `RSI(14) < 30 AND close > SMA(200) AND avg volume > 1M` on the latest
+//! daily bar of every symbol in a universe, ranks the symbols that pass by
+//! another expression and keeps the top N. Expressions combine the bar
+//! fields and `trade_math` indicators listed on [`Indicator`] with
+//! arithmetic, comparisons and `AND`, `OR` and `NOT`; numbers may carry a
+//! `K`, `M` or `B` suffix.
+//!
+//! The matches convert into a [`Watchlist`] for a backtest or the nightly
+//! Bubo scan. A symbol whose data cannot be loaded or is too short for an
+//! indicator is listed as skipped.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use nyxs_owl::data::{MarketDataStore, Watchlist};
+//! use nyxs_owl::screener::{RankOrder, Screener};
+//!
+//! let screener = Screener::new()
+//!     .with_filter("RSI(14) < 30 AND close > SMA(200) AND avg volume > 1M")
+//!     .unwrap()
+//!     .with_rank("RSI(14)", RankOrder::Lowest)
+//!     .unwrap()
+//!     .with_top(10);
+//!
+//! let universe = Watchlist::from_file("universe.txt").unwrap();
+//! let result = screener.screen_store(&MarketDataStore::new("data"), &universe, 300);
+//! result.watchlist().unwrap().write("oversold.txt").unwrap();
+//! ```
+
+mod expr;
+mod indicators;
+
+pub use expr::{ArithOp, CompareOp, Expr};
+pub use indicators::{Field, Indicator};
+
+use crate::bubo::SkippedSymbol;
+use crate::data::{Bar, MarketDataStore, Watchlist};
+use crate::error::{OwlError, Result};
+use expr::Context;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Which end of the ranking comes first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RankOrder {
+    /// Largest values first
+    #[default]
+    Highest,
+    /// Smallest values first
+    Lowest,
+}
+
+/// Filters, ranking and result size of a screen
+#[derive(Debug, Clone, Default)]
+pub struct Screener {
+    filters: Vec<Expr>,
+    rank: Option<(Expr, RankOrder)>,
+    top: Option<usize>,
+    columns: Vec<(String, Expr)>,
+}
+
+impl Screener {
+    /// A screen that passes every symbol
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require `condition` to hold; several filters must all hold
+    pub fn with_filter(mut self, condition: &str) -> Result<Self> {
+        let filter = Expr::parse_condition(condition)?;
+        self.add_columns(&filter)?;
+        self.filters.push(filter);
+        Ok(self)
+    }
+
+    /// Rank the matches by the value of `expression`
+    pub fn with_rank(mut self, expression: &str, order: RankOrder) -> Result<Self> {
+        let rank = Expr::parse_value(expression)?;
+        self.add_columns(&rank)?;
+        self.rank = Some((rank, order));
+        Ok(self)
+    }
+
+    /// Keep at most `top` matches
+    pub fn with_top(mut self, top: usize) -> Self {
+        self.top = Some(top);
+        self
+    }
+
+    /// Filter conditions
+    pub fn filters(&self) -> &[Expr] {
+        &self.filters
+    }
+
+    /// Ranking expression and order
+    pub fn rank(&self) -> Option<(&Expr, RankOrder)> {
+        self.rank.as_ref().map(|(expr, order)| (expr, *order))
+    }
+
+    /// Names of the fields and indicators reported with each match
+    pub fn columns(&self) -> Vec<&str> {
+        self.columns.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    fn add_columns(&mut self, expr: &Expr) -> Result<()> {
+        for term in expr.terms() {
+            if !self.columns.iter().any(|(name, _)| *name == term) {
+                let value = Expr::parse_value(&term)?;
+                self.columns.push((term, value));
+            }
+        }
+        Ok(())
+    }
+
+    /// Screen the daily bars of each symbol, oldest first
+    pub fn screen<'a, S, I>(&self, universe: I) -> ScreenResult
+    where
+        S: Into<String>,
+        I: IntoIterator<Item = (S, &'a [Bar])>,
+    {
+        let mut result = ScreenResult::default();
+        for (symbol, bars) in universe {
+            let symbol = symbol.into();
+            result.screened += 1;
+            match self.evaluate(&symbol, bars) {
+                Ok(Some(found)) => result.matches.push(found),
+                Ok(None) => {}
+                Err(e) => result.skipped.push(SkippedSymbol {
+                    symbol,
+                    reason: e.to_string(),
+                }),
+            }
+        }
+
+        if let Some((_, order)) = &self.rank {
+            result.matches.sort_by(|a, b| {
+                let (a_value, b_value) = (a.rank_value.unwrap_or(0.0), b.rank_value.unwrap_or(0.0));
+                match order {
+                    RankOrder::Highest => b_value.total_cmp(&a_value),
+                    RankOrder::Lowest => a_value.total_cmp(&b_value),
+                }
+                .then_with(|| a.symbol.cmp(&b.symbol))
+            });
+        } else {
+            result.matches.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+        }
+        if let Some(top) = self.top {
+            result.matches.truncate(top);
+        }
+        result
+    }
+
+    /// Screen every symbol of `universe` with at most `lookback` daily bars
+    /// from `store`
+    pub fn screen_store(
+        &self,
+        store: &MarketDataStore,
+        universe: &Watchlist,
+        lookback: usize,
+    ) -> ScreenResult {
+        let mut loaded = Vec::new();
+        let mut skipped = Vec::new();
+        for symbol in universe.symbols() {
+            match store.load(symbol, lookback) {
+                Ok(data) => loaded.push((symbol.clone(), data)),
+                Err(e) => skipped.push(SkippedSymbol {
+                    symbol: symbol.clone(),
+                    reason: e.to_string(),
+                }),
+            }
+        }
+
+        let mut result = self.screen(
+            loaded
+                .iter()
+                .map(|(symbol, data)| (symbol.as_str(), data.daily())),
+        );
+        result.screened += skipped.len();
+        result.skipped.extend(skipped);
+        result
+    }
+
+    /// Evaluate one symbol; `Ok(None)` when a filter rejects it
+    fn evaluate(&self, symbol: &str, bars: &[Bar]) -> Result<Option<ScreenMatch>> {
+        let last = bars
+            .last()
+            .ok_or_else(|| OwlError::DataLoad("No daily bars".to_string()))?;
+        let mut context = Context::new(bars);
+        for filter in &self.filters {
+            if !filter.holds(&mut context)? {
+                return Ok(None);
+            }
+        }
+
+        let rank_value = match &self.rank {
+            Some((rank, _)) => {
+                let value = rank.value(&mut context)?;
+                if !value.is_finite() {
+                    return Err(OwlError::Strategy(format!("{} is not a number", rank)));
+                }
+                Some(value)
+            }
+            None => None,
+        };
+        // A column may be unevaluated when an OR short-circuits; it is left
+        // out rather than failing a symbol that passed
+        let values = self
+            .columns
+            .iter()
+            .filter_map(|(name, expr)| {
+                expr.value(&mut context)
+                    .ok()
+                    .map(|value| (name.clone(), value))
+            })
+            .collect();
+
+        Ok(Some(ScreenMatch {
+            symbol: symbol.to_string(),
+            date: last.timestamp.date_naive(),
+            close: last.close,
+            rank_value,
+            values,
+        }))
+    }
+}
+
+/// A symbol that passed the screen
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScreenMatch {
+    /// Symbol
+    pub symbol: String,
+    /// Date of the latest bar
+    pub date: chrono::NaiveDate,
+    /// Latest close
+    pub close: f64,
+    /// Value of the ranking expression
+    pub rank_value: Option<f64>,
+    /// Values of the fields and indicators in the filters and ranking
+    pub values: BTreeMap<String, f64>,
+}
+
+/// Outcome of a screen
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ScreenResult {
+    /// Number of symbols screened, including skipped ones
+    pub screened: usize,
+    /// Matches in rank order
+    pub matches: Vec<ScreenMatch>,
+    /// Symbols that could not be evaluated
+    pub skipped: Vec<SkippedSymbol>,
+}
+
+impl ScreenResult {
+    /// Matched symbols in rank order
+    pub fn symbols(&self) -> Vec<&str> {
+        self.matches.iter().map(|m| m.symbol.as_str()).collect()
+    }
+
+    /// Matched symbols as a watchlist; an error when nothing matched
+    pub fn watchlist(&self) -> Result<Watchlist> {
+        Watchlist::new(self.symbols())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn bars(start: f64, step: f64, volume: f64, count: usize) -> Vec<Bar> {
+        let first = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        (0..count)
+            .map(|i| {
+                let close = start + step * i as f64;
+                Bar {
+                    timestamp: first + Duration::days(i as i64),
+                    open: close,
+                    high: close + 0.5,
+                    low: close - 0.5,
+                    close,
+                    volume,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_screen_filters_and_ranks() {
+        let rising = bars(50.0, 0.5, 2_000_000.0, 60);
+        let steep = bars(20.0, 1.0, 3_000_000.0, 60);
+        let thin = bars(50.0, 0.5, 10_000.0, 60);
+        let falling = bars(100.0, -0.5, 2_000_000.0, 60);
+        let short = bars(50.0, 0.5, 2_000_000.0, 5);
+        let universe = [
+            ("RISE", rising.as_slice()),
+            ("STEEP", steep.as_slice()),
+            ("THIN", thin.as_slice()),
+            ("FALL", falling.as_slice()),
+            ("NEW", short.as_slice()),
+        ];
+
+        let screener = Screener::new()
+            .with_filter("close > SMA(20) AND avg volume > 1M")
+            .unwrap()
+            .with_rank("CHANGE(10)", RankOrder::Highest)
+            .unwrap();
+        let result = screener.screen(universe);
+
+        assert_eq!(result.screened, 5);
+        assert_eq!(result.symbols(), ["STEEP", "RISE"]);
+        assert_eq!(result.skipped.len(), 1);
+        assert_eq!(result.skipped[0].symbol, "NEW");
+        let top = &result.matches[0];
+        assert_eq!(top.close, 79.0);
+        assert_eq!(top.values["AVG_VOLUME(20)"], 3_000_000.0);
+        assert!(top.rank_value.unwrap() > result.matches[1].rank_value.unwrap());
+        assert_eq!(
+            screener.columns(),
+            ["CLOSE", "SMA(20)", "AVG_VOLUME(20)", "CHANGE(10)"]
+        );
+
+        let lowest = screener
+            .clone()
+            .with_rank("CHANGE(10)", RankOrder::Lowest)
+            .unwrap()
+            .with_top(1)
+            .screen(universe);
+        assert_eq!(lowest.symbols(), ["RISE"]);
+        assert_eq!(lowest.watchlist().unwrap().symbols(), ["RISE"]);
+
+        let none = Screener::new()
+            .with_filter("close < 0")
+            .unwrap()
+            .screen(universe);
+        assert!(none.matches.is_empty());
+        assert!(none.watchlist().is_err());
+        assert!(Screener::new().with_filter("SMA(20)").is_err());
+        assert!(Screener::new()
+            .with_rank("close > 1", RankOrder::Highest)
+            .is_err());
+    }
+}
@@ -0,0 +1,163 @@
+//! Sector, industry and benchmark of each symbol
+
+use crate::error::{OwlError, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Classification of one symbol
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SymbolInfo {
+    /// Symbol, upper-case
+    pub symbol: String,
+    /// Sector, such as `Technology`
+    pub sector: String,
+    /// Industry within the sector; empty when unknown
+    pub industry: String,
+    /// Benchmark the symbol is measured against, when it has its own
+    pub benchmark: Option<String>,
+}
+
+impl SymbolInfo {
+    /// Classify `symbol` in `sector`
+    pub fn new(symbol: &str, sector: &str) -> Self {
+        Self {
+            symbol: symbol.trim().to_uppercase(),
+            sector: sector.trim().to_string(),
+            industry: String::new(),
+            benchmark: None,
+        }
+    }
+
+    /// Set the industry
+    pub fn with_industry(mut self, industry: &str) -> Self {
+        self.industry = industry.trim().to_string();
+        self
+    }
+
+    /// Measure the symbol against `benchmark`
+    pub fn with_benchmark(mut self, benchmark: &str) -> Self {
+        self.benchmark = Some(benchmark.trim().to_uppercase()).filter(|b| !b.is_empty());
+        self
+    }
+}
+
+/// Classifications of a universe of symbols
+///
+/// Files are CSV with a header row naming at least `symbol` and `sector`
+/// columns; `industry` and `benchmark` are optional:
+///
+/// ```text
+/// symbol,sector,industry,benchmark
+/// AAPL,Technology,Consumer Electronics,QQQ
+/// XOM,Energy,Oil & Gas Integrated,
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SymbolMetadata {
+    symbols: BTreeMap<String, SymbolInfo>,
+}
+
+impl SymbolMetadata {
+    /// Create metadata from classifications; a later entry for the same
+    /// symbol replaces an earlier one
+    pub fn new<I: IntoIterator<Item = SymbolInfo>>(infos: I) -> Self {
+        Self {
+            symbols: infos
+                .into_iter()
+                .map(|info| (info.symbol.clone(), info))
+                .collect(),
+        }
+    }
+
+    /// Parse metadata from CSV text
+    pub fn parse(contents: &str) -> Result<Self> {
+        let mut lines = contents
+            .lines()
+            .enumerate()
+            .filter(|(_, l)| !l.trim().is_empty());
+        let (_, header) = lines
+            .next()
+            .ok_or_else(|| OwlError::DataLoad("Empty metadata file".to_string()))?;
+        let columns: Vec<String> = header.split(',').map(|c| c.trim().to_lowercase()).collect();
+        let find = |name: &str| columns.iter().position(|c| c == name);
+        let symbol = find("symbol").ok_or_else(|| {
+            OwlError::DataLoad("Missing 'symbol' column in metadata header".to_string())
+        })?;
+        let sector = find("sector").ok_or_else(|| {
+            OwlError::DataLoad("Missing 'sector' column in metadata header".to_string())
+        })?;
+        let (industry, benchmark) = (find("industry"), find("benchmark"));
+
+        let mut infos = Vec::new();
+        for (i, line) in lines {
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let field = |index: Option<usize>| index.and_then(|i| fields.get(i)).copied();
+            let (Some(name), Some(sector)) = (field(Some(symbol)), field(Some(sector))) else {
+                return Err(OwlError::DataLoad(format!(
+                    "Missing symbol or sector at line {}",
+                    i + 1
+                )));
+            };
+            if name.is_empty() || sector.is_empty() {
+                return Err(OwlError::DataLoad(format!(
+                    "Empty symbol or sector at line {}",
+                    i + 1
+                )));
+            }
+            let mut info =
+                SymbolInfo::new(name, sector).with_industry(field(industry).unwrap_or_default());
+            if let Some(benchmark) = field(benchmark) {
+                info = info.with_benchmark(benchmark);
+            }
+            infos.push(info);
+        }
+        Ok(Self::new(infos))
+    }
+
+    /// Load a metadata file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Classification of `symbol`
+    pub fn get(&self, symbol: &str) -> Option<&SymbolInfo> {
+        self.symbols.get(&symbol.trim().to_uppercase())
+    }
+
+    /// Classifications in symbol order
+    pub fn iter(&self) -> impl Iterator<Item = &SymbolInfo> {
+        self.symbols.values()
+    }
+
+    /// Number of symbols
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    /// Whether no symbol is classified
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    /// Distinct sectors in alphabetical order
+    pub fn sectors(&self) -> Vec<&str> {
+        let mut sectors: Vec<&str> = self.iter().map(|i| i.sector.as_str()).collect();
+        sectors.sort_unstable();
+        sectors.dedup();
+        sectors
+    }
+
+    /// Symbols in `sector`, compared without regard to case
+    pub fn members(&self, sector: &str) -> Vec<&str> {
+        self.iter()
+            .filter(|i| i.sector.eq_ignore_ascii_case(sector))
+            .map(|i| i.symbol.as_str())
+            .collect()
+    }
+
+    /// Only the symbols on `symbols`; unknown symbols are ignored
+    pub fn restricted_to(&self, symbols: &[String]) -> Self {
+        Self::new(symbols.iter().filter_map(|s| self.get(s).cloned()))
+    }
+}
@@ -0,0 +1,423 @@
+//! Sector and industry flow
+//!
+//! [`SymbolMetadata`] classifies symbols by sector and industry and names
+//! the benchmark each is measured against. With the daily bars of a
+//! universe loaded into a [`SectorUniverse`], a [`SectorAnalyzer`] reports
+//! how each sector and symbol performed against its benchmark, how each
+//! symbol did against its own sector and how broadly each sector's members
+//! took part: the share above a moving average and the advancers and
+//! decliners on the latest day.
+//!
+//! A sector's prices are an [`equal_weight_index`] of its members, so a
+//! metadata file listing sector ETFs, each as the only member of its
+//! sector, analyzes the ETFs themselves. [`SectorRotation`] backtests
+//! holding the strongest sectors on a rebalance schedule.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use nyxs_owl::data::MarketDataStore;
+//! use nyxs_owl::sectors::{SectorAnalyzer, SectorRotation, SectorUniverse, SymbolMetadata};
+//!
+//! let metadata = SymbolMetadata::from_file("sectors.csv").unwrap();
+//! let universe = SectorUniverse::load(metadata, &MarketDataStore::new("data"), 300, Some("SPY"));
+//!
+//! let report = SectorAnalyzer::default().with_benchmark("SPY").analyze(&universe);
+//! for sector in &report.sectors {
+//!     println!("{} {:?}", sector.sector, sector.vs_benchmark);
+//! }
+//!
+//! let rotation = SectorRotation::default().run(&universe.sector_indices()).unwrap();
+//! println!("{:.1}%", rotation.total_return);
+//! ```
+
+mod metadata;
+mod rotation;
+mod strength;
+
+pub use metadata::{SymbolInfo, SymbolMetadata};
+pub use rotation::{Rebalance, RebalanceSchedule, RotationResult, SectorRotation};
+pub use strength::{
+    closes, equal_weight_index, relative_strength, relative_strength_line, RelativeStrength,
+    SectorBreadth,
+};
+
+use crate::bubo::SkippedSymbol;
+use crate::data::{Bar, MarketDataStore};
+use crate::error::{OwlError, Result};
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Daily closes of a classified universe and its benchmarks
+#[derive(Debug, Clone, Default)]
+pub struct SectorUniverse {
+    metadata: SymbolMetadata,
+    closes: BTreeMap<String, Vec<(NaiveDate, f64)>>,
+    skipped: Vec<SkippedSymbol>,
+}
+
+impl SectorUniverse {
+    /// A universe without prices
+    pub fn new(metadata: SymbolMetadata) -> Self {
+        Self {
+            metadata,
+            ..Self::default()
+        }
+    }
+
+    /// Add the daily bars of a symbol or benchmark
+    pub fn with_bars(mut self, symbol: &str, bars: &[Bar]) -> Self {
+        self.closes
+            .insert(symbol.trim().to_uppercase(), closes(bars));
+        self
+    }
+
+    /// Load at most `lookback` daily bars of every classified symbol, their
+    /// benchmarks and `benchmark`; symbols that fail to load are skipped
+    pub fn load(
+        metadata: SymbolMetadata,
+        store: &MarketDataStore,
+        lookback: usize,
+        benchmark: Option<&str>,
+    ) -> Self {
+        let mut symbols: Vec<String> = metadata.iter().map(|i| i.symbol.clone()).collect();
+        symbols.extend(metadata.iter().filter_map(|i| i.benchmark.clone()));
+        symbols.extend(benchmark.map(|b| b.trim().to_uppercase()));
+        symbols.sort();
+        symbols.dedup();
+
+        let mut universe = Self::new(metadata);
+        for symbol in symbols {
+            match store.load(&symbol, lookback) {
+                Ok(data) => {
+                    universe.closes.insert(symbol, closes(data.daily()));
+                }
+                Err(e) => universe.skipped.push(SkippedSymbol {
+                    symbol,
+                    reason: e.to_string(),
+                }),
+            }
+        }
+        universe
+    }
+
+    /// The classifications
+    pub fn metadata(&self) -> &SymbolMetadata {
+        &self.metadata
+    }
+
+    /// Daily closes of `symbol`, if loaded
+    pub fn closes(&self, symbol: &str) -> Option<&[(NaiveDate, f64)]> {
+        self.closes
+            .get(&symbol.trim().to_uppercase())
+            .map(Vec::as_slice)
+    }
+
+    /// Symbols that could not be loaded
+    pub fn skipped(&self) -> &[SkippedSymbol] {
+        &self.skipped
+    }
+
+    /// Loaded members of `sector`
+    fn member_closes(&self, sector: &str) -> Vec<(&str, &[(NaiveDate, f64)])> {
+        self.metadata
+            .members(sector)
+            .into_iter()
+            .filter_map(|s| self.closes(s).map(|c| (s, c)))
+            .collect()
+    }
+
+    /// Equal-weight index of the loaded members of `sector`, `None` when
+    /// none is loaded
+    pub fn sector_index(&self, sector: &str) -> Option<Vec<(NaiveDate, f64)>> {
+        let members: Vec<&[(NaiveDate, f64)]> = self
+            .member_closes(sector)
+            .into_iter()
+            .map(|m| m.1)
+            .collect();
+        Some(equal_weight_index(&members)).filter(|index| !index.is_empty())
+    }
+
+    /// Index of every sector with loaded members
+    pub fn sector_indices(&self) -> BTreeMap<String, Vec<(NaiveDate, f64)>> {
+        self.metadata
+            .sectors()
+            .into_iter()
+            .filter_map(|s| self.sector_index(s).map(|index| (s.to_string(), index)))
+            .collect()
+    }
+}
+
+/// Performance and breadth of one sector
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SectorSummary {
+    /// Sector
+    pub sector: String,
+    /// Rank by return, 1 for the strongest
+    pub rank: usize,
+    /// Return of the sector index over the period, in percent
+    pub period_return: f64,
+    /// Relative strength against the benchmark, in percent
+    pub vs_benchmark: Option<f64>,
+    /// Participation of the members
+    pub breadth: SectorBreadth,
+}
+
+/// Performance of one symbol against its benchmark and its sector
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SymbolStrength {
+    /// Symbol
+    pub symbol: String,
+    /// Sector
+    pub sector: String,
+    /// Industry
+    pub industry: String,
+    /// Return over the period, in percent
+    pub period_return: f64,
+    /// Benchmark the symbol was measured against
+    pub benchmark: Option<String>,
+    /// Relative strength against the benchmark, in percent
+    pub vs_benchmark: Option<f64>,
+    /// Relative strength against the other members of its sector, in
+    /// percent; `None` for a sector of one
+    pub vs_sector: Option<f64>,
+}
+
+/// Sector flow over a period
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SectorReport {
+    /// Trading days the returns cover
+    pub period: usize,
+    /// Sectors, strongest first
+    pub sectors: Vec<SectorSummary>,
+    /// Symbols, strongest against their sector first
+    pub symbols: Vec<SymbolStrength>,
+    /// Symbols left out and why
+    pub skipped: Vec<SkippedSymbol>,
+}
+
+/// Relative strength and breadth of the sectors of a universe
+#[derive(Debug, Clone, PartialEq)]
+pub struct SectorAnalyzer {
+    period: usize,
+    ma_period: usize,
+    benchmark: Option<String>,
+}
+
+impl Default for SectorAnalyzer {
+    /// Returns over 63 trading days and breadth against the 50-day average,
+    /// without a default benchmark
+    fn default() -> Self {
+        Self {
+            period: 63,
+            ma_period: 50,
+            benchmark: None,
+        }
+    }
+}
+
+impl SectorAnalyzer {
+    /// Measure returns over `period` trading days
+    pub fn with_period(mut self, period: usize) -> Result<Self> {
+        if period == 0 {
+            return Err(OwlError::InvalidConfig(
+                "Period must be at least one day".to_string(),
+            ));
+        }
+        self.period = period;
+        Ok(self)
+    }
+
+    /// Count members above their `ma_period`-day moving average
+    pub fn with_ma_period(mut self, ma_period: usize) -> Result<Self> {
+        if ma_period < 2 {
+            return Err(OwlError::InvalidConfig(
+                "Moving average period must be at least 2".to_string(),
+            ));
+        }
+        self.ma_period = ma_period;
+        Ok(self)
+    }
+
+    /// Measure sectors, and symbols without a benchmark of their own,
+    /// against `benchmark`
+    pub fn with_benchmark(mut self, benchmark: &str) -> Self {
+        self.benchmark = Some(benchmark.trim().to_uppercase());
+        self
+    }
+
+    /// Analyze the sectors and symbols of `universe`
+    pub fn analyze(&self, universe: &SectorUniverse) -> SectorReport {
+        let mut skipped = universe.skipped().to_vec();
+        let benchmark = self.benchmark.as_deref().and_then(|b| universe.closes(b));
+        let vs = |closes: &[(NaiveDate, f64)], against: Option<&[(NaiveDate, f64)]>| {
+            against
+                .and_then(|b| relative_strength(closes, b, self.period).ok())
+                .map(|rs| rs.relative * 100.0)
+        };
+
+        let mut sectors = Vec::new();
+        for sector in universe.metadata().sectors() {
+            let members = universe.member_closes(sector);
+            let series: Vec<&[(NaiveDate, f64)]> = members.iter().map(|m| m.1).collect();
+            let index = equal_weight_index(&series);
+            let Some(period_return) = period_return(&index, self.period) else {
+                continue;
+            };
+            sectors.push(SectorSummary {
+                sector: sector.to_string(),
+                rank: 0,
+                period_return,
+                vs_benchmark: vs(&index, benchmark),
+                breadth: SectorBreadth::measure(&series, self.ma_period),
+            });
+        }
+        sectors.sort_by(|a, b| {
+            b.period_return
+                .total_cmp(&a.period_return)
+                .then_with(|| a.sector.cmp(&b.sector))
+        });
+        for (i, sector) in sectors.iter_mut().enumerate() {
+            sector.rank = i + 1;
+        }
+
+        let mut symbols = Vec::new();
+        for info in universe.metadata().iter() {
+            let Some(closes) = universe.closes(&info.symbol) else {
+                continue;
+            };
+            let Some(period_return) = period_return(closes, self.period) else {
+                skipped.push(SkippedSymbol {
+                    symbol: info.symbol.clone(),
+                    reason: format!("Fewer than {} daily bars", self.period + 1),
+                });
+                continue;
+            };
+            let benchmark_name = info.benchmark.clone().or_else(|| self.benchmark.clone());
+            let peers: Vec<&[(NaiveDate, f64)]> = universe
+                .member_closes(&info.sector)
+                .into_iter()
+                .filter(|m| m.0 != info.symbol)
+                .map(|m| m.1)
+                .collect();
+            let sector_index = equal_weight_index(&peers);
+
+            symbols.push(SymbolStrength {
+                symbol: info.symbol.clone(),
+                sector: info.sector.clone(),
+                industry: info.industry.clone(),
+                period_return,
+                vs_benchmark: vs(
+                    closes,
+                    benchmark_name.as_deref().and_then(|b| universe.closes(b)),
+                ),
+                benchmark: benchmark_name,
+                vs_sector: vs(
+                    closes,
+                    Some(&sector_index)
+                        .filter(|i| !i.is_empty())
+                        .map(Vec::as_slice),
+                ),
+            });
+        }
+        symbols.sort_by(|a, b| {
+            let key = |s: &SymbolStrength| s.vs_sector.unwrap_or(f64::NEG_INFINITY);
+            key(b)
+                .total_cmp(&key(a))
+                .then_with(|| a.symbol.cmp(&b.symbol))
+        });
+
+        SectorReport {
+            period: self.period,
+            sectors,
+            symbols,
+            skipped,
+        }
+    }
+}
+
+/// Return over the last `period` closes, in percent
+fn period_return(closes: &[(NaiveDate, f64)], period: usize) -> Option<f64> {
+    let end = closes.last()?.1;
+    let start = closes.len().checked_sub(period + 1).map(|i| closes[i].1)?;
+    Some((end / start - 1.0) * 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn bars(daily: f64, days: usize) -> Vec<Bar> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        (0..days)
+            .map(|i| {
+                let close = 100.0 * (1.0 + daily).powi(i as i32);
+                Bar {
+                    timestamp: start + Duration::days(i as i64),
+                    open: close,
+                    high: close,
+                    low: close,
+                    close,
+                    volume: 1000.0,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_metadata_and_sector_report() {
+        let metadata = SymbolMetadata::parse(
+            "Symbol,Sector,Industry,Benchmark\n\
+             aaa,Tech,Software,QQQ\n\
+             bbb,Tech,Hardware,\n\
+             ccc,Energy,Oil,\n\
+             ddd,Energy,Gas,\n",
+        )
+        .unwrap();
+        assert_eq!(metadata.sectors(), ["Energy", "Tech"]);
+        assert_eq!(metadata.members("tech"), ["AAA", "BBB"]);
+        assert_eq!(
+            metadata.get("AAA").unwrap().benchmark.as_deref(),
+            Some("QQQ")
+        );
+        assert!(SymbolMetadata::parse("symbol,industry\nAAA,Software\n").is_err());
+        assert!(SymbolMetadata::parse("symbol,sector\nAAA,\n").is_err());
+
+        let universe = SectorUniverse::new(metadata)
+            .with_bars("AAA", &bars(0.004, 40))
+            .with_bars("BBB", &bars(0.002, 40))
+            .with_bars("CCC", &bars(-0.001, 40))
+            .with_bars("DDD", &bars(0.0, 5))
+            .with_bars("SPY", &bars(0.001, 40))
+            .with_bars("QQQ", &bars(0.005, 40));
+        let report = SectorAnalyzer::default()
+            .with_period(20)
+            .unwrap()
+            .with_ma_period(10)
+            .unwrap()
+            .with_benchmark("spy")
+            .analyze(&universe);
+
+        assert_eq!(report.sectors.len(), 2);
+        let tech = &report.sectors[0];
+        assert_eq!((tech.sector.as_str(), tech.rank), ("Tech", 1));
+        assert!(tech.vs_benchmark.unwrap() > 0.0);
+        assert_eq!(tech.breadth.percent_above_ma, Some(100.0));
+        // DDD's five bars leave the Energy index to CCC
+        assert!(report.sectors[1].vs_benchmark.unwrap() < 0.0);
+        assert_eq!(report.sectors[1].breadth.measured, 1);
+
+        let aaa = report.symbols.iter().find(|s| s.symbol == "AAA").unwrap();
+        assert_eq!(aaa.benchmark.as_deref(), Some("QQQ"));
+        assert!(aaa.vs_benchmark.unwrap() < 0.0);
+        assert!(aaa.vs_sector.unwrap() > 0.0);
+        assert_eq!(report.symbols[0].symbol, "AAA");
+        assert_eq!(report.skipped[0].symbol, "DDD");
+
+        let indices = universe.sector_indices();
+        assert_eq!(indices["Tech"].len(), 40);
+        assert!(universe.sector_index("Utilities").is_none());
+    }
+}
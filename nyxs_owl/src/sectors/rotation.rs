@@ -0,0 +1,348 @@
+//! Sector rotation by trailing momentum
+
+use super::strength::align;
+use crate::error::{OwlError, Result};
+use chrono::{Datelike, NaiveDate};
+use day_trade::monte_carlo::{max_drawdown, sharpe_ratio};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Trading days per year used to annualise the Sharpe ratio
+const PERIODS_PER_YEAR: f64 = 252.0;
+
+/// When a rotation picks its sectors again
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RebalanceSchedule {
+    /// First trading day of each week
+    Weekly,
+    /// First trading day of each month
+    #[default]
+    Monthly,
+    /// First trading day of each quarter
+    Quarterly,
+    /// Every given number of trading days
+    EveryBars(usize),
+}
+
+impl RebalanceSchedule {
+    /// Whether `date` starts a new period after `previous`
+    fn starts_period(&self, previous: NaiveDate, date: NaiveDate, bars_since: usize) -> bool {
+        match self {
+            RebalanceSchedule::Weekly => previous.iso_week() != date.iso_week(),
+            RebalanceSchedule::Monthly => {
+                (previous.year(), previous.month()) != (date.year(), date.month())
+            }
+            RebalanceSchedule::Quarterly => {
+                (previous.year(), previous.month0() / 3) != (date.year(), date.month0() / 3)
+            }
+            RebalanceSchedule::EveryBars(n) => bars_since >= *n,
+        }
+    }
+}
+
+impl FromStr for RebalanceSchedule {
+    type Err = OwlError;
+
+    /// `weekly`, `monthly`, `quarterly` or a number of trading days
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "weekly" => Ok(RebalanceSchedule::Weekly),
+            "monthly" => Ok(RebalanceSchedule::Monthly),
+            "quarterly" => Ok(RebalanceSchedule::Quarterly),
+            other => match other.parse::<usize>() {
+                Ok(n) if n > 0 => Ok(RebalanceSchedule::EveryBars(n)),
+                _ => Err(OwlError::InvalidConfig(format!(
+                    "Unknown rebalance schedule '{}'; use weekly, monthly, quarterly or a number of days",
+                    s
+                ))),
+            },
+        }
+    }
+}
+
+impl fmt::Display for RebalanceSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RebalanceSchedule::Weekly => write!(f, "weekly"),
+            RebalanceSchedule::Monthly => write!(f, "monthly"),
+            RebalanceSchedule::Quarterly => write!(f, "quarterly"),
+            RebalanceSchedule::EveryBars(n) => write!(f, "every {} days", n),
+        }
+    }
+}
+
+/// Sectors picked on one rebalance date
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Rebalance {
+    /// Date whose close the rotation traded at
+    pub date: NaiveDate,
+    /// Date whose close the sectors were ranked on, the bar before `date`
+    pub ranked_on: NaiveDate,
+    /// Sectors held until the next rebalance, strongest first
+    pub holdings: Vec<String>,
+    /// Trailing return of every sector in percent, strongest first
+    pub ranking: Vec<(String, f64)>,
+}
+
+/// Outcome of a rotation backtest
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RotationResult {
+    /// Every rebalance in date order
+    pub rebalances: Vec<Rebalance>,
+    /// Portfolio value from 100 on the first rebalance date
+    pub equity: Vec<(NaiveDate, f64)>,
+    /// Total return in percent
+    pub total_return: f64,
+    /// Return of holding every sector in equal weights on the same schedule,
+    /// in percent
+    pub equal_weight_return: f64,
+    /// Largest peak-to-trough decline of the equity curve, in percent
+    pub max_drawdown: f64,
+    /// Annualised Sharpe ratio of daily returns
+    pub sharpe_ratio: f64,
+}
+
+/// Holds the sectors with the strongest trailing returns
+///
+/// On each rebalance date the sectors are ranked by their return over the
+/// last `lookback` trading days, using closes up to and including that
+/// date, and the portfolio is split equally across the `top` strongest at
+/// the next close, like the next-bar fills of the backtests. Between
+/// rebalances the positions are left to drift. Sector
+/// series may be sector ETFs or composites such as
+/// [`equal_weight_index`](super::equal_weight_index); only the dates all
+/// series share are used.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SectorRotation {
+    top: usize,
+    lookback: usize,
+    schedule: RebalanceSchedule,
+}
+
+impl Default for SectorRotation {
+    /// The top 3 sectors by 3-month return, rebalanced monthly
+    fn default() -> Self {
+        Self {
+            top: 3,
+            lookback: 63,
+            schedule: RebalanceSchedule::Monthly,
+        }
+    }
+}
+
+impl SectorRotation {
+    /// Hold the `top` strongest sectors
+    pub fn with_top(mut self, top: usize) -> Result<Self> {
+        if top == 0 {
+            return Err(OwlError::InvalidConfig(
+                "Rotation must hold at least one sector".to_string(),
+            ));
+        }
+        self.top = top;
+        Ok(self)
+    }
+
+    /// Rank sectors by their return over `lookback` trading days
+    pub fn with_lookback(mut self, lookback: usize) -> Result<Self> {
+        if lookback == 0 {
+            return Err(OwlError::InvalidConfig(
+                "Momentum lookback must be at least one day".to_string(),
+            ));
+        }
+        self.lookback = lookback;
+        Ok(self)
+    }
+
+    /// Rebalance on `schedule`
+    pub fn with_schedule(mut self, schedule: RebalanceSchedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
+    /// Backtest the rotation on daily closes of each sector
+    pub fn run(&self, sectors: &BTreeMap<String, Vec<(NaiveDate, f64)>>) -> Result<RotationResult> {
+        let names: Vec<&String> = sectors.keys().collect();
+        let series: Vec<&[(NaiveDate, f64)]> = sectors.values().map(Vec::as_slice).collect();
+        let aligned = align(&series);
+        if names.is_empty() || aligned.len() < self.lookback + 2 {
+            return Err(OwlError::DataLoad(format!(
+                "Rotation needs at least {} dates shared by all sectors, have {}",
+                self.lookback + 2,
+                aligned.len()
+            )));
+        }
+
+        let mut rebalances = Vec::new();
+        let mut equity = Vec::new();
+        let mut units = vec![0.0; names.len()];
+        let mut benchmark_units = vec![0.0; names.len()];
+        let mut benchmark_value = 100.0;
+        let mut last_rebalance = self.lookback;
+        let mut pending: Option<(NaiveDate, Vec<(usize, f64)>)> = None;
+
+        for t in self.lookback..aligned.len() {
+            let (date, closes) = &aligned[t];
+            let value = |units: &[f64]| units.iter().zip(closes).map(|(u, c)| u * c).sum::<f64>();
+            let invested = !equity.is_empty();
+            let portfolio = if invested { value(&units) } else { 100.0 };
+            if invested {
+                benchmark_value = value(&benchmark_units);
+            }
+
+            if let Some((ranked_on, ranking)) = pending.take() {
+                let held: Vec<usize> = ranking.iter().take(self.top).map(|r| r.0).collect();
+                units = vec![0.0; names.len()];
+                for &i in &held {
+                    units[i] = portfolio / held.len() as f64 / closes[i];
+                }
+                benchmark_units = closes
+                    .iter()
+                    .map(|c| benchmark_value / names.len() as f64 / c)
+                    .collect();
+
+                rebalances.push(Rebalance {
+                    date: *date,
+                    ranked_on,
+                    holdings: held.iter().map(|&i| names[i].clone()).collect(),
+                    ranking: ranking
+                        .iter()
+                        .map(|&(i, r)| (names[i].clone(), r))
+                        .collect(),
+                });
+            }
+            if !rebalances.is_empty() {
+                equity.push((*date, portfolio));
+            }
+
+            // Rank on this close and trade at the next one
+            let due = t == self.lookback
+                || self
+                    .schedule
+                    .starts_period(aligned[t - 1].0, *date, t - last_rebalance);
+            if due && t + 1 < aligned.len() {
+                let past = &aligned[t - self.lookback].1;
+                let mut ranking: Vec<(usize, f64)> = closes
+                    .iter()
+                    .zip(past)
+                    .map(|(c, p)| (c / p - 1.0) * 100.0)
+                    .enumerate()
+                    .collect();
+                ranking
+                    .sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| names[a.0].cmp(names[b.0])));
+                pending = Some((*date, ranking));
+                last_rebalance = t;
+            }
+        }
+
+        let curve: Vec<f64> = equity.iter().map(|e| e.1).collect();
+        let returns: Vec<f64> = curve.windows(2).map(|w| w[1] / w[0] - 1.0).collect();
+        Ok(RotationResult {
+            rebalances,
+            total_return: curve[curve.len() - 1] - 100.0,
+            equal_weight_return: benchmark_value - 100.0,
+            max_drawdown: max_drawdown(&curve),
+            sharpe_ratio: sharpe_ratio(&returns, PERIODS_PER_YEAR),
+            equity,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trend(daily: f64, days: usize) -> Vec<(NaiveDate, f64)> {
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        (0..days)
+            .map(|i| {
+                (
+                    start + chrono::Duration::days(i as i64),
+                    100.0 * (1.0 + daily).powi(i as i32),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rotation_holds_the_leaders() {
+        let mut sectors = BTreeMap::new();
+        sectors.insert("Energy".to_string(), trend(-0.001, 120));
+        sectors.insert("Health".to_string(), trend(0.001, 120));
+        sectors.insert("Tech".to_string(), trend(0.003, 120));
+
+        let rotation = SectorRotation::default()
+            .with_top(1)
+            .unwrap()
+            .with_lookback(20)
+            .unwrap();
+        let result = rotation.run(&sectors).unwrap();
+
+        // Ranked on Jan 21, then the first trading day of February, March
+        // and April, each filled the day after
+        assert_eq!(result.rebalances.len(), 4);
+        assert_eq!(
+            result.rebalances[1].ranked_on,
+            NaiveDate::from_ymd_opt(2024, 2, 1).unwrap()
+        );
+        assert_eq!(
+            result.rebalances[1].date,
+            NaiveDate::from_ymd_opt(2024, 2, 2).unwrap()
+        );
+        assert!(result.rebalances.iter().all(|r| r.holdings == ["Tech"]));
+        assert_eq!(result.rebalances[0].ranking[2].0, "Energy");
+        let expected = (1.003f64.powi(98) - 1.0) * 100.0;
+        assert!((result.total_return - expected).abs() < 1e-6);
+        assert!(result.total_return > result.equal_weight_return);
+        assert_eq!(result.max_drawdown, 0.0);
+
+        let every_ten = rotation.with_schedule(RebalanceSchedule::EveryBars(10));
+        assert_eq!(every_ten.run(&sectors).unwrap().rebalances.len(), 10);
+        let long = SectorRotation::default().with_lookback(200).unwrap();
+        assert!(long.run(&sectors).is_err());
+        assert!(SectorRotation::default().with_top(0).is_err());
+
+        assert_eq!(
+            "Quarterly".parse::<RebalanceSchedule>().unwrap(),
+            RebalanceSchedule::Quarterly
+        );
+        assert_eq!(
+            "5".parse::<RebalanceSchedule>().unwrap(),
+            RebalanceSchedule::EveryBars(5)
+        );
+        assert!("0".parse::<RebalanceSchedule>().is_err());
+        assert!("daily".parse::<RebalanceSchedule>().is_err());
+    }
+
+    #[test]
+    fn test_rotation_fills_on_the_next_bar() {
+        // Tech leads into the ranking close, then jumps on the fill bar
+        let mut tech = trend(0.001, 40);
+        for (i, point) in tech.iter_mut().enumerate() {
+            point.1 = if i <= 20 { 100.0 + i as f64 } else { 130.0 };
+        }
+        let mut sectors = BTreeMap::new();
+        sectors.insert("Energy".to_string(), trend(0.0, 40));
+        sectors.insert("Tech".to_string(), tech);
+
+        let result = SectorRotation::default()
+            .with_top(1)
+            .unwrap()
+            .with_lookback(20)
+            .unwrap()
+            .run(&sectors)
+            .unwrap();
+        let first = &result.rebalances[0];
+        assert_eq!(
+            first.ranked_on,
+            NaiveDate::from_ymd_opt(2024, 1, 21).unwrap()
+        );
+        assert_eq!(first.date, NaiveDate::from_ymd_opt(2024, 1, 22).unwrap());
+        assert_eq!(first.holdings, ["Tech"]);
+        // Bought at the jump's close, so none of it is captured
+        assert_eq!(result.equity[0], (first.date, 100.0));
+        assert!(result.total_return.abs() < 1e-9);
+    }
+}
@@ -0,0 +1,248 @@
+//! Relative strength, sector composites and sector breadth
+
+use crate::data::Bar;
+use crate::error::{OwlError, Result};
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Daily closes of `bars` keyed by date
+pub fn closes(bars: &[Bar]) -> Vec<(NaiveDate, f64)> {
+    bars.iter()
+        .map(|b| (b.timestamp.date_naive(), b.close))
+        .collect()
+}
+
+/// Values of each series on the dates all of them share, oldest first
+pub(crate) fn align(series: &[&[(NaiveDate, f64)]]) -> Vec<(NaiveDate, Vec<f64>)> {
+    let Some((first, rest)) = series.split_first() else {
+        return Vec::new();
+    };
+    let lookups: Vec<BTreeMap<NaiveDate, f64>> =
+        rest.iter().map(|s| s.iter().copied().collect()).collect();
+    first
+        .iter()
+        .filter_map(|&(date, value)| {
+            let mut values = vec![value];
+            for lookup in &lookups {
+                values.push(*lookup.get(&date)?);
+            }
+            Some((date, values))
+        })
+        .collect()
+}
+
+/// Equal-weight index of `members`, starting at 100 on the first date any
+/// of them trades
+///
+/// Each day the index moves by the average return of the members that
+/// traded on that day and the one before, so members listed later join
+/// the index without gaps in its history.
+pub fn equal_weight_index(members: &[&[(NaiveDate, f64)]]) -> Vec<(NaiveDate, f64)> {
+    let lookups: Vec<BTreeMap<NaiveDate, f64>> = members
+        .iter()
+        .map(|m| m.iter().copied().collect())
+        .collect();
+    let mut dates: Vec<NaiveDate> = lookups.iter().flat_map(|l| l.keys().copied()).collect();
+    dates.sort_unstable();
+    dates.dedup();
+
+    let mut index: Vec<(NaiveDate, f64)> = Vec::with_capacity(dates.len());
+    for (i, &date) in dates.iter().enumerate() {
+        let level = match index.last() {
+            None => 100.0,
+            Some(&(_, previous_level)) => {
+                let returns: Vec<f64> = lookups
+                    .iter()
+                    .filter_map(|l| Some(l.get(&date)? / l.get(&dates[i - 1])? - 1.0))
+                    .collect();
+                if returns.is_empty() {
+                    previous_level
+                } else {
+                    previous_level * (1.0 + returns.iter().sum::<f64>() / returns.len() as f64)
+                }
+            }
+        };
+        index.push((date, level));
+    }
+    index
+}
+
+/// Performance of an asset against a benchmark over a period
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct RelativeStrength {
+    /// Return of the asset, as a fraction
+    pub asset_return: f64,
+    /// Return of the benchmark, as a fraction
+    pub benchmark_return: f64,
+    /// Growth of the asset relative to the benchmark minus one; positive
+    /// when the asset outperformed
+    pub relative: f64,
+}
+
+/// Relative strength of `asset` against `benchmark` over the last `period`
+/// dates the two share
+pub fn relative_strength(
+    asset: &[(NaiveDate, f64)],
+    benchmark: &[(NaiveDate, f64)],
+    period: usize,
+) -> Result<RelativeStrength> {
+    let aligned = align(&[asset, benchmark]);
+    if period == 0 || aligned.len() <= period {
+        return Err(OwlError::Strategy(format!(
+            "Relative strength over {} bars needs {} common dates, have {}",
+            period,
+            period + 1,
+            aligned.len()
+        )));
+    }
+    let (start, end) = (
+        &aligned[aligned.len() - 1 - period].1,
+        &aligned[aligned.len() - 1].1,
+    );
+    let asset_return = end[0] / start[0] - 1.0;
+    let benchmark_return = end[1] / start[1] - 1.0;
+    Ok(RelativeStrength {
+        asset_return,
+        benchmark_return,
+        relative: (1.0 + asset_return) / (1.0 + benchmark_return) - 1.0,
+    })
+}
+
+/// Ratio of `asset` to `benchmark` on their common dates, scaled to 100 on
+/// the first; rising while the asset outperforms
+pub fn relative_strength_line(
+    asset: &[(NaiveDate, f64)],
+    benchmark: &[(NaiveDate, f64)],
+) -> Vec<(NaiveDate, f64)> {
+    let aligned = align(&[asset, benchmark]);
+    let Some((_, first)) = aligned.first() else {
+        return Vec::new();
+    };
+    let base = first[0] / first[1];
+    aligned
+        .iter()
+        .map(|(date, v)| (*date, v[0] / v[1] / base * 100.0))
+        .collect()
+}
+
+/// Participation of a sector's members in its move, on each member's
+/// latest bar
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct SectorBreadth {
+    /// Members with data
+    pub members: usize,
+    /// Members with enough history for the moving average
+    pub measured: usize,
+    /// Members closing above their moving average
+    pub above_ma: usize,
+    /// `above_ma` as a percentage of `measured`
+    pub percent_above_ma: Option<f64>,
+    /// Members that closed up
+    pub advancers: usize,
+    /// Members that closed down
+    pub decliners: usize,
+    /// Members that closed unchanged
+    pub unchanged: usize,
+}
+
+impl SectorBreadth {
+    /// Breadth of `members` against a simple moving average of `ma_period`
+    /// closes
+    pub fn measure(members: &[&[(NaiveDate, f64)]], ma_period: usize) -> Self {
+        let mut breadth = SectorBreadth {
+            members: members.len(),
+            measured: 0,
+            above_ma: 0,
+            percent_above_ma: None,
+            advancers: 0,
+            decliners: 0,
+            unchanged: 0,
+        };
+        for closes in members {
+            let Some(&(_, last)) = closes.last() else {
+                continue;
+            };
+            if ma_period > 0 && closes.len() >= ma_period {
+                let window = &closes[closes.len() - ma_period..];
+                let average = window.iter().map(|c| c.1).sum::<f64>() / ma_period as f64;
+                breadth.measured += 1;
+                if last > average {
+                    breadth.above_ma += 1;
+                }
+            }
+            if let Some(&(_, previous)) = closes.len().checked_sub(2).map(|i| &closes[i]) {
+                match last.partial_cmp(&previous) {
+                    Some(std::cmp::Ordering::Greater) => breadth.advancers += 1,
+                    Some(std::cmp::Ordering::Less) => breadth.decliners += 1,
+                    _ => breadth.unchanged += 1,
+                }
+            }
+        }
+        breadth.percent_above_ma = (breadth.measured > 0)
+            .then(|| breadth.above_ma as f64 / breadth.measured as f64 * 100.0);
+        breadth
+    }
+
+    /// Advancers per decliner; `None` without decliners
+    pub fn advance_decline_ratio(&self) -> Option<f64> {
+        (self.decliners > 0).then(|| self.advancers as f64 / self.decliners as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(start_day: u32, closes: &[f64]) -> Vec<(NaiveDate, f64)> {
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| {
+                let date = NaiveDate::from_ymd_opt(2024, 1, start_day).unwrap();
+                (date + chrono::Duration::days(i as i64), c)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_relative_strength_and_index() {
+        let asset = series(1, &[100.0, 105.0, 110.0, 121.0]);
+        let benchmark = series(2, &[50.0, 50.0, 55.0]);
+
+        let rs = relative_strength(&asset, &benchmark, 2).unwrap();
+        assert!((rs.asset_return - 0.1523809524).abs() < 1e-9);
+        assert!((rs.benchmark_return - 0.1).abs() < 1e-12);
+        assert!((rs.relative - (1.1523809524 / 1.1 - 1.0)).abs() < 1e-9);
+        assert!(relative_strength(&asset, &benchmark, 3).is_err());
+
+        let line = relative_strength_line(&asset, &benchmark);
+        assert_eq!(line.len(), 3);
+        assert_eq!(line[0].1, 100.0);
+        assert!(line[1].1 > line[0].1);
+
+        let index = equal_weight_index(&[&asset, &series(1, &[10.0, 11.0, 11.0, 11.0])]);
+        assert_eq!(index.len(), 4);
+        assert!((index[1].1 - 107.5).abs() < 1e-9);
+        assert!(equal_weight_index(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_sector_breadth() {
+        let up = series(1, &[10.0, 11.0, 12.0]);
+        let down = series(1, &[12.0, 11.0, 10.0]);
+        let flat = series(1, &[10.0, 10.0]);
+        let breadth = SectorBreadth::measure(&[&up, &down, &flat], 3);
+
+        assert_eq!(
+            (breadth.members, breadth.measured, breadth.above_ma),
+            (3, 2, 1)
+        );
+        assert_eq!(breadth.percent_above_ma, Some(50.0));
+        assert_eq!(
+            (breadth.advancers, breadth.decliners, breadth.unchanged),
+            (1, 1, 1)
+        );
+        assert_eq!(breadth.advance_decline_ratio(), Some(1.0));
+    }
+}
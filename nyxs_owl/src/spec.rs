@@ -0,0 +1,460 @@
+//! Declarative strategy specifications
+//!
+//! A spec names a strategy type and its parameters together with risk
+//! limits, trading costs, the timeframe and where its data lives, so that
+//! strategies can be kept in version control and changed without
+//! recompiling. Specs are read from TOML, YAML or JSON:
+//!
+//! ```toml
+//! name = "MACD swing"
+//! type = "macd"
+//! timeframe = "daily"
+//!
+//! [params]
+//! fast_period = 8
+//! slow_period = 21
+//!
+//! [risk]
+//! stop_loss = 0.05
+//! trailing_stop = 0.08
+//!
+//! [costs]
+//! commission = 0.001
+//! slippage = 0.0005
+//!
+//! [data]
+//! dir = "data"
+//! symbols = ["AAPL", "MSFT"]
+//! ```
+//!
+//! Unknown keys are rejected, as are parameters the strategy does not take.
+
+use crate::data::{MarketDataStore, Watchlist};
+use crate::error::{OwlError, Result};
+use crate::strategies::{Params, StrategyInstance, StrategyRegistry};
+use day_trade::ledger::TradingCosts;
+use day_trade::risk::RiskOverlay;
+use day_trade::{DailyOhlcv, TradingStrategy};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub use crate::strategies::Timeframe;
+
+/// Protective exits, as fractions of the entry price
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RiskSpec {
+    /// Sell when a close falls this far below the entry
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_loss: Option<f64>,
+    /// Sell when a close rises this far above the entry
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub take_profit: Option<f64>,
+    /// Sell when a close falls this far below the highest close since entry
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trailing_stop: Option<f64>,
+    /// Sell after holding for this many bars
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_holding_bars: Option<usize>,
+}
+
+impl RiskSpec {
+    /// Whether any limit is set
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Wrap `strategy` in an overlay with these limits
+    pub fn apply<S>(&self, strategy: S) -> Result<RiskOverlay<S>> {
+        let mut overlay = RiskOverlay::new(strategy);
+        if let Some(fraction) = self.stop_loss {
+            overlay = overlay.with_stop_loss(fraction).map_err(risk_error)?;
+        }
+        if let Some(fraction) = self.take_profit {
+            overlay = overlay.with_take_profit(fraction).map_err(risk_error)?;
+        }
+        if let Some(fraction) = self.trailing_stop {
+            overlay = overlay.with_trailing_stop(fraction).map_err(risk_error)?;
+        }
+        if let Some(bars) = self.max_holding_bars {
+            overlay = overlay.with_max_holding(bars).map_err(risk_error)?;
+        }
+        Ok(overlay)
+    }
+}
+
+fn risk_error(e: String) -> OwlError {
+    OwlError::InvalidConfig(format!("risk: {}", e))
+}
+
+/// Trading costs charged on every fill
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CostSpec {
+    /// Commission as a fraction of traded value
+    pub commission: f64,
+    /// Slippage as a fraction of the price
+    pub slippage: f64,
+    /// Fixed fee per fill
+    pub fixed_fee: f64,
+}
+
+impl CostSpec {
+    /// Whether every cost is zero
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The ledger's cost model
+    pub fn to_costs(&self) -> Result<TradingCosts> {
+        TradingCosts::new(self.commission, self.slippage, self.fixed_fee)
+            .map_err(|e| OwlError::InvalidConfig(format!("costs: {}", e)))
+    }
+}
+
+/// Where a strategy's data comes from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DataSourceSpec {
+    /// Directory with `{SYMBOL}_daily_ohlcv.csv` files
+    pub dir: PathBuf,
+    /// Symbols to trade
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub symbols: Vec<String>,
+    /// Watchlist file, used when no symbols are listed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watchlist: Option<PathBuf>,
+    /// Most recent bars to load per symbol
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lookback: Option<usize>,
+}
+
+impl DataSourceSpec {
+    /// Store reading from `dir`
+    pub fn store(&self) -> MarketDataStore {
+        MarketDataStore::new(&self.dir)
+    }
+
+    /// The listed symbols, or the watchlist file's
+    pub fn watchlist(&self) -> Result<Watchlist> {
+        match (&self.watchlist, self.symbols.is_empty()) {
+            (_, false) => Watchlist::new(&self.symbols),
+            (Some(path), true) => Watchlist::from_file(path),
+            (None, true) => Err(OwlError::InvalidConfig(
+                "data: list symbols or give a watchlist".to_string(),
+            )),
+        }
+    }
+
+    /// Daily bars of every symbol, oldest first
+    pub fn load_daily(&self) -> Result<Vec<(String, Vec<DailyOhlcv>)>> {
+        let store = self.store();
+        let lookback = self.lookback.unwrap_or(usize::MAX);
+        self.watchlist()?
+            .symbols()
+            .iter()
+            .map(|symbol| {
+                let data = store.load(symbol, lookback)?;
+                Ok((symbol.clone(), data.daily_ohlcv()))
+            })
+            .collect()
+    }
+
+    /// Resolve relative paths against `base`
+    fn rebase(&mut self, base: &Path) {
+        if self.dir.is_relative() {
+            self.dir = base.join(&self.dir);
+        }
+        if let Some(path) = self.watchlist.as_mut().filter(|p| p.is_relative()) {
+            *path = base.join(&*path);
+        }
+    }
+}
+
+/// File format of a spec
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpecFormat {
+    /// TOML
+    Toml,
+    /// YAML
+    Yaml,
+    /// JSON
+    Json,
+}
+
+impl SpecFormat {
+    /// Format implied by a file extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "toml" => Some(SpecFormat::Toml),
+            "yaml" | "yml" => Some(SpecFormat::Yaml),
+            "json" => Some(SpecFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+/// A strategy with its parameters, risk limits, costs and data
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StrategySpec {
+    /// Display name; defaults to the type and parameters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Strategy type, e.g. `macd`
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Bar interval
+    #[serde(default)]
+    pub timeframe: Timeframe,
+    /// Parameter overrides; others take the strategy's defaults
+    #[serde(default, skip_serializing_if = "Params::is_empty")]
+    pub params: Params,
+    /// Protective exits
+    #[serde(default, skip_serializing_if = "RiskSpec::is_empty")]
+    pub risk: RiskSpec,
+    /// Trading costs
+    #[serde(default, skip_serializing_if = "CostSpec::is_empty")]
+    pub costs: CostSpec,
+    /// Data source
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<DataSourceSpec>,
+}
+
+impl StrategySpec {
+    /// Spec for a daily strategy type with default parameters
+    pub fn new(kind: &str) -> Self {
+        Self {
+            name: None,
+            kind: kind.to_string(),
+            timeframe: Timeframe::Daily,
+            params: Params::new(),
+            risk: RiskSpec::default(),
+            costs: CostSpec::default(),
+            data: None,
+        }
+    }
+
+    /// Builder form of setting `params`
+    pub fn with_params(mut self, params: Params) -> Self {
+        self.params = params;
+        self
+    }
+
+    /// Parse a spec from text in `format`
+    pub fn parse(text: &str, format: SpecFormat) -> Result<Self> {
+        let parsed = match format {
+            SpecFormat::Toml => toml::from_str(text).map_err(|e| e.to_string()),
+            SpecFormat::Yaml => serde_yaml::from_str(text).map_err(|e| e.to_string()),
+            SpecFormat::Json => serde_json::from_str(text).map_err(|e| e.to_string()),
+        };
+        parsed.map_err(|e| OwlError::InvalidConfig(format!("Invalid strategy spec: {}", e)))
+    }
+
+    /// Read a spec, choosing the format by extension
+    ///
+    /// Relative data paths are resolved against the spec's directory.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let format = SpecFormat::from_path(path).ok_or_else(|| {
+            OwlError::InvalidConfig(format!(
+                "Cannot tell the format of {}; use .toml, .yaml, .yml or .json",
+                path.display()
+            ))
+        })?;
+        let text = fs::read_to_string(path)?;
+        let mut spec = Self::parse(&text, format).map_err(|e| match e {
+            OwlError::InvalidConfig(msg) => {
+                OwlError::InvalidConfig(format!("{}: {}", path.display(), msg))
+            }
+            other => other,
+        })?;
+        if let (Some(data), Some(base)) = (spec.data.as_mut(), path.parent()) {
+            data.rebase(base);
+        }
+        Ok(spec)
+    }
+
+    /// Display name: `name`, or the type with its parameters
+    pub fn label(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None if self.params.is_empty() => self.kind.clone(),
+            None => format!("{}:{}", self.kind, self.params),
+        }
+    }
+
+    /// Build the strategy from the built-in registry with its risk overlay
+    ///
+    /// The strategy must support the spec's timeframe. Risk limits apply to
+    /// `day_trade` strategies only.
+    pub fn build_instance(&self) -> Result<StrategyInstance> {
+        let registry = StrategyRegistry::default();
+        let info = registry.lookup(&self.kind)?;
+        if !info.supports(self.timeframe) {
+            return Err(OwlError::InvalidConfig(format!(
+                "{}: {} does not trade on {} bars",
+                self.label(),
+                self.kind,
+                self.timeframe
+            )));
+        }
+        let instance = info.build(&self.params)?;
+        if self.risk.is_empty() {
+            return Ok(instance);
+        }
+        match instance {
+            StrategyInstance::Daily(strategy) => Ok(StrategyInstance::Daily(Box::new(
+                self.risk.apply(strategy)?,
+            ))),
+            StrategyInstance::Intraday(strategy) => Ok(StrategyInstance::Intraday(Box::new(
+                self.risk.apply(strategy)?,
+            ))),
+            StrategyInstance::Minute(_) => Err(OwlError::InvalidConfig(format!(
+                "{}: risk limits are not supported for {}",
+                self.label(),
+                self.kind
+            ))),
+        }
+    }
+
+    /// Build the daily strategy with its risk overlay
+    pub fn build(&self) -> Result<Box<dyn TradingStrategy>> {
+        if self.timeframe != Timeframe::Daily {
+            return Err(OwlError::InvalidConfig(format!(
+                "{}: only daily strategies can be built, not {}",
+                self.label(),
+                self.timeframe
+            )));
+        }
+        self.build_instance()?.into_daily().ok_or_else(|| {
+            OwlError::InvalidConfig(format!("{}: not a daily strategy", self.label()))
+        })
+    }
+
+    /// Check that the spec builds and its costs are valid
+    pub fn validate(&self) -> Result<()> {
+        self.build_instance()?;
+        self.costs.to_costs()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use day_trade::utils::generate_test_data;
+
+    const TOML_SPEC: &str = r#"
+name = "MACD swing"
+type = "macd"
+
+[params]
+fast_period = 8
+slow_period = 21
+
+[risk]
+stop_loss = 0.05
+max_holding_bars = 10
+
+[costs]
+commission = 0.001
+fixed_fee = 1.0
+
+[data]
+dir = "data"
+symbols = ["AAPL"]
+"#;
+
+    #[test]
+    fn test_formats_agree() {
+        let toml = StrategySpec::parse(TOML_SPEC, SpecFormat::Toml).unwrap();
+        assert_eq!(toml.label(), "MACD swing");
+        assert_eq!(toml.params.get("fast_period"), Some(8.0));
+        assert_eq!(toml.risk.max_holding_bars, Some(10));
+        assert_eq!(toml.timeframe, Timeframe::Daily);
+
+        let yaml = serde_yaml::to_string(&toml).unwrap();
+        assert_eq!(StrategySpec::parse(&yaml, SpecFormat::Yaml).unwrap(), toml);
+        let json = serde_json::to_string(&toml).unwrap();
+        assert_eq!(StrategySpec::parse(&json, SpecFormat::Json).unwrap(), toml);
+
+        assert_eq!(
+            SpecFormat::from_path(Path::new("a/b.YML")),
+            Some(SpecFormat::Yaml)
+        );
+        assert_eq!(SpecFormat::from_path(Path::new("spec.txt")), None);
+    }
+
+    #[test]
+    fn test_build_applies_risk_limits() {
+        let spec = StrategySpec::parse(TOML_SPEC, SpecFormat::Toml).unwrap();
+        spec.validate().unwrap();
+        let data = generate_test_data(200, 100.0, 0.02);
+
+        let limited = spec.build().unwrap().generate_signals(&data).unwrap();
+        let plain = StrategySpec::new("macd")
+            .with_params(spec.params.clone())
+            .build()
+            .unwrap()
+            .generate_signals(&data)
+            .unwrap();
+        assert_eq!(limited.len(), plain.len());
+        // No position outlives the ten-bar holding limit
+        let mut held = 0;
+        for signal in &limited {
+            match signal {
+                day_trade::Signal::Buy if held == 0 => held = 1,
+                day_trade::Signal::Sell => held = 0,
+                _ if held > 0 => held += 1,
+                _ => {}
+            }
+            assert!(held <= 11);
+        }
+    }
+
+    #[test]
+    fn test_rejects_bad_specs() {
+        let typo = "type = \"macd\"\nstop_loss = 0.05\n";
+        assert!(StrategySpec::parse(typo, SpecFormat::Toml).is_err());
+
+        let unknown_param = "{\"type\": \"rsi\", \"params\": {\"fast\": 3}}";
+        let spec = StrategySpec::parse(unknown_param, SpecFormat::Json).unwrap();
+        assert!(spec.validate().is_err());
+
+        let bad_risk = "type: macd\nrisk:\n  stop_loss: 1.5\n";
+        let spec = StrategySpec::parse(bad_risk, SpecFormat::Yaml).unwrap();
+        assert!(spec.build().is_err());
+
+        let minute = "type: macd\ntimeframe: minute\n";
+        let spec = StrategySpec::parse(minute, SpecFormat::Yaml).unwrap();
+        assert!(spec.build().is_err());
+        assert!(spec.validate().is_err());
+
+        let scalping = "type: scalping\ntimeframe: minute\nparams:\n  period: 3\n";
+        let spec = StrategySpec::parse(scalping, SpecFormat::Yaml).unwrap();
+        assert!(matches!(
+            spec.build_instance().unwrap(),
+            StrategyInstance::Minute(_)
+        ));
+        assert!(spec.build().is_err());
+        let mut spec = spec;
+        spec.risk.stop_loss = Some(0.05);
+        assert!(spec.build_instance().is_err());
+    }
+
+    #[test]
+    fn test_from_file_resolves_data_paths() {
+        let dir = std::env::temp_dir().join(format!("owl_spec_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("macd.toml");
+        fs::write(&path, TOML_SPEC).unwrap();
+
+        let spec = StrategySpec::from_file(&path).unwrap();
+        assert_eq!(spec.data.unwrap().dir, dir.join("data"));
+        assert!(StrategySpec::from_file(dir.join("macd.ini")).is_err());
+        fs::remove_dir_all(dir).ok();
+    }
+}
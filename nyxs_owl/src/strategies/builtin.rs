@@ -0,0 +1,739 @@
+//! Registrations for the `day_trade` and `minute_trade` strategies
+//!
+//! Parameter ranges repeat the checks each constructor applies, so that a
+//! value is rejected with its valid range before the constructor runs.
+//! Checks that relate two parameters (such as fast below slow) stay with the
+//! factories. Strategies that need other objects to construct, such as
+//! `DualTimeframeStrategy` or `RegimeSwitchingStrategy`, are not registered,
+//! and neither are the planned `minute_trade` placeholders.
+
+use super::registry::{Category, ParamSpec, StrategyInfo, StrategyInstance, Timeframe};
+use super::Params;
+
+type Built = Result<StrategyInstance, String>;
+
+/// Parameters are resolved before a factory runs, so every name is present
+fn num(p: &Params, name: &str) -> f64 {
+    p.get(name).unwrap_or_default()
+}
+
+fn int(p: &Params, name: &str) -> usize {
+    num(p, name) as usize
+}
+
+fn flag(p: &Params, name: &str) -> bool {
+    num(p, name) != 0.0
+}
+
+fn daily(name: &'static str, category: Category, factory: fn(&Params) -> Built) -> StrategyInfo {
+    StrategyInfo::new(name, category, factory).with_source("day_trade")
+}
+
+fn intraday(name: &'static str, category: Category, factory: fn(&Params) -> Built) -> StrategyInfo {
+    StrategyInfo::new(name, category, factory)
+        .with_source("day_trade")
+        .with_timeframes(&[Timeframe::Minute])
+}
+
+fn minute(name: &'static str, category: Category, factory: fn(&Params) -> Built) -> StrategyInfo {
+    StrategyInfo::new(name, category, factory)
+        .with_source("minute_trade")
+        .with_timeframes(&[Timeframe::Minute])
+}
+
+fn chart_pattern(
+    name: &'static str,
+    description: &'static str,
+    factory: fn(&Params) -> Built,
+) -> StrategyInfo {
+    minute(name, Category::Pattern, factory)
+        .with_description(description)
+        .with_param(ParamSpec::period(
+            "lookback_period",
+            30,
+            10,
+            "Bars searched for the pattern",
+        ))
+        .with_param(ParamSpec::period(
+            "min_pattern_size",
+            5,
+            3,
+            "Smallest pattern in bars",
+        ))
+        .with_param(
+            ParamSpec::positive("pattern_threshold", 0.5, "Recognition threshold").at_most(1.0),
+        )
+}
+
+fn build_chart_pattern(p: &Params, pattern: &str) -> Built {
+    Ok(StrategyInstance::Minute(Box::new(
+        minute_trade::ChartPatternStrategy::new(
+            int(p, "lookback_period"),
+            int(p, "min_pattern_size"),
+            num(p, "pattern_threshold"),
+            pattern,
+        )?,
+    )))
+}
+
+/// Every built-in strategy
+pub(super) fn strategies() -> Vec<StrategyInfo> {
+    vec![
+        // day_trade, daily bars
+        daily("adaptive_ma", Category::Buy, |p| {
+            Ok(StrategyInstance::Daily(Box::new(
+                day_trade::AdaptiveMovingAverageStrategy::new(
+                    int(p, "base_period"),
+                    int(p, "min_period"),
+                    int(p, "max_period"),
+                    int(p, "atr_period"),
+                    num(p, "volatility_factor"),
+                )?,
+            )))
+        })
+        .with_description("Moving average whose period adapts to volatility")
+        .with_param(ParamSpec::period(
+            "base_period",
+            20,
+            2,
+            "Period at average volatility",
+        ))
+        .with_param(ParamSpec::period(
+            "min_period",
+            10,
+            2,
+            "Shortest period, below base_period",
+        ))
+        .with_param(ParamSpec::period(
+            "max_period",
+            40,
+            3,
+            "Longest period, above base_period",
+        ))
+        .with_param(ParamSpec::period("atr_period", 14, 5, "ATR period"))
+        .with_param(ParamSpec::positive(
+            "volatility_factor",
+            2.0,
+            "Volatility sensitivity",
+        )),
+        daily("breakout", Category::Buy, |p| {
+            Ok(StrategyInstance::Daily(Box::new(
+                day_trade::BreakoutStrategy::new(
+                    int(p, "lookback_period"),
+                    num(p, "atr_multiplier"),
+                    int(p, "atr_period"),
+                )?,
+            )))
+        })
+        .with_description("Buys closes above the recent high by an ATR margin")
+        .with_param(ParamSpec::period(
+            "lookback_period",
+            20,
+            2,
+            "Bars in the breakout range",
+        ))
+        .with_param(ParamSpec::positive(
+            "atr_multiplier",
+            1.5,
+            "ATR margin beyond the range",
+        ))
+        .with_param(ParamSpec::period("atr_period", 14, 2, "ATR period")),
+        daily("ma_crossover", Category::Buy, |p| {
+            let (short, long) = (int(p, "short_period"), int(p, "long_period"));
+            if short >= long {
+                return Err("short period must be below the long period".to_string());
+            }
+            Ok(StrategyInstance::Daily(Box::new(
+                day_trade::MACrossover::new(short, long),
+            )))
+        })
+        .with_description("Short and long simple moving average crossover")
+        .with_param(ParamSpec::period(
+            "short_period",
+            10,
+            1,
+            "Short moving average period",
+        ))
+        .with_param(ParamSpec::period(
+            "long_period",
+            30,
+            2,
+            "Long moving average period",
+        )),
+        daily("macd", Category::Buy, |p| {
+            let (fast, slow) = (int(p, "fast_period"), int(p, "slow_period"));
+            if fast >= slow {
+                return Err("fast period must be below the slow period".to_string());
+            }
+            Ok(StrategyInstance::Daily(Box::new(
+                day_trade::MacdStrategy::new(fast, slow, int(p, "signal_period")),
+            )))
+        })
+        .with_description("MACD line crossing its signal line")
+        .with_param(ParamSpec::period("fast_period", 12, 1, "Fast EMA period"))
+        .with_param(ParamSpec::period("slow_period", 26, 2, "Slow EMA period"))
+        .with_param(ParamSpec::period(
+            "signal_period",
+            9,
+            1,
+            "Signal line EMA period",
+        )),
+        daily("mean_reversion", Category::Sell, |p| {
+            Ok(StrategyInstance::Daily(Box::new(
+                day_trade::MeanReversionStrategy::new(
+                    int(p, "period"),
+                    num(p, "std_dev_multiplier"),
+                    num(p, "oversold_threshold"),
+                    num(p, "overbought_threshold"),
+                )?,
+            )))
+        })
+        .with_description("Bollinger %B reversion from the band edges")
+        .with_param(ParamSpec::period("period", 20, 2, "Band period"))
+        .with_param(ParamSpec::positive(
+            "std_dev_multiplier",
+            2.0,
+            "Band width in standard deviations",
+        ))
+        .with_param(ParamSpec::range(
+            "oversold_threshold",
+            0.1,
+            0.0,
+            1.0,
+            "%B buy level",
+        ))
+        .with_param(ParamSpec::range(
+            "overbought_threshold",
+            0.9,
+            0.0,
+            1.0,
+            "%B sell level",
+        )),
+        daily("rsi", Category::Sell, |p| {
+            let (overbought, oversold) =
+                (num(p, "overbought_threshold"), num(p, "oversold_threshold"));
+            if oversold >= overbought {
+                return Err("oversold threshold must be below the overbought threshold".to_string());
+            }
+            Ok(StrategyInstance::Daily(Box::new(
+                day_trade::RsiStrategy::new(int(p, "period"), overbought, oversold),
+            )))
+        })
+        .with_description("RSI leaving the oversold and overbought zones")
+        .with_param(ParamSpec::period("period", 14, 2, "RSI period"))
+        .with_param(ParamSpec::range(
+            "overbought_threshold",
+            70.0,
+            0.0,
+            100.0,
+            "RSI sell level",
+        ))
+        .with_param(ParamSpec::range(
+            "oversold_threshold",
+            30.0,
+            0.0,
+            100.0,
+            "RSI buy level",
+        )),
+        daily("volume", Category::Sell, |p| {
+            Ok(StrategyInstance::Daily(Box::new(
+                day_trade::VolumeBasedStrategy::new(
+                    int(p, "obv_ma_period"),
+                    int(p, "vpt_ma_period"),
+                )?,
+            )))
+        })
+        .with_description("On-balance volume and volume-price trend against their averages")
+        .with_param(ParamSpec::period(
+            "obv_ma_period",
+            20,
+            2,
+            "OBV moving average period",
+        ))
+        .with_param(ParamSpec::period(
+            "vpt_ma_period",
+            14,
+            2,
+            "VPT moving average period",
+        )),
+        daily("grid", Category::Hold, |p| {
+            Ok(StrategyInstance::Daily(Box::new(
+                day_trade::GridTradingStrategy::new(
+                    int(p, "grid_levels"),
+                    num(p, "grid_spacing"),
+                    int(p, "atr_period"),
+                    num(p, "max_position_size"),
+                )?,
+            )))
+        })
+        .with_description("Buys and sells at ATR-spaced grid levels")
+        .with_param(ParamSpec::period(
+            "grid_levels",
+            5,
+            1,
+            "Levels on each side",
+        ))
+        .with_param(ParamSpec::positive(
+            "grid_spacing",
+            0.5,
+            "Level spacing in ATRs",
+        ))
+        .with_param(ParamSpec::period("atr_period", 14, 5, "ATR period"))
+        .with_param(
+            ParamSpec::positive("max_position_size", 0.2, "Largest position as a fraction")
+                .at_most(1.0),
+        ),
+        daily("composite", Category::Hold, |p| {
+            let (oversold, overbought) = (num(p, "rsi_oversold"), num(p, "rsi_overbought"));
+            if oversold >= overbought {
+                return Err("rsi_oversold must be below rsi_overbought".to_string());
+            }
+            if int(p, "macd_fast_period") >= int(p, "macd_slow_period") {
+                return Err("macd_fast_period must be below macd_slow_period".to_string());
+            }
+            let (short, medium, long) = (
+                int(p, "short_ma_period"),
+                int(p, "medium_ma_period"),
+                int(p, "long_ma_period"),
+            );
+            if short >= medium || medium >= long {
+                return Err("moving average periods must increase from short to long".to_string());
+            }
+            Ok(StrategyInstance::Daily(Box::new(
+                day_trade::CompositeStrategy::new(
+                    int(p, "rsi_period"),
+                    overbought,
+                    oversold,
+                    int(p, "macd_fast_period"),
+                    int(p, "macd_slow_period"),
+                    int(p, "macd_signal_period"),
+                    short,
+                    medium,
+                    long,
+                    num(p, "rsi_weight"),
+                    num(p, "macd_weight"),
+                    num(p, "ma_weight"),
+                ),
+            )))
+        })
+        .with_description("Weighted vote of RSI, MACD and moving average trend")
+        .with_param(ParamSpec::period("rsi_period", 14, 2, "RSI period"))
+        .with_param(ParamSpec::range(
+            "rsi_overbought",
+            70.0,
+            0.0,
+            100.0,
+            "RSI sell level",
+        ))
+        .with_param(ParamSpec::range(
+            "rsi_oversold",
+            30.0,
+            0.0,
+            100.0,
+            "RSI buy level",
+        ))
+        .with_param(ParamSpec::period(
+            "macd_fast_period",
+            12,
+            1,
+            "MACD fast EMA period",
+        ))
+        .with_param(ParamSpec::period(
+            "macd_slow_period",
+            26,
+            2,
+            "MACD slow EMA period",
+        ))
+        .with_param(ParamSpec::period(
+            "macd_signal_period",
+            9,
+            1,
+            "MACD signal EMA period",
+        ))
+        .with_param(ParamSpec::period(
+            "short_ma_period",
+            20,
+            1,
+            "Short moving average period",
+        ))
+        .with_param(ParamSpec::period(
+            "medium_ma_period",
+            50,
+            2,
+            "Medium moving average period",
+        ))
+        .with_param(ParamSpec::period(
+            "long_ma_period",
+            200,
+            3,
+            "Long moving average period",
+        ))
+        .with_param(ParamSpec::range(
+            "rsi_weight",
+            0.33,
+            0.0,
+            1.0,
+            "Weight of the RSI vote",
+        ))
+        .with_param(ParamSpec::range(
+            "macd_weight",
+            0.33,
+            0.0,
+            1.0,
+            "Weight of the MACD vote",
+        ))
+        .with_param(ParamSpec::range(
+            "ma_weight",
+            0.34,
+            0.0,
+            1.0,
+            "Weight of the trend vote",
+        )),
+        // day_trade, minute bars
+        intraday("bollinger_bands", Category::Hold, |p| {
+            if num(p, "oversold_threshold") >= num(p, "overbought_threshold") {
+                return Err("oversold threshold must be below the overbought threshold".to_string());
+            }
+            Ok(StrategyInstance::Intraday(Box::new(
+                day_trade::BollingerBandsStrategy::new(
+                    int(p, "period"),
+                    num(p, "std_dev_multiplier"),
+                    num(p, "oversold_threshold"),
+                    num(p, "overbought_threshold"),
+                    num(p, "bandwidth_expansion_threshold"),
+                    int(p, "trend_confirmation_length"),
+                ),
+            )))
+        })
+        .with_description("Intraday Bollinger %B with bandwidth and trend confirmation")
+        .with_param(ParamSpec::period("period", 20, 2, "Band period"))
+        .with_param(ParamSpec::positive(
+            "std_dev_multiplier",
+            2.0,
+            "Band width in standard deviations",
+        ))
+        .with_param(ParamSpec::range(
+            "oversold_threshold",
+            0.1,
+            0.0,
+            1.0,
+            "%B buy level",
+        ))
+        .with_param(ParamSpec::range(
+            "overbought_threshold",
+            0.9,
+            0.0,
+            1.0,
+            "%B sell level",
+        ))
+        .with_param(ParamSpec::positive(
+            "bandwidth_expansion_threshold",
+            5.0,
+            "Bandwidth change treated as expansion, in percent",
+        ))
+        .with_param(ParamSpec::period(
+            "trend_confirmation_length",
+            5,
+            1,
+            "Bars confirming a trend",
+        )),
+        intraday("vwap", Category::Hold, |p| {
+            Ok(StrategyInstance::Intraday(Box::new(
+                day_trade::VwapStrategy::new(
+                    int(p, "period"),
+                    flag(p, "reset_on_new_session"),
+                    num(p, "deviation_threshold"),
+                    flag(p, "mean_reversion_mode"),
+                    int(p, "lookback_period"),
+                ),
+            )))
+        })
+        .with_description("Trades deviations from the volume-weighted average price")
+        .with_param(ParamSpec::period("period", 390, 1, "VWAP window in bars"))
+        .with_param(ParamSpec::flag(
+            "reset_on_new_session",
+            true,
+            "Restart VWAP each session",
+        ))
+        .with_param(ParamSpec::positive(
+            "deviation_threshold",
+            1.0,
+            "Deviation that triggers a trade, in percent",
+        ))
+        .with_param(ParamSpec::flag(
+            "mean_reversion_mode",
+            true,
+            "Fade deviations instead of following them",
+        ))
+        .with_param(ParamSpec::period(
+            "lookback_period",
+            20,
+            1,
+            "Bars used for the deviation statistics",
+        )),
+        // minute_trade
+        minute("scalping", Category::Momentum, |p| {
+            Ok(StrategyInstance::Minute(Box::new(
+                minute_trade::ScalpingStrategy::new(int(p, "period"), num(p, "threshold"))?,
+            )))
+        })
+        .with_description("Quick entries on short-term EMA momentum")
+        .with_param(ParamSpec::period("period", 5, 1, "EMA period"))
+        .with_param(
+            ParamSpec::positive("threshold", 0.1, "Move that triggers a trade, in percent")
+                .at_most(1.0),
+        ),
+        minute("momentum_breakout", Category::Momentum, |p| {
+            Ok(StrategyInstance::Minute(Box::new(
+                minute_trade::MomentumBreakoutStrategy::new(
+                    int(p, "period"),
+                    num(p, "volume_threshold"),
+                )?,
+            )))
+        })
+        .with_description("Range breakouts confirmed by above-average volume")
+        .with_param(ParamSpec::period("period", 30, 5, "Breakout range in bars"))
+        .with_param(ParamSpec::positive(
+            "volume_threshold",
+            1.5,
+            "Volume relative to its average",
+        )),
+        minute("mean_reversion_oscillator", Category::MeanReversion, |p| {
+            Ok(StrategyInstance::Minute(Box::new(
+                minute_trade::MeanReversionOscillatorStrategy::new(
+                    int(p, "rsi_period"),
+                    num(p, "oversold_threshold"),
+                    num(p, "overbought_threshold"),
+                )?,
+            )))
+        })
+        .with_description("Intraday RSI oversold and overbought reversals")
+        .with_param(ParamSpec::period("rsi_period", 14, 2, "RSI period"))
+        .with_param(ParamSpec::range(
+            "oversold_threshold",
+            30.0,
+            0.0,
+            50.0,
+            "RSI buy level",
+        ))
+        .with_param(ParamSpec::range(
+            "overbought_threshold",
+            70.0,
+            50.0,
+            100.0,
+            "RSI sell level",
+        )),
+        minute("statistical_arbitrage", Category::MeanReversion, |p| {
+            Ok(StrategyInstance::Minute(Box::new(
+                minute_trade::StatisticalArbitrageStrategy::new(
+                    int(p, "lookback_period"),
+                    num(p, "zscore_threshold"),
+                )?,
+            )))
+        })
+        .with_description("Price divergence from its moving average in z-scores")
+        .with_param(ParamSpec::period(
+            "lookback_period",
+            30,
+            10,
+            "Average and deviation window",
+        ))
+        .with_param(ParamSpec::range(
+            "zscore_threshold",
+            2.0,
+            1.0,
+            f64::INFINITY,
+            "Entry z-score",
+        )),
+        minute("zscore", Category::Statistical, |p| {
+            Ok(StrategyInstance::Minute(Box::new(
+                minute_trade::ZScoreStrategy::new(
+                    int(p, "lookback_period"),
+                    num(p, "entry_threshold"),
+                    num(p, "exit_threshold"),
+                )?,
+            )))
+        })
+        .with_description("Enters at extreme z-scores and exits near the mean")
+        .with_param(ParamSpec::period(
+            "lookback_period",
+            30,
+            10,
+            "Z-score window",
+        ))
+        .with_param(ParamSpec::range(
+            "entry_threshold",
+            2.0,
+            1.0,
+            f64::INFINITY,
+            "Entry z-score",
+        ))
+        .with_param(ParamSpec::positive(
+            "exit_threshold",
+            0.5,
+            "Exit z-score, below the entry",
+        )),
+        minute("support_resistance", Category::Pattern, |p| {
+            Ok(StrategyInstance::Minute(Box::new(
+                minute_trade::SupportResistanceStrategy::new(
+                    int(p, "lookback_period"),
+                    int(p, "min_strength"),
+                    num(p, "level_zone_pct"),
+                    flag(p, "trade_bounces"),
+                )?,
+            )))
+        })
+        .with_description("Bounces off or breaks through repeatedly tested price levels")
+        .with_param(ParamSpec::period(
+            "lookback_period",
+            60,
+            30,
+            "Bars searched for levels",
+        ))
+        .with_param(ParamSpec::period(
+            "min_strength",
+            3,
+            2,
+            "Touches that make a level",
+        ))
+        .with_param(
+            ParamSpec::positive("level_zone_pct", 0.2, "Zone around a level, in percent")
+                .at_most(1.0),
+        )
+        .with_param(ParamSpec::flag(
+            "trade_bounces",
+            true,
+            "Trade bounces instead of breakouts",
+        )),
+        chart_pattern("chart_pattern_flag", "Flag continuation patterns", |p| {
+            build_chart_pattern(p, "flag")
+        }),
+        chart_pattern(
+            "chart_pattern_double",
+            "Double top and bottom reversals",
+            |p| build_chart_pattern(p, "double"),
+        ),
+        chart_pattern(
+            "chart_pattern_triangle",
+            "Triangle consolidation breakouts",
+            |p| build_chart_pattern(p, "triangle"),
+        ),
+        chart_pattern(
+            "chart_pattern_head_and_shoulders",
+            "Head and shoulders reversals",
+            |p| build_chart_pattern(p, "head_and_shoulders"),
+        ),
+        minute("time_of_day", Category::TimeBased, |p| {
+            Ok(StrategyInstance::Minute(Box::new(
+                minute_trade::TimeOfDayStrategy::new(
+                    int(p, "entry_hour") as u32,
+                    int(p, "entry_minute") as u32,
+                    int(p, "exit_hour") as u32,
+                    int(p, "exit_minute") as u32,
+                    flag(p, "go_long"),
+                )?,
+            )))
+        })
+        .with_description("Enters and exits at fixed times of day")
+        .with_param(ParamSpec::integer("entry_hour", 9, 0, 23, "Entry hour"))
+        .with_param(ParamSpec::integer(
+            "entry_minute",
+            30,
+            0,
+            59,
+            "Entry minute",
+        ))
+        .with_param(ParamSpec::integer("exit_hour", 16, 0, 23, "Exit hour"))
+        .with_param(ParamSpec::integer("exit_minute", 0, 0, 59, "Exit minute"))
+        .with_param(ParamSpec::flag(
+            "go_long",
+            true,
+            "Buy at entry instead of selling",
+        )),
+        minute("volatility_breakout", Category::Volatility, |p| {
+            Ok(StrategyInstance::Minute(Box::new(
+                minute_trade::VolatilityBreakoutStrategy::new(
+                    int(p, "lookback_period"),
+                    int(p, "contraction_period"),
+                    num(p, "breakout_multiplier"),
+                )?,
+            )))
+        })
+        .with_description("Breakouts after a period of low volatility")
+        .with_param(ParamSpec::period(
+            "lookback_period",
+            20,
+            5,
+            "Volatility baseline window",
+        ))
+        .with_param(ParamSpec::period(
+            "contraction_period",
+            5,
+            2,
+            "Contraction window, below the lookback",
+        ))
+        .with_param(ParamSpec::range(
+            "breakout_multiplier",
+            1.5,
+            1.0,
+            f64::INFINITY,
+            "Range multiple that counts as a breakout",
+        )),
+        minute("bollinger_band_contraction", Category::Volatility, |p| {
+            Ok(StrategyInstance::Minute(Box::new(
+                minute_trade::BollingerBandContractionStrategy::new(
+                    int(p, "bollinger_period"),
+                    num(p, "std_dev_multiplier"),
+                    num(p, "contraction_threshold"),
+                )?,
+            )))
+        })
+        .with_description("Expansion following tight Bollinger Bands")
+        .with_param(ParamSpec::period("bollinger_period", 20, 5, "Band period"))
+        .with_param(ParamSpec::range(
+            "std_dev_multiplier",
+            2.0,
+            1.0,
+            3.0,
+            "Band width in standard deviations",
+        ))
+        .with_param(
+            ParamSpec::positive(
+                "contraction_threshold",
+                0.3,
+                "Bandwidth that counts as tight, in percent",
+            )
+            .at_most(2.0),
+        ),
+        minute("volume_profile", Category::Volume, |p| {
+            Ok(StrategyInstance::Minute(Box::new(
+                minute_trade::VolumeProfileStrategy::new(
+                    int(p, "lookback_period"),
+                    int(p, "num_price_levels"),
+                    num(p, "volume_threshold"),
+                )?,
+            )))
+        })
+        .with_description("Trades around high-volume price levels")
+        .with_param(ParamSpec::period(
+            "lookback_period",
+            60,
+            20,
+            "Profile window",
+        ))
+        .with_param(ParamSpec::period(
+            "num_price_levels",
+            10,
+            5,
+            "Price buckets in the profile",
+        ))
+        .with_param(
+            ParamSpec::positive(
+                "volume_threshold",
+                0.1,
+                "Share of volume that marks a level",
+            )
+            .at_most(0.5),
+        ),
+    ]
+}
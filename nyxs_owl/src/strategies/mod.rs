@@ -0,0 +1,265 @@
+//! Strategies and forecast models by name
+//!
+//! The command-line tools refer to strategies by short names such as `macd`
+//! and override their parameters with `name=value` pairs. Unspecified
+//! parameters take the strategy's defaults; unknown parameter names are an
+//! error so that typos do not silently fall back to defaults.
+//!
+//! Strategies from `day_trade` and `minute_trade` are listed in a
+//! [`StrategyRegistry`] together with their category, supported timeframes
+//! and parameter schema:
+//!
+//! ```
+//! use nyxs_owl::strategies::{Category, Params, StrategyRegistry, Timeframe};
+//!
+//! let registry = StrategyRegistry::default();
+//! for info in registry.by_category(Category::Buy) {
+//!     assert!(info.supports(Timeframe::Daily));
+//! }
+//!
+//! let macd = registry.get("macd").unwrap();
+//! assert_eq!(macd.param("slow_period").unwrap().default, 26.0);
+//! let strategy = registry.build_daily("macd", &Params::new().with("fast_period", 8.0));
+//! assert!(strategy.is_ok());
+//! ```
+
+mod builtin;
+mod registry;
+
+pub use registry::{
+    Category, Factory, ParamKind, ParamSpec, StrategyInfo, StrategyInstance, StrategyRegistry,
+    Timeframe,
+};
+
+use crate::error::{OwlError, Result};
+use day_trade::TradingStrategy;
+use forecast_trade::models::arima::ArimaModel;
+use forecast_trade::models::auto_arima::AutoArima;
+use forecast_trade::models::auto_ets::AutoEts;
+use forecast_trade::models::ensemble::DynForecastModel;
+use forecast_trade::models::exponential_smoothing::ExponentialSmoothing;
+use forecast_trade::models::moving_average::MovingAverage;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// Names of the forecast models known to `build_forecast_model`
+pub const FORECAST_MODELS: &[&str] = &["arima", "auto_arima", "auto_ets", "ma", "ses"];
+
+/// Named numeric parameters
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Params {
+    values: BTreeMap<String, f64>,
+}
+
+impl Params {
+    /// Empty parameter set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse `name=value` pairs separated by commas, e.g. `fast=12,slow=26`
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut params = Self::new();
+        for pair in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (name, value) = pair.split_once('=').ok_or_else(|| {
+                OwlError::InvalidConfig(format!("Expected name=value, got '{}'", pair))
+            })?;
+            let value = value.trim().parse::<f64>().map_err(|_| {
+                OwlError::InvalidConfig(format!("Invalid value for parameter '{}'", name.trim()))
+            })?;
+            params.set(name.trim(), value);
+        }
+        Ok(params)
+    }
+
+    /// Set a parameter, replacing any previous value
+    pub fn set(&mut self, name: &str, value: f64) {
+        self.values.insert(name.to_string(), value);
+    }
+
+    /// Builder form of `set`
+    pub fn with(mut self, name: &str, value: f64) -> Self {
+        self.set(name, value);
+        self
+    }
+
+    /// Value of a parameter
+    pub fn get(&self, name: &str) -> Option<f64> {
+        self.values.get(name).copied()
+    }
+
+    /// Parameters in name order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, f64)> {
+        self.values.iter().map(|(k, v)| (k.as_str(), *v))
+    }
+
+    /// Add every parameter of `other`, overriding existing values
+    pub fn merge(&mut self, other: &Params) {
+        for (name, value) in other.iter() {
+            self.set(name, value);
+        }
+    }
+
+    /// Whether no parameters are set
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl fmt::Display for Params {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pairs: Vec<String> = self.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        write!(f, "{}", pairs.join(","))
+    }
+}
+
+/// Split `name:k=v,k=v` into a name and its parameters
+///
+/// A bare name has no parameters.
+pub fn parse_spec(spec: &str) -> Result<(String, Params)> {
+    let (name, params) = match spec.split_once(':') {
+        Some((name, params)) => (name.trim(), Params::parse(params)?),
+        None => (spec.trim(), Params::new()),
+    };
+    if name.is_empty() {
+        return Err(OwlError::InvalidConfig(format!(
+            "Missing name in specification '{}'",
+            spec
+        )));
+    }
+    Ok((name.to_string(), params))
+}
+
+/// Reads parameters with defaults and remembers which names were used
+struct ParamReader<'a> {
+    params: &'a Params,
+    used: RefCell<BTreeSet<&'static str>>,
+}
+
+impl<'a> ParamReader<'a> {
+    fn new(params: &'a Params) -> Self {
+        Self {
+            params,
+            used: RefCell::new(BTreeSet::new()),
+        }
+    }
+
+    fn f64(&self, name: &'static str, default: f64) -> f64 {
+        self.used.borrow_mut().insert(name);
+        self.params.get(name).unwrap_or(default)
+    }
+
+    fn usize(&self, name: &'static str, default: usize) -> Result<usize> {
+        let value = self.f64(name, default as f64);
+        if value < 0.0 || value.fract() != 0.0 {
+            return Err(OwlError::InvalidConfig(format!(
+                "Parameter '{}' must be a whole number, got {}",
+                name, value
+            )));
+        }
+        Ok(value as usize)
+    }
+
+    /// Fail on parameters the strategy never asked for
+    fn finish(&self, owner: &str) -> Result<()> {
+        let used = self.used.borrow();
+        if let Some((unknown, _)) = self.params.iter().find(|(k, _)| !used.contains(k)) {
+            let expected: Vec<&str> = used.iter().copied().collect();
+            return Err(OwlError::InvalidConfig(format!(
+                "Unknown parameter '{}' for {}; expected one of: {}",
+                unknown,
+                owner,
+                expected.join(", ")
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Build a daily strategy from the built-in registry by name
+pub fn build_daily_strategy(name: &str, params: &Params) -> Result<Box<dyn TradingStrategy>> {
+    StrategyRegistry::default().build_daily(name, params)
+}
+
+/// Build a `forecast_trade` model by name with parameter overrides
+pub fn build_forecast_model(name: &str, params: &Params) -> Result<Box<dyn DynForecastModel>> {
+    let p = ParamReader::new(params);
+    let model: Box<dyn DynForecastModel> = match name {
+        "arima" => Box::new(ArimaModel::new(
+            p.usize("p", 1)?,
+            p.usize("d", 1)?,
+            p.usize("q", 1)?,
+        )?),
+        "auto_arima" => Box::new(AutoArima::new()),
+        "auto_ets" => Box::new(AutoEts::new()),
+        "ma" => Box::new(MovingAverage::new(p.usize("window", 20)?)?),
+        "ses" => Box::new(ExponentialSmoothing::new(p.f64("alpha", 0.3))?),
+        _ => {
+            return Err(OwlError::InvalidConfig(format!(
+                "Unknown forecast model '{}'; available: {}",
+                name,
+                FORECAST_MODELS.join(", ")
+            )))
+        }
+    };
+    p.finish(name)?;
+    Ok(model)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_params_parsing() {
+        let params = Params::parse("fast_period=5, slow_period=20").unwrap();
+        assert_eq!(params.get("fast_period"), Some(5.0));
+        assert_eq!(params.to_string(), "fast_period=5,slow_period=20");
+        assert!(Params::parse("fast").is_err());
+        assert!(Params::parse("fast=x").is_err());
+        assert!(Params::parse("").unwrap().is_empty());
+
+        let (name, params) = parse_spec("macd:fast_period=8").unwrap();
+        assert_eq!(name, "macd");
+        assert_eq!(params.get("fast_period"), Some(8.0));
+        assert!(parse_spec("rsi").unwrap().1.is_empty());
+        assert!(parse_spec(":period=3").is_err());
+    }
+
+    #[test]
+    fn test_build_by_name() {
+        for info in StrategyRegistry::default().supporting(Timeframe::Daily) {
+            assert!(
+                build_daily_strategy(info.name(), &Params::new()).is_ok(),
+                "{}",
+                info.name()
+            );
+        }
+        for name in FORECAST_MODELS {
+            assert!(
+                build_forecast_model(name, &Params::new()).is_ok(),
+                "{}",
+                name
+            );
+        }
+
+        let params = Params::new()
+            .with("fast_period", 5.0)
+            .with("slow_period", 35.0);
+        assert!(build_daily_strategy("macd", &params).is_ok());
+
+        let err = build_daily_strategy("macd", &Params::new().with("fast", 5.0))
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("Unknown parameter 'fast'"));
+        assert!(build_daily_strategy("macd", &Params::new().with("fast_period", 2.5)).is_err());
+        assert!(
+            build_daily_strategy("rsi", &Params::new().with("oversold_threshold", 80.0)).is_err()
+        );
+        assert!(build_daily_strategy("nope", &Params::new()).is_err());
+        assert!(build_forecast_model("ses", &Params::new().with("alpha", 2.0)).is_err());
+    }
+}
@@ -0,0 +1,592 @@
+//! Strategy metadata, parameter schemas and factories
+
+use super::Params;
+use crate::error::{OwlError, Result};
+use clap::ValueEnum;
+use day_trade::{IntradayTradingStrategy, TradingStrategy};
+use minute_trade::IntradayStrategy;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Bar interval a strategy trades on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Timeframe {
+    /// Daily bars
+    #[default]
+    Daily,
+    /// Minute bars
+    Minute,
+}
+
+impl fmt::Display for Timeframe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Timeframe::Daily => write!(f, "daily"),
+            Timeframe::Minute => write!(f, "minute"),
+        }
+    }
+}
+
+/// Family a strategy belongs to
+///
+/// `day_trade` strategies are grouped by the side they favour, `minute_trade`
+/// strategies by their approach.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Category {
+    /// Trend following and momentum entries
+    Buy,
+    /// Reversal and overbought exits
+    Sell,
+    /// Range-bound and market-neutral
+    Hold,
+    /// Intraday momentum
+    Momentum,
+    /// Intraday mean reversion
+    MeanReversion,
+    /// Chart patterns and price levels
+    Pattern,
+    /// Statistical models
+    Statistical,
+    /// Time-of-day rules
+    TimeBased,
+    /// Volatility contraction and expansion
+    Volatility,
+    /// Volume analysis
+    Volume,
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Category::Buy => "buy",
+            Category::Sell => "sell",
+            Category::Hold => "hold",
+            Category::Momentum => "momentum",
+            Category::MeanReversion => "mean_reversion",
+            Category::Pattern => "pattern",
+            Category::Statistical => "statistical",
+            Category::TimeBased => "time_based",
+            Category::Volatility => "volatility",
+            Category::Volume => "volume",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// How a parameter value is interpreted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParamKind {
+    /// Whole number such as a period
+    Integer,
+    /// Real number
+    Float,
+    /// Switch given as 0 (off) or 1 (on)
+    Flag,
+}
+
+/// Name, default and valid range of one parameter
+///
+/// The constructors mirror the checks the strategies apply themselves:
+/// `period` matches `validate_period`, `positive` matches
+/// `validate_positive` and `range` matches `validate_range`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ParamSpec {
+    /// Parameter name
+    pub name: &'static str,
+    /// Value kind
+    pub kind: ParamKind,
+    /// Value used when the parameter is not given
+    pub default: f64,
+    /// Smallest valid value
+    pub min: f64,
+    /// Largest valid value
+    pub max: f64,
+    /// Whether `min` itself is excluded
+    pub min_exclusive: bool,
+    /// What the parameter controls
+    pub description: &'static str,
+}
+
+impl ParamSpec {
+    /// A period of at least `min` bars
+    pub fn period(
+        name: &'static str,
+        default: usize,
+        min: usize,
+        description: &'static str,
+    ) -> Self {
+        Self::integer(name, default, min, usize::MAX, description)
+    }
+
+    /// A whole number between `min` and `max` inclusive
+    pub fn integer(
+        name: &'static str,
+        default: usize,
+        min: usize,
+        max: usize,
+        description: &'static str,
+    ) -> Self {
+        Self {
+            name,
+            kind: ParamKind::Integer,
+            default: default as f64,
+            min: min as f64,
+            max: if max == usize::MAX {
+                f64::INFINITY
+            } else {
+                max as f64
+            },
+            min_exclusive: false,
+            description,
+        }
+    }
+
+    /// A number greater than zero
+    pub fn positive(name: &'static str, default: f64, description: &'static str) -> Self {
+        Self {
+            name,
+            kind: ParamKind::Float,
+            default,
+            min: 0.0,
+            max: f64::INFINITY,
+            min_exclusive: true,
+            description,
+        }
+    }
+
+    /// A number between `min` and `max` inclusive
+    pub fn range(
+        name: &'static str,
+        default: f64,
+        min: f64,
+        max: f64,
+        description: &'static str,
+    ) -> Self {
+        Self {
+            name,
+            kind: ParamKind::Float,
+            default,
+            min,
+            max,
+            min_exclusive: false,
+            description,
+        }
+    }
+
+    /// An on/off switch
+    pub fn flag(name: &'static str, default: bool, description: &'static str) -> Self {
+        Self {
+            name,
+            kind: ParamKind::Flag,
+            default: if default { 1.0 } else { 0.0 },
+            min: 0.0,
+            max: 1.0,
+            min_exclusive: false,
+            description,
+        }
+    }
+
+    /// Lower the upper bound to `max`
+    pub fn at_most(mut self, max: f64) -> Self {
+        self.max = max;
+        self
+    }
+
+    /// Check a value against the kind and range
+    pub fn check(&self, value: f64) -> std::result::Result<(), String> {
+        let whole = matches!(self.kind, ParamKind::Integer | ParamKind::Flag);
+        if !value.is_finite() || (whole && value.fract() != 0.0) {
+            return Err(format!(
+                "{} must be {}",
+                self.name,
+                match self.kind {
+                    ParamKind::Integer => "a whole number",
+                    ParamKind::Flag => "0 or 1",
+                    ParamKind::Float => "a finite number",
+                }
+            ));
+        }
+        let below = if self.min_exclusive {
+            value <= self.min
+        } else {
+            value < self.min
+        };
+        if below || value > self.max {
+            return Err(format!(
+                "{} must be in {}, got {}",
+                self.name,
+                self.range_text(),
+                value
+            ));
+        }
+        Ok(())
+    }
+
+    /// Valid range in interval notation, e.g. `[2, inf)` or `(0, 1]`
+    pub fn range_text(&self) -> String {
+        if self.kind == ParamKind::Flag {
+            return "0 or 1".to_string();
+        }
+        let open = if self.min_exclusive { '(' } else { '[' };
+        if self.max.is_infinite() {
+            format!("{}{}, inf)", open, self.min)
+        } else {
+            format!("{}{}, {}]", open, self.min, self.max)
+        }
+    }
+}
+
+/// A constructed strategy of any of the supported kinds
+pub enum StrategyInstance {
+    /// `day_trade` strategy on daily bars
+    Daily(Box<dyn TradingStrategy>),
+    /// `day_trade` strategy on minute bars
+    Intraday(Box<dyn IntradayTradingStrategy>),
+    /// `minute_trade` strategy on minute bars
+    Minute(Box<dyn IntradayStrategy>),
+}
+
+impl StrategyInstance {
+    /// Bar interval the instance trades on
+    pub fn timeframe(&self) -> Timeframe {
+        match self {
+            StrategyInstance::Daily(_) => Timeframe::Daily,
+            StrategyInstance::Intraday(_) | StrategyInstance::Minute(_) => Timeframe::Minute,
+        }
+    }
+
+    /// The daily strategy, if this is one
+    pub fn into_daily(self) -> Option<Box<dyn TradingStrategy>> {
+        match self {
+            StrategyInstance::Daily(strategy) => Some(strategy),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Debug for StrategyInstance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            StrategyInstance::Daily(_) => "Daily",
+            StrategyInstance::Intraday(_) => "Intraday",
+            StrategyInstance::Minute(_) => "Minute",
+        };
+        write!(f, "StrategyInstance::{}(..)", kind)
+    }
+}
+
+/// Builds a strategy from a complete, range-checked parameter set
+pub type Factory = fn(&Params) -> std::result::Result<StrategyInstance, String>;
+
+/// Everything the registry knows about one strategy
+#[derive(Debug, Clone)]
+pub struct StrategyInfo {
+    name: &'static str,
+    source: &'static str,
+    category: Category,
+    timeframes: Vec<Timeframe>,
+    description: &'static str,
+    params: Vec<ParamSpec>,
+    factory: Factory,
+}
+
+impl StrategyInfo {
+    /// A daily strategy without parameters
+    pub fn new(name: &'static str, category: Category, factory: Factory) -> Self {
+        Self {
+            name,
+            source: "",
+            category,
+            timeframes: vec![Timeframe::Daily],
+            description: "",
+            params: Vec::new(),
+            factory,
+        }
+    }
+
+    /// Crate the strategy comes from
+    pub fn with_source(mut self, source: &'static str) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Bar intervals the strategy supports (default daily only)
+    pub fn with_timeframes(mut self, timeframes: &[Timeframe]) -> Self {
+        self.timeframes = timeframes.to_vec();
+        self
+    }
+
+    /// One-line description
+    pub fn with_description(mut self, description: &'static str) -> Self {
+        self.description = description;
+        self
+    }
+
+    /// Add a parameter
+    pub fn with_param(mut self, param: ParamSpec) -> Self {
+        self.params.push(param);
+        self
+    }
+
+    /// Registry name
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Crate the strategy comes from
+    pub fn source(&self) -> &'static str {
+        self.source
+    }
+
+    /// Category
+    pub fn category(&self) -> Category {
+        self.category
+    }
+
+    /// Supported bar intervals
+    pub fn timeframes(&self) -> &[Timeframe] {
+        &self.timeframes
+    }
+
+    /// Whether the strategy trades on `timeframe`
+    pub fn supports(&self, timeframe: Timeframe) -> bool {
+        self.timeframes.contains(&timeframe)
+    }
+
+    /// One-line description
+    pub fn description(&self) -> &'static str {
+        self.description
+    }
+
+    /// Parameter schema in constructor order
+    pub fn params(&self) -> &[ParamSpec] {
+        &self.params
+    }
+
+    /// Schema of one parameter
+    pub fn param(&self, name: &str) -> Option<&ParamSpec> {
+        self.params.iter().find(|p| p.name == name)
+    }
+
+    /// Every parameter at its default
+    pub fn defaults(&self) -> Params {
+        let mut params = Params::new();
+        for p in &self.params {
+            params.set(p.name, p.default);
+        }
+        params
+    }
+
+    /// Defaults overridden by `overrides`, with every value checked
+    ///
+    /// Unknown names are an error so that typos do not silently fall back
+    /// to defaults.
+    pub fn resolve(&self, overrides: &Params) -> Result<Params> {
+        if let Some((unknown, _)) = overrides.iter().find(|(k, _)| self.param(k).is_none()) {
+            let expected: Vec<&str> = self.params.iter().map(|p| p.name).collect();
+            return Err(OwlError::InvalidConfig(format!(
+                "Unknown parameter '{}' for {}; expected one of: {}",
+                unknown,
+                self.name,
+                if expected.is_empty() {
+                    "none".to_string()
+                } else {
+                    expected.join(", ")
+                }
+            )));
+        }
+        let mut params = self.defaults();
+        params.merge(overrides);
+        for spec in &self.params {
+            if let Some(value) = params.get(spec.name) {
+                spec.check(value)
+                    .map_err(|e| OwlError::InvalidConfig(format!("{}: {}", self.name, e)))?;
+            }
+        }
+        Ok(params)
+    }
+
+    /// Construct the strategy with `overrides` applied to the defaults
+    pub fn build(&self, overrides: &Params) -> Result<StrategyInstance> {
+        let params = self.resolve(overrides)?;
+        (self.factory)(&params)
+            .map_err(|e| OwlError::InvalidConfig(format!("{}: {}", self.name, e)))
+    }
+}
+
+/// Strategies by name
+///
+/// `StrategyRegistry::default()` holds every `day_trade` and `minute_trade`
+/// strategy that can be built from numeric parameters; more can be added
+/// with `register`.
+#[derive(Debug, Clone)]
+pub struct StrategyRegistry {
+    entries: Vec<StrategyInfo>,
+}
+
+impl Default for StrategyRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        for info in super::builtin::strategies() {
+            registry
+                .register(info)
+                .expect("built-in strategy names are unique");
+        }
+        registry
+    }
+}
+
+impl StrategyRegistry {
+    /// Registry without any strategies
+    pub fn empty() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Add a strategy; names must be unique
+    pub fn register(&mut self, info: StrategyInfo) -> Result<()> {
+        if self.get(info.name).is_some() {
+            return Err(OwlError::InvalidConfig(format!(
+                "Strategy '{}' is already registered",
+                info.name
+            )));
+        }
+        let index = self.entries.partition_point(|e| e.name < info.name);
+        self.entries.insert(index, info);
+        Ok(())
+    }
+
+    /// Strategy by name
+    pub fn get(&self, name: &str) -> Option<&StrategyInfo> {
+        self.entries.iter().find(|e| e.name == name)
+    }
+
+    /// Strategies in name order
+    pub fn iter(&self) -> impl Iterator<Item = &StrategyInfo> {
+        self.entries.iter()
+    }
+
+    /// Registered names in order
+    pub fn names(&self) -> Vec<&'static str> {
+        self.entries.iter().map(|e| e.name).collect()
+    }
+
+    /// Number of strategies
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no strategies are registered
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Strategies in `category`
+    pub fn by_category(&self, category: Category) -> impl Iterator<Item = &StrategyInfo> {
+        self.entries.iter().filter(move |e| e.category == category)
+    }
+
+    /// Strategies that trade on `timeframe`
+    pub fn supporting(&self, timeframe: Timeframe) -> impl Iterator<Item = &StrategyInfo> {
+        self.entries.iter().filter(move |e| e.supports(timeframe))
+    }
+
+    /// Strategy by name, or an error listing the known names
+    pub fn lookup(&self, name: &str) -> Result<&StrategyInfo> {
+        self.get(name).ok_or_else(|| {
+            OwlError::InvalidConfig(format!(
+                "Unknown strategy '{}'; available: {}",
+                name,
+                self.names().join(", ")
+            ))
+        })
+    }
+
+    /// Construct a strategy by name
+    pub fn build(&self, name: &str, params: &Params) -> Result<StrategyInstance> {
+        self.lookup(name)?.build(params)
+    }
+
+    /// Construct a daily strategy by name
+    pub fn build_daily(&self, name: &str, params: &Params) -> Result<Box<dyn TradingStrategy>> {
+        let info = self.lookup(name)?;
+        if !info.supports(Timeframe::Daily) {
+            return Err(OwlError::InvalidConfig(format!(
+                "{} trades on minute bars, not daily bars",
+                name
+            )));
+        }
+        info.build(params)?.into_daily().ok_or_else(|| {
+            OwlError::InvalidConfig(format!("{} did not build a daily strategy", name))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_param_checks() {
+        let period = ParamSpec::period("period", 14, 2, "Lookback");
+        assert!(period.check(2.0).is_ok());
+        assert!(period.check(1.0).is_err());
+        assert!(period.check(2.5).is_err());
+        assert_eq!(period.range_text(), "[2, inf)");
+
+        let positive = ParamSpec::positive("threshold", 0.1, "Threshold").at_most(1.0);
+        assert!(positive.check(0.0).is_err());
+        assert!(positive.check(1.0).is_ok());
+        assert!(positive.check(1.01).is_err());
+        assert_eq!(positive.range_text(), "(0, 1]");
+
+        let flag = ParamSpec::flag("go_long", true, "Side");
+        assert_eq!(flag.default, 1.0);
+        assert!(flag.check(0.5).is_err());
+        assert!(ParamSpec::range("x", 0.5, 0.0, 1.0, "")
+            .check(f64::NAN)
+            .is_err());
+    }
+
+    #[test]
+    fn test_registry_covers_both_crates() {
+        let registry = StrategyRegistry::default();
+        assert!(registry.len() >= 20);
+        for info in registry.iter() {
+            let instance = info.build(&Params::new());
+            assert!(instance.is_ok(), "{}: {:?}", info.name(), instance.err());
+            let instance = instance.unwrap();
+            assert!(info.supports(instance.timeframe()), "{}", info.name());
+            assert!(!info.description().is_empty(), "{}", info.name());
+        }
+
+        assert!(registry
+            .by_category(Category::Momentum)
+            .all(|i| i.source() == "minute_trade"));
+        assert!(registry
+            .supporting(Timeframe::Daily)
+            .any(|i| i.name() == "composite"));
+        assert!(matches!(
+            registry.build("scalping", &Params::new()).unwrap(),
+            StrategyInstance::Minute(_)
+        ));
+        assert!(registry.build_daily("scalping", &Params::new()).is_err());
+
+        let info = registry.get("mean_reversion").unwrap();
+        let resolved = info.resolve(&Params::new().with("period", 30.0)).unwrap();
+        assert_eq!(resolved.get("period"), Some(30.0));
+        assert_eq!(resolved.get("std_dev_multiplier"), Some(2.0));
+        let err = info
+            .resolve(&Params::new().with("oversold_threshold", 1.5))
+            .unwrap_err();
+        assert!(err.to_string().contains("[0, 1]"));
+
+        let mut registry = registry;
+        let duplicate = StrategyInfo::new("macd", Category::Buy, |_| Err("unused".to_string()));
+        assert!(registry.register(duplicate).is_err());
+    }
+}
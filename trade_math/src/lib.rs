@@ -10,6 +10,7 @@ use thiserror::Error;
 pub mod forecasting;
 pub mod moving_averages;
 pub mod oscillators;
+pub mod state_space;
 pub mod volatility;
 pub mod volume;
 
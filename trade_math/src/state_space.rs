@@ -0,0 +1,673 @@
+//! Linear-Gaussian state-space models
+//!
+//! Models a scalar series with a hidden state vector:
+//!
+//! ```text
+//! y_t     = z_t' a_t + e_t,      e_t ~ N(0, h)
+//! a_{t+1} = T a_t + u_t,         u_t ~ N(0, Q)
+//! ```
+//!
+//! The observation vector `z_t` is fixed for structural models such as the
+//! local level and local linear trend, and supplied at every step for
+//! dynamic regression, where it holds the regressors. `KalmanFilter`
+//! processes observations online; `smooth` runs the filter over a whole
+//! sample followed by a Rauch-Tung-Striebel smoother. Observations that are
+//! NaN are treated as missing.
+
+use crate::{MathError, Result};
+
+type Matrix = Vec<Vec<f64>>;
+
+/// Variance of the default diffuse initial state
+const DIFFUSE_VARIANCE: f64 = 1e7;
+
+/// System matrices of a state-space model
+#[derive(Debug, Clone)]
+pub struct StateSpaceModel {
+    transition: Matrix,
+    state_noise: Matrix,
+    observation: Vec<f64>,
+    observation_noise: f64,
+    initial_state: Vec<f64>,
+    initial_covariance: Matrix,
+}
+
+impl StateSpaceModel {
+    /// Create a model from the transition matrix `T`, state noise covariance
+    /// `Q`, observation vector `z` and observation noise variance `h`
+    ///
+    /// The initial state is zero with a diffuse covariance.
+    pub fn new(
+        transition: Matrix,
+        state_noise: Matrix,
+        observation: Vec<f64>,
+        observation_noise: f64,
+    ) -> Result<Self> {
+        let n = transition.len();
+        if n == 0 {
+            return Err(MathError::InvalidInput(
+                "State dimension must be positive".to_string(),
+            ));
+        }
+        check_square(&transition, n, "Transition matrix")?;
+        check_covariance(&state_noise, n, "State noise covariance")?;
+        if observation.len() != n {
+            return Err(MathError::InvalidInput(format!(
+                "Observation vector has length {}, expected {}",
+                observation.len(),
+                n
+            )));
+        }
+        if !(observation_noise >= 0.0 && observation_noise.is_finite()) {
+            return Err(MathError::InvalidInput(
+                "Observation noise variance must be finite and non-negative".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            initial_state: vec![0.0; n],
+            initial_covariance: scaled_identity(n, DIFFUSE_VARIANCE),
+            transition,
+            state_noise,
+            observation,
+            observation_noise,
+        })
+    }
+
+    /// Random walk observed with noise
+    ///
+    /// State: level. Forecasts are flat at the filtered level.
+    pub fn local_level(level_variance: f64, observation_variance: f64) -> Result<Self> {
+        Self::new(
+            vec![vec![1.0]],
+            vec![vec![level_variance]],
+            vec![1.0],
+            observation_variance,
+        )
+    }
+
+    /// Level and slope that both follow random walks
+    ///
+    /// State: level, slope. Forecasts extrapolate the filtered slope.
+    pub fn local_linear_trend(
+        level_variance: f64,
+        slope_variance: f64,
+        observation_variance: f64,
+    ) -> Result<Self> {
+        Self::new(
+            vec![vec![1.0, 1.0], vec![0.0, 1.0]],
+            vec![vec![level_variance, 0.0], vec![0.0, slope_variance]],
+            vec![1.0, 0.0],
+            observation_variance,
+        )
+    }
+
+    /// Regression whose coefficients follow random walks
+    ///
+    /// State: the coefficients of `regressors` variables, preceded by an
+    /// intercept when `intercept` is true. Observations must be supplied
+    /// with their regressors through `KalmanFilter::update_with`; the
+    /// intercept's 1 is added automatically.
+    pub fn dynamic_regression(
+        regressors: usize,
+        coefficient_variance: f64,
+        observation_variance: f64,
+        intercept: bool,
+    ) -> Result<Self> {
+        let n = regressors + usize::from(intercept);
+        if n == 0 {
+            return Err(MathError::InvalidInput(
+                "Dynamic regression needs at least one coefficient".to_string(),
+            ));
+        }
+        let mut model = Self::new(
+            scaled_identity(n, 1.0),
+            scaled_identity(n, coefficient_variance),
+            vec![0.0; n],
+            observation_variance,
+        )?;
+        if intercept {
+            model.observation[0] = 1.0;
+        }
+        Ok(model)
+    }
+
+    /// Set the mean and covariance of the state before the first
+    /// observation
+    pub fn with_initial_state(mut self, state: Vec<f64>, covariance: Matrix) -> Result<Self> {
+        let n = self.state_dimension();
+        if state.len() != n {
+            return Err(MathError::InvalidInput(format!(
+                "Initial state has length {}, expected {}",
+                state.len(),
+                n
+            )));
+        }
+        check_covariance(&covariance, n, "Initial covariance")?;
+        self.initial_state = state;
+        self.initial_covariance = covariance;
+        Ok(self)
+    }
+
+    /// Number of state variables
+    pub fn state_dimension(&self) -> usize {
+        self.transition.len()
+    }
+
+    /// Transition matrix `T`
+    pub fn transition(&self) -> &[Vec<f64>] {
+        &self.transition
+    }
+
+    /// State noise covariance `Q`
+    pub fn state_noise(&self) -> &[Vec<f64>] {
+        &self.state_noise
+    }
+
+    /// Fixed observation vector `z`
+    pub fn observation(&self) -> &[f64] {
+        &self.observation
+    }
+
+    /// Observation noise variance `h`
+    pub fn observation_noise(&self) -> f64 {
+        self.observation_noise
+    }
+
+    /// Observation vector for one step, with `regressors` filling the
+    /// entries after any intercept
+    fn design(&self, regressors: Option<&[f64]>) -> Result<Vec<f64>> {
+        let Some(regressors) = regressors else {
+            return Ok(self.observation.clone());
+        };
+        let n = self.state_dimension();
+        let offset = n.checked_sub(regressors.len()).ok_or_else(|| {
+            MathError::InvalidInput(format!(
+                "Got {} regressors for a state of dimension {}",
+                regressors.len(),
+                n
+            ))
+        })?;
+        let mut z = self.observation.clone();
+        z[offset..].copy_from_slice(regressors);
+        Ok(z)
+    }
+}
+
+/// Result of processing one observation
+#[derive(Debug, Clone)]
+pub struct FilterStep {
+    /// One-step-ahead prediction of the observation
+    pub prediction: f64,
+    /// Variance of the prediction error
+    pub prediction_variance: f64,
+    /// Observation minus prediction, NaN if the observation was missing
+    pub innovation: f64,
+    /// State mean after the observation
+    pub state: Vec<f64>,
+}
+
+/// Mean and variance of a forecast observation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ObservationForecast {
+    /// Forecast mean
+    pub mean: f64,
+    /// Forecast variance
+    pub variance: f64,
+}
+
+/// Online Kalman filter
+#[derive(Debug, Clone)]
+pub struct KalmanFilter {
+    model: StateSpaceModel,
+    predicted_state: Vec<f64>,
+    predicted_covariance: Matrix,
+    filtered_state: Vec<f64>,
+    filtered_covariance: Matrix,
+    log_likelihood: f64,
+    observations: usize,
+}
+
+impl KalmanFilter {
+    /// Create a filter at the model's initial state
+    pub fn new(model: StateSpaceModel) -> Self {
+        Self {
+            predicted_state: model.initial_state.clone(),
+            predicted_covariance: model.initial_covariance.clone(),
+            filtered_state: model.initial_state.clone(),
+            filtered_covariance: model.initial_covariance.clone(),
+            log_likelihood: 0.0,
+            observations: 0,
+            model,
+        }
+    }
+
+    /// Process an observation using the model's observation vector
+    pub fn update(&mut self, value: f64) -> Result<FilterStep> {
+        let z = self.model.design(None)?;
+        self.step(value, &z)
+    }
+
+    /// Process an observation with its regressors (dynamic regression)
+    pub fn update_with(&mut self, value: f64, regressors: &[f64]) -> Result<FilterStep> {
+        let z = self.model.design(Some(regressors))?;
+        self.step(value, &z)
+    }
+
+    fn step(&mut self, value: f64, z: &[f64]) -> Result<FilterStep> {
+        let pz = mat_vec(&self.predicted_covariance, z);
+        let prediction = dot(z, &self.predicted_state);
+        let variance = dot(z, &pz) + self.model.observation_noise;
+
+        let innovation = value - prediction;
+        if value.is_nan() {
+            self.filtered_state = self.predicted_state.clone();
+            self.filtered_covariance = self.predicted_covariance.clone();
+        } else {
+            if !(variance > 0.0 && variance.is_finite()) {
+                return Err(MathError::CalculationError(
+                    "Prediction variance is not positive".to_string(),
+                ));
+            }
+            self.filtered_state = self
+                .predicted_state
+                .iter()
+                .zip(&pz)
+                .map(|(a, k)| a + k * innovation / variance)
+                .collect();
+            self.filtered_covariance = self
+                .predicted_covariance
+                .iter()
+                .zip(&pz)
+                .map(|(row, ki)| {
+                    row.iter()
+                        .zip(&pz)
+                        .map(|(p, kj)| p - ki * kj / variance)
+                        .collect()
+                })
+                .collect();
+
+            // The first observations only pin down the diffuse initial state
+            if self.observations >= self.model.state_dimension() {
+                self.log_likelihood -= 0.5
+                    * ((2.0 * std::f64::consts::PI).ln()
+                        + variance.ln()
+                        + innovation * innovation / variance);
+            }
+            self.observations += 1;
+        }
+
+        let (state, covariance) = self.propagate(&self.filtered_state, &self.filtered_covariance);
+        self.predicted_state = state;
+        self.predicted_covariance = covariance;
+
+        Ok(FilterStep {
+            prediction,
+            prediction_variance: variance,
+            innovation,
+            state: self.filtered_state.clone(),
+        })
+    }
+
+    /// Move a state mean and covariance one step forward
+    fn propagate(&self, state: &[f64], covariance: &[Vec<f64>]) -> (Vec<f64>, Matrix) {
+        let t = &self.model.transition;
+        let state = mat_vec(t, state);
+        let covariance = add(
+            &mat_mul(&mat_mul(t, covariance), &transpose(t)),
+            &self.model.state_noise,
+        );
+        (state, covariance)
+    }
+
+    /// Forecast the next `horizon` observations with the fixed observation
+    /// vector
+    pub fn forecast(&self, horizon: usize) -> Result<Vec<ObservationForecast>> {
+        let z = self.model.design(None)?;
+        Ok(self.forecast_path(std::iter::repeat_n(z, horizon)))
+    }
+
+    /// Forecast observations given the regressors of each future step
+    pub fn forecast_with(&self, regressors: &[Vec<f64>]) -> Result<Vec<ObservationForecast>> {
+        let designs = regressors
+            .iter()
+            .map(|r| self.model.design(Some(r)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(self.forecast_path(designs.into_iter()))
+    }
+
+    fn forecast_path<I: Iterator<Item = Vec<f64>>>(&self, designs: I) -> Vec<ObservationForecast> {
+        let mut state = self.predicted_state.clone();
+        let mut covariance = self.predicted_covariance.clone();
+        designs
+            .map(|z| {
+                let forecast = ObservationForecast {
+                    mean: dot(&z, &state),
+                    variance: dot(&z, &mat_vec(&covariance, &z)) + self.model.observation_noise,
+                };
+                (state, covariance) = self.propagate(&state, &covariance);
+                forecast
+            })
+            .collect()
+    }
+
+    /// State mean given the observations so far
+    pub fn state(&self) -> &[f64] {
+        &self.filtered_state
+    }
+
+    /// State covariance given the observations so far
+    pub fn covariance(&self) -> &[Vec<f64>] {
+        &self.filtered_covariance
+    }
+
+    /// State mean predicted for the next step
+    pub fn predicted_state(&self) -> &[f64] {
+        &self.predicted_state
+    }
+
+    /// Gaussian log-likelihood of the observations, excluding the first
+    /// `state_dimension` ones which initialise the diffuse state
+    pub fn log_likelihood(&self) -> f64 {
+        self.log_likelihood
+    }
+
+    /// Number of non-missing observations processed
+    pub fn observations(&self) -> usize {
+        self.observations
+    }
+
+    /// The model being filtered
+    pub fn model(&self) -> &StateSpaceModel {
+        &self.model
+    }
+
+    /// Return to the initial state, forgetting all observations
+    pub fn reset(&mut self) {
+        *self = Self::new(self.model.clone());
+    }
+}
+
+/// State mean and covariance given the whole sample
+#[derive(Debug, Clone)]
+pub struct SmoothedState {
+    /// Smoothed state mean
+    pub state: Vec<f64>,
+    /// Smoothed state covariance
+    pub covariance: Matrix,
+}
+
+/// Filter a whole sample and smooth the states backwards
+///
+/// `regressors` gives the regressors of every observation for dynamic
+/// regression models.
+pub fn smooth(
+    model: &StateSpaceModel,
+    values: &[f64],
+    regressors: Option<&[Vec<f64>]>,
+) -> Result<Vec<SmoothedState>> {
+    if let Some(regressors) = regressors {
+        if regressors.len() != values.len() {
+            return Err(MathError::InvalidInput(format!(
+                "Got {} regressor rows for {} observations",
+                regressors.len(),
+                values.len()
+            )));
+        }
+    }
+
+    let mut filter = KalmanFilter::new(model.clone());
+    let mut filtered = Vec::with_capacity(values.len());
+    let mut predicted = Vec::with_capacity(values.len());
+    for (t, &value) in values.iter().enumerate() {
+        match regressors {
+            Some(rows) => filter.update_with(value, &rows[t])?,
+            None => filter.update(value)?,
+        };
+        filtered.push((
+            filter.filtered_state.clone(),
+            filter.filtered_covariance.clone(),
+        ));
+        predicted.push((
+            filter.predicted_state.clone(),
+            filter.predicted_covariance.clone(),
+        ));
+    }
+
+    let Some((state, covariance)) = filtered.last().cloned() else {
+        return Ok(Vec::new());
+    };
+    let mut smoothed = vec![SmoothedState { state, covariance }];
+    let transition_t = transpose(&model.transition);
+    for t in (0..values.len() - 1).rev() {
+        let (state, covariance) = &filtered[t];
+        let (next_state, next_covariance) = &predicted[t];
+        let later = smoothed.last().unwrap();
+
+        let gain = mat_mul(
+            &mat_mul(covariance, &transition_t),
+            &invert(next_covariance)?,
+        );
+        let state_gap: Vec<f64> = later
+            .state
+            .iter()
+            .zip(next_state)
+            .map(|(s, p)| s - p)
+            .collect();
+        let covariance_gap = add(&later.covariance, &scale(next_covariance, -1.0));
+
+        smoothed.push(SmoothedState {
+            state: state
+                .iter()
+                .zip(mat_vec(&gain, &state_gap))
+                .map(|(a, g)| a + g)
+                .collect(),
+            covariance: add(
+                covariance,
+                &mat_mul(&mat_mul(&gain, &covariance_gap), &transpose(&gain)),
+            ),
+        });
+    }
+    smoothed.reverse();
+    Ok(smoothed)
+}
+
+fn check_square(m: &[Vec<f64>], n: usize, name: &str) -> Result<()> {
+    if m.len() != n || m.iter().any(|row| row.len() != n) {
+        return Err(MathError::InvalidInput(format!(
+            "{} must be {}x{}",
+            name, n, n
+        )));
+    }
+    if m.iter().flatten().any(|v| !v.is_finite()) {
+        return Err(MathError::InvalidInput(format!("{} must be finite", name)));
+    }
+    Ok(())
+}
+
+fn check_covariance(m: &[Vec<f64>], n: usize, name: &str) -> Result<()> {
+    check_square(m, n, name)?;
+    for (i, row) in m.iter().enumerate() {
+        if row[i] < 0.0 || (0..i).any(|j| (row[j] - m[j][i]).abs() > 1e-12) {
+            return Err(MathError::InvalidInput(format!(
+                "{} must be symmetric with a non-negative diagonal",
+                name
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn scaled_identity(n: usize, value: f64) -> Matrix {
+    (0..n)
+        .map(|i| (0..n).map(|j| if i == j { value } else { 0.0 }).collect())
+        .collect()
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn mat_vec(m: &[Vec<f64>], v: &[f64]) -> Vec<f64> {
+    m.iter().map(|row| dot(row, v)).collect()
+}
+
+fn mat_mul(a: &[Vec<f64>], b: &[Vec<f64>]) -> Matrix {
+    let columns = b.first().map_or(0, Vec::len);
+    a.iter()
+        .map(|row| {
+            (0..columns)
+                .map(|j| row.iter().zip(b).map(|(x, b_row)| x * b_row[j]).sum())
+                .collect()
+        })
+        .collect()
+}
+
+fn transpose(m: &[Vec<f64>]) -> Matrix {
+    let columns = m.first().map_or(0, Vec::len);
+    (0..columns)
+        .map(|j| m.iter().map(|row| row[j]).collect())
+        .collect()
+}
+
+fn add(a: &[Vec<f64>], b: &[Vec<f64>]) -> Matrix {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| x.iter().zip(y).map(|(p, q)| p + q).collect())
+        .collect()
+}
+
+fn scale(m: &[Vec<f64>], factor: f64) -> Matrix {
+    m.iter()
+        .map(|row| row.iter().map(|v| v * factor).collect())
+        .collect()
+}
+
+/// Gauss-Jordan inversion with partial pivoting
+fn invert(m: &[Vec<f64>]) -> Result<Matrix> {
+    let n = m.len();
+    let mut a = m.to_vec();
+    let mut inverse = scaled_identity(n, 1.0);
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))
+            .unwrap();
+        if a[pivot][col].abs() < 1e-300 {
+            return Err(MathError::CalculationError(
+                "Predicted state covariance is singular".to_string(),
+            ));
+        }
+        a.swap(col, pivot);
+        inverse.swap(col, pivot);
+
+        let diagonal = a[col][col];
+        for j in 0..n {
+            a[col][j] /= diagonal;
+            inverse[col][j] /= diagonal;
+        }
+        for i in 0..n {
+            if i != col {
+                let factor = a[i][col];
+                for j in 0..n {
+                    a[i][j] -= factor * a[col][j];
+                    inverse[i][j] -= factor * inverse[col][j];
+                }
+            }
+        }
+    }
+    Ok(inverse)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-noise in [-0.5, 0.5)
+    fn noise(t: usize) -> f64 {
+        ((t as f64 * 12.9898).sin() * 43758.5453).fract().abs() - 0.5
+    }
+
+    #[test]
+    fn test_local_level_tracks_level_shift() {
+        let model = StateSpaceModel::local_level(0.1, 1.0).unwrap();
+        let mut filter = KalmanFilter::new(model);
+
+        let first = filter.update(10.0).unwrap();
+        // A diffuse prior takes the first observation at face value
+        assert!((first.state[0] - 10.0).abs() < 1e-4);
+
+        for t in 0..100 {
+            let level = if t < 50 { 10.0 } else { 20.0 };
+            filter.update(level + noise(t)).unwrap();
+        }
+        assert!((filter.state()[0] - 20.0).abs() < 0.5);
+
+        let forecast = filter.forecast(3).unwrap();
+        assert_eq!(forecast[0].mean, forecast[2].mean);
+        assert!(forecast[2].variance > forecast[0].variance);
+        assert!(filter.log_likelihood().is_finite());
+
+        filter.reset();
+        assert_eq!(filter.observations(), 0);
+    }
+
+    #[test]
+    fn test_local_linear_trend_forecast() {
+        let model = StateSpaceModel::local_linear_trend(0.01, 0.0001, 0.25).unwrap();
+        let mut filter = KalmanFilter::new(model);
+        for t in 0..80 {
+            filter.update(5.0 + 2.0 * t as f64 + noise(t)).unwrap();
+        }
+
+        assert!((filter.state()[1] - 2.0).abs() < 0.05);
+        let forecast = filter.forecast(2).unwrap();
+        assert!((forecast[0].mean - (5.0 + 2.0 * 80.0)).abs() < 1.0);
+        assert!((forecast[1].mean - forecast[0].mean - 2.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_dynamic_regression_hedge_ratio() {
+        // y = 1 + beta_t x with beta moving from 0.5 to 1.5
+        let model = StateSpaceModel::dynamic_regression(1, 1e-4, 0.01, true).unwrap();
+        let mut filter = KalmanFilter::new(model);
+        for t in 0..400 {
+            let x = 50.0 + 10.0 * (t as f64 * 0.1).sin();
+            let beta = 0.5 + t as f64 / 400.0;
+            filter
+                .update_with(1.0 + beta * x + 0.1 * noise(t), &[x])
+                .unwrap();
+        }
+
+        let beta = filter.state()[1];
+        assert!((beta - 1.5).abs() < 0.1, "beta {}", beta);
+        let forecast = filter.forecast_with(&[vec![50.0]]).unwrap();
+        assert!((forecast[0].mean - (1.0 + 1.5 * 50.0)).abs() < 5.0);
+        assert!(filter.update_with(1.0, &[1.0, 2.0, 3.0]).is_err());
+    }
+
+    #[test]
+    fn test_smoother_and_missing_values() {
+        let model = StateSpaceModel::local_level(0.05, 1.0).unwrap();
+        let mut values: Vec<f64> = (0..60).map(|t| 3.0 + noise(t)).collect();
+        values[30] = f64::NAN;
+
+        let smoothed = smooth(&model, &values, None).unwrap();
+        assert_eq!(smoothed.len(), 60);
+
+        let mut filter = KalmanFilter::new(model.clone());
+        let mut filtered = Vec::new();
+        for &v in &values {
+            filter.update(v).unwrap();
+            filtered.push(filter.covariance()[0][0]);
+        }
+        assert_eq!(filter.observations(), 59);
+
+        // Smoothing uses later observations, so it is never less certain
+        for (s, f) in smoothed.iter().zip(&filtered) {
+            assert!(s.covariance[0][0] <= f + 1e-12);
+        }
+        assert!((smoothed[30].state[0] - 3.0).abs() < 0.5);
+        assert!(smoothed[30].covariance[0][0] < filtered[30]);
+        assert!(StateSpaceModel::new(vec![vec![1.0]], vec![vec![-1.0]], vec![1.0], 1.0).is_err());
+    }
+}